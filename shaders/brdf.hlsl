@@ -0,0 +1,73 @@
+// Surface BRDF
+//
+// Cook-Torrance microfacet specular (GGX distribution, height-correlated Smith
+// visibility, Schlick fresnel) with a Lambertian diffuse lobe.
+// Must match with `brdf.rs` of the reference renderer.
+
+#define PI 3.14159265359
+
+// Reflectance at normal incidence of dielectrics (4%).
+#define DIELECTRIC_F0 0.04
+
+// Lower bound of the perceptual roughness, avoids the singularity of smooth surfaces.
+#define MIN_ROUGHNESS 0.045
+
+struct Surface {
+    float3 normal;
+    float3 albedo;
+    float roughness; // perceptual, alpha = roughness^2
+    float metallic;
+};
+
+float d_ggx(float n_dot_h, float alpha) {
+    float alpha_sq = alpha * alpha;
+    float f = (n_dot_h * alpha_sq - n_dot_h) * n_dot_h + 1.0;
+    return alpha_sq / (PI * f * f);
+}
+
+// Height-correlated Smith visibility, includes the `4 * n_dot_l * n_dot_v` denominator.
+float v_smith_ggx_correlated(float n_dot_v, float n_dot_l, float alpha) {
+    float alpha_sq = alpha * alpha;
+    float ggx_l = n_dot_v * sqrt(n_dot_l * n_dot_l * (1.0 - alpha_sq) + alpha_sq);
+    float ggx_v = n_dot_l * sqrt(n_dot_v * n_dot_v * (1.0 - alpha_sq) + alpha_sq);
+    return 0.5 / (ggx_v + ggx_l);
+}
+
+float3 f_schlick(float3 f0, float v_dot_h) {
+    float f = pow(1.0 - v_dot_h, 5.0);
+    return f0 + (1.0 - f0) * f;
+}
+
+// Specular reflectance at normal incidence.
+float3 specular_f0(Surface surface) {
+    return lerp(float3(DIELECTRIC_F0, DIELECTRIC_F0, DIELECTRIC_F0), surface.albedo, surface.metallic);
+}
+
+// Albedo of the Lambertian lobe, metals have no diffuse reflection.
+float3 diffuse_albedo(Surface surface) {
+    return surface.albedo * (1.0 - surface.metallic);
+}
+
+// Reflected radiance per unit irradiance, multiplied by `n_dot_l`.
+//
+// `view` and `light` point away from the surface.
+float3 evaluate_brdf(Surface surface, float3 view, float3 light) {
+    float n_dot_l = saturate(dot(surface.normal, light));
+    if (n_dot_l <= 0.0) {
+        return float3(0.0, 0.0, 0.0);
+    }
+    // Normals facing away from the viewer (e.g. due to normal mapping) are clamped.
+    float n_dot_v = max(dot(surface.normal, view), 1e-4);
+
+    float3 half_dir = normalize(view + light);
+    float n_dot_h = saturate(dot(surface.normal, half_dir));
+    float v_dot_h = saturate(dot(view, half_dir));
+
+    float roughness = max(surface.roughness, MIN_ROUGHNESS);
+    float alpha = roughness * roughness;
+    float3 fresnel = f_schlick(specular_f0(surface), v_dot_h);
+    float3 specular = d_ggx(n_dot_h, alpha) * v_smith_ggx_correlated(n_dot_v, n_dot_l, alpha) * fresnel;
+    float3 diffuse = diffuse_albedo(surface) / PI;
+
+    return (diffuse + specular) * n_dot_l;
+}
@@ -0,0 +1,67 @@
+// Light clusters
+//
+// Using space6. The view frustum of the rendered region is divided into a froxel grid:
+// screen space tiles, each split into depth slices with exponentially growing depth.
+// Clusters store the indices of the point and spot lights intersecting them.
+// Must match with `cluster.rs` of the reference renderer.
+
+// Must match with `pass/cluster.rs`.
+#define CLUSTER_TILE_SIZE 64
+#define CLUSTER_SLICES 16
+#define MAX_CLUSTER_LIGHTS 128
+
+struct ClusterData {
+    uint3 grid; // clusters along x, y and depth
+    float depth_scale; // CLUSTER_SLICES / log(far / near)
+    uint2 extent; // rendered region, starting at the origin
+    float depth_bias; // -log(near) * depth_scale
+    uint debug_heatmap; // output the number of lights per cluster instead of lighting
+};
+ConstantBuffer<ClusterData> cluster_data : register(b0, space6);
+
+// Light counts saturating the heatmap.
+#define HEATMAP_MAX_LIGHTS 32
+
+uint cluster_index(uint3 cluster) {
+    return (cluster.z * cluster_data.grid.y + cluster.y) * cluster_data.grid.x + cluster.x;
+}
+
+// Cluster of a pixel with view space `depth`, the distance along the view direction.
+uint3 pixel_cluster(uint2 pixel, float depth) {
+    float slice = log(depth) * cluster_data.depth_scale + cluster_data.depth_bias;
+    return uint3(pixel / CLUSTER_TILE_SIZE, uint(clamp(slice, 0.0, CLUSTER_SLICES - 1)));
+}
+
+// View space bounding box of a cluster.
+void cluster_bounds(uint3 cluster, out float3 aabb_min, out float3 aabb_max) {
+    float2 extent = float2(cluster_data.extent);
+    float2 pixel_min = float2(cluster.xy * CLUSTER_TILE_SIZE);
+    float2 pixel_max = min(float2((cluster.xy + 1) * CLUSTER_TILE_SIZE), extent);
+
+    // Tile corners at unit depth, y is pointing down in screen space.
+    float2 unit_min = float2(pixel_min.x / extent.x * 2.0 - 1.0, 1.0 - pixel_max.y / extent.y * 2.0);
+    float2 unit_max = float2(pixel_max.x / extent.x * 2.0 - 1.0, 1.0 - pixel_min.y / extent.y * 2.0);
+    unit_min /= float2(proj[0][0], proj[1][1]);
+    unit_max /= float2(proj[0][0], proj[1][1]);
+
+    float depth_near = exp((cluster.z - cluster_data.depth_bias) / cluster_data.depth_scale);
+    float depth_far = exp((cluster.z + 1 - cluster_data.depth_bias) / cluster_data.depth_scale);
+
+    aabb_min = float3(min(unit_min * depth_near, unit_min * depth_far), -depth_far);
+    aabb_max = float3(max(unit_max * depth_near, unit_max * depth_far), -depth_near);
+}
+
+bool sphere_intersects_aabb(float3 center, float radius, float3 aabb_min, float3 aabb_max) {
+    float3 d = max(max(aabb_min - center, 0.0), center - aabb_max);
+    return dot(d, d) <= radius * radius;
+}
+
+// Debug color for the number of lights of a cluster, black without lights and
+// blue over green to red for increasing counts.
+float3 light_heatmap(uint num_lights) {
+    if (num_lights == 0) {
+        return float3(0.0, 0.0, 0.0);
+    }
+    float t = saturate(float(num_lights) / HEATMAP_MAX_LIGHTS);
+    return saturate(float3(2.0 * t - 1.0, 1.0 - abs(2.0 * t - 1.0), 1.0 - 2.0 * t));
+}
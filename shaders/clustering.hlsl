@@ -0,0 +1,52 @@
+#include "shaders/resources.hlsl"
+#include "shaders/light.hlsl"
+#include "shaders/cluster.hlsl"
+
+// View data (see resources.hlsl) -------------------------------- space 0
+// Light information (see light.hlsl) ----------------------------- space 4
+
+// Light clusters (see cluster.hlsl) ------------------------------ space 6
+RWStructuredBuffer<uint2> cluster_grid : register(u0, space6); // point and spot light counts
+RWStructuredBuffer<uint> cluster_light_indices : register(u1, space6); // MAX_CLUSTER_LIGHTS per cluster
+
+// Assign the point and spot lights to the cluster of the thread.
+//
+// Point lights are stored before the spot lights. Lights exceeding `MAX_CLUSTER_LIGHTS`
+// are dropped in light index order.
+[numthreads(64, 1, 1)]
+void cs_cluster_lights(uint3 thread_id: SV_DispatchThreadID) {
+    uint3 grid = cluster_data.grid;
+    uint id = thread_id.x;
+    // Dispatch is rounded up to full groups.
+    if (id >= grid.x * grid.y * grid.z) {
+        return;
+    }
+
+    uint3 cluster = uint3(id % grid.x, (id / grid.x) % grid.y, id / (grid.x * grid.y));
+    float3 aabb_min, aabb_max;
+    cluster_bounds(cluster, aabb_min, aabb_max);
+
+    uint base = id * MAX_CLUSTER_LIGHTS;
+    uint num_lights = 0;
+    for (uint i = 0; i < light_data.num_point_lights && num_lights < MAX_CLUSTER_LIGHTS; i++) {
+        PointLight point_light = point_lights[i];
+        float3 center = mul(view, float4(point_light.position, 1.0)).xyz;
+        if (sphere_intersects_aabb(center, point_light.range, aabb_min, aabb_max)) {
+            cluster_light_indices[base + num_lights] = i;
+            num_lights++;
+        }
+    }
+
+    uint num_point_lights = num_lights;
+    // Spot lights are bounded by the sphere of their range, ignoring the cone.
+    for (uint s = 0; s < light_data.num_spot_lights && num_lights < MAX_CLUSTER_LIGHTS; s++) {
+        SpotLight spot_light = spot_lights[s];
+        float3 center = mul(view, float4(spot_light.position, 1.0)).xyz;
+        if (sphere_intersects_aabb(center, spot_light.range, aabb_min, aabb_max)) {
+            cluster_light_indices[base + num_lights] = s;
+            num_lights++;
+        }
+    }
+
+    cluster_grid[id] = uint2(num_point_lights, num_lights - num_point_lights);
+}
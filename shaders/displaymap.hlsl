@@ -2,6 +2,12 @@
 Texture2D<float4> g_input_hdr : register(t0, space0);
 SamplerState g_sampler : register(s0, space0);
 
+struct DisplayMapData {
+    float2 uv_scale;
+    float2 uv_max;
+};
+ConstantBuffer<DisplayMapData> g_display_map : register(b0, space0);
+
 struct VsOutput {
     float4 pos: SV_Position;
     float2 uv: TEXCOORD0;
@@ -18,7 +24,9 @@ VsOutput vs_main(uint id: SV_VertexID) {
 
 float4 ps_displaymap(VsOutput input) : SV_Target0 {
     const float exposure = 8.0f; // TODO:
-    float3 color = g_input_hdr.SampleLevel(g_sampler, input.uv, 0).xyz;
+    // Only the top-left region of the input is rendered, avoid filtering outside.
+    float2 uv = min(input.uv * g_display_map.uv_scale, g_display_map.uv_max);
+    float3 color = g_input_hdr.SampleLevel(g_sampler, uv, 0).xyz;
     color *= exposure;
 
     return float4(color / (1.0 + color), 1.0);
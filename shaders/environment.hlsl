@@ -0,0 +1,60 @@
+// Image based lighting
+//
+// Using space8. Lighting of a distant environment map, preprocessed on the CPU:
+//  * Diffuse: irradiance as L2 spherical harmonics.
+//  * Specular: split-sum approximation, radiance prefiltered per roughness in the mip
+//    levels of a cube map and the fresnel scale and bias of the BRDF integral.
+// Must match with `ibl/mod.rs`, the lookup table is integrated from `brdf.hlsl`.
+
+// Must match with `SPECULAR_LEVELS` in `ibl/mod.rs`.
+#define SPECULAR_LEVELS 6
+// Must match with `BRDF_LUT_SIZE` in `ibl/brdf_lut.rs`.
+#define BRDF_LUT_SIZE 32
+
+struct _EnvironmentData {
+    float4 irradiance[9]; // SH coefficients (rgb)
+    float intensity;
+    float3 _alignment;
+};
+
+TextureCube<float4> environment_specular : register(t0, space8);
+Texture2D<float2> brdf_lut : register(t1, space8); // n_dot_v along x, roughness along y
+StructuredBuffer<_EnvironmentData> environment_data : register(t2, space8);
+SamplerState environment_sampler : register(s0, space8);
+
+// Irradiance along the normalized direction `n`.
+float3 sh_evaluate(float4 coefficients[9], float3 n) {
+    float3 result = coefficients[0].rgb * 0.282095;
+    result += coefficients[1].rgb * (0.488603 * n.y);
+    result += coefficients[2].rgb * (0.488603 * n.z);
+    result += coefficients[3].rgb * (0.488603 * n.x);
+    result += coefficients[4].rgb * (1.092548 * n.x * n.y);
+    result += coefficients[5].rgb * (1.092548 * n.y * n.z);
+    result += coefficients[6].rgb * (0.315392 * (3.0 * n.z * n.z - 1.0));
+    result += coefficients[7].rgb * (1.092548 * n.x * n.z);
+    result += coefficients[8].rgb * (0.546274 * (n.x * n.x - n.y * n.y));
+    return result;
+}
+
+// Fresnel scale (x) and bias (y) of the specular BRDF integral, sampling texel centers.
+float2 brdf_lut_lookup(float n_dot_v, float roughness) {
+    float2 coord = float2(saturate(n_dot_v), saturate(roughness)) * (BRDF_LUT_SIZE - 1);
+    return brdf_lut.SampleLevel(environment_sampler, (coord + 0.5) / BRDF_LUT_SIZE, 0);
+}
+
+// Reflected radiance of the environment towards `view`.
+float3 environment_lighting(Surface surface, float3 view) {
+    _EnvironmentData environment = environment_data[0];
+
+    float n_dot_v = max(dot(surface.normal, view), 1e-4);
+    float3 reflected = reflect(-view, surface.normal);
+    float lod = saturate(surface.roughness) * (SPECULAR_LEVELS - 1);
+    float3 radiance = environment_specular.SampleLevel(environment_sampler, reflected, lod).rgb;
+    float2 dfg = brdf_lut_lookup(n_dot_v, surface.roughness);
+    float3 specular = radiance * (specular_f0(surface) * dfg.x + dfg.y);
+
+    float3 irradiance = max(sh_evaluate(environment.irradiance, surface.normal), 0.0);
+    float3 diffuse = diffuse_albedo(surface) * irradiance / PI;
+
+    return (diffuse + specular) * environment.intensity;
+}
@@ -2,13 +2,14 @@
 #include "shaders/pack.hlsl"
 #include "shaders/resources.hlsl"
 #include "shaders/resources_triangle.hlsl"
+#include "shaders/material.hlsl"
 
 ConstantBuffer<_DrawData> draw_data : register(b0, space2);
 
-struct GeometryId {
+struct InstanceId {
     uint id;
 };
-ConstantBuffer<GeometryId> geometry_id : register(b1, space2);
+ConstantBuffer<InstanceId> instance_id : register(b1, space2);
 
 struct VsInput {
     float3 pos: Attr0;
@@ -21,10 +22,13 @@ struct VsOutput {
 };
 
 VsOutput vs_main(VsInput input) {
+    float4x4 world = instance_data[instance_id.id].world;
+    float3 world_pos = transform_position(world, input.pos);
+
     VsOutput output;
-    output.pos = mul(proj, mul(view, float4(input.pos.xyz, 1.0)));
-    output.view_dir = input.pos - camera_pos;
-    output.vertex0 = input.pos;
+    output.pos = mul(proj, mul(view, float4(world_pos, 1.0)));
+    output.view_dir = world_pos - camera_pos.xyz;
+    output.vertex0 = world_pos;
     return output;
 }
 
@@ -33,12 +37,14 @@ uint4 ps_main(
     uint prim_id: SV_PrimitiveID
 ) : SV_TARGET0 {
     uint index0 = 3 * prim_id + draw_data.base_index;
+    uint e0 = index_buffer.Load(index0);
     uint e1 = index_buffer.Load(index0 + 1);
     uint e2 = index_buffer.Load(index0 + 2);
 
+    float4x4 world = instance_data[instance_id.id].world;
     float3 vertex0 = input.vertex0;
-    float3 vertex1 = vertex_buffer_position.Load(draw_data.base_vertex + e1);
-    float3 vertex2 = vertex_buffer_position.Load(draw_data.base_vertex + e2);
+    float3 vertex1 = transform_position(world, vertex_buffer_position.Load(draw_data.base_vertex + e1));
+    float3 vertex2 = transform_position(world, vertex_buffer_position.Load(draw_data.base_vertex + e2));
 
     float3 barycentric = raycast_triangle_barycentric(
         camera_pos.xyz,
@@ -46,9 +52,19 @@ uint4 ps_main(
         vertex0, vertex1, vertex2
     );
 
+    // Alpha test with the texture coordinates of the covered surface point.
+    _MaterialData material = materials[draw_data.material_id];
+    if (material.alpha_mode == ALPHA_MODE_MASK) {
+        float2 uv = interpolate_attribute(VERTEX_UV0, draw_data.base_vertex, uint3(e0, e1, e2), barycentric).xy;
+        if (material_alpha(material, uv, ddx(uv), ddy(uv)) < material.alpha_cutoff) {
+            discard;
+        }
+    }
+
+    // Ids are clamped below `INVALID_ID`, which marks uncovered pixels.
     return uint4(
-        prim_id,
-        geometry_id.id,
+        min(prim_id, INVALID_ID - 1),
+        min(instance_id.id, INVALID_ID - 1),
         pack_barycentric_f16(barycentric.xy)
     );
 }
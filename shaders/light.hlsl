@@ -0,0 +1,62 @@
+// Light resources
+//
+// Using space4. Lights point along their local -z axis, colors are linear RGB.
+
+struct LightData {
+    uint num_point_lights;
+    uint num_directional_lights;
+    uint num_spot_lights;
+    uint num_area_lights;
+};
+ConstantBuffer<LightData> light_data : register(b0, space4);
+
+struct PointLight {
+    float3 position;
+    float intensity; // luminous intensity (cd)
+    float3 color;
+    float range; // no influence beyond
+    uint shadow; // first cube face shadow view
+};
+StructuredBuffer<PointLight> point_lights : register(t0, space4);
+
+struct DirectionalLight {
+    float3 direction; // normalized, pointing away from the light
+    float illuminance; // lux
+    float3 color;
+    uint shadow; // first cascade shadow view
+};
+StructuredBuffer<DirectionalLight> directional_lights : register(t1, space4);
+
+struct SpotLight {
+    float3 position;
+    float intensity; // luminous intensity (cd)
+    float3 color;
+    float angle_scale; // 1 / (cos(inner angle) - cos(outer angle))
+    float3 direction; // normalized cone axis
+    float angle_offset; // -cos(outer angle) * angle_scale
+    float range; // no influence beyond
+    uint shadow; // shadow view
+    float2 _alignment;
+};
+StructuredBuffer<SpotLight> spot_lights : register(t2, space4);
+
+// Must match with `AREA_SHAPE_*` in `light.rs`.
+#define AREA_SHAPE_RECT 0
+#define AREA_SHAPE_DISK 1
+
+struct AreaLight {
+    float3 position; // center
+    float luminance; // cd/m^2, emitted from the front face only
+    float3 color;
+    uint shape;
+    float3 axis_x; // half extent or radius along the local x axis
+    float3 axis_y; // half extent or radius along the local y axis
+};
+StructuredBuffer<AreaLight> area_lights : register(t3, space4);
+
+// Inverse square law, windowed to fall off smoothly to zero at `range`.
+float distance_attenuation(float dist_sq, float range) {
+    float ratio = dist_sq / (range * range);
+    float window = saturate(1.0 - ratio * ratio);
+    return window * window / dist_sq;
+}
@@ -1,51 +1,157 @@
 
 #include "shaders/pack.hlsl"
+#include "shaders/resources.hlsl"
 #include "shaders/resources_triangle.hlsl"
+#include "shaders/material.hlsl"
+#include "shaders/brdf.hlsl"
+#include "shaders/ltc.hlsl"
+#include "shaders/light.hlsl"
+#include "shaders/cluster.hlsl"
+#include "shaders/shadow.hlsl"
+#include "shaders/environment.hlsl"
 
 // Draw information ( + triangle resources) ----------------------- space 1
 StructuredBuffer<_DrawData> g_draw_data : register(t2, space1);
 
-// Texture data --------------------------------------------------- space 2
-Texture2D<float4> textures[] : register(t0, space2);
-SamplerState sampler_texture : register(s0, space2);
+// View data (see resources.hlsl) -------------------------------- space 0
+// Texture data (see material.hlsl) ------------------------------- space 2
+// Material data (see material.hlsl) ------------------------------ space 5
 
 // Input/Ouput render targets ------------------------------------- space 3
 RWTexture2D<float4> lighting_buffer : register(u0, space3);
+#ifndef SAMPLES
+#define SAMPLES 1
+#endif
+
+#if SAMPLES > 1
+Texture2DMS<uint4, SAMPLES> geometry_buffer : register(t1, space3);
+#else
 Texture2D<uint4> geometry_buffer : register(t1, space3);
+#endif
 
-// Light information ---------------------------------------------- space 4
-struct LightData {
-    uint num_point_lights;
+struct TargetData {
+    uint2 extent; // rendered region, starting at the origin
 };
-ConstantBuffer<LightData> light_data : register(b0, space4);
+ConstantBuffer<TargetData> target_data : register(b0, space3);
 
-struct PointLight {
-    float3 position;
-    float intensity; // TODO: unit luminous intensity
-};
-StructuredBuffer<PointLight> point_lights : register(t0, space4);
+// Light information (see light.hlsl) ----------------------------- space 4
+StructuredBuffer<_LtcData> ltc_table : register(t4, space4);
+// Shadow views (see shadow.hlsl)
 
+// Light clusters (see cluster.hlsl) ------------------------------ space 6
+StructuredBuffer<uint2> cluster_grid : register(t0, space6); // point and spot light counts
+StructuredBuffer<uint> cluster_light_indices : register(t1, space6); // MAX_CLUSTER_LIGHTS per cluster
 
-[numthreads(16, 16, 1)]
-void cs_lighting(
-    uint3 thread_id: SV_DispatchThreadID,
-    uint3 tile_thread_id: SV_GroupThreadID
+// Shadow atlas (see shadow.hlsl) --------------------------------- space 7
+// Environment (see environment.hlsl) ----------------------------- space 8
+
+// Disks are approximated by regular polygons with the same area.
+#define DISK_VERTICES 8
+// sqrt(PI / (DISK_VERTICES / 2 * sin(2 * PI / DISK_VERTICES)))
+#define DISK_RADIUS_SCALE 1.0539
+
+// Vertices of an area light relative to `origin`, front faces are wound clockwise
+// seen from the front.
+uint area_light_polygon(AreaLight light, float3 origin, out float3 points[MAX_POLYGON_VERTICES]) {
+    float3 center = light.position - origin;
+    for (uint i = 0; i < MAX_POLYGON_VERTICES; i++) {
+        points[i] = center;
+    }
+
+    if (light.shape == AREA_SHAPE_DISK) {
+        for (uint v = 0; v < DISK_VERTICES; v++) {
+            float angle = 2.0 * PI * v / DISK_VERTICES;
+            points[v] += (light.axis_x * cos(angle) + light.axis_y * sin(angle)) * DISK_RADIUS_SCALE;
+        }
+        return DISK_VERTICES;
+    }
+
+    points[0] += -light.axis_x - light.axis_y;
+    points[1] += light.axis_x - light.axis_y;
+    points[2] += light.axis_x + light.axis_y;
+    points[3] += -light.axis_x + light.axis_y;
+    return 4;
+}
+
+// Texture coordinate gradients along the pixel axes.
+//
+// Compute shaders have no screen space derivatives, the gradients are the differences
+// to the triangle points hit by the rays through the neighboring pixels.
+void uv_gradients(
+    float3 world_position,
+    float3 vertex0, float3 vertex1, float3 vertex2,
+    uint base_vertex, uint3 indices, float2 uv,
+    out float2 uv_dx, out float2 uv_dy
 ) {
-    uint4 geometry = geometry_buffer.Load(uint3(thread_id.xy, 0));
+    // View direction scaled to unit depth in view space.
+    float3x3 view_rotation = (float3x3)view;
+    float3 dir = world_position - camera_pos.xyz;
+    dir /= -mul(view_rotation, dir).z;
+
+    // Offset of the direction to the neighboring pixels.
+    float3 pixel_dx = mul(float3(2.0 / (target_data.extent.x * proj[0][0]), 0.0, 0.0), view_rotation);
+    float3 pixel_dy = mul(float3(0.0, -2.0 / (target_data.extent.y * proj[1][1]), 0.0), view_rotation);
+
+    float3 barycentric_dx = raycast_triangle_barycentric(camera_pos.xyz, dir + pixel_dx, vertex0, vertex1, vertex2);
+    float3 barycentric_dy = raycast_triangle_barycentric(camera_pos.xyz, dir + pixel_dy, vertex0, vertex1, vertex2);
+    uv_dx = interpolate_attribute(VERTEX_UV0, base_vertex, indices, barycentric_dx).xy - uv;
+    uv_dy = interpolate_attribute(VERTEX_UV0, base_vertex, indices, barycentric_dy).xy - uv;
+}
+
+// Shading normal from the interpolated vertex normal and the material normal map.
+//
+// Normal maps only store `x` and `y` of the tangent space normal (BC5), `z` is
+// reconstructed. Meshes without normals fall back to the triangle normal facing
+// the viewer, without tangents the normal map is ignored.
+float3 surface_normal(
+    float4x4 world, float3 vertex0, float3 vertex1, float3 vertex2, float3 view_dir,
+    uint base_vertex, uint3 indices, float3 barycentric,
+    uint normal_texture, float2 uv, float2 uv_dx, float2 uv_dy
+) {
+    // Directions are transformed with the upper 3x3 matrix, assuming uniform scaling.
+    float3 normal = mul((float3x3)world, interpolate_attribute(VERTEX_NORMAL, base_vertex, indices, barycentric).xyz);
+    if (dot(normal, normal) == 0.0) {
+        normal = cross(vertex1 - vertex0, vertex2 - vertex0);
+        normal = dot(normal, view_dir) < 0.0 ? -normal : normal;
+        return normalize(normal);
+    }
+    normal = normalize(normal);
+
+    float4 tangent = interpolate_attribute(VERTEX_TANGENT, base_vertex, indices, barycentric);
+    tangent.xyz = mul((float3x3)world, tangent.xyz);
+    if (normal_texture == INVALID_TEXTURE || dot(tangent.xyz, tangent.xyz) == 0.0) {
+        return normal;
+    }
+    tangent.xyz = normalize(tangent.xyz - normal * dot(normal, tangent.xyz));
+    float3 bitangent = cross(normal, tangent.xyz) * (tangent.w < 0.0 ? -1.0 : 1.0);
+
+    float2 normal_xy = sample_material_texture(normal_texture, uv, uv_dx, uv_dy, float4(0.5, 0.5, 1.0, 1.0)).xy * 2.0 - 1.0;
+    float normal_z = sqrt(saturate(1.0 - dot(normal_xy, normal_xy)));
+    return normalize(tangent.xyz * normal_xy.x + bitangent * normal_xy.y + normal * normal_z);
+}
+
+// Shade the surface point stored in a geometry buffer sample of `pixel`.
+//
+// Background samples aren't covered by any surface and stay unlit.
+float3 shade(uint4 geometry, uint2 pixel) {
     uint prim_id = geometry.x;
-    uint geometry_id = geometry.y;
+    uint instance_id = geometry.y;
+    if (instance_id == INVALID_ID) {
+        return float3(0.0, 0.0, 0.0);
+    }
 
     // Reconstruct triangle -----------------------------------------
-    _DrawData draw_data = g_draw_data[geometry_id];
+    _InstanceData instance = instance_data[instance_id];
+    _DrawData draw_data = g_draw_data[instance.geometry_id];
 
     uint index0 = 3 * prim_id + draw_data.base_index;
     uint e0 = index_buffer.Load(index0);
     uint e1 = index_buffer.Load(index0 + 1);
     uint e2 = index_buffer.Load(index0 + 2);
 
-    float3 vertex0 = vertex_buffer_position.Load(draw_data.base_vertex + e0);
-    float3 vertex1 = vertex_buffer_position.Load(draw_data.base_vertex + e1);
-    float3 vertex2 = vertex_buffer_position.Load(draw_data.base_vertex + e2);
+    float3 vertex0 = transform_position(instance.world, vertex_buffer_position.Load(draw_data.base_vertex + e0));
+    float3 vertex1 = transform_position(instance.world, vertex_buffer_position.Load(draw_data.base_vertex + e1));
+    float3 vertex2 = transform_position(instance.world, vertex_buffer_position.Load(draw_data.base_vertex + e2));
 
     // Reconstruct barycentrics
     float2 barycentrics = unpack_barycentric_f16(geometry.zw);
@@ -54,18 +160,152 @@ void cs_lighting(
     float bary_w = 1.0 - bary_u - bary_v;
 
     float3 world_position = vertex0 * bary_u + vertex1 * bary_v + vertex2 * bary_w;
+
+    // Lights of the cluster
+    float view_depth = -mul(view, float4(world_position, 1.0)).z;
+    uint cluster = cluster_index(pixel_cluster(pixel, view_depth));
+    uint2 num_cluster_lights = cluster_grid[cluster];
+    uint cluster_base = cluster * MAX_CLUSTER_LIGHTS;
+    if (cluster_data.debug_heatmap != 0) {
+        return light_heatmap(num_cluster_lights.x + num_cluster_lights.y);
+    }
+
+    uint3 indices = uint3(e0, e1, e2);
+    float3 barycentric = float3(bary_u, bary_v, bary_w);
+    float2 uv = interpolate_attribute(VERTEX_UV0, draw_data.base_vertex, indices, barycentric).xy;
+    float4 vertex_color = interpolate_attribute(VERTEX_COLOR, draw_data.base_vertex, indices, barycentric);
+
+    float2 uv_dx, uv_dy;
+    uv_gradients(
+        world_position,
+        vertex0, vertex1, vertex2,
+        draw_data.base_vertex, indices, uv,
+        uv_dx, uv_dy
+    );
+
+    // Material -----------------------------------------------------
+    _MaterialData material = materials[draw_data.material_id];
+    float4 white = float4(1.0, 1.0, 1.0, 1.0);
+    float3 albedo = material.albedo.rgb * vertex_color.rgb
+        * sample_material_texture(material.albedo_texture, uv, uv_dx, uv_dy, white).rgb;
+    float3 emissive = material.emissive
+        * sample_material_texture(material.emissive_texture, uv, uv_dx, uv_dy, white).rgb;
+    float4 roughness_metal = sample_material_texture(material.roughness_metal_texture, uv, uv_dx, uv_dy, white);
+
+    float3 view_dir = normalize(camera_pos.xyz - world_position);
+
+    Surface surface;
+    surface.normal = surface_normal(
+        instance.world, vertex0, vertex1, vertex2, view_dir,
+        draw_data.base_vertex, indices, barycentric,
+        material.normal_texture, uv, uv_dx, uv_dy
+    );
+    surface.albedo = albedo;
+    surface.roughness = material.roughness * roughness_metal.g;
+    surface.metallic = material.metallic * roughness_metal.b;
+
     float3 lighting = float3(0.0, 0.0, 0.0);
 
     // Accumulate lighting -----------------------------------------
-    // Point lights
-    for (uint i = 0; i < light_data.num_point_lights; i++) {
-        PointLight point_light = point_lights[i];
+    // Point lights, illuminance (lux) from the windowed inverse square law.
+    for (uint i = 0; i < num_cluster_lights.x; i++) {
+        PointLight point_light = point_lights[cluster_light_indices[cluster_base + i]];
         float3 v_light = point_light.position - world_position;
-        float distSq = dot(v_light, v_light);
-        float light = point_light.intensity / distSq;
+        float dist_sq = dot(v_light, v_light);
+        float3 illuminance = point_light.color
+            * (point_light.intensity * distance_attenuation(dist_sq, point_light.range));
+        illuminance *= point_shadow(point_light.shadow, point_light.position, world_position, surface.normal);
+
+        lighting += evaluate_brdf(surface, view_dir, v_light * rsqrt(dist_sq)) * illuminance;
+    }
+
+    // Directional lights
+    for (uint d = 0; d < light_data.num_directional_lights; d++) {
+        DirectionalLight directional_light = directional_lights[d];
+        float3 illuminance = directional_light.color * directional_light.illuminance;
+        illuminance *= directional_shadow(directional_light.shadow, world_position, surface.normal, view_depth);
+
+        lighting += evaluate_brdf(surface, view_dir, -directional_light.direction) * illuminance;
+    }
+
+    // Spot lights, point lights with smooth falloff between the inner and outer cone.
+    uint spot_base = cluster_base + num_cluster_lights.x;
+    for (uint s = 0; s < num_cluster_lights.y; s++) {
+        SpotLight spot_light = spot_lights[cluster_light_indices[spot_base + s]];
+        float3 v_light = spot_light.position - world_position;
+        float dist_sq = dot(v_light, v_light);
+        float3 l = v_light * rsqrt(dist_sq);
+        float cone = saturate(dot(-l, spot_light.direction) * spot_light.angle_scale + spot_light.angle_offset);
+        float attenuation = distance_attenuation(dist_sq, spot_light.range);
+        float3 illuminance = spot_light.color * (spot_light.intensity * cone * cone * attenuation);
+        illuminance *= spot_shadow(spot_light.shadow, spot_light.position, world_position, surface.normal);
+
+        lighting += evaluate_brdf(surface, view_dir, l) * illuminance;
+    }
+
+    // Area lights
+    if (light_data.num_area_lights > 0) {
+        _LtcData ltc = ltc_lookup(ltc_table, surface.roughness, dot(surface.normal, view_dir));
+        float3x3 identity = float3x3(1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0);
+        float3x3 ltc_matrix = ltc_inv_matrix(ltc);
+        float3 f0 = specular_f0(surface);
+        float3 specular_albedo = f0 * ltc.magnitude + (1.0 - f0) * ltc.fresnel;
+
+        for (uint a = 0; a < light_data.num_area_lights; a++) {
+            AreaLight area_light = area_lights[a];
+            float3 points[MAX_POLYGON_VERTICES];
+            uint num_points = area_light_polygon(area_light, world_position, points);
+
+            float diffuse = ltc_evaluate(surface.normal, view_dir, identity, points, num_points);
+            float specular = ltc_evaluate(surface.normal, view_dir, ltc_matrix, points, num_points);
+            float3 luminance = area_light.color * area_light.luminance;
+
+            lighting += (diffuse_albedo(surface) * diffuse + specular_albedo * specular) * luminance;
+        }
+    }
+
+    // Environment
+    lighting += environment_lighting(surface, view_dir);
+
+    return lighting + emissive;
+}
+
+[numthreads(16, 16, 1)]
+void cs_lighting(
+    uint3 thread_id: SV_DispatchThreadID,
+    uint3 tile_thread_id: SV_GroupThreadID
+) {
+    // Dispatch is rounded up to full tiles.
+    if (any(thread_id.xy >= target_data.extent)) {
+        return;
+    }
+
+#if SAMPLES > 1
+    // Resolve: shade each unique triangle of the pixel once, weighted by its coverage.
+    uint4 samples[SAMPLES];
+    for (uint i = 0; i < SAMPLES; i++) {
+        samples[i] = geometry_buffer.Load(thread_id.xy, i);
+    }
+
+    float3 lighting = float3(0.0, 0.0, 0.0);
+    for (uint s = 0; s < SAMPLES; s++) {
+        bool shaded = false;
+        for (uint p = 0; p < s; p++) {
+            shaded = shaded || all(samples[p].xy == samples[s].xy);
+        }
+        if (shaded) {
+            continue;
+        }
 
-        lighting += float3(light, light, light);
+        uint coverage = 1;
+        for (uint n = s + 1; n < SAMPLES; n++) {
+            coverage += all(samples[n].xy == samples[s].xy) ? 1 : 0;
+        }
+        lighting += shade(samples[s], thread_id.xy) * (float(coverage) / SAMPLES);
     }
+#else
+    float3 lighting = shade(geometry_buffer.Load(uint3(thread_id.xy, 0)), thread_id.xy);
+#endif
 
     lighting_buffer[thread_id.xy] = float4(lighting, 0);
 }
\ No newline at end of file
@@ -0,0 +1,116 @@
+// Linearly transformed cosines for polygonal area lights
+//
+// Must match with `ltc/mod.rs`, the table is fitted to `brdf.hlsl`.
+
+// Must match with `LTC_SIZE` in `ltc/mod.rs`.
+#define LTC_SIZE 32
+
+// Upper bound of polygon vertices, e.g. disk lights.
+#define MAX_POLYGON_VERTICES 8
+
+struct _LtcData {
+    float4 inv_matrix; // entries (0, 0), (0, 2), (2, 0) and (2, 2)
+    float magnitude;
+    float fresnel;
+    float2 _alignment;
+};
+
+// Bilinear interpolation of the table entries, indexed by roughness and `sqrt(1 - n_dot_v)`.
+_LtcData ltc_lookup(StructuredBuffer<_LtcData> table, float roughness, float n_dot_v) {
+    float2 coord = float2(saturate(roughness), sqrt(1.0 - saturate(n_dot_v))) * (LTC_SIZE - 1);
+    uint2 base = min(uint2(coord), LTC_SIZE - 2);
+    float2 f = coord - base;
+
+    _LtcData e00 = table[base.y * LTC_SIZE + base.x];
+    _LtcData e10 = table[base.y * LTC_SIZE + base.x + 1];
+    _LtcData e01 = table[(base.y + 1) * LTC_SIZE + base.x];
+    _LtcData e11 = table[(base.y + 1) * LTC_SIZE + base.x + 1];
+
+    _LtcData ltc;
+    ltc.inv_matrix = lerp(lerp(e00.inv_matrix, e10.inv_matrix, f.x), lerp(e01.inv_matrix, e11.inv_matrix, f.x), f.y);
+    ltc.magnitude = lerp(lerp(e00.magnitude, e10.magnitude, f.x), lerp(e01.magnitude, e11.magnitude, f.x), f.y);
+    ltc.fresnel = lerp(lerp(e00.fresnel, e10.fresnel, f.x), lerp(e01.fresnel, e11.fresnel, f.x), f.y);
+    ltc._alignment = float2(0.0, 0.0);
+    return ltc;
+}
+
+float3x3 ltc_inv_matrix(_LtcData ltc) {
+    return float3x3(
+        ltc.inv_matrix.x, 0.0, ltc.inv_matrix.y,
+        0.0, 1.0, 0.0,
+        ltc.inv_matrix.z, 0.0, ltc.inv_matrix.w
+    );
+}
+
+// Vector form factor of an edge between two directions.
+//
+// Rational fit of `theta / sin(theta)`, including the normalization by `2 * PI`.
+float3 integrate_edge(float3 v1, float3 v2) {
+    v1 = normalize(v1);
+    v2 = normalize(v2);
+    float x = dot(v1, v2);
+    float y = abs(x);
+    float a = 0.8543985 + (0.4965155 + 0.0145206 * y) * y;
+    float b = 3.4175940 + (4.1616724 + y) * y;
+    float v = a / b;
+    float theta_sin_theta = x > 0.0 ? v : 0.5 * rsqrt(max(1.0 - x * x, 1e-7)) - v;
+    return cross(v1, v2) * theta_sin_theta;
+}
+
+// Cosine weighted integral of a convex polygon over the upper hemisphere.
+//
+// Polygons are clipped against the horizon. Front faces are wound clockwise seen
+// from the origin, back faces return zero.
+float integrate_polygon(float3 points[MAX_POLYGON_VERTICES], uint num_points) {
+    float sum = 0.0;
+    bool has_exit = false;
+    bool has_entry = false;
+    float3 exit_point = float3(0.0, 0.0, 0.0);
+    float3 entry_point = float3(0.0, 0.0, 0.0);
+    for (uint i = 0; i < num_points; i++) {
+        float3 a = points[i];
+        float3 b = points[(i + 1) % num_points];
+        float3 p = a + (b - a) * (a.z / (a.z - b.z));
+        if (a.z >= 0.0 && b.z >= 0.0) {
+            sum += integrate_edge(a, b).z;
+        } else if (a.z >= 0.0) {
+            sum += integrate_edge(a, p).z;
+            exit_point = p;
+            has_exit = true;
+        } else if (b.z >= 0.0) {
+            sum += integrate_edge(p, b).z;
+            entry_point = p;
+            has_entry = true;
+        }
+    }
+    // Closing edge along the horizon.
+    if (has_exit && has_entry) {
+        sum += integrate_edge(exit_point, entry_point).z;
+    }
+
+    return max(sum, 0.0);
+}
+
+// Integral of the transformed cosine over a polygon with `points` relative to the shading point.
+float ltc_evaluate(
+    float3 normal, float3 view, float3x3 inv_matrix,
+    float3 points[MAX_POLYGON_VERTICES], uint num_points
+) {
+    // Local frame with the view direction in the xz-plane.
+    float3 tangent = view - normal * dot(normal, view);
+    if (dot(tangent, tangent) > 1e-8) {
+        tangent = normalize(tangent);
+    } else if (abs(normal.z) < 0.999) {
+        tangent = normalize(cross(normal, float3(0.0, 0.0, 1.0)));
+    } else {
+        tangent = float3(1.0, 0.0, 0.0);
+    }
+    float3 bitangent = cross(normal, tangent);
+    float3x3 local_frame = float3x3(tangent, bitangent, normal);
+
+    float3 local[MAX_POLYGON_VERTICES];
+    for (uint i = 0; i < num_points; i++) {
+        local[i] = mul(inv_matrix, mul(local_frame, points[i]));
+    }
+    return integrate_polygon(local, num_points);
+}
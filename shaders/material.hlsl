@@ -0,0 +1,48 @@
+// Material resources
+//
+// Using space2 (textures) and space5 (material data)
+
+#define ALPHA_MODE_OPAQUE 0
+#define ALPHA_MODE_MASK 1
+#define ALPHA_MODE_BLEND 2
+
+// Texture index of materials without the respective texture.
+#define INVALID_TEXTURE 0xFFFFFFFF
+
+struct _MaterialData {
+    float4 albedo;
+    float3 emissive;
+    float roughness;
+    float metallic;
+    float alpha_cutoff;
+    uint albedo_texture;
+    uint opacity_texture;
+    uint normal_texture;
+    uint roughness_metal_texture;
+    uint emissive_texture;
+    uint alpha_mode;
+};
+
+// Bindless texture table, indexed by the material texture indices.
+Texture2D<float4> textures[] : register(t0, space2);
+SamplerState sampler_texture : register(s0, space2);
+
+StructuredBuffer<_MaterialData> materials : register(t0, space5);
+
+// Sample a material texture, `fallback` for missing textures.
+//
+// The mip level is selected from the texture coordinate gradients along the pixel axes.
+float4 sample_material_texture(uint texture_id, float2 uv, float2 uv_dx, float2 uv_dy, float4 fallback) {
+    if (texture_id == INVALID_TEXTURE) {
+        return fallback;
+    }
+    return textures[NonUniformResourceIndex(texture_id)].SampleGrad(sampler_texture, uv, uv_dx, uv_dy);
+}
+
+float material_alpha(_MaterialData material, float2 uv, float2 uv_dx, float2 uv_dy) {
+    float4 opaque = float4(1.0, 1.0, 1.0, 1.0);
+    float alpha = material.albedo.a;
+    alpha *= sample_material_texture(material.albedo_texture, uv, uv_dx, uv_dy, opaque).a;
+    alpha *= sample_material_texture(material.opacity_texture, uv, uv_dx, uv_dy, opaque).r;
+    return alpha;
+}
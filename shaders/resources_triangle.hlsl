@@ -5,10 +5,84 @@
 struct _DrawData {
     uint base_index;
     uint base_vertex;
+    uint material_id;
 };
 
+struct _InstanceData {
+    float4x4 world;
+    uint geometry_id;
+    uint3 _alignment;
+};
+
+// Primitive and instance id of geometry buffer samples not covered by any triangle.
+// Must match with `INVALID_ID` in `geometry.rs`.
+#define INVALID_ID 0xFFFF
+
 StructuredBuffer<uint> index_buffer: register(t0, space1);
 StructuredBuffer<float3> vertex_buffer_position: register(t1, space1);
+StructuredBuffer<_InstanceData> instance_data: register(t3, space1);
+
+// Vertex attribute streams, must match with `VertexAttribute` in `geometry.rs`.
+#define VERTEX_NORMAL 0
+#define VERTEX_TANGENT 1 // bitangent sign in w
+#define VERTEX_UV0 2
+#define VERTEX_UV1 3
+#define VERTEX_COLOR 4
+#define NUM_VERTEX_STREAMS 5
+
+// Stream encodings, floats store the number of components.
+#define STREAM_UNORM4 1
+#define STREAM_FLOAT2 2
+#define STREAM_FLOAT3 3
+#define STREAM_FLOAT4 4
+
+// Must match with `VERTEX_STREAMS` in `geometry.rs`.
+static const uint vertex_stream_formats[NUM_VERTEX_STREAMS] = {
+    STREAM_FLOAT3, // normal
+    STREAM_FLOAT4, // tangent
+    STREAM_FLOAT2, // uv0
+    STREAM_FLOAT2, // uv1
+    STREAM_UNORM4, // color
+};
+
+StructuredBuffer<uint> vertex_streams[NUM_VERTEX_STREAMS]: register(t4, space1);
+
+float3 transform_position(float4x4 world, float3 pos) {
+    return mul(world, float4(pos, 1.0)).xyz;
+}
+
+// Load a vertex attribute, missing components default to (0, 0, 0, 1).
+float4 load_attribute(uint attribute, uint vertex) {
+    uint format = vertex_stream_formats[attribute];
+    if (format == STREAM_UNORM4) {
+        uint packed = vertex_streams[attribute].Load(vertex);
+        return float4(
+            packed & 0xFF,
+            (packed >> 8) & 0xFF,
+            (packed >> 16) & 0xFF,
+            packed >> 24
+        ) / 255.0;
+    }
+
+    uint base = vertex * format;
+    float4 value = float4(0.0, 0.0, 0.0, 1.0);
+    value.x = asfloat(vertex_streams[attribute].Load(base));
+    value.y = asfloat(vertex_streams[attribute].Load(base + 1));
+    if (format >= STREAM_FLOAT3) {
+        value.z = asfloat(vertex_streams[attribute].Load(base + 2));
+    }
+    if (format >= STREAM_FLOAT4) {
+        value.w = asfloat(vertex_streams[attribute].Load(base + 3));
+    }
+    return value;
+}
+
+// Interpolate a vertex attribute of the triangle with the vertex `indices`.
+float4 interpolate_attribute(uint attribute, uint base_vertex, uint3 indices, float3 barycentric) {
+    return load_attribute(attribute, base_vertex + indices.x) * barycentric.x
+        + load_attribute(attribute, base_vertex + indices.y) * barycentric.y
+        + load_attribute(attribute, base_vertex + indices.z) * barycentric.z;
+}
 
 // Möller–Trumbore intersection
 float3 raycast_triangle_barycentric(float3 origin, float3 dir, float3 v0, float3 v1, float3 v2) {
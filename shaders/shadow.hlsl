@@ -0,0 +1,98 @@
+// Shadow maps
+//
+// Using space7. The shadow views of all lights are packed into a single depth atlas.
+// Point lights use six consecutive views (cube faces in +x, -x, +y, -y, +z, -z order),
+// spot lights a single view and directional lights NUM_CASCADES cascades.
+// Must match with `shadow.rs` of the reference renderer.
+
+// Must match with `pass/shadow.rs`.
+#define SHADOW_ATLAS_SIZE 4096
+#define CUBE_FACES 6
+#define NUM_CASCADES 4
+#define INVALID_SHADOW 0xFFFFFFFF
+
+// Receivers are offset along the normal by this number of texels against self-shadowing.
+#define SHADOW_NORMAL_OFFSET 1.5
+// PCF kernel of (2 * SHADOW_PCF_RADIUS + 1)^2 bilinear comparisons.
+#define SHADOW_PCF_RADIUS 1
+
+struct ShadowView {
+    float4x4 view_proj;
+    float4 atlas_rect; // texture coordinate offset (xy) and scale (zw) of the tile
+    float2 texel_size; // world space texel size, x + y * distance to the light
+    float split_depth; // far view depth of a cascade
+    float _alignment;
+};
+
+StructuredBuffer<ShadowView> shadow_views : register(t5, space4); // light table (see light.hlsl)
+Texture2D<float> shadow_atlas : register(t0, space7);
+SamplerComparisonState shadow_sampler : register(s0, space7);
+
+// Fraction of light reaching `position` in a shadow view, 1 outside of the view.
+//
+// The normal offset of perspective views grows with the `distance` to the light.
+float sample_shadow(uint view_index, float3 position, float3 normal, float distance) {
+    ShadowView shadow_view = shadow_views[view_index];
+    float offset = (shadow_view.texel_size.x + shadow_view.texel_size.y * distance) * SHADOW_NORMAL_OFFSET;
+    float4 clip = mul(shadow_view.view_proj, float4(position + normal * offset, 1.0));
+    float3 ndc = clip.xyz / clip.w;
+    if (clip.w <= 0.0 || any(abs(ndc.xy) > 1.0) || ndc.z > 1.0) {
+        return 1.0;
+    }
+
+    // Taps are clamped to the tile, neighboring tiles belong to other views.
+    float texel = 1.0 / SHADOW_ATLAS_SIZE;
+    float2 tile_min = shadow_view.atlas_rect.xy + 0.5 * texel;
+    float2 tile_max = shadow_view.atlas_rect.xy + shadow_view.atlas_rect.zw - 0.5 * texel;
+    float2 uv = shadow_view.atlas_rect.xy + float2(ndc.x * 0.5 + 0.5, 0.5 - ndc.y * 0.5) * shadow_view.atlas_rect.zw;
+
+    float visibility = 0.0;
+    for (int y = -SHADOW_PCF_RADIUS; y <= SHADOW_PCF_RADIUS; y++) {
+        for (int x = -SHADOW_PCF_RADIUS; x <= SHADOW_PCF_RADIUS; x++) {
+            float2 tap = clamp(uv + float2(x, y) * texel, tile_min, tile_max);
+            visibility += shadow_atlas.SampleCmpLevelZero(shadow_sampler, tap, ndc.z);
+        }
+    }
+    float taps = 2 * SHADOW_PCF_RADIUS + 1;
+    return visibility / (taps * taps);
+}
+
+// Cube face of the direction from the light to the surface.
+uint cube_face(float3 dir) {
+    float3 a = abs(dir);
+    if (a.x >= a.y && a.x >= a.z) {
+        return dir.x >= 0.0 ? 0 : 1;
+    }
+    if (a.y >= a.z) {
+        return dir.y >= 0.0 ? 2 : 3;
+    }
+    return dir.z >= 0.0 ? 4 : 5;
+}
+
+float point_shadow(uint shadow, float3 light_position, float3 position, float3 normal) {
+    if (shadow == INVALID_SHADOW) {
+        return 1.0;
+    }
+    float3 dir = position - light_position;
+    return sample_shadow(shadow + cube_face(dir), position, normal, length(dir));
+}
+
+float spot_shadow(uint shadow, float3 light_position, float3 position, float3 normal) {
+    if (shadow == INVALID_SHADOW) {
+        return 1.0;
+    }
+    return sample_shadow(shadow, position, normal, length(position - light_position));
+}
+
+// Cascade covering the view depth of the surface, unshadowed beyond the last cascade.
+float directional_shadow(uint shadow, float3 position, float3 normal, float view_depth) {
+    if (shadow == INVALID_SHADOW) {
+        return 1.0;
+    }
+    for (uint c = 0; c < NUM_CASCADES; c++) {
+        if (view_depth < shadow_views[shadow + c].split_depth) {
+            return sample_shadow(shadow + c, position, normal, 0.0);
+        }
+    }
+    return 1.0;
+}
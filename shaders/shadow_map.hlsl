@@ -0,0 +1,46 @@
+#include "shaders/resources_triangle.hlsl"
+#include "shaders/material.hlsl"
+#include "shaders/shadow.hlsl"
+
+// Draw information ( + triangle resources) ----------------------- space 1
+// Texture data (see material.hlsl) ------------------------------- space 2
+// Material data (see material.hlsl) ------------------------------ space 5
+
+ConstantBuffer<_DrawData> draw_data : register(b0, space2);
+
+struct InstanceId {
+    uint id;
+};
+ConstantBuffer<InstanceId> instance_id : register(b1, space2);
+
+// Shadow view (see shadow.hlsl) ---------------------------------- space 7
+ConstantBuffer<ShadowView> shadow_view : register(b0, space7);
+
+struct VsInput {
+    float3 pos: Attr0;
+};
+
+struct VsOutput {
+    float4 pos: SV_Position;
+    float2 uv: TEXCOORD;
+};
+
+// `SV_VertexID` includes the base vertex of the draw.
+VsOutput vs_shadow(VsInput input, uint vertex_id: SV_VertexID) {
+    float4x4 world = instance_data[instance_id.id].world;
+
+    VsOutput output;
+    output.pos = mul(shadow_view.view_proj, float4(transform_position(world, input.pos), 1.0));
+    output.uv = load_attribute(VERTEX_UV0, vertex_id).xy;
+    return output;
+}
+
+// Depth only, alpha tested materials discard like in the geometry pass.
+void ps_shadow(VsOutput input) {
+    _MaterialData material = materials[draw_data.material_id];
+    if (material.alpha_mode == ALPHA_MODE_MASK) {
+        if (material_alpha(material, input.uv, ddx(input.uv), ddy(input.uv)) < material.alpha_cutoff) {
+            discard;
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! Adapter enumeration and selection.
+//!
+//! Backends expose their adapters through the `Adapter` trait, the selection policy
+//! is independent of the backend and only operates on the reported capabilities.
+
+use backend::{EngineError, ErrorKind};
+use std::fmt;
+use std::str::FromStr;
+
+/// Minimum set of hardware features supported by a device.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FeatureLevel {
+    L11_0,
+    L11_1,
+    L12_0,
+    L12_1,
+}
+
+impl fmt::Display for FeatureLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            FeatureLevel::L11_0 => "11_0",
+            FeatureLevel::L11_1 => "11_1",
+            FeatureLevel::L12_0 => "12_0",
+            FeatureLevel::L12_1 => "12_1",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for FeatureLevel {
+    type Err = String;
+
+    /// Parse `11_0`, `11_1`, `12_0` or `12_1`, `.` is accepted as separator.
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.replace('.', "_").as_str() {
+            "11_0" => Ok(FeatureLevel::L11_0),
+            "11_1" => Ok(FeatureLevel::L11_1),
+            "12_0" => Ok(FeatureLevel::L12_0),
+            "12_1" => Ok(FeatureLevel::L12_1),
+            _ => Err(format!("unknown feature level `{}`", s)),
+        }
+    }
+}
+
+/// Description of an adapter as reported by the backend.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdapterInfo {
+    /// Enumeration index of the adapter.
+    pub index: u32,
+    pub name: String,
+    /// Dedicated video memory in bytes.
+    pub dedicated_video_memory: u64,
+    /// Software rasterizer, e.g. WARP.
+    pub software: bool,
+}
+
+/// Adapter enumerated by a backend.
+pub trait Adapter {
+    fn info(&self) -> AdapterInfo;
+
+    /// Whether a device with feature level `level` can be created on the adapter.
+    fn supports(&self, level: FeatureLevel) -> bool;
+}
+
+/// Preferred adapter among the ones supporting the minimum feature level.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AdapterPreference {
+    /// First hardware adapter, falling back to software adapters.
+    First,
+    /// Adapter with the given enumeration index.
+    Index(u32),
+    /// First adapter whose name contains the string, ignoring case.
+    Name(String),
+    /// Adapter with the largest amount of dedicated video memory.
+    LargestVram,
+}
+
+impl Default for AdapterPreference {
+    fn default() -> Self {
+        AdapterPreference::First
+    }
+}
+
+impl FromStr for AdapterPreference {
+    type Err = String;
+
+    /// Parse `first`, `largest-vram`, an adapter index or a name substring.
+    fn from_str(s: &str) -> Result<Self, String> {
+        if s.is_empty() {
+            return Err("empty adapter preference".into());
+        }
+        Ok(match s {
+            "first" => AdapterPreference::First,
+            "largest-vram" => AdapterPreference::LargestVram,
+            _ => match s.parse::<u32>() {
+                Ok(index) => AdapterPreference::Index(index),
+                Err(_) => AdapterPreference::Name(s.into()),
+            },
+        })
+    }
+}
+
+/// Select an adapter according to `preference`.
+///
+/// Only adapters supporting `min_feature_level` are considered.
+pub fn select_adapter<A, I>(
+    adapters: I,
+    min_feature_level: FeatureLevel,
+    preference: &AdapterPreference,
+) -> Result<A, EngineError>
+where
+    A: Adapter,
+    I: IntoIterator<Item = A>,
+{
+    let mut candidates = adapters
+        .into_iter()
+        .filter(|adapter| adapter.supports(min_feature_level))
+        .map(|adapter| (adapter.info(), adapter))
+        .collect::<Vec<_>>();
+
+    let selected = match *preference {
+        AdapterPreference::First => candidates
+            .iter()
+            .position(|&(ref info, _)| !info.software)
+            .or_else(|| if candidates.is_empty() { None } else { Some(0) }),
+        AdapterPreference::Index(index) => candidates
+            .iter()
+            .position(|&(ref info, _)| info.index == index),
+        AdapterPreference::Name(ref name) => {
+            let name = name.to_lowercase();
+            candidates
+                .iter()
+                .position(|&(ref info, _)| info.name.to_lowercase().contains(&name))
+        }
+        AdapterPreference::LargestVram => {
+            let mut largest: Option<(usize, u64)> = None;
+            for (i, &(ref info, _)) in candidates.iter().enumerate() {
+                match largest {
+                    Some((_, memory)) if memory >= info.dedicated_video_memory => (),
+                    _ => largest = Some((i, info.dedicated_video_memory)),
+                }
+            }
+            largest.map(|(i, _)| i)
+        }
+    };
+
+    match selected {
+        Some(i) => Ok(candidates.swap_remove(i).1),
+        None => Err(EngineError::new(
+            "select_adapter",
+            format!("{:?} with feature level {}", preference, min_feature_level),
+            ErrorKind::NoAdapter,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockAdapter {
+        info: AdapterInfo,
+        max_feature_level: FeatureLevel,
+    }
+
+    impl Adapter for MockAdapter {
+        fn info(&self) -> AdapterInfo {
+            self.info.clone()
+        }
+
+        fn supports(&self, level: FeatureLevel) -> bool {
+            level <= self.max_feature_level
+        }
+    }
+
+    fn adapter(index: u32, name: &str, vram_mb: u64, software: bool) -> MockAdapter {
+        MockAdapter {
+            info: AdapterInfo {
+                index,
+                name: name.into(),
+                dedicated_video_memory: vram_mb * 1024 * 1024,
+                software,
+            },
+            max_feature_level: FeatureLevel::L12_1,
+        }
+    }
+
+    /// Software adapter enumerated first, a hardware adapter only supporting 11_0.
+    fn adapters() -> Vec<MockAdapter> {
+        vec![
+            adapter(0, "Microsoft Basic Render Driver", 0, true),
+            MockAdapter {
+                max_feature_level: FeatureLevel::L11_0,
+                ..adapter(1, "Intel UHD Graphics", 128, false)
+            },
+            adapter(2, "NVIDIA GeForce GTX 1080", 8192, false),
+            adapter(3, "AMD Radeon RX 580", 4096, false),
+        ]
+    }
+
+    fn select(level: FeatureLevel, preference: AdapterPreference) -> Result<u32, EngineError> {
+        select_adapter(adapters(), level, &preference).map(|adapter| adapter.info.index)
+    }
+
+    #[test]
+    fn first_prefers_hardware() {
+        assert_eq!(
+            select(FeatureLevel::L11_0, AdapterPreference::First).unwrap(),
+            1
+        );
+        assert_eq!(
+            select(FeatureLevel::L12_0, AdapterPreference::First).unwrap(),
+            2
+        );
+
+        let software = vec![adapter(0, "WARP", 0, true)];
+        let selected =
+            select_adapter(software, FeatureLevel::L12_0, &AdapterPreference::First).unwrap();
+        assert_eq!(selected.info.index, 0);
+    }
+
+    #[test]
+    fn index() {
+        assert_eq!(
+            select(FeatureLevel::L12_0, AdapterPreference::Index(3)).unwrap(),
+            3
+        );
+        assert_eq!(
+            select(FeatureLevel::L11_0, AdapterPreference::Index(1)).unwrap(),
+            1
+        );
+        // Enumerated, but not supporting the feature level.
+        assert!(select(FeatureLevel::L12_0, AdapterPreference::Index(1)).is_err());
+    }
+
+    #[test]
+    fn name_ignores_case() {
+        let name = |name: &str| AdapterPreference::Name(name.into());
+        assert_eq!(select(FeatureLevel::L12_0, name("radeon")).unwrap(), 3);
+        assert_eq!(select(FeatureLevel::L12_0, name("GEFORCE")).unwrap(), 2);
+        assert_eq!(select(FeatureLevel::L11_0, name("uhd")).unwrap(), 1);
+    }
+
+    #[test]
+    fn largest_vram() {
+        assert_eq!(
+            select(FeatureLevel::L11_0, AdapterPreference::LargestVram).unwrap(),
+            2
+        );
+
+        // Ties keep the first adapter.
+        let tied = vec![adapter(0, "a", 1024, false), adapter(1, "b", 1024, false)];
+        let selected =
+            select_adapter(tied, FeatureLevel::L12_0, &AdapterPreference::LargestVram).unwrap();
+        assert_eq!(selected.info.index, 0);
+    }
+
+    #[test]
+    fn no_adapter() {
+        let no_adapter = |result: Result<u32, EngineError>| match result {
+            Err(ref err) => err.kind == ErrorKind::NoAdapter,
+            Ok(_) => false,
+        };
+        assert!(no_adapter(select(
+            FeatureLevel::L12_0,
+            AdapterPreference::Index(7)
+        )));
+        assert!(no_adapter(select(
+            FeatureLevel::L12_0,
+            AdapterPreference::Name("Matrox".into())
+        )));
+        for preference in &[AdapterPreference::First, AdapterPreference::LargestVram] {
+            let result = select_adapter(Vec::<MockAdapter>::new(), FeatureLevel::L11_0, preference);
+            assert!(no_adapter(result.map(|adapter| adapter.info.index)));
+        }
+    }
+}
@@ -0,0 +1,1825 @@
+//! D3D12 backend.
+
+use std::ffi::CString;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{mem, ptr, slice};
+
+use winapi::shared::dxgi::*;
+use winapi::shared::dxgi1_2::*;
+use winapi::shared::dxgi1_3::*;
+use winapi::shared::dxgi1_4::*;
+use winapi::shared::dxgiformat::*;
+use winapi::shared::dxgitype::*;
+use winapi::shared::minwindef::{FALSE, TRUE, UINT};
+use winapi::shared::ntdef::{HANDLE, HRESULT};
+use winapi::shared::winerror;
+use winapi::um::d3d12::*;
+use winapi::um::d3d12sdklayers::*;
+use winapi::um::d3dcommon::*;
+use winapi::um::d3dcompiler::*;
+use winapi::um::synchapi::*;
+use winapi::um::winbase::WAIT_OBJECT_0;
+use winapi::Interface;
+use winit;
+use winit::os::windows::WindowExt;
+use wio::com::ComPtr;
+
+use backend::{self, *};
+use config::EngineConfig;
+
+pub enum D3D12 {}
+
+impl Backend for D3D12 {
+    type Device = Device;
+    type Resource = Resource;
+    type DescriptorHeap = DescriptorHeap;
+    type Heap = Heap;
+    type CommandAllocator = CommandAllocator;
+    type CommandList = CommandList;
+    type Fence = Fence;
+    type Shader = Shader;
+    type RootSignature = RootSignature;
+    type PipelineState = PipelineState;
+    type Swapchain = Swapchain;
+
+    fn create_device(config: &EngineConfig) -> Result<Device, EngineError> {
+        Device::new(config)
+    }
+
+    fn enumerate_adapters() -> Result<Vec<AdapterInfo>, EngineError> {
+        let factory = create_factory(false)?;
+        let adapters = enumerate_adapters(&factory)?;
+        Ok(adapters.iter().map(Adapter::info).collect())
+    }
+}
+
+#[derive(Clone)]
+pub struct Resource(pub ComPtr<ID3D12Resource>);
+unsafe impl Send for Resource {}
+unsafe impl Sync for Resource {}
+
+impl ResourceId for Resource {
+    fn resource_id(&self) -> u64 {
+        self.0.as_raw() as u64
+    }
+}
+
+pub struct DescriptorHeap {
+    pub heap: ComPtr<ID3D12DescriptorHeap>,
+    shader_visible: bool,
+}
+unsafe impl Send for DescriptorHeap {}
+unsafe impl Sync for DescriptorHeap {}
+
+pub struct Heap(pub ComPtr<ID3D12Heap>);
+unsafe impl Send for Heap {}
+unsafe impl Sync for Heap {}
+
+pub struct CommandAllocator(pub ComPtr<ID3D12CommandAllocator>);
+pub struct CommandList(pub ComPtr<ID3D12GraphicsCommandList>);
+pub struct Fence(pub ComPtr<ID3D12Fence>);
+pub struct Shader(pub ComPtr<ID3DBlob>);
+pub struct RootSignature(pub ComPtr<ID3D12RootSignature>);
+pub struct PipelineState(pub ComPtr<ID3D12PipelineState>);
+
+pub struct Swapchain {
+    swapchain: ComPtr<IDXGISwapChain3>,
+    buffers: Vec<Resource>,
+}
+
+pub struct Device {
+    pub factory: ComPtr<IDXGIFactory4>,
+    pub device: ComPtr<ID3D12Device>,
+    pub queue: ComPtr<ID3D12CommandQueue>,
+    pub wait_event: HANDLE,
+    // Set by `simulate_device_removed`.
+    removed: AtomicBool,
+}
+
+const NULL_SHADER: D3D12_SHADER_BYTECODE = D3D12_SHADER_BYTECODE {
+    pShaderBytecode: ptr::null_mut(),
+    BytecodeLength: 0,
+};
+
+const DEFAULT_PIPELINE_STATE_DESC: D3D12_GRAPHICS_PIPELINE_STATE_DESC =
+    D3D12_GRAPHICS_PIPELINE_STATE_DESC {
+        pRootSignature: ptr::null_mut(),
+        VS: NULL_SHADER,
+        PS: NULL_SHADER,
+        DS: NULL_SHADER,
+        HS: NULL_SHADER,
+        GS: NULL_SHADER,
+        StreamOutput: D3D12_STREAM_OUTPUT_DESC {
+            pSODeclaration: ptr::null(),
+            NumEntries: 0,
+            pBufferStrides: ptr::null(),
+            NumStrides: 0,
+            RasterizedStream: 0,
+        },
+        BlendState: D3D12_BLEND_DESC {
+            AlphaToCoverageEnable: FALSE,
+            IndependentBlendEnable: FALSE,
+            RenderTarget: [D3D12_RENDER_TARGET_BLEND_DESC {
+                BlendEnable: FALSE,
+                LogicOpEnable: FALSE,
+                SrcBlend: D3D12_BLEND_ONE,
+                DestBlend: D3D12_BLEND_ZERO,
+                BlendOp: D3D12_BLEND_OP_ADD,
+                SrcBlendAlpha: D3D12_BLEND_ONE,
+                DestBlendAlpha: D3D12_BLEND_ZERO,
+                BlendOpAlpha: D3D12_BLEND_OP_ADD,
+                LogicOp: D3D12_LOGIC_OP_NOOP,
+                RenderTargetWriteMask: D3D12_COLOR_WRITE_ENABLE_ALL as _,
+            }; 8],
+        },
+        SampleMask: !0,
+        RasterizerState: D3D12_RASTERIZER_DESC {
+            FillMode: D3D12_FILL_MODE_SOLID,
+            CullMode: D3D12_CULL_MODE_NONE,
+            FrontCounterClockwise: TRUE,
+            DepthBias: 0,
+            DepthBiasClamp: 0.0,
+            SlopeScaledDepthBias: 0.0,
+            DepthClipEnable: TRUE,
+            MultisampleEnable: FALSE,
+            AntialiasedLineEnable: FALSE,
+            ForcedSampleCount: 0,
+            ConservativeRaster: D3D12_CONSERVATIVE_RASTERIZATION_MODE_OFF,
+        },
+        DepthStencilState: D3D12_DEPTH_STENCIL_DESC {
+            DepthEnable: FALSE,
+            DepthWriteMask: D3D12_DEPTH_WRITE_MASK_ALL,
+            DepthFunc: D3D12_COMPARISON_FUNC_LESS,
+            StencilEnable: FALSE,
+            StencilReadMask: D3D12_DEFAULT_STENCIL_READ_MASK as _,
+            StencilWriteMask: D3D12_DEFAULT_STENCIL_WRITE_MASK as _,
+            FrontFace: D3D12_DEPTH_STENCILOP_DESC {
+                StencilFailOp: D3D12_STENCIL_OP_KEEP,
+                StencilDepthFailOp: D3D12_STENCIL_OP_KEEP,
+                StencilPassOp: D3D12_STENCIL_OP_KEEP,
+                StencilFunc: D3D12_COMPARISON_FUNC_ALWAYS,
+            },
+            BackFace: D3D12_DEPTH_STENCILOP_DESC {
+                StencilFailOp: D3D12_STENCIL_OP_KEEP,
+                StencilDepthFailOp: D3D12_STENCIL_OP_KEEP,
+                StencilPassOp: D3D12_STENCIL_OP_KEEP,
+                StencilFunc: D3D12_COMPARISON_FUNC_ALWAYS,
+            },
+        },
+        InputLayout: D3D12_INPUT_LAYOUT_DESC {
+            pInputElementDescs: ptr::null(),
+            NumElements: 0,
+        },
+        IBStripCutValue: D3D12_INDEX_BUFFER_STRIP_CUT_VALUE_DISABLED,
+        PrimitiveTopologyType: D3D12_PRIMITIVE_TOPOLOGY_TYPE_UNDEFINED,
+        NumRenderTargets: 0,
+        RTVFormats: [DXGI_FORMAT_UNKNOWN; 8],
+        DSVFormat: DXGI_FORMAT_UNKNOWN,
+        SampleDesc: DXGI_SAMPLE_DESC {
+            Count: 1,
+            Quality: 0,
+        },
+        NodeMask: 0,
+        CachedPSO: D3D12_CACHED_PIPELINE_STATE {
+            pCachedBlob: ptr::null(),
+            CachedBlobSizeInBytes: 0,
+        },
+        Flags: D3D12_PIPELINE_STATE_FLAG_NONE,
+    };
+
+const DEFAULT_SHADER_4_COMPONENT_MAPPING: UINT = 0x1688;
+
+pub fn map_format(format: Format) -> DXGI_FORMAT {
+    match format {
+        Format::Unknown => DXGI_FORMAT_UNKNOWN,
+        Format::R32Typeless => DXGI_FORMAT_R32_TYPELESS,
+        Format::R32Uint => DXGI_FORMAT_R32_UINT,
+        Format::R32Float => DXGI_FORMAT_R32_FLOAT,
+        Format::R32G32Float => DXGI_FORMAT_R32G32_FLOAT,
+        Format::R32G32B32Float => DXGI_FORMAT_R32G32B32_FLOAT,
+        Format::R32G32B32A32Float => DXGI_FORMAT_R32G32B32A32_FLOAT,
+        Format::R16G16B16A16Typeless => DXGI_FORMAT_R16G16B16A16_TYPELESS,
+        Format::R16G16B16A16Uint => DXGI_FORMAT_R16G16B16A16_UINT,
+        Format::R16G16B16A16Float => DXGI_FORMAT_R16G16B16A16_FLOAT,
+        Format::R8G8B8A8Unorm => DXGI_FORMAT_R8G8B8A8_UNORM,
+        Format::R8G8B8A8UnormSrgb => DXGI_FORMAT_R8G8B8A8_UNORM_SRGB,
+        Format::D32Float => DXGI_FORMAT_D32_FLOAT,
+        Format::Bc1Unorm => DXGI_FORMAT_BC1_UNORM,
+        Format::Bc1UnormSrgb => DXGI_FORMAT_BC1_UNORM_SRGB,
+        Format::Bc3Unorm => DXGI_FORMAT_BC3_UNORM,
+        Format::Bc3UnormSrgb => DXGI_FORMAT_BC3_UNORM_SRGB,
+        Format::Bc5Unorm => DXGI_FORMAT_BC5_UNORM,
+        Format::Bc7Unorm => DXGI_FORMAT_BC7_UNORM,
+        Format::Bc7UnormSrgb => DXGI_FORMAT_BC7_UNORM_SRGB,
+    }
+}
+
+fn map_heap_type(heap: HeapType) -> D3D12_HEAP_TYPE {
+    match heap {
+        HeapType::Default => D3D12_HEAP_TYPE_DEFAULT,
+        HeapType::Upload => D3D12_HEAP_TYPE_UPLOAD,
+        HeapType::Readback => D3D12_HEAP_TYPE_READBACK,
+    }
+}
+
+fn map_descriptor_heap_type(ty: DescriptorHeapType) -> D3D12_DESCRIPTOR_HEAP_TYPE {
+    match ty {
+        DescriptorHeapType::CbvSrvUav => D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV,
+        DescriptorHeapType::Sampler => D3D12_DESCRIPTOR_HEAP_TYPE_SAMPLER,
+        DescriptorHeapType::Rtv => D3D12_DESCRIPTOR_HEAP_TYPE_RTV,
+        DescriptorHeapType::Dsv => D3D12_DESCRIPTOR_HEAP_TYPE_DSV,
+    }
+}
+
+fn map_heap_flags(flags: HeapFlags) -> D3D12_HEAP_FLAGS {
+    let mut d3d12_flags = D3D12_HEAP_FLAG_NONE;
+    if flags.contains(HeapFlags::ALLOW_ONLY_BUFFERS) {
+        d3d12_flags |= D3D12_HEAP_FLAG_ALLOW_ONLY_BUFFERS;
+    }
+    if flags.contains(HeapFlags::ALLOW_ONLY_NON_RT_DS_TEXTURES) {
+        d3d12_flags |= D3D12_HEAP_FLAG_ALLOW_ONLY_NON_RT_DS_TEXTURES;
+    }
+    if flags.contains(HeapFlags::ALLOW_ONLY_RT_DS_TEXTURES) {
+        d3d12_flags |= D3D12_HEAP_FLAG_ALLOW_ONLY_RT_DS_TEXTURES;
+    }
+    d3d12_flags
+}
+
+fn map_resource_flags(flags: ResourceFlags) -> D3D12_RESOURCE_FLAGS {
+    let mut d3d12_flags = D3D12_RESOURCE_FLAG_NONE;
+    if flags.contains(ResourceFlags::ALLOW_RENDER_TARGET) {
+        d3d12_flags |= D3D12_RESOURCE_FLAG_ALLOW_RENDER_TARGET;
+    }
+    if flags.contains(ResourceFlags::ALLOW_DEPTH_STENCIL) {
+        d3d12_flags |= D3D12_RESOURCE_FLAG_ALLOW_DEPTH_STENCIL;
+    }
+    if flags.contains(ResourceFlags::ALLOW_UNORDERED_ACCESS) {
+        d3d12_flags |= D3D12_RESOURCE_FLAG_ALLOW_UNORDERED_ACCESS;
+    }
+    d3d12_flags
+}
+
+pub fn map_resource_desc(desc: &ResourceDesc) -> D3D12_RESOURCE_DESC {
+    let (dimension, layout) = match desc.dimension {
+        ResourceDimension::Buffer => (
+            D3D12_RESOURCE_DIMENSION_BUFFER,
+            D3D12_TEXTURE_LAYOUT_ROW_MAJOR,
+        ),
+        ResourceDimension::Texture2D => (
+            D3D12_RESOURCE_DIMENSION_TEXTURE2D,
+            D3D12_TEXTURE_LAYOUT_UNKNOWN,
+        ),
+    };
+
+    D3D12_RESOURCE_DESC {
+        Dimension: dimension,
+        Alignment: 0,
+        Width: desc.width,
+        Height: desc.height,
+        DepthOrArraySize: desc.depth_or_array_size,
+        Format: map_format(desc.format),
+        MipLevels: desc.mip_levels,
+        SampleDesc: DXGI_SAMPLE_DESC {
+            Count: desc.samples,
+            Quality: 0,
+        },
+        Layout: layout,
+        Flags: map_resource_flags(desc.flags),
+    }
+}
+
+fn map_clear_value(clear_value: ClearValue) -> D3D12_CLEAR_VALUE {
+    let format = match clear_value {
+        ClearValue::Color(format, _) | ClearValue::DepthStencil(format, _, _) => format,
+    };
+    let mut d3d12_clear_value = D3D12_CLEAR_VALUE {
+        Format: map_format(format),
+        ..unsafe { mem::zeroed() }
+    };
+    match clear_value {
+        ClearValue::Color(_, color) => unsafe {
+            *d3d12_clear_value.u.Color_mut() = color;
+        },
+        ClearValue::DepthStencil(_, depth, stencil) => unsafe {
+            *d3d12_clear_value.u.DepthStencil_mut() = D3D12_DEPTH_STENCIL_VALUE {
+                Depth: depth,
+                Stencil: stencil,
+            };
+        },
+    }
+    d3d12_clear_value
+}
+
+fn map_visibility(visibility: ShaderVisibility) -> D3D12_SHADER_VISIBILITY {
+    match visibility {
+        ShaderVisibility::All => D3D12_SHADER_VISIBILITY_ALL,
+        ShaderVisibility::Vertex => D3D12_SHADER_VISIBILITY_VERTEX,
+        ShaderVisibility::Pixel => D3D12_SHADER_VISIBILITY_PIXEL,
+    }
+}
+
+fn map_compare_func(func: CompareFunc) -> D3D12_COMPARISON_FUNC {
+    match func {
+        CompareFunc::Less => D3D12_COMPARISON_FUNC_LESS,
+        CompareFunc::LessEqual => D3D12_COMPARISON_FUNC_LESS_EQUAL,
+    }
+}
+
+fn map_cpu_descriptor(handle: CpuDescriptor) -> D3D12_CPU_DESCRIPTOR_HANDLE {
+    D3D12_CPU_DESCRIPTOR_HANDLE { ptr: handle.ptr }
+}
+
+fn map_gpu_descriptor(handle: GpuDescriptor) -> D3D12_GPU_DESCRIPTOR_HANDLE {
+    D3D12_GPU_DESCRIPTOR_HANDLE { ptr: handle.ptr }
+}
+
+fn unpack_shader_bc(shader: &Shader) -> D3D12_SHADER_BYTECODE {
+    unsafe {
+        D3D12_SHADER_BYTECODE {
+            pShaderBytecode: shader.0.GetBufferPointer() as *const _,
+            BytecodeLength: shader.0.GetBufferSize(),
+        }
+    }
+}
+
+/// Turn a failure code returned by `operation` into an error.
+fn check_hr<F>(hr: HRESULT, operation: &'static str, description: F) -> Result<(), EngineError>
+where
+    F: FnOnce() -> String,
+{
+    if winerror::SUCCEEDED(hr) {
+        Ok(())
+    } else {
+        Err(EngineError::new(
+            operation,
+            description(),
+            ErrorKind::Hresult(hr),
+        ))
+    }
+}
+
+fn blob_to_string(blob: *mut ID3DBlob) -> String {
+    unsafe {
+        let pointer = (*blob).GetBufferPointer();
+        let size = (*blob).GetBufferSize();
+        let slice = slice::from_raw_parts(pointer as *const u8, size as usize);
+        String::from_utf8_lossy(slice).into_owned()
+    }
+}
+
+fn gen_root_descriptor_param(
+    ty: D3D12_ROOT_PARAMETER_TYPE,
+    visibility: D3D12_SHADER_VISIBILITY,
+    descriptor: D3D12_ROOT_DESCRIPTOR,
+) -> D3D12_ROOT_PARAMETER {
+    let mut view_parameter = D3D12_ROOT_PARAMETER {
+        ParameterType: ty,
+        ShaderVisibility: visibility,
+        ..unsafe { mem::zeroed() }
+    };
+    unsafe {
+        *view_parameter.u.Descriptor_mut() = descriptor;
+    }
+    view_parameter
+}
+
+fn gen_root_table_param(
+    visibility: D3D12_SHADER_VISIBILITY,
+    table: D3D12_ROOT_DESCRIPTOR_TABLE,
+) -> D3D12_ROOT_PARAMETER {
+    let mut view_parameter = D3D12_ROOT_PARAMETER {
+        ParameterType: D3D12_ROOT_PARAMETER_TYPE_DESCRIPTOR_TABLE,
+        ShaderVisibility: visibility,
+        ..unsafe { mem::zeroed() }
+    };
+    unsafe {
+        *view_parameter.u.DescriptorTable_mut() = table;
+    }
+    view_parameter
+}
+
+fn gen_root_constants_param(
+    visibility: D3D12_SHADER_VISIBILITY,
+    constants: D3D12_ROOT_CONSTANTS,
+) -> D3D12_ROOT_PARAMETER {
+    let mut view_parameter = D3D12_ROOT_PARAMETER {
+        ParameterType: D3D12_ROOT_PARAMETER_TYPE_32BIT_CONSTANTS,
+        ShaderVisibility: visibility,
+        ..unsafe { mem::zeroed() }
+    };
+    unsafe {
+        *view_parameter.u.Constants_mut() = constants;
+    }
+    view_parameter
+}
+
+/// Adapter enumerated by the DXGI factory.
+struct DxgiAdapter {
+    index: u32,
+    adapter: ComPtr<IDXGIAdapter1>,
+}
+
+impl Adapter for DxgiAdapter {
+    fn info(&self) -> AdapterInfo {
+        let mut desc: DXGI_ADAPTER_DESC1 = unsafe { mem::zeroed() };
+        unsafe {
+            self.adapter.GetDesc1(&mut desc);
+        }
+        let len = desc
+            .Description
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(desc.Description.len());
+
+        AdapterInfo {
+            index: self.index,
+            name: String::from_utf16_lossy(&desc.Description[..len]),
+            dedicated_video_memory: desc.DedicatedVideoMemory as _,
+            software: desc.Flags & DXGI_ADAPTER_FLAG_SOFTWARE != 0,
+        }
+    }
+
+    fn supports(&self, level: FeatureLevel) -> bool {
+        // Only checks for support without creating a device.
+        let hr = unsafe {
+            D3D12CreateDevice(
+                self.adapter.as_raw() as *mut _,
+                map_feature_level(level),
+                &ID3D12Device::uuidof(),
+                ptr::null_mut(),
+            )
+        };
+        winerror::SUCCEEDED(hr)
+    }
+}
+
+fn map_feature_level(level: FeatureLevel) -> D3D_FEATURE_LEVEL {
+    match level {
+        FeatureLevel::L11_0 => D3D_FEATURE_LEVEL_11_0,
+        FeatureLevel::L11_1 => D3D_FEATURE_LEVEL_11_1,
+        FeatureLevel::L12_0 => D3D_FEATURE_LEVEL_12_0,
+        FeatureLevel::L12_1 => D3D_FEATURE_LEVEL_12_1,
+    }
+}
+
+fn create_factory(debug: bool) -> Result<ComPtr<IDXGIFactory4>, EngineError> {
+    let mut dxgi_factory: *mut IDXGIFactory4 = ptr::null_mut();
+
+    let hr = if debug {
+        unsafe {
+            CreateDXGIFactory2(
+                DXGI_CREATE_FACTORY_DEBUG,
+                &IDXGIFactory4::uuidof(),
+                &mut dxgi_factory as *mut *mut _ as *mut *mut _,
+            )
+        }
+    } else {
+        unsafe {
+            CreateDXGIFactory1(
+                &IDXGIFactory4::uuidof(),
+                &mut dxgi_factory as *mut *mut _ as *mut *mut _,
+            )
+        }
+    };
+    check_hr(hr, "CreateDXGIFactory", String::new)?;
+
+    Ok(unsafe { ComPtr::from_raw(dxgi_factory) })
+}
+
+fn enumerate_adapters(factory: &ComPtr<IDXGIFactory4>) -> Result<Vec<DxgiAdapter>, EngineError> {
+    let mut adapters = Vec::new();
+    for index in 0.. {
+        let mut adapter: *mut IDXGIAdapter1 = ptr::null_mut();
+        let hr = unsafe { factory.EnumAdapters1(index, &mut adapter as *mut *mut _) };
+        if hr == winerror::DXGI_ERROR_NOT_FOUND {
+            break;
+        }
+        check_hr(hr, "EnumAdapters1", || format!("adapter {}", index))?;
+        adapters.push(DxgiAdapter {
+            index,
+            adapter: unsafe { ComPtr::from_raw(adapter) },
+        });
+    }
+    Ok(adapters)
+}
+
+impl Device {
+    fn init_debug(gpu_validation: bool) -> Result<(), EngineError> {
+        let mut debug_controller: *mut ID3D12Debug = ptr::null_mut();
+        let hr = unsafe {
+            D3D12GetDebugInterface(
+                &ID3D12Debug::uuidof(),
+                &mut debug_controller as *mut *mut _ as *mut *mut _,
+            )
+        };
+
+        if winerror::SUCCEEDED(hr) {
+            let debug_controller = unsafe { ComPtr::from_raw(debug_controller) };
+            unsafe {
+                debug_controller.EnableDebugLayer();
+            }
+            if gpu_validation {
+                let debug_controller = debug_controller.cast::<ID3D12Debug1>().map_err(|hr| {
+                    EngineError::new(
+                        "EnableGPUBasedValidation",
+                        String::new(),
+                        ErrorKind::Hresult(hr),
+                    )
+                })?;
+                unsafe {
+                    debug_controller.SetEnableGPUBasedValidation(TRUE);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn new(config: &EngineConfig) -> Result<Self, EngineError> {
+        // GPU-based validation is part of the debug layer.
+        let debug = config.debug_layer || config.gpu_validation;
+        if debug {
+            Self::init_debug(config.gpu_validation)?;
+        }
+        let factory = create_factory(debug)?;
+
+        // Find suitable adapter and open device.
+        let adapter = backend::select_adapter(
+            enumerate_adapters(&factory)?,
+            config.min_feature_level,
+            &config.adapter,
+        )?;
+        let device = {
+            let mut device: *mut ID3D12Device = ptr::null_mut();
+            let hr = unsafe {
+                D3D12CreateDevice(
+                    adapter.adapter.as_raw() as *mut _,
+                    map_feature_level(config.min_feature_level),
+                    &ID3D12Device::uuidof(),
+                    &mut device as *mut *mut _ as *mut *mut _,
+                )
+            };
+            check_hr(hr, "D3D12CreateDevice", || {
+                format!(
+                    "{} with feature level {}",
+                    adapter.info().name,
+                    config.min_feature_level
+                )
+            })?;
+            unsafe { ComPtr::from_raw(device) }
+        };
+
+        // Create associated direct queue (also used for present).
+        let queue = {
+            let queue_desc = D3D12_COMMAND_QUEUE_DESC {
+                Type: D3D12_COMMAND_LIST_TYPE_DIRECT,
+                Priority: 0,
+                Flags: D3D12_COMMAND_QUEUE_FLAG_NONE,
+                NodeMask: 0,
+            };
+
+            let mut queue: *mut ID3D12CommandQueue = ptr::null_mut();
+            let hr = unsafe {
+                device.CreateCommandQueue(
+                    &queue_desc,
+                    &ID3D12CommandQueue::uuidof(),
+                    &mut queue as *mut *mut _ as *mut *mut _,
+                )
+            };
+            check_hr(hr, "CreateCommandQueue", || "direct queue".into())?;
+            unsafe { ComPtr::from_raw(queue) }
+        };
+
+        let wait_event = unsafe { CreateEventA(ptr::null_mut(), 0, 0, ptr::null_mut()) };
+        if wait_event.is_null() {
+            return Err(EngineError::new(
+                "CreateEventA",
+                "fence wait event".into(),
+                ErrorKind::Hresult(winerror::E_FAIL),
+            ));
+        }
+
+        Ok(Device {
+            factory,
+            device,
+            queue,
+            wait_event,
+            removed: AtomicBool::new(false),
+        })
+    }
+}
+
+impl backend::Device<D3D12> for Device {
+    fn create_command_allocator(&self) -> Result<CommandAllocator, EngineError> {
+        let mut command_allocator: *mut ID3D12CommandAllocator = ptr::null_mut();
+        let hr = unsafe {
+            self.device.CreateCommandAllocator(
+                D3D12_COMMAND_LIST_TYPE_DIRECT,
+                &ID3D12CommandAllocator::uuidof(),
+                &mut command_allocator as *mut *mut _ as *mut *mut _,
+            )
+        };
+        check_hr(hr, "CreateCommandAllocator", || "direct".into())?;
+        Ok(CommandAllocator(unsafe { ComPtr::from_raw(command_allocator) }))
+    }
+
+    fn create_command_list(
+        &self,
+        allocator: &CommandAllocator,
+    ) -> Result<CommandList, EngineError> {
+        let mut command_list: *mut ID3D12GraphicsCommandList = ptr::null_mut();
+        let hr = unsafe {
+            self.device.CreateCommandList(
+                0,
+                D3D12_COMMAND_LIST_TYPE_DIRECT,
+                allocator.0.as_raw(),
+                ptr::null_mut(),
+                &ID3D12CommandList::uuidof(),
+                &mut command_list as *mut *mut _ as *mut *mut _,
+            )
+        };
+        check_hr(hr, "CreateCommandList", || "direct".into())?;
+
+        unsafe {
+            (*command_list).Close();
+            Ok(CommandList(ComPtr::from_raw(command_list)))
+        }
+    }
+
+    fn create_committed_resource(
+        &self,
+        heap: HeapType,
+        desc: &ResourceDesc,
+        initial: ResourceStates,
+        clear_value: Option<ClearValue>,
+    ) -> Result<Resource, EngineError> {
+        let mut resource: *mut ID3D12Resource = ptr::null_mut();
+        let heap_properties = unsafe { self.device.GetCustomHeapProperties(0, map_heap_type(heap)) };
+        let resource_desc = map_resource_desc(desc);
+
+        let clear_value = clear_value.map(map_clear_value);
+
+        let hr = unsafe {
+            self.device.CreateCommittedResource(
+                &heap_properties,
+                D3D12_HEAP_FLAG_NONE,
+                &resource_desc,
+                initial.0,
+                match clear_value {
+                    Some(ref cv) => cv,
+                    None => ptr::null(),
+                },
+                &ID3D12Resource::uuidof(),
+                &mut resource as *mut *mut _ as *mut *mut _,
+            )
+        };
+        check_hr(hr, "CreateCommittedResource", || {
+            format!("{:?} in {:?} heap", desc, heap)
+        })?;
+
+        Ok(Resource(unsafe { ComPtr::from_raw(resource) }))
+    }
+
+    fn create_heap(&self, desc: &HeapDesc) -> Result<Heap, EngineError> {
+        let mut heap: *mut ID3D12Heap = ptr::null_mut();
+        let properties = unsafe { self.device.GetCustomHeapProperties(0, map_heap_type(desc.ty)) };
+        let heap_desc = D3D12_HEAP_DESC {
+            SizeInBytes: desc.size,
+            Properties: properties,
+            Alignment: desc.alignment,
+            Flags: map_heap_flags(desc.flags),
+        };
+        let hr = unsafe {
+            self.device.CreateHeap(
+                &heap_desc,
+                &ID3D12Heap::uuidof(),
+                &mut heap as *mut *mut _ as *mut *mut _,
+            )
+        };
+        check_hr(hr, "CreateHeap", || format!("{:?}", desc))?;
+
+        Ok(Heap(unsafe { ComPtr::from_raw(heap) }))
+    }
+
+    fn create_placed_resource(
+        &self,
+        heap: &Heap,
+        offset: u64,
+        desc: &ResourceDesc,
+        initial: ResourceStates,
+        clear_value: Option<ClearValue>,
+    ) -> Result<Resource, EngineError> {
+        let mut resource: *mut ID3D12Resource = ptr::null_mut();
+        let resource_desc = map_resource_desc(desc);
+        let clear_value = clear_value.map(map_clear_value);
+
+        let hr = unsafe {
+            self.device.CreatePlacedResource(
+                heap.0.as_raw(),
+                offset,
+                &resource_desc,
+                initial.0,
+                match clear_value {
+                    Some(ref cv) => cv,
+                    None => ptr::null(),
+                },
+                &ID3D12Resource::uuidof(),
+                &mut resource as *mut *mut _ as *mut *mut _,
+            )
+        };
+        check_hr(hr, "CreatePlacedResource", || {
+            format!("{:?} at heap offset {}", desc, offset)
+        })?;
+
+        Ok(Resource(unsafe { ComPtr::from_raw(resource) }))
+    }
+
+    fn resource_allocation_info(&self, desc: &ResourceDesc) -> AllocationInfo {
+        let desc = map_resource_desc(desc);
+        let info = unsafe { self.device.GetResourceAllocationInfo(0, 1, &desc) };
+        AllocationInfo {
+            size: info.SizeInBytes,
+            alignment: info.Alignment,
+        }
+    }
+
+    fn create_descriptor_heap(
+        &self,
+        ty: DescriptorHeapType,
+        size: usize,
+        shader_visible: bool,
+    ) -> Result<DescriptorHeap, EngineError> {
+        let mut heap: *mut ID3D12DescriptorHeap = ptr::null_mut();
+        let desc = D3D12_DESCRIPTOR_HEAP_DESC {
+            Type: map_descriptor_heap_type(ty),
+            NumDescriptors: size as _,
+            Flags: if shader_visible {
+                D3D12_DESCRIPTOR_HEAP_FLAG_SHADER_VISIBLE
+            } else {
+                D3D12_DESCRIPTOR_HEAP_FLAG_NONE
+            },
+            NodeMask: 0,
+        };
+        let hr = unsafe {
+            self.device.CreateDescriptorHeap(
+                &desc,
+                &ID3D12DescriptorHeap::uuidof(),
+                &mut heap as *mut *mut _ as *mut *mut _,
+            )
+        };
+        check_hr(hr, "CreateDescriptorHeap", || {
+            format!("{} {:?} descriptors", size, ty)
+        })?;
+
+        Ok(DescriptorHeap {
+            heap: unsafe { ComPtr::from_raw(heap) },
+            shader_visible,
+        })
+    }
+
+    fn descriptor_increment_size(&self, ty: DescriptorHeapType) -> u32 {
+        unsafe {
+            self.device
+                .GetDescriptorHandleIncrementSize(map_descriptor_heap_type(ty))
+        }
+    }
+
+    fn descriptor_heap_start(&self, heap: &DescriptorHeap) -> (CpuDescriptor, GpuDescriptor) {
+        unsafe {
+            let cpu = heap.heap.GetCPUDescriptorHandleForHeapStart();
+            // Only valid to query for shader visible heaps.
+            let gpu = if heap.shader_visible {
+                heap.heap.GetGPUDescriptorHandleForHeapStart().ptr
+            } else {
+                0
+            };
+            (CpuDescriptor { ptr: cpu.ptr }, GpuDescriptor { ptr: gpu })
+        }
+    }
+
+    fn create_fence(&self, initial: u64) -> Result<Fence, EngineError> {
+        let mut fence: *mut ID3D12Fence = ptr::null_mut();
+        let hr = unsafe {
+            self.device.CreateFence(
+                initial,
+                D3D12_FENCE_FLAG_NONE,
+                &ID3D12Fence::uuidof(),
+                &mut fence as *mut *mut _ as *mut *mut _,
+            )
+        };
+        check_hr(hr, "CreateFence", || format!("initial value {}", initial))?;
+        Ok(Fence(unsafe { ComPtr::from_raw(fence) }))
+    }
+
+    fn create_shader(
+        &self,
+        name: &str,
+        source: &str,
+        entry_point: &str,
+        target: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<Shader, EngineError> {
+        let mut shader = ptr::null_mut();
+        let mut error: *mut ID3DBlob = ptr::null_mut();
+
+        let defines = defines
+            .iter()
+            .map(|&(name, definition)| {
+                (
+                    CString::new(name).unwrap(),
+                    CString::new(definition).unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        // Terminated by a null entry.
+        let macros = defines
+            .iter()
+            .map(|&(ref name, ref definition)| D3D_SHADER_MACRO {
+                Name: name.as_ptr(),
+                Definition: definition.as_ptr(),
+            })
+            .chain(Some(D3D_SHADER_MACRO {
+                Name: ptr::null(),
+                Definition: ptr::null(),
+            }))
+            .collect::<Vec<_>>();
+
+        let hr = unsafe {
+            D3DCompile(
+                source.as_ptr() as *const _,
+                source.len(),
+                ptr::null(),
+                macros.as_ptr(),
+                D3D_COMPILE_STANDARD_FILE_INCLUDE,
+                entry_point.as_ptr() as *const _,
+                target.as_ptr() as *const i8,
+                D3DCOMPILE_DEBUG | D3DCOMPILE_ENABLE_UNBOUNDED_DESCRIPTOR_TABLES, // TODO
+                0,
+                &mut shader as *mut *mut _,
+                &mut error as *mut *mut _,
+            )
+        };
+        if !winerror::SUCCEEDED(hr) {
+            let description = format!(
+                "{} ({}, {})",
+                name,
+                entry_point.trim_right_matches('\0'),
+                target.trim_right_matches('\0')
+            );
+            let kind = if error.is_null() {
+                ErrorKind::Hresult(hr)
+            } else {
+                let error = unsafe { ComPtr::<ID3DBlob>::from_raw(error) };
+                ErrorKind::Compilation(blob_to_string(error.as_raw()))
+            };
+            Err(EngineError::new("D3DCompile", description, kind))
+        } else {
+            Ok(Shader(unsafe { ComPtr::<ID3DBlob>::from_raw(shader) }))
+        }
+    }
+
+    fn create_root_signature(
+        &self,
+        desc: &RootSignatureDesc,
+    ) -> Result<RootSignature, EngineError> {
+        let tables = desc
+            .parameters
+            .iter()
+            .map(|parameter| match *parameter {
+                RootParameter::Table { ranges, .. } => ranges
+                    .iter()
+                    .map(|range| D3D12_DESCRIPTOR_RANGE {
+                        RangeType: match range.ty {
+                            RangeType::Srv => D3D12_DESCRIPTOR_RANGE_TYPE_SRV,
+                            RangeType::Uav => D3D12_DESCRIPTOR_RANGE_TYPE_UAV,
+                        },
+                        NumDescriptors: range.count,
+                        BaseShaderRegister: range.base_register,
+                        RegisterSpace: range.space,
+                        OffsetInDescriptorsFromTableStart: D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND,
+                    })
+                    .collect::<Vec<_>>(),
+                _ => Vec::new(),
+            })
+            .collect::<Vec<_>>();
+
+        let parameters = desc
+            .parameters
+            .iter()
+            .zip(tables.iter())
+            .map(|(parameter, table)| match *parameter {
+                RootParameter::Cbv {
+                    register,
+                    space,
+                    visibility,
+                } => gen_root_descriptor_param(
+                    D3D12_ROOT_PARAMETER_TYPE_CBV,
+                    map_visibility(visibility),
+                    D3D12_ROOT_DESCRIPTOR {
+                        ShaderRegister: register,
+                        RegisterSpace: space,
+                    },
+                ),
+                RootParameter::Constants {
+                    register,
+                    space,
+                    num_values,
+                    visibility,
+                } => gen_root_constants_param(
+                    map_visibility(visibility),
+                    D3D12_ROOT_CONSTANTS {
+                        ShaderRegister: register,
+                        RegisterSpace: space,
+                        Num32BitValues: num_values,
+                    },
+                ),
+                RootParameter::Table { visibility, .. } => gen_root_table_param(
+                    map_visibility(visibility),
+                    D3D12_ROOT_DESCRIPTOR_TABLE {
+                        NumDescriptorRanges: table.len() as _,
+                        pDescriptorRanges: table.as_ptr(),
+                    },
+                ),
+            })
+            .collect::<Vec<_>>();
+
+        let static_samplers = desc
+            .static_samplers
+            .iter()
+            .map(|sampler| {
+                let address = match sampler.address {
+                    AddressMode::Clamp => D3D12_TEXTURE_ADDRESS_MODE_CLAMP,
+                    AddressMode::Wrap => D3D12_TEXTURE_ADDRESS_MODE_WRAP,
+                };
+                let (filter, comparison) = match sampler.filter {
+                    Filter::Linear => (D3D12_FILTER_MIN_MAG_MIP_LINEAR, D3D12_COMPARISON_FUNC_ALWAYS),
+                    Filter::LinearCompare(func) => (
+                        D3D12_FILTER_COMPARISON_MIN_MAG_LINEAR_MIP_POINT,
+                        map_compare_func(func),
+                    ),
+                };
+                D3D12_STATIC_SAMPLER_DESC {
+                    Filter: filter,
+                    AddressU: address,
+                    AddressV: address,
+                    AddressW: address,
+                    MipLODBias: 0.0,
+                    MaxAnisotropy: 0,
+                    ComparisonFunc: comparison,
+                    BorderColor: D3D12_STATIC_BORDER_COLOR_TRANSPARENT_BLACK,
+                    MinLOD: 0.0,
+                    MaxLOD: D3D12_FLOAT32_MAX,
+                    ShaderRegister: sampler.register,
+                    RegisterSpace: sampler.space,
+                    ShaderVisibility: map_visibility(sampler.visibility),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let d3d12_desc = D3D12_ROOT_SIGNATURE_DESC {
+            NumParameters: parameters.len() as _,
+            pParameters: parameters.as_ptr(),
+            NumStaticSamplers: static_samplers.len() as _,
+            pStaticSamplers: static_samplers.as_ptr(),
+            Flags: if desc.allow_input_layout {
+                D3D12_ROOT_SIGNATURE_FLAG_ALLOW_INPUT_ASSEMBLER_INPUT_LAYOUT
+            } else {
+                D3D12_ROOT_SIGNATURE_FLAG_NONE
+            },
+        };
+
+        let mut signature = ptr::null_mut();
+        let mut serialized = ptr::null_mut();
+        let mut error = ptr::null_mut();
+
+        let description = || format!("{} parameters", desc.parameters.len());
+        unsafe {
+            let hr = D3D12SerializeRootSignature(
+                &d3d12_desc as *const _,
+                D3D_ROOT_SIGNATURE_VERSION_1_0,
+                &mut serialized,
+                &mut error,
+            );
+
+            if !error.is_null() {
+                let message = blob_to_string(error);
+                (*error).Release();
+
+                return Err(EngineError::new(
+                    "D3D12SerializeRootSignature",
+                    description(),
+                    ErrorKind::Compilation(message),
+                ));
+            }
+            check_hr(hr, "D3D12SerializeRootSignature", description)?;
+
+            let hr = self.device.CreateRootSignature(
+                0,
+                (*serialized).GetBufferPointer(),
+                (*serialized).GetBufferSize(),
+                &ID3D12RootSignature::uuidof(),
+                &mut signature as *mut *mut _ as *mut *mut _,
+            );
+            (*serialized).Release();
+            check_hr(hr, "CreateRootSignature", description)?;
+
+            Ok(RootSignature(ComPtr::from_raw(signature)))
+        }
+    }
+
+    fn create_graphics_pipeline(
+        &self,
+        desc: &GraphicsPipelineDesc<D3D12>,
+    ) -> Result<PipelineState, EngineError> {
+        let semantics = desc
+            .input_layout
+            .iter()
+            .map(|element| {
+                CString::new(element.semantic).map_err(|_| {
+                    EngineError::new(
+                        "CreateGraphicsPipelineState",
+                        format!("semantic {:?}", element.semantic),
+                        ErrorKind::InvalidArgument("semantic contains nul byte".into()),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let input_layout = desc
+            .input_layout
+            .iter()
+            .zip(semantics.iter())
+            .map(|(element, semantic)| D3D12_INPUT_ELEMENT_DESC {
+                SemanticName: semantic.as_ptr(),
+                SemanticIndex: element.index,
+                Format: map_format(element.format),
+                InputSlot: element.slot,
+                AlignedByteOffset: D3D12_APPEND_ALIGNED_ELEMENT,
+                InputSlotClass: D3D12_INPUT_CLASSIFICATION_PER_VERTEX_DATA,
+                InstanceDataStepRate: 0,
+            })
+            .collect::<Vec<_>>();
+
+        let mut pso_desc = D3D12_GRAPHICS_PIPELINE_STATE_DESC {
+            pRootSignature: desc.signature.0.as_raw(),
+            VS: unpack_shader_bc(desc.vs),
+            PS: unpack_shader_bc(desc.ps),
+            PrimitiveTopologyType: D3D12_PRIMITIVE_TOPOLOGY_TYPE_TRIANGLE,
+            NumRenderTargets: desc.render_targets.len() as _,
+            InputLayout: D3D12_INPUT_LAYOUT_DESC {
+                pInputElementDescs: input_layout.as_ptr(),
+                NumElements: input_layout.len() as _,
+            },
+            DSVFormat: map_format(desc.depth_format),
+            SampleDesc: DXGI_SAMPLE_DESC {
+                Count: desc.samples,
+                Quality: 0,
+            },
+            ..DEFAULT_PIPELINE_STATE_DESC
+        };
+        for (i, format) in desc.render_targets.iter().enumerate() {
+            pso_desc.RTVFormats[i] = map_format(*format);
+        }
+        if let Some(func) = desc.depth_test {
+            pso_desc.DepthStencilState.DepthEnable = TRUE;
+            pso_desc.DepthStencilState.DepthFunc = map_compare_func(func);
+        }
+        if desc.samples > 1 {
+            pso_desc.RasterizerState.MultisampleEnable = TRUE;
+        }
+
+        let mut pipeline = ptr::null_mut();
+        let hr = unsafe {
+            self.device.CreateGraphicsPipelineState(
+                &pso_desc as *const _,
+                &ID3D12PipelineState::uuidof(),
+                &mut pipeline as *mut *mut _ as *mut *mut _,
+            )
+        };
+        check_hr(hr, "CreateGraphicsPipelineState", || {
+            format!(
+                "render targets {:?}, depth {:?}, samples {}",
+                desc.render_targets, desc.depth_format, desc.samples
+            )
+        })?;
+        Ok(PipelineState(unsafe { ComPtr::from_raw(pipeline) }))
+    }
+
+    fn create_compute_pipeline(
+        &self,
+        signature: &RootSignature,
+        shader: &Shader,
+    ) -> Result<PipelineState, EngineError> {
+        let desc = D3D12_COMPUTE_PIPELINE_STATE_DESC {
+            pRootSignature: signature.0.as_raw(),
+            CS: unpack_shader_bc(shader),
+            NodeMask: 0,
+            CachedPSO: D3D12_CACHED_PIPELINE_STATE {
+                pCachedBlob: ptr::null(),
+                CachedBlobSizeInBytes: 0,
+            },
+            Flags: D3D12_PIPELINE_STATE_FLAG_NONE,
+        };
+
+        let mut pipeline = ptr::null_mut();
+        let hr = unsafe {
+            self.device.CreateComputePipelineState(
+                &desc as *const _,
+                &ID3D12PipelineState::uuidof(),
+                &mut pipeline as *mut *mut _ as *mut *mut _,
+            )
+        };
+        check_hr(hr, "CreateComputePipelineState", String::new)?;
+        Ok(PipelineState(unsafe { ComPtr::from_raw(pipeline) }))
+    }
+
+    fn create_shader_resource_view(&self, resource: &Resource, desc: &SrvDesc, handle: CpuDescriptor) {
+        let mut srv_desc = D3D12_SHADER_RESOURCE_VIEW_DESC {
+            Shader4ComponentMapping: DEFAULT_SHADER_4_COMPONENT_MAPPING,
+            ..unsafe { mem::zeroed() }
+        };
+        match *desc {
+            SrvDesc::Buffer {
+                first_element,
+                num_elements,
+                stride,
+            } => unsafe {
+                srv_desc.Format = DXGI_FORMAT_UNKNOWN;
+                srv_desc.ViewDimension = D3D12_SRV_DIMENSION_BUFFER;
+                *srv_desc.u.Buffer_mut() = D3D12_BUFFER_SRV {
+                    FirstElement: first_element,
+                    NumElements: num_elements,
+                    StructureByteStride: stride,
+                    Flags: D3D12_BUFFER_SRV_FLAG_NONE,
+                };
+            },
+            SrvDesc::Texture2D { format, mip_levels } => unsafe {
+                srv_desc.Format = map_format(format);
+                srv_desc.ViewDimension = D3D12_SRV_DIMENSION_TEXTURE2D;
+                *srv_desc.u.Texture2D_mut() = D3D12_TEX2D_SRV {
+                    MostDetailedMip: 0,
+                    MipLevels: mip_levels,
+                    PlaneSlice: 0,
+                    ResourceMinLODClamp: 0.0,
+                };
+            },
+            SrvDesc::Texture2DMs { format } => {
+                srv_desc.Format = map_format(format);
+                srv_desc.ViewDimension = D3D12_SRV_DIMENSION_TEXTURE2DMS;
+            }
+            SrvDesc::TextureCube { format, mip_levels } => unsafe {
+                srv_desc.Format = map_format(format);
+                srv_desc.ViewDimension = D3D12_SRV_DIMENSION_TEXTURECUBE;
+                *srv_desc.u.TextureCube_mut() = D3D12_TEXCUBE_SRV {
+                    MostDetailedMip: 0,
+                    MipLevels: mip_levels,
+                    ResourceMinLODClamp: 0.0,
+                };
+            },
+        }
+
+        unsafe {
+            self.device.CreateShaderResourceView(
+                resource.0.as_raw(),
+                &srv_desc,
+                map_cpu_descriptor(handle),
+            );
+        }
+    }
+
+    fn create_unordered_access_view(
+        &self,
+        resource: &Resource,
+        desc: &UavDesc,
+        handle: CpuDescriptor,
+    ) {
+        let mut uav_desc: D3D12_UNORDERED_ACCESS_VIEW_DESC = unsafe { mem::zeroed() };
+        match *desc {
+            UavDesc::Buffer {
+                first_element,
+                num_elements,
+                stride,
+            } => unsafe {
+                uav_desc.Format = DXGI_FORMAT_UNKNOWN;
+                uav_desc.ViewDimension = D3D12_UAV_DIMENSION_BUFFER;
+                *uav_desc.u.Buffer_mut() = D3D12_BUFFER_UAV {
+                    FirstElement: first_element,
+                    NumElements: num_elements,
+                    StructureByteStride: stride,
+                    CounterOffsetInBytes: 0,
+                    Flags: D3D12_BUFFER_UAV_FLAG_NONE,
+                };
+            },
+            UavDesc::Texture2D { format } => unsafe {
+                uav_desc.Format = map_format(format);
+                uav_desc.ViewDimension = D3D12_UAV_DIMENSION_TEXTURE2D;
+                *uav_desc.u.Texture2D_mut() = D3D12_TEX2D_UAV {
+                    MipSlice: 0,
+                    PlaneSlice: 0,
+                };
+            },
+        }
+
+        unsafe {
+            self.device.CreateUnorderedAccessView(
+                resource.0.as_raw(),
+                ptr::null_mut(),
+                &uav_desc,
+                map_cpu_descriptor(handle),
+            );
+        }
+    }
+
+    fn create_render_target_view(&self, resource: &Resource, desc: &RtvDesc, handle: CpuDescriptor) {
+        let rtv_desc = match *desc {
+            RtvDesc::Texture2D { format } => D3D12_RENDER_TARGET_VIEW_DESC {
+                Format: map_format(format),
+                ViewDimension: D3D12_RTV_DIMENSION_TEXTURE2D,
+                ..unsafe { mem::zeroed() }
+            },
+            RtvDesc::Texture2DMs { format } => D3D12_RENDER_TARGET_VIEW_DESC {
+                Format: map_format(format),
+                ViewDimension: D3D12_RTV_DIMENSION_TEXTURE2DMS,
+                ..unsafe { mem::zeroed() }
+            },
+        };
+
+        unsafe {
+            self.device.CreateRenderTargetView(
+                resource.0.as_raw(),
+                &rtv_desc,
+                map_cpu_descriptor(handle),
+            );
+        }
+    }
+
+    fn create_depth_stencil_view(&self, resource: &Resource, desc: &DsvDesc, handle: CpuDescriptor) {
+        let dsv_desc = match *desc {
+            DsvDesc::Texture2D { format } => D3D12_DEPTH_STENCIL_VIEW_DESC {
+                Format: map_format(format),
+                ViewDimension: D3D12_DSV_DIMENSION_TEXTURE2D,
+                ..unsafe { mem::zeroed() }
+            },
+            DsvDesc::Texture2DMs { format } => D3D12_DEPTH_STENCIL_VIEW_DESC {
+                Format: map_format(format),
+                ViewDimension: D3D12_DSV_DIMENSION_TEXTURE2DMS,
+                ..unsafe { mem::zeroed() }
+            },
+        };
+
+        unsafe {
+            self.device.CreateDepthStencilView(
+                resource.0.as_raw(),
+                &dsv_desc,
+                map_cpu_descriptor(handle),
+            );
+        }
+    }
+
+    unsafe fn map(&self, resource: &Resource) -> Result<*mut u8, EngineError> {
+        let mut data = ptr::null_mut();
+        let hr = resource.0.Map(0, ptr::null(), &mut data);
+        check_hr(hr, "Map", String::new)?;
+        Ok(data as *mut u8)
+    }
+
+    fn unmap(&self, resource: &Resource) {
+        unsafe {
+            resource.0.Unmap(0, ptr::null());
+        }
+    }
+
+    fn gpu_virtual_address(&self, resource: &Resource) -> u64 {
+        unsafe { resource.0.GetGPUVirtualAddress() }
+    }
+
+    fn execute_command_lists(&self, lists: &[&CommandList]) {
+        let lists = lists
+            .iter()
+            .map(|list| list.0.as_raw() as *mut ID3D12CommandList)
+            .collect::<Vec<_>>();
+        unsafe {
+            self.queue
+                .ExecuteCommandLists(lists.len() as _, lists.as_ptr());
+        }
+    }
+
+    fn signal(&self, fence: &Fence, value: u64) {
+        unsafe {
+            self.queue.Signal(fence.0.as_raw(), value);
+        }
+    }
+
+    fn fence_value(&self, fence: &Fence) -> u64 {
+        unsafe { fence.0.GetCompletedValue() }
+    }
+
+    fn wait_for_fence(
+        &self,
+        fence: &Fence,
+        value: u64,
+        timeout_ms: u32,
+    ) -> Result<(), EngineError> {
+        self.check_device_removed()?;
+
+        // Fences of a removed device complete with `u64::MAX`.
+        let completed = unsafe { fence.0.GetCompletedValue() };
+        if completed == u64::MAX {
+            self.check_device_removed()?;
+        } else if completed >= value {
+            return Ok(());
+        }
+
+        let signaled = unsafe {
+            fence.0.SetEventOnCompletion(value, self.wait_event);
+            WaitForSingleObject(self.wait_event, timeout_ms) == WAIT_OBJECT_0
+        };
+        // A device hang may surface as timeout.
+        self.check_device_removed()?;
+        if signaled {
+            Ok(())
+        } else {
+            Err(EngineError::new(
+                "wait_for_fence",
+                format!("fence value {} after {}ms", value, timeout_ms),
+                ErrorKind::Timeout,
+            ))
+        }
+    }
+
+    fn check_device_removed(&self) -> Result<(), EngineError> {
+        let reason = if self.removed.load(Ordering::Relaxed) {
+            DXGI_ERROR_DEVICE_REMOVED
+        } else {
+            unsafe { self.device.GetDeviceRemovedReason() }
+        };
+        if winerror::SUCCEEDED(reason) {
+            Ok(())
+        } else {
+            Err(EngineError::new(
+                "GetDeviceRemovedReason",
+                String::new(),
+                ErrorKind::DeviceRemoved(reason),
+            ))
+        }
+    }
+
+    fn simulate_device_removed(&self) {
+        self.removed.store(true, Ordering::Relaxed);
+    }
+
+    fn validation_errors(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn create_swapchain(
+        &self,
+        window: Option<&winit::Window>,
+        desc: &SwapchainDesc,
+    ) -> Result<Swapchain, EngineError> {
+        let window = window.ok_or_else(|| {
+            EngineError::new(
+                "CreateSwapChainForHwnd",
+                format!("{:?}", desc),
+                ErrorKind::InvalidArgument("D3D12 swapchain requires a window".into()),
+            )
+        })?;
+        let swapchain = {
+            let mut swapchain: *mut IDXGISwapChain3 = ptr::null_mut();
+            let swapchain_desc = DXGI_SWAP_CHAIN_DESC1 {
+                AlphaMode: DXGI_ALPHA_MODE_IGNORE,
+                BufferCount: desc.buffer_count,
+                Width: desc.width,
+                Height: desc.height,
+                Format: map_format(desc.format),
+                Flags: 0,
+                BufferUsage: DXGI_USAGE_RENDER_TARGET_OUTPUT,
+                SampleDesc: DXGI_SAMPLE_DESC {
+                    Count: 1,
+                    Quality: 0,
+                },
+                Scaling: DXGI_SCALING_NONE,
+                Stereo: FALSE,
+                SwapEffect: DXGI_SWAP_EFFECT_FLIP_DISCARD,
+            };
+            let hr = unsafe {
+                self.factory.CreateSwapChainForHwnd(
+                    self.queue.as_raw() as *mut _,
+                    window.get_hwnd() as *mut _,
+                    &swapchain_desc,
+                    ptr::null(),
+                    ptr::null_mut(),
+                    &mut swapchain as *mut *mut _ as *mut *mut _,
+                )
+            };
+            check_hr(hr, "CreateSwapChainForHwnd", || format!("{:?}", desc))?;
+            unsafe { ComPtr::from_raw(swapchain) }
+        };
+
+        let buffers = swapchain_buffers(&swapchain, desc.buffer_count)?;
+
+        Ok(Swapchain { swapchain, buffers })
+    }
+}
+
+fn swapchain_buffers(
+    swapchain: &ComPtr<IDXGISwapChain3>,
+    count: u32,
+) -> Result<Vec<Resource>, EngineError> {
+    (0..count)
+        .map(|i| {
+            let mut resource: *mut ID3D12Resource = ptr::null_mut();
+            let hr = unsafe {
+                swapchain.GetBuffer(
+                    i as _,
+                    &ID3D12Resource::uuidof(),
+                    &mut resource as *mut *mut _ as *mut *mut _,
+                )
+            };
+            check_hr(hr, "GetBuffer", || format!("swapchain buffer {}", i))?;
+            Ok(Resource(unsafe { ComPtr::from_raw(resource) }))
+        })
+        .collect()
+}
+
+impl backend::CommandList<D3D12> for CommandList {
+    fn reset(&self, allocator: &CommandAllocator) {
+        unsafe {
+            allocator.0.Reset();
+            self.0.Reset(allocator.0.as_raw(), ptr::null_mut());
+        }
+    }
+
+    fn close(&self) {
+        unsafe {
+            self.0.Close();
+        }
+    }
+
+    fn set_descriptor_heaps(&self, heaps: &[&DescriptorHeap]) {
+        let mut heaps = heaps.iter().map(|heap| heap.heap.as_raw()).collect::<Vec<_>>();
+        unsafe {
+            self.0.SetDescriptorHeaps(heaps.len() as _, heaps.as_mut_ptr());
+        }
+    }
+
+    fn resource_barrier(&self, barriers: &[Barrier<D3D12>]) {
+        let barriers = barriers
+            .iter()
+            .map(|barrier| match *barrier {
+                Barrier::Transition {
+                    resource,
+                    subresource,
+                    before,
+                    after,
+                } => {
+                    let mut d3d12_barrier = D3D12_RESOURCE_BARRIER {
+                        Type: D3D12_RESOURCE_BARRIER_TYPE_TRANSITION,
+                        Flags: D3D12_RESOURCE_BARRIER_FLAG_NONE,
+                        u: unsafe { mem::zeroed() },
+                    };
+                    *unsafe { d3d12_barrier.u.Transition_mut() } =
+                        D3D12_RESOURCE_TRANSITION_BARRIER {
+                            pResource: resource.0.as_raw(),
+                            Subresource: subresource,
+                            StateBefore: before.0,
+                            StateAfter: after.0,
+                        };
+                    d3d12_barrier
+                }
+                Barrier::Uav { resource } => {
+                    let mut d3d12_barrier = D3D12_RESOURCE_BARRIER {
+                        Type: D3D12_RESOURCE_BARRIER_TYPE_UAV,
+                        Flags: D3D12_RESOURCE_BARRIER_FLAG_NONE,
+                        u: unsafe { mem::zeroed() },
+                    };
+                    *unsafe { d3d12_barrier.u.UAV_mut() } = D3D12_RESOURCE_UAV_BARRIER {
+                        pResource: resource.0.as_raw(),
+                    };
+                    d3d12_barrier
+                }
+                Barrier::Aliasing { before, after } => {
+                    let mut d3d12_barrier = D3D12_RESOURCE_BARRIER {
+                        Type: D3D12_RESOURCE_BARRIER_TYPE_ALIASING,
+                        Flags: D3D12_RESOURCE_BARRIER_FLAG_NONE,
+                        u: unsafe { mem::zeroed() },
+                    };
+                    *unsafe { d3d12_barrier.u.Aliasing_mut() } = D3D12_RESOURCE_ALIASING_BARRIER {
+                        pResourceBefore: before.map_or(ptr::null_mut(), |res| res.0.as_raw()),
+                        pResourceAfter: after.map_or(ptr::null_mut(), |res| res.0.as_raw()),
+                    };
+                    d3d12_barrier
+                }
+            })
+            .collect::<Vec<_>>();
+
+        unsafe {
+            self.0.ResourceBarrier(barriers.len() as _, barriers.as_ptr());
+        }
+    }
+
+    fn clear_render_target_view(&self, rtv: CpuDescriptor, color: [f32; 4]) {
+        unsafe {
+            self.0
+                .ClearRenderTargetView(map_cpu_descriptor(rtv), &color, 0, ptr::null());
+        }
+    }
+
+    fn clear_depth_stencil_view(&self, dsv: CpuDescriptor, depth: f32) {
+        unsafe {
+            self.0.ClearDepthStencilView(
+                map_cpu_descriptor(dsv),
+                D3D12_CLEAR_FLAG_DEPTH,
+                depth,
+                0,
+                0,
+                ptr::null(),
+            );
+        }
+    }
+
+    fn set_pipeline_state(&self, pipeline: &PipelineState) {
+        unsafe {
+            self.0.SetPipelineState(pipeline.0.as_raw());
+        }
+    }
+
+    fn set_graphics_root_signature(&self, signature: &RootSignature) {
+        unsafe {
+            self.0.SetGraphicsRootSignature(signature.0.as_raw());
+        }
+    }
+
+    fn set_compute_root_signature(&self, signature: &RootSignature) {
+        unsafe {
+            self.0.SetComputeRootSignature(signature.0.as_raw());
+        }
+    }
+
+    fn set_graphics_root_constant_buffer_view(&self, slot: u32, address: u64) {
+        unsafe {
+            self.0.SetGraphicsRootConstantBufferView(slot, address);
+        }
+    }
+
+    fn set_graphics_root_descriptor_table(&self, slot: u32, table: GpuDescriptor) {
+        unsafe {
+            self.0
+                .SetGraphicsRootDescriptorTable(slot, map_gpu_descriptor(table));
+        }
+    }
+
+    fn set_graphics_root_constants(&self, slot: u32, constants: &[u32], offset: u32) {
+        unsafe {
+            self.0.SetGraphicsRoot32BitConstants(
+                slot,
+                constants.len() as _,
+                constants.as_ptr() as _,
+                offset,
+            );
+        }
+    }
+
+    fn set_compute_root_constant_buffer_view(&self, slot: u32, address: u64) {
+        unsafe {
+            self.0.SetComputeRootConstantBufferView(slot, address);
+        }
+    }
+
+    fn set_compute_root_descriptor_table(&self, slot: u32, table: GpuDescriptor) {
+        unsafe {
+            self.0
+                .SetComputeRootDescriptorTable(slot, map_gpu_descriptor(table));
+        }
+    }
+
+    fn set_compute_root_constants(&self, slot: u32, constants: &[u32], offset: u32) {
+        unsafe {
+            self.0.SetComputeRoot32BitConstants(
+                slot,
+                constants.len() as _,
+                constants.as_ptr() as _,
+                offset,
+            );
+        }
+    }
+
+    fn set_primitive_topology(&self, topology: PrimitiveTopology) {
+        let topology = match topology {
+            PrimitiveTopology::TriangleList => D3D_PRIMITIVE_TOPOLOGY_TRIANGLELIST,
+        };
+        unsafe {
+            self.0.IASetPrimitiveTopology(topology);
+        }
+    }
+
+    fn set_viewport(&self, viewport: &Viewport) {
+        unsafe {
+            self.0.RSSetViewports(
+                1,
+                &D3D12_VIEWPORT {
+                    TopLeftX: viewport.x,
+                    TopLeftY: viewport.y,
+                    Width: viewport.width,
+                    Height: viewport.height,
+                    MinDepth: viewport.min_depth,
+                    MaxDepth: viewport.max_depth,
+                },
+            );
+        }
+    }
+
+    fn set_scissor(&self, rect: &Rect) {
+        unsafe {
+            self.0.RSSetScissorRects(
+                1,
+                &D3D12_RECT {
+                    left: rect.left,
+                    top: rect.top,
+                    right: rect.right,
+                    bottom: rect.bottom,
+                },
+            );
+        }
+    }
+
+    fn set_render_targets(&self, rtvs: &[CpuDescriptor], dsv: Option<CpuDescriptor>) {
+        let rtvs = rtvs
+            .iter()
+            .map(|rtv| map_cpu_descriptor(*rtv))
+            .collect::<Vec<_>>();
+        let dsv = dsv.map(map_cpu_descriptor);
+        unsafe {
+            self.0.OMSetRenderTargets(
+                rtvs.len() as _,
+                rtvs.as_ptr(),
+                FALSE,
+                match dsv {
+                    Some(ref dsv) => dsv,
+                    None => ptr::null(),
+                },
+            );
+        }
+    }
+
+    fn set_index_buffer(&self, view: &IndexBufferView) {
+        unsafe {
+            self.0.IASetIndexBuffer(&D3D12_INDEX_BUFFER_VIEW {
+                BufferLocation: view.location,
+                SizeInBytes: view.size,
+                Format: map_format(view.format),
+            });
+        }
+    }
+
+    fn set_vertex_buffers(&self, start_slot: u32, views: &[VertexBufferView]) {
+        let views = views
+            .iter()
+            .map(|view| D3D12_VERTEX_BUFFER_VIEW {
+                BufferLocation: view.location,
+                SizeInBytes: view.size,
+                StrideInBytes: view.stride,
+            })
+            .collect::<Vec<_>>();
+        unsafe {
+            self.0
+                .IASetVertexBuffers(start_slot, views.len() as _, views.as_ptr());
+        }
+    }
+
+    fn draw_instanced(
+        &self,
+        vertex_count: u32,
+        instance_count: u32,
+        start_vertex: u32,
+        start_instance: u32,
+    ) {
+        unsafe {
+            self.0
+                .DrawInstanced(vertex_count, instance_count, start_vertex, start_instance);
+        }
+    }
+
+    fn draw_indexed_instanced(
+        &self,
+        index_count: u32,
+        instance_count: u32,
+        start_index: u32,
+        base_vertex: i32,
+        start_instance: u32,
+    ) {
+        unsafe {
+            self.0.DrawIndexedInstanced(
+                index_count,
+                instance_count,
+                start_index,
+                base_vertex,
+                start_instance,
+            );
+        }
+    }
+
+    fn dispatch(&self, x: u32, y: u32, z: u32) {
+        unsafe {
+            self.0.Dispatch(x, y, z);
+        }
+    }
+
+    fn copy_resource(&self, dst: &Resource, src: &Resource) {
+        unsafe {
+            self.0.CopyResource(dst.0.as_raw(), src.0.as_raw());
+        }
+    }
+
+    fn copy_buffer_region(
+        &self,
+        dst: &Resource,
+        dst_offset: u64,
+        src: &Resource,
+        src_offset: u64,
+        size: u64,
+    ) {
+        unsafe {
+            self.0
+                .CopyBufferRegion(dst.0.as_raw(), dst_offset, src.0.as_raw(), src_offset, size);
+        }
+    }
+
+    fn copy_texture_region(
+        &self,
+        dst: &Resource,
+        dst_subresource: u32,
+        dst_origin: [u32; 3],
+        src: &Resource,
+        src_footprint: &PlacedFootprint,
+    ) {
+        let mut dst_location = D3D12_TEXTURE_COPY_LOCATION {
+            pResource: dst.0.as_raw(),
+            Type: D3D12_TEXTURE_COPY_TYPE_SUBRESOURCE_INDEX,
+            ..unsafe { mem::zeroed() }
+        };
+        unsafe {
+            *dst_location.u.SubresourceIndex_mut() = dst_subresource;
+        }
+
+        let mut src_location = D3D12_TEXTURE_COPY_LOCATION {
+            pResource: src.0.as_raw(),
+            Type: D3D12_TEXTURE_COPY_TYPE_PLACED_FOOTPRINT,
+            ..unsafe { mem::zeroed() }
+        };
+        unsafe {
+            *src_location.u.PlacedFootprint_mut() = D3D12_PLACED_SUBRESOURCE_FOOTPRINT {
+                Offset: src_footprint.offset,
+                Footprint: D3D12_SUBRESOURCE_FOOTPRINT {
+                    Format: map_format(src_footprint.format),
+                    Width: src_footprint.width,
+                    Height: src_footprint.height,
+                    Depth: src_footprint.depth,
+                    RowPitch: src_footprint.row_pitch,
+                },
+            };
+        }
+
+        unsafe {
+            self.0.CopyTextureRegion(
+                &dst_location,
+                dst_origin[0],
+                dst_origin[1],
+                dst_origin[2],
+                &src_location,
+                ptr::null(),
+            );
+        }
+    }
+}
+
+impl backend::Swapchain<D3D12> for Swapchain {
+    fn num_buffers(&self) -> usize {
+        self.buffers.len()
+    }
+
+    fn buffer(&self, index: usize) -> Resource {
+        self.buffers[index].clone()
+    }
+
+    fn current_index(&self) -> usize {
+        unsafe { self.swapchain.GetCurrentBackBufferIndex() as _ }
+    }
+
+    fn present(&self) -> Result<(), EngineError> {
+        let hr = unsafe { self.swapchain.Present(0, 0) };
+        check_hr(hr, "Present", String::new)
+    }
+
+    fn resize(&mut self, _device: &Device, width: u32, height: u32) -> Result<(), EngineError> {
+        let count = self.buffers.len() as u32;
+        // Buffers must be released before resizing.
+        self.buffers.clear();
+        let hr = unsafe {
+            self.swapchain
+                .ResizeBuffers(count, width, height, DXGI_FORMAT_UNKNOWN, 0)
+        };
+        check_hr(hr, "ResizeBuffers", || format!("{}x{}", width, height))?;
+        self.buffers = swapchain_buffers(&self.swapchain, count)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+//! Errors of fallible engine operations.
+
+use failure::Fail;
+use std::fmt;
+
+pub const DXGI_ERROR_DEVICE_REMOVED: i32 = 0x887A_0005u32 as i32;
+pub const DXGI_ERROR_DEVICE_HUNG: i32 = 0x887A_0006u32 as i32;
+pub const DXGI_ERROR_DEVICE_RESET: i32 = 0x887A_0007u32 as i32;
+pub const DXGI_ERROR_DRIVER_INTERNAL_ERROR: i32 = 0x887A_0020u32 as i32;
+
+/// Cause of a failed operation.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ErrorKind {
+    /// Native call returned a failure code.
+    Hresult(i32),
+    /// No adapter supporting the required feature level found.
+    NoAdapter,
+    /// Shader compilation or root signature serialization failed.
+    Compilation(String),
+    /// Descriptor heap has no free range of the requested size left.
+    OutOfDescriptors,
+    /// Arguments rejected before reaching the device.
+    InvalidArgument(String),
+    /// Reading or decoding an asset failed.
+    Asset(String),
+    /// Waiting for the GPU timed out.
+    Timeout,
+    /// Device was lost, including the reason reported by the device.
+    DeviceRemoved(i32),
+}
+
+/// Failed engine operation including the operation and the involved description.
+#[derive(Clone, Debug)]
+pub struct EngineError {
+    /// Failed operation, e.g. the native function name.
+    pub operation: &'static str,
+    /// Description of the arguments of the operation, e.g. the resource description.
+    pub description: String,
+    pub kind: ErrorKind,
+}
+
+impl EngineError {
+    pub fn new(operation: &'static str, description: String, kind: ErrorKind) -> Self {
+        EngineError {
+            operation,
+            description,
+            kind,
+        }
+    }
+
+    /// Whether the operation failed due to a lost device.
+    ///
+    /// All objects of the device need to be recreated on a new device.
+    pub fn is_device_lost(&self) -> bool {
+        match self.kind {
+            ErrorKind::DeviceRemoved(_) => true,
+            ErrorKind::Hresult(hr) => [
+                DXGI_ERROR_DEVICE_REMOVED,
+                DXGI_ERROR_DEVICE_HUNG,
+                DXGI_ERROR_DEVICE_RESET,
+                DXGI_ERROR_DRIVER_INTERNAL_ERROR,
+            ]
+                .contains(&hr),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ErrorKind::Hresult(hr) => match hresult_name(hr) {
+                Some(name) => write!(f, "{} (0x{:08X})", name, hr as u32),
+                None => write!(f, "HRESULT 0x{:08X}", hr as u32),
+            },
+            ErrorKind::NoAdapter => write!(f, "no suitable adapter found"),
+            ErrorKind::Compilation(ref log) => write!(f, "compilation failed:\n{}", log),
+            ErrorKind::OutOfDescriptors => write!(f, "descriptor heap exhausted"),
+            ErrorKind::InvalidArgument(ref reason) => write!(f, "invalid argument: {}", reason),
+            ErrorKind::Asset(ref reason) => write!(f, "{}", reason),
+            ErrorKind::Timeout => write!(f, "timed out"),
+            ErrorKind::DeviceRemoved(reason) => {
+                write!(f, "device removed, reason: {}", ErrorKind::Hresult(reason))
+            }
+        }
+    }
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} failed", self.operation)?;
+        if !self.description.is_empty() {
+            write!(f, " for {}", self.description)?;
+        }
+        write!(f, ": {}", self.kind)
+    }
+}
+
+impl Fail for EngineError {}
+
+/// Symbolic name of common D3D12 and DXGI failure codes.
+pub fn hresult_name(hr: i32) -> Option<&'static str> {
+    let name = match hr as u32 {
+        0x8000_4001 => "E_NOTIMPL",
+        0x8000_4002 => "E_NOINTERFACE",
+        0x8000_4003 => "E_POINTER",
+        0x8000_4005 => "E_FAIL",
+        0x8007_000E => "E_OUTOFMEMORY",
+        0x8007_0057 => "E_INVALIDARG",
+        0x887A_0001 => "DXGI_ERROR_INVALID_CALL",
+        0x887A_0002 => "DXGI_ERROR_NOT_FOUND",
+        0x887A_0004 => "DXGI_ERROR_UNSUPPORTED",
+        0x887A_0005 => "DXGI_ERROR_DEVICE_REMOVED",
+        0x887A_0006 => "DXGI_ERROR_DEVICE_HUNG",
+        0x887A_0007 => "DXGI_ERROR_DEVICE_RESET",
+        0x887A_000A => "DXGI_ERROR_WAS_STILL_DRAWING",
+        0x887A_0020 => "DXGI_ERROR_DRIVER_INTERNAL_ERROR",
+        0x887A_0022 => "DXGI_ERROR_NOT_CURRENTLY_AVAILABLE",
+        0x887E_0001 => "D3D12_ERROR_ADAPTER_NOT_FOUND",
+        0x887E_0002 => "D3D12_ERROR_DRIVER_VERSION_MISMATCH",
+        _ => return None,
+    };
+    Some(name)
+}
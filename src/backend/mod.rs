@@ -0,0 +1,264 @@
+//! Rendering backends.
+//!
+//! The engine, passes and scene only talk to the GPU through the `Device`,
+//! `CommandList` and `Swapchain` traits defined here:
+//!
+//!  * D3D12: Native backend, only available on Windows.
+//!  * Null: Headless backend without any GPU. Records all calls and validates
+//!          their arguments, allowing to run the full frame loop in CI.
+//!
+//! Creation of device objects is fallible, failures are reported as `EngineError`
+//! including the operation and the decoded HRESULT.
+
+use config::EngineConfig;
+use winit;
+
+mod adapter;
+#[cfg(windows)]
+pub mod d3d12;
+mod error;
+pub mod null;
+mod types;
+
+pub use self::adapter::*;
+pub use self::error::*;
+pub use self::types::*;
+
+pub trait Backend: Sized + 'static {
+    type Device: Device<Self>;
+    type Resource: Clone + Send + Sync + ResourceId;
+    type DescriptorHeap: Send + Sync;
+    type Heap: Send + Sync;
+    type CommandAllocator;
+    type CommandList: CommandList<Self>;
+    type Fence;
+    type Shader;
+    type RootSignature;
+    type PipelineState;
+    type Swapchain: Swapchain<Self>;
+
+    /// Open a device including its direct queue on the adapter selected by `config`.
+    fn create_device(config: &EngineConfig) -> Result<Self::Device, EngineError>;
+
+    /// Available adapters in enumeration order.
+    fn enumerate_adapters() -> Result<Vec<AdapterInfo>, EngineError>;
+}
+
+/// Identity of a resource, shared by all handles to the same resource.
+pub trait ResourceId {
+    fn resource_id(&self) -> u64;
+}
+
+pub trait Device<B: Backend> {
+    fn create_command_allocator(&self) -> Result<B::CommandAllocator, EngineError>;
+
+    /// Create a new command list in closed state.
+    fn create_command_list(
+        &self,
+        allocator: &B::CommandAllocator,
+    ) -> Result<B::CommandList, EngineError>;
+
+    fn create_committed_resource(
+        &self,
+        heap: HeapType,
+        desc: &ResourceDesc,
+        initial: ResourceStates,
+        clear_value: Option<ClearValue>,
+    ) -> Result<B::Resource, EngineError>;
+
+    /// Create a memory heap for placed resources.
+    fn create_heap(&self, desc: &HeapDesc) -> Result<B::Heap, EngineError>;
+
+    /// Create a resource at `offset` in the heap, the offset must be aligned as
+    /// reported by `resource_allocation_info`.
+    fn create_placed_resource(
+        &self,
+        heap: &B::Heap,
+        offset: u64,
+        desc: &ResourceDesc,
+        initial: ResourceStates,
+        clear_value: Option<ClearValue>,
+    ) -> Result<B::Resource, EngineError>;
+
+    /// Memory requirements for placing the resource in a heap.
+    fn resource_allocation_info(&self, desc: &ResourceDesc) -> AllocationInfo;
+
+    fn create_descriptor_heap(
+        &self,
+        ty: DescriptorHeapType,
+        size: usize,
+        shader_visible: bool,
+    ) -> Result<B::DescriptorHeap, EngineError>;
+
+    fn descriptor_increment_size(&self, ty: DescriptorHeapType) -> u32;
+
+    /// CPU and GPU handle of the first descriptor in the heap.
+    ///
+    /// The GPU handle is null for heaps which are not shader visible.
+    fn descriptor_heap_start(&self, heap: &B::DescriptorHeap) -> (CpuDescriptor, GpuDescriptor);
+
+    fn create_fence(&self, initial: u64) -> Result<B::Fence, EngineError>;
+
+    /// Compile a shader from source, `entry_point` and `target` are nul-terminated.
+    ///
+    /// `defines` are passed as preprocessor macros, as `(name, definition)` pairs.
+    fn create_shader(
+        &self,
+        name: &str,
+        source: &str,
+        entry_point: &str,
+        target: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<B::Shader, EngineError>;
+
+    fn create_root_signature(
+        &self,
+        desc: &RootSignatureDesc,
+    ) -> Result<B::RootSignature, EngineError>;
+
+    fn create_graphics_pipeline(
+        &self,
+        desc: &GraphicsPipelineDesc<B>,
+    ) -> Result<B::PipelineState, EngineError>;
+
+    fn create_compute_pipeline(
+        &self,
+        signature: &B::RootSignature,
+        shader: &B::Shader,
+    ) -> Result<B::PipelineState, EngineError>;
+
+    fn create_shader_resource_view(
+        &self,
+        resource: &B::Resource,
+        desc: &SrvDesc,
+        handle: CpuDescriptor,
+    );
+    fn create_unordered_access_view(
+        &self,
+        resource: &B::Resource,
+        desc: &UavDesc,
+        handle: CpuDescriptor,
+    );
+    fn create_render_target_view(&self, resource: &B::Resource, desc: &RtvDesc, handle: CpuDescriptor);
+    fn create_depth_stencil_view(&self, resource: &B::Resource, desc: &DsvDesc, handle: CpuDescriptor);
+
+    /// Map the whole resource for CPU access.
+    ///
+    /// Only valid for resources in upload or readback heaps.
+    unsafe fn map(&self, resource: &B::Resource) -> Result<*mut u8, EngineError>;
+    fn unmap(&self, resource: &B::Resource);
+
+    fn gpu_virtual_address(&self, resource: &B::Resource) -> u64;
+
+    fn execute_command_lists(&self, lists: &[&B::CommandList]);
+    fn signal(&self, fence: &B::Fence, value: u64);
+    /// Last value the fence reached on the GPU.
+    fn fence_value(&self, fence: &B::Fence) -> u64;
+
+    /// Block until the fence reached `value` or `timeout_ms` passed.
+    ///
+    /// Fails with `ErrorKind::Timeout` if the wait timed out, or with
+    /// `ErrorKind::DeviceRemoved` if the device was lost.
+    fn wait_for_fence(
+        &self,
+        fence: &B::Fence,
+        value: u64,
+        timeout_ms: u32,
+    ) -> Result<(), EngineError>;
+
+    /// Check whether the device has been removed, returning the removal reason.
+    fn check_device_removed(&self) -> Result<(), EngineError>;
+
+    /// Mark the device as removed, e.g. for testing the recovery from device loss.
+    fn simulate_device_removed(&self);
+
+    /// Validation errors reported by the backend so far.
+    ///
+    /// Only the null backend validates calls, native backends report through their
+    /// debug layer instead.
+    fn validation_errors(&self) -> Vec<String>;
+
+    /// Create a swapchain for the window, headless backends may ignore the window.
+    fn create_swapchain(
+        &self,
+        window: Option<&winit::Window>,
+        desc: &SwapchainDesc,
+    ) -> Result<B::Swapchain, EngineError>;
+}
+
+pub trait CommandList<B: Backend> {
+    fn reset(&self, allocator: &B::CommandAllocator);
+    fn close(&self);
+
+    fn set_descriptor_heaps(&self, heaps: &[&B::DescriptorHeap]);
+    fn resource_barrier(&self, barriers: &[Barrier<B>]);
+
+    fn clear_render_target_view(&self, rtv: CpuDescriptor, color: [f32; 4]);
+    fn clear_depth_stencil_view(&self, dsv: CpuDescriptor, depth: f32);
+
+    fn set_pipeline_state(&self, pipeline: &B::PipelineState);
+    fn set_graphics_root_signature(&self, signature: &B::RootSignature);
+    fn set_compute_root_signature(&self, signature: &B::RootSignature);
+
+    fn set_graphics_root_constant_buffer_view(&self, slot: u32, address: u64);
+    fn set_graphics_root_descriptor_table(&self, slot: u32, table: GpuDescriptor);
+    fn set_graphics_root_constants(&self, slot: u32, constants: &[u32], offset: u32);
+    fn set_compute_root_constant_buffer_view(&self, slot: u32, address: u64);
+    fn set_compute_root_descriptor_table(&self, slot: u32, table: GpuDescriptor);
+    fn set_compute_root_constants(&self, slot: u32, constants: &[u32], offset: u32);
+
+    fn set_primitive_topology(&self, topology: PrimitiveTopology);
+    fn set_viewport(&self, viewport: &Viewport);
+    fn set_scissor(&self, rect: &Rect);
+    fn set_render_targets(&self, rtvs: &[CpuDescriptor], dsv: Option<CpuDescriptor>);
+    fn set_index_buffer(&self, view: &IndexBufferView);
+    fn set_vertex_buffers(&self, start_slot: u32, views: &[VertexBufferView]);
+
+    fn draw_instanced(
+        &self,
+        vertex_count: u32,
+        instance_count: u32,
+        start_vertex: u32,
+        start_instance: u32,
+    );
+    fn draw_indexed_instanced(
+        &self,
+        index_count: u32,
+        instance_count: u32,
+        start_index: u32,
+        base_vertex: i32,
+        start_instance: u32,
+    );
+    fn dispatch(&self, x: u32, y: u32, z: u32);
+
+    fn copy_resource(&self, dst: &B::Resource, src: &B::Resource);
+    fn copy_buffer_region(
+        &self,
+        dst: &B::Resource,
+        dst_offset: u64,
+        src: &B::Resource,
+        src_offset: u64,
+        size: u64,
+    );
+    /// Copy the footprint into the subresource, placing its first texel at `dst_origin` (x, y, z).
+    fn copy_texture_region(
+        &self,
+        dst: &B::Resource,
+        dst_subresource: u32,
+        dst_origin: [u32; 3],
+        src: &B::Resource,
+        src_footprint: &PlacedFootprint,
+    );
+}
+
+pub trait Swapchain<B: Backend> {
+    fn num_buffers(&self) -> usize;
+    fn buffer(&self, index: usize) -> B::Resource;
+    fn current_index(&self) -> usize;
+    fn present(&self) -> Result<(), EngineError>;
+
+    /// Resize all buffers, keeping their number and format.
+    ///
+    /// All references to the buffers must be released and the GPU must be idle.
+    fn resize(&mut self, device: &B::Device, width: u32, height: u32) -> Result<(), EngineError>;
+}
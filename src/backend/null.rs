@@ -0,0 +1,2079 @@
+//! Null backend.
+//!
+//! Headless backend without any GPU. All device and command list calls are recorded
+//! and their arguments validated similar to the D3D12 debug layer:
+//!
+//!  * Resource descriptions, heap types and view compatibility.
+//!  * Placed resources against alignment, bounds and flags of their heap.
+//!  * Descriptor handles against their heaps and bounds.
+//!  * Barrier states against the tracked resource states.
+//!  * Root arguments against the currently bound root signature.
+//!  * Input layouts against the root signature and the bound vertex buffers, unmaps
+//!    against outstanding maps.
+//!
+//! Buffers are backed by CPU memory and copies between buffers are executed
+//! while recording, allowing to inspect uploaded data.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use winit;
+
+use backend::{self, *};
+use config::EngineConfig;
+
+/// Increment size between two descriptors of a heap.
+const DESCRIPTOR_SIZE: u32 = 32;
+
+pub enum Null {}
+
+impl Backend for Null {
+    type Device = Device;
+    type Resource = Resource;
+    type DescriptorHeap = DescriptorHeap;
+    type Heap = Heap;
+    type CommandAllocator = CommandAllocator;
+    type CommandList = CommandList;
+    type Fence = Fence;
+    type Shader = Shader;
+    type RootSignature = RootSignature;
+    type PipelineState = PipelineState;
+    type Swapchain = Swapchain;
+
+    fn create_device(config: &EngineConfig) -> Result<Device, EngineError> {
+        backend::select_adapter(Some(NullAdapter), config.min_feature_level, &config.adapter)?;
+        Ok(Device::new())
+    }
+
+    fn enumerate_adapters() -> Result<Vec<AdapterInfo>, EngineError> {
+        Ok(vec![NullAdapter.info()])
+    }
+}
+
+/// Single software adapter supporting all feature levels.
+struct NullAdapter;
+
+impl Adapter for NullAdapter {
+    fn info(&self) -> AdapterInfo {
+        AdapterInfo {
+            index: 0,
+            name: "Null Adapter".into(),
+            dedicated_video_memory: 0,
+            software: true,
+        }
+    }
+
+    fn supports(&self, _level: FeatureLevel) -> bool {
+        true
+    }
+}
+
+/// Recorded device or command list call.
+#[derive(Clone, Debug)]
+pub struct Call {
+    pub name: &'static str,
+    pub args: String,
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}({})", self.name, self.args)
+    }
+}
+
+struct HeapInfo {
+    ty: DescriptorHeapType,
+    size: usize,
+    shader_visible: bool,
+}
+
+#[derive(Default)]
+struct Log {
+    calls: Vec<Call>,
+    errors: Vec<String>,
+    next_id: u64,
+    heaps: HashMap<u64, HeapInfo>,
+    // Reason of the simulated device removal.
+    removed: Option<i32>,
+}
+
+impl Log {
+    fn call(&mut self, name: &'static str, args: String) {
+        self.calls.push(Call { name, args });
+    }
+
+    fn check_removed(&self, operation: &'static str) -> Result<(), EngineError> {
+        match self.removed {
+            Some(reason) => Err(EngineError::new(
+                operation,
+                String::new(),
+                ErrorKind::DeviceRemoved(reason),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn error(&mut self, message: String) {
+        let call = self
+            .calls
+            .last()
+            .map(|call| call.name)
+            .unwrap_or("<none>");
+        self.errors.push(format!("{}: {}", call, message));
+    }
+
+    fn check(&mut self, condition: bool, message: &str) {
+        if !condition {
+            self.error(message.into());
+        }
+    }
+
+    fn alloc_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Resolve a cpu descriptor handle into heap id and slot.
+    fn resolve_cpu(&mut self, handle: CpuDescriptor, ty: DescriptorHeapType) -> Option<(u64, usize)> {
+        let heap_id = (handle.ptr >> 32) as u64;
+        let offset = handle.ptr & 0xFFFF_FFFF;
+        self.resolve(heap_id, offset as u64, ty, false)
+    }
+
+    /// Resolve a gpu descriptor handle into heap id and slot.
+    fn resolve_gpu(&mut self, handle: GpuDescriptor, ty: DescriptorHeapType) -> Option<(u64, usize)> {
+        let heap_id = handle.ptr >> 32;
+        let offset = handle.ptr & 0xFFFF_FFFF;
+        self.resolve(heap_id, offset, ty, true)
+    }
+
+    fn resolve(
+        &mut self,
+        heap_id: u64,
+        offset: u64,
+        ty: DescriptorHeapType,
+        gpu: bool,
+    ) -> Option<(u64, usize)> {
+        let result = match self.heaps.get(&heap_id) {
+            None => Err(format!("descriptor handle doesn't belong to a heap ({:x})", heap_id)),
+            Some(heap) if heap.ty != ty => Err(format!(
+                "descriptor handle of heap type {:?}, expected {:?}",
+                heap.ty, ty
+            )),
+            Some(heap) if gpu && !heap.shader_visible => {
+                Err("gpu descriptor handle of non shader visible heap".into())
+            }
+            Some(_) if offset % DESCRIPTOR_SIZE as u64 != 0 => {
+                Err(format!("misaligned descriptor handle offset {}", offset))
+            }
+            Some(heap) if (offset / DESCRIPTOR_SIZE as u64) as usize >= heap.size => Err(format!(
+                "descriptor slot {} out of heap bounds ({})",
+                offset / DESCRIPTOR_SIZE as u64,
+                heap.size
+            )),
+            Some(_) => Ok((heap_id, (offset / DESCRIPTOR_SIZE as u64) as usize)),
+        };
+
+        match result {
+            Ok(slot) => Some(slot),
+            Err(message) => {
+                self.error(message);
+                None
+            }
+        }
+    }
+}
+
+type SharedLog = Arc<Mutex<Log>>;
+
+/// Placement size and alignment, approximating the D3D12 layouts.
+fn allocation_info(desc: &ResourceDesc) -> AllocationInfo {
+    let alignment = if desc.samples > 1 {
+        DEFAULT_MSAA_RESOURCE_PLACEMENT_ALIGNMENT
+    } else {
+        DEFAULT_RESOURCE_PLACEMENT_ALIGNMENT
+    };
+
+    let size = match desc.dimension {
+        ResourceDimension::Buffer => desc.width,
+        ResourceDimension::Texture2D => {
+            let format = desc.format;
+            let layer_size = (0..desc.mip_levels as u32)
+                .map(|level| {
+                    let width = (desc.width as u32 >> level).max(1);
+                    let height = (desc.height >> level).max(1);
+                    format.row_size(width) as u64
+                        * format.num_rows(height) as u64
+                        * desc.samples as u64
+                })
+                .sum::<u64>();
+            layer_size * desc.depth_or_array_size as u64
+        }
+    };
+
+    AllocationInfo {
+        size: (size + alignment - 1) / alignment * alignment,
+        alignment,
+    }
+}
+
+pub struct Device {
+    log: SharedLog,
+}
+
+impl Device {
+    pub fn new() -> Self {
+        Device {
+            log: Arc::new(Mutex::new(Log::default())),
+        }
+    }
+
+    /// All calls recorded so far.
+    #[cfg(test)]
+    pub fn calls(&self) -> Vec<Call> {
+        self.log.lock().unwrap().calls.clone()
+    }
+
+    fn validate_initial_state(
+        log: &mut Log,
+        heap: HeapType,
+        desc: &ResourceDesc,
+        initial: ResourceStates,
+        clear_value: Option<ClearValue>,
+    ) {
+        if clear_value.is_some() {
+            log.check(
+                desc.flags.intersects(
+                    ResourceFlags::ALLOW_RENDER_TARGET | ResourceFlags::ALLOW_DEPTH_STENCIL,
+                ),
+                "clear value for resource which is neither render target nor depth stencil",
+            );
+        }
+        match heap {
+            HeapType::Upload => log.check(
+                ResourceStates::GENERIC_READ.contains(initial),
+                "upload heap resources must be in a read state",
+            ),
+            HeapType::Readback => log.check(
+                initial == ResourceStates::COPY_DEST,
+                "readback heap resources must be in copy destination state",
+            ),
+            HeapType::Default => (),
+        }
+    }
+
+    fn check_removed(&self, operation: &'static str) -> Result<(), EngineError> {
+        self.log.lock().unwrap().check_removed(operation)
+    }
+
+    fn new_resource(
+        id: u64,
+        heap: HeapType,
+        placement: Option<Placement>,
+        desc: &ResourceDesc,
+        initial: ResourceStates,
+    ) -> Resource {
+        let data = match desc.dimension {
+            ResourceDimension::Buffer => vec![0; desc.width as usize],
+            ResourceDimension::Texture2D => Vec::new(),
+        };
+
+        Resource(Arc::new(ResourceInner {
+            id,
+            heap,
+            placement,
+            desc: *desc,
+            state: Mutex::new(initial),
+            data: Mutex::new(data),
+            mapped: Mutex::new(0),
+        }))
+    }
+
+    fn validate_resource_desc(log: &mut Log, heap: HeapType, desc: &ResourceDesc) {
+        log.check(desc.width > 0 && desc.height > 0, "resource with zero extent");
+        log.check(
+            desc.depth_or_array_size > 0 && desc.mip_levels > 0,
+            "resource with zero layers or mip levels",
+        );
+        log.check(
+            [1, 2, 4, 8].contains(&desc.samples),
+            "unsupported sample count",
+        );
+        log.check(
+            !(desc.flags.contains(ResourceFlags::ALLOW_RENDER_TARGET)
+                && desc.flags.contains(ResourceFlags::ALLOW_DEPTH_STENCIL)),
+            "resource can't be render target and depth stencil at the same time",
+        );
+
+        match desc.dimension {
+            ResourceDimension::Buffer => {
+                log.check(desc.height == 1, "buffer height must be 1");
+                log.check(desc.format == Format::Unknown, "buffer format must be unknown");
+                log.check(desc.samples == 1, "buffers can't be multisampled");
+                log.check(
+                    !desc.flags.intersects(
+                        ResourceFlags::ALLOW_RENDER_TARGET | ResourceFlags::ALLOW_DEPTH_STENCIL,
+                    ),
+                    "buffers can't be render or depth targets",
+                );
+            }
+            ResourceDimension::Texture2D => {
+                log.check(desc.format != Format::Unknown, "texture format unknown");
+                log.check(
+                    heap == HeapType::Default,
+                    "textures are only supported in default heaps",
+                );
+                log.check(
+                    desc.samples == 1 || desc.mip_levels == 1,
+                    "multisampled textures can't have mip levels",
+                );
+                if desc.format.is_compressed() {
+                    let extent = desc.format.block_extent();
+                    log.check(
+                        desc.width % extent as u64 == 0 && desc.height % extent == 0,
+                        "block compressed texture extent must be a multiple of the block size",
+                    );
+                    log.check(
+                        desc.samples == 1,
+                        "block compressed textures can't be multisampled",
+                    );
+                    log.check(
+                        !desc.flags.intersects(
+                            ResourceFlags::ALLOW_RENDER_TARGET
+                                | ResourceFlags::ALLOW_DEPTH_STENCIL
+                                | ResourceFlags::ALLOW_UNORDERED_ACCESS,
+                        ),
+                        "block compressed textures can't be written by the GPU",
+                    );
+                }
+                if desc.flags.contains(ResourceFlags::ALLOW_DEPTH_STENCIL) {
+                    log.check(
+                        desc.format.is_depth_compatible(),
+                        "depth stencil requires depth format",
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Memory range of a placed resource.
+#[derive(Copy, Clone, Debug)]
+struct Placement {
+    heap: u64,
+    offset: u64,
+    size: u64,
+}
+
+pub struct ResourceInner {
+    id: u64,
+    heap: HeapType,
+    placement: Option<Placement>,
+    desc: ResourceDesc,
+    state: Mutex<ResourceStates>,
+    data: Mutex<Vec<u8>>,
+    // Number of outstanding `map` calls.
+    mapped: Mutex<u32>,
+}
+
+#[derive(Clone)]
+pub struct Resource(Arc<ResourceInner>);
+
+impl Resource {
+    pub fn id(&self) -> u64 {
+        self.0.id
+    }
+
+    pub fn desc(&self) -> &ResourceDesc {
+        &self.0.desc
+    }
+
+    /// Currently tracked resource state.
+    pub fn state(&self) -> ResourceStates {
+        *self.0.state.lock().unwrap()
+    }
+
+    /// Copy of the CPU backing memory (buffers only).
+    #[cfg(test)]
+    pub fn data(&self) -> Vec<u8> {
+        self.0.data.lock().unwrap().clone()
+    }
+}
+
+impl ResourceId for Resource {
+    fn resource_id(&self) -> u64 {
+        self.0.id
+    }
+}
+
+pub struct DescriptorHeap {
+    id: u64,
+}
+
+pub struct Heap {
+    id: u64,
+    desc: HeapDesc,
+}
+
+pub struct CommandAllocator {
+    id: u64,
+}
+
+pub struct Fence {
+    value: Cell<u64>,
+}
+
+pub struct Shader {
+    pub entry_point: String,
+    pub target: String,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum RootSlot {
+    Cbv,
+    Constants(u32),
+    Table,
+}
+
+#[derive(Clone)]
+pub struct RootSignature {
+    id: u64,
+    slots: Arc<Vec<RootSlot>>,
+    allow_input_layout: bool,
+}
+
+#[derive(Clone)]
+pub struct PipelineState {
+    id: u64,
+    compute: bool,
+    // Size of the input elements of each vertex buffer slot.
+    input_strides: Arc<HashMap<u32, u32>>,
+}
+
+#[derive(Default)]
+struct ListState {
+    open: bool,
+    heaps: Vec<u64>,
+    pipeline: Option<PipelineState>,
+    graphics_signature: Option<RootSignature>,
+    compute_signature: Option<RootSignature>,
+    render_targets: usize,
+    index_buffer: bool,
+    // Stride of the vertex buffer bound to each slot.
+    vertex_strides: HashMap<u32, u32>,
+}
+
+pub struct CommandList {
+    id: u64,
+    log: SharedLog,
+    state: RefCell<ListState>,
+}
+
+impl CommandList {
+    fn record<'a>(&'a self, name: &'static str, args: String) -> MutexGuard<'a, Log> {
+        let mut log = self.log.lock().unwrap();
+        log.call(name, format!("list {}: {}", self.id, args));
+        if !self.state.borrow().open {
+            log.error("recording into closed command list".into());
+        }
+        log
+    }
+
+    fn check_root_slot(
+        log: &mut Log,
+        signature: &Option<RootSignature>,
+        slot: u32,
+        expected: RootSlot,
+        num_values: u32,
+    ) {
+        let signature = match *signature {
+            Some(ref signature) => signature,
+            None => {
+                log.error("root argument set without bound root signature".into());
+                return;
+            }
+        };
+
+        match signature.slots.get(slot as usize) {
+            None => log.error(format!("root parameter {} out of bounds", slot)),
+            Some(&RootSlot::Constants(num)) => {
+                if let RootSlot::Constants(_) = expected {
+                    log.check(num_values <= num, "too many root constants");
+                } else {
+                    log.error(format!("root parameter {} type mismatch", slot));
+                }
+            }
+            Some(&kind) => log.check(kind == expected, "root parameter type mismatch"),
+        }
+    }
+
+    /// Check the draw state against the bound graphics pipeline.
+    fn check_draw(&self, log: &mut Log) {
+        let state = self.state.borrow();
+        let pipeline = match state.pipeline {
+            Some(ref pipeline) if !pipeline.compute => pipeline,
+            _ => {
+                log.error("draw without graphics pipeline".into());
+                return;
+            }
+        };
+        for (slot, &size) in pipeline.input_strides.iter() {
+            match state.vertex_strides.get(slot) {
+                Some(&stride) => log.check(
+                    stride >= size,
+                    "vertex buffer stride smaller than input layout",
+                ),
+                None => log.error(format!("input slot {} without vertex buffer", slot)),
+            }
+        }
+        log.check(
+            state.graphics_signature.is_some(),
+            "draw without root signature",
+        );
+    }
+
+    fn check_table(&self, log: &mut Log, table: GpuDescriptor) {
+        if let Some((heap, _)) = log.resolve_gpu(table, DescriptorHeapType::CbvSrvUav) {
+            let bound = self.state.borrow().heaps.contains(&heap);
+            log.check(bound, "descriptor table of unbound descriptor heap");
+        }
+    }
+}
+
+pub struct Swapchain {
+    buffers: Vec<Resource>,
+    current: Cell<usize>,
+    log: SharedLog,
+}
+
+impl backend::Device<Null> for Device {
+    fn create_command_allocator(&self) -> Result<CommandAllocator, EngineError> {
+        self.check_removed("create_command_allocator")?;
+        let mut log = self.log.lock().unwrap();
+        let id = log.alloc_id();
+        log.call("create_command_allocator", format!("{}", id));
+        Ok(CommandAllocator { id })
+    }
+
+    fn create_command_list(
+        &self,
+        allocator: &CommandAllocator,
+    ) -> Result<CommandList, EngineError> {
+        self.check_removed("create_command_list")?;
+        let mut log = self.log.lock().unwrap();
+        let id = log.alloc_id();
+        log.call(
+            "create_command_list",
+            format!("{}, allocator: {}", id, allocator.id),
+        );
+        Ok(CommandList {
+            id,
+            log: self.log.clone(),
+            state: RefCell::new(ListState::default()),
+        })
+    }
+
+    fn create_committed_resource(
+        &self,
+        heap: HeapType,
+        desc: &ResourceDesc,
+        initial: ResourceStates,
+        clear_value: Option<ClearValue>,
+    ) -> Result<Resource, EngineError> {
+        self.check_removed("create_committed_resource")?;
+        let mut log = self.log.lock().unwrap();
+        let id = log.alloc_id();
+        log.call(
+            "create_committed_resource",
+            format!("{}, {:?}, {:?}, {:?}", id, heap, desc, initial),
+        );
+
+        Self::validate_resource_desc(&mut log, heap, desc);
+        Self::validate_initial_state(&mut log, heap, desc, initial, clear_value);
+        Ok(Self::new_resource(id, heap, None, desc, initial))
+    }
+
+    fn create_heap(&self, desc: &HeapDesc) -> Result<Heap, EngineError> {
+        self.check_removed("create_heap")?;
+        let mut log = self.log.lock().unwrap();
+        let id = log.alloc_id();
+        log.call("create_heap", format!("{}, {:?}", id, desc));
+        log.check(desc.size > 0, "empty heap");
+        log.check(
+            desc.alignment == DEFAULT_RESOURCE_PLACEMENT_ALIGNMENT
+                || desc.alignment == DEFAULT_MSAA_RESOURCE_PLACEMENT_ALIGNMENT,
+            "unsupported heap alignment",
+        );
+        log.check(
+            desc.size % desc.alignment == 0,
+            "heap size must be a multiple of its alignment",
+        );
+        log.check(
+            desc.flags != HeapFlags::NONE,
+            "heaps must be restricted to a single resource category",
+        );
+
+        Ok(Heap { id, desc: *desc })
+    }
+
+    fn create_placed_resource(
+        &self,
+        heap: &Heap,
+        offset: u64,
+        desc: &ResourceDesc,
+        initial: ResourceStates,
+        clear_value: Option<ClearValue>,
+    ) -> Result<Resource, EngineError> {
+        self.check_removed("create_placed_resource")?;
+        let mut log = self.log.lock().unwrap();
+        let id = log.alloc_id();
+        log.call(
+            "create_placed_resource",
+            format!(
+                "{}, heap: {}, offset: {}, {:?}, {:?}",
+                id, heap.id, offset, desc, initial
+            ),
+        );
+
+        Self::validate_resource_desc(&mut log, heap.desc.ty, desc);
+        Self::validate_initial_state(&mut log, heap.desc.ty, desc, initial, clear_value);
+
+        let info = allocation_info(desc);
+        log.check(offset % info.alignment == 0, "misaligned placed resource");
+        log.check(
+            info.alignment <= heap.desc.alignment,
+            "resource alignment exceeds heap alignment",
+        );
+        log.check(
+            offset + info.size <= heap.desc.size,
+            "placed resource out of heap bounds",
+        );
+
+        let required = match desc.dimension {
+            ResourceDimension::Buffer => HeapFlags::ALLOW_ONLY_BUFFERS,
+            ResourceDimension::Texture2D
+                if desc.flags.intersects(
+                    ResourceFlags::ALLOW_RENDER_TARGET | ResourceFlags::ALLOW_DEPTH_STENCIL,
+                ) =>
+            {
+                HeapFlags::ALLOW_ONLY_RT_DS_TEXTURES
+            }
+            ResourceDimension::Texture2D => HeapFlags::ALLOW_ONLY_NON_RT_DS_TEXTURES,
+        };
+        log.check(
+            heap.desc.flags == required,
+            "resource category not allowed in heap",
+        );
+
+        let placement = Placement {
+            heap: heap.id,
+            offset,
+            size: info.size,
+        };
+        Ok(Self::new_resource(
+            id,
+            heap.desc.ty,
+            Some(placement),
+            desc,
+            initial,
+        ))
+    }
+
+    fn resource_allocation_info(&self, desc: &ResourceDesc) -> AllocationInfo {
+        allocation_info(desc)
+    }
+
+    fn create_descriptor_heap(
+        &self,
+        ty: DescriptorHeapType,
+        size: usize,
+        shader_visible: bool,
+    ) -> Result<DescriptorHeap, EngineError> {
+        self.check_removed("create_descriptor_heap")?;
+        let mut log = self.log.lock().unwrap();
+        let id = log.alloc_id();
+        log.call(
+            "create_descriptor_heap",
+            format!("{}, {:?}, {}, {}", id, ty, size, shader_visible),
+        );
+        log.check(size > 0, "empty descriptor heap");
+        if shader_visible {
+            log.check(
+                ty == DescriptorHeapType::CbvSrvUav || ty == DescriptorHeapType::Sampler,
+                "only cbv/srv/uav and sampler heaps can be shader visible",
+            );
+        }
+        if ty == DescriptorHeapType::Sampler {
+            log.check(size <= 2048, "sampler heaps are limited to 2048 descriptors");
+        }
+
+        log.heaps.insert(
+            id,
+            HeapInfo {
+                ty,
+                size,
+                shader_visible,
+            },
+        );
+        Ok(DescriptorHeap { id })
+    }
+
+    fn descriptor_increment_size(&self, _ty: DescriptorHeapType) -> u32 {
+        DESCRIPTOR_SIZE
+    }
+
+    fn descriptor_heap_start(&self, heap: &DescriptorHeap) -> (CpuDescriptor, GpuDescriptor) {
+        let log = self.log.lock().unwrap();
+        let shader_visible = log.heaps[&heap.id].shader_visible;
+        let cpu = CpuDescriptor {
+            ptr: (heap.id as usize) << 32,
+        };
+        let gpu = GpuDescriptor {
+            ptr: if shader_visible { heap.id << 32 } else { 0 },
+        };
+        (cpu, gpu)
+    }
+
+    fn create_fence(&self, initial: u64) -> Result<Fence, EngineError> {
+        self.check_removed("create_fence")?;
+        self.log
+            .lock()
+            .unwrap()
+            .call("create_fence", format!("{}", initial));
+        Ok(Fence {
+            value: Cell::new(initial),
+        })
+    }
+
+    fn create_shader(
+        &self,
+        name: &str,
+        source: &str,
+        entry_point: &str,
+        target: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<Shader, EngineError> {
+        self.check_removed("create_shader")?;
+        let mut log = self.log.lock().unwrap();
+        log.call(
+            "create_shader",
+            format!("{}, {:?}, {:?}, {:?}", name, entry_point, target, defines),
+        );
+
+        let entry_point = entry_point.trim_right_matches('\0');
+        let target = target.trim_right_matches('\0');
+        let error = |log: String| {
+            EngineError::new(
+                "create_shader",
+                format!("{} ({}, {})", name, entry_point, target),
+                ErrorKind::Compilation(log),
+            )
+        };
+        let stages = ["vs_5_1", "ps_5_1", "cs_5_1"];
+        if !stages.contains(&target) {
+            return Err(error(format!("unsupported shader target {}", target)));
+        }
+        if !source.contains(&format!("{}(", entry_point)) {
+            return Err(error(format!("entry point {} not found", entry_point)));
+        }
+
+        Ok(Shader {
+            entry_point: entry_point.into(),
+            target: target.into(),
+        })
+    }
+
+    fn create_root_signature(
+        &self,
+        desc: &RootSignatureDesc,
+    ) -> Result<RootSignature, EngineError> {
+        self.check_removed("create_root_signature")?;
+        let mut log = self.log.lock().unwrap();
+        let id = log.alloc_id();
+        log.call(
+            "create_root_signature",
+            format!("{}, {} parameters", id, desc.parameters.len()),
+        );
+
+        let error = |reason: String| {
+            EngineError::new(
+                "create_root_signature",
+                format!("{} parameters", desc.parameters.len()),
+                ErrorKind::Compilation(reason),
+            )
+        };
+        let mut size = 0;
+        let mut slots = Vec::new();
+        for parameter in desc.parameters {
+            let slot = match *parameter {
+                RootParameter::Cbv { .. } => {
+                    size += 2;
+                    RootSlot::Cbv
+                }
+                RootParameter::Constants { num_values, .. } => {
+                    size += num_values;
+                    RootSlot::Constants(num_values)
+                }
+                RootParameter::Table { ranges, .. } => {
+                    if ranges.is_empty() {
+                        return Err(error("empty descriptor table".into()));
+                    }
+                    let unbounded = ranges
+                        .iter()
+                        .position(|range| range.count == UNBOUNDED_RANGE);
+                    if let Some(i) = unbounded {
+                        if i + 1 != ranges.len() {
+                            return Err(error("unbounded range must be last in table".into()));
+                        }
+                    }
+                    size += 1;
+                    RootSlot::Table
+                }
+            };
+            slots.push(slot);
+        }
+
+        if size > 64 {
+            return Err(error(format!("root signature exceeds 64 dwords ({})", size)));
+        }
+
+        Ok(RootSignature {
+            id,
+            slots: Arc::new(slots),
+            allow_input_layout: desc.allow_input_layout,
+        })
+    }
+
+    fn create_graphics_pipeline(
+        &self,
+        desc: &GraphicsPipelineDesc<Null>,
+    ) -> Result<PipelineState, EngineError> {
+        self.check_removed("create_graphics_pipeline")?;
+        let mut log = self.log.lock().unwrap();
+        let id = log.alloc_id();
+        log.call(
+            "create_graphics_pipeline",
+            format!(
+                "{}, signature: {}, vs: {}, ps: {}, targets: {:?}, depth: {:?}",
+                id,
+                desc.signature.id,
+                desc.vs.entry_point,
+                desc.ps.entry_point,
+                desc.render_targets,
+                desc.depth_format,
+            ),
+        );
+
+        log.check(desc.vs.target.starts_with("vs_"), "invalid vertex shader");
+        log.check(desc.ps.target.starts_with("ps_"), "invalid pixel shader");
+        log.check(desc.render_targets.len() <= 8, "too many render targets");
+        log.check(
+            desc.input_layout.is_empty() || desc.signature.allow_input_layout,
+            "input layout with a root signature not allowing it",
+        );
+        for (i, element) in desc.input_layout.iter().enumerate() {
+            let format = element.format;
+            log.check(
+                format.texel_size() > 0 && !format.is_depth_compatible(),
+                "invalid input element format",
+            );
+            log.check(element.slot < 16, "input slot out of range");
+            log.check(
+                desc.input_layout[..i].iter().all(|other| {
+                    (other.semantic, other.index) != (element.semantic, element.index)
+                }),
+                "duplicate input element semantic",
+            );
+        }
+        log.check(
+            [1, 2, 4, 8].contains(&desc.samples),
+            "unsupported sample count",
+        );
+        if desc.depth_test.is_some() {
+            log.check(desc.depth_format.is_depth(), "depth test without depth format");
+        }
+
+        let mut input_strides = HashMap::new();
+        for element in desc.input_layout {
+            *input_strides.entry(element.slot).or_insert(0) += element.format.texel_size();
+        }
+
+        Ok(PipelineState {
+            id,
+            compute: false,
+            input_strides: Arc::new(input_strides),
+        })
+    }
+
+    fn create_compute_pipeline(
+        &self,
+        signature: &RootSignature,
+        shader: &Shader,
+    ) -> Result<PipelineState, EngineError> {
+        self.check_removed("create_compute_pipeline")?;
+        let mut log = self.log.lock().unwrap();
+        let id = log.alloc_id();
+        log.call(
+            "create_compute_pipeline",
+            format!("{}, signature: {}, cs: {}", id, signature.id, shader.entry_point),
+        );
+        log.check(shader.target.starts_with("cs_"), "invalid compute shader");
+
+        Ok(PipelineState {
+            id,
+            compute: true,
+            input_strides: Arc::new(HashMap::new()),
+        })
+    }
+
+    fn create_shader_resource_view(&self, resource: &Resource, desc: &SrvDesc, handle: CpuDescriptor) {
+        let mut log = self.log.lock().unwrap();
+        log.call(
+            "create_shader_resource_view",
+            format!("{}, {:?}", resource.id(), desc),
+        );
+        log.resolve_cpu(handle, DescriptorHeapType::CbvSrvUav);
+
+        let res_desc = resource.desc();
+        match *desc {
+            SrvDesc::Buffer {
+                first_element,
+                num_elements,
+                stride,
+            } => {
+                log.check(
+                    res_desc.dimension == ResourceDimension::Buffer,
+                    "buffer view of texture",
+                );
+                log.check(
+                    (first_element + num_elements as u64) * stride as u64 <= res_desc.width,
+                    "buffer view exceeds buffer size",
+                );
+            }
+            SrvDesc::Texture2D { format, mip_levels } => {
+                log.check(
+                    res_desc.dimension == ResourceDimension::Texture2D,
+                    "texture view of buffer",
+                );
+                log.check(
+                    format.block_size() == res_desc.format.block_size()
+                        && format.block_extent() == res_desc.format.block_extent(),
+                    "incompatible view format",
+                );
+                log.check(mip_levels <= res_desc.mip_levels as u32, "too many mip levels");
+                log.check(res_desc.samples == 1, "single-sampled view of multisampled texture");
+            }
+            SrvDesc::Texture2DMs { format } => {
+                log.check(
+                    res_desc.dimension == ResourceDimension::Texture2D,
+                    "texture view of buffer",
+                );
+                log.check(
+                    format.texel_size() == res_desc.format.texel_size(),
+                    "incompatible view format",
+                );
+                log.check(res_desc.samples > 1, "multisampled view of single-sampled texture");
+            }
+            SrvDesc::TextureCube { format, mip_levels } => {
+                log.check(
+                    res_desc.dimension == ResourceDimension::Texture2D,
+                    "texture view of buffer",
+                );
+                log.check(res_desc.depth_or_array_size == 6, "cube view requires 6 layers");
+                log.check(
+                    format.texel_size() == res_desc.format.texel_size(),
+                    "incompatible view format",
+                );
+                log.check(mip_levels <= res_desc.mip_levels as u32, "too many mip levels");
+            }
+        }
+    }
+
+    fn create_unordered_access_view(
+        &self,
+        resource: &Resource,
+        desc: &UavDesc,
+        handle: CpuDescriptor,
+    ) {
+        let mut log = self.log.lock().unwrap();
+        log.call(
+            "create_unordered_access_view",
+            format!("{}, {:?}", resource.id(), desc),
+        );
+        log.resolve_cpu(handle, DescriptorHeapType::CbvSrvUav);
+        log.check(
+            resource
+                .desc()
+                .flags
+                .contains(ResourceFlags::ALLOW_UNORDERED_ACCESS),
+            "unordered access view of resource without unordered access",
+        );
+        let res_desc = resource.desc();
+        match *desc {
+            UavDesc::Buffer {
+                first_element,
+                num_elements,
+                stride,
+            } => {
+                log.check(
+                    res_desc.dimension == ResourceDimension::Buffer,
+                    "buffer view of texture",
+                );
+                log.check(
+                    (first_element + num_elements as u64) * stride as u64 <= res_desc.width,
+                    "buffer view exceeds buffer size",
+                );
+            }
+            UavDesc::Texture2D { format } => log.check(
+                format.texel_size() == res_desc.format.texel_size(),
+                "incompatible view format",
+            ),
+        }
+    }
+
+    fn create_render_target_view(&self, resource: &Resource, desc: &RtvDesc, handle: CpuDescriptor) {
+        let mut log = self.log.lock().unwrap();
+        log.call(
+            "create_render_target_view",
+            format!("{}, {:?}", resource.id(), desc),
+        );
+        log.resolve_cpu(handle, DescriptorHeapType::Rtv);
+        log.check(
+            resource
+                .desc()
+                .flags
+                .contains(ResourceFlags::ALLOW_RENDER_TARGET),
+            "render target view of resource without render target usage",
+        );
+        let (format, multisampled) = match *desc {
+            RtvDesc::Texture2D { format } => (format, false),
+            RtvDesc::Texture2DMs { format } => (format, true),
+        };
+        log.check(
+            format.texel_size() == resource.desc().format.texel_size(),
+            "incompatible view format",
+        );
+        log.check(
+            (resource.desc().samples > 1) == multisampled,
+            "view and resource sample count mismatch",
+        );
+    }
+
+    fn create_depth_stencil_view(&self, resource: &Resource, desc: &DsvDesc, handle: CpuDescriptor) {
+        let mut log = self.log.lock().unwrap();
+        log.call(
+            "create_depth_stencil_view",
+            format!("{}, {:?}", resource.id(), desc),
+        );
+        log.resolve_cpu(handle, DescriptorHeapType::Dsv);
+        log.check(
+            resource
+                .desc()
+                .flags
+                .contains(ResourceFlags::ALLOW_DEPTH_STENCIL),
+            "depth stencil view of resource without depth stencil usage",
+        );
+        let (format, multisampled) = match *desc {
+            DsvDesc::Texture2D { format } => (format, false),
+            DsvDesc::Texture2DMs { format } => (format, true),
+        };
+        log.check(format.is_depth(), "non-depth view format");
+        log.check(
+            (resource.desc().samples > 1) == multisampled,
+            "view and resource sample count mismatch",
+        );
+    }
+
+    unsafe fn map(&self, resource: &Resource) -> Result<*mut u8, EngineError> {
+        self.check_removed("map")?;
+        let mut log = self.log.lock().unwrap();
+        log.call("map", format!("{}", resource.id()));
+        log.check(
+            resource.0.heap != HeapType::Default,
+            "mapping resource of default heap",
+        );
+        *resource.0.mapped.lock().unwrap() += 1;
+        Ok(resource.0.data.lock().unwrap().as_mut_ptr())
+    }
+
+    fn unmap(&self, resource: &Resource) {
+        let mut log = self.log.lock().unwrap();
+        log.call("unmap", format!("{}", resource.id()));
+        let mut mapped = resource.0.mapped.lock().unwrap();
+        if *mapped == 0 {
+            log.error("unmapping resource which isn't mapped".into());
+        } else {
+            *mapped -= 1;
+        }
+    }
+
+    fn gpu_virtual_address(&self, resource: &Resource) -> u64 {
+        let mut log = self.log.lock().unwrap();
+        log.check(
+            resource.desc().dimension == ResourceDimension::Buffer,
+            "gpu virtual address of texture",
+        );
+        resource.id() << 32
+    }
+
+    fn execute_command_lists(&self, lists: &[&CommandList]) {
+        let mut log = self.log.lock().unwrap();
+        log.call(
+            "execute_command_lists",
+            format!("{:?}", lists.iter().map(|list| list.id).collect::<Vec<_>>()),
+        );
+        for list in lists {
+            log.check(!list.state.borrow().open, "executing open command list");
+        }
+    }
+
+    fn signal(&self, fence: &Fence, value: u64) {
+        self.log
+            .lock()
+            .unwrap()
+            .call("signal", format!("{}", value));
+        // Work is completed instantly.
+        fence.value.set(value);
+    }
+
+    fn fence_value(&self, fence: &Fence) -> u64 {
+        fence.value.get()
+    }
+
+    fn wait_for_fence(
+        &self,
+        fence: &Fence,
+        value: u64,
+        timeout_ms: u32,
+    ) -> Result<(), EngineError> {
+        self.check_removed("wait_for_fence")?;
+        let mut log = self.log.lock().unwrap();
+        log.call("wait_for_fence", format!("{}, {}ms", value, timeout_ms));
+        let reached = fence.value.get() >= value;
+        log.check(reached, "waiting on fence value which has never been signaled");
+        if reached {
+            Ok(())
+        } else {
+            Err(EngineError::new(
+                "wait_for_fence",
+                format!("fence value {}", value),
+                ErrorKind::Timeout,
+            ))
+        }
+    }
+
+    fn check_device_removed(&self) -> Result<(), EngineError> {
+        self.log.lock().unwrap().check_removed("check_device_removed")
+    }
+
+    fn simulate_device_removed(&self) {
+        let mut log = self.log.lock().unwrap();
+        log.call("simulate_device_removed", String::new());
+        log.removed = Some(DXGI_ERROR_DEVICE_REMOVED);
+    }
+
+    fn validation_errors(&self) -> Vec<String> {
+        self.log.lock().unwrap().errors.clone()
+    }
+
+    fn create_swapchain(
+        &self,
+        _window: Option<&winit::Window>,
+        desc: &SwapchainDesc,
+    ) -> Result<Swapchain, EngineError> {
+        self.check_removed("create_swapchain")?;
+        self.log
+            .lock()
+            .unwrap()
+            .call("create_swapchain", format!("{:?}", desc));
+
+        let buffers = (0..desc.buffer_count)
+            .map(|_| {
+                let buffer_desc = ResourceDesc {
+                    flags: ResourceFlags::ALLOW_RENDER_TARGET,
+                    ..ResourceDesc::texture_2d(desc.width, desc.height, desc.format, 1)
+                };
+                self.create_committed_resource(
+                    HeapType::Default,
+                    &buffer_desc,
+                    ResourceStates::PRESENT,
+                    None,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Swapchain {
+            buffers,
+            current: Cell::new(0),
+            log: self.log.clone(),
+        })
+    }
+}
+
+impl backend::CommandList<Null> for CommandList {
+    fn reset(&self, allocator: &CommandAllocator) {
+        let mut log = self.log.lock().unwrap();
+        log.call(
+            "reset",
+            format!("list {}, allocator: {}", self.id, allocator.id),
+        );
+        let mut state = self.state.borrow_mut();
+        log.check(!state.open, "resetting open command list");
+        *state = ListState {
+            open: true,
+            ..ListState::default()
+        };
+    }
+
+    fn close(&self) {
+        let _log = self.record("close", String::new());
+        self.state.borrow_mut().open = false;
+    }
+
+    fn set_descriptor_heaps(&self, heaps: &[&DescriptorHeap]) {
+        let mut log = self.record(
+            "set_descriptor_heaps",
+            format!("{:?}", heaps.iter().map(|heap| heap.id).collect::<Vec<_>>()),
+        );
+        let mut types = Vec::new();
+        for heap in heaps {
+            let info = &log.heaps[&heap.id];
+            let (ty, shader_visible) = (info.ty, info.shader_visible);
+            log.check(shader_visible, "binding non shader visible descriptor heap");
+            log.check(!types.contains(&ty), "multiple descriptor heaps of the same type");
+            types.push(ty);
+        }
+        self.state.borrow_mut().heaps = heaps.iter().map(|heap| heap.id).collect();
+    }
+
+    fn resource_barrier(&self, barriers: &[Barrier<Null>]) {
+        let mut log = self.record("resource_barrier", format!("{} barriers", barriers.len()));
+        for barrier in barriers {
+            match *barrier {
+                Barrier::Transition {
+                    resource,
+                    subresource,
+                    before,
+                    after,
+                } => {
+                    // States are only tracked per resource.
+                    log.check(
+                        subresource == ALL_SUBRESOURCES,
+                        "transition of a single subresource",
+                    );
+                    let mut state = resource.0.state.lock().unwrap();
+                    if *state != before {
+                        log.error(format!(
+                            "resource {} transition from {:?} but current state is {:?}",
+                            resource.id(),
+                            before,
+                            *state
+                        ));
+                    }
+                    log.check(before != after, "transition barrier without state change");
+                    *state = after;
+                }
+                Barrier::Uav { resource } => {
+                    log.check(
+                        resource.state().contains(ResourceStates::UNORDERED_ACCESS),
+                        "uav barrier on resource not in unordered access state",
+                    );
+                }
+                Barrier::Aliasing { before, after } => {
+                    let before = before.map(|resource| resource.0.placement);
+                    let after = after.map(|resource| resource.0.placement);
+                    log.check(
+                        before.map_or(true, |p| p.is_some()) && after.map_or(true, |p| p.is_some()),
+                        "aliasing barrier on committed resource",
+                    );
+                    if let (Some(Some(before)), Some(Some(after))) = (before, after) {
+                        log.check(
+                            before.heap == after.heap
+                                && before.offset < after.offset + after.size
+                                && after.offset < before.offset + before.size,
+                            "aliasing barrier between non-overlapping resources",
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn clear_render_target_view(&self, rtv: CpuDescriptor, color: [f32; 4]) {
+        let mut log = self.record("clear_render_target_view", format!("{:?}", color));
+        log.resolve_cpu(rtv, DescriptorHeapType::Rtv);
+    }
+
+    fn clear_depth_stencil_view(&self, dsv: CpuDescriptor, depth: f32) {
+        let mut log = self.record("clear_depth_stencil_view", format!("{}", depth));
+        log.resolve_cpu(dsv, DescriptorHeapType::Dsv);
+    }
+
+    fn set_pipeline_state(&self, pipeline: &PipelineState) {
+        let _log = self.record("set_pipeline_state", format!("{}", pipeline.id));
+        self.state.borrow_mut().pipeline = Some(pipeline.clone());
+    }
+
+    fn set_graphics_root_signature(&self, signature: &RootSignature) {
+        let _log = self.record("set_graphics_root_signature", format!("{}", signature.id));
+        self.state.borrow_mut().graphics_signature = Some(signature.clone());
+    }
+
+    fn set_compute_root_signature(&self, signature: &RootSignature) {
+        let _log = self.record("set_compute_root_signature", format!("{}", signature.id));
+        self.state.borrow_mut().compute_signature = Some(signature.clone());
+    }
+
+    fn set_graphics_root_constant_buffer_view(&self, slot: u32, address: u64) {
+        let mut log = self.record(
+            "set_graphics_root_constant_buffer_view",
+            format!("{}, {:x}", slot, address),
+        );
+        Self::check_root_slot(
+            &mut log,
+            &self.state.borrow().graphics_signature,
+            slot,
+            RootSlot::Cbv,
+            0,
+        );
+        log.check(address % 256 == 0, "constant buffer address must be 256 byte aligned");
+    }
+
+    fn set_graphics_root_descriptor_table(&self, slot: u32, table: GpuDescriptor) {
+        let mut log = self.record(
+            "set_graphics_root_descriptor_table",
+            format!("{}, {:x}", slot, table.ptr),
+        );
+        Self::check_root_slot(
+            &mut log,
+            &self.state.borrow().graphics_signature,
+            slot,
+            RootSlot::Table,
+            0,
+        );
+        self.check_table(&mut log, table);
+    }
+
+    fn set_graphics_root_constants(&self, slot: u32, constants: &[u32], offset: u32) {
+        let mut log = self.record(
+            "set_graphics_root_constants",
+            format!("{}, {:?}, {}", slot, constants, offset),
+        );
+        Self::check_root_slot(
+            &mut log,
+            &self.state.borrow().graphics_signature,
+            slot,
+            RootSlot::Constants(0),
+            offset + constants.len() as u32,
+        );
+    }
+
+    fn set_compute_root_constant_buffer_view(&self, slot: u32, address: u64) {
+        let mut log = self.record(
+            "set_compute_root_constant_buffer_view",
+            format!("{}, {:x}", slot, address),
+        );
+        Self::check_root_slot(
+            &mut log,
+            &self.state.borrow().compute_signature,
+            slot,
+            RootSlot::Cbv,
+            0,
+        );
+        log.check(address % 256 == 0, "constant buffer address must be 256 byte aligned");
+    }
+
+    fn set_compute_root_descriptor_table(&self, slot: u32, table: GpuDescriptor) {
+        let mut log = self.record(
+            "set_compute_root_descriptor_table",
+            format!("{}, {:x}", slot, table.ptr),
+        );
+        Self::check_root_slot(
+            &mut log,
+            &self.state.borrow().compute_signature,
+            slot,
+            RootSlot::Table,
+            0,
+        );
+        self.check_table(&mut log, table);
+    }
+
+    fn set_compute_root_constants(&self, slot: u32, constants: &[u32], offset: u32) {
+        let mut log = self.record(
+            "set_compute_root_constants",
+            format!("{}, {:?}, {}", slot, constants, offset),
+        );
+        Self::check_root_slot(
+            &mut log,
+            &self.state.borrow().compute_signature,
+            slot,
+            RootSlot::Constants(0),
+            offset + constants.len() as u32,
+        );
+    }
+
+    fn set_primitive_topology(&self, topology: PrimitiveTopology) {
+        let _log = self.record("set_primitive_topology", format!("{:?}", topology));
+    }
+
+    fn set_viewport(&self, viewport: &Viewport) {
+        let mut log = self.record("set_viewport", format!("{:?}", viewport));
+        log.check(
+            viewport.width > 0.0 && viewport.height > 0.0,
+            "empty viewport",
+        );
+        log.check(
+            viewport.min_depth <= viewport.max_depth,
+            "invalid viewport depth range",
+        );
+    }
+
+    fn set_scissor(&self, rect: &Rect) {
+        let mut log = self.record("set_scissor", format!("{:?}", rect));
+        log.check(
+            rect.left <= rect.right && rect.top <= rect.bottom,
+            "invalid scissor rect",
+        );
+    }
+
+    fn set_render_targets(&self, rtvs: &[CpuDescriptor], dsv: Option<CpuDescriptor>) {
+        let mut log = self.record(
+            "set_render_targets",
+            format!("{} targets, depth: {}", rtvs.len(), dsv.is_some()),
+        );
+        log.check(rtvs.len() <= 8, "too many render targets");
+        for rtv in rtvs {
+            log.resolve_cpu(*rtv, DescriptorHeapType::Rtv);
+        }
+        if let Some(dsv) = dsv {
+            log.resolve_cpu(dsv, DescriptorHeapType::Dsv);
+        }
+        self.state.borrow_mut().render_targets = rtvs.len();
+    }
+
+    fn set_index_buffer(&self, view: &IndexBufferView) {
+        let mut log = self.record("set_index_buffer", format!("{:?}", view));
+        log.check(
+            view.format == Format::R32Uint,
+            "unsupported index buffer format",
+        );
+        self.state.borrow_mut().index_buffer = true;
+    }
+
+    fn set_vertex_buffers(&self, start_slot: u32, views: &[VertexBufferView]) {
+        let mut log = self.record(
+            "set_vertex_buffers",
+            format!("{}, {:?}", start_slot, views),
+        );
+        let mut state = self.state.borrow_mut();
+        for (slot, view) in (start_slot..).zip(views) {
+            log.check(view.size % view.stride == 0, "vertex buffer size not a multiple of stride");
+            state.vertex_strides.insert(slot, view.stride);
+        }
+    }
+
+    fn draw_instanced(
+        &self,
+        vertex_count: u32,
+        instance_count: u32,
+        start_vertex: u32,
+        start_instance: u32,
+    ) {
+        let mut log = self.record(
+            "draw_instanced",
+            format!(
+                "{}, {}, {}, {}",
+                vertex_count, instance_count, start_vertex, start_instance
+            ),
+        );
+        self.check_draw(&mut log);
+        log.check(
+            self.state.borrow().render_targets > 0,
+            "draw without render targets",
+        );
+    }
+
+    fn draw_indexed_instanced(
+        &self,
+        index_count: u32,
+        instance_count: u32,
+        start_index: u32,
+        base_vertex: i32,
+        start_instance: u32,
+    ) {
+        let mut log = self.record(
+            "draw_indexed_instanced",
+            format!(
+                "{}, {}, {}, {}, {}",
+                index_count, instance_count, start_index, base_vertex, start_instance
+            ),
+        );
+        self.check_draw(&mut log);
+        log.check(
+            self.state.borrow().index_buffer,
+            "indexed draw without index buffer",
+        );
+    }
+
+    fn dispatch(&self, x: u32, y: u32, z: u32) {
+        let mut log = self.record("dispatch", format!("{}, {}, {}", x, y, z));
+        let state = self.state.borrow();
+        log.check(
+            state.pipeline.as_ref().map_or(false, |pso| pso.compute),
+            "dispatch without compute pipeline",
+        );
+        log.check(state.compute_signature.is_some(), "dispatch without root signature");
+        log.check(x > 0 && y > 0 && z > 0, "empty dispatch");
+    }
+
+    fn copy_resource(&self, dst: &Resource, src: &Resource) {
+        let mut log = self.record("copy_resource", format!("{} <- {}", dst.id(), src.id()));
+        // Flags may differ, e.g. for copies into readback buffers.
+        log.check(
+            ResourceDesc {
+                flags: src.desc().flags,
+                ..*dst.desc()
+            } == *src.desc(),
+            "copy between incompatible resources",
+        );
+        log.check(
+            dst.state() == ResourceStates::COPY_DEST,
+            "copy destination not in copy destination state",
+        );
+        log.check(
+            src.0.heap == HeapType::Upload || src.state().contains(ResourceStates::COPY_SOURCE),
+            "copy source not in copy source state",
+        );
+
+        if dst.id() != src.id() {
+            let src_data = src.0.data.lock().unwrap();
+            let mut dst_data = dst.0.data.lock().unwrap();
+            if dst_data.len() == src_data.len() {
+                dst_data.copy_from_slice(&src_data);
+            }
+        }
+    }
+
+    fn copy_buffer_region(
+        &self,
+        dst: &Resource,
+        dst_offset: u64,
+        src: &Resource,
+        src_offset: u64,
+        size: u64,
+    ) {
+        let mut log = self.record(
+            "copy_buffer_region",
+            format!(
+                "{}+{} <- {}+{}, {}",
+                dst.id(),
+                dst_offset,
+                src.id(),
+                src_offset,
+                size
+            ),
+        );
+        log.check(
+            dst.desc().dimension == ResourceDimension::Buffer
+                && src.desc().dimension == ResourceDimension::Buffer,
+            "buffer region copy between non buffers",
+        );
+        log.check(
+            dst.state() == ResourceStates::COPY_DEST,
+            "copy destination not in copy destination state",
+        );
+        log.check(
+            src.0.heap == HeapType::Upload || src.state().contains(ResourceStates::COPY_SOURCE),
+            "copy source not in copy source state",
+        );
+        log.check(
+            dst_offset + size <= dst.desc().width && src_offset + size <= src.desc().width,
+            "buffer region copy out of bounds",
+        );
+        log.check(
+            dst.id() != src.id(),
+            "buffer region copy within the same resource",
+        );
+
+        if dst.id() != src.id()
+            && dst_offset + size <= dst.desc().width
+            && src_offset + size <= src.desc().width
+        {
+            let src_data = src.0.data.lock().unwrap();
+            let mut dst_data = dst.0.data.lock().unwrap();
+            let (src_offset, dst_offset, size) =
+                (src_offset as usize, dst_offset as usize, size as usize);
+            dst_data[dst_offset..dst_offset + size]
+                .copy_from_slice(&src_data[src_offset..src_offset + size]);
+        }
+    }
+
+    fn copy_texture_region(
+        &self,
+        dst: &Resource,
+        dst_subresource: u32,
+        dst_origin: [u32; 3],
+        src: &Resource,
+        src_footprint: &PlacedFootprint,
+    ) {
+        let mut log = self.record(
+            "copy_texture_region",
+            format!(
+                "{}[{}] at {:?} <- {}, {:?}",
+                dst.id(),
+                dst_subresource,
+                dst_origin,
+                src.id(),
+                src_footprint
+            ),
+        );
+        let desc = dst.desc();
+        let mip = dst_subresource % desc.mip_levels as u32;
+        log.check(
+            dst_subresource < desc.mip_levels as u32 * desc.depth_or_array_size as u32,
+            "copy into non existent subresource",
+        );
+        // Mip levels of compressed textures smaller than a block are copied as whole blocks.
+        let extent = desc.format.block_extent();
+        let align = |size: u32| (size + extent - 1) / extent * extent;
+        log.check(
+            dst_origin[0] + src_footprint.width <= align((desc.width as u32 >> mip).max(1))
+                && dst_origin[1] + src_footprint.height <= align((desc.height >> mip).max(1))
+                && dst_origin[2] + src_footprint.depth <= 1,
+            "texture copy exceeds destination subresource",
+        );
+        log.check(
+            dst_origin[0] % extent == 0
+                && dst_origin[1] % extent == 0
+                && src_footprint.width % extent == 0
+                && src_footprint.height % extent == 0,
+            "texture copy not aligned to compression blocks",
+        );
+        log.check(
+            src_footprint.format == desc.format,
+            "footprint format differs from destination format",
+        );
+        log.check(
+            dst.desc().dimension == ResourceDimension::Texture2D,
+            "texture copy into buffer",
+        );
+        log.check(
+            src.desc().dimension == ResourceDimension::Buffer,
+            "texture copy from non buffer",
+        );
+        log.check(
+            dst.state() == ResourceStates::COPY_DEST,
+            "copy destination not in copy destination state",
+        );
+        log.check(
+            src_footprint.offset % 512 == 0,
+            "footprint offset must be 512 byte aligned",
+        );
+        log.check(
+            src_footprint.row_pitch % 256 == 0,
+            "footprint row pitch must be 256 byte aligned",
+        );
+        log.check(
+            src_footprint.row_pitch >= src_footprint.format.row_size(src_footprint.width),
+            "footprint row pitch smaller than row size",
+        );
+        log.check(
+            src_footprint.offset
+                + src_footprint.row_pitch as u64
+                    * src_footprint.format.num_rows(src_footprint.height) as u64
+                    * src_footprint.depth as u64
+                <= src.desc().width,
+            "footprint exceeds source buffer",
+        );
+    }
+}
+
+impl backend::Swapchain<Null> for Swapchain {
+    fn num_buffers(&self) -> usize {
+        self.buffers.len()
+    }
+
+    fn buffer(&self, index: usize) -> Resource {
+        self.buffers[index].clone()
+    }
+
+    fn current_index(&self) -> usize {
+        self.current.get()
+    }
+
+    fn present(&self) -> Result<(), EngineError> {
+        let mut log = self.log.lock().unwrap();
+        log.check_removed("present")?;
+        let current = self.current.get();
+        log.call("present", format!("{}", current));
+        log.check(
+            self.buffers[current].state() == ResourceStates::PRESENT,
+            "presenting backbuffer not in present state",
+        );
+        self.current.set((current + 1) % self.buffers.len());
+        Ok(())
+    }
+
+    fn resize(&mut self, device: &Device, width: u32, height: u32) -> Result<(), EngineError> {
+        {
+            let mut log = self.log.lock().unwrap();
+            log.check_removed("resize")?;
+            log.call("resize", format!("{}x{}", width, height));
+            log.check(width > 0 && height > 0, "resizing swapchain to zero size");
+            let referenced = self
+                .buffers
+                .iter()
+                .any(|buffer| Arc::strong_count(&buffer.0) > 1);
+            log.check(!referenced, "resizing swapchain with referenced buffers");
+        }
+
+        let format = self.buffers[0].desc().format;
+        let buffer_desc = ResourceDesc {
+            flags: ResourceFlags::ALLOW_RENDER_TARGET,
+            ..ResourceDesc::texture_2d(width, height, format, 1)
+        };
+        let count = self.buffers.len();
+        self.buffers = (0..count)
+            .map(|_| {
+                backend::Device::create_committed_resource(
+                    device,
+                    HeapType::Default,
+                    &buffer_desc,
+                    ResourceStates::PRESENT,
+                    None,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+        self.current.set(0);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    // Shadowed by the null types.
+    use backend::{CommandList as CommandListTrait, Device as DeviceTrait};
+
+    const SOURCE: &str = "float4 vs_main() {} float4 ps_main() {}";
+
+    fn target_desc() -> ResourceDesc {
+        ResourceDesc {
+            flags: ResourceFlags::ALLOW_RENDER_TARGET,
+            ..ResourceDesc::texture_2d(4, 4, Format::R8G8B8A8Unorm, 1)
+        }
+    }
+
+    fn open_list(device: &Device) -> CommandList {
+        let allocator = device.create_command_allocator().unwrap();
+        let cmd_list = device.create_command_list(&allocator).unwrap();
+        cmd_list.reset(&allocator);
+        cmd_list
+    }
+
+    fn pipeline(
+        device: &Device,
+        allow_input_layout: bool,
+        input_layout: &[InputElement],
+    ) -> (RootSignature, PipelineState) {
+        let signature = device
+            .create_root_signature(&RootSignatureDesc {
+                parameters: &[],
+                static_samplers: &[],
+                allow_input_layout,
+            })
+            .unwrap();
+        let vs = device
+            .create_shader("test", SOURCE, "vs_main", "vs_5_1", &[])
+            .unwrap();
+        let ps = device
+            .create_shader("test", SOURCE, "ps_main", "ps_5_1", &[])
+            .unwrap();
+        let pipeline = device
+            .create_graphics_pipeline(&GraphicsPipelineDesc {
+                signature: &signature,
+                vs: &vs,
+                ps: &ps,
+                input_layout,
+                render_targets: &[Format::R8G8B8A8Unorm],
+                depth_format: Format::Unknown,
+                depth_test: None,
+                samples: 1,
+            })
+            .unwrap();
+        (signature, pipeline)
+    }
+
+    fn position(semantic: &'static str, format: Format) -> InputElement {
+        InputElement {
+            semantic,
+            index: 0,
+            format,
+            slot: 0,
+        }
+    }
+
+    /// Record a triangle into a render target, optionally binding the root signature.
+    fn draw(device: &Device, bind_signature: bool) {
+        let heap = device
+            .create_descriptor_heap(DescriptorHeapType::Rtv, 1, false)
+            .unwrap();
+        let rtv = device.descriptor_heap_start(&heap).0;
+        let target = device
+            .create_committed_resource(
+                HeapType::Default,
+                &target_desc(),
+                ResourceStates::RENDER_TARGET,
+                None,
+            )
+            .unwrap();
+        device.create_render_target_view(
+            &target,
+            &RtvDesc::Texture2D {
+                format: Format::R8G8B8A8Unorm,
+            },
+            rtv,
+        );
+
+        let (signature, pipeline) = pipeline(device, false, &[]);
+        let cmd_list = open_list(device);
+        cmd_list.set_pipeline_state(&pipeline);
+        if bind_signature {
+            cmd_list.set_graphics_root_signature(&signature);
+        }
+        cmd_list.set_render_targets(&[rtv], None);
+        cmd_list.draw_instanced(3, 1, 0, 0);
+        cmd_list.close();
+    }
+
+    #[test]
+    fn valid_calls() {
+        let device = Device::new();
+        draw(&device, true);
+
+        let buffer = device
+            .create_committed_resource(
+                HeapType::Upload,
+                &ResourceDesc::buffer(256),
+                ResourceStates::GENERIC_READ,
+                None,
+            )
+            .unwrap();
+        unsafe { device.map(&buffer).unwrap() };
+        device.unmap(&buffer);
+
+        let texture = device
+            .create_committed_resource(
+                HeapType::Default,
+                &target_desc(),
+                ResourceStates::RENDER_TARGET,
+                None,
+            )
+            .unwrap();
+        let cmd_list = open_list(&device);
+        cmd_list.resource_barrier(&[Barrier::Transition {
+            resource: &texture,
+            subresource: ALL_SUBRESOURCES,
+            before: ResourceStates::RENDER_TARGET,
+            after: ResourceStates::PIXEL_SHADER_RESOURCE,
+        }]);
+        cmd_list.close();
+
+        let (_, _) = pipeline(
+            &device,
+            true,
+            &[
+                position("Attr", Format::R32G32B32Float),
+                position("Color", Format::R8G8B8A8Unorm),
+            ],
+        );
+
+        assert_eq!(device.validation_errors(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn barrier_state_mismatch() {
+        let device = Device::new();
+        let texture = device
+            .create_committed_resource(
+                HeapType::Default,
+                &target_desc(),
+                ResourceStates::COPY_DEST,
+                None,
+            )
+            .unwrap();
+        let cmd_list = open_list(&device);
+        cmd_list.resource_barrier(&[Barrier::Transition {
+            resource: &texture,
+            subresource: ALL_SUBRESOURCES,
+            before: ResourceStates::RENDER_TARGET,
+            after: ResourceStates::PIXEL_SHADER_RESOURCE,
+        }]);
+
+        assert_eq!(
+            device.validation_errors(),
+            vec![format!(
+                "resource_barrier: resource {} transition from ResourceStates(4) but current \
+                 state is ResourceStates(1024)",
+                texture.id()
+            )]
+        );
+    }
+
+    #[test]
+    fn descriptor_out_of_heap() {
+        let device = Device::new();
+        let heap = device
+            .create_descriptor_heap(DescriptorHeapType::CbvSrvUav, 4, false)
+            .unwrap();
+        let start = device.descriptor_heap_start(&heap).0;
+        let buffer = device
+            .create_committed_resource(
+                HeapType::Default,
+                &ResourceDesc::buffer(64),
+                ResourceStates::COMMON,
+                None,
+            )
+            .unwrap();
+        let desc = SrvDesc::Buffer {
+            first_element: 0,
+            num_elements: 16,
+            stride: 4,
+        };
+        let increment = device.descriptor_increment_size(DescriptorHeapType::CbvSrvUav);
+        device.create_shader_resource_view(&buffer, &desc, start);
+        device.create_shader_resource_view(
+            &buffer,
+            &desc,
+            CpuDescriptor {
+                ptr: start.ptr + 4 * increment as usize,
+            },
+        );
+
+        assert_eq!(
+            device.validation_errors(),
+            vec!["create_shader_resource_view: descriptor slot 4 out of heap bounds (4)"]
+        );
+    }
+
+    #[test]
+    fn unmap_unmapped_resource() {
+        let device = Device::new();
+        let buffer = device
+            .create_committed_resource(
+                HeapType::Upload,
+                &ResourceDesc::buffer(256),
+                ResourceStates::GENERIC_READ,
+                None,
+            )
+            .unwrap();
+        unsafe { device.map(&buffer).unwrap() };
+        device.unmap(&buffer);
+        device.unmap(&buffer);
+
+        assert_eq!(
+            device.validation_errors(),
+            vec!["unmap: unmapping resource which isn't mapped"]
+        );
+    }
+
+    #[test]
+    fn invalid_input_layout() {
+        let device = Device::new();
+        let layout = [position("Attr", Format::R32G32B32Float)];
+        pipeline(&device, false, &layout);
+        pipeline(
+            &device,
+            true,
+            &[
+                position("Attr", Format::R32G32B32Float),
+                position("Attr", Format::D32Float),
+            ],
+        );
+
+        assert_eq!(
+            device.validation_errors(),
+            vec![
+                "create_graphics_pipeline: input layout with a root signature not allowing it",
+                "create_graphics_pipeline: invalid input element format",
+                "create_graphics_pipeline: duplicate input element semantic",
+            ]
+        );
+    }
+
+    #[test]
+    fn draw_without_root_signature() {
+        let device = Device::new();
+        draw(&device, false);
+
+        assert_eq!(
+            device.validation_errors(),
+            vec!["draw_instanced: draw without root signature"]
+        );
+    }
+
+    #[test]
+    fn input_layout_vertex_buffers() {
+        let device = Device::new();
+        let heap = device
+            .create_descriptor_heap(DescriptorHeapType::Rtv, 1, false)
+            .unwrap();
+        let rtv = device.descriptor_heap_start(&heap).0;
+        let target = device
+            .create_committed_resource(
+                HeapType::Default,
+                &target_desc(),
+                ResourceStates::RENDER_TARGET,
+                None,
+            )
+            .unwrap();
+        device.create_render_target_view(
+            &target,
+            &RtvDesc::Texture2D {
+                format: Format::R8G8B8A8Unorm,
+            },
+            rtv,
+        );
+
+        let layout = [
+            position("Position", Format::R32G32B32Float),
+            InputElement {
+                slot: 1,
+                ..position("Normal", Format::R32G32B32Float)
+            },
+            InputElement {
+                slot: 1,
+                ..position("Uv", Format::R32G32Float)
+            },
+        ];
+        let (signature, pipeline) = pipeline(&device, true, &layout);
+        let view = |stride: u32| VertexBufferView {
+            location: 0,
+            size: 4 * stride,
+            stride,
+        };
+
+        let cmd_list = open_list(&device);
+        cmd_list.set_pipeline_state(&pipeline);
+        cmd_list.set_graphics_root_signature(&signature);
+        cmd_list.set_render_targets(&[rtv], None);
+        cmd_list.set_vertex_buffers(0, &[view(12)]);
+        cmd_list.draw_instanced(3, 1, 0, 0);
+        // Deinterleaved streams with the normal and texture coordinates in slot 1.
+        cmd_list.set_vertex_buffers(1, &[view(16)]);
+        cmd_list.draw_instanced(3, 1, 0, 0);
+        cmd_list.set_vertex_buffers(0, &[view(12), view(20)]);
+        cmd_list.draw_instanced(3, 1, 0, 0);
+        cmd_list.close();
+
+        assert_eq!(
+            device.validation_errors(),
+            vec![
+                "draw_instanced: input slot 1 without vertex buffer",
+                "draw_instanced: vertex buffer stride smaller than input layout",
+            ]
+        );
+    }
+}
@@ -0,0 +1,536 @@
+//! Backend independent resource, view and pipeline descriptions.
+//!
+//! Closely mirrors the D3D12 structures the engine used directly before,
+//! reduced to the subset actually required by the passes.
+
+use std::ops::{BitAnd, BitOr, BitOrAssign};
+
+use backend::Backend;
+
+/// Set operations shared by all flag types.
+pub trait Flags: Copy + PartialEq + BitAnd<Output = Self> {
+    /// No flag set.
+    const EMPTY: Self;
+
+    fn contains(&self, other: Self) -> bool {
+        *self & other == other
+    }
+
+    fn intersects(&self, other: Self) -> bool {
+        *self & other != Self::EMPTY
+    }
+}
+
+macro_rules! flags {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident: $ty:ty {
+            $( $(#[$flag_meta:meta])* const $flag:ident = $value:expr; )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub struct $name(pub $ty);
+
+        impl $name {
+            $( $(#[$flag_meta])* pub const $flag: $name = $name($value); )*
+        }
+
+        impl Flags for $name {
+            const EMPTY: $name = $name(0);
+        }
+
+        impl BitOr for $name {
+            type Output = $name;
+            fn bitor(self, other: $name) -> $name {
+                $name(self.0 | other.0)
+            }
+        }
+
+        impl BitOrAssign for $name {
+            fn bitor_assign(&mut self, other: $name) {
+                self.0 |= other.0;
+            }
+        }
+
+        impl BitAnd for $name {
+            type Output = $name;
+            fn bitand(self, other: $name) -> $name {
+                $name(self.0 & other.0)
+            }
+        }
+    };
+}
+
+/// Barrier subresource index covering the whole resource.
+pub const ALL_SUBRESOURCES: u32 = 0xFFFF_FFFF;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    Unknown,
+    R32Typeless,
+    R32Uint,
+    R32Float,
+    R32G32Float,
+    R32G32B32Float,
+    R32G32B32A32Float,
+    R16G16B16A16Typeless,
+    R16G16B16A16Uint,
+    R16G16B16A16Float,
+    R8G8B8A8Unorm,
+    R8G8B8A8UnormSrgb,
+    D32Float,
+    Bc1Unorm,
+    Bc1UnormSrgb,
+    Bc3Unorm,
+    Bc3UnormSrgb,
+    Bc5Unorm,
+    Bc7Unorm,
+    Bc7UnormSrgb,
+}
+
+impl Format {
+    /// Size of a single texel in bytes, 0 for block compressed formats.
+    pub fn texel_size(&self) -> u32 {
+        match *self {
+            Format::Unknown => 0,
+            Format::Bc1Unorm
+            | Format::Bc1UnormSrgb
+            | Format::Bc3Unorm
+            | Format::Bc3UnormSrgb
+            | Format::Bc5Unorm
+            | Format::Bc7Unorm
+            | Format::Bc7UnormSrgb => 0,
+            Format::R32Typeless | Format::R32Uint | Format::R32Float | Format::D32Float => 4,
+            Format::R8G8B8A8Unorm | Format::R8G8B8A8UnormSrgb => 4,
+            Format::R32G32Float => 8,
+            Format::R32G32B32Float => 12,
+            Format::R32G32B32A32Float => 16,
+            Format::R16G16B16A16Typeless
+            | Format::R16G16B16A16Uint
+            | Format::R16G16B16A16Float => 8,
+        }
+    }
+
+    pub fn is_depth(&self) -> bool {
+        match *self {
+            Format::D32Float => true,
+            _ => false,
+        }
+    }
+
+    /// Depth formats and typeless formats with a depth view format.
+    pub fn is_depth_compatible(&self) -> bool {
+        match *self {
+            Format::R32Typeless => true,
+            _ => self.is_depth(),
+        }
+    }
+
+    pub fn is_srgb(&self) -> bool {
+        match *self {
+            Format::R8G8B8A8UnormSrgb
+            | Format::Bc1UnormSrgb
+            | Format::Bc3UnormSrgb
+            | Format::Bc7UnormSrgb => true,
+            _ => false,
+        }
+    }
+
+    /// Width and height of a compression block in texels, 1 for uncompressed formats.
+    pub fn block_extent(&self) -> u32 {
+        match *self {
+            Format::Bc1Unorm
+            | Format::Bc1UnormSrgb
+            | Format::Bc3Unorm
+            | Format::Bc3UnormSrgb
+            | Format::Bc5Unorm
+            | Format::Bc7Unorm
+            | Format::Bc7UnormSrgb => 4,
+            _ => 1,
+        }
+    }
+
+    /// Size of a compression block in bytes, the texel size for uncompressed formats.
+    pub fn block_size(&self) -> u32 {
+        match *self {
+            Format::Bc1Unorm | Format::Bc1UnormSrgb => 8,
+            Format::Bc3Unorm
+            | Format::Bc3UnormSrgb
+            | Format::Bc5Unorm
+            | Format::Bc7Unorm
+            | Format::Bc7UnormSrgb => 16,
+            _ => self.texel_size(),
+        }
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.block_extent() > 1
+    }
+
+    /// Size in bytes of a row of `width` texels, a row of blocks for compressed formats.
+    pub fn row_size(&self, width: u32) -> u32 {
+        let extent = self.block_extent();
+        (width + extent - 1) / extent * self.block_size()
+    }
+
+    /// Number of rows of `height` texels, rows of blocks for compressed formats.
+    pub fn num_rows(&self, height: u32) -> u32 {
+        let extent = self.block_extent();
+        (height + extent - 1) / extent
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HeapType {
+    Default,
+    Upload,
+    Readback,
+}
+
+flags! {
+    pub struct ResourceStates: u32 {
+        const COMMON = 0;
+        const VERTEX_AND_CONSTANT_BUFFER = 0x1;
+        const INDEX_BUFFER = 0x2;
+        const RENDER_TARGET = 0x4;
+        const UNORDERED_ACCESS = 0x8;
+        const DEPTH_WRITE = 0x10;
+        const DEPTH_READ = 0x20;
+        const NON_PIXEL_SHADER_RESOURCE = 0x40;
+        const PIXEL_SHADER_RESOURCE = 0x80;
+        const COPY_DEST = 0x400;
+        const COPY_SOURCE = 0x800;
+        const RESOLVE_SOURCE = 0x2000;
+        const GENERIC_READ = 0x1 | 0x2 | 0x40 | 0x80 | 0x200 | 0x800;
+        const PRESENT = 0;
+    }
+}
+
+flags! {
+    pub struct ResourceFlags: u32 {
+        const NONE = 0;
+        const ALLOW_RENDER_TARGET = 0x1;
+        const ALLOW_DEPTH_STENCIL = 0x2;
+        const ALLOW_UNORDERED_ACCESS = 0x4;
+    }
+}
+
+flags! {
+    /// Resource categories a heap can contain.
+    ///
+    /// Resource heap tier 1 requires separate heaps for buffers, textures and
+    /// render/depth targets.
+    pub struct HeapFlags: u32 {
+        const NONE = 0;
+        const ALLOW_ONLY_BUFFERS = 0x1;
+        const ALLOW_ONLY_NON_RT_DS_TEXTURES = 0x2;
+        const ALLOW_ONLY_RT_DS_TEXTURES = 0x4;
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HeapDesc {
+    pub size: u64,
+    pub ty: HeapType,
+    pub alignment: u64,
+    pub flags: HeapFlags,
+}
+
+/// Size and alignment of a resource placed in a heap.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AllocationInfo {
+    pub size: u64,
+    pub alignment: u64,
+}
+
+/// Default placement alignment of buffers and textures.
+pub const DEFAULT_RESOURCE_PLACEMENT_ALIGNMENT: u64 = 64 * 1024;
+/// Placement alignment of multisampled textures.
+pub const DEFAULT_MSAA_RESOURCE_PLACEMENT_ALIGNMENT: u64 = 4 * 1024 * 1024;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ResourceDimension {
+    Buffer,
+    Texture2D,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ResourceDesc {
+    pub dimension: ResourceDimension,
+    pub width: u64,
+    pub height: u32,
+    pub depth_or_array_size: u16,
+    pub mip_levels: u16,
+    pub format: Format,
+    pub samples: u32,
+    pub flags: ResourceFlags,
+}
+
+impl ResourceDesc {
+    pub fn buffer(size: u64) -> Self {
+        ResourceDesc {
+            dimension: ResourceDimension::Buffer,
+            width: size,
+            height: 1,
+            depth_or_array_size: 1,
+            mip_levels: 1,
+            format: Format::Unknown,
+            samples: 1,
+            flags: ResourceFlags::NONE,
+        }
+    }
+
+    pub fn texture_2d(width: u32, height: u32, format: Format, samples: u32) -> Self {
+        ResourceDesc {
+            dimension: ResourceDimension::Texture2D,
+            width: width as _,
+            height,
+            depth_or_array_size: 1,
+            mip_levels: 1,
+            format,
+            samples,
+            flags: ResourceFlags::NONE,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ClearValue {
+    Color(Format, [f32; 4]),
+    DepthStencil(Format, f32, u8),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DescriptorHeapType {
+    CbvSrvUav,
+    Sampler,
+    Rtv,
+    Dsv,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CpuDescriptor {
+    pub ptr: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GpuDescriptor {
+    pub ptr: u64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SrvDesc {
+    Buffer {
+        first_element: u64,
+        num_elements: u32,
+        stride: u32,
+    },
+    Texture2D {
+        format: Format,
+        mip_levels: u32,
+    },
+    Texture2DMs {
+        format: Format,
+    },
+    /// Cube map of a texture array with 6 layers, in `+x, -x, +y, -y, +z, -z` order.
+    TextureCube {
+        format: Format,
+        mip_levels: u32,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum UavDesc {
+    Buffer {
+        first_element: u64,
+        num_elements: u32,
+        stride: u32,
+    },
+    Texture2D {
+        format: Format,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RtvDesc {
+    Texture2D { format: Format },
+    Texture2DMs { format: Format },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DsvDesc {
+    Texture2D { format: Format },
+    Texture2DMs { format: Format },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ShaderVisibility {
+    All,
+    Vertex,
+    Pixel,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RangeType {
+    Srv,
+    Uav,
+}
+
+/// Number of descriptors for an unbounded table range.
+pub const UNBOUNDED_RANGE: u32 = !0;
+
+/// Descriptor range inside a root descriptor table.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TableRange {
+    pub ty: RangeType,
+    pub count: u32,
+    pub base_register: u32,
+    pub space: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RootParameter<'a> {
+    Cbv {
+        register: u32,
+        space: u32,
+        visibility: ShaderVisibility,
+    },
+    Constants {
+        register: u32,
+        space: u32,
+        num_values: u32,
+        visibility: ShaderVisibility,
+    },
+    Table {
+        ranges: &'a [TableRange],
+        visibility: ShaderVisibility,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Filter {
+    Linear,
+    /// Bilinear filtered comparison against a reference value (`SampleCmp`).
+    LinearCompare(CompareFunc),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AddressMode {
+    Clamp,
+    Wrap,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StaticSampler {
+    pub filter: Filter,
+    pub address: AddressMode,
+    pub register: u32,
+    pub space: u32,
+    pub visibility: ShaderVisibility,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RootSignatureDesc<'a> {
+    pub parameters: &'a [RootParameter<'a>],
+    pub static_samplers: &'a [StaticSampler],
+    pub allow_input_layout: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InputElement {
+    pub semantic: &'static str,
+    pub index: u32,
+    pub format: Format,
+    pub slot: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CompareFunc {
+    Less,
+    LessEqual,
+}
+
+pub struct GraphicsPipelineDesc<'a, B: Backend> {
+    pub signature: &'a B::RootSignature,
+    pub vs: &'a B::Shader,
+    pub ps: &'a B::Shader,
+    pub input_layout: &'a [InputElement],
+    pub render_targets: &'a [Format],
+    pub depth_format: Format,
+    pub depth_test: Option<CompareFunc>,
+    pub samples: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PrimitiveTopology {
+    TriangleList,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub min_depth: f32,
+    pub max_depth: f32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IndexBufferView {
+    pub location: u64,
+    pub size: u32,
+    pub format: Format,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VertexBufferView {
+    pub location: u64,
+    pub size: u32,
+    pub stride: u32,
+}
+
+/// Layout of a texture subresource inside a buffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PlacedFootprint {
+    pub offset: u64,
+    pub format: Format,
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+    pub row_pitch: u32,
+}
+
+pub enum Barrier<'a, B: Backend> {
+    Transition {
+        resource: &'a B::Resource,
+        subresource: u32,
+        before: ResourceStates,
+        after: ResourceStates,
+    },
+    Uav {
+        resource: &'a B::Resource,
+    },
+    /// Switch between resources placed in overlapping memory.
+    Aliasing {
+        before: Option<&'a B::Resource>,
+        after: Option<&'a B::Resource>,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SwapchainDesc {
+    pub width: u32,
+    pub height: u32,
+    pub buffer_count: u32,
+    pub format: Format,
+}
@@ -0,0 +1,124 @@
+//! Engine configuration.
+//!
+//! Loaded from a JSON file and overridden by command line options, e.g.
+//!
+//! ```json
+//! {
+//!     "debug_layer": true,
+//!     "gpu_validation": false,
+//!     "min_feature_level": "12_0",
+//!     "adapter": "largest-vram",
+//!     "frame_latency": 2
+//! }
+//! ```
+//!
+//! All keys are optional, missing keys keep their current value.
+
+use backend::{AdapterPreference, FeatureLevel};
+use failure::{err_msg, Error};
+use serde_json::{self, Value};
+use std::fs::File;
+use std::path::Path;
+
+#[derive(Clone, Debug)]
+pub struct EngineConfig {
+    /// Enable the debug layer of the backend.
+    pub debug_layer: bool,
+    /// Enable GPU-based validation, implies the debug layer. Device creation fails if
+    /// it isn't supported.
+    pub gpu_validation: bool,
+    /// Minimum feature level required from the adapter.
+    pub min_feature_level: FeatureLevel,
+    pub adapter: AdapterPreference,
+    /// Number of frames recorded ahead of the GPU.
+    pub frame_latency: u64,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        EngineConfig {
+            debug_layer: cfg!(debug_assertions),
+            gpu_validation: false,
+            min_feature_level: FeatureLevel::L12_0,
+            adapter: AdapterPreference::First,
+            frame_latency: 2,
+        }
+    }
+}
+
+impl EngineConfig {
+    /// Apply the settings of a JSON config file.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let file = File::open(path.as_ref())?;
+        let config: Value = serde_json::from_reader(file)?;
+        self.apply_json(&config)
+    }
+
+    /// Apply the settings of a JSON object.
+    pub fn apply_json(&mut self, config: &Value) -> Result<(), Error> {
+        let config = config
+            .as_object()
+            .ok_or_else(|| err_msg("engine config must be an object"))?;
+
+        for (key, value) in config {
+            let invalid = || err_msg(format!("invalid value {} for `{}`", value, key));
+            match key.as_str() {
+                "debug_layer" => self.debug_layer = value.as_bool().ok_or_else(invalid)?,
+                "gpu_validation" => self.gpu_validation = value.as_bool().ok_or_else(invalid)?,
+                "min_feature_level" => {
+                    let level = value.as_str().ok_or_else(invalid)?;
+                    self.min_feature_level = level.parse().map_err(err_msg)?;
+                }
+                "adapter" => {
+                    self.adapter = match *value {
+                        Value::Number(ref index) => {
+                            let index = index.as_u64().ok_or_else(invalid)?;
+                            AdapterPreference::Index(index as _)
+                        }
+                        Value::String(ref preference) => preference.parse().map_err(err_msg)?,
+                        _ => return Err(invalid()),
+                    };
+                }
+                "frame_latency" => {
+                    self.frame_latency = value.as_u64().ok_or_else(invalid)?;
+                }
+                _ => return Err(err_msg(format!("unknown engine config key `{}`", key))),
+            }
+        }
+
+        self.validate()
+    }
+
+    /// Apply a command line option, taking its value from `args`.
+    ///
+    /// Returns `false` if `arg` isn't an engine option.
+    pub fn apply_arg<I>(&mut self, arg: &str, args: &mut I) -> Result<bool, Error>
+    where
+        I: Iterator<Item = String>,
+    {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| err_msg(format!("missing value for `{}`", arg)))
+        };
+        match arg {
+            "--config" => self.load(value()?)?,
+            "--debug-layer" => self.debug_layer = true,
+            "--no-debug-layer" => self.debug_layer = false,
+            "--gpu-validation" => self.gpu_validation = true,
+            "--feature-level" => self.min_feature_level = value()?.parse().map_err(err_msg)?,
+            "--adapter" => self.adapter = value()?.parse().map_err(err_msg)?,
+            "--frame-latency" => self.frame_latency = value()?.parse()?,
+            _ => return Ok(false),
+        }
+
+        self.validate()?;
+        Ok(true)
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.frame_latency == 0 {
+            return Err(err_msg("frame latency must be at least 1"));
+        }
+        Ok(())
+    }
+}
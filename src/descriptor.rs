@@ -0,0 +1,395 @@
+//! Descriptor heap allocation.
+//!
+//! Manages the slots of a descriptor heap independent of the device.
+//! Allocations are contiguous `DescriptorRange`s, which are returned to the heap
+//! when dropped.
+//!
+//! Free ranges are kept in a sorted list, allocation is first-fit and adjacent
+//! free ranges are merged on release.
+
+use backend::{CpuDescriptor, DescriptorHeapType, GpuDescriptor};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Allocation statistics of a descriptor heap.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct DescriptorStats {
+    /// Total number of descriptors in the heap.
+    pub capacity: u32,
+    /// Number of descriptors currently allocated.
+    pub allocated: u32,
+    /// Maximum number of descriptors allocated at the same time.
+    pub high_water_mark: u32,
+    /// Number of live allocations.
+    pub num_allocations: u32,
+    /// Number of disjoint free ranges.
+    pub num_free_ranges: u32,
+    /// Size of the largest free range.
+    pub largest_free_range: u32,
+}
+
+impl DescriptorStats {
+    /// Fraction of free descriptors not usable for an allocation of the whole free space.
+    ///
+    /// `0.0` if all free descriptors are in a single range.
+    pub fn fragmentation(&self) -> f32 {
+        let free = self.capacity - self.allocated;
+        if free == 0 {
+            0.0
+        } else {
+            1.0 - self.largest_free_range as f32 / free as f32
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FreeList {
+    capacity: u32,
+    // Sorted by start, never adjacent or overlapping.
+    free: Vec<(u32, u32)>,
+    allocated: u32,
+    high_water_mark: u32,
+    num_allocations: u32,
+}
+
+impl FreeList {
+    fn allocate(&mut self, count: u32) -> Option<u32> {
+        let idx = self.free.iter().position(|&(_, len)| len >= count)?;
+        let (start, len) = self.free[idx];
+        if len == count {
+            self.free.remove(idx);
+        } else {
+            self.free[idx] = (start + count, len - count);
+        }
+
+        self.allocated += count;
+        self.high_water_mark = self.high_water_mark.max(self.allocated);
+        self.num_allocations += 1;
+        Some(start)
+    }
+
+    fn free(&mut self, start: u32, count: u32) {
+        let idx = self
+            .free
+            .iter()
+            .position(|&(free_start, _)| free_start > start)
+            .unwrap_or(self.free.len());
+
+        debug_assert!(
+            idx == 0 || {
+                let (prev_start, prev_len) = self.free[idx - 1];
+                prev_start + prev_len <= start
+            }
+        );
+        debug_assert!(idx == self.free.len() || start + count <= self.free[idx].0);
+
+        let merge_prev = idx > 0 && {
+            let (prev_start, prev_len) = self.free[idx - 1];
+            prev_start + prev_len == start
+        };
+        let merge_next = idx < self.free.len() && start + count == self.free[idx].0;
+
+        match (merge_prev, merge_next) {
+            (true, true) => {
+                let (_, next_len) = self.free.remove(idx);
+                self.free[idx - 1].1 += count + next_len;
+            }
+            (true, false) => self.free[idx - 1].1 += count,
+            (false, true) => self.free[idx] = (start, count + self.free[idx].1),
+            (false, false) => self.free.insert(idx, (start, count)),
+        }
+
+        self.allocated -= count;
+        self.num_allocations -= 1;
+    }
+
+    fn stats(&self) -> DescriptorStats {
+        DescriptorStats {
+            capacity: self.capacity,
+            allocated: self.allocated,
+            high_water_mark: self.high_water_mark,
+            num_allocations: self.num_allocations,
+            num_free_ranges: self.free.len() as _,
+            largest_free_range: self.free.iter().map(|&(_, len)| len).max().unwrap_or(0),
+        }
+    }
+}
+
+/// Free-list allocator for descriptor heap slots.
+#[derive(Clone)]
+pub struct DescriptorAllocator {
+    ty: DescriptorHeapType,
+    cpu_start: CpuDescriptor,
+    gpu_start: Option<GpuDescriptor>,
+    increment: u32,
+    free_list: Arc<Mutex<FreeList>>,
+}
+
+impl DescriptorAllocator {
+    /// Create an allocator for a heap with `capacity` descriptors.
+    ///
+    /// `start` is the handle pair of the first descriptor in the heap, a null GPU handle
+    /// marks heaps which are not shader visible.
+    pub fn new(
+        ty: DescriptorHeapType,
+        capacity: u32,
+        start: (CpuDescriptor, GpuDescriptor),
+        increment: u32,
+    ) -> Self {
+        let free = if capacity > 0 {
+            vec![(0, capacity)]
+        } else {
+            Vec::new()
+        };
+
+        DescriptorAllocator {
+            ty,
+            cpu_start: start.0,
+            gpu_start: if start.1.ptr != 0 {
+                Some(start.1)
+            } else {
+                None
+            },
+            increment,
+            free_list: Arc::new(Mutex::new(FreeList {
+                capacity,
+                free,
+                allocated: 0,
+                high_water_mark: 0,
+                num_allocations: 0,
+            })),
+        }
+    }
+
+    /// Allocate `count` contiguous descriptors.
+    ///
+    /// Returns `None` if no free range is large enough.
+    pub fn allocate(&self, count: u32, name: &str) -> Option<DescriptorRange> {
+        assert!(count > 0, "empty descriptor allocation `{}`", name);
+        let start = self.free_list.lock().unwrap().allocate(count)?;
+        let offset = start * self.increment;
+
+        Some(DescriptorRange {
+            name: name.to_string(),
+            ty: self.ty,
+            start,
+            count,
+            cpu_base: CpuDescriptor {
+                ptr: self.cpu_start.ptr + offset as usize,
+            },
+            gpu_base: self.gpu_start.map(|gpu| GpuDescriptor {
+                ptr: gpu.ptr + offset as u64,
+            }),
+            increment: self.increment,
+            free_list: self.free_list.clone(),
+        })
+    }
+
+    pub fn stats(&self) -> DescriptorStats {
+        self.free_list.lock().unwrap().stats()
+    }
+}
+
+/// Contiguous range of descriptors in a heap.
+///
+/// Descriptors are returned to the allocator on drop.
+pub struct DescriptorRange {
+    name: String,
+    ty: DescriptorHeapType,
+    start: u32,
+    count: u32,
+    cpu_base: CpuDescriptor,
+    gpu_base: Option<GpuDescriptor>,
+    increment: u32,
+    free_list: Arc<Mutex<FreeList>>,
+}
+
+impl DescriptorRange {
+    fn check_bounds(&self, idx: u32) {
+        assert!(
+            idx < self.count,
+            "descriptor index {} out of bounds for `{}` with {} descriptors",
+            idx,
+            self.name,
+            self.count
+        );
+    }
+
+    /// CPU handle of the `idx`-th descriptor in the range.
+    pub fn cpu(&self, idx: u32) -> CpuDescriptor {
+        self.check_bounds(idx);
+        CpuDescriptor {
+            ptr: self.cpu_base.ptr + (idx * self.increment) as usize,
+        }
+    }
+
+    /// GPU handle of the `idx`-th descriptor in the range.
+    ///
+    /// Only available for ranges in shader visible heaps.
+    pub fn gpu(&self, idx: u32) -> GpuDescriptor {
+        self.check_bounds(idx);
+        let gpu_base = self
+            .gpu_base
+            .unwrap_or_else(|| panic!("`{}` is not in a shader visible heap", self.name));
+        GpuDescriptor {
+            ptr: gpu_base.ptr + (idx * self.increment) as u64,
+        }
+    }
+}
+
+impl Drop for DescriptorRange {
+    fn drop(&mut self) {
+        self.free_list.lock().unwrap().free(self.start, self.count);
+    }
+}
+
+impl fmt::Debug for DescriptorRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DescriptorRange")
+            .field("name", &self.name)
+            .field("heap", &self.ty)
+            .field("start", &self.start)
+            .field("count", &self.count)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INCREMENT: u32 = 32;
+
+    fn new_allocator(capacity: u32) -> DescriptorAllocator {
+        DescriptorAllocator::new(
+            DescriptorHeapType::CbvSrvUav,
+            capacity,
+            (CpuDescriptor { ptr: 0x1000 }, GpuDescriptor { ptr: 0x2000 }),
+            INCREMENT,
+        )
+    }
+
+    fn free_ranges(allocator: &DescriptorAllocator) -> Vec<(u32, u32)> {
+        allocator.free_list.lock().unwrap().free.clone()
+    }
+
+    #[test]
+    fn first_fit() {
+        let allocator = new_allocator(16);
+        let a = allocator.allocate(4, "a").unwrap();
+        let b = allocator.allocate(4, "b").unwrap();
+        let c = allocator.allocate(4, "c").unwrap();
+        assert_eq!((a.start, b.start, c.start), (0, 4, 8));
+
+        drop(b);
+        let d = allocator.allocate(2, "d").unwrap();
+        assert_eq!(d.start, 4);
+        // Remaining hole of 2 descriptors is too small.
+        let e = allocator.allocate(4, "e").unwrap();
+        assert_eq!(e.start, 12);
+        assert_eq!(free_ranges(&allocator), vec![(6, 2)]);
+    }
+
+    #[test]
+    fn handles() {
+        let allocator = new_allocator(16);
+        let _a = allocator.allocate(3, "a").unwrap();
+        let b = allocator.allocate(2, "b").unwrap();
+        assert_eq!(b.cpu(1).ptr, 0x1000 + 4 * INCREMENT as usize);
+        assert_eq!(b.gpu(1).ptr, 0x2000 + 4 * INCREMENT as u64);
+    }
+
+    #[test]
+    fn merge_with_previous() {
+        let allocator = new_allocator(12);
+        let a = allocator.allocate(4, "a").unwrap();
+        let b = allocator.allocate(4, "b").unwrap();
+        let _c = allocator.allocate(4, "c").unwrap();
+
+        drop(a);
+        drop(b);
+        assert_eq!(free_ranges(&allocator), vec![(0, 8)]);
+    }
+
+    #[test]
+    fn merge_with_next() {
+        let allocator = new_allocator(12);
+        let _a = allocator.allocate(4, "a").unwrap();
+        let b = allocator.allocate(4, "b").unwrap();
+        let c = allocator.allocate(4, "c").unwrap();
+
+        drop(c);
+        drop(b);
+        assert_eq!(free_ranges(&allocator), vec![(4, 8)]);
+    }
+
+    #[test]
+    fn merge_with_both() {
+        let allocator = new_allocator(12);
+        let a = allocator.allocate(4, "a").unwrap();
+        let b = allocator.allocate(4, "b").unwrap();
+        let c = allocator.allocate(4, "c").unwrap();
+
+        drop(a);
+        drop(c);
+        assert_eq!(free_ranges(&allocator), vec![(0, 4), (8, 4)]);
+        drop(b);
+        assert_eq!(free_ranges(&allocator), vec![(0, 12)]);
+        assert_eq!(allocator.allocate(12, "all").unwrap().start, 0);
+    }
+
+    #[test]
+    fn exhaustion() {
+        let allocator = new_allocator(8);
+        assert!(allocator.allocate(9, "too large").is_none());
+
+        let a = allocator.allocate(6, "a").unwrap();
+        assert!(allocator.allocate(3, "b").is_none());
+        let _b = allocator.allocate(2, "b").unwrap();
+        assert!(allocator.allocate(1, "c").is_none());
+
+        drop(a);
+        assert!(allocator.allocate(6, "c").is_some());
+        assert!(new_allocator(0).allocate(1, "empty").is_none());
+    }
+
+    #[test]
+    fn high_water_mark() {
+        let allocator = new_allocator(16);
+        let a = allocator.allocate(4, "a").unwrap();
+        let b = allocator.allocate(4, "b").unwrap();
+        drop(a);
+        let _c = allocator.allocate(2, "c").unwrap();
+
+        let stats = allocator.stats();
+        assert_eq!(stats.allocated, 6);
+        assert_eq!(stats.high_water_mark, 8);
+        assert_eq!(stats.num_allocations, 2);
+
+        drop(b);
+        let stats = allocator.stats();
+        assert_eq!(stats.allocated, 2);
+        assert_eq!(stats.high_water_mark, 8);
+    }
+
+    #[test]
+    fn fragmentation() {
+        let allocator = new_allocator(12);
+        assert_eq!(allocator.stats().fragmentation(), 0.0);
+
+        let a = allocator.allocate(4, "a").unwrap();
+        let b = allocator.allocate(4, "b").unwrap();
+        let c = allocator.allocate(4, "c").unwrap();
+        // Fully allocated heaps aren't fragmented.
+        assert_eq!(allocator.stats().fragmentation(), 0.0);
+
+        drop(a);
+        drop(c);
+        let stats = allocator.stats();
+        assert_eq!((stats.num_free_ranges, stats.largest_free_range), (2, 4));
+        assert_eq!(stats.fragmentation(), 0.5);
+
+        drop(b);
+        assert_eq!(allocator.stats().fragmentation(), 0.0);
+    }
+}
@@ -0,0 +1,259 @@
+//! Frames in flight.
+//!
+//! The CPU records up to `frame_latency` frames ahead of the GPU. Each frame in
+//! flight owns a context with its command allocator and list, transient constant
+//! and structured buffer memory and a queue of objects released during the frame.
+//! A context is only reused after the GPU finished the frame previously recorded
+//! with it, released objects are dropped at this point.
+
+use backend::{
+    Backend, CommandList, Device, EngineError, HeapType, ResourceDesc, ResourceStates, SrvDesc,
+};
+use engine::Engine;
+use std::any::Any;
+use std::{mem, ptr};
+
+/// Size of the transient constant and structured buffer memory of each frame.
+const CONSTANT_MEMORY_SIZE: u64 = 1024 * 1024;
+/// Required alignment of constant buffer views.
+const CONSTANT_ALIGNMENT: u64 = 256;
+
+/// Maximum time waiting for the GPU to reach a fence value.
+pub const FENCE_TIMEOUT_MS: u32 = 50_000;
+
+/// Per-frame resources of a frame in flight.
+pub struct FrameContext<B: Backend> {
+    /// Index of the context, in `0..frame_latency`.
+    pub index: usize,
+    pub cmd_list: B::CommandList,
+    cmd_allocator: B::CommandAllocator,
+    // Fence value signaled after the last submission of this context.
+    fence_value: u64,
+
+    constants: B::Resource,
+    constants_cpu: *mut u8,
+    constants_gpu: u64,
+    constants_offset: u64,
+
+    releases: Vec<Box<dyn Any>>,
+}
+
+impl<B: Backend> FrameContext<B> {
+    /// Copy `data` into the constant memory of the frame.
+    ///
+    /// Returns the GPU virtual address, valid until the end of the frame.
+    pub fn push_constants<T: Copy>(&mut self, data: &T) -> u64 {
+        let size = mem::size_of::<T>() as u64;
+        let offset = self.constants_offset;
+        assert!(
+            offset + size <= CONSTANT_MEMORY_SIZE,
+            "frame constant memory exhausted"
+        );
+
+        unsafe {
+            ptr::copy_nonoverlapping(
+                data as *const T,
+                self.constants_cpu.offset(offset as _) as *mut T,
+                1,
+            );
+        }
+        self.constants_offset =
+            (offset + size + CONSTANT_ALIGNMENT - 1) / CONSTANT_ALIGNMENT * CONSTANT_ALIGNMENT;
+
+        self.constants_gpu + offset
+    }
+
+    /// Copy `data` as structured buffer elements into the memory of the frame.
+    ///
+    /// Returns the view of the elements in `memory`, valid until the end of the frame.
+    /// Empty slices store a single zeroed element, views can't be empty.
+    pub fn push_structured<T: Copy>(&mut self, data: &[T]) -> SrvDesc {
+        let stride = mem::size_of::<T>() as u64;
+        let num_elements = data.len().max(1) as u64;
+        // Views address whole elements.
+        let first_element = (self.constants_offset + stride - 1) / stride;
+        let offset = first_element * stride;
+        let size = num_elements * stride;
+        assert!(
+            offset + size <= CONSTANT_MEMORY_SIZE,
+            "frame constant memory exhausted"
+        );
+
+        unsafe {
+            let dst = self.constants_cpu.offset(offset as _);
+            ptr::write_bytes(dst, 0, size as _);
+            ptr::copy_nonoverlapping(data.as_ptr(), dst as *mut T, data.len());
+        }
+        self.constants_offset =
+            (offset + size + CONSTANT_ALIGNMENT - 1) / CONSTANT_ALIGNMENT * CONSTANT_ALIGNMENT;
+
+        SrvDesc::Buffer {
+            first_element,
+            num_elements: num_elements as _,
+            stride: stride as _,
+        }
+    }
+
+    /// Upload buffer backing the constant and structured buffer memory of the frame.
+    pub fn memory(&self) -> &B::Resource {
+        &self.constants
+    }
+
+    /// Keep `object` alive until the GPU finished the frame.
+    pub fn release<T: 'static>(&mut self, object: T) {
+        self.releases.push(Box::new(object));
+    }
+}
+
+/// Ring of frame contexts.
+pub struct Frames<B: Backend> {
+    fence: B::Fence,
+    fence_value: u64,
+    contexts: Vec<FrameContext<B>>,
+    // Number of frames begun so far.
+    tick: u64,
+}
+
+impl<B: Backend> Frames<B> {
+    /// Create a context for each frame in flight of the engine.
+    pub fn new(engine: &Engine<B>) -> Result<Self, EngineError> {
+        let device = &engine.device;
+        let contexts = (0..engine.frame_latency() as usize)
+            .map(|index| {
+                let cmd_allocator = device.create_command_allocator()?;
+                let cmd_list = device.create_command_list(&cmd_allocator)?;
+                let constants = device.create_committed_resource(
+                    HeapType::Upload,
+                    &ResourceDesc::buffer(CONSTANT_MEMORY_SIZE),
+                    ResourceStates::GENERIC_READ,
+                    None,
+                )?;
+                let constants_cpu = unsafe { device.map(&constants)? };
+                let constants_gpu = device.gpu_virtual_address(&constants);
+
+                Ok(FrameContext {
+                    index,
+                    cmd_list,
+                    cmd_allocator,
+                    fence_value: 0,
+                    constants,
+                    constants_cpu,
+                    constants_gpu,
+                    constants_offset: 0,
+                    releases: Vec::new(),
+                })
+            })
+            .collect::<Result<_, EngineError>>()?;
+
+        Ok(Frames {
+            fence: device.create_fence(0)?,
+            fence_value: 0,
+            contexts,
+            tick: 0,
+        })
+    }
+
+    /// Wait for the next frame context to become available and start recording.
+    ///
+    /// Objects released in the previous use of the context are dropped.
+    pub fn begin(&mut self, device: &B::Device) -> Result<&mut FrameContext<B>, EngineError> {
+        let index = (self.tick % self.contexts.len() as u64) as usize;
+        let fence_value = self.contexts[index].fence_value;
+        self.wait(device, fence_value)?;
+        self.tick += 1;
+
+        let context = &mut self.contexts[index];
+        context.releases.clear();
+        context.constants_offset = 0;
+        context.cmd_list.reset(&context.cmd_allocator);
+        Ok(context)
+    }
+
+    /// Close and submit the command list of the current frame.
+    ///
+    /// Objects released to the engine so far are kept alive by the frame.
+    pub fn end(&mut self, engine: &mut Engine<B>) {
+        assert!(self.tick > 0, "no frame begun");
+        let index = ((self.tick - 1) % self.contexts.len() as u64) as usize;
+        let context = &mut self.contexts[index];
+        let released = engine.take_releases();
+        if !released.is_empty() {
+            context.release(released);
+        }
+        let device = &engine.device;
+
+        context.cmd_list.close();
+        device.execute_command_lists(&[&context.cmd_list]);
+        self.fence_value += 1;
+        device.signal(&self.fence, self.fence_value);
+        context.fence_value = self.fence_value;
+    }
+
+    /// Block until the GPU finished all submitted frames and drop released objects.
+    pub fn wait_idle(&mut self, engine: &mut Engine<B>) -> Result<(), EngineError> {
+        let fence_value = self.fence_value;
+        self.wait(&engine.device, fence_value)?;
+        for context in &mut self.contexts {
+            context.releases.clear();
+        }
+        engine.take_releases();
+        Ok(())
+    }
+
+    fn wait(&self, device: &B::Device, fence_value: u64) -> Result<(), EngineError> {
+        device.wait_for_fence(&self.fence, fence_value, FENCE_TIMEOUT_MS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::null::Null;
+    use config::EngineConfig;
+    use std::rc::Rc;
+
+    fn waited_for(engine: &Engine<Null>, fence_value: u64) -> bool {
+        let wait = format!("wait_for_fence({}, {}ms)", fence_value, FENCE_TIMEOUT_MS);
+        engine
+            .device
+            .calls()
+            .iter()
+            .any(|call| call.to_string() == wait)
+    }
+
+    #[test]
+    fn drop_released_objects_after_fence() {
+        let mut engine = Engine::<Null>::new(&EngineConfig::default()).unwrap();
+        let mut frames = Frames::new(&engine).unwrap();
+        assert_eq!(engine.frame_latency(), 2);
+        let object = Rc::new(());
+
+        // Released while recording the first frame, which signals fence value 1.
+        frames.begin(&engine.device).unwrap();
+        engine.release(object.clone());
+        frames.end(&mut engine);
+        assert_eq!(Rc::strong_count(&object), 2);
+
+        // The second frame uses the other context, the first one is still in flight.
+        frames.begin(&engine.device).unwrap();
+        frames.end(&mut engine);
+        assert!(!waited_for(&engine, 1));
+        assert_eq!(Rc::strong_count(&object), 2);
+
+        // The third frame reuses the context after the first frame passed its fence.
+        let frame = frames.begin(&engine.device).unwrap();
+        assert!(waited_for(&engine, 1));
+        assert_eq!(Rc::strong_count(&object), 1);
+
+        // Objects released to the frame directly are kept until idle.
+        frame.release(object.clone());
+        frames.end(&mut engine);
+        engine.release(object.clone());
+        assert_eq!(Rc::strong_count(&object), 3);
+        frames.wait_idle(&mut engine).unwrap();
+        assert!(waited_for(&engine, 3));
+        assert_eq!(Rc::strong_count(&object), 1);
+
+        assert_eq!(engine.validation_errors(), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,1109 @@
+//! Render graph.
+//!
+//! Passes declare the resources they read and write, the graph derives from these:
+//!
+//!  * Execution order: Writes create a new version of a resource, passes depend on the
+//!    writer of each version they access and on all readers of the version they overwrite.
+//!  * Culling: Passes not contributing to an output (imported resources) are dropped.
+//!  * Lifetimes of transient resources, from first to last use in execution order.
+//!    Transient resources with equal description and disjoint lifetimes share the same
+//!    physical resource, others with disjoint lifetimes may alias the same memory.
+//!  * Barriers: Each access is forwarded to the state tracker before the pass is recorded.
+//!
+//! Building and compiling a graph is independent of the device, `RenderGraph` allocates
+//! the physical resources and executes the compiled graph.
+
+use backend::{
+    Backend, Barrier, ClearValue, CommandList, Device, EngineError, HeapType, ResourceDesc,
+    ResourceStates,
+};
+use engine::Engine;
+use failure::{err_msg, Error};
+use memory::{Allocation, ResourceClass};
+use serde_json::Value;
+use state::StateTracker;
+use std::fmt::Write;
+
+/// Version of a resource in the graph.
+///
+/// Writing a resource returns a handle to the next version.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ResourceHandle {
+    index: usize,
+    version: u32,
+}
+
+/// Way a pass accesses a resource.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Access {
+    RenderTarget,
+    DepthWrite,
+    UnorderedAccess,
+    PixelShaderResource,
+    NonPixelShaderResource,
+    CopySource,
+    CopyDest,
+    Present,
+}
+
+impl Access {
+    pub fn state(&self) -> ResourceStates {
+        match *self {
+            Access::RenderTarget => ResourceStates::RENDER_TARGET,
+            Access::DepthWrite => ResourceStates::DEPTH_WRITE,
+            Access::UnorderedAccess => ResourceStates::UNORDERED_ACCESS,
+            Access::PixelShaderResource => ResourceStates::PIXEL_SHADER_RESOURCE,
+            Access::NonPixelShaderResource => ResourceStates::NON_PIXEL_SHADER_RESOURCE,
+            Access::CopySource => ResourceStates::COPY_SOURCE,
+            Access::CopyDest => ResourceStates::COPY_DEST,
+            Access::Present => ResourceStates::PRESENT,
+        }
+    }
+
+    pub fn is_write(&self) -> bool {
+        match *self {
+            Access::RenderTarget
+            | Access::DepthWrite
+            | Access::UnorderedAccess
+            | Access::CopyDest => true,
+            _ => false,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {
+            Access::RenderTarget => "render_target",
+            Access::DepthWrite => "depth_write",
+            Access::UnorderedAccess => "unordered_access",
+            Access::PixelShaderResource => "pixel_shader_resource",
+            Access::NonPixelShaderResource => "non_pixel_shader_resource",
+            Access::CopySource => "copy_source",
+            Access::CopyDest => "copy_dest",
+            Access::Present => "present",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum ResourceKind {
+    /// Owned by the graph, allocated in the state of the first access.
+    Transient {
+        desc: ResourceDesc,
+        clear_value: Option<ClearValue>,
+    },
+    /// Provided on execution, left in `final_access` after the frame.
+    Imported { final_access: Access },
+}
+
+#[derive(Debug)]
+struct ResourceNode {
+    name: String,
+    kind: ResourceKind,
+    num_versions: u32,
+    output: bool,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct ResourceAccess {
+    handle: ResourceHandle,
+    access: Access,
+}
+
+#[derive(Debug)]
+struct PassNode<P> {
+    name: String,
+    tag: P,
+    accesses: Vec<ResourceAccess>,
+}
+
+/// Graph description, passes and resources in declaration order.
+pub struct GraphBuilder<P> {
+    resources: Vec<ResourceNode>,
+    passes: Vec<PassNode<P>>,
+}
+
+impl<P: Copy> GraphBuilder<P> {
+    pub fn new() -> Self {
+        GraphBuilder {
+            resources: Vec::new(),
+            passes: Vec::new(),
+        }
+    }
+
+    fn add_resource(&mut self, name: &str, kind: ResourceKind, output: bool) -> ResourceHandle {
+        self.resources.push(ResourceNode {
+            name: name.to_string(),
+            kind,
+            num_versions: 1,
+            output,
+        });
+        ResourceHandle {
+            index: self.resources.len() - 1,
+            version: 0,
+        }
+    }
+
+    /// Texture owned by the graph, only valid during the frame.
+    pub fn create_texture(
+        &mut self,
+        name: &str,
+        desc: ResourceDesc,
+        clear_value: Option<ClearValue>,
+    ) -> ResourceHandle {
+        self.add_resource(name, ResourceKind::Transient { desc, clear_value }, false)
+    }
+
+    /// Buffer owned by the graph, only valid during the frame.
+    pub fn create_buffer(&mut self, name: &str, desc: ResourceDesc) -> ResourceHandle {
+        self.add_resource(
+            name,
+            ResourceKind::Transient {
+                desc,
+                clear_value: None,
+            },
+            false,
+        )
+    }
+
+    /// External resource bound on execution (e.g. the backbuffer).
+    ///
+    /// Imported resources are graph outputs.
+    pub fn import(&mut self, name: &str, final_access: Access) -> ResourceHandle {
+        self.add_resource(name, ResourceKind::Imported { final_access }, true)
+    }
+
+    pub fn add_pass<'a>(&'a mut self, name: &str, tag: P) -> PassBuilder<'a, P> {
+        self.passes.push(PassNode {
+            name: name.to_string(),
+            tag,
+            accesses: Vec::new(),
+        });
+        PassBuilder { graph: self }
+    }
+
+    /// Derive execution order, culled passes and resource lifetimes.
+    pub fn compile(self) -> Result<CompiledGraph<P>, Error> {
+        let num_passes = self.passes.len();
+
+        // Producer of each resource version and consumers reading it.
+        let mut writers = self
+            .resources
+            .iter()
+            .map(|resource| vec![None; resource.num_versions as usize])
+            .collect::<Vec<Vec<Option<usize>>>>();
+        let mut readers = self
+            .resources
+            .iter()
+            .map(|resource| vec![Vec::new(); resource.num_versions as usize])
+            .collect::<Vec<Vec<Vec<usize>>>>();
+
+        for (pass_id, pass) in self.passes.iter().enumerate() {
+            for access in &pass.accesses {
+                let ResourceHandle { index, version } = access.handle;
+                if access.access.is_write() {
+                    let writer = &mut writers[index][version as usize + 1];
+                    if let Some(other) = *writer {
+                        return Err(err_msg(format!(
+                            "`{}` version {} written by `{}` and `{}`",
+                            self.resources[index].name, version, self.passes[other].name, pass.name
+                        )));
+                    }
+                    *writer = Some(pass_id);
+                } else {
+                    readers[index][version as usize].push(pass_id);
+                }
+            }
+        }
+
+        // Dependencies between passes
+        let mut dependencies = vec![Vec::new(); num_passes];
+        for (pass_id, pass) in self.passes.iter().enumerate() {
+            for access in &pass.accesses {
+                let ResourceHandle { index, version } = access.handle;
+                let resource = &self.resources[index];
+
+                // Read after write, write after write
+                match writers[index][version as usize] {
+                    Some(writer) => dependencies[pass_id].push(writer),
+                    None if version > 0 => {
+                        return Err(err_msg(format!(
+                            "`{}` version {} accessed by `{}` is never written",
+                            resource.name, version, pass.name
+                        )));
+                    }
+                    None => {
+                        if let ResourceKind::Transient { .. } = resource.kind {
+                            if !access.access.is_write() {
+                                return Err(err_msg(format!(
+                                    "transient `{}` read by `{}` before being written",
+                                    resource.name, pass.name
+                                )));
+                            }
+                        }
+                    }
+                }
+
+                // Write after read
+                if access.access.is_write() {
+                    dependencies[pass_id].extend(
+                        readers[index][version as usize]
+                            .iter()
+                            .cloned()
+                            .filter(|&reader| reader != pass_id),
+                    );
+                }
+            }
+        }
+
+        // Culling, walk backwards from the final versions of all outputs.
+        let mut alive = vec![false; num_passes];
+        let mut stack = self
+            .resources
+            .iter()
+            .enumerate()
+            .filter(|&(_, resource)| resource.output)
+            .filter_map(|(index, resource)| writers[index][resource.num_versions as usize - 1])
+            .collect::<Vec<_>>();
+        while let Some(pass_id) = stack.pop() {
+            if alive[pass_id] {
+                continue;
+            }
+            alive[pass_id] = true;
+            stack.extend(dependencies[pass_id].iter().cloned());
+        }
+
+        // Execution order, topological sort preferring declaration order.
+        let mut order = Vec::new();
+        let mut scheduled = vec![false; num_passes];
+        while order.len() < alive.iter().filter(|&&alive| alive).count() {
+            let next = (0..num_passes).find(|&pass_id| {
+                alive[pass_id]
+                    && !scheduled[pass_id]
+                    && dependencies[pass_id].iter().all(|&dep| scheduled[dep])
+            });
+            match next {
+                Some(pass_id) => {
+                    scheduled[pass_id] = true;
+                    order.push(pass_id);
+                }
+                None => return Err(err_msg("cyclic dependency between passes")),
+            }
+        }
+
+        // Transient resource lifetimes and physical resources.
+        let mut lifetimes = vec![None; self.resources.len()];
+        for (position, &pass_id) in order.iter().enumerate() {
+            for access in &self.passes[pass_id].accesses {
+                let lifetime = &mut lifetimes[access.handle.index];
+                *lifetime = match *lifetime {
+                    None => Some((position, position)),
+                    Some((first, _)) => Some((first, position)),
+                };
+            }
+        }
+
+        let mut physical: Vec<Option<usize>> = vec![None; self.resources.len()];
+        let mut physical_resources: Vec<PhysicalResource> = Vec::new();
+        let mut transients = (0..self.resources.len())
+            .filter(|&index| match self.resources[index].kind {
+                ResourceKind::Transient { .. } => lifetimes[index].is_some(),
+                ResourceKind::Imported { .. } => false,
+            })
+            .collect::<Vec<_>>();
+        transients.sort_by_key(|&index| lifetimes[index].unwrap().0);
+        for index in transients {
+            let (first, last) = lifetimes[index].unwrap();
+            let (desc, clear_value) = match self.resources[index].kind {
+                ResourceKind::Transient { desc, clear_value } => (desc, clear_value),
+                ResourceKind::Imported { .. } => unreachable!(),
+            };
+            let first_access = order[first..]
+                .iter()
+                .flat_map(|&pass_id| self.passes[pass_id].accesses.iter())
+                .find(|access| access.handle.index == index)
+                .unwrap()
+                .access;
+
+            let reuse = physical_resources.iter().position(|resource| {
+                resource.desc == desc
+                    && resource.clear_value == clear_value
+                    && resource.last < first
+            });
+            physical[index] = Some(match reuse {
+                Some(id) => {
+                    physical_resources[id].last = last;
+                    id
+                }
+                None => {
+                    physical_resources.push(PhysicalResource {
+                        desc,
+                        clear_value,
+                        initial_access: first_access,
+                        first,
+                        last,
+                    });
+                    physical_resources.len() - 1
+                }
+            });
+        }
+
+        Ok(CompiledGraph {
+            resources: self.resources,
+            passes: self.passes,
+            order,
+            alive,
+            lifetimes,
+            physical,
+            physical_resources,
+        })
+    }
+}
+
+/// Declares the resource accesses of a pass.
+pub struct PassBuilder<'a, P: 'a> {
+    graph: &'a mut GraphBuilder<P>,
+}
+
+impl<'a, P> PassBuilder<'a, P> {
+    fn access(&mut self, handle: ResourceHandle, access: Access) {
+        self.graph
+            .passes
+            .last_mut()
+            .unwrap()
+            .accesses
+            .push(ResourceAccess { handle, access });
+    }
+
+    pub fn read(&mut self, handle: ResourceHandle, access: Access) {
+        assert!(!access.is_write(), "{:?} is not a read access", access);
+        self.access(handle, access);
+    }
+
+    /// Write the resource, returns the handle of the new version.
+    pub fn write(&mut self, handle: ResourceHandle, access: Access) -> ResourceHandle {
+        assert!(access.is_write(), "{:?} is not a write access", access);
+        let resource = &mut self.graph.resources[handle.index];
+        assert_eq!(
+            handle.version + 1,
+            resource.num_versions,
+            "`{}` written from outdated version {}",
+            resource.name,
+            handle.version
+        );
+        resource.num_versions += 1;
+        self.access(handle, access);
+        ResourceHandle {
+            index: handle.index,
+            version: handle.version + 1,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct PhysicalResource {
+    desc: ResourceDesc,
+    clear_value: Option<ClearValue>,
+    initial_access: Access,
+    // Positions of the first and last pass using the resource in execution order.
+    first: usize,
+    last: usize,
+}
+
+/// Graph with derived execution order and resource lifetimes.
+pub struct CompiledGraph<P> {
+    resources: Vec<ResourceNode>,
+    passes: Vec<PassNode<P>>,
+    order: Vec<usize>,
+    alive: Vec<bool>,
+    lifetimes: Vec<Option<(usize, usize)>>,
+    physical: Vec<Option<usize>>,
+    physical_resources: Vec<PhysicalResource>,
+}
+
+impl<P: Copy> CompiledGraph<P> {
+    /// Tags of the passes in execution order.
+    pub fn pass_order(&self) -> Vec<P> {
+        self.order
+            .iter()
+            .map(|&pass_id| self.passes[pass_id].tag)
+            .collect()
+    }
+
+    pub fn num_physical_resources(&self) -> usize {
+        self.physical_resources.len()
+    }
+
+    /// Frame structure in Graphviz DOT format.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph frame {{").unwrap();
+        writeln!(dot, "    rankdir=LR;").unwrap();
+
+        for (pass_id, pass) in self.passes.iter().enumerate() {
+            let label = match self.order.iter().position(|&id| id == pass_id) {
+                Some(position) => format!("{}: {}", position, pass.name),
+                None => format!("{} (culled)", pass.name),
+            };
+            let style = if self.alive[pass_id] {
+                "solid"
+            } else {
+                "dashed"
+            };
+            writeln!(
+                dot,
+                "    pass{} [shape=box, style={}, label=\"{}\"];",
+                pass_id, style, label
+            )
+            .unwrap();
+        }
+
+        for (index, resource) in self.resources.iter().enumerate() {
+            let shape = match resource.kind {
+                ResourceKind::Transient { .. } => "ellipse",
+                ResourceKind::Imported { .. } => "doubleoctagon",
+            };
+            for version in 0..resource.num_versions {
+                writeln!(
+                    dot,
+                    "    res{}_{} [shape={}, label=\"{} v{}\"];",
+                    index, version, shape, resource.name, version
+                )
+                .unwrap();
+            }
+        }
+
+        for (pass_id, pass) in self.passes.iter().enumerate() {
+            for access in &pass.accesses {
+                let ResourceHandle { index, version } = access.handle;
+                if access.access.is_write() {
+                    writeln!(
+                        dot,
+                        "    pass{} -> res{}_{} [label=\"{}\"];",
+                        pass_id,
+                        index,
+                        version + 1,
+                        access.access.name()
+                    )
+                    .unwrap();
+                } else {
+                    writeln!(
+                        dot,
+                        "    res{}_{} -> pass{} [label=\"{}\"];",
+                        index,
+                        version,
+                        pass_id,
+                        access.access.name()
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+
+    /// Frame structure as JSON.
+    pub fn to_json(&self) -> Value {
+        let passes = self
+            .passes
+            .iter()
+            .enumerate()
+            .map(|(pass_id, pass)| {
+                let accesses = pass
+                    .accesses
+                    .iter()
+                    .map(|access| {
+                        json!({
+                            "resource": self.resources[access.handle.index].name,
+                            "version": access.handle.version,
+                            "access": access.access.name(),
+                            "write": access.access.is_write(),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json!({
+                    "name": pass.name,
+                    "order": self.order.iter().position(|&id| id == pass_id),
+                    "culled": !self.alive[pass_id],
+                    "accesses": accesses,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let resources = self
+            .resources
+            .iter()
+            .enumerate()
+            .map(|(index, resource)| {
+                let kind = match resource.kind {
+                    ResourceKind::Transient { desc, .. } => json!({
+                        "type": "transient",
+                        "width": desc.width,
+                        "height": desc.height,
+                        "format": format!("{:?}", desc.format),
+                        "samples": desc.samples,
+                    }),
+                    ResourceKind::Imported { final_access } => json!({
+                        "type": "imported",
+                        "final_access": final_access.name(),
+                    }),
+                };
+                json!({
+                    "name": resource.name,
+                    "kind": kind,
+                    "versions": resource.num_versions,
+                    "output": resource.output,
+                    "lifetime": self.lifetimes[index].map(|(first, last)| vec![first, last]),
+                    "physical": self.physical[index],
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "passes": passes,
+            "resources": resources,
+            "physical_resources": self.physical_resources.len(),
+        })
+    }
+}
+
+/// Compiled graph with allocated transient resources.
+pub struct RenderGraph<B: Backend, P> {
+    graph: CompiledGraph<P>,
+    physical_resources: Vec<B::Resource>,
+    // Physical resources sharing memory with others, requiring an aliasing barrier
+    // before their first use.
+    aliased: Vec<bool>,
+    // Released after the placed resources.
+    _memory: Vec<Allocation<B>>,
+}
+
+impl<B: Backend, P: Copy> RenderGraph<B, P> {
+    pub fn new(engine: &mut Engine<B>, graph: CompiledGraph<P>) -> Result<Self, EngineError> {
+        let num_physical = graph.physical_resources.len();
+        let infos = graph
+            .physical_resources
+            .iter()
+            .map(|resource| engine.device.resource_allocation_info(&resource.desc))
+            .collect::<Vec<_>>();
+
+        // Place resources of each class into a shared memory block. Resources only
+        // overlap in memory if their lifetimes are disjoint.
+        let mut offsets = vec![0; num_physical];
+        let mut aliased = vec![false; num_physical];
+        let mut memory = Vec::new();
+        for &class in &[
+            ResourceClass::Buffer,
+            ResourceClass::Texture,
+            ResourceClass::RenderTarget,
+        ] {
+            let mut placed: Vec<usize> = Vec::new();
+            let mut size = 0;
+            let mut alignment = 1;
+            for id in 0..num_physical {
+                let resource = &graph.physical_resources[id];
+                if ResourceClass::of(&resource.desc) != class {
+                    continue;
+                }
+                let info = infos[id];
+                let live = placed
+                    .iter()
+                    .cloned()
+                    .filter(|&other| {
+                        let other = &graph.physical_resources[other];
+                        other.first <= resource.last && resource.first <= other.last
+                    })
+                    .collect::<Vec<_>>();
+
+                // Lowest offset not overlapping any live resource.
+                let mut offset = 0;
+                loop {
+                    let conflict = live.iter().find(|&&other| {
+                        offset < offsets[other] + infos[other].size
+                            && offsets[other] < offset + info.size
+                    });
+                    match conflict {
+                        Some(&other) => {
+                            let end = offsets[other] + infos[other].size;
+                            offset = (end + info.alignment - 1) / info.alignment * info.alignment;
+                        }
+                        None => break,
+                    }
+                }
+
+                for &other in &placed {
+                    if offset < offsets[other] + infos[other].size
+                        && offsets[other] < offset + info.size
+                    {
+                        aliased[other] = true;
+                        aliased[id] = true;
+                    }
+                }
+
+                offsets[id] = offset;
+                size = size.max(offset + info.size);
+                alignment = alignment.max(info.alignment);
+                placed.push(id);
+            }
+
+            if !placed.is_empty() {
+                let allocation =
+                    engine.allocate_memory(HeapType::Default, class, size, alignment)?;
+                for &id in &placed {
+                    offsets[id] += allocation.offset();
+                }
+                memory.push((class, allocation));
+            }
+        }
+
+        let physical_resources = graph
+            .physical_resources
+            .iter()
+            .enumerate()
+            .map(|(id, resource)| {
+                let class = ResourceClass::of(&resource.desc);
+                let allocation = &memory
+                    .iter()
+                    .find(|&&(memory_class, _)| memory_class == class)
+                    .unwrap()
+                    .1;
+                let state = resource.initial_access.state();
+                let resource = engine.device.create_placed_resource(
+                    allocation.heap(),
+                    offsets[id],
+                    &resource.desc,
+                    state,
+                    resource.clear_value,
+                )?;
+                engine.states.register(&resource, state);
+                Ok(resource)
+            })
+            .collect::<Result<_, EngineError>>()?;
+
+        Ok(RenderGraph {
+            graph,
+            physical_resources,
+            aliased,
+            _memory: memory
+                .into_iter()
+                .map(|(_, allocation)| allocation)
+                .collect(),
+        })
+    }
+
+    pub fn compiled(&self) -> &CompiledGraph<P> {
+        &self.graph
+    }
+
+    /// Physical resource backing a transient resource, e.g. for view creation.
+    ///
+    /// Panics for imported or culled resources.
+    pub fn resource(&self, handle: ResourceHandle) -> &B::Resource {
+        let resource = &self.graph.resources[handle.index];
+        let physical = self.graph.physical[handle.index]
+            .unwrap_or_else(|| panic!("`{}` has no physical resource", resource.name));
+        &self.physical_resources[physical]
+    }
+
+    /// Stop tracking the states of the physical resources before dropping the graph.
+    pub fn unregister(&self, states: &mut StateTracker<B::Resource>) {
+        for resource in &self.physical_resources {
+            states.unregister(resource);
+        }
+    }
+
+    /// Record all passes in execution order.
+    ///
+    /// Barriers of each pass are flushed before calling `record` with the pass tag.
+    /// `imports` binds the imported resources for this frame.
+    pub fn execute<F>(
+        &self,
+        engine: &mut Engine<B>,
+        cmd_list: &B::CommandList,
+        imports: &[(ResourceHandle, &B::Resource)],
+        mut record: F,
+    ) where
+        F: FnMut(P, &B::CommandList),
+    {
+        let lookup = |index: usize| -> &B::Resource {
+            match self.graph.physical[index] {
+                Some(physical) => &self.physical_resources[physical],
+                None => imports
+                    .iter()
+                    .find(|&&(handle, _)| handle.index == index)
+                    .map(|&(_, resource)| resource)
+                    .unwrap_or_else(|| panic!("`{}` not bound", self.graph.resources[index].name)),
+            }
+        };
+
+        for (position, &pass_id) in self.graph.order.iter().enumerate() {
+            // Aliased memory is (re)acquired on first use in each frame.
+            let aliasing = self
+                .graph
+                .physical_resources
+                .iter()
+                .enumerate()
+                .filter(|&(id, resource)| self.aliased[id] && resource.first == position)
+                .map(|(id, _)| Barrier::Aliasing {
+                    before: None,
+                    after: Some(&self.physical_resources[id]),
+                })
+                .collect::<Vec<_>>();
+            if !aliasing.is_empty() {
+                cmd_list.resource_barrier(&aliasing);
+            }
+
+            let pass = &self.graph.passes[pass_id];
+            for access in &pass.accesses {
+                let resource = lookup(access.handle.index);
+                engine.states.use_resource(resource, access.access.state());
+            }
+            engine.flush_barriers(cmd_list);
+
+            record(pass.tag, cmd_list);
+        }
+
+        for (index, node) in self.graph.resources.iter().enumerate() {
+            if let ResourceKind::Imported { final_access } = node.kind {
+                if self.graph.lifetimes[index].is_some() {
+                    engine
+                        .states
+                        .use_resource(lookup(index), final_access.state());
+                }
+            }
+        }
+        engine.flush_barriers(cmd_list);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::null::Null;
+    use backend::{Format, PrimitiveTopology, ResourceFlags};
+    use config::EngineConfig;
+
+    fn target(format: Format) -> ResourceDesc {
+        ResourceDesc {
+            flags: ResourceFlags::ALLOW_RENDER_TARGET,
+            ..ResourceDesc::texture_2d(16, 16, format, 1)
+        }
+    }
+
+    fn clear(value: f32) -> Option<ClearValue> {
+        Some(ClearValue::Color(Format::R8G8B8A8Unorm, [value; 4]))
+    }
+
+    #[test]
+    fn cull_unreachable_passes() {
+        let build = |keep_debug: bool| {
+            let mut graph = GraphBuilder::new();
+            let backbuffer = graph.import("backbuffer", Access::Present);
+            let color = graph.create_texture("color", target(Format::R8G8B8A8Unorm), None);
+            let debug = if keep_debug {
+                graph.import("debug", Access::PixelShaderResource)
+            } else {
+                graph.create_texture("debug", target(Format::R8G8B8A8Unorm), None)
+            };
+
+            let color = graph
+                .add_pass("main", "main")
+                .write(color, Access::RenderTarget);
+            {
+                let mut pass = graph.add_pass("debug", "debug");
+                pass.read(color, Access::PixelShaderResource);
+                pass.write(debug, Access::RenderTarget);
+            }
+            {
+                let mut pass = graph.add_pass("present", "present");
+                pass.read(color, Access::PixelShaderResource);
+                pass.write(backbuffer, Access::RenderTarget);
+            }
+            graph.compile().unwrap()
+        };
+
+        let graph = build(false);
+        assert_eq!(graph.pass_order(), vec!["main", "present"]);
+        assert_eq!(graph.alive, vec![true, false, true]);
+        assert_eq!(graph.lifetimes[2], None);
+        assert_eq!(graph.num_physical_resources(), 1);
+
+        let graph = build(true);
+        assert_eq!(graph.pass_order(), vec!["main", "debug", "present"]);
+        assert_eq!(graph.num_physical_resources(), 1);
+    }
+
+    #[test]
+    fn order_reads_before_overwrites() {
+        let mut graph = GraphBuilder::new();
+        let backbuffer = graph.import("backbuffer", Access::Present);
+        let color = graph.create_texture("color", target(Format::R8G8B8A8Unorm), None);
+
+        let first = graph
+            .add_pass("clear", "clear")
+            .write(color, Access::RenderTarget);
+        // Declared before the reader of the first version.
+        let second = graph
+            .add_pass("overwrite", "overwrite")
+            .write(first, Access::RenderTarget);
+        let backbuffer = {
+            let mut pass = graph.add_pass("read", "read");
+            pass.read(first, Access::PixelShaderResource);
+            pass.write(backbuffer, Access::RenderTarget)
+        };
+        {
+            let mut pass = graph.add_pass("present", "present");
+            pass.read(second, Access::PixelShaderResource);
+            pass.write(backbuffer, Access::RenderTarget);
+        }
+
+        let graph = graph.compile().unwrap();
+        // Write after read delays `overwrite`, read after write orders `present` last.
+        assert_eq!(
+            graph.pass_order(),
+            vec!["clear", "read", "overwrite", "present"]
+        );
+    }
+
+    #[test]
+    fn reuse_physical_resources() {
+        let mut graph = GraphBuilder::new();
+        let backbuffer = graph.import("backbuffer", Access::Present);
+        let rgba = target(Format::R8G8B8A8Unorm);
+        let chain = [
+            graph.create_texture("a", rgba, clear(0.0)),
+            graph.create_texture("b", rgba, clear(0.0)),
+            graph.create_texture("c", rgba, clear(0.0)),
+            graph.create_texture("d", rgba, clear(1.0)),
+            graph.create_texture("e", target(Format::R32Float), clear(0.0)),
+        ];
+
+        // Each pass reads the previous resource of the chain and writes the next one.
+        let mut previous = None;
+        for (i, &handle) in chain.iter().enumerate() {
+            let mut pass = graph.add_pass(&format!("pass {}", i), i);
+            if let Some(previous) = previous {
+                pass.read(previous, Access::PixelShaderResource);
+            }
+            previous = Some(pass.write(handle, Access::RenderTarget));
+        }
+        {
+            let mut pass = graph.add_pass("present", chain.len());
+            pass.read(previous.unwrap(), Access::PixelShaderResource);
+            pass.write(backbuffer, Access::RenderTarget);
+        }
+
+        let graph = graph.compile().unwrap();
+        // `c` reuses `a` as their lifetimes are disjoint, `b` overlaps `a`, `d` has
+        // a different clear value and `e` a different description.
+        assert_eq!(
+            graph.lifetimes,
+            vec![
+                Some((5, 5)),
+                Some((0, 1)),
+                Some((1, 2)),
+                Some((2, 3)),
+                Some((3, 4)),
+                Some((4, 5)),
+            ]
+        );
+        assert_eq!(
+            graph.physical,
+            vec![None, Some(0), Some(1), Some(0), Some(2), Some(3)]
+        );
+        assert_eq!(graph.num_physical_resources(), 4);
+    }
+
+    #[test]
+    fn alias_disjoint_memory() {
+        let mut engine = Engine::<Null>::new(&EngineConfig::default()).unwrap();
+        let allocator = engine.device.create_command_allocator().unwrap();
+        let cmd_list = engine.device.create_command_list(&allocator).unwrap();
+        cmd_list.reset(&allocator);
+        let backbuffer = engine
+            .create_committed_resource(
+                HeapType::Default,
+                &target(Format::R8G8B8A8Unorm),
+                ResourceStates::PRESENT,
+                None,
+            )
+            .unwrap();
+
+        let mut graph = GraphBuilder::new();
+        let output = graph.import("backbuffer", Access::Present);
+        let a = graph.create_texture("a", target(Format::R8G8B8A8Unorm), None);
+        let b = graph.create_texture("b", target(Format::R8G8B8A8UnormSrgb), None);
+        let c = graph.create_texture("c", target(Format::R32Float), None);
+        let a = graph.add_pass("a", 0).write(a, Access::RenderTarget);
+        let b = {
+            let mut pass = graph.add_pass("b", 1);
+            pass.read(a, Access::PixelShaderResource);
+            pass.write(b, Access::RenderTarget)
+        };
+        let c = {
+            let mut pass = graph.add_pass("c", 2);
+            pass.read(b, Access::PixelShaderResource);
+            pass.write(c, Access::RenderTarget)
+        };
+        {
+            let mut pass = graph.add_pass("present", 3);
+            pass.read(c, Access::PixelShaderResource);
+            pass.write(output, Access::RenderTarget);
+        }
+
+        let graph = RenderGraph::new(&mut engine, graph.compile().unwrap()).unwrap();
+        assert_eq!(graph.compiled().num_physical_resources(), 3);
+        // `c` is placed in the memory of `a`, `b` is alive during both.
+        assert_eq!(graph.aliased, vec![true, false, true]);
+
+        let start = engine.device.calls().len();
+        graph.execute(
+            &mut engine,
+            &cmd_list,
+            &[(output, &backbuffer)],
+            |_, cmd_list| cmd_list.set_primitive_topology(PrimitiveTopology::TriangleList),
+        );
+        // Arguments without the command list id.
+        let calls = engine.device.calls()[start..]
+            .iter()
+            .map(|call| format!("{}({})", call.name, call.args.split(": ").last().unwrap()))
+            .collect::<Vec<_>>();
+        let barriers = "resource_barrier(1 barriers)";
+        let pass = "set_primitive_topology(TriangleList)";
+        assert_eq!(
+            calls,
+            vec![
+                // Aliasing barrier for `a`.
+                barriers,
+                pass,
+                // `a` to shader resource
+                barriers,
+                pass,
+                // Aliasing barrier for `c`, `b` to shader resource
+                barriers,
+                barriers,
+                pass,
+                // `c` to shader resource, `backbuffer` to render target
+                "resource_barrier(2 barriers)",
+                pass,
+                // `backbuffer` to present
+                barriers,
+            ]
+        );
+        assert_eq!(engine.validation_errors(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn dump_graph() {
+        let mut graph = GraphBuilder::new();
+        let backbuffer = graph.import("backbuffer", Access::Present);
+        let color = graph.create_texture("color", target(Format::R8G8B8A8Unorm), None);
+        let unused = graph.create_texture("unused", target(Format::R8G8B8A8Unorm), None);
+        graph
+            .add_pass("unused", ())
+            .write(unused, Access::RenderTarget);
+        let color = graph
+            .add_pass("main", ())
+            .write(color, Access::RenderTarget);
+        {
+            let mut pass = graph.add_pass("present", ());
+            pass.read(color, Access::PixelShaderResource);
+            pass.write(backbuffer, Access::RenderTarget);
+        }
+        let graph = graph.compile().unwrap();
+
+        assert_eq!(
+            graph.to_dot(),
+            "digraph frame {
+    rankdir=LR;
+    pass0 [shape=box, style=dashed, label=\"unused (culled)\"];
+    pass1 [shape=box, style=solid, label=\"0: main\"];
+    pass2 [shape=box, style=solid, label=\"1: present\"];
+    res0_0 [shape=doubleoctagon, label=\"backbuffer v0\"];
+    res0_1 [shape=doubleoctagon, label=\"backbuffer v1\"];
+    res1_0 [shape=ellipse, label=\"color v0\"];
+    res1_1 [shape=ellipse, label=\"color v1\"];
+    res2_0 [shape=ellipse, label=\"unused v0\"];
+    res2_1 [shape=ellipse, label=\"unused v1\"];
+    pass0 -> res2_1 [label=\"render_target\"];
+    pass1 -> res1_1 [label=\"render_target\"];
+    res1_1 -> pass2 [label=\"pixel_shader_resource\"];
+    pass2 -> res0_1 [label=\"render_target\"];
+}
+"
+        );
+
+        assert_eq!(
+            graph.to_json(),
+            json!({
+                "passes": [
+                    {
+                        "name": "unused",
+                        "order": null,
+                        "culled": true,
+                        "accesses": [
+                            { "resource": "unused", "version": 0, "access": "render_target", "write": true },
+                        ],
+                    },
+                    {
+                        "name": "main",
+                        "order": 0,
+                        "culled": false,
+                        "accesses": [
+                            { "resource": "color", "version": 0, "access": "render_target", "write": true },
+                        ],
+                    },
+                    {
+                        "name": "present",
+                        "order": 1,
+                        "culled": false,
+                        "accesses": [
+                            { "resource": "color", "version": 1, "access": "pixel_shader_resource", "write": false },
+                            { "resource": "backbuffer", "version": 0, "access": "render_target", "write": true },
+                        ],
+                    },
+                ],
+                "resources": [
+                    {
+                        "name": "backbuffer",
+                        "kind": { "type": "imported", "final_access": "present" },
+                        "versions": 2,
+                        "output": true,
+                        "lifetime": [1, 1],
+                        "physical": null,
+                    },
+                    {
+                        "name": "color",
+                        "kind": {
+                            "type": "transient",
+                            "width": 16,
+                            "height": 16,
+                            "format": "R8G8B8A8Unorm",
+                            "samples": 1,
+                        },
+                        "versions": 2,
+                        "output": false,
+                        "lifetime": [0, 1],
+                        "physical": 0,
+                    },
+                    {
+                        "name": "unused",
+                        "kind": {
+                            "type": "transient",
+                            "width": 16,
+                            "height": 16,
+                            "format": "R8G8B8A8Unorm",
+                            "samples": 1,
+                        },
+                        "versions": 2,
+                        "output": false,
+                        "lifetime": null,
+                        "physical": null,
+                    },
+                ],
+                "physical_resources": 1,
+            })
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Split-sum BRDF lookup table.
+//!
+//! The integral of the specular BRDF (`brdf.hlsl`) times `n_dot_l` over the
+//! hemisphere, for a white environment. The Schlick fresnel is split into a scale
+//! and a bias of the specular reflectance, `f0 * scale + bias`. Integrals are
+//! computed by importance sampling the GGX distribution.
+
+use backend::Format;
+use cgmath::{InnerSpace, Vector3};
+use ibl::{hammersley, sample_ggx, texel_bytes};
+use reference::brdf::{self, MIN_ROUGHNESS};
+use texture::TextureData;
+
+/// Entries of the table along each axis.
+///
+/// Must match with `BRDF_LUT_SIZE` in `environment.hlsl`.
+pub const BRDF_LUT_SIZE: u32 = 32;
+
+/// Importance samples per table entry.
+const BRDF_LUT_SAMPLES: u32 = 1024;
+
+/// Smallest `n_dot_v`, like the clamping of `evaluate_brdf`.
+const MIN_N_DOT_V: f32 = 1e-4;
+
+/// `n_dot_v` and perceptual roughness of a table entry.
+fn table_coords(x: u32, y: u32) -> (f32, f32) {
+    let max_coord = (BRDF_LUT_SIZE - 1) as f32;
+    (x as f32 / max_coord, y as f32 / max_coord)
+}
+
+/// Fresnel scale and bias of the specular BRDF integral.
+pub fn integrate(n_dot_v: f32, roughness: f32) -> [f32; 2] {
+    let n_dot_v = n_dot_v.max(MIN_N_DOT_V).min(1.0);
+    let roughness = roughness.max(MIN_ROUGHNESS);
+    let alpha = roughness * roughness;
+    let view = Vector3::new((1.0 - n_dot_v * n_dot_v).sqrt(), 0.0, n_dot_v);
+
+    let (mut scale, mut bias) = (0.0, 0.0);
+    for i in 0..BRDF_LUT_SAMPLES {
+        let (u1, u2) = hammersley(i, BRDF_LUT_SAMPLES);
+        let half_dir = sample_ggx(alpha, u1, u2);
+        let v_dot_h = view.dot(half_dir);
+        let light = half_dir * (2.0 * v_dot_h) - view;
+        if light.z <= 0.0 || v_dot_h <= 0.0 {
+            continue;
+        }
+
+        // BRDF times `n_dot_l` divided by the PDF `d * n_dot_h / (4 * v_dot_h)`.
+        let n_dot_h = half_dir.z;
+        let weight =
+            brdf::v_smith_ggx_correlated(n_dot_v, light.z, alpha) * light.z * 4.0 * v_dot_h
+                / n_dot_h;
+        let fresnel = (1.0 - v_dot_h).powf(5.0);
+        scale += (1.0 - fresnel) * weight;
+        bias += fresnel * weight;
+    }
+
+    let num_samples = BRDF_LUT_SAMPLES as f32;
+    [scale / num_samples, bias / num_samples]
+}
+
+/// Table entries, row-major with `n_dot_v` along `x` and the perceptual roughness
+/// along `y`, both in `[0, 1]`.
+pub fn brdf_lut() -> Vec<[f32; 2]> {
+    (0..BRDF_LUT_SIZE * BRDF_LUT_SIZE)
+        .map(|i| {
+            let (n_dot_v, roughness) = table_coords(i % BRDF_LUT_SIZE, i / BRDF_LUT_SIZE);
+            integrate(n_dot_v, roughness)
+        })
+        .collect()
+}
+
+/// Texture of the table entries.
+pub fn texture(lut: &[[f32; 2]]) -> TextureData {
+    TextureData {
+        format: Format::R32G32Float,
+        width: BRDF_LUT_SIZE,
+        height: BRDF_LUT_SIZE,
+        levels: vec![texel_bytes(lut).to_vec()],
+    }
+}
+
+/// `brdf_lut_lookup`, bilinear interpolation of the table entries.
+pub fn lookup(lut: &[[f32; 2]], n_dot_v: f32, roughness: f32) -> [f32; 2] {
+    let saturate = |value: f32| value.max(0.0).min(1.0);
+    let max_coord = (BRDF_LUT_SIZE - 1) as f32;
+    let coord = [
+        saturate(n_dot_v) * max_coord,
+        saturate(roughness) * max_coord,
+    ];
+    let base = [
+        (coord[0] as u32).min(BRDF_LUT_SIZE - 2),
+        (coord[1] as u32).min(BRDF_LUT_SIZE - 2),
+    ];
+    let (fx, fy) = (coord[0] - base[0] as f32, coord[1] - base[1] as f32);
+
+    let entry = |x: u32, y: u32| lut[((base[1] + y) * BRDF_LUT_SIZE + base[0] + x) as usize];
+    let (e00, e10, e01, e11) = (entry(0, 0), entry(1, 0), entry(0, 1), entry(1, 1));
+    // Weighted sums, exact at the texel centers like the texture filtering.
+    let mut value = [0.0; 2];
+    for c in 0..2 {
+        let v0 = e00[c] * (1.0 - fx) + e10[c] * fx;
+        let v1 = e01[c] * (1.0 - fx) + e11[c] * fx;
+        value[c] = v0 * (1.0 - fy) + v1 * fy;
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integrate_roughest() {
+        // `D = 1 / PI` and `V = 0.5 / (n_dot_l + 1)` for `alpha = 1` and `n_dot_v = 1`.
+        // The BRDF integral is `1 - ln(2)`, fresnel only contributes at grazing angles.
+        let [scale, bias] = integrate(1.0, 1.0);
+        assert!((scale - 0.306_82).abs() < 0.005, "{}", scale);
+        assert!(bias.abs() < 0.001, "{}", bias);
+    }
+
+    #[test]
+    fn lookup_corners() {
+        let lut = brdf_lut();
+        let last = BRDF_LUT_SIZE - 1;
+        let corners = [
+            (0.0, 0.0, 0),
+            (1.0, 0.0, last),
+            (0.0, 1.0, last * BRDF_LUT_SIZE),
+            (1.0, 1.0, last * BRDF_LUT_SIZE + last),
+        ];
+        for &(n_dot_v, roughness, entry) in &corners {
+            assert_eq!(lookup(&lut, n_dot_v, roughness), lut[entry as usize]);
+        }
+
+        // Coordinates outside of the table are clamped.
+        assert_eq!(
+            lookup(&lut, -1.0, 2.0),
+            lut[(last * BRDF_LUT_SIZE) as usize]
+        );
+    }
+}
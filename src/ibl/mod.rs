@@ -0,0 +1,365 @@
+//! Image based lighting (IBL) from distant environment maps.
+//!
+//! Equirectangular HDR environment maps are preprocessed on the CPU into the
+//! resources of `environment.hlsl`:
+//!
+//!  * Irradiance: Diffuse lighting as L2 spherical harmonics (`sh`).
+//!  * Specular: Split-sum approximation, the radiance prefiltered with the GGX
+//!              distribution in the mip levels of a cube map (`prefilter`) and the
+//!              integral of the specular BRDF from a lookup table (`brdf_lut`).
+//!
+//! See "Real Shading in Unreal Engine 4" (Karis 2013) and "An Efficient
+//! Representation for Irradiance Environment Maps" (Ramamoorthi and Hanrahan 2001).
+
+use backend::{EngineError, ErrorKind, Format, ResourceDesc};
+use cgmath::{InnerSpace, Vector3};
+use image::hdr::HDRDecoder;
+use std::f32::consts::PI;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::{mem, slice};
+
+pub mod brdf_lut;
+pub mod prefilter;
+pub mod sh;
+
+/// Width and height of the top level of the specular cube map.
+pub const SPECULAR_SIZE: u32 = 128;
+
+/// Mip levels of the specular cube map, level `i` is prefiltered for the perceptual
+/// roughness `i / (SPECULAR_LEVELS - 1)`.
+///
+/// Must match with `SPECULAR_LEVELS` in `environment.hlsl`.
+pub const SPECULAR_LEVELS: u32 = 6;
+
+/// Environment lighting data.
+///
+/// Must match with `_EnvironmentData` in `environment.hlsl`.
+// #[repr(hlsl)]
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct EnvironmentData {
+    /// Irradiance SH coefficients (rgb), see `sh::irradiance`.
+    pub irradiance: [[f32; 4]; 9],
+    /// Scale of the environment radiance.
+    pub intensity: f32,
+    pub _alignment: [f32; 3],
+}
+
+/// Environment map in equirectangular (latitude-longitude) layout.
+///
+/// The top row points along `+y`, the center of the image along `-z`.
+#[derive(Clone, Debug)]
+pub struct EnvironmentMap {
+    pub width: u32,
+    pub height: u32,
+    /// Linear radiance, row-major.
+    pub texels: Vec<[f32; 3]>,
+}
+
+impl EnvironmentMap {
+    /// Import a Radiance HDR (`.hdr`) file.
+    pub fn import(path: &Path) -> Result<Self, EngineError> {
+        File::open(path)
+            .map_err(|err| err.to_string())
+            .and_then(|file| {
+                let decoder =
+                    HDRDecoder::new(BufReader::new(file)).map_err(|err| err.to_string())?;
+                let metadata = decoder.metadata();
+                let texels = decoder.read_image_hdr().map_err(|err| err.to_string())?;
+                Ok(EnvironmentMap {
+                    width: metadata.width,
+                    height: metadata.height,
+                    texels: texels.iter().map(|texel| texel.data).collect(),
+                })
+            })
+            .map_err(|err| {
+                EngineError::new(
+                    "import_environment",
+                    path.display().to_string(),
+                    ErrorKind::Asset(err),
+                )
+            })
+    }
+
+    /// Direction through the image coordinates `u` and `v` in `[0, 1]`.
+    pub fn direction(u: f32, v: f32) -> Vector3<f32> {
+        let phi = (u - 0.5) * 2.0 * PI;
+        let theta = v * PI;
+        Vector3::new(
+            theta.sin() * phi.sin(),
+            theta.cos(),
+            -theta.sin() * phi.cos(),
+        )
+    }
+
+    /// Image coordinates of a normalized direction, inverse of `direction`.
+    pub fn coords(dir: Vector3<f32>) -> (f32, f32) {
+        let u = 0.5 + dir.x.atan2(-dir.z) / (2.0 * PI);
+        let v = dir.y.max(-1.0).min(1.0).acos() / PI;
+        (u, v)
+    }
+
+    /// Direction through the center of a texel.
+    pub fn texel_direction(&self, x: u32, y: u32) -> Vector3<f32> {
+        EnvironmentMap::direction(
+            (x as f32 + 0.5) / self.width as f32,
+            (y as f32 + 0.5) / self.height as f32,
+        )
+    }
+
+    /// Solid angle covered by each texel of row `y`, the rows sum up to `4 * PI`.
+    pub fn texel_solid_angle(&self, y: u32) -> f32 {
+        let theta0 = y as f32 / self.height as f32 * PI;
+        let theta1 = (y + 1) as f32 / self.height as f32 * PI;
+        2.0 * PI / self.width as f32 * (theta0.cos() - theta1.cos())
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Vector3<f32> {
+        Vector3::from(self.texels[(y * self.width + x) as usize])
+    }
+
+    /// Bilinear filtered radiance along a normalized direction, repeating around the
+    /// horizon and clamped at the poles.
+    pub fn sample(&self, dir: Vector3<f32>) -> Vector3<f32> {
+        let (u, v) = EnvironmentMap::coords(dir);
+        let x = u * self.width as f32 - 0.5;
+        let y = v * self.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+
+        let texel = |x: i64, y: i64| {
+            let width = self.width as i64;
+            let x = (((x % width) + width) % width) as u32;
+            let y = y.max(0).min(self.height as i64 - 1) as u32;
+            self.get(x, y)
+        };
+        let (x0, y0) = (x0 as i64, y0 as i64);
+        let top = texel(x0, y0) * (1.0 - fx) + texel(x0 + 1, y0) * fx;
+        let bottom = texel(x0, y0 + 1) * (1.0 - fx) + texel(x0 + 1, y0 + 1) * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+
+    /// Box filtered reduction to half the extent, rounded up.
+    pub fn downsample(&self) -> Self {
+        let width = (self.width + 1) / 2;
+        let height = (self.height + 1) / 2;
+        let mut texels = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = Vector3::new(0.0, 0.0, 0.0);
+                for &(dx, dy) in &[(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let sx = (2 * x + dx).min(self.width - 1);
+                    let sy = (2 * y + dy).min(self.height - 1);
+                    sum += self.get(sx, sy);
+                }
+                texels.push((sum * 0.25).into());
+            }
+        }
+        EnvironmentMap {
+            width,
+            height,
+            texels,
+        }
+    }
+}
+
+/// Cube map with a mip chain, faces in `+x, -x, +y, -y, +z, -z` order.
+#[derive(Clone, Debug)]
+pub struct CubeMap {
+    pub size: u32,
+    pub mip_levels: u32,
+    /// RGBA texels of each subresource, ordered by face and then by level.
+    pub texels: Vec<Vec<[f32; 4]>>,
+}
+
+impl CubeMap {
+    pub const FORMAT: Format = Format::R32G32B32A32Float;
+
+    /// Single texel cube map with constant `radiance`.
+    pub fn uniform(radiance: [f32; 3]) -> Self {
+        let texel = [radiance[0], radiance[1], radiance[2], 1.0];
+        CubeMap {
+            size: 1,
+            mip_levels: 1,
+            texels: vec![vec![texel]; 6],
+        }
+    }
+
+    pub fn level_size(&self, level: u32) -> u32 {
+        (self.size >> level).max(1)
+    }
+
+    pub fn desc(&self) -> ResourceDesc {
+        ResourceDesc {
+            depth_or_array_size: 6,
+            mip_levels: self.mip_levels as _,
+            ..ResourceDesc::texture_2d(self.size, self.size, CubeMap::FORMAT, 1)
+        }
+    }
+
+    /// Upload data of all subresources.
+    pub fn subresources(&self) -> Vec<&[u8]> {
+        self.texels.iter().map(|texels| texel_bytes(texels)).collect()
+    }
+
+    /// `SampleLevel` with trilinear filtering along a normalized direction.
+    ///
+    /// Bilinear filtering is clamped to the face, the GPU filters across the edges.
+    pub fn sample(&self, dir: Vector3<f32>, lod: f32) -> Vector3<f32> {
+        let (face, u, v) = cube_face_coords(dir);
+        let lod = lod.max(0.0).min((self.mip_levels - 1) as f32);
+        let level = (lod as u32).min(self.mip_levels.saturating_sub(2));
+        let t = lod - level as f32;
+
+        let bilinear = |level: u32| {
+            let size = self.level_size(level);
+            let texels = &self.texels[(face * self.mip_levels + level) as usize];
+            let x = u * size as f32 - 0.5;
+            let y = v * size as f32 - 0.5;
+            let (x0, y0) = (x.floor(), y.floor());
+            let (fx, fy) = (x - x0, y - y0);
+            let texel = |x: i64, y: i64| {
+                let x = x.max(0).min(size as i64 - 1);
+                let y = y.max(0).min(size as i64 - 1);
+                let texel = texels[(y * size as i64 + x) as usize];
+                Vector3::new(texel[0], texel[1], texel[2])
+            };
+            let (x0, y0) = (x0 as i64, y0 as i64);
+            let top = texel(x0, y0) * (1.0 - fx) + texel(x0 + 1, y0) * fx;
+            let bottom = texel(x0, y0 + 1) * (1.0 - fx) + texel(x0 + 1, y0 + 1) * fx;
+            top * (1.0 - fy) + bottom * fy
+        };
+
+        if self.mip_levels == 1 {
+            return bilinear(0);
+        }
+        bilinear(level) * (1.0 - t) + bilinear(level + 1) * t
+    }
+}
+
+/// Direction through the texture coordinates `u` and `v` in `[0, 1]` of a cube face.
+pub fn cube_direction(face: u32, u: f32, v: f32) -> Vector3<f32> {
+    let s = 2.0 * u - 1.0;
+    let t = 2.0 * v - 1.0;
+    let dir = match face {
+        0 => Vector3::new(1.0, -t, -s),
+        1 => Vector3::new(-1.0, -t, s),
+        2 => Vector3::new(s, 1.0, t),
+        3 => Vector3::new(s, -1.0, -t),
+        4 => Vector3::new(s, -t, 1.0),
+        _ => Vector3::new(-s, -t, -1.0),
+    };
+    dir.normalize()
+}
+
+/// Face and texture coordinates of a direction, inverse of `cube_direction`.
+pub fn cube_face_coords(dir: Vector3<f32>) -> (u32, f32, f32) {
+    let a = Vector3::new(dir.x.abs(), dir.y.abs(), dir.z.abs());
+    let (face, s, t, major) = if a.x >= a.y && a.x >= a.z {
+        if dir.x >= 0.0 {
+            (0, -dir.z, -dir.y, a.x)
+        } else {
+            (1, dir.z, -dir.y, a.x)
+        }
+    } else if a.y >= a.z {
+        if dir.y >= 0.0 {
+            (2, dir.x, dir.z, a.y)
+        } else {
+            (3, dir.x, -dir.z, a.y)
+        }
+    } else if dir.z >= 0.0 {
+        (4, dir.x, -dir.y, a.z)
+    } else {
+        (5, -dir.x, -dir.y, a.z)
+    };
+    (face, 0.5 * (s / major + 1.0), 0.5 * (t / major + 1.0))
+}
+
+/// Preprocessed lighting of an environment, see the module documentation.
+#[derive(Clone, Debug)]
+pub struct Environment {
+    /// Irradiance SH coefficients, see `sh::irradiance`.
+    pub irradiance: sh::ShCoefficients,
+    /// Prefiltered radiance, see `prefilter`.
+    pub specular: CubeMap,
+    /// Scale of the environment radiance.
+    pub intensity: f32,
+}
+
+impl Environment {
+    /// Preprocess an environment map, scaling its radiance by `intensity`.
+    pub fn new(map: &EnvironmentMap, intensity: f32) -> Self {
+        Environment {
+            irradiance: sh::irradiance(&sh::project(map)),
+            specular: prefilter::prefilter(map),
+            intensity,
+        }
+    }
+
+    /// Environment with the same `radiance` along all directions.
+    pub fn uniform(radiance: [f32; 3], intensity: f32) -> Self {
+        let mut coefficients = [Vector3::new(0.0, 0.0, 0.0); 9];
+        coefficients[0] = Vector3::from(radiance) * (4.0 * PI * sh::basis(Vector3::unit_y())[0]);
+        Environment {
+            irradiance: sh::irradiance(&coefficients),
+            specular: CubeMap::uniform(radiance),
+            intensity,
+        }
+    }
+
+    pub fn data(&self) -> EnvironmentData {
+        let mut irradiance = [[0.0; 4]; 9];
+        for (dst, src) in irradiance.iter_mut().zip(self.irradiance.iter()) {
+            *dst = src.extend(0.0).into();
+        }
+        EnvironmentData {
+            irradiance,
+            intensity: self.intensity,
+            _alignment: [0.0; 3],
+        }
+    }
+}
+
+/// Point `i` of the Hammersley set with `n` points in `[0, 1)^2`.
+fn hammersley(i: u32, n: u32) -> (f32, f32) {
+    let mut bits = i;
+    bits = (bits << 16) | (bits >> 16);
+    bits = ((bits & 0x5555_5555) << 1) | ((bits & 0xAAAA_AAAA) >> 1);
+    bits = ((bits & 0x3333_3333) << 2) | ((bits & 0xCCCC_CCCC) >> 2);
+    bits = ((bits & 0x0F0F_0F0F) << 4) | ((bits & 0xF0F0_F0F0) >> 4);
+    bits = ((bits & 0x00FF_00FF) << 8) | ((bits & 0xFF00_FF00) >> 8);
+    (i as f32 / n as f32, bits as f32 * (1.0 / 4_294_967_296.0))
+}
+
+/// Half vector by importance sampling the GGX distribution around `+z`.
+///
+/// The PDF of the sample is `d_ggx(n_dot_h, alpha) * n_dot_h`.
+fn sample_ggx(alpha: f32, u1: f32, u2: f32) -> Vector3<f32> {
+    let phi = 2.0 * PI * u1;
+    let cos_theta = ((1.0 - u2) / (1.0 + (alpha * alpha - 1.0) * u2)).sqrt();
+    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+    Vector3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta)
+}
+
+/// Orthonormal basis with `normal` as third axis.
+fn tangent_frame(normal: Vector3<f32>) -> (Vector3<f32>, Vector3<f32>) {
+    let up = if normal.y.abs() < 0.999 {
+        Vector3::unit_y()
+    } else {
+        Vector3::unit_x()
+    };
+    let tangent = up.cross(normal).normalize();
+    (tangent, normal.cross(tangent))
+}
+
+/// Raw bytes of tightly packed texels.
+fn texel_bytes<T: Copy>(texels: &[T]) -> &[u8] {
+    unsafe {
+        slice::from_raw_parts(
+            texels.as_ptr() as *const u8,
+            texels.len() * mem::size_of::<T>(),
+        )
+    }
+}
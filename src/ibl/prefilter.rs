@@ -0,0 +1,149 @@
+//! GGX prefiltering of the specular environment lighting.
+//!
+//! Each mip level of the cube map stores the radiance convolved with the GGX
+//! distribution of its roughness, assuming `n = v = r` and weighted by `n_dot_l`
+//! (Karis 2013). Samples are importance sampled and read from the mip level of the
+//! environment map matching their solid angle, which avoids noise with few samples
+//! ("GPU-Based Importance Sampling", Colbert and Krivanek 2007).
+
+use cgmath::{InnerSpace, Vector3};
+use ibl::{
+    cube_direction, hammersley, sample_ggx, tangent_frame, CubeMap, EnvironmentMap,
+    SPECULAR_LEVELS, SPECULAR_SIZE,
+};
+use reference::brdf::{self, MIN_ROUGHNESS};
+use std::f32::consts::PI;
+
+/// Importance samples per texel of the rough levels.
+const PREFILTER_SAMPLES: u32 = 128;
+
+/// Importance sample of the GGX lobe in its local frame.
+struct LobeSample {
+    /// Light direction around `+z`.
+    light: Vector3<f32>,
+    /// Mip level of the environment map covering the solid angle of the sample.
+    lod: f32,
+}
+
+/// Environment map and its box filtered mip chain.
+struct MipChain {
+    levels: Vec<EnvironmentMap>,
+}
+
+impl MipChain {
+    fn new(map: &EnvironmentMap) -> Self {
+        let mut levels = Vec::new();
+        let mut level = map.clone();
+        while level.width > 1 || level.height > 1 {
+            let next = level.downsample();
+            levels.push(level);
+            level = next;
+        }
+        levels.push(level);
+        MipChain { levels }
+    }
+
+    /// Average solid angle of a texel of the top level.
+    fn texel_solid_angle(&self) -> f32 {
+        let map = &self.levels[0];
+        4.0 * PI / (map.width * map.height) as f32
+    }
+
+    /// Mip level with texels covering `solid_angle`.
+    fn lod(&self, solid_angle: f32) -> f32 {
+        let lod = 0.5 * (solid_angle / self.texel_solid_angle()).log2();
+        lod.max(0.0).min((self.levels.len() - 1) as f32)
+    }
+
+    /// Trilinear filtered radiance along a normalized direction.
+    fn sample(&self, dir: Vector3<f32>, lod: f32) -> Vector3<f32> {
+        let level = (lod as usize).min(self.levels.len() - 1);
+        let t = lod - level as f32;
+        let radiance = self.levels[level].sample(dir);
+        if t > 0.0 && level + 1 < self.levels.len() {
+            radiance * (1.0 - t) + self.levels[level + 1].sample(dir) * t
+        } else {
+            radiance
+        }
+    }
+}
+
+/// Samples of the lobe with perceptual `roughness` in a cube map of `size`.
+fn lobe_samples(chain: &MipChain, roughness: f32, size: u32) -> Vec<LobeSample> {
+    let roughness = roughness.max(MIN_ROUGHNESS);
+    let alpha = roughness * roughness;
+    let texel_solid_angle = 4.0 * PI / (6 * size * size) as f32;
+
+    (0..PREFILTER_SAMPLES)
+        .filter_map(|i| {
+            let (u1, u2) = hammersley(i, PREFILTER_SAMPLES);
+            let half_dir = sample_ggx(alpha, u1, u2);
+            let light = half_dir * (2.0 * half_dir.z) - Vector3::unit_z();
+            if light.z <= 0.0 {
+                return None;
+            }
+            // PDF of the light direction, `d * n_dot_h / (4 * v_dot_h)` with `n = v`.
+            let pdf = brdf::d_ggx(half_dir.z, alpha) / 4.0;
+            let solid_angle = 1.0 / (PREFILTER_SAMPLES as f32 * pdf);
+            Some(LobeSample {
+                light,
+                lod: chain.lod(solid_angle.max(texel_solid_angle)),
+            })
+        })
+        .collect()
+}
+
+/// Prefilter the radiance of an environment map into the levels of the specular
+/// cube map.
+///
+/// The top level isn't convolved, only filtered to the resolution of the cube map.
+pub fn prefilter(map: &EnvironmentMap) -> CubeMap {
+    let chain = MipChain::new(map);
+    let mut texels = Vec::with_capacity((6 * SPECULAR_LEVELS) as usize);
+
+    for face in 0..6 {
+        for level in 0..SPECULAR_LEVELS {
+            let size = (SPECULAR_SIZE >> level).max(1);
+            let roughness = level as f32 / (SPECULAR_LEVELS - 1) as f32;
+            let samples = if level == 0 {
+                Vec::new()
+            } else {
+                lobe_samples(&chain, roughness, size)
+            };
+            let top_lod = chain.lod(4.0 * PI / (6 * size * size) as f32);
+
+            let mut level_texels = Vec::with_capacity((size * size) as usize);
+            for y in 0..size {
+                for x in 0..size {
+                    let normal = cube_direction(
+                        face,
+                        (x as f32 + 0.5) / size as f32,
+                        (y as f32 + 0.5) / size as f32,
+                    );
+                    let radiance = if samples.is_empty() {
+                        chain.sample(normal, top_lod)
+                    } else {
+                        let (tangent, bitangent) = tangent_frame(normal);
+                        let mut sum = Vector3::new(0.0, 0.0, 0.0);
+                        let mut weight = 0.0;
+                        for sample in &samples {
+                            let l = sample.light;
+                            let dir = (tangent * l.x + bitangent * l.y + normal * l.z).normalize();
+                            sum += chain.sample(dir, sample.lod) * l.z;
+                            weight += l.z;
+                        }
+                        sum / weight
+                    };
+                    level_texels.push([radiance.x, radiance.y, radiance.z, 1.0]);
+                }
+            }
+            texels.push(level_texels);
+        }
+    }
+
+    CubeMap {
+        size: SPECULAR_SIZE,
+        mip_levels: SPECULAR_LEVELS,
+        texels,
+    }
+}
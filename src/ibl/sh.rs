@@ -0,0 +1,159 @@
+//! L2 spherical harmonics (SH) of the diffuse environment lighting.
+//!
+//! Radiance is projected onto the 9 real SH basis functions of the first three
+//! bands. Convolution with the clamped cosine lobe turns the radiance coefficients
+//! into irradiance coefficients, evaluated per normal by `sh_evaluate` in
+//! `environment.hlsl`. Irradiance is smooth enough to be approximated by these
+//! bands with an average error of a few percent.
+
+use cgmath::Vector3;
+use ibl::EnvironmentMap;
+use std::f32::consts::PI;
+
+/// Coefficients of the basis functions in `basis` order, per color channel.
+pub type ShCoefficients = [Vector3<f32>; 9];
+
+/// Convolution of each band with the clamped cosine lobe.
+const COSINE_LOBE: [f32; 3] = [PI, 2.0 * PI / 3.0, PI / 4.0];
+
+/// Band of each basis function.
+const BANDS: [usize; 9] = [0, 1, 1, 1, 2, 2, 2, 2, 2];
+
+/// Real SH basis functions along a normalized direction.
+///
+/// Ordered by band `l` and `m = -l..l`: `1, y, z, x, xy, yz, 3z^2 - 1, xz, x^2 - y^2`.
+pub fn basis(dir: Vector3<f32>) -> [f32; 9] {
+    let (x, y, z) = (dir.x, dir.y, dir.z);
+    [
+        0.282_095,
+        0.488_603 * y,
+        0.488_603 * z,
+        0.488_603 * x,
+        1.092_548 * x * y,
+        1.092_548 * y * z,
+        0.315_392 * (3.0 * z * z - 1.0),
+        1.092_548 * x * z,
+        0.546_274 * (x * x - y * y),
+    ]
+}
+
+/// Project the radiance of an environment map, weighting each texel by its solid angle.
+pub fn project(map: &EnvironmentMap) -> ShCoefficients {
+    let mut coefficients = [Vector3::new(0.0, 0.0, 0.0); 9];
+    for y in 0..map.height {
+        let solid_angle = map.texel_solid_angle(y);
+        for x in 0..map.width {
+            let radiance = map.get(x, y) * solid_angle;
+            for (coefficient, basis) in coefficients
+                .iter_mut()
+                .zip(basis(map.texel_direction(x, y)).iter())
+            {
+                *coefficient += radiance * *basis;
+            }
+        }
+    }
+    coefficients
+}
+
+/// Irradiance coefficients of the radiance coefficients.
+pub fn irradiance(radiance: &ShCoefficients) -> ShCoefficients {
+    let mut coefficients = *radiance;
+    for (coefficient, &band) in coefficients.iter_mut().zip(BANDS.iter()) {
+        *coefficient *= COSINE_LOBE[band];
+    }
+    coefficients
+}
+
+/// `sh_evaluate`, reconstruct the function along a normalized direction.
+pub fn evaluate(coefficients: &ShCoefficients, dir: Vector3<f32>) -> Vector3<f32> {
+    coefficients
+        .iter()
+        .zip(basis(dir).iter())
+        .fold(Vector3::new(0.0, 0.0, 0.0), |sum, (coefficient, basis)| {
+            sum + coefficient * *basis
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::InnerSpace;
+
+    /// Environment map with the radiance of `f` along each texel direction.
+    fn environment<F: Fn(Vector3<f32>) -> f32>(f: F) -> EnvironmentMap {
+        let (width, height) = (256, 128);
+        let mut texels = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let dir = EnvironmentMap::direction(
+                    (x as f32 + 0.5) / width as f32,
+                    (y as f32 + 0.5) / height as f32,
+                );
+                let radiance = f(dir);
+                texels.push([radiance, radiance, radiance]);
+            }
+        }
+        EnvironmentMap {
+            width,
+            height,
+            texels,
+        }
+    }
+
+    fn directions() -> Vec<Vector3<f32>> {
+        vec![
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(1.0, 2.0, -3.0).normalize(),
+            Vector3::new(-0.5, 0.5, 0.7).normalize(),
+        ]
+    }
+
+    fn irradiance_along(map: &EnvironmentMap, dir: Vector3<f32>) -> f32 {
+        evaluate(&irradiance(&project(map)), dir).x
+    }
+
+    #[test]
+    fn constant_environment() {
+        let map = environment(|_| 2.0);
+        for dir in directions() {
+            let irradiance = irradiance_along(&map, dir);
+            assert!(
+                (irradiance - 2.0 * PI).abs() < 1e-3,
+                "{:?}: {}",
+                dir,
+                irradiance
+            );
+        }
+    }
+
+    #[test]
+    fn linear_environment() {
+        // Bands 0 and 1 only, irradiance is exact: `PI + 2 * PI / 3 * dot(n, axis)`.
+        let axis = Vector3::new(0.3, -0.8, 0.5).normalize();
+        let map = environment(|dir| 1.0 + dir.dot(axis));
+        for dir in directions() {
+            let expected = PI + 2.0 * PI / 3.0 * dir.dot(axis);
+            let irradiance = irradiance_along(&map, dir);
+            assert!(
+                (irradiance - expected).abs() < 1e-3,
+                "{:?}: {}",
+                dir,
+                irradiance
+            );
+        }
+    }
+
+    #[test]
+    fn cosine_lobe() {
+        // Irradiance along the lobe is `2 * PI / 3`, nothing opposite to it. The
+        // truncated higher bands cause a small error.
+        let axis = Vector3::new(0.3, -0.8, 0.5).normalize();
+        let map = environment(|dir| dir.dot(axis).max(0.0));
+        let along = irradiance_along(&map, axis);
+        let opposite = irradiance_along(&map, -axis);
+        assert!((along - 2.0 * PI / 3.0).abs() < 0.05, "{}", along);
+        assert!(opposite.abs() < 0.05, "{}", opposite);
+    }
+}
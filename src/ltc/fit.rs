@@ -0,0 +1,461 @@
+//! Fitting of the LTC table to the specular BRDF.
+//!
+//! Follows the reference implementation of the paper: Nelder-Mead minimization of
+//! the cubed difference between the BRDF and the distribution, sampling both.
+
+use cgmath::{InnerSpace, Matrix3, SquareMatrix, Vector3};
+use ltc::{LtcData, LTC_SIZE};
+use reference::brdf::{self, MIN_ROUGHNESS};
+use std::f32::consts::PI;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Samples per axis for the numerical integration of the BRDF.
+const FIT_SAMPLES: u32 = 32;
+const FIT_MAX_ITERATIONS: u32 = 100;
+
+/// Lower bound of `alpha` during fitting, keeps the matrices invertible.
+const MIN_FIT_ALPHA: f32 = 1e-4;
+
+/// Roughness and `n_dot_v` of a table entry.
+fn table_coords(x: u32, y: u32) -> (f32, f32) {
+    let roughness = x as f32 / (LTC_SIZE - 1) as f32;
+    let v = y as f32 / (LTC_SIZE - 1) as f32;
+    (roughness, 1.0 - v * v)
+}
+
+/// Fit the table of the specular BRDF, see `ltc::table` for the layout.
+///
+/// Entries are fitted from rough to smooth, starting with the previous fit.
+pub fn fit_table() -> Vec<LtcData> {
+    let mut table = vec![
+        LtcData {
+            inv_matrix: [1.0, 0.0, 0.0, 1.0],
+            magnitude: 0.0,
+            fresnel: 0.0,
+            _alignment: [0.0; 2],
+        };
+        (LTC_SIZE * LTC_SIZE) as usize
+    ];
+
+    let mut ltc = Ltc::identity();
+    let mut smoother_params = [1.0, 1.0, 0.0];
+    for x in (0..LTC_SIZE).rev() {
+        for y in 0..LTC_SIZE {
+            let (roughness, n_dot_v) = table_coords(x, y);
+            let alpha = {
+                let roughness = roughness.max(MIN_ROUGHNESS);
+                (roughness * roughness).max(MIN_FIT_ALPHA)
+            };
+            let theta = n_dot_v.acos().min(1.57);
+            let view = Vector3::new(theta.sin(), 0.0, theta.cos());
+
+            let terms = average_terms(view, alpha);
+
+            // Lobes are isotropic at normal incidence, otherwise centered around the
+            // average direction of the BRDF.
+            let isotropic = y == 0;
+            if isotropic {
+                ltc = Ltc::identity();
+                ltc.params = [smoother_params[0], smoother_params[0], 0.0];
+            } else {
+                let dir = terms.direction;
+                ltc.frame = Matrix3::from_cols(
+                    Vector3::new(dir.z, 0.0, -dir.x),
+                    Vector3::new(0.0, 1.0, 0.0),
+                    dir,
+                );
+            }
+
+            let start = ltc.params;
+            let params = nelder_mead(start, 0.05, 1e-5, FIT_MAX_ITERATIONS, |params| {
+                let mut candidate = ltc.clone();
+                candidate.set_params(params, isotropic);
+                candidate.error(view, alpha, terms.magnitude)
+            });
+            ltc.set_params(params, isotropic);
+            if isotropic {
+                smoother_params = ltc.params;
+            }
+
+            table[(y * LTC_SIZE + x) as usize] = ltc.table_entry(&terms);
+        }
+    }
+
+    table
+}
+
+/// Integrals of the BRDF used for fitting and scaling the LTC.
+struct AverageTerms {
+    magnitude: f32,
+    fresnel: f32,
+    /// Average light direction, projected into the plane of incidence.
+    direction: Vector3<f32>,
+}
+
+/// Specular BRDF with unit fresnel times `n_dot_l`, and the PDF of `sample_brdf`.
+fn eval_brdf(view: Vector3<f32>, light: Vector3<f32>, alpha: f32) -> (f32, f32) {
+    if view.z <= 0.0 || light.z <= 0.0 {
+        return (0.0, 0.0);
+    }
+    let half_dir = (view + light).normalize();
+    let d = brdf::d_ggx(half_dir.z, alpha);
+    let value = d * brdf::v_smith_ggx_correlated(view.z, light.z, alpha) * light.z;
+    let pdf = d * half_dir.z / (4.0 * view.dot(half_dir));
+    (value, pdf)
+}
+
+/// Light direction by importance sampling the GGX distribution.
+fn sample_brdf(view: Vector3<f32>, alpha: f32, u1: f32, u2: f32) -> Vector3<f32> {
+    let phi = 2.0 * PI * u1;
+    let r = alpha * (u2 / (1.0 - u2)).sqrt();
+    let normal = Vector3::new(r * phi.cos(), r * phi.sin(), 1.0).normalize();
+    normal * (2.0 * normal.dot(view)) - view
+}
+
+/// Stratified sample positions in `[0, 1)^2`.
+fn samples() -> impl Iterator<Item = (f32, f32)> {
+    (0..FIT_SAMPLES * FIT_SAMPLES).map(|i| {
+        let n = FIT_SAMPLES as f32;
+        (
+            ((i % FIT_SAMPLES) as f32 + 0.5) / n,
+            ((i / FIT_SAMPLES) as f32 + 0.5) / n,
+        )
+    })
+}
+
+fn average_terms(view: Vector3<f32>, alpha: f32) -> AverageTerms {
+    let mut magnitude = 0.0;
+    let mut fresnel = 0.0;
+    let mut direction = Vector3::new(0.0, 0.0, 0.0);
+
+    for (u1, u2) in samples() {
+        let light = sample_brdf(view, alpha, u1, u2);
+        let (value, pdf) = eval_brdf(view, light, alpha);
+        if pdf > 0.0 {
+            let weight = value / pdf;
+            let half_dir = (view + light).normalize();
+            magnitude += weight;
+            fresnel += weight * (1.0 - view.dot(half_dir).max(0.0)).powf(5.0);
+            direction += light * weight;
+        }
+    }
+
+    let num_samples = (FIT_SAMPLES * FIT_SAMPLES) as f32;
+    direction.y = 0.0;
+    AverageTerms {
+        magnitude: magnitude / num_samples,
+        fresnel: fresnel / num_samples,
+        direction: if direction.magnitude2() > 0.0 {
+            direction.normalize()
+        } else {
+            Vector3::new(0.0, 0.0, 1.0)
+        },
+    }
+}
+
+/// Transformed cosine distribution during fitting.
+#[derive(Clone)]
+struct Ltc {
+    /// Scale of the x and y axis, skew of x along z.
+    params: [f32; 3],
+    /// Orientation of the lobe.
+    frame: Matrix3<f32>,
+}
+
+impl Ltc {
+    fn identity() -> Self {
+        Ltc {
+            params: [1.0, 1.0, 0.0],
+            frame: Matrix3::identity(),
+        }
+    }
+
+    fn set_params(&mut self, params: [f32; 3], isotropic: bool) {
+        let m11 = params[0].max(1e-7);
+        let m22 = params[1].max(1e-7);
+        self.params = if isotropic {
+            [m11, m11, 0.0]
+        } else {
+            [m11, m22, params[2]]
+        };
+    }
+
+    fn matrix(&self) -> Matrix3<f32> {
+        let [m11, m22, m13] = self.params;
+        self.frame
+            * Matrix3::from_cols(
+                Vector3::new(m11, 0.0, 0.0),
+                Vector3::new(0.0, m22, 0.0),
+                Vector3::new(m13, 0.0, 1.0),
+            )
+    }
+
+    /// Table entry of the fitted distribution, see `LtcData`.
+    fn table_entry(&self, terms: &AverageTerms) -> LtcData {
+        let inv = self.matrix().invert().unwrap_or(Matrix3::identity());
+        let scale = inv.y.y;
+        LtcData {
+            // cgmath matrices are column-major, `inv.z.x` is entry (0, 2).
+            inv_matrix: [
+                inv.x.x / scale,
+                inv.z.x / scale,
+                inv.x.z / scale,
+                inv.z.z / scale,
+            ],
+            magnitude: terms.magnitude,
+            fresnel: terms.fresnel,
+            _alignment: [0.0; 2],
+        }
+    }
+
+    /// Error between the BRDF and the distribution scaled to its `magnitude`,
+    /// sampling both.
+    fn error(&self, view: Vector3<f32>, alpha: f32, magnitude: f32) -> f32 {
+        let matrix = self.matrix();
+        let inv_matrix = match matrix.invert() {
+            Some(inv) => inv,
+            None => return ::std::f32::MAX,
+        };
+        let det = matrix.determinant().abs();
+
+        let eval_ltc = |light: Vector3<f32>| {
+            let original = (inv_matrix * light).normalize();
+            let transformed = matrix * original;
+            let l = transformed.magnitude();
+            let jacobian = det / (l * l * l);
+            magnitude * original.z.max(0.0) / PI / jacobian
+        };
+
+        let mut error = 0.0;
+        for (u1, u2) in samples() {
+            // Importance sample the LTC
+            let theta = u1.sqrt().acos();
+            let phi = 2.0 * PI * u2;
+            let light = (matrix
+                * Vector3::new(
+                    theta.sin() * phi.cos(),
+                    theta.sin() * phi.sin(),
+                    theta.cos(),
+                ))
+            .normalize();
+            let (value, pdf_brdf) = eval_brdf(view, light, alpha);
+            let value_ltc = eval_ltc(light);
+            let pdf_ltc = value_ltc / magnitude;
+            let e = (value - value_ltc).abs();
+            if pdf_ltc + pdf_brdf > 0.0 {
+                error += e * e * e / (pdf_ltc + pdf_brdf);
+            }
+
+            // Importance sample the BRDF
+            let light = sample_brdf(view, alpha, u1, u2);
+            let (value, pdf_brdf) = eval_brdf(view, light, alpha);
+            let value_ltc = eval_ltc(light);
+            let pdf_ltc = value_ltc / magnitude;
+            let e = (value - value_ltc).abs();
+            if pdf_ltc + pdf_brdf > 0.0 {
+                error += e * e * e / (pdf_ltc + pdf_brdf);
+            }
+        }
+
+        error / (FIT_SAMPLES * FIT_SAMPLES) as f32
+    }
+}
+
+/// Minimize `f` with the Nelder-Mead simplex method, starting at `start`.
+fn nelder_mead<F>(
+    start: [f32; 3],
+    delta: f32,
+    tolerance: f32,
+    max_iterations: u32,
+    f: F,
+) -> [f32; 3]
+where
+    F: Fn([f32; 3]) -> f32,
+{
+    let combine = |a: [f32; 3], b: [f32; 3], t: f32| {
+        [
+            a[0] + (b[0] - a[0]) * t,
+            a[1] + (b[1] - a[1]) * t,
+            a[2] + (b[2] - a[2]) * t,
+        ]
+    };
+
+    let mut simplex = [start; 4];
+    for i in 0..3 {
+        simplex[i + 1][i] += delta;
+    }
+    let mut values = [0.0; 4];
+    for i in 0..4 {
+        values[i] = f(simplex[i]);
+    }
+
+    for _ in 0..max_iterations {
+        // Order the vertices by value, best first.
+        let mut order = [0, 1, 2, 3];
+        order.sort_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap());
+        simplex = [
+            simplex[order[0]],
+            simplex[order[1]],
+            simplex[order[2]],
+            simplex[order[3]],
+        ];
+        values = [
+            values[order[0]],
+            values[order[1]],
+            values[order[2]],
+            values[order[3]],
+        ];
+        if (values[3] - values[0]).abs() < tolerance {
+            break;
+        }
+
+        let mut centroid = [0.0; 3];
+        for vertex in &simplex[..3] {
+            for c in 0..3 {
+                centroid[c] += vertex[c] / 3.0;
+            }
+        }
+
+        let reflected = combine(centroid, simplex[3], -1.0);
+        let reflected_value = f(reflected);
+        if reflected_value < values[0] {
+            let expanded = combine(centroid, simplex[3], -2.0);
+            let expanded_value = f(expanded);
+            if expanded_value < reflected_value {
+                simplex[3] = expanded;
+                values[3] = expanded_value;
+            } else {
+                simplex[3] = reflected;
+                values[3] = reflected_value;
+            }
+        } else if reflected_value < values[2] {
+            simplex[3] = reflected;
+            values[3] = reflected_value;
+        } else {
+            let contracted = combine(centroid, simplex[3], 0.5);
+            let contracted_value = f(contracted);
+            if contracted_value < values[3] {
+                simplex[3] = contracted;
+                values[3] = contracted_value;
+            } else {
+                // Shrink towards the best vertex.
+                for i in 1..4 {
+                    simplex[i] = combine(simplex[0], simplex[i], 0.5);
+                    values[i] = f(simplex[i]);
+                }
+            }
+        }
+    }
+
+    let best = (0..4)
+        .min_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap())
+        .unwrap();
+    simplex[best]
+}
+
+/// Write the table as Rust source defining `LTC_TABLE`.
+pub fn write_table(path: &Path, table: &[LtcData]) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(file, "// Generated by `--fit-ltc`, don't edit manually.")?;
+    writeln!(file, "//")?;
+    writeln!(
+        file,
+        "// Per entry: inverse matrix entries, magnitude and fresnel (see `LtcData`).\n"
+    )?;
+    writeln!(file, "#[cfg_attr(rustfmt, rustfmt_skip)]")?;
+    writeln!(
+        file,
+        "pub const LTC_TABLE: [[f32; 6]; {}] = [",
+        LTC_SIZE * LTC_SIZE
+    )?;
+    for entry in table {
+        let m = entry.inv_matrix;
+        writeln!(
+            file,
+            "    [{:e}, {:e}, {:e}, {:e}, {:e}, {:e}],",
+            m[0], m[1], m[2], m[3], entry.magnitude, entry.fresnel
+        )?;
+    }
+    writeln!(file, "];")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::Rad;
+    use ltc;
+
+    #[test]
+    fn table_entry_normalization() {
+        let ltc = Ltc {
+            params: [0.4, 0.7, 0.3],
+            frame: Matrix3::from_angle_y(Rad(0.5)),
+        };
+        let terms = AverageTerms {
+            magnitude: 0.8,
+            fresnel: 0.1,
+            direction: Vector3::unit_z(),
+        };
+        let entry = ltc.table_entry(&terms);
+        assert_eq!((entry.magnitude, entry.fresnel), (0.8, 0.1));
+
+        // The stored matrix is the inverse scaled to `(1, 1) = 1`.
+        let inv = ltc.matrix().invert().unwrap();
+        let normalized = ltc::inv_matrix(&entry) * inv.y.y;
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((normalized[i][j] - inv[i][j]).abs() < 1e-5, "{:?}", entry);
+            }
+        }
+
+        // Scaling doesn't change the integral over polygons.
+        let points = [
+            Vector3::new(-0.5, -0.2, 1.0),
+            Vector3::new(0.8, -0.2, 1.0),
+            Vector3::new(0.8, 0.4, 0.6),
+            Vector3::new(-0.5, 0.4, 0.6),
+        ];
+        let normal = Vector3::unit_z();
+        let view = Vector3::new(0.6, 0.0, 0.8);
+        let expected = ltc::evaluate(normal, view, inv, &points);
+        let integral = ltc::evaluate(normal, view, ltc::inv_matrix(&entry), &points);
+        assert!(expected > 0.0);
+        assert!((integral - expected).abs() < 1e-4 * expected);
+    }
+
+    #[test]
+    fn average_terms_albedo() {
+        // Directional albedo by the midpoint rule over the hemisphere.
+        const STEPS: u32 = 256;
+        for &(alpha, n_dot_v) in &[(0.1f32, 0.9f32), (0.5, 0.5), (1.0, 0.2)] {
+            let view = Vector3::new((1.0 - n_dot_v * n_dot_v).sqrt(), 0.0, n_dot_v);
+            let mut albedo = 0.0;
+            for i in 0..STEPS {
+                let cos_theta = (i as f32 + 0.5) / STEPS as f32;
+                let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+                for j in 0..STEPS {
+                    let phi = 2.0 * PI * (j as f32 + 0.5) / STEPS as f32;
+                    let light =
+                        Vector3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+                    albedo += eval_brdf(view, light, alpha).0;
+                }
+            }
+            albedo *= 2.0 * PI / (STEPS * STEPS) as f32;
+
+            let terms = average_terms(view, alpha);
+            assert!(
+                (terms.magnitude - albedo).abs() < 0.02 * albedo,
+                "alpha {}, n_dot_v {}: {} != {}",
+                alpha,
+                n_dot_v,
+                terms.magnitude,
+                albedo
+            );
+            assert!(terms.fresnel > 0.0 && terms.fresnel < terms.magnitude);
+            assert!((terms.direction.magnitude() - 1.0).abs() < 1e-5);
+            assert_eq!(terms.direction.y, 0.0);
+        }
+    }
+}
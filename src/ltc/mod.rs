@@ -0,0 +1,287 @@
+//! Linearly transformed cosines (LTC) for area lights.
+//!
+//! The specular lobe of the BRDF (`brdf.hlsl`) for a given roughness and view angle
+//! is approximated by a clamped cosine distribution transformed by a 3x3 matrix.
+//! Integrals over polygonal lights reduce to the analytic cosine integral of the
+//! polygon transformed by the inverse matrix.
+//!
+//!  * Table: Inverse matrices and BRDF magnitudes per roughness and view angle,
+//!           fitted offline (`fit`) and uploaded as `StructuredBuffer`.
+//!  * Evaluation: CPU port of `ltc.hlsl` for the reference renderer.
+//!
+//! See "Real-Time Polygonal-Light Shading with Linearly Transformed Cosines"
+//! (Heitz et al. 2016).
+
+use cgmath::{InnerSpace, Matrix3, Vector3};
+
+pub mod fit;
+mod table;
+
+pub use self::table::LTC_TABLE;
+
+/// Entries of the table along each axis.
+///
+/// Must match with `LTC_SIZE` in `ltc.hlsl`.
+pub const LTC_SIZE: u32 = 32;
+
+/// Single table entry.
+///
+/// Must match with `_LtcData` in `ltc.hlsl`.
+// #[repr(hlsl)]
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LtcData {
+    /// Entries `(0, 0)`, `(0, 2)`, `(2, 0)` and `(2, 2)` of the inverse matrix in the
+    /// local frame, normalized to `(1, 1) = 1`. All other entries are zero.
+    pub inv_matrix: [f32; 4],
+    /// Directional albedo of the specular BRDF without fresnel.
+    pub magnitude: f32,
+    /// Schlick fresnel weight of the directional albedo.
+    pub fresnel: f32,
+    pub _alignment: [f32; 2],
+}
+
+/// Table entries, row-major with the perceptual roughness along `x` and
+/// `sqrt(1 - n_dot_v)` along `y`, both in `[0, 1]`.
+pub fn table() -> Vec<LtcData> {
+    LTC_TABLE
+        .iter()
+        .map(|entry| LtcData {
+            inv_matrix: [entry[0], entry[1], entry[2], entry[3]],
+            magnitude: entry[4],
+            fresnel: entry[5],
+            _alignment: [0.0; 2],
+        })
+        .collect()
+}
+
+/// `ltc_lookup`, bilinear interpolation of the table entries.
+pub fn lookup(table: &[LtcData], roughness: f32, n_dot_v: f32) -> LtcData {
+    let saturate = |value: f32| value.max(0.0).min(1.0);
+    let max_coord = (LTC_SIZE - 1) as f32;
+    let coord = [
+        saturate(roughness) * max_coord,
+        (1.0 - saturate(n_dot_v)).sqrt() * max_coord,
+    ];
+    let base = [
+        (coord[0] as u32).min(LTC_SIZE - 2),
+        (coord[1] as u32).min(LTC_SIZE - 2),
+    ];
+    let (fx, fy) = (coord[0] - base[0] as f32, coord[1] - base[1] as f32);
+
+    let entry = |x: u32, y: u32| table[((base[1] + y) * LTC_SIZE + base[0] + x) as usize];
+    let (e00, e10, e01, e11) = (entry(0, 0), entry(1, 0), entry(0, 1), entry(1, 1));
+    let bilinear = |v00: f32, v10: f32, v01: f32, v11: f32| {
+        let v0 = v00 + (v10 - v00) * fx;
+        let v1 = v01 + (v11 - v01) * fx;
+        v0 + (v1 - v0) * fy
+    };
+
+    let mut inv_matrix = [0.0; 4];
+    for i in 0..4 {
+        inv_matrix[i] = bilinear(
+            e00.inv_matrix[i],
+            e10.inv_matrix[i],
+            e01.inv_matrix[i],
+            e11.inv_matrix[i],
+        );
+    }
+    LtcData {
+        inv_matrix,
+        magnitude: bilinear(e00.magnitude, e10.magnitude, e01.magnitude, e11.magnitude),
+        fresnel: bilinear(e00.fresnel, e10.fresnel, e01.fresnel, e11.fresnel),
+        _alignment: [0.0; 2],
+    }
+}
+
+/// `ltc_inv_matrix`, inverse matrix of an entry in the local frame.
+pub fn inv_matrix(ltc: &LtcData) -> Matrix3<f32> {
+    let m = ltc.inv_matrix;
+    // Column-major
+    Matrix3::new(m[0], 0.0, m[2], 0.0, 1.0, 0.0, m[1], 0.0, m[3])
+}
+
+/// `integrate_edge`, vector form factor of an edge between two directions.
+///
+/// Uses the rational fit of `theta / sin(theta)` of the paper, including the
+/// normalization by `2 * PI`.
+fn integrate_edge(v1: Vector3<f32>, v2: Vector3<f32>) -> Vector3<f32> {
+    let (v1, v2) = (v1.normalize(), v2.normalize());
+    let x = v1.dot(v2);
+    let y = x.abs();
+    let a = 0.8543985 + (0.4965155 + 0.0145206 * y) * y;
+    let b = 3.4175940 + (4.1616724 + y) * y;
+    let v = a / b;
+    let theta_sin_theta = if x > 0.0 {
+        v
+    } else {
+        0.5 / (1.0 - x * x).max(1e-7).sqrt() - v
+    };
+    v1.cross(v2) * theta_sin_theta
+}
+
+/// `integrate_polygon`, cosine weighted integral of a convex polygon over the
+/// upper hemisphere, relative to the origin.
+///
+/// Polygons are clipped against the horizon. Front faces are wound clockwise seen
+/// from the origin, back faces return zero.
+pub fn integrate_polygon(points: &[Vector3<f32>]) -> f32 {
+    let mut sum = 0.0;
+    let mut exit = None;
+    let mut entry = None;
+    for i in 0..points.len() {
+        let (a, b) = (points[i], points[(i + 1) % points.len()]);
+        let intersect = || a + (b - a) * (a.z / (a.z - b.z));
+        if a.z >= 0.0 && b.z >= 0.0 {
+            sum += integrate_edge(a, b).z;
+        } else if a.z >= 0.0 {
+            let p = intersect();
+            sum += integrate_edge(a, p).z;
+            exit = Some(p);
+        } else if b.z >= 0.0 {
+            let p = intersect();
+            sum += integrate_edge(p, b).z;
+            entry = Some(p);
+        }
+    }
+    // Closing edge along the horizon.
+    if let (Some(exit), Some(entry)) = (exit, entry) {
+        sum += integrate_edge(exit, entry).z;
+    }
+
+    sum.max(0.0)
+}
+
+/// `ltc_evaluate`, integral of the transformed cosine over a polygon with `points`
+/// relative to the shading point.
+pub fn evaluate(
+    normal: Vector3<f32>,
+    view: Vector3<f32>,
+    inv_matrix: Matrix3<f32>,
+    points: &[Vector3<f32>],
+) -> f32 {
+    // Local frame with the view direction in the xz-plane.
+    let tangent = view - normal * normal.dot(view);
+    let tangent = if tangent.magnitude2() > 1e-8 {
+        tangent.normalize()
+    } else if normal.z.abs() < 0.999 {
+        normal.cross(Vector3::unit_z()).normalize()
+    } else {
+        Vector3::unit_x()
+    };
+    let bitangent = normal.cross(tangent);
+
+    let local = points
+        .iter()
+        .map(|p| inv_matrix * Vector3::new(p.dot(tangent), p.dot(bitangent), p.dot(normal)))
+        .collect::<Vec<_>>();
+    integrate_polygon(&local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::SquareMatrix;
+
+    /// Square at height 1 above the origin with half extent `size`, front facing
+    /// the origin.
+    fn square(size: f32) -> Vec<Vector3<f32>> {
+        vec![
+            Vector3::new(-size, -size, 1.0),
+            Vector3::new(size, -size, 1.0),
+            Vector3::new(size, size, 1.0),
+            Vector3::new(-size, size, 1.0),
+        ]
+    }
+
+    fn assert_entry_eq(a: &LtcData, b: &LtcData) {
+        let values = |e: &LtcData| {
+            let m = e.inv_matrix;
+            [m[0], m[1], m[2], m[3], e.magnitude, e.fresnel]
+        };
+        for (x, y) in values(a).iter().zip(&values(b)) {
+            assert!((x - y).abs() <= 1e-4 * x.abs().max(1.0), "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn lookup_table_entries() {
+        let table = table();
+        assert_eq!(table.len(), (LTC_SIZE * LTC_SIZE) as usize);
+        let max_coord = (LTC_SIZE - 1) as f32;
+        let coords = |x: f32, y: f32| (x / max_coord, 1.0 - (y / max_coord).powi(2));
+        let entry = |x: u32, y: u32| table[(y * LTC_SIZE + x) as usize];
+
+        for &(x, y) in &[(0, 0), (5, 17), (30, 1), (31, 31), (31, 0), (0, 31)] {
+            let (roughness, n_dot_v) = coords(x as f32, y as f32);
+            assert_entry_eq(&lookup(&table, roughness, n_dot_v), &entry(x, y));
+        }
+
+        // Halfway between four entries.
+        let (roughness, n_dot_v) = coords(10.5, 20.5);
+        let mut expected = entry(10, 20);
+        let neighbors = [entry(11, 20), entry(10, 21), entry(11, 21)];
+        for i in 0..4 {
+            expected.inv_matrix[i] = (expected.inv_matrix[i]
+                + neighbors.iter().map(|e| e.inv_matrix[i]).sum::<f32>())
+                / 4.0;
+        }
+        expected.magnitude =
+            (expected.magnitude + neighbors.iter().map(|e| e.magnitude).sum::<f32>()) / 4.0;
+        expected.fresnel =
+            (expected.fresnel + neighbors.iter().map(|e| e.fresnel).sum::<f32>()) / 4.0;
+        assert_entry_eq(&lookup(&table, roughness, n_dot_v), &expected);
+
+        // Coordinates are clamped to the table.
+        assert_entry_eq(&lookup(&table, -1.0, 2.0), &entry(0, 0));
+        assert_entry_eq(&lookup(&table, 2.0, -1.0), &entry(31, 31));
+    }
+
+    #[test]
+    fn table_normalization() {
+        let table = table();
+        for (i, entry) in table.iter().enumerate() {
+            assert!(
+                entry.magnitude > 0.0 && entry.magnitude <= 1.0,
+                "entry {}: {:?}",
+                i,
+                entry
+            );
+            assert!(
+                entry.fresnel >= 0.0 && entry.fresnel <= entry.magnitude,
+                "entry {}: {:?}",
+                i,
+                entry
+            );
+        }
+
+        // Lobes at normal incidence are isotropic, the horizon stays in place and the
+        // distribution integrates to one over the hemisphere.
+        let normal = Vector3::unit_z();
+        for entry in &table[..LTC_SIZE as usize] {
+            let m = entry.inv_matrix;
+            assert_eq!((m[0], m[1], m[2]), (1.0, 0.0, 0.0));
+            let integral = evaluate(normal, normal, inv_matrix(entry), &square(1e4));
+            assert!((integral - 1.0).abs() < 1e-2, "{:?}: {}", entry, integral);
+        }
+    }
+
+    #[test]
+    fn cosine_integral() {
+        let normal = Vector3::unit_z();
+        let integral = evaluate(normal, normal, Matrix3::identity(), &square(1e4));
+        assert!((integral - 1.0).abs() < 1e-3);
+
+        // Form factor of a square with half extent 1 at distance 1.
+        let x = 1.0 / 2f32.sqrt();
+        let expected = 4.0 * x * x.atan() / ::std::f32::consts::PI;
+        assert!((integrate_polygon(&square(1.0)) - expected).abs() < 1e-3);
+
+        // Back faces and polygons below the horizon don't contribute.
+        let mut back = square(1.0);
+        back.reverse();
+        assert_eq!(integrate_polygon(&back), 0.0);
+        let below = square(1.0).into_iter().map(|p| -p).collect::<Vec<_>>();
+        assert_eq!(integrate_polygon(&below), 0.0);
+    }
+}
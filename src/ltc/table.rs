@@ -0,0 +1,1031 @@
+// Generated by `--fit-ltc`, don't edit manually.
+//
+// Per entry: inverse matrix entries, magnitude and fresnel (see `LtcData`).
+
+#[cfg_attr(rustfmt, rustfmt_skip)]
+pub const LTC_TABLE: [[f32; 6]; 1024] = [
+    [1e0, 0e0, 0e0, 3.9501553e-3, 1e0, 1.1276367e-21],
+    [1e0, 0e0, 0e0, 3.9501553e-3, 1e0, 1.1276367e-21],
+    [1e0, 0e0, 0e0, 8.290373e-3, 1e0, 1.5127276e-18],
+    [1e0, 0e0, 0e0, 1.8704964e-2, 1e0, 4.946395e-15],
+    [1e0, 0e0, 0e0, 3.3296272e-2, 1e0, 1.4923267e-12],
+    [1e0, 0e0, 0e0, 5.2055918e-2, 9.999981e-1, 1.1810289e-10],
+    [1e0, 0e0, 0e0, 7.4995086e-2, 9.999943e-1, 3.852144e-9],
+    [1e0, 0e0, 0e0, 1.02138065e-1, 9.999675e-1, 6.526563e-8],
+    [1e0, 0e0, 0e0, 1.33095e-1, 9.9987406e-1, 6.570104e-7],
+    [1e0, 0e0, 0e0, 1.6922662e-1, 9.9953496e-1, 4.285693e-6],
+    [1e0, 0e0, 0e0, 2.0889263e-1, 9.976334e-1, 1.8675535e-5],
+    [1e0, 0e0, 0e0, 2.4921972e-1, 9.6815705e-1, 1.9665722e-6],
+    [1e0, 0e0, 0e0, 2.9583296e-1, 9.665059e-1, 5.1432366e-6],
+    [1e0, 0e0, 0e0, 3.4583297e-1, 9.6260905e-1, 1.5693071e-5],
+    [1e0, 0e0, 0e0, 4.0138856e-1, 9.488822e-1, 2.9812885e-5],
+    [1e0, 0e0, 0e0, 4.569441e-1, 9.241975e-1, 1.948711e-5],
+    [1e0, 0e0, 0e0, 5.14583e-1, 9.06191e-1, 3.3990458e-5],
+    [1e0, 0e0, 0e0, 5.7222193e-1, 8.755536e-1, 3.0426305e-5],
+    [1e0, 0e0, 0e0, 6.277777e-1, 8.4585196e-1, 3.946461e-5],
+    [1e0, 0e0, 0e0, 6.8333346e-1, 8.107779e-1, 4.2441312e-5],
+    [1e0, 0e0, 0e0, 7.38889e-1, 7.7071935e-1, 4.3166365e-5],
+    [1e0, 0e0, 0e0, 7.888891e-1, 7.278218e-1, 4.391325e-5],
+    [1e0, 0e0, 0e0, 8.444447e-1, 6.8340725e-1, 4.537302e-5],
+    [1e0, 0e0, 0e0, 8.9444464e-1, 6.379136e-1, 4.658738e-5],
+    [1e0, 0e0, 0e0, 9.5000005e-1, 5.91581e-1, 4.641792e-5],
+    [1e0, 0e0, 0e0, 1e0, 5.4544175e-1, 4.5442874e-5],
+    [1e0, 0e0, 0e0, 1e0, 5.0061417e-1, 4.4631663e-5],
+    [1e0, 0e0, 0e0, 1.0999999e0, 4.5731205e-1, 4.3250213e-5],
+    [1e0, 0e0, 0e0, 1.0999999e0, 4.1599566e-1, 4.1370215e-5],
+    [1e0, 0e0, 0e0, 1.0999999e0, 3.7702116e-1, 3.9106177e-5],
+    [1e0, 0e0, 0e0, 1.0999999e0, 3.4063673e-1, 3.6592275e-5],
+    [1e0, 0e0, 0e0, 1.0499998e0, 3.0697405e-1, 3.395958e-5],
+    [1.0132056e0, 4.6235103e-2, -1.8679189e-4, 4.0913443e-3, 1e0, 1.5632355e-15],
+    [1.0132056e0, 4.6235103e-2, -1.8679189e-4, 4.0913443e-3, 1e0, 1.5632355e-15],
+    [1.0000116e0, 4.5652263e-2, -3.8022138e-4, 8.328085e-3, 1e0, 4.2247462e-15],
+    [9.9723667e-1, 4.5528755e-2, -8.524822e-4, 1.8672151e-2, 9.9999994e-1, 1.6320468e-13],
+    [9.989595e-1, 4.560771e-2, -1.5185666e-3, 3.3261627e-2, 9.999993e-1, 8.533951e-12],
+    [9.9824667e-1, 4.557216e-2, -2.3701172e-3, 5.191372e-2, 9.9999666e-1, 2.9650266e-10],
+    [9.981599e-1, 4.5576748e-2, -3.4126807e-3, 7.47507e-2, 9.9998856e-1, 6.42643e-9],
+    [9.989596e-1, 4.5604613e-2, -4.6579666e-3, 1.020318e-1, 9.999604e-1, 8.821783e-8],
+    [9.9556434e-1, 4.5406822e-2, -6.069278e-3, 1.3296819e-1, 9.998679e-1, 7.928782e-7],
+    [9.989618e-1, 4.5557108e-2, -7.7094743e-3, 1.6905092e-1, 9.9952376e-1, 4.846456e-6],
+    [9.989826e-1, 4.5096792e-2, -9.420387e-3, 2.0868008e-1, 9.9749964e-1, 2.0075528e-5],
+    [1.0113113e0, 4.4534523e-2, -1.0148396e-2, 2.4886936e-1, 9.735983e-1, 9.724951e-6],
+    [9.989697e-1, 4.538164e-2, -1.3425385e-2, 2.9552814e-1, 9.6646047e-1, 5.8585647e-6],
+    [9.990003e-1, 4.470159e-2, -1.5459283e-2, 3.4548727e-1, 9.624453e-1, 1.7135337e-5],
+    [9.9914676e-1, 4.129844e-2, -1.6576722e-2, 4.0104607e-1, 9.481773e-1, 2.9251572e-5],
+    [9.9904335e-1, 4.373069e-2, -1.9982481e-2, 4.5650697e-1, 9.239428e-1, 2.1241649e-5],
+    [1.0158923e0, 4.595651e-2, -2.0436447e-2, 5.0949574e-1, 9.0557396e-1, 3.447282e-5],
+    [9.991858e-1, 4.034425e-2, -2.3085868e-2, 5.7175606e-1, 8.7554705e-1, 3.3094737e-5],
+    [9.9936724e-1, 3.556786e-2, -2.232871e-2, 6.273805e-1, 8.452375e-1, 3.8902504e-5],
+    [9.9937725e-1, 3.5286505e-2, -2.4112448e-2, 6.829078e-1, 8.100944e-1, 4.3351378e-5],
+    [9.994455e-1, 3.3299994e-2, -2.4604999e-2, 7.3847926e-1, 7.7054006e-1, 4.5429526e-5],
+    [9.995519e-1, 2.9930728e-2, -2.3612026e-2, 7.8853565e-1, 7.2863394e-1, 4.7742087e-5],
+    [9.9963963e-1, 2.6840877e-2, -2.2665635e-2, 8.4414035e-1, 6.843393e-1, 4.9079328e-5],
+    [9.997163e-1, 2.382179e-2, -2.1307275e-2, 8.941909e-1, 6.3857687e-1, 4.9589446e-5],
+    [9.997846e-1, 2.0757202e-2, -1.9719342e-2, 9.497954e-1, 5.9225637e-1, 4.9371174e-5],
+    [9.998446e-1, 1.7628202e-2, -1.7628202e-2, 9.998446e-1, 5.461863e-1, 4.8472535e-5],
+    [9.998955e-1, 1.445079e-2, -1.445079e-2, 9.998955e-1, 5.01075e-1, 4.693718e-5],
+    [9.9993646e-1, 1.1262837e-2, -1.2389119e-2, 1.09993e0, 4.5752823e-1, 4.4866727e-5],
+    [9.999673e-1, 8.092572e-3, -8.901829e-3, 1.099964e0, 4.1603556e-1, 4.2397798e-5],
+    [9.9998647e-1, 5.189796e-3, -5.708775e-3, 1.0999851e0, 3.7699288e-1, 3.9794595e-5],
+    [9.9999684e-1, 2.5139241e-3, -2.7653163e-3, 1.0999964e0, 3.4060323e-1, 3.7115107e-5],
+    [9.545455e-1, -3.62562e-5, 3.9881812e-5, 1.05e0, 3.0696478e-1, 3.439659e-5],
+    [9.884495e-1, 9.044768e-2, -3.6736598e-4, 4.0138126e-3, 9.999999e-1, 1.3217062e-12],
+    [9.884495e-1, 9.044768e-2, -3.6736598e-4, 4.0138126e-3, 9.999999e-1, 1.3217062e-12],
+    [9.899915e-1, 9.062636e-2, -7.5620884e-4, 8.262299e-3, 1e0, 1.6238031e-12],
+    [9.915692e-1, 9.0751745e-2, -1.69913e-3, 1.8564638e-2, 9.9999934e-1, 6.0402247e-12],
+    [9.9189365e-1, 9.0789706e-2, -3.022578e-3, 3.3024885e-2, 9.999987e-1, 7.9769476e-11],
+    [9.9256223e-1, 9.085717e-2, -4.7274753e-3, 5.16535e-2, 9.999944e-1, 1.2971534e-9],
+    [9.893185e-1, 9.053404e-2, -6.793113e-3, 7.422568e-2, 9.9998426e-1, 1.7270928e-8],
+    [9.888467e-1, 9.061137e-2, -9.245246e-3, 1.0102684e-1, 9.999519e-1, 1.7188228e-7],
+    [9.881598e-1, 9.036021e-2, -1.2067034e-2, 1.3189024e-1, 9.9985015e-1, 1.251365e-6],
+    [9.947116e-1, 9.098547e-2, -1.5272987e-2, 1.6709587e-1, 9.9947935e-1, 6.6540138e-6],
+    [9.875899e-1, 9.053626e-2, -1.8581279e-2, 2.0579274e-1, 9.970236e-1, 2.408302e-5],
+    [9.9098384e-1, 9.009232e-2, -2.0876195e-2, 2.486174e-1, 9.7695863e-1, 1.1291359e-5],
+    [9.958829e-1, 9.0648085e-2, -2.6816694e-2, 2.94615e-1, 9.663133e-1, 8.183101e-6],
+    [9.9601585e-1, 8.917765e-2, -3.0840574e-2, 3.4445515e-1, 9.619339e-1, 2.1520214e-5],
+    [9.847566e-1, 8.507802e-2, -3.252906e-2, 3.944931e-1, 9.462019e-1, 2.5909805e-5],
+    [9.952313e-1, 9.514222e-2, -3.8558718e-2, 4.5177227e-1, 9.25014e-1, 2.8568793e-5],
+    [1.0132669e0, 8.625255e-2, -4.064799e-2, 5.082828e-1, 9.037227e-1, 3.4601042e-5],
+    [9.9694353e-1, 7.812524e-2, -4.470498e-2, 5.70473e-1, 8.7653124e-1, 4.083142e-5],
+    [9.972939e-1, 7.3518455e-2, -4.6153247e-2, 6.2607884e-1, 8.4517133e-1, 4.404157e-5],
+    [9.975571e-1, 6.9855206e-2, -4.77344e-2, 6.8166417e-1, 8.089561e-1, 4.695239e-5],
+    [9.886497e-1, 9.137709e-2, -4.7189396e-2, 7.3181343e-1, 7.7040875e-1, 5.0746858e-5],
+    [9.9824375e-1, 5.9239544e-2, -4.6733435e-2, 7.8750366e-1, 7.2843087e-1, 5.3104843e-5],
+    [9.985497e-1, 5.383544e-2, -4.5461055e-2, 8.4322006e-1, 6.8399733e-1, 5.4140317e-5],
+    [9.988502e-1, 4.793734e-2, -4.28773e-2, 8.9341635e-1, 6.382069e-1, 5.4271415e-5],
+    [9.9913186e-1, 4.166416e-2, -3.9580952e-2, 9.491753e-1, 5.920395e-1, 5.3869695e-5],
+    [9.993744e-1, 3.536744e-2, -3.536744e-2, 9.993744e-1, 5.4615015e-1, 5.281913e-5],
+    [9.9957573e-1, 2.9127553e-2, -2.9127553e-2, 9.9957573e-1, 5.0121176e-1, 5.112077e-5],
+    [9.997368e-1, 2.2944119e-2, -2.5238529e-2, 1.0997103e0, 4.5783833e-1, 4.8889226e-5],
+    [9.9985766e-1, 1.6874153e-2, -1.8561564e-2, 1.0998434e0, 4.1650057e-1, 4.6245445e-5],
+    [9.999396e-1, 1.0987338e-2, -1.208607e-2, 1.0999335e0, 3.7754083e-1, 4.3311058e-5],
+    [9.9998575e-1, 5.316799e-3, -5.848479e-3, 1.0999843e0, 3.4120297e-1, 4.0256335e-5],
+    [1.0000001e0, -5.3004514e-5, 5.830496e-5, 1.1000001e0, 3.0759406e-1, 3.71804e-5],
+    [9.670571e-1, 1.3327178e-1, -5.460719e-4, 3.96194e-3, 1e0, 7.382452e-11],
+    [9.670571e-1, 1.3327178e-1, -5.460719e-4, 3.96194e-3, 1e0, 7.382452e-11],
+    [9.8099387e-1, 1.3520816e-1, -1.1274519e-3, 8.180048e-3, 9.9999994e-1, 8.0279776e-11],
+    [9.808942e-1, 1.3519523e-1, -2.5323788e-3, 1.8373376e-2, 9.999993e-1, 1.375695e-10],
+    [9.8081255e-1, 1.3519892e-1, -4.5028357e-3, 3.267044e-2, 9.999975e-1, 6.229663e-10],
+    [9.8181653e-1, 1.353422e-1, -7.045264e-3, 5.1118758e-2, 9.999914e-1, 5.36262e-9],
+    [9.829741e-1, 1.3545282e-1, -1.0152747e-2, 7.367087e-2, 9.999772e-1, 4.8333114e-8],
+    [9.8268634e-1, 1.3520111e-1, -1.3802183e-2, 1.00165084e-1, 9.999375e-1, 3.670697e-7],
+    [9.827208e-1, 1.356787e-1, -1.8044092e-2, 1.3098826e-1, 9.9982226e-1, 2.1973524e-6],
+    [9.895324e-1, 1.36217e-1, -2.2871183e-2, 1.6622637e-1, 9.9940133e-1, 1.0096727e-5],
+    [9.798386e-1, 1.3555048e-1, -2.758082e-2, 2.0387696e-1, 9.9591994e-1, 2.9272163e-5],
+    [9.8609084e-1, 1.3681081e-1, -3.1505875e-2, 2.4494442e-1, 9.790786e-1, 1.2120864e-5],
+    [9.8315024e-1, 1.3754684e-1, -3.9536953e-2, 2.8870052e-1, 9.6605563e-1, 1.2671498e-5],
+    [9.720603e-1, 1.3490112e-1, -4.517721e-2, 3.3622348e-1, 9.6097684e-1, 2.8791137e-5],
+    [9.8015267e-1, 1.2760985e-1, -4.956874e-2, 3.92716e-1, 9.456104e-1, 2.864302e-5],
+    [9.905772e-1, 1.3525598e-1, -5.6771614e-2, 4.4984663e-1, 9.263377e-1, 3.8657647e-5],
+    [9.946969e-1, 1.3672802e-1, -6.2124703e-2, 5.096491e-1, 9.025692e-1, 4.086769e-5],
+    [9.890953e-1, 1.4229362e-1, -6.565662e-2, 5.64041e-1, 8.763836e-1, 4.928544e-5],
+    [9.819073e-1, 1.3782275e-1, -6.934577e-2, 6.1834574e-1, 8.4425217e-1, 5.363406e-5],
+    [9.8361063e-1, 1.3067216e-1, -7.056779e-2, 6.740942e-1, 8.091697e-1, 5.7916186e-5],
+    [9.851398e-1, 1.2360436e-1, -7.10554e-2, 7.2988284e-1, 7.7009976e-1, 6.08725e-5],
+    [9.959878e-1, 8.9489065e-2, -7.0596956e-2, 7.8572387e-1, 7.2783005e-1, 6.283399e-5],
+    [9.9674577e-1, 8.060788e-2, -6.80689e-2, 8.416967e-1, 6.838825e-1, 6.416504e-5],
+    [9.9744195e-1, 7.148057e-2, -6.3935414e-2, 8.9215666e-1, 6.3841945e-1, 6.422868e-5],
+    [9.980671e-1, 6.2144563e-2, -5.9037335e-2, 9.4816375e-1, 5.923998e-1, 6.332515e-5],
+    [9.986149e-1, 5.2614186e-2, -5.2614186e-2, 9.986149e-1, 5.465764e-1, 6.138749e-5],
+    [9.9907106e-1, 4.3093327e-2, -4.3093327e-2, 9.9907106e-1, 5.017314e-1, 5.880224e-5],
+    [9.9942714e-1, 3.3841215e-2, -3.7225332e-2, 1.0993698e0, 4.585068e-1, 5.595171e-5],
+    [9.996921e-1, 2.4810737e-2, -2.7291808e-2, 1.0996612e0, 4.173168e-1, 5.2737985e-5],
+    [9.998708e-1, 1.6072936e-2, -1.7680228e-2, 1.0998578e0, 3.7848273e-1, 4.925128e-5],
+    [9.9996996e-1, 7.7470085e-3, -8.521709e-3, 1.0999669e0, 3.422429e-1, 4.568677e-5],
+    [1e0, -1.3441152e-4, 1.4785265e-4, 1.0999999e0, 3.0868146e-1, 4.2072912e-5],
+    [9.653364e-1, 1.7837298e-1, -7.2642457e-4, 3.9309897e-3, 1e0, 1.2967533e-9],
+    [9.653364e-1, 1.7837298e-1, -7.2642457e-4, 3.9309897e-3, 1e0, 1.2967533e-9],
+    [9.670918e-1, 1.7871651e-1, -1.4893911e-3, 8.059738e-3, 9.999995e-1, 1.3567767e-9],
+    [9.6594787e-1, 1.7850293e-1, -3.34437e-3, 1.8098028e-2, 9.99998e-1, 1.7875724e-9],
+    [9.6732235e-1, 1.7874622e-1, -5.9529915e-3, 3.221544e-2, 9.999949e-1, 4.2467074e-9],
+    [9.677008e-1, 1.7887206e-1, -9.305041e-3, 5.0358523e-2, 9.9998605e-1, 2.054198e-8],
+    [9.648438e-1, 1.7828664e-1, -1.3384201e-2, 7.244317e-2, 9.9996626e-1, 1.3023359e-7],
+    [9.690272e-1, 1.7902173e-1, -1.82611e-2, 9.885997e-2, 9.999162e-1, 7.8251867e-7],
+    [9.6794945e-1, 1.7909297e-1, -2.3820639e-2, 1.2901099e-1, 9.9978155e-1, 3.949561e-6],
+    [9.72916e-1, 1.7974088e-1, -3.024142e-2, 1.6402969e-1, 9.9928105e-1, 1.5859614e-5],
+    [9.6358126e-1, 1.7697985e-1, -3.6050968e-2, 2.0090953e-1, 9.934064e-1, 2.8575821e-5],
+    [9.7898763e-1, 1.807646e-1, -4.2426452e-2, 2.4329077e-1, 9.8050976e-1, 1.4446048e-5],
+    [9.7599417e-1, 1.8149167e-1, -5.2442145e-2, 2.866374e-1, 9.6601665e-1, 2.0528294e-5],
+    [9.652373e-1, 1.7724627e-1, -5.9825283e-2, 3.3392832e-1, 9.5939183e-1, 3.784415e-5],
+    [9.6453804e-1, 1.7240545e-1, -6.596314e-2, 3.8565612e-1, 9.452149e-1, 3.750805e-5],
+    [9.743272e-1, 1.8374172e-1, -7.3939465e-2, 4.414765e-1, 9.264848e-1, 5.0058734e-5],
+    [9.881026e-1, 1.7823938e-1, -8.340067e-2, 5.066024e-1, 9.012983e-1, 5.4031127e-5],
+    [9.537139e-1, 1.7153332e-1, -8.391947e-2, 5.401624e-1, 8.755791e-1, 6.081162e-5],
+    [9.762829e-1, 1.7323075e-1, -9.165499e-2, 6.1543447e-1, 8.432601e-1, 6.67891e-5],
+    [9.7856116e-1, 1.6426507e-1, -9.3601026e-2, 6.712835e-1, 8.0855954e-1, 7.262179e-5],
+    [9.805779e-1, 1.557094e-1, -9.485273e-2, 7.271731e-1, 7.690639e-1, 7.575268e-5],
+    [9.795675e-1, 1.7209822e-1, -9.363535e-2, 7.8704226e-1, 7.275587e-1, 7.797873e-5],
+    [9.9428993e-1, 1.067115e-1, -9.011196e-2, 8.396229e-1, 6.835918e-1, 7.854242e-5],
+    [9.9546605e-1, 9.511739e-2, -8.507725e-2, 8.903893e-1, 6.381481e-1, 7.797394e-5],
+    [9.965578e-1, 8.2901515e-2, -7.875644e-2, 9.4672996e-1, 5.924047e-1, 7.659415e-5],
+    [9.975281e-1, 7.026888e-2, -7.026888e-2, 9.975281e-1, 5.469428e-1, 7.408106e-5],
+    [9.9834037e-1, 5.7589598e-2, -5.7589598e-2, 9.9834037e-1, 5.0241935e-1, 7.070989e-5],
+    [9.989875e-1, 4.4988457e-2, -4.9487296e-2, 1.0988863e0, 4.5934883e-1, 6.645075e-5],
+    [9.9945694e-1, 3.2952942e-2, -3.6248233e-2, 1.0994025e0, 4.1835222e-1, 6.2245796e-5],
+    [9.997717e-1, 2.1368193e-2, -2.3505008e-2, 1.0997487e0, 3.7969664e-1, 5.7885e-5],
+    [9.999468e-1, 1.03042e-2, -1.133462e-2, 1.0999414e0, 3.43569e-1, 5.3420565e-5],
+    [1e0, -1.5904724e-4, 1.7495193e-4, 1.0999999e0, 3.1008506e-1, 4.8982267e-5],
+    [9.539643e-1, 2.2193223e-1, -9.008274e-4, 3.871814e-3, 9.999998e-1, 1.2016978e-8],
+    [9.539643e-1, 2.2193223e-1, -9.008274e-4, 3.871814e-3, 9.999998e-1, 1.2016978e-8],
+    [9.4885176e-1, 2.2076944e-1, -1.8385872e-3, 7.902389e-3, 9.9999976e-1, 1.2360793e-8],
+    [9.477663e-1, 2.2050852e-1, -4.1302773e-3, 1.7752543e-2, 9.999975e-1, 1.457927e-8],
+    [9.4847995e-1, 2.2068168e-1, -7.3485593e-3, 3.158662e-2, 9.9999225e-1, 2.4582215e-8],
+    [9.476356e-1, 2.2049053e-1, -1.1471133e-2, 4.9311586e-2, 9.999791e-1, 7.451215e-8],
+    [9.51861e-1, 2.2149716e-1, -1.6575122e-2, 7.126496e-2, 9.999516e-1, 3.3774447e-7],
+    [9.4556177e-1, 2.2005066e-1, -2.25009e-2, 9.6772365e-2, 9.998877e-1, 1.6326369e-6],
+    [9.503911e-1, 2.2133535e-1, -2.9456519e-2, 1.2675938e-1, 9.997245e-1, 7.0593705e-6],
+    [9.446675e-1, 2.1964024e-1, -3.7187982e-2, 1.6034348e-1, 9.990937e-1, 2.4955503e-5],
+    [9.577475e-1, 2.220434e-1, -4.4556763e-2, 1.983046e-1, 9.911199e-1, 2.3973478e-5],
+    [9.3859076e-1, 2.1743718e-1, -5.2025132e-2, 2.3521155e-1, 9.815254e-1, 1.9705134e-5],
+    [9.549611e-1, 2.235421e-1, -6.369242e-2, 2.808804e-1, 9.684446e-1, 3.3865825e-5],
+    [9.675089e-1, 2.2464539e-1, -7.38064e-2, 3.2995918e-1, 9.568776e-1, 4.41383e-5],
+    [9.560553e-1, 2.1451259e-1, -8.2801916e-2, 3.823946e-1, 9.446164e-1, 5.3375752e-5],
+    [9.661444e-1, 2.22799e-1, -9.1644205e-2, 4.3814367e-1, 9.25652e-1, 6.199042e-5],
+    [9.237345e-1, 2.0578699e-1, -9.677503e-2, 4.7113666e-1, 9.010888e-1, 7.419429e-5],
+    [9.462598e-1, 2.0877321e-1, -1.0502149e-1, 5.3645915e-1, 8.745928e-1, 8.160079e-5],
+    [9.8904794e-1, 2.1539426e-1, -1.12173684e-1, 6.0370433e-1, 8.429061e-1, 8.834824e-5],
+    [9.407485e-1, 1.9938059e-1, -1.12308934e-1, 6.415585e-1, 8.072736e-1, 9.3746756e-5],
+    [9.752278e-1, 1.8630403e-1, -1.1755108e-1, 7.2385055e-1, 7.685736e-1, 9.788853e-5],
+    [9.7411996e-1, 2.0065157e-1, -1.1659585e-1, 7.839698e-1, 7.2703856e-1, 1.0006642e-4],
+    [9.772323e-1, 1.8732592e-1, -1.1322324e-1, 8.405572e-1, 6.8303925e-1, 1.00110876e-4],
+    [9.9298465e-1, 1.182438e-1, -1.05762534e-1, 8.881698e-1, 6.3821805e-1, 9.870358e-5],
+    [9.947157e-1, 1.0266781e-1, -9.753442e-2, 9.449799e-1, 5.9274304e-1, 9.553595e-5],
+    [9.9619746e-1, 8.7123014e-2, -8.7123014e-2, 9.9619746e-1, 5.4755116e-1, 9.168561e-5],
+    [9.974278e-1, 7.167812e-2, -7.167812e-2, 9.974278e-1, 5.03308e-1, 8.709863e-5],
+    [9.984123e-1, 5.6327548e-2, -6.1960302e-2, 1.0982535e0, 4.6054715e-1, 8.1671285e-5],
+    [9.9914896e-1, 4.1245382e-2, -4.5369912e-2, 1.0990639e0, 4.1974655e-1, 7.5781696e-5],
+    [9.996425e-1, 2.6737532e-2, -2.9411282e-2, 1.0996066e0, 3.812661e-1, 6.9914306e-5],
+    [9.999166e-1, 1.29281655e-2, -1.4220982e-2, 1.0999082e0, 3.452836e-1, 6.413569e-5],
+    [1e0, -1.3014628e-4, 1.4316088e-4, 1.0999999e0, 3.1189466e-1, 5.8490357e-5],
+    [9.329192e-1, 2.6278108e-1, -1.0680289e-3, 3.7914352e-3, 9.999998e-1, 7.420587e-8],
+    [9.329192e-1, 2.6278108e-1, -1.0680289e-3, 3.7914352e-3, 9.999998e-1, 7.420587e-8],
+    [9.256883e-1, 2.6076585e-1, -2.1743926e-3, 7.71898e-3, 9.9999946e-1, 7.5642745e-8],
+    [9.263496e-1, 2.609488e-1, -4.887357e-3, 1.73503e-2, 9.999966e-1, 8.4372914e-8],
+    [9.2549896e-1, 2.6070365e-1, -8.685818e-3, 3.0837012e-2, 9.9998826e-1, 1.1833641e-7],
+    [9.250761e-1, 2.6059833e-1, -1.3566505e-2, 4.81715e-2, 9.9997085e-1, 2.5630865e-7],
+    [9.2440706e-1, 2.6032063e-1, -1.951509e-2, 6.931112e-2, 9.9993265e-1, 8.516668e-7],
+    [9.2828685e-1, 2.6131123e-1, -2.6668802e-2, 9.4759405e-2, 9.998521e-1, 3.3345375e-6],
+    [9.261307e-1, 2.6085097e-1, -3.4673225e-2, 1.23295486e-1, 9.9965334e-1, 1.2452226e-5],
+    [9.22762e-1, 2.6001915e-1, -4.3681607e-2, 1.5575118e-1, 9.9879336e-1, 3.863319e-5],
+    [9.175769e-1, 2.582555e-1, -5.220406e-2, 1.9168167e-1, 9.901671e-1, 2.6674777e-5],
+    [9.278104e-1, 2.5961438e-1, -6.259729e-2, 2.3262131e-1, 9.822644e-1, 2.9664265e-5],
+    [9.1628265e-1, 2.585234e-1, -7.422038e-2, 2.7325958e-1, 9.70539e-1, 5.2635012e-5],
+    [9.2072076e-1, 2.5969318e-1, -8.6469114e-2, 3.2035348e-1, 9.549685e-1, 5.6677014e-5],
+    [9.2434186e-1, 2.5609958e-1, -9.769653e-2, 3.7225288e-1, 9.4342375e-1, 7.648765e-5],
+    [9.081429e-1, 2.5493377e-1, -1.053085e-1, 4.164578e-1, 9.250159e-1, 8.5445114e-5],
+    [9.317125e-1, 2.652896e-1, -1.1651591e-1, 4.7463548e-1, 9.0052533e-1, 9.797651e-5],
+    [9.3740827e-1, 2.4547802e-1, -1.2584051e-1, 5.3196055e-1, 8.72792e-1, 1.1037792e-4],
+    [9.806949e-1, 2.5069523e-1, -1.3373342e-1, 5.992973e-1, 8.421156e-1, 1.19192475e-4],
+    [9.333278e-1, 2.3164597e-1, -1.3432685e-1, 6.3731235e-1, 8.059278e-1, 1.2524129e-4],
+    [9.6297705e-1, 2.4269764e-1, -1.3921496e-1, 7.155439e-1, 7.6770735e-1, 1.288323e-4],
+    [9.678212e-1, 2.2911283e-1, -1.3952069e-1, 7.8021604e-1, 7.2585803e-1, 1.2990746e-4],
+    [9.721475e-1, 2.1213928e-1, -1.3458174e-1, 8.3740294e-1, 6.82747e-1, 1.2927166e-4],
+    [9.8295546e-1, 1.9052161e-1, -1.3083644e-1, 9.185063e-1, 6.3814e-1, 1.2666917e-4],
+    [9.92402e-1, 1.230372e-1, -1.1688534e-1, 9.42782e-1, 5.9288085e-1, 1.2190828e-4],
+    [9.945613e-1, 1.0415335e-1, -1.0415335e-1, 9.945613e-1, 5.481737e-1, 1.1578404e-4],
+    [9.963445e-1, 8.542702e-2, -8.542702e-2, 9.963445e-1, 5.044029e-1, 1.0915107e-4],
+    [9.977553e-1, 6.696549e-2, -7.366203e-2, 1.0975308e0, 4.620592e-1, 1.01778285e-4],
+    [9.987948e-1, 4.907963e-2, -5.398759e-2, 1.0986742e0, 4.2161152e-1, 9.422293e-5],
+    [9.994939e-1, 3.181178e-2, -3.499296e-2, 1.0994432e0, 3.833541e-1, 8.646456e-5],
+    [9.9988276e-1, 1.5314775e-2, -1.684625e-2, 1.0998709e0, 3.47516e-1, 7.880067e-5],
+    [9.999999e-1, -2.490323e-4, 2.7393556e-4, 1.0999998e0, 3.1423613e-1, 7.152402e-5],
+    [9.0157694e-1, 2.9946184e-1, -1.2187337e-3, 3.6689132e-3, 9.999999e-1, 3.4610068e-7],
+    [9.0157694e-1, 2.9946184e-1, -1.2187337e-3, 3.6689132e-3, 9.999999e-1, 3.4610068e-7],
+    [9.035366e-1, 3.0012152e-1, -2.4965166e-3, 7.5156507e-3, 9.999988e-1, 3.5091293e-7],
+    [9.00564e-1, 2.9914406e-1, -5.600589e-3, 1.6860878e-2, 9.99996e-1, 3.7908833e-7],
+    [9.0094584e-1, 2.9927194e-1, -9.960113e-3, 2.9988233e-2, 9.9998504e-1, 4.785825e-7],
+    [8.9965117e-1, 2.988421e-1, -1.5549517e-2, 4.6826027e-2, 9.9996066e-1, 8.2625183e-7],
+    [8.9838755e-1, 2.984383e-1, -2.2367388e-2, 6.738091e-2, 9.9990994e-1, 2.1005364e-6],
+    [8.971546e-1, 2.979526e-1, -3.0440511e-2, 9.1754384e-2, 9.9980813e-1, 6.7007713e-6],
+    [9.0185213e-1, 2.996321e-1, -3.9914053e-2, 1.2043267e-1, 9.9956304e-1, 2.1676535e-5],
+    [9.0477824e-1, 3.0026367e-1, -5.035707e-2, 1.5256856e-1, 9.9824107e-1, 5.7527108e-5],
+    [8.928583e-1, 2.9626352e-1, -5.9905306e-2, 1.8626215e-1, 9.89607e-1, 3.4613673e-5],
+    [9.0454644e-1, 2.9938483e-1, -7.234214e-2, 2.2730158e-1, 9.8278767e-1, 4.704797e-5],
+    [9.1417e-1, 3.017808e-1, -8.5139036e-2, 2.6787743e-1, 9.71822e-1, 7.828074e-5],
+    [8.924384e-1, 2.9395816e-1, -9.837735e-2, 3.0945855e-1, 9.5356405e-1, 8.2467785e-5],
+    [9.07872e-1, 2.973502e-1, -1.122521e-1, 3.6347848e-1, 9.416945e-1, 1.07747095e-4],
+    [8.9672e-1, 2.9258877e-1, -1.2258648e-1, 4.1170317e-1, 9.2403734e-1, 1.2285128e-4],
+    [9.207041e-1, 3.0128184e-1, -1.3486522e-1, 4.697512e-1, 9.001316e-1, 1.367979e-4],
+    [9.6202004e-1, 3.0563653e-1, -1.4931805e-1, 5.38799e-1, 8.707471e-1, 1.4923025e-4],
+    [9.4012344e-1, 3.0384883e-1, -1.5195258e-1, 5.819756e-1, 8.4053725e-1, 1.6076693e-4],
+    [9.264566e-1, 2.904611e-1, -1.551713e-1, 6.325603e-1, 8.0497897e-1, 1.6832669e-4],
+    [9.548798e-1, 2.7282146e-1, -1.6161886e-1, 7.108187e-1, 7.6639813e-1, 1.7196457e-4],
+    [9.6102506e-1, 2.5612745e-1, -1.6131645e-1, 7.7600265e-1, 7.2532415e-1, 1.7281309e-4],
+    [9.664154e-1, 2.3688586e-1, -1.5591405e-1, 8.3369464e-1, 6.8220407e-1, 1.692839e-4],
+    [9.371282e-1, 1.944349e-1, -1.4382349e-1, 8.665406e-1, 6.3788503e-1, 1.6437993e-4],
+    [9.898437e-1, 1.4215925e-1, -1.3505128e-1, 9.4035167e-1, 5.934668e-1, 1.57272e-4],
+    [9.926927e-1, 1.206691e-1, -1.206691e-1, 9.926927e-1, 5.491468e-1, 1.48617e-4],
+    [9.901069e-1, 1.48958e-1, -1.0251146e-1, 1.0282359e0, 5.057066e-1, 1.3870388e-4],
+    [9.9695295e-1, 7.800514e-2, -8.580565e-2, 1.0966481e0, 4.637523e-1, 1.286114e-4],
+    [9.98368e-1, 5.7107642e-2, -6.281841e-2, 1.0982047e0, 4.2367256e-1, 1.1821903e-4],
+    [9.9931806e-1, 3.692408e-2, -4.061648e-2, 1.0992497e0, 3.8569126e-1, 1.0766174e-4],
+    [9.998432e-1, 1.7708372e-2, -1.9479208e-2, 1.0998274e0, 3.5005742e-1, 9.749939e-5],
+    [1e0, -3.9620575e-4, 4.3582628e-4, 1.0999999e0, 3.169149e-1, 8.800387e-5],
+    [8.698467e-1, 3.343701e-1, -1.3655155e-3, 3.55204e-3, 9.999998e-1, 1.3142115e-6],
+    [8.698467e-1, 3.343701e-1, -1.3655155e-3, 3.55204e-3, 9.999998e-1, 1.3142115e-6],
+    [8.699873e-1, 3.3446154e-1, -2.7878778e-3, 7.252006e-3, 9.999986e-1, 1.3278908e-6],
+    [8.717565e-1, 3.3512524e-1, -6.2738727e-3, 1.6320767e-2, 9.9999374e-1, 1.4060464e-6],
+    [8.7222046e-1, 3.3531782e-1, -1.1158463e-2, 2.9031035e-2, 9.999787e-1, 1.6642914e-6],
+    [8.719202e-1, 3.3517078e-1, -1.742016e-2, 4.5333825e-2, 9.9994713e-1, 2.4714052e-6],
+    [8.697241e-1, 3.3435157e-1, -2.5051855e-2, 6.522476e-2, 9.9988323e-1, 5.0625104e-6],
+    [8.653901e-1, 3.3246645e-1, -3.4003176e-2, 8.859834e-2, 9.997548e-1, 1.33101785e-5],
+    [8.685077e-1, 3.339554e-1, -4.4481765e-2, 1.1605588e-1, 9.994469e-1, 3.7332356e-5],
+    [8.694443e-1, 3.3395702e-1, -5.6059964e-2, 1.4733045e-1, 9.969797e-1, 7.6461954e-5],
+    [8.7006867e-1, 3.3372328e-1, -6.774713e-2, 1.8185417e-1, 9.891729e-1, 5.0243652e-5],
+    [8.6479e-1, 3.3222213e-1, -8.042266e-2, 2.1801998e-1, 9.8310965e-1, 7.610102e-5],
+    [8.8410383e-1, 3.3671948e-1, -9.538961e-2, 2.6117983e-1, 9.71781e-1, 1.0763891e-4],
+    [8.776748e-1, 3.371038e-1, -1.09936364e-1, 3.0119362e-1, 9.5384604e-1, 1.2414427e-4],
+    [8.8810515e-1, 3.3710912e-1, -1.2574342e-1, 3.5341844e-1, 9.393428e-1, 1.4669215e-4],
+    [8.986543e-1, 3.4013525e-1, -1.3797012e-1, 4.0186366e-1, 9.223486e-1, 1.7487066e-4],
+    [9.082265e-1, 3.370332e-1, -1.531203e-1, 4.6412176e-1, 8.9912367e-1, 1.9442364e-4],
+    [9.160861e-1, 3.345474e-1, -1.6451174e-1, 5.189924e-1, 8.700069e-1, 2.0999121e-4],
+    [9.2856854e-1, 3.375153e-1, -1.72817e-1, 5.761245e-1, 8.385112e-1, 2.215339e-4],
+    [9.165087e-1, 3.2047048e-1, -1.7568381e-1, 6.2717295e-1, 8.038198e-1, 2.2926625e-4],
+    [9.048469e-1, 2.8437382e-1, -1.7334855e-1, 6.658184e-1, 7.646583e-1, 2.3103767e-4],
+    [1.0056989e0, 2.9261836e-1, -1.8092047e-1, 7.63846e-1, 7.244874e-1, 2.3010775e-4],
+    [9.0511656e-1, 2.4106836e-1, -1.6664994e-1, 7.7939945e-1, 6.814607e-1, 2.2442144e-4],
+    [9.326663e-1, 2.148204e-1, -1.6268206e-1, 8.631989e-1, 6.380374e-1, 2.1530107e-4],
+    [9.907173e-1, 2.479738e-1, -1.542343e-1, 9.4302636e-1, 5.939818e-1, 2.0458747e-4],
+    [9.905393e-1, 1.3722856e-1, -1.3722856e-1, 9.905393e-1, 5.502103e-1, 1.9224484e-4],
+    [9.880217e-1, 1.6221294e-1, -1.1628154e-1, 1.0267698e0, 5.073858e-1, 1.7819567e-4],
+    [9.9611706e-1, 8.803888e-2, -9.684276e-2, 1.0957286e0, 4.659116e-1, 1.6319657e-4],
+    [9.979165e-1, 6.451811e-2, -7.096991e-2, 1.0977081e0, 4.2617399e-1, 1.4881162e-4],
+    [9.9911577e-1, 4.2046633e-2, -4.625129e-2, 1.0990273e0, 3.8851616e-1, 1.3524183e-4],
+    [9.9979186e-1, 2.0405507e-2, -2.2446053e-2, 1.0997709e0, 3.5317555e-1, 1.2227672e-4],
+    [1.0000001e0, -2.250375e-4, 2.4754123e-4, 1.1e0, 3.2022157e-1, 1.09888715e-4],
+    [8.2819045e-1, 3.633922e-1, -1.4901594e-3, 3.395845e-3, 9.999996e-1, 4.2645993e-6],
+    [8.2819045e-1, 3.633922e-1, -1.4901594e-3, 3.395845e-3, 9.999996e-1, 4.2645993e-6],
+    [8.3940876e-1, 3.6835182e-1, -3.0678613e-3, 6.991258e-3, 9.999982e-1, 4.298854e-6],
+    [8.388481e-1, 3.6810532e-1, -6.890699e-3, 1.5703954e-2, 9.999916e-1, 4.491265e-6],
+    [8.3834326e-1, 3.678813e-1, -1.2250594e-2, 2.7923705e-2, 9.999728e-1, 5.0976787e-6],
+    [8.377482e-1, 3.6759806e-1, -1.9135688e-2, 4.3632023e-2, 9.9993294e-1, 6.840035e-6],
+    [8.387491e-1, 3.6801505e-1, -2.7576802e-2, 6.291686e-2, 9.9985015e-1, 1.1862967e-5],
+    [8.426488e-1, 3.6960006e-1, -3.759324e-2, 8.5854724e-2, 9.9968994e-1, 2.6192487e-5],
+    [8.4251684e-1, 3.694072e-1, -4.9020596e-2, 1.121457e-1, 9.9929637e-1, 6.3781365e-5],
+    [8.441432e-1, 3.6999553e-1, -6.1325304e-2, 1.4207554e-1, 9.946635e-1, 8.071113e-5],
+    [8.3752793e-1, 3.6719745e-1, -7.417759e-2, 1.7438617e-1, 9.888592e-1, 7.8554185e-5],
+    [8.381502e-1, 3.665874e-1, -8.8176936e-2, 2.0938468e-1, 9.8320454e-1, 1.2308305e-4],
+    [8.453946e-1, 3.6615175e-1, -1.04528576e-1, 2.5231883e-1, 9.7142947e-1, 1.4575929e-4],
+    [8.3829767e-1, 3.654883e-1, -1.19267784e-1, 2.8933117e-1, 9.5509094e-1, 1.8887199e-4],
+    [8.626244e-1, 3.742828e-1, -1.3858631e-1, 3.4233472e-1, 9.3737435e-1, 2.1569981e-4],
+    [8.3351e-1, 3.62243e-1, -1.5012275e-1, 3.853519e-1, 9.1991115e-1, 2.4410823e-4],
+    [8.1833744e-1, 3.4305432e-1, -1.5839726e-1, 4.2441544e-1, 8.973389e-1, 2.7055386e-4],
+    [8.697205e-1, 3.7649065e-1, -1.7651561e-1, 4.9461582e-1, 8.6885023e-1, 2.9180685e-4],
+    [9.1591007e-1, 3.7049282e-1, -1.9330105e-1, 5.6957865e-1, 8.3591735e-1, 3.0533582e-4],
+    [9.322263e-1, 3.6879832e-1, -1.9433774e-1, 6.1576253e-1, 8.0212456e-1, 3.1358615e-4],
+    [9.5037323e-1, 3.470942e-1, -2.0085247e-1, 6.877583e-1, 7.636941e-1, 3.1398216e-4],
+    [9.603001e-1, 3.4108123e-1, -2.0511968e-1, 7.7016973e-1, 7.2310305e-1, 3.0758718e-4],
+    [9.86087e-1, 3.241937e-1, -1.983507e-1, 8.291782e-1, 6.812028e-1, 2.983494e-4],
+    [9.403564e-1, 2.8149983e-1, -1.8372336e-1, 8.6906385e-1, 6.381334e-1, 2.8469015e-4],
+    [9.8581684e-1, 2.6679e-1, -1.7215957e-1, 9.399192e-1, 5.9457463e-1, 2.673196e-4],
+    [9.8830724e-1, 1.5247557e-1, -1.5247557e-1, 9.8830724e-1, 5.515844e-1, 2.4799016e-4],
+    [9.858082e-1, 1.7516361e-1, -1.297446e-1, 1.0251557e0, 5.0924516e-1, 2.288343e-4],
+    [9.951165e-1, 9.8709434e-2, -1.08580366e-1, 1.094628e0, 4.683531e-1, 2.0945679e-4],
+    [9.9739194e-1, 7.2174706e-2, -7.939217e-2, 1.097131e0, 4.2919534e-1, 1.9012576e-4],
+    [9.9890465e-1, 4.6791263e-2, -5.147038e-2, 1.098795e0, 3.9193985e-1, 1.7159547e-4],
+    [9.9974585e-1, 2.254587e-2, -2.4800455e-2, 1.0997204e0, 3.568673e-1, 1.5419922e-4],
+    [1e0, -2.472935e-4, 2.7202282e-4, 1.0999999e0, 3.240729e-1, 1.3831834e-4],
+    [8.0504966e-1, 3.990515e-1, -1.627028e-3, 3.2819037e-3, 9.9999976e-1, 1.2224477e-5],
+    [8.0504966e-1, 3.990515e-1, -1.627028e-3, 3.2819037e-3, 9.9999976e-1, 1.2224477e-5],
+    [8.024058e-1, 3.977769e-1, -3.317124e-3, 6.6911275e-3, 9.999981e-1, 1.2302006e-5],
+    [8.033006e-1, 3.9823994e-1, -7.4527157e-3, 1.5034351e-2, 9.999892e-1, 1.2732159e-5],
+    [8.031428e-1, 3.981504e-1, -1.325417e-2, 2.6743097e-2, 9.999651e-1, 1.4041426e-5],
+    [8.042513e-1, 3.9871827e-1, -2.0717314e-2, 4.1819528e-2, 9.999134e-1, 1.7568063e-5],
+    [8.0340564e-1, 3.9824706e-1, -2.9826548e-2, 6.0253546e-2, 9.998112e-1, 2.6880738e-5],
+    [8.033891e-1, 3.9813793e-1, -4.057873e-2, 8.20775e-2, 9.9961305e-1, 5.1034895e-5],
+    [8.047058e-1, 3.9872172e-1, -5.293816e-2, 1.0731677e-1, 9.990893e-1, 1.0821833e-4],
+    [7.957541e-1, 3.9386278e-1, -6.5724514e-2, 1.3515231e-1, 9.935071e-1, 1.1005958e-4],
+    [8.070793e-1, 3.9869067e-1, -8.042323e-2, 1.6736253e-1, 9.8873204e-1, 1.3109876e-4],
+    [8.208142e-1, 4.039136e-1, -9.7505115e-2, 2.052069e-1, 9.8296535e-1, 1.9604148e-4],
+    [8.1885976e-1, 4.0245202e-1, -1.13047734e-1, 2.4244228e-1, 9.713152e-1, 2.207428e-4],
+    [7.87305e-1, 3.8707513e-1, -1.2844895e-1, 2.7891836e-1, 9.55632e-1, 2.7641444e-4],
+    [8.2241344e-1, 4.0309554e-1, -1.4977832e-1, 3.2900134e-1, 9.351006e-1, 3.200264e-4],
+    [8.1793565e-1, 3.961565e-1, -1.6581637e-1, 3.7886384e-1, 9.175421e-1, 3.5564808e-4],
+    [8.233803e-1, 3.9017066e-1, -1.7679599e-1, 4.2269087e-1, 8.9537984e-1, 3.885442e-4],
+    [8.556314e-1, 4.0749678e-1, -1.9417328e-1, 4.8795417e-1, 8.672092e-1, 4.1083878e-4],
+    [8.398963e-1, 3.7620327e-1, -1.9974665e-1, 5.2933407e-1, 8.3420086e-1, 4.2490746e-4],
+    [9.197817e-1, 3.9882284e-1, -2.141957e-1, 6.0913944e-1, 7.997649e-1, 4.289463e-4],
+    [9.073844e-1, 3.875655e-1, -2.1529673e-1, 6.650141e-1, 7.62403e-1, 4.2611157e-4],
+    [9.665218e-1, 3.8665882e-1, -2.154757e-1, 7.281493e-1, 7.2184485e-1, 4.1515144e-4],
+    [9.780735e-1, 3.4762242e-1, -2.180725e-1, 8.24211e-1, 6.8075085e-1, 3.9718667e-4],
+    [1.0013374e0, 3.131532e-1, -2.031539e-1, 8.6853236e-1, 6.379411e-1, 3.739707e-4],
+    [9.8087955e-1, 2.8440693e-1, -1.8896946e-1, 9.366844e-1, 5.9556085e-1, 3.4910554e-4],
+    [1.0274991e0, 2.521797e-1, -1.6862708e-1, 9.8714167e-1, 5.529574e-1, 3.2263395e-4],
+    [9.8351604e-1, 1.8760647e-1, -1.426883e-1, 1.0234343e0, 5.1150036e-1, 2.9546206e-4],
+    [9.94117e-1, 1.0831164e-1, -1.191428e-1, 1.0935286e0, 4.7115842e-1, 2.6789002e-4],
+    [9.968205e-1, 7.9680495e-2, -8.764853e-2, 1.0965024e0, 4.32389e-1, 2.4186775e-4],
+    [9.9865246e-1, 5.1898863e-2, -5.7088744e-2, 1.0985175e0, 3.9558402e-1, 2.1756155e-4],
+    [9.996886e-1, 2.4954282e-2, -2.7449707e-2, 1.0996574e0, 3.6093965e-1, 1.9486004e-4],
+    [1e0, -3.6965526e-4, 4.0662073e-4, 1.0999999e0, 3.284021e-1, 1.7396727e-4],
+    [7.649031e-1, 4.2501938e-1, -1.7266468e-3, 3.1069627e-3, 9.999995e-1, 3.169511e-5],
+    [7.649031e-1, 4.2501938e-1, -1.7266468e-3, 3.1069627e-3, 9.999995e-1, 3.169511e-5],
+    [7.6431924e-1, 4.2475256e-1, -3.535694e-3, 6.3623013e-3, 9.99998e-1, 3.1856594e-5],
+    [7.6471347e-1, 4.2497876e-1, -7.9532815e-3, 1.4312911e-2, 9.9998605e-1, 3.2744414e-5],
+    [7.6323074e-1, 4.241412e-1, -1.4128158e-2, 2.5431948e-2, 9.999583e-1, 3.5376415e-5],
+    [7.6347864e-1, 4.2423934e-1, -2.2065228e-2, 3.974064e-2, 9.9989015e-1, 4.2116862e-5],
+    [7.6359206e-1, 4.242968e-1, -3.1753175e-2, 5.72443e-2, 9.997658e-1, 5.867873e-5],
+    [7.671692e-1, 4.261539e-1, -4.324175e-2, 7.807879e-2, 9.9951994e-1, 9.8214245e-5],
+    [7.724941e-1, 4.2891943e-1, -5.666804e-2, 1.0262698e-1, 9.987714e-1, 1.8181346e-4],
+    [7.7354e-1, 4.2943433e-1, -7.0814945e-2, 1.301928e-1, 9.926279e-1, 1.6720757e-4],
+    [7.65172e-1, 4.2520356e-1, -8.5793994e-2, 1.5942833e-1, 9.8845387e-1, 2.19349e-4],
+    [7.703684e-1, 4.2780703e-1, -1.0292431e-1, 1.9384667e-1, 9.821357e-1, 3.0121184e-4],
+    [7.714567e-1, 4.2595166e-1, -1.2059081e-1, 2.3157212e-1, 9.7097915e-1, 3.4435056e-4],
+    [7.651652e-1, 4.195884e-1, -1.3790853e-1, 2.697917e-1, 9.553983e-1, 4.024553e-4],
+    [8.0180645e-1, 4.3905616e-1, -1.5875655e-1, 3.1493574e-1, 9.3412036e-1, 4.6620052e-4],
+    [8.048173e-1, 4.3468267e-1, -1.7483708e-1, 3.591164e-1, 9.146012e-1, 5.151676e-4],
+    [8.159538e-1, 4.3507034e-1, -1.8957798e-1, 4.0855122e-1, 8.924656e-1, 5.512112e-4],
+    [8.2429016e-1, 4.4058865e-1, -2.0471348e-1, 4.6563515e-1, 8.650881e-1, 5.754865e-4],
+    [8.4822613e-1, 4.2346916e-1, -2.1384187e-1, 5.15135e-1, 8.32557e-1, 5.902525e-4],
+    [8.4202796e-1, 4.0306503e-1, -2.2091557e-1, 5.687442e-1, 7.9682034e-1, 5.9015607e-4],
+    [8.555789e-1, 3.8977548e-1, -2.2057152e-1, 6.1998266e-1, 7.6056296e-1, 5.7833805e-4],
+    [9.564615e-1, 4.1091442e-1, -2.337733e-1, 7.2248274e-1, 7.208834e-1, 5.560333e-4],
+    [9.479077e-1, 3.7096208e-1, -2.2294217e-1, 7.68463e-1, 6.799279e-1, 5.2688643e-4],
+    [9.9484897e-1, 3.3319256e-1, -2.2054927e-1, 8.64279e-1, 6.38529e-1, 4.945732e-4],
+    [9.1710675e-1, 2.7493078e-1, -1.9404903e-1, 8.78832e-1, 5.963175e-1, 4.566391e-4],
+    [1.0236863e0, 2.672365e-1, -1.8310042e-1, 9.845597e-1, 5.547645e-1, 4.184946e-4],
+    [9.811363e-1, 1.9967932e-1, -1.5525508e-1, 1.0216035e0, 5.1405996e-1, 3.8102013e-4],
+    [9.9296534e-1, 1.1840448e-1, -1.3024491e-1, 1.0922618e0, 4.7439724e-1, 3.4484905e-4],
+    [9.962437e-1, 8.659362e-2, -9.5252976e-2, 1.095868e0, 4.3642902e-1, 3.098575e-4],
+    [9.9841994e-1, 5.6195088e-2, -6.181459e-2, 1.098262e0, 4.0007308e-1, 2.7693663e-4],
+    [9.996256e-1, 2.736189e-2, -3.0098077e-2, 1.099588e0, 3.6560556e-1, 2.4671428e-4],
+    [9.999999e-1, -2.7271532e-4, 2.9998683e-4, 1.0999998e0, 3.3327615e-1, 2.1912013e-4],
+    [7.153658e-1, 4.429882e-1, -1.808245e-3, 2.91968e-3, 9.999993e-1, 7.563996e-5],
+    [7.153658e-1, 4.429882e-1, -1.808245e-3, 2.91968e-3, 9.999993e-1, 7.563996e-5],
+    [7.2126496e-1, 4.4670156e-1, -3.7282482e-3, 6.019946e-3, 9.999968e-1, 7.595378e-5],
+    [7.2308975e-1, 4.478302e-1, -8.382033e-3, 1.353595e-2, 9.9998385e-1, 7.766665e-5],
+    [7.22511e-1, 4.4746596e-1, -1.4898229e-2, 2.406649e-2, 9.9994606e-1, 8.2641774e-5],
+    [7.2203004e-1, 4.471353e-1, -2.3246964e-2, 3.7578076e-2, 9.998668e-1, 9.488053e-5],
+    [7.2165364e-1, 4.468612e-1, -3.344766e-2, 5.4131795e-2, 9.997101e-1, 1.2320768e-4],
+    [7.2683376e-1, 4.498782e-1, -4.5641717e-2, 7.401132e-2, 9.994055e-1, 1.8607618e-4],
+    [7.2183454e-1, 4.470997e-1, -5.9117023e-2, 9.627718e-2, 9.981298e-1, 2.9755526e-4],
+    [7.227519e-1, 4.47332e-1, -7.38584e-2, 1.2207431e-1, 9.920385e-1, 2.7755584e-4],
+    [7.2614783e-1, 4.4916514e-1, -9.0914026e-2, 1.5189505e-1, 9.8796135e-1, 3.6521567e-4],
+    [7.220119e-1, 4.4818047e-1, -1.08071335e-1, 1.8361816e-1, 9.8049974e-1, 4.4125464e-4],
+    [7.4141043e-1, 4.5574352e-1, -1.283779e-1, 2.2229335e-1, 9.7026634e-1, 5.3629244e-4],
+    [7.407028e-1, 4.5113602e-1, -1.4656916e-1, 2.5948763e-1, 9.553831e-1, 6.1313855e-4],
+    [7.5717473e-1, 4.609506e-1, -1.6726907e-1, 3.022491e-1, 9.3410116e-1, 6.846354e-4],
+    [7.584785e-1, 4.5553905e-1, -1.8515654e-1, 3.4451392e-1, 9.112311e-1, 7.4541365e-4],
+    [7.6121217e-1, 4.4939628e-1, -1.9701043e-1, 3.8535836e-1, 8.891621e-1, 7.890148e-4],
+    [7.7771115e-1, 4.5014426e-1, -2.124246e-1, 4.4007644e-1, 8.6235046e-1, 8.132614e-4],
+    [8.225148e-1, 4.7289476e-1, -2.2837307e-1, 5.0406337e-1, 8.3058476e-1, 8.1740157e-4],
+    [8.507771e-1, 4.4905394e-1, -2.3508042e-1, 5.558332e-1, 7.946962e-1, 8.0611e-4],
+    [8.4419566e-1, 4.1385236e-1, -2.3804332e-1, 6.134865e-1, 7.58197e-1, 7.81044e-4],
+    [8.8403505e-1, 3.951201e-1, -2.3528744e-1, 6.696406e-1, 7.199329e-1, 7.476872e-4],
+    [9.392321e-1, 3.9240903e-1, -2.4034914e-1, 7.631977e-1, 6.793382e-1, 7.006572e-4],
+    [9.0629953e-1, 3.2933474e-1, -2.1644308e-1, 7.8298074e-1, 6.387705e-1, 6.506374e-4],
+    [9.973102e-1, 3.4821767e-1, -2.2195695e-1, 9.2942035e-1, 5.976289e-1, 5.985816e-4],
+    [1.0197282e0, 2.8196296e-1, -1.9727166e-1, 9.818185e-1, 5.5706805e-1, 5.4368534e-4],
+    [9.7866637e-1, 2.1145253e-1, -1.675177e-1, 1.0196645e0, 5.170681e-1, 4.915948e-4],
+    [9.918759e-1, 1.2720887e-1, -1.3992977e-1, 1.0910634e0, 4.782993e-1, 4.421791e-4],
+    [9.9558663e-1, 9.384665e-2, -1.0323131e-1, 1.0951452e0, 4.4066134e-1, 3.95551e-4],
+    [9.981446e-1, 6.0889583e-2, -6.697854e-2, 1.097959e0, 4.0489537e-1, 3.5238708e-4],
+    [9.995674e-1, 2.9409466e-2, -3.235041e-2, 1.099524e0, 3.7094694e-1, 3.1293675e-4],
+    [1.0454544e0, -3.0390953e-4, 3.3430045e-4, 1.1499997e0, 3.388967e-1, 2.7734393e-4],
+    [7.0366526e-1, 4.8351792e-1, -1.9392194e-3, 2.8217717e-3, 9.999995e-1, 1.6837385e-4],
+    [7.0366526e-1, 4.8351792e-1, -1.9392194e-3, 2.8217717e-3, 9.999995e-1, 1.6837385e-4],
+    [6.819414e-1, 4.6865946e-1, -3.8973594e-3, 5.671221e-3, 9.999963e-1, 1.6894819e-4],
+    [6.792818e-1, 4.668238e-1, -8.740891e-3, 1.2721159e-2, 9.999799e-1, 1.7206647e-4],
+    [6.79523e-1, 4.6698472e-1, -1.5544177e-2, 2.2631254e-2, 9.9993265e-1, 1.8097684e-4],
+    [6.8030995e-1, 4.6750402e-1, -2.4282495e-2, 3.538268e-2, 9.998359e-1, 2.0219284e-4],
+    [6.809319e-1, 4.6789974e-1, -3.4970645e-2, 5.1031552e-2, 9.9964464e-1, 2.4889578e-4],
+    [6.78344e-1, 4.6597308e-1, -4.7385883e-2, 6.931588e-2, 9.9926436e-1, 3.4601247e-4],
+    [6.8209064e-1, 4.682788e-1, -6.1710764e-2, 9.0983756e-2, 9.9624443e-1, 4.393111e-4],
+    [6.792386e-1, 4.659794e-1, -7.7042796e-2, 1.1501612e-1, 9.914473e-1, 4.6752382e-4],
+    [6.8125767e-1, 4.6644258e-1, -9.464098e-2, 1.4290594e-1, 9.87322e-1, 6.0512905e-4],
+    [6.902328e-1, 4.7216138e-1, -1.1269038e-1, 1.7332186e-1, 9.794599e-1, 6.938403e-4],
+    [6.830903e-1, 4.7021875e-1, -1.3146703e-1, 2.0643957e-1, 9.693039e-1, 8.256001e-4],
+    [6.9551295e-1, 4.7624433e-1, -1.5198927e-1, 2.4478859e-1, 9.5431393e-1, 9.271724e-4],
+    [6.955913e-1, 4.6703327e-1, -1.7105685e-1, 2.8272566e-1, 9.339036e-1, 1.0183678e-3],
+    [7.257707e-1, 4.8072132e-1, -1.9489895e-1, 3.3098483e-1, 9.080206e-1, 1.0836457e-3],
+    [7.438275e-1, 4.7762072e-1, -2.1132028e-1, 3.7770084e-1, 8.853189e-1, 1.1231712e-3],
+    [7.7623826e-1, 4.9672776e-1, -2.2621481e-1, 4.29615e-1, 8.5895294e-1, 1.1416323e-3],
+    [7.985009e-1, 4.9321863e-1, -2.3824589e-1, 4.8420078e-1, 8.2794607e-1, 1.1338785e-3],
+    [8.3764464e-1, 4.7309864e-1, -2.50813e-1, 5.4891354e-1, 7.929386e-1, 1.1049113e-3],
+    [8.571722e-1, 4.801394e-1, -2.4970971e-1, 5.935318e-1, 7.557213e-1, 1.0608097e-3],
+    [9.181484e-1, 4.74451e-1, -2.6356724e-1, 6.9540036e-1, 7.1870667e-1, 9.997898e-4],
+    [9.307442e-1, 4.1213682e-1, -2.5639698e-1, 7.5795716e-1, 6.793967e-1, 9.3218853e-4],
+    [8.995754e-1, 3.472813e-1, -2.3196113e-1, 7.785246e-1, 6.389282e-1, 8.558248e-4],
+    [9.818623e-1, 3.1420648e-1, -2.272512e-1, 8.8805133e-1, 5.9952974e-1, 7.823534e-4],
+    [1.0155323e0, 2.9672036e-1, -2.1148826e-1, 9.788546e-1, 5.5950594e-1, 7.0733705e-4],
+    [9.762876e-1, 2.2217716e-1, -1.7869481e-1, 1.0177652e0, 5.20536e-1, 6.328125e-4],
+    [9.9061996e-1, 1.3664596e-1, -1.5031055e-1, 1.0896819e0, 4.8229662e-1, 5.669762e-4],
+    [9.9498874e-1, 9.998652e-2, -1.0998516e-1, 1.0944875e0, 4.4579422e-1, 5.0522265e-4],
+    [9.978786e-1, 6.5101534e-2, -7.161168e-2, 1.0976665e0, 4.1053057e-1, 4.4827143e-4],
+    [9.994968e-1, 3.1720486e-2, -3.489253e-2, 1.0994464e0, 3.7690225e-1, 3.9685264e-4],
+    [1e0, -3.6121445e-4, 4.1539656e-4, 1.1499999e0, 3.4519404e-1, 3.5108245e-4],
+    [6.247234e-1, 4.748988e-1, -1.9423999e-3, 2.554795e-3, 9.9999857e-1, 3.53218e-4],
+    [6.247234e-1, 4.748988e-1, -1.9423999e-3, 2.554795e-3, 9.9999857e-1, 3.53218e-4],
+    [6.340505e-1, 4.820574e-1, -4.021454e-3, 5.289515e-3, 9.9999464e-1, 3.542161e-4],
+    [6.339031e-1, 4.8195416e-1, -9.021624e-3, 1.1868472e-2, 9.9997514e-1, 3.596101e-4],
+    [6.336025e-1, 4.8171845e-1, -1.603597e-2, 2.1106416e-2, 9.999192e-1, 3.7482165e-4],
+    [6.3343924e-1, 4.8155555e-1, -2.5025995e-2, 3.297217e-2, 9.997988e-1, 4.1007623e-4],
+    [6.326201e-1, 4.808759e-1, -3.5989013e-2, 4.7501285e-2, 9.9956614e-1, 4.844347e-4],
+    [6.349845e-1, 4.823945e-1, -4.9020108e-2, 6.489353e-2, 9.990849e-1, 6.299818e-4],
+    [6.3637006e-1, 4.8339835e-1, -6.36128e-2, 8.5097365e-2, 9.9478126e-1, 6.987186e-4],
+    [6.3362974e-1, 4.8070237e-1, -7.96285e-2, 1.0769624e-1, 9.911165e-1, 8.0674543e-4],
+    [6.4561385e-1, 4.8923874e-1, -9.798972e-2, 1.3425349e-1, 9.865497e-1, 9.922376e-4],
+    [6.369615e-1, 4.810801e-1, -1.1597028e-1, 1.6203864e-1, 9.784793e-1, 1.1110804e-3],
+    [6.599761e-1, 4.950802e-1, -1.373553e-1, 1.9652495e-1, 9.677098e-1, 1.2555812e-3],
+    [6.59006e-1, 4.9336216e-1, -1.5824288e-1, 2.3293562e-1, 9.5270103e-1, 1.3854905e-3],
+    [6.664652e-1, 4.9186257e-1, -1.7847039e-1, 2.7106547e-1, 9.324236e-1, 1.485916e-3],
+    [6.908627e-1, 5.074953e-1, -1.973242e-1, 3.0926135e-1, 9.066464e-1, 1.5636248e-3],
+    [7.097539e-1, 5.075037e-1, -2.1622781e-1, 3.548554e-1, 8.8113385e-1, 1.607486e-3],
+    [7.373435e-1, 5.221558e-1, -2.3419608e-1, 4.097049e-1, 8.552413e-1, 1.608493e-3],
+    [7.481432e-1, 4.9587026e-1, -2.436424e-1, 4.5816287e-1, 8.251469e-1, 1.5763718e-3],
+    [7.842184e-1, 4.9034676e-1, -2.535458e-1, 5.1650435e-1, 7.9080206e-1, 1.5072003e-3],
+    [8.25053e-1, 5.021336e-1, -2.687974e-1, 5.9614164e-1, 7.5405043e-1, 1.4340166e-3],
+    [8.2875055e-1, 4.5214766e-1, -2.5968143e-1, 6.379035e-1, 7.1691316e-1, 1.3351289e-3],
+    [8.913311e-1, 4.532346e-1, -2.585765e-1, 7.1561116e-1, 6.7913103e-1, 1.2333733e-3],
+    [9.0279764e-1, 4.1224208e-1, -2.4874297e-1, 7.8369445e-1, 6.4007753e-1, 1.1247706e-3],
+    [8.8322103e-1, 3.3141452e-1, -2.3422037e-1, 8.574758e-1, 6.0122263e-1, 1.0180367e-3],
+    [1.011698e0, 3.0954152e-1, -2.2385232e-1, 9.761013e-1, 5.625863e-1, 9.159679e-4],
+    [9.73578e-1, 2.3376474e-1, -1.9077843e-1, 1.0155696e0, 5.24354e-1, 8.197148e-4],
+    [9.8947537e-1, 1.4470121e-1, -1.5917133e-1, 1.0884228e0, 4.874557e-1, 7.289846e-4],
+    [9.94293e-1, 1.06685154e-1, -1.17353655e-1, 1.0937222e0, 4.5128596e-1, 6.459994e-4],
+    [9.976019e-1, 6.921369e-2, -7.613505e-2, 1.097362e0, 4.1669902e-1, 5.714644e-4],
+    [9.9943656e-1, 3.356573e-2, -3.6922295e-2, 1.0993801e0, 3.8365486e-1, 5.045407e-4],
+    [9.999999e-1, -3.333144e-4, 3.833115e-4, 1.1499996e0, 3.5222507e-1, 4.4406246e-4],
+    [5.810396e-1, 4.8773313e-1, -1.9970061e-3, 2.3786486e-3, 9.9999875e-1, 7.040629e-4],
+    [5.810396e-1, 4.8773313e-1, -1.9970061e-3, 2.3786486e-3, 9.9999875e-1, 7.040629e-4],
+    [5.9149057e-1, 4.9657977e-1, -4.121847e-3, 4.909769e-3, 9.9999374e-1, 7.057182e-4],
+    [5.866272e-1, 4.9250835e-1, -9.220576e-3, 1.0985562e-2, 9.999688e-1, 7.1463385e-4],
+    [5.867179e-1, 4.9255854e-1, -1.6390981e-2, 1.9539986e-2, 9.9990094e-1, 7.394958e-4],
+    [5.874507e-1, 4.9313268e-1, -2.5612654e-2, 3.0570693e-2, 9.997547e-1, 7.958263e-4],
+    [5.86861e-1, 4.925912e-1, -3.6773015e-2, 4.39877e-2, 9.994709e-1, 9.103036e-4],
+    [5.848603e-1, 4.9077556e-1, -4.9897075e-2, 5.9907332e-2, 9.988342e-1, 1.1208637e-3],
+    [5.882717e-1, 4.9322057e-1, -6.470499e-2, 7.861722e-2, 9.939414e-1, 1.1818028e-3],
+    [5.8731043e-1, 4.9208978e-1, -8.131171e-2, 9.992296e-2, 9.904337e-1, 1.3614882e-3],
+    [5.898309e-1, 4.9306187e-1, -9.9364646e-2, 1.2404416e-1, 9.8479617e-1, 1.5607866e-3],
+    [5.90281e-1, 4.936122e-1, -1.18507184e-1, 1.5087588e-1, 9.7735554e-1, 1.7750341e-3],
+    [6.0699725e-1, 5.047781e-1, -1.4033093e-1, 1.8345216e-1, 9.660065e-1, 1.9364775e-3],
+    [5.9235424e-1, 4.8895872e-1, -1.5791689e-1, 2.1311422e-1, 9.508949e-1, 2.0794012e-3],
+    [6.379368e-1, 5.2106184e-1, -1.8385431e-1, 2.571426e-1, 9.310057e-1, 2.1966654e-3],
+    [6.2579864e-1, 5.0401247e-1, -2.0133822e-1, 2.924001e-1, 9.053958e-1, 2.262792e-3],
+    [6.7058325e-1, 5.3078735e-1, -2.2358073e-1, 3.3900246e-1, 8.7639904e-1, 2.277958e-3],
+    [7.132435e-1, 5.5085295e-1, -2.4457033e-1, 3.9633125e-1, 8.5049725e-1, 2.2424872e-3],
+    [7.338103e-1, 5.4003614e-1, -2.5738162e-1, 4.501688e-1, 8.212754e-1, 2.1689131e-3],
+    [7.9833585e-1, 5.522917e-1, -2.708872e-1, 5.1579225e-1, 7.881437e-1, 2.0527216e-3],
+    [8.1528556e-1, 5.2502686e-1, -2.7427596e-1, 5.7170725e-1, 7.528788e-1, 1.9285294e-3],
+    [8.429688e-1, 4.9516392e-1, -2.7312997e-1, 6.294157e-1, 7.155869e-1, 1.7800239e-3],
+    [8.8196206e-1, 4.712066e-1, -2.7302706e-1, 7.1022344e-1, 6.7904055e-1, 1.628492e-3],
+    [8.9585084e-1, 4.2712834e-1, -2.6168025e-1, 7.7947e-1, 6.416853e-1, 1.4722158e-3],
+    [9.2144054e-1, 4.0040106e-1, -2.4518253e-1, 8.47054e-1, 6.034097e-1, 1.3248362e-3],
+    [9.1789854e-1, 3.2757032e-1, -2.2976425e-1, 9.461648e-1, 5.66176e-1, 1.1866087e-3],
+    [9.712916e-1, 2.4308956e-1, -2.005079e-1, 1.0136933e0, 5.292063e-1, 1.0544232e-3],
+    [9.882073e-1, 1.5312219e-1, -1.684344e-1, 1.0870278e0, 4.9277428e-1, 9.3487615e-4],
+    [9.9367374e-1, 1.1230486e-1, -1.23535335e-1, 1.0930411e0, 4.577694e-1, 8.2671514e-4],
+    [9.9730587e-1, 7.335475e-2, -8.069022e-2, 1.0970364e0, 4.2379764e-1, 7.288516e-4],
+    [9.5591664e-1, 3.4015164e-2, -3.9117433e-2, 1.0993041e0, 3.911999e-1, 6.406213e-4],
+    [1e0, -3.539498e-4, 4.0704224e-4, 1.1499999e0, 3.6013457e-1, 5.624976e-4],
+    [5.4718655e-1, 5.0678414e-1, -2.0421122e-3, 2.2045383e-3, 9.9999857e-1, 1.3422878e-3],
+    [5.4718655e-1, 5.0678414e-1, -2.0421122e-3, 2.2045383e-3, 9.9999857e-1, 1.3422878e-3],
+    [5.3879917e-1, 4.9910048e-1, -4.1570743e-3, 4.4879443e-3, 9.9999213e-1, 1.3449206e-3],
+    [5.380324e-1, 4.9839494e-1, -9.332769e-3, 1.00782905e-2, 9.9996316e-1, 1.3590512e-3],
+    [5.3802574e-1, 4.9836862e-1, -1.659446e-2, 1.7932823e-2, 9.998793e-1, 1.3980914e-3],
+    [5.386672e-1, 4.9888474e-1, -2.5919318e-2, 2.8051542e-2, 9.9970156e-1, 1.4848241e-3],
+    [5.380626e-1, 4.9821776e-1, -3.724569e-2, 4.0417276e-2, 9.993532e-1, 1.6553364e-3],
+    [5.383385e-1, 4.9823317e-1, -5.058759e-2, 5.5155925e-2, 9.9839866e-1, 1.9426108e-3],
+    [5.3859764e-1, 4.982352e-1, -6.56502e-2, 7.253031e-2, 9.9312747e-1, 1.995032e-3],
+    [5.3674453e-1, 4.9601582e-1, -8.2173295e-2, 9.188781e-2, 9.897402e-1, 2.2734813e-3],
+    [5.42136e-1, 4.99639e-1, -1.0044696e-1, 1.14372164e-1, 9.833601e-1, 2.4965566e-3],
+    [5.480135e-1, 5.041933e-1, -1.2043549e-1, 1.4021058e-1, 9.752115e-1, 2.7543015e-3],
+    [5.535084e-1, 5.081361e-1, -1.4035903e-1, 1.6813658e-1, 9.637912e-1, 2.9605832e-3],
+    [5.558962e-1, 5.044866e-1, -1.6018161e-1, 1.9867754e-1, 9.4864464e-1, 3.1234324e-3],
+    [5.861831e-1, 5.2414715e-1, -1.8339738e-1, 2.3696008e-1, 9.287094e-1, 3.2142906e-3],
+    [6.1507523e-1, 5.400501e-1, -2.064489e-1, 2.7877408e-1, 9.037513e-1, 3.2478867e-3],
+    [6.3506734e-1, 5.44166e-1, -2.2701311e-1, 3.2138425e-1, 8.735508e-1, 3.2043648e-3],
+    [6.7287284e-1, 5.639753e-1, -2.4730754e-1, 3.727001e-1, 8.452241e-1, 3.125246e-3],
+    [7.066889e-1, 5.536049e-1, -2.6175883e-1, 4.2763546e-1, 8.166968e-1, 2.9718976e-3],
+    [7.4619204e-1, 5.49485e-1, -2.7233222e-1, 4.8651797e-1, 7.8526855e-1, 2.7868417e-3],
+    [7.785774e-1, 5.3059006e-1, -2.807512e-1, 5.5229574e-1, 7.515168e-1, 2.5836485e-3],
+    [8.2126075e-1, 5.3494555e-1, -2.8366622e-1, 6.1993265e-1, 7.1513027e-1, 2.3564228e-3],
+    [8.559372e-1, 5.03552e-1, -2.8080687e-1, 6.88849e-1, 6.786586e-1, 2.1427039e-3],
+    [8.9153385e-1, 4.487198e-1, -2.6832646e-1, 7.574558e-1, 6.4324766e-1, 1.9254133e-3],
+    [9.1607547e-1, 4.125284e-1, -2.563416e-1, 8.437441e-1, 6.067179e-1, 1.7195629e-3],
+    [9.3154174e-1, 2.8827792e-1, -2.3472433e-1, 9.162986e-1, 5.7015675e-1, 1.532314e-3],
+    [9.6888834e-1, 2.5249827e-1, -2.1032983e-1, 1.0117011e0, 5.3452426e-1, 1.3575971e-3],
+    [9.1090244e-1, 1.7939739e-1, -1.7202587e-1, 1.0583324e0, 4.993449e-1, 1.1982904e-3],
+    [9.93004e-1, 1.1808092e-1, -1.29889e-1, 1.0923042e0, 4.6496207e-1, 1.0535711e-3],
+    [9.970486e-1, 7.677278e-2, -8.445005e-2, 1.0967534e0, 4.3165883e-1, 9.250642e-4],
+    [9.9930316e-1, 3.732461e-2, -4.29233e-2, 1.1491985e0, 3.9968398e-1, 8.122012e-4],
+    [9.9999994e-1, -4.8330217e-4, 5.557974e-4, 1.1499997e0, 3.6909848e-1, 7.1411376e-4],
+    [4.923729e-1, 5.0324154e-1, -2.027287e-3, 1.9831283e-3, 9.999977e-1, 2.4608932e-3],
+    [4.923729e-1, 5.0324154e-1, -2.027287e-3, 1.9831283e-3, 9.999977e-1, 2.4608932e-3],
+    [4.8896593e-1, 4.9986324e-1, -4.171024e-3, 4.0804166e-3, 9.9999094e-1, 2.464914e-3],
+    [4.8890597e-1, 4.9978966e-1, -9.3562035e-3, 9.1559375e-3, 9.99953e-1, 2.4864494e-3],
+    [4.8905838e-1, 4.9990562e-1, -1.6636662e-2, 1.629474e-2, 9.9985355e-1, 2.5454564e-3],
+    [4.8958382e-1, 5.0039333e-1, -2.5988303e-2, 2.5500359e-2, 9.996359e-1, 2.6742977e-3],
+    [4.8964724e-1, 5.002989e-1, -3.73478e-2, 3.676588e-2, 9.992069e-1, 2.9198977e-3],
+    [4.9006534e-1, 5.0054294e-1, -5.0691094e-2, 5.026082e-2, 9.970325e-1, 3.221068e-3],
+    [4.9131292e-1, 5.0140953e-1, -6.575224e-2, 6.6050515e-2, 9.925186e-1, 3.359725e-3],
+    [4.9555716e-1, 5.0498915e-1, -8.285677e-2, 8.436152e-2, 9.8898923e-1, 3.7433936e-3],
+    [4.9566948e-1, 5.0426245e-1, -1.0062458e-1, 1.04548745e-1, 9.82114e-1, 4.0062694e-3],
+    [4.9554178e-1, 5.024573e-1, -1.199295e-1, 1.2780605e-1, 9.7309184e-1, 4.2729625e-3],
+    [5.006876e-1, 5.0547904e-1, -1.400829e-1, 1.5406111e-1, 9.612071e-1, 4.500592e-3],
+    [5.201295e-1, 5.1950413e-1, -1.6346358e-1, 1.8685569e-1, 9.455612e-1, 4.645623e-3],
+    [5.456452e-1, 5.360614e-1, -1.8682414e-1, 2.2349916e-1, 9.255286e-1, 4.6920115e-3],
+    [5.6541085e-1, 5.437286e-1, -2.0621547e-1, 2.593315e-1, 9.011788e-1, 4.6620127e-3],
+    [5.928571e-1, 5.6124055e-1, -2.2948983e-1, 3.0481392e-1, 8.716442e-1, 4.508446e-3],
+    [6.435601e-1, 5.94124e-1, -2.5498843e-1, 3.5960582e-1, 8.3979404e-1, 4.315652e-3],
+    [6.864764e-1, 5.887879e-1, -2.6910508e-1, 4.1224587e-1, 8.118631e-1, 4.054162e-3],
+    [7.066834e-1, 5.779095e-1, -2.7858007e-1, 4.6885845e-1, 7.818798e-1, 3.7554926e-3],
+    [7.734434e-1, 5.912031e-1, -2.9085663e-1, 5.419677e-1, 7.4968684e-1, 3.4431398e-3],
+    [8.1094545e-1, 5.5045766e-1, -2.953973e-1, 6.144294e-1, 7.151075e-1, 3.1197758e-3],
+    [8.6577487e-1, 5.379366e-1, -2.9038107e-1, 6.799798e-1, 6.799697e-1, 2.8076638e-3],
+    [8.840014e-1, 4.6338302e-1, -2.807999e-1, 7.5292087e-1, 6.447163e-1, 2.5092454e-3],
+    [9.1059124e-1, 4.2449656e-1, -2.6737535e-1, 8.403128e-1, 6.1041313e-1, 2.2278093e-3],
+    [9.93315e-1, 3.655268e-1, -2.4557495e-1, 9.19841e-1, 5.7522637e-1, 1.9731303e-3],
+    [9.473342e-1, 3.4906894e-1, -2.0468515e-1, 9.371665e-1, 5.4043627e-1, 1.7429164e-3],
+    [9.7478133e-1, 2.3290807e-1, -1.8854143e-1, 1.1119059e0, 5.0663966e-1, 1.5323422e-3],
+    [9.862101e-1, 1.7288667e-1, -1.3970372e-1, 1.1246898e0, 4.730282e-1, 1.3437624e-3],
+    [9.9677026e-1, 8.030471e-2, -8.833517e-2, 1.0964472e0, 4.4058067e-1, 1.1782518e-3],
+    [9.992382e-1, 3.902663e-2, -4.488062e-2, 1.1491238e0, 4.09165e-1, 1.0346322e-3],
+    [1e0, -3.1879984e-4, 3.6661976e-4, 1.1499999e0, 3.790646e-1, 9.058026e-4],
+    [4.4343776e-1, 5.007884e-1, -2.0225656e-3, 1.7905699e-3, 9.999976e-1, 4.358038e-3],
+    [4.4343776e-1, 5.007884e-1, -2.0225656e-3, 1.7905699e-3, 9.999976e-1, 4.358038e-3],
+    [4.4181868e-1, 4.9905884e-1, -4.148886e-3, 3.6732703e-3, 9.9998844e-1, 4.363944e-3],
+    [4.395601e-1, 4.965088e-1, -9.2936875e-3, 8.231549e-3, 9.999432e-1, 4.3955427e-3],
+    [4.395073e-1, 4.964038e-1, -1.6517606e-2, 1.4645412e-2, 9.998207e-1, 4.481484e-3],
+    [4.4003513e-1, 4.9690905e-1, -2.5797343e-2, 2.292404e-2, 9.9955547e-1, 4.6661203e-3],
+    [4.4038442e-1, 4.9712154e-1, -3.710803e-2, 3.3106327e-2, 9.9901724e-1, 5.007385e-3],
+    [4.40957e-1, 4.97451e-1, -5.0158728e-2, 4.521001e-2, 9.9529433e-1, 5.239118e-3],
+    [4.4181088e-1, 4.980679e-1, -6.5207906e-2, 5.9539303e-2, 9.9172986e-1, 5.5390936e-3],
+    [4.4661838e-1, 5.022334e-1, -8.204279e-2, 7.616102e-2, 9.870643e-1, 5.9473007e-3],
+    [4.4963962e-1, 5.048629e-1, -1.0076685e-1, 9.560791e-2, 9.8036563e-1, 6.3177315e-3],
+    [4.4928053e-1, 5.0130796e-1, -1.1933499e-1, 1.1648987e-1, 9.7074825e-1, 6.5967157e-3],
+    [4.6603245e-1, 5.1798415e-1, -1.4149728e-1, 1.4315663e-1, 9.578117e-1, 6.7600487e-3],
+    [4.6134713e-1, 5.0666213e-1, -1.5978138e-1, 1.6880001e-1, 9.413877e-1, 6.8169152e-3],
+    [4.9871168e-1, 5.381002e-1, -1.84259e-1, 2.0467678e-1, 9.21681e-1, 6.7682685e-3],
+    [5.1790017e-1, 5.4830927e-1, -2.0557976e-1, 2.4161917e-1, 8.9790493e-1, 6.593986e-3],
+    [5.533635e-1, 5.70378e-1, -2.2838145e-1, 2.8543898e-1, 8.69284e-1, 6.2989444e-3],
+    [6.167918e-1, 6.1397916e-1, -2.5429785e-1, 3.3920655e-1, 8.371756e-1, 5.9319353e-3],
+    [6.4804506e-1, 6.038963e-1, -2.7171993e-1, 3.9186263e-1, 8.063754e-1, 5.496785e-3],
+    [6.869561e-1, 6.032491e-1, -2.885999e-1, 4.5913157e-1, 7.779633e-1, 5.042096e-3],
+    [7.2701883e-1, 5.827603e-1, -2.9212546e-1, 5.1729596e-1, 7.4773574e-1, 4.568229e-3],
+    [7.9870665e-1, 5.819265e-1, -2.9987457e-1, 5.9597886e-1, 7.15668e-1, 4.1078925e-3],
+    [8.097884e-1, 5.2414954e-1, -2.9298618e-1, 6.590841e-1, 6.820446e-1, 3.6671713e-3],
+    [8.859927e-1, 5.177588e-1, -2.8956923e-1, 7.447922e-1, 6.477731e-1, 3.2581594e-3],
+    [8.45907e-1, 4.053001e-1, -2.6164144e-1, 7.8629893e-1, 6.143979e-1, 2.8808294e-3],
+    [8.96564e-1, 3.7411228e-1, -2.4711272e-1, 8.9063424e-1, 5.81295e-1, 2.537481e-3],
+    [9.5580024e-1, 3.0716145e-1, -2.1147756e-1, 9.356571e-1, 5.476743e-1, 2.2286e-3],
+    [9.729908e-1, 2.4027835e-1, -1.9695073e-1, 1.1104473e0, 5.143091e-1, 1.9550945e-3],
+    [9.854108e-1, 1.7738542e-1, -1.4483476e-1, 1.1240405e0, 4.8246467e-1, 1.715818e-3],
+    [9.964829e-1, 8.379605e-2, -9.2175655e-2, 1.0961312e0, 4.506589e-1, 1.5049203e-3],
+    [9.991651e-1, 4.085543e-2, -4.6983737e-2, 1.1490397e0, 4.1998583e-1, 1.314772e-3],
+    [9.999999e-1, -5.5907073e-4, 6.429313e-4, 1.1499996e0, 3.9030778e-1, 1.1507508e-3],
+    [3.932958e-1, 4.91944e-1, -1.9941882e-3, 1.5939502e-3, 9.999968e-1, 7.4828966e-3],
+    [3.932958e-1, 4.91944e-1, -1.9941882e-3, 1.5939502e-3, 9.999968e-1, 7.4828966e-3],
+    [3.9246735e-1, 4.9099675e-1, -4.0794183e-3, 3.2609706e-3, 9.99986e-1, 7.491267e-3],
+    [3.8992086e-1, 4.8782632e-1, -9.128276e-3, 7.3004235e-3, 9.999302e-1, 7.535876e-3],
+    [3.8995603e-1, 4.878181e-1, -1.6233223e-2, 1.2999629e-2, 9.997804e-1, 7.6563344e-3],
+    [3.901065e-1, 4.878986e-1, -2.5337944e-2, 2.0345848e-2, 9.994535e-1, 7.911163e-3],
+    [3.906161e-1, 4.8829374e-1, -3.6426917e-2, 2.9394282e-2, 9.9875516e-1, 8.365242e-3],
+    [3.923015e-1, 4.900736e-1, -4.9328018e-2, 4.0302135e-2, 9.942744e-1, 8.552238e-3],
+    [3.917991e-1, 4.8868448e-1, -6.398153e-2, 5.299808e-2, 9.9120843e-1, 9.022488e-3],
+    [3.9553317e-1, 4.9245036e-1, -8.055047e-2, 6.809262e-2, 9.851652e-1, 9.375336e-3],
+    [3.9621502e-1, 4.916584e-1, -9.8071516e-2, 8.503443e-2, 9.775777e-1, 9.734762e-3],
+    [4.011969e-1, 4.951302e-1, -1.1688935e-1, 1.0469908e-1, 9.670642e-1, 9.9428855e-3],
+    [4.0713334e-1, 4.9868804e-1, -1.3682488e-1, 1.275106e-1, 9.5373774e-1, 1.0043983e-2],
+    [4.285424e-1, 5.184597e-1, -1.5891907e-1, 1.5534912e-1, 9.370995e-1, 9.949454e-3],
+    [4.4854602e-1, 5.323293e-1, -1.8067348e-1, 1.8681651e-1, 9.1709995e-1, 9.668276e-3],
+    [4.7683197e-1, 5.494998e-1, -2.047492e-1, 2.254762e-1, 8.935759e-1, 9.261391e-3],
+    [5.088862e-1, 5.689904e-1, -2.2683574e-1, 2.6762506e-1, 8.658361e-1, 8.709085e-3],
+    [5.5323356e-1, 5.892149e-1, -2.4969363e-1, 3.1666866e-1, 8.3511335e-1, 8.091958e-3],
+    [5.9368974e-1, 5.9652096e-1, -2.675794e-1, 3.671334e-1, 8.028614e-1, 7.40186e-3],
+    [6.4859396e-1, 6.1419755e-1, -2.8701422e-1, 4.3330112e-1, 7.737784e-1, 6.7153266e-3],
+    [7.048822e-1, 6.071413e-1, -2.9609683e-1, 5.000221e-1, 7.4548906e-1, 6.0271914e-3],
+    [7.546225e-1, 5.7882255e-1, -2.9469436e-1, 5.6117064e-1, 7.1565866e-1, 5.377198e-3],
+    [8.172925e-1, 5.5641145e-1, -2.988399e-1, 6.477198e-1, 6.844496e-1, 4.771279e-3],
+    [8.801367e-1, 5.2765214e-1, -2.9789492e-1, 7.415015e-1, 6.52459e-1, 4.2173676e-3],
+    [9.233465e-1, 4.8671767e-1, -2.8844073e-1, 8.3587503e-1, 6.1932117e-1, 3.7086725e-3],
+    [8.926824e-1, 3.8328212e-1, -2.5622848e-1, 8.880546e-1, 5.8767384e-1, 3.2567913e-3],
+    [9.5361286e-1, 3.1388667e-1, -2.1806322e-1, 9.3414426e-1, 5.560019e-1, 2.8536806e-3],
+    [8.987351e-1, 2.7821994e-1, -1.8440829e-1, 1.0098435e0, 5.241894e-1, 2.499813e-3],
+    [9.8438084e-1, 1.8301457e-1, -1.5125678e-1, 1.1231943e0, 4.924501e-1, 2.189048e-3],
+    [9.9627733e-1, 8.62068e-2, -9.482747e-2, 1.095905e0, 4.6213067e-1, 1.9105644e-3],
+    [9.9911296e-1, 4.2111155e-2, -4.842782e-2, 1.1489798e0, 4.320362e-1, 1.6736083e-3],
+    [1e0, 4.735014e-5, -5.4452656e-5, 1.1499999e0, 4.0298098e-1, 1.4652548e-3],
+    [3.3939672e-1, 4.7189555e-1, -1.9323742e-3, 1.3894155e-3, 9.9999547e-1, 1.2496996e-2],
+    [3.3939672e-1, 4.7189555e-1, -1.9323742e-3, 1.3894155e-3, 9.9999547e-1, 1.2496996e-2],
+    [3.4066123e-1, 4.738028e-1, -3.939309e-3, 2.8327603e-3, 9.999826e-1, 1.2508356e-2],
+    [3.4075308e-1, 4.739047e-1, -8.870331e-3, 6.382511e-3, 9.9991405e-1, 1.2568771e-2],
+    [3.4085235e-1, 4.7398645e-1, -1.5771722e-2, 1.1366585e-2, 9.997282e-1, 1.2730791e-2],
+    [3.4109393e-1, 4.7418284e-1, -2.4625055e-2, 1.7806798e-2, 9.993213e-1, 1.3067914e-2],
+    [3.4179732e-1, 4.7491562e-1, -3.5403483e-2, 2.5761364e-2, 9.9830073e-1, 1.3627853e-2],
+    [3.4224594e-1, 4.7512272e-1, -4.7865715e-2, 3.5345003e-2, 9.932502e-1, 1.3732535e-2],
+    [3.4450507e-1, 4.7761667e-1, -6.231132e-2, 4.6752945e-2, 9.8989004e-1, 1.4308373e-2],
+    [3.4597e-1, 4.78291e-1, -7.806308e-2, 5.9978362e-2, 9.8311996e-1, 1.4610717e-2],
+    [3.5015237e-1, 4.824377e-1, -9.55498e-2, 7.56118e-2, 9.746665e-1, 1.4869829e-2],
+    [3.5506377e-1, 4.8641673e-1, -1.1421319e-1, 9.377786e-2, 9.6348035e-1, 1.4939481e-2],
+    [3.6558098e-1, 4.9525508e-1, -1.3377507e-1, 1.149863e-1, 9.48665e-1, 1.4729169e-2],
+    [3.8366094e-1, 5.1232076e-1, -1.5548834e-1, 1.4089295e-1, 9.320529e-1, 1.4371952e-2],
+    [4.0747303e-1, 5.297877e-1, -1.7781423e-1, 1.7156273e-1, 9.1159284e-1, 1.3691221e-2],
+    [4.4158927e-1, 5.5876935e-1, -2.0151843e-1, 2.0857581e-1, 8.880142e-1, 1.2880374e-2],
+    [4.688983e-1, 5.684351e-1, -2.2290811e-1, 2.4903105e-1, 8.62051e-1, 1.1929467e-2],
+    [5.2283376e-1, 6.030048e-1, -2.478514e-1, 3.0014494e-1, 8.3307046e-1, 1.0927344e-2],
+    [5.6985545e-1, 6.215979e-1, -2.6684892e-1, 3.51874e-1, 8.0150914e-1, 9.880081e-3],
+    [6.389076e-1, 6.395364e-1, -2.883787e-1, 4.1618782e-1, 7.7055013e-1, 8.881608e-3],
+    [6.8819326e-1, 6.3057905e-1, -3.003092e-1, 4.8511034e-1, 7.4311215e-1, 7.907247e-3],
+    [7.42565e-1, 6.011631e-1, -3.051542e-1, 5.5827767e-1, 7.161921e-1, 7.0073553e-3],
+    [7.956472e-1, 5.7911116e-1, -3.0568305e-1, 6.3949424e-1, 6.874535e-1, 6.1837723e-3],
+    [8.51424e-1, 5.1540005e-1, -2.9381245e-1, 7.099864e-1, 6.5755504e-1, 5.43699e-3],
+    [8.5959107e-1, 4.5670807e-1, -2.769311e-1, 7.826824e-1, 6.267896e-1, 4.7649294e-3],
+    [9.0573436e-1, 4.0161434e-1, -2.4938516e-1, 8.381187e-1, 5.9523875e-1, 4.170017e-3],
+    [9.510074e-1, 3.216943e-1, -2.2571447e-1, 9.32325e-1, 5.6513256e-1, 3.6495037e-3],
+    [9.7686464e-1, 2.7263853e-1, -1.9553941e-1, 1.0416822e0, 5.3500265e-1, 3.1951969e-3],
+    [9.838673e-1, 1.857558e-1, -1.5438479e-1, 1.1227686e0, 5.0475913e-1, 2.7896154e-3],
+    [9.5262223e-1, 8.628279e-2, -9.92252e-2, 1.0955155e0, 4.7461793e-1, 2.440741e-3],
+    [9.9905324e-1, 4.3503974e-2, -5.002956e-2, 1.148911e0, 4.458306e-1, 2.1346014e-3],
+    [9.9999994e-1, -5.710791e-4, 6.567408e-4, 1.1499997e0, 4.1742504e-1, 1.8674703e-3],
+    [2.864242e-1, 4.4500664e-1, -1.8322321e-3, 1.178981e-3, 9.999953e-1, 2.035512e-2],
+    [2.864242e-1, 4.4500664e-1, -1.8322321e-3, 1.178981e-3, 9.999953e-1, 2.035512e-2],
+    [2.909281e-1, 4.5214063e-1, -3.7882046e-3, 2.4379268e-3, 9.999784e-1, 2.036981e-2],
+    [2.927218e-1, 4.5490792e-1, -8.517856e-3, 5.4858495e-3, 9.9989307e-1, 2.0447811e-2],
+    [2.9289463e-1, 4.5510316e-1, -1.5140443e-2, 9.7706625e-3, 9.9966025e-1, 2.0655302e-2],
+    [2.930668e-1, 4.5523006e-1, -2.363442e-2, 1.5316216e-2, 9.9914604e-1, 2.1078775e-2],
+    [2.937787e-1, 4.560614e-1, -3.3937775e-2, 2.2203237e-2, 9.964562e-1, 2.1475993e-2],
+    [2.9428878e-1, 4.5631704e-1, -4.5946676e-2, 3.0523654e-2, 9.925312e-1, 2.1742493e-2],
+    [2.9621574e-1, 4.5851138e-1, -5.9666377e-2, 4.0466905e-2, 9.8737717e-1, 2.208615e-2],
+    [2.9904965e-1, 4.615725e-1, -7.4972354e-2, 5.2241884e-2, 9.804896e-1, 2.2351563e-2],
+    [3.0274066e-1, 4.6495226e-1, -9.1639854e-2, 6.6118255e-2, 9.708202e-1, 2.2338202e-2],
+    [3.0945894e-1, 4.711568e-1, -1.09773904e-1, 8.265933e-2, 9.586245e-1, 2.2032496e-2],
+    [3.2142583e-1, 4.839758e-1, -1.2893751e-1, 1.0234355e-1, 9.4252e-1, 2.1284867e-2],
+    [3.3638248e-1, 4.9559295e-1, -1.496422e-1, 1.2605388e-1, 9.2544895e-1, 2.040532e-2],
+    [3.6646143e-1, 5.2621216e-1, -1.732088e-1, 1.564591e-1, 9.048073e-1, 1.9135294e-2],
+    [3.9816353e-1, 5.499809e-1, -1.9554569e-1, 1.9080779e-1, 8.8243824e-1, 1.7716207e-2],
+    [4.378301e-1, 5.7763094e-1, -2.2015423e-1, 2.3376344e-1, 8.575062e-1, 1.6177647e-2],
+    [4.9280825e-1, 6.107883e-1, -2.4528918e-1, 2.8461838e-1, 8.301462e-1, 1.463018e-2],
+    [5.331147e-1, 6.133555e-1, -2.6469952e-1, 3.3706835e-1, 8.010367e-1, 1.3092267e-2],
+    [5.902059e-1, 6.288516e-1, -2.865355e-1, 4.013721e-1, 7.714264e-1, 1.166459e-2],
+    [6.553434e-1, 6.3718575e-1, -2.9941946e-1, 4.652626e-1, 7.418436e-1, 1.03162555e-2],
+    [7.266969e-1, 6.31578e-1, -3.0505964e-1, 5.378635e-1, 7.164412e-1, 9.076833e-3],
+    [7.879184e-1, 5.8958364e-1, -3.141142e-1, 6.3539535e-1, 6.908916e-1, 7.980687e-3],
+    [8.1543344e-1, 5.313194e-1, -2.969544e-1, 6.9779986e-1, 6.634312e-1, 6.980725e-3],
+    [8.702179e-1, 4.9953374e-1, -2.761813e-1, 7.6272357e-1, 6.350974e-1, 6.104222e-3],
+    [9.0368885e-1, 4.0619573e-1, -2.5362614e-1, 8.368449e-1, 6.0590154e-1, 5.3356984e-3],
+    [9.4923776e-1, 3.2687923e-1, -2.3079886e-1, 9.3107945e-1, 5.7585585e-1, 4.66362e-3],
+    [9.750315e-1, 2.791238e-1, -2.0245714e-1, 1.0403599e0, 5.468555e-1, 4.0737963e-3],
+    [9.832165e-1, 1.8916963e-1, -1.582809e-1, 1.122226e0, 5.183172e-1, 3.559741e-3],
+    [9.525552e-1, 8.701997e-2, -1.0007296e-1, 1.0954384e0, 4.8979947e-1, 3.1109487e-3],
+    [9.9898493e-1, 4.5046177e-2, -5.1803097e-2, 1.1488324e0, 4.6121955e-1, 2.7213534e-3],
+    [1e0, -4.4938606e-6, 5.167939e-6, 1.1499999e0, 4.3347782e-1, 2.378516e-3],
+    [2.44418e-1, 4.2733452e-1, -1.7484867e-3, 9.997762e-4, 9.99994e-1, 3.2410365e-2],
+    [2.44418e-1, 4.2733452e-1, -1.7484867e-3, 9.997762e-4, 9.99994e-1, 3.2410365e-2],
+    [2.5520235e-1, 4.4608405e-1, -3.6754883e-3, 2.1020037e-3, 9.999729e-1, 3.2428246e-2],
+    [2.465398e-1, 4.311565e-1, -8.06976e-3, 4.6194717e-3, 9.9986553e-1, 3.2522794e-2],
+    [2.4658099e-1, 4.3114802e-1, -1.4341623e-2, 8.23067e-3, 9.995705e-1, 3.277186e-2],
+    [2.4678999e-1, 4.3135172e-1, -2.237821e-2, 1.291134e-2, 9.989028e-1, 3.3265665e-2],
+    [2.4746977e-1, 4.3216935e-1, -3.2088786e-2, 1.8759167e-2, 9.946156e-1, 3.3310037e-2],
+    [2.4833968e-1, 4.3307748e-1, -4.3515675e-2, 2.5873534e-2, 9.914451e-1, 3.370896e-2],
+    [2.4960108e-1, 4.342565e-1, -5.640266e-2, 3.438781e-2, 9.8540026e-1, 3.378121e-2],
+    [2.5144044e-1, 4.3578836e-1, -7.079953e-2, 4.4612423e-2, 9.770402e-1, 3.3587113e-2],
+    [2.558146e-1, 4.4018903e-1, -8.654861e-2, 5.6853596e-2, 9.6584743e-1, 3.2993346e-2],
+    [2.6559737e-1, 4.5264974e-1, -1.0428485e-1, 7.199041e-2, 9.527258e-1, 3.2040905e-2],
+    [2.7897748e-1, 4.684561e-1, -1.2304796e-1, 9.024772e-2, 9.3557525e-1, 3.0433126e-2],
+    [2.9859537e-1, 4.8874852e-1, -1.4418088e-1, 1.1321762e-1, 9.175177e-1, 2.8563019e-2],
+    [3.2988924e-1, 5.231322e-1, -1.6729407e-1, 1.4198284e-1, 8.969929e-1, 2.6397511e-2],
+    [3.657353e-1, 5.533925e-1, -1.9179715e-1, 1.7720872e-1, 8.758337e-1, 2.4076046e-2],
+    [4.0192446e-1, 5.7002014e-1, -2.1439306e-1, 2.1737474e-1, 8.5197353e-1, 2.1679709e-2],
+    [4.6066928e-1, 6.1333615e-1, -2.4033457e-1, 2.6831204e-1, 8.2811797e-1, 1.9401133e-2],
+    [5.183978e-1, 6.388215e-1, -2.628159e-1, 3.2460988e-1, 8.0131453e-1, 1.7208582e-2],
+    [5.668737e-1, 6.3119084e-1, -2.7982447e-1, 3.8311148e-1, 7.7371275e-1, 1.5193933e-2],
+    [6.2729424e-1, 6.327882e-1, -2.968436e-1, 4.5216057e-1, 7.4577725e-1, 1.3361436e-2],
+    [6.7160803e-1, 6.044181e-1, -3.03185e-1, 5.180366e-1, 7.180001e-1, 1.1704469e-2],
+    [7.401556e-1, 5.99094e-1, -3.052116e-1, 5.983728e-1, 6.9449437e-1, 1.0247034e-2],
+    [7.918897e-1, 5.564275e-1, -3.0003884e-1, 6.8528163e-1, 6.701418e-1, 8.944346e-3],
+    [8.664736e-1, 5.06001e-1, -2.818541e-1, 7.6064557e-1, 6.443504e-1, 7.807526e-3],
+    [9.01683e-1, 4.106298e-1, -2.5773364e-1, 8.3558923e-1, 6.176088e-1, 6.8169856e-3],
+    [9.3611884e-1, 3.7811688e-1, -2.4160175e-1, 9.6273935e-1, 5.899661e-1, 5.961339e-3],
+    [9.743363e-1, 2.8154138e-1, -2.05037e-1, 1.0398545e0, 5.612364e-1, 5.1966403e-3],
+    [9.822512e-1, 1.9411956e-1, -1.6393141e-1, 1.1214145e0, 5.3336453e-1, 4.542823e-3],
+    [9.490011e-1, 1.3772576e-1, -1.0655983e-1, 1.1283125e0, 5.062143e-1, 3.9781774e-3],
+    [9.5736814e-1, 4.3003093e-2, -5.1603705e-2, 1.1488415e0, 4.7933587e-1, 3.470356e-3],
+    [1.0434781e0, -6.069422e-4, 6.979835e-4, 1.1999997e0, 4.5243895e-1, 3.052686e-3],
+    [2.0289245e-1, 4.0301487e-1, -1.6417956e-3, 8.262853e-4, 9.999924e-1, 5.0549187e-2],
+    [2.0289245e-1, 4.0301487e-1, -1.6417956e-3, 8.262853e-4, 9.999924e-1, 5.0549187e-2],
+    [2.0728515e-1, 4.115171e-1, -3.3860405e-3, 1.7045268e-3, 9.9996585e-1, 5.0568964e-2],
+    [2.0228751e-1, 4.0192246e-1, -7.519314e-3, 3.7898629e-3, 9.998258e-1, 5.0673056e-2],
+    [2.0230623e-1, 4.0185738e-1, -1.336505e-2, 6.7583635e-3, 9.9944264e-1, 5.094283e-2],
+    [2.0249219e-1, 4.0201172e-1, -2.0856993e-2, 1.0620034e-2, 9.9852115e-1, 5.144262e-2],
+    [2.0317139e-1, 4.0296566e-1, -2.989122e-2, 1.5475713e-2, 9.9333835e-1, 5.1049493e-2],
+    [2.0398794e-1, 4.038349e-1, -4.054453e-2, 2.1428065e-2, 9.898257e-1, 5.127091e-2],
+    [2.0535223e-1, 4.0529373e-1, -5.2565746e-2, 2.8655248e-2, 9.825863e-1, 5.0729062e-2],
+    [2.0835133e-1, 4.0909266e-1, -6.6092156e-2, 3.7510682e-2, 9.7274876e-1, 4.967283e-2],
+    [2.1321425e-1, 4.151505e-1, -8.085984e-2, 4.8275866e-2, 9.595359e-1, 4.7904972e-2],
+    [2.2132745e-1, 4.2519748e-1, -9.710022e-2, 6.152039e-2, 9.4459903e-1, 4.5625564e-2],
+    [2.3676963e-1, 4.4532466e-1, -1.1563872e-1, 7.853445e-2, 9.270321e-1, 4.2742763e-2],
+    [2.614047e-1, 4.7794423e-1, -1.3676739e-1, 1.0046279e-1, 9.08211e-1, 3.9379183e-2],
+    [2.892054e-1, 5.066647e-1, -1.5945232e-1, 1.2768918e-1, 8.886015e-1, 3.586467e-2],
+    [3.3162034e-1, 5.489597e-1, -1.8429583e-1, 1.6215128e-1, 8.682225e-1, 3.2248836e-2],
+    [3.746587e-1, 5.7975245e-1, -2.0824851e-1, 2.025192e-1, 8.4732276e-1, 2.8738827e-2],
+    [4.2464277e-1, 6.105663e-1, -2.3602171e-1, 2.5489718e-1, 8.254952e-1, 2.545336e-2],
+    [4.8592213e-1, 6.380953e-1, -2.6109332e-1, 3.141845e-1, 8.0262244e-1, 2.2415122e-2],
+    [5.4629123e-1, 6.538834e-1, -2.806867e-1, 3.7765545e-1, 7.7775353e-1, 1.9641472e-2],
+    [6.040059e-1, 6.6081476e-1, -2.9504982e-1, 4.4433156e-1, 7.523718e-1, 1.719915e-2],
+    [6.6670346e-1, 6.323605e-1, -3.032522e-1, 5.1328206e-1, 7.2651285e-1, 1.5032483e-2],
+    [7.145219e-1, 5.9200686e-1, -3.0141145e-1, 5.7825047e-1, 7.0017517e-1, 1.31042935e-2],
+    [7.925081e-1, 5.728113e-1, -3.0078956e-1, 6.6791296e-1, 6.7747796e-1, 1.1440444e-2],
+    [8.368812e-1, 5.0464296e-1, -2.9137918e-1, 7.6638323e-1, 6.546948e-1, 9.974251e-3],
+    [8.992285e-1, 4.15977e-1, -2.626911e-1, 8.340439e-1, 6.3043946e-1, 8.705926e-3],
+    [9.349052e-1, 3.8110813e-1, -2.446788e-1, 9.61962e-1, 6.05312e-1, 7.5964984e-3],
+    [9.7400177e-1, 2.8269663e-1, -2.0627001e-1, 1.0396107e0, 5.7909167e-1, 6.644213e-3],
+    [9.823102e-1, 1.938209e-1, -1.6359043e-1, 1.1214643e0, 5.521478e-1, 5.8103823e-3],
+    [9.487027e-1, 1.397678e-1, -1.08987845e-1, 1.1280807e0, 5.2539563e-1, 5.0859014e-3],
+    [9.988198e-1, 4.8567113e-2, -5.8280524e-2, 1.1985836e0, 4.9905354e-1, 4.4694147e-3],
+    [9.9999994e-1, -4.4832376e-4, 5.379884e-4, 1.1999997e0, 4.7372618e-1, 3.9213034e-3],
+    [1.600383e-1, 3.6585087e-1, -1.4924906e-3, 6.526492e-4, 9.999882e-1, 7.736198e-2],
+    [1.600383e-1, 3.6585087e-1, -1.4924906e-3, 6.526492e-4, 9.999882e-1, 7.736198e-2],
+    [1.6031912e-1, 3.6663887e-1, -3.0635307e-3, 1.340057e-3, 9.999548e-1, 7.738012e-2],
+    [1.605596e-1, 3.6716208e-1, -6.8700165e-3, 3.0099924e-3, 9.997694e-1, 7.747454e-2],
+    [1.6060118e-1, 3.6713356e-1, -1.22129675e-2, 5.374252e-3, 9.992579e-1, 7.771016e-2],
+    [1.6094042e-1, 3.676444e-1, -1.9050175e-2, 8.467456e-3, 9.9720794e-1, 7.7837035e-2],
+    [1.6157266e-1, 3.6859763e-1, -2.7335728e-2, 1.2390533e-2, 9.9254316e-1, 7.7128194e-2],
+    [1.6248658e-1, 3.698026e-1, -3.702172e-2, 1.7264217e-2, 9.86291e-1, 7.61142e-2],
+    [1.6435763e-1, 3.7256175e-1, -4.807e-2, 2.3293652e-2, 9.7795945e-1, 7.455269e-2],
+    [1.6673242e-1, 3.7524605e-1, -6.035296e-2, 3.0730926e-2, 9.667141e-1, 7.206447e-2],
+    [1.7254199e-1, 3.8405642e-1, -7.427282e-2, 4.0215455e-2, 9.5186055e-1, 6.845704e-2],
+    [1.8327846e-1, 4.0013307e-1, -8.979417e-2, 5.2226473e-2, 9.35123e-1, 6.400975e-2],
+    [2.0031738e-1, 4.2557886e-1, -1.07958876e-1, 6.8112776e-2, 9.1664314e-1, 5.8946326e-2],
+    [2.2406949e-1, 4.570007e-1, -1.2824506e-1, 8.8544175e-2, 8.9755875e-1, 5.3412218e-2],
+    [2.5708964e-1, 4.9621484e-1, -1.5122217e-1, 1.1494606e-1, 8.793514e-1, 4.7998335e-2],
+    [2.9236874e-1, 5.29995e-1, -1.7560852e-1, 1.4811128e-1, 8.6024666e-1, 4.259291e-2],
+    [3.3794838e-1, 5.663647e-1, -2.0127192e-1, 1.8903615e-1, 8.425544e-1, 3.761125e-2],
+    [3.909004e-1, 5.96493e-1, -2.2588754e-1, 2.3731542e-1, 8.2404387e-1, 3.3037223e-2],
+    [4.5513564e-1, 6.332011e-1, -2.5261074e-1, 2.978792e-1, 8.0458856e-1, 2.8919723e-2],
+    [5.1607424e-1, 6.398256e-1, -2.7178097e-1, 3.6064398e-1, 7.838719e-1, 2.524009e-2],
+    [5.774555e-1, 6.468809e-1, -2.8702918e-1, 4.2987117e-1, 7.6128995e-1, 2.2001417e-2],
+    [6.596153e-1, 6.339176e-1, -3.0159086e-1, 5.105138e-1, 7.3835635e-1, 1.9185873e-2],
+    [6.889924e-1, 5.8267087e-1, -2.9865503e-1, 5.743225e-1, 7.1410966e-1, 1.6728789e-2],
+    [7.9068106e-1, 5.753308e-1, -3.0291384e-1, 6.6695225e-1, 6.891784e-1, 1.4574041e-2],
+    [8.050861e-1, 5.112408e-1, -2.8205127e-1, 7.219792e-1, 6.6577315e-1, 1.2711207e-2],
+    [8.8151014e-1, 4.6980402e-1, -2.7923405e-1, 8.637539e-1, 6.446326e-1, 1.1099643e-2],
+    [9.32773e-1, 3.8629743e-1, -2.500205e-1, 9.605875e-1, 6.219554e-1, 9.708063e-3],
+    [9.732578e-1, 2.8524742e-1, -2.089929e-1, 1.0390667e0, 5.983967e-1, 8.481015e-3],
+    [9.821677e-1, 1.945423e-1, -1.6441405e-1, 1.121344e0, 5.7410073e-1, 7.45076e-3],
+    [9.489334e-1, 1.381912e-1, -1.0711321e-1, 1.1282601e0, 5.4886323e-1, 6.5323776e-3],
+    [9.989102e-1, 4.667122e-2, -5.6005456e-2, 1.1986921e0, 5.2320313e-1, 5.7325503e-3],
+    [9.999997e-1, 8.576707e-4, -1.0292046e-3, 1.1999995e0, 4.9823815e-1, 5.0468678e-3],
+    [1.23497464e-1, 3.3075377e-1, -1.3329051e-3, 4.9749133e-4, 9.999853e-1, 1.1635714e-1],
+    [1.23497464e-1, 3.3075377e-1, -1.3329051e-3, 4.9749133e-4, 9.999853e-1, 1.1635714e-1],
+    [1.2230057e-1, 3.2772133e-1, -2.7300674e-3, 1.0193938e-3, 9.999383e-1, 1.1636559e-1],
+    [1.2225877e-1, 3.2754096e-1, -6.13018e-3, 2.2940913e-3, 9.996825e-1, 1.1640673e-1],
+    [1.2244479e-1, 3.2789117e-1, -1.0904138e-2, 4.1051023e-3, 9.9896765e-1, 1.1648443e-1],
+    [1.2274154e-1, 3.2836476e-1, -1.6978797e-2, 6.4931884e-3, 9.945406e-1, 1.1534352e-1],
+    [1.2324308e-1, 3.2909796e-1, -2.4381682e-2, 9.546062e-3, 9.9073696e-1, 1.1424237e-1],
+    [1.24106586e-1, 3.302795e-1, -3.3025306e-2, 1.3418105e-2, 9.8290527e-1, 1.1160503e-1],
+    [1.257453e-1, 3.3275563e-1, -4.2840093e-2, 1.8306132e-2, 9.7182894e-1, 1.0761364e-1],
+    [1.295271e-1, 3.3937043e-1, -5.4060277e-2, 2.4602411e-2, 9.581922e-1, 1.0240743e-1],
+    [1.3652222e-1, 3.5186854e-1, -6.6763595e-2, 3.280893e-2, 9.4072145e-1, 9.547808e-2],
+    [1.4912315e-1, 3.7455136e-1, -8.1889845e-2, 4.386029e-2, 9.2296815e-1, 8.790454e-2],
+    [1.6663772e-1, 4.01824e-1, -9.91813e-2, 5.8470618e-2, 9.0398926e-1, 7.957312e-2],
+    [1.9112276e-1, 4.3708345e-1, -1.1917265e-1, 7.790661e-2, 8.855561e-1, 7.1049824e-2],
+    [2.2729346e-1, 4.854251e-1, -1.4254022e-1, 1.0371356e-1, 8.6945266e-1, 6.309261e-2],
+    [2.6794934e-1, 5.2698594e-1, -1.6807783e-1, 1.3684946e-1, 8.536822e-1, 5.551357e-2],
+    [3.109427e-1, 5.6178e-1, -1.9383563e-1, 1.7733341e-1, 8.3837765e-1, 4.8556212e-2],
+    [3.6418045e-1, 5.9167844e-1, -2.1960305e-1, 2.2611447e-1, 8.240192e-1, 4.2406134e-2],
+    [4.2541587e-1, 6.191251e-1, -2.4352123e-1, 2.8303847e-1, 8.085539e-1, 3.697434e-2],
+    [4.9131843e-1, 6.375289e-1, -2.6702583e-1, 3.5149163e-1, 7.915075e-1, 3.2176033e-2],
+    [5.54472e-1, 6.3335544e-1, -2.8008366e-1, 4.1826713e-1, 7.7346987e-1, 2.8014682e-2],
+    [6.283232e-1, 6.341089e-1, -2.9671624e-1, 5.05072e-1, 7.527694e-1, 2.4361916e-2],
+    [6.885816e-1, 6.020105e-1, -2.985499e-1, 5.806311e-1, 7.3207927e-1, 2.1248188e-2],
+    [7.6360005e-1, 5.5890936e-1, -3.0293265e-1, 6.7708385e-1, 7.0908225e-1, 1.8554226e-2],
+    [8.23401e-1, 5.420281e-1, -2.898194e-1, 7.527328e-1, 6.8580675e-1, 1.6186833e-2],
+    [8.799468e-1, 4.727258e-1, -2.8209797e-1, 8.628229e-1, 6.622555e-1, 1.4148238e-2],
+    [9.2959434e-1, 3.938851e-1, -2.5783935e-1, 9.585184e-1, 6.4022624e-1, 1.2406029e-2],
+    [9.7128093e-1, 2.919079e-1, -2.1610598e-1, 1.0376108e0, 6.195717e-1, 1.0889455e-2],
+    [9.080132e-1, 2.1504208e-1, -1.6455774e-1, 1.0932398e0, 5.974106e-1, 9.552786e-3],
+    [9.4869053e-1, 1.3985005e-1, -1.0908563e-1, 1.1280712e0, 5.7527e-1, 8.415899e-3],
+    [9.9895215e-1, 4.576955e-2, -5.4923445e-2, 1.1987424e0, 5.5210453e-1, 7.422843e-3],
+    [9.5999724e-1, -2.3417145e-3, 2.9271424e-3, 1.1999964e0, 5.2846366e-1, 6.5636733e-3],
+    [8.757533e-2, 2.8183797e-1, -1.150445e-3, 3.5732237e-4, 9.9997866e-1, 1.7222734e-1],
+    [8.757533e-2, 2.8183797e-1, -1.150445e-3, 3.5732237e-4, 9.9997866e-1, 1.7222734e-1],
+    [8.777128e-2, 2.825889e-1, -2.3557707e-3, 7.3213514e-4, 9.999099e-1, 1.722083e-1],
+    [8.7978825e-2, 2.8323525e-1, -5.2991593e-3, 1.6522077e-3, 9.9954015e-1, 1.7210276e-1],
+    [8.809553e-2, 2.834288e-1, -9.424906e-3, 2.9641672e-3, 9.984359e-1, 1.7173618e-1],
+    [8.8405535e-2, 2.8402266e-1, -1.4682851e-2, 4.719591e-3, 9.930152e-1, 1.6912715e-1],
+    [8.890807e-2, 2.8489092e-1, -2.1062287e-2, 7.0049684e-3, 9.863184e-1, 1.6551833e-1],
+    [8.990308e-2, 2.8663477e-1, -2.8545067e-2, 9.975336e-3, 9.7678316e-1, 1.6013488e-1],
+    [9.211782e-2, 2.9113877e-1, -3.7168123e-2, 1.3891246e-2, 9.627237e-1, 1.5212181e-1],
+    [9.652383e-2, 3.004523e-1, -4.7132313e-2, 1.9126577e-2, 9.458723e-1, 1.421907e-1],
+    [1.0659165e-1, 3.2333773e-1, -5.926041e-2, 2.6483629e-2, 9.264749e-1, 1.3028286e-1],
+    [1.1864726e-1, 3.4592718e-1, -7.330496e-2, 3.640328e-2, 9.076139e-1, 1.1779198e-1],
+    [1.382777e-1, 3.8133287e-1, -9.0263925e-2, 5.0178833e-2, 8.894878e-1, 1.0504954e-1],
+    [1.6281268e-1, 4.178237e-1, -1.10005625e-1, 6.879801e-2, 8.730786e-1, 9.2648715e-2],
+    [1.9602738e-1, 4.6159965e-1, -1.330738e-1, 9.371236e-2, 8.600446e-1, 8.143619e-2],
+    [2.3421605e-1, 5.0170875e-1, -1.5780473e-1, 1.2540032e-1, 8.483641e-1, 7.1204565e-2],
+    [2.815927e-1, 5.419995e-1, -1.837968e-1, 1.6519058e-1, 8.3715224e-1, 6.1997093e-2],
+    [3.3703765e-1, 5.797505e-1, -2.1100546e-1, 2.1492608e-1, 8.259486e-1, 5.3829942e-2],
+    [3.9939964e-1, 6.1104906e-1, -2.3748033e-1, 2.7404898e-1, 8.152096e-1, 4.686424e-2],
+    [4.6956992e-1, 6.326435e-1, -2.608671e-1, 3.4214714e-1, 8.025376e-1, 4.0757462e-2],
+    [5.4218465e-1, 6.4105874e-1, -2.795513e-1, 4.1835442e-1, 7.8765917e-1, 3.5466734e-2],
+    [6.094501e-1, 6.1545384e-1, -2.8806707e-1, 4.932907e-1, 7.71824e-1, 3.0902108e-2],
+    [6.7685944e-1, 6.006996e-1, -2.914844e-1, 5.74741e-1, 7.536709e-1, 2.694331e-2],
+    [7.470794e-1, 5.798336e-1, -2.9129678e-1, 6.659597e-1, 7.338094e-1, 2.353576e-2],
+    [8.28505e-1, 5.341939e-1, -2.826667e-1, 7.5544775e-1, 7.1349925e-1, 2.064285e-2],
+    [8.2169956e-1, 4.4285512e-1, -2.664169e-1, 8.441959e-1, 6.909305e-1, 1.8089224e-2],
+    [9.3257785e-1, 3.867681e-1, -2.5050527e-1, 9.6046114e-1, 6.685644e-1, 1.5882788e-2],
+    [8.9152884e-1, 3.0155534e-1, -2.1169108e-1, 1.0101554e0, 6.4567435e-1, 1.398407e-2],
+    [9.061416e-1, 2.2279723e-1, -1.738983e-1, 1.091793e0, 6.2374365e-1, 1.2345934e-2],
+    [9.475688e-1, 1.4725852e-1, -1.17896676e-1, 1.1271844e0, 6.0391587e-1, 1.0924063e-2],
+    [9.587572e-1, 4.8834465e-2, -6.1043065e-2, 1.1984463e0, 5.833433e-1, 9.678104e-3],
+    [1e0, -1.8340166e-5, 2.2925204e-5, 1.2499998e0, 5.6263655e-1, 8.58461e-3],
+    [5.9018068e-2, 2.3703867e-1, -9.641551e-4, 2.399263e-4, 9.999668e-1, 2.511746e-1],
+    [5.9018068e-2, 2.3703867e-1, -9.641551e-4, 2.399263e-4, 9.999668e-1, 2.511746e-1],
+    [5.827455e-2, 2.3411424e-1, -1.9562994e-3, 4.8727816e-4, 9.9985886e-1, 2.5109047e-1],
+    [5.8282476e-2, 2.341793e-1, -4.3842043e-3, 1.0975199e-3, 9.9927986e-1, 2.5062934e-1],
+    [5.8456346e-2, 2.3463206e-1, -7.7880095e-3, 1.9805452e-3, 9.9545974e-1, 2.4812138e-1],
+    [5.875902e-2, 2.3533951e-1, -1.2143724e-2, 3.1820086e-3, 9.904156e-1, 2.4384136e-1],
+    [5.929425e-2, 2.3644204e-1, -1.7427703e-2, 4.8016687e-3, 9.8070145e-1, 2.3598251e-1],
+    [6.053043e-2, 2.3944946e-1, -2.3665978e-2, 7.005683e-3, 9.666806e-1, 2.2457105e-1],
+    [6.339088e-2, 2.4709523e-1, -3.1032126e-2, 1.00767985e-2, 9.4892734e-1, 2.0974426e-1],
+    [6.9989234e-2, 2.6556405e-1, -4.006688e-2, 1.4542052e-2, 9.2765737e-1, 1.9165455e-1],
+    [7.9176955e-2, 2.8786355e-1, -5.1056642e-2, 2.0934843e-2, 9.077066e-1, 1.7271048e-1],
+    [9.353157e-2, 3.1959075e-1, -6.4746104e-2, 3.0195117e-2, 8.896354e-1, 1.5362518e-1],
+    [1.13828264e-1, 3.5924742e-1, -8.146771e-2, 4.3351855e-2, 8.741734e-1, 1.3521217e-1],
+    [1.3865846e-1, 3.9887106e-1, -1.0087611e-1, 6.1241712e-2, 8.621192e-1, 1.1834019e-1],
+    [1.6998504e-1, 4.4020933e-1, -1.2321848e-1, 8.521729e-2, 8.530382e-1, 1.0317336e-1],
+    [2.1168861e-1, 4.8792392e-1, -1.4859138e-1, 1.1683186e-1, 8.461107e-1, 8.986732e-2],
+    [2.5776687e-1, 5.25986e-1, -1.7498331e-1, 1.5632276e-1, 8.3974797e-1, 7.815276e-2],
+    [3.1258377e-1, 5.668807e-1, -2.0311448e-1, 2.0628275e-1, 8.3292675e-1, 6.7864984e-2],
+    [3.770713e-1, 5.984271e-1, -2.2781458e-1, 2.6340908e-1, 8.2500565e-1, 5.8898732e-2],
+    [4.4448945e-1, 6.18885e-1, -2.53202e-1, 3.3319992e-1, 8.1677806e-1, 5.131218e-2],
+    [5.087441e-1, 6.1522603e-1, -2.6830545e-1, 4.0328786e-1, 8.0647457e-1, 4.472742e-2],
+    [5.811096e-1, 6.111379e-1, -2.8066945e-1, 4.8430616e-1, 7.9403e-1, 3.9065205e-2],
+    [6.4494455e-1, 5.813084e-1, -2.8608552e-1, 5.711815e-1, 7.7926004e-1, 3.416423e-2],
+    [7.258731e-1, 5.5338424e-1, -2.8314373e-1, 6.592137e-1, 7.6352394e-1, 2.9918546e-2],
+    [7.622908e-1, 4.7714797e-1, -2.6565194e-1, 7.306275e-1, 7.45913e-1, 2.6268082e-2],
+    [8.451724e-1, 4.496365e-1, -2.558005e-1, 8.439709e-1, 7.2692084e-1, 2.3160767e-2],
+    [8.5567063e-1, 3.7452745e-1, -2.3124039e-1, 9.366933e-1, 7.0723236e-1, 2.0457966e-2],
+    [8.958321e-1, 2.8852063e-1, -1.969358e-1, 1.0131354e0, 6.864167e-1, 1.810914e-2],
+    [9.09149e-1, 2.1018912e-1, -1.5871616e-1, 1.0941032e0, 6.646041e-1, 1.6038053e-2],
+    [9.9030435e-1, 1.4763902e-1, -1.1582359e-1, 1.1776513e0, 6.430102e-1, 1.423869e-2],
+    [9.5706946e-1, 9.961313e-2, -6.625924e-2, 1.2315519e0, 6.214022e-1, 1.2661743e-2],
+    [1.0399499e0, 1.0200113e-2, -1.2750139e-2, 1.2999371e0, 6.006943e-1, 1.1288209e-2],
+    [3.3451e-2, 1.7839655e-1, -7.326346e-4, 1.373588e-4, 9.9994135e-1, 3.6130393e-1],
+    [3.3451e-2, 1.7839655e-1, -7.326346e-4, 1.373588e-4, 9.9994135e-1, 3.6130393e-1],
+    [3.384519e-2, 1.8056431e-1, -1.5070635e-3, 2.8302424e-4, 9.997523e-1, 3.610599e-1],
+    [3.395197e-2, 1.8103856e-1, -3.386675e-3, 6.4166926e-4, 9.986917e-1, 3.597218e-1],
+    [3.41076e-2, 1.8153952e-1, -6.0170228e-3, 1.1715374e-3, 9.9275744e-1, 3.5389107e-1],
+    [3.4393217e-2, 1.8232982e-1, -9.377655e-3, 1.9202024e-3, 9.836288e-1, 3.4384847e-1],
+    [3.506045e-2, 1.8438508e-1, -1.3485231e-2, 2.9917588e-3, 9.6859473e-1, 3.275549e-1],
+    [3.6877774e-2, 1.9085585e-1, -1.8482385e-2, 4.574722e-3, 9.4886756e-1, 3.055208e-1],
+    [4.10436e-2, 2.0593835e-1, -2.4799308e-2, 7.0247003e-3, 9.2538345e-1, 2.7841982e-1],
+    [4.860575e-2, 2.3122872e-1, -3.300351e-2, 1.0859047e-2, 9.031652e-1, 2.494989e-1],
+    [5.9580453e-2, 2.6284426e-1, -4.3510046e-2, 1.6716981e-2, 8.8500905e-1, 2.210778e-1],
+    [7.5535946e-2, 3.0216488e-1, -5.684884e-2, 2.5491007e-2, 8.712408e-1, 1.9421051e-1],
+    [9.349222e-2, 3.3542156e-1, -7.272498e-2, 3.7891503e-2, 8.6181e-1, 1.6971402e-1],
+    [1.1818766e-1, 3.761015e-1, -9.187793e-2, 5.5312533e-2, 8.5573983e-1, 1.478224e-1],
+    [1.5097035e-1, 4.226558e-1, -1.13619685e-1, 7.8606196e-2, 8.519209e-1, 1.2851284e-1],
+    [1.905897e-1, 4.692891e-1, -1.3916244e-1, 1.1003568e-1, 8.498452e-1, 1.11815654e-1],
+    [2.3499072e-1, 5.062012e-1, -1.6500176e-1, 1.4861363e-1, 8.479731e-1, 9.73215e-2],
+    [2.8786018e-1, 5.436425e-1, -1.9245784e-1, 1.9714935e-1, 8.453928e-1, 8.4741674e-2],
+    [3.486488e-1, 5.7289374e-1, -2.1840134e-1, 2.5452223e-1, 8.4145385e-1, 7.380069e-2],
+    [4.1985616e-1, 5.9985495e-1, -2.4273024e-1, 3.2234555e-1, 8.359221e-1, 6.435995e-2],
+    [4.8085105e-1, 5.9519035e-1, -2.6045272e-1, 3.954942e-1, 8.2859707e-1, 5.6197114e-2],
+    [5.610709e-1, 6.0445666e-1, -2.7489844e-1, 4.7903773e-1, 8.2007325e-1, 4.9261946e-2],
+    [6.270867e-1, 5.771898e-1, -2.7813998e-1, 5.600139e-1, 8.099157e-1, 4.331251e-2],
+    [7.050303e-1, 5.542572e-1, -2.7848142e-1, 6.548607e-1, 7.979028e-1, 3.8167812e-2],
+    [7.7479255e-1, 5.051406e-1, -2.716048e-1, 7.5743777e-1, 7.841256e-1, 3.3708666e-2],
+    [8.053062e-1, 4.2147115e-1, -2.5009224e-1, 8.4568e-1, 7.6864266e-1, 2.9789811e-2],
+    [8.5893863e-1, 3.66971e-1, -2.2297528e-1, 9.386952e-1, 7.527486e-1, 2.6428059e-2],
+    [8.990908e-1, 2.7820018e-1, -1.8527253e-1, 1.0153333e0, 7.3566526e-1, 2.3476679e-2],
+    [9.119968e-1, 1.974673e-1, -1.4341542e-1, 1.0962137e0, 7.177695e-1, 2.096394e-2],
+    [9.5238763e-1, 1.2578312e-1, -9.616149e-2, 1.1794194e0, 6.988088e-1, 1.8761363e-2],
+    [9.9764687e-1, 8.485625e-2, -4.4771235e-2, 1.2825519e0, 6.801098e-1, 1.683062e-2],
+    [9.999533e-1, -9.6577415e-3, 1.2555061e-2, 1.2999392e0, 6.605123e-1, 1.5124253e-2],
+    [1.6071277e-2, 1.27562e-1, -5.107409e-4, 6.435297e-5, 9.998698e-1, 5.1307666e-1],
+    [1.6071277e-2, 1.27562e-1, -5.107409e-4, 6.435297e-5, 9.998698e-1, 5.1307666e-1],
+    [1.5542598e-2, 1.2331703e-1, -1.029748e-3, 1.3022727e-4, 9.994461e-1, 5.123846e-1],
+    [1.5665587e-2, 1.2424904e-1, -2.3174738e-3, 2.9976413e-4, 9.94372e-1, 5.0657326e-1],
+    [1.5778065e-2, 1.2462607e-1, -4.1157845e-3, 5.619999e-4, 9.850855e-1, 4.9403965e-1],
+    [1.6193286e-2, 1.2668364e-1, -6.43398e-3, 9.697257e-4, 9.674007e-1, 4.7063765e-1],
+    [1.7298158e-2, 1.3248962e-1, -9.399928e-3, 1.6377398e-3, 9.4259894e-1, 4.3657252e-1],
+    [2.0821545e-2, 1.5239309e-1, -1.359493e-2, 2.8385716e-3, 9.150093e-1, 3.950785e-1],
+    [2.6261115e-2, 1.7709684e-1, -1.9178016e-2, 4.884607e-3, 8.909774e-1, 3.5149193e-1],
+    [3.2995585e-2, 2.004356e-1, -2.6543772e-2, 8.248857e-3, 8.7438786e-1, 3.1005254e-1],
+    [4.3444507e-2, 2.3218715e-1, -3.6346484e-2, 1.3655786e-2, 8.646272e-1, 2.7203256e-1],
+    [5.871116e-2, 2.718478e-1, -4.91213e-2, 2.2014895e-2, 8.603291e-1, 2.3801994e-1],
+    [7.717924e-2, 3.095091e-1, -6.457147e-2, 3.4060635e-2, 8.594784e-1, 2.0785806e-1],
+    [1.0042734e-1, 3.478138e-1, -8.306697e-2, 5.097697e-2, 8.604125e-1, 1.8124296e-1],
+    [1.3232133e-1, 3.9433536e-1, -1.048376e-1, 7.4146666e-2, 8.6210734e-1, 1.5797809e-1],
+    [1.709065e-1, 4.401822e-1, -1.2860815e-1, 1.0411413e-1, 8.636068e-1, 1.37642e-1],
+    [2.1194416e-1, 4.7482136e-1, -1.5416868e-1, 1.4215715e-1, 8.64556e-1, 1.20020166e-1],
+    [2.6809764e-1, 5.182637e-1, -1.818286e-1, 1.9030826e-1, 8.648453e-1, 1.0491448e-1],
+    [3.2210693e-1, 5.4303855e-1, -2.0710497e-1, 2.4581112e-1, 8.638501e-1, 9.188233e-2],
+    [3.911542e-1, 5.723456e-1, -2.3273082e-1, 3.135708e-1, 8.613682e-1, 8.061543e-2],
+    [4.6319824e-1, 5.8747977e-1, -2.5339183e-1, 3.8875827e-1, 8.573139e-1, 7.08554e-2],
+    [5.3649455e-1, 5.816673e-1, -2.6598775e-1, 4.6717927e-1, 8.516751e-1, 6.2366914e-2],
+    [6.1158675e-1, 5.7064414e-1, -2.7653113e-1, 5.599758e-1, 8.447662e-1, 5.5103466e-2],
+    [6.731863e-1, 5.3369015e-1, -2.724495e-1, 6.4316165e-1, 8.36447e-1, 4.8804123e-2],
+    [7.752055e-1, 5.045065e-1, -2.7098495e-1, 7.576597e-1, 8.267783e-1, 4.3303475e-2],
+    [8.228836e-1, 4.4068906e-1, -2.501034e-1, 8.429529e-1, 8.16719e-1, 3.8680155e-2],
+    [8.584789e-1, 3.6804476e-1, -2.2414891e-1, 9.384155e-1, 8.056267e-1, 3.4644105e-2],
+    [9.124694e-1, 2.916777e-1, -1.9191341e-1, 1.0451807e0, 7.935589e-1, 3.1133095e-2],
+    [9.0792626e-1, 2.4370342e-1, -1.4728299e-1, 1.1293252e0, 7.8052866e-1, 2.8041776e-2],
+    [9.499766e-1, 1.7141002e-1, -9.583066e-2, 1.2128866e0, 7.66655e-1, 2.5327494e-2],
+    [9.6070284e-1, 7.59773e-2, -3.71633e-2, 1.282795e0, 7.519419e-1, 2.2906285e-2],
+    [1.038275e0, -1.9681202e-2, 2.5585558e-2, 1.3497572e0, 7.371404e-1, 2.0794388e-2],
+    [4.025652e-3, 6.312767e-2, -2.5823672e-4, 1.649871e-5, 9.9948716e-1, 7.1959484e-1],
+    [4.025652e-3, 6.312767e-2, -2.5823672e-4, 1.649871e-5, 9.9948716e-1, 7.1959484e-1],
+    [3.9919442e-3, 6.2084273e-2, -5.233116e-4, 3.3985172e-5, 9.9585944e-1, 7.1519804e-1],
+    [4.0786476e-3, 6.367159e-2, -1.1824925e-3, 8.308919e-5, 9.819776e-1, 6.963955e-1],
+    [4.310735e-3, 6.602135e-2, -2.1242741e-3, 1.7684387e-4, 9.528251e-1, 6.5567684e-1],
+    [5.220798e-3, 7.563074e-2, -3.5340658e-3, 3.7949946e-4, 9.1516995e-1, 5.965504e-1],
+    [7.4488553e-3, 9.637517e-2, -5.830091e-3, 8.4224646e-4, 8.839486e-1, 5.3196996e-1],
+    [1.0745065e-2, 1.1759929e-1, -9.223266e-3, 1.7859711e-3, 8.677219e-1, 4.721046e-1],
+    [1.569182e-2, 1.4266382e-1, -1.42452335e-2, 3.6060328e-3, 8.6261183e-1, 4.1774824e-1],
+    [2.327637e-2, 1.7411998e-1, -2.1289678e-2, 6.8261786e-3, 8.6429965e-1, 3.6889276e-1],
+    [3.3397645e-2, 2.0712915e-1, -3.0547515e-2, 1.2047154e-2, 8.68936e-1, 3.2484668e-1],
+    [4.5723516e-2, 2.3763672e-1, -4.1820943e-2, 1.9791884e-2, 8.745059e-1, 2.8539333e-1],
+    [6.376406e-2, 2.7627417e-1, -5.691947e-2, 3.170887e-2, 8.797937e-1, 2.5019884e-1],
+    [8.658122e-2, 3.1709883e-1, -7.431857e-2, 4.8017897e-2, 8.845073e-1, 2.1919455e-1],
+    [1.131549e-1, 3.533846e-1, -9.4458245e-2, 7.002681e-2, 8.883116e-1, 1.919762e-1],
+    [1.4798461e-1, 3.9606154e-1, -1.1726022e-1, 9.900071e-2, 8.910835e-1, 1.6815887e-1],
+    [1.8949792e-1, 4.3569556e-1, -1.4200215e-1, 1.3583413e-1, 8.92939e-1, 1.4749156e-1],
+    [2.3884718e-1, 4.7474083e-1, -1.676745e-1, 1.8116923e-1, 8.9388967e-1, 1.2962383e-1],
+    [2.9880443e-1, 5.142672e-1, -1.948284e-1, 2.3750487e-1, 8.939308e-1, 1.1420165e-1],
+    [3.627708e-1, 5.40024e-1, -2.1845467e-1, 3.0068418e-1, 8.9302367e-1, 1.0085789e-1],
+    [4.3348593e-1, 5.583939e-1, -2.3995349e-1, 3.7384748e-1, 8.9121246e-1, 8.932203e-2],
+    [5.108809e-1, 5.6541437e-1, -2.5910345e-1, 4.5860913e-1, 8.8878953e-1, 7.947469e-2],
+    [5.9817713e-1, 5.628689e-1, -2.736157e-1, 5.536575e-1, 8.856114e-1, 7.0990294e-2],
+    [6.6484904e-1, 5.4024637e-1, -2.7536544e-1, 6.429131e-1, 8.816639e-1, 6.364309e-2],
+    [7.225052e-1, 4.9825364e-1, -2.6724058e-1, 7.293081e-1, 8.769897e-1, 5.7258107e-2],
+    [8.1570256e-1, 4.573409e-1, -2.6467758e-1, 8.598145e-1, 8.716276e-1, 5.1681217e-2],
+    [8.33119e-1, 3.723256e-1, -2.371647e-1, 9.4133914e-1, 8.65634e-1, 4.6795394e-2],
+    [9.213284e-1, 3.1740233e-1, -2.126687e-1, 1.0723242e0, 8.590721e-1, 4.2505257e-2],
+    [8.933718e-1, 2.4734384e-1, -1.6661026e-1, 1.135059e0, 8.520907e-1, 3.878216e-2],
+    [9.1085786e-1, 1.8096012e-1, -1.1716288e-1, 1.2110121e0, 8.4460384e-1, 3.5484053e-2],
+    [9.964599e-1, 9.781612e-2, -6.383124e-2, 1.3318045e0, 8.366498e-1, 3.255138e-2],
+    [9.8208743e-1, -1.3031763e-2, -1.480721e-4, 1.4100138e0, 8.282744e-1, 2.9934287e-2],
+    [3.8847147e-6, 4.3582966e-4, -6.1347864e-6, 5.3768808e-8, 8.848769e-1, 8.640734e-1],
+    [3.8847147e-6, 4.3582966e-4, -6.1347864e-6, 5.3768808e-8, 8.848769e-1, 8.640734e-1],
+    [1.5049762e-5, 8.130359e-4, -2.4103936e-5, 4.544087e-7, 9.0642244e-1, 8.634224e-1],
+    [7.1098926e-5, 1.8626626e-3, -1.1791405e-4, 5.1247807e-6, 9.2105895e-1, 8.268666e-1],
+    [2.3142705e-4, 3.7666855e-3, -3.7854307e-4, 2.944351e-5, 9.265247e-1, 7.6808155e-1],
+    [1.4051137e-3, 3.946762e-2, -1.5924431e-3, 1.9340529e-4, 9.290696e-1, 6.9941115e-1],
+    [2.8687583e-3, 5.5841077e-2, -3.2419823e-3, 5.634824e-4, 9.304456e-1, 6.2826496e-1],
+    [5.2710706e-3, 7.511402e-2, -5.924831e-3, 1.3854221e-3, 9.312642e-1, 5.5946594e-1],
+    [9.005294e-3, 9.78961e-2, -1.0017058e-2, 3.0077025e-3, 9.3179405e-1, 4.9564588e-1],
+    [1.4577401e-2, 1.2393774e-1, -1.578912e-2, 5.870693e-3, 9.3215287e-1, 4.3777302e-1],
+    [2.2355638e-2, 1.5237164e-1, -2.3614133e-2, 1.0568882e-2, 9.324073e-1, 3.859264e-1],
+    [3.3077687e-2, 1.8376367e-1, -3.3806346e-2, 1.7818987e-2, 9.3259186e-1, 3.3982846e-1],
+    [4.7336385e-2, 2.1737987e-1, -4.6524845e-2, 2.840642e-2, 9.327276e-1, 2.990987e-1],
+    [6.6331185e-2, 2.5464085e-1, -6.205435e-2, 4.3354746e-2, 9.3282837e-1, 2.6331198e-1],
+    [9.054176e-2, 2.9351768e-1, -8.030751e-2, 6.363629e-2, 9.3290293e-1, 2.3202133e-1],
+    [1.2094982e-1, 3.3354193e-1, -1.0102277e-1, 9.022928e-2, 9.329553e-1, 2.0477138e-1],
+    [1.5771855e-1, 3.733367e-1, -1.23957165e-1, 1.24273874e-1, 9.329911e-1, 1.8111579e-1],
+    [2.0415328e-1, 4.1651112e-1, -1.4879009e-1, 1.6703497e-1, 9.3300956e-1, 1.6063026e-1],
+    [2.5622275e-1, 4.5101225e-1, -1.7350638e-1, 2.1790646e-1, 9.3301517e-1, 1.4292122e-1],
+    [3.172376e-1, 4.841176e-1, -1.984325e-1, 2.7892455e-1, 9.330085e-1, 1.2763053e-1],
+    [3.8444203e-1, 5.111098e-1, -2.2192934e-1, 3.497919e-1, 9.329889e-1, 1.1443645e-1],
+    [4.5373875e-1, 5.256088e-1, -2.4334726e-1, 4.315023e-1, 9.329576e-1, 1.03054106e-1],
+    [5.3779787e-1, 5.462554e-1, -2.6584533e-1, 5.329827e-1, 9.329165e-1, 9.323254e-2],
+    [6.048266e-1, 5.202483e-1, -2.7012226e-1, 6.1660314e-1, 9.328649e-1, 8.475294e-2],
+    [6.934949e-1, 5.078433e-1, -2.786166e-1, 7.3104507e-1, 9.328047e-1, 7.742581e-2],
+    [7.607447e-1, 4.690036e-1, -2.772672e-1, 8.470737e-1, 9.3273574e-1, 7.108704e-2],
+    [8.2048404e-1, 4.1677615e-1, -2.649646e-1, 9.592954e-1, 9.326586e-1, 6.5596096e-2],
+    [8.882028e-1, 3.7292555e-1, -2.5182024e-1, 1.1075345e0, 9.325728e-1, 6.0831908e-2],
+    [9.3136966e-1, 2.7985224e-1, -2.1388364e-1, 1.1851324e0, 9.324807e-1, 5.6691296e-2],
+    [9.7564685e-1, 2.4074112e-1, -1.8551962e-1, 1.371909e0, 9.323819e-1, 5.308588e-2],
+    [1.0046569e0, 1.4275488e-1, -1.4131221e-1, 1.5462571e0, 9.3227684e-1, 4.9940154e-2],
+    [9.985144e-1, 3.914839e-2, -8.1027195e-2, 1.6621841e0, 9.3216634e-1, 4.718985e-2],
+];
@@ -3,121 +3,304 @@ extern crate assimp_sys;
 extern crate cgmath;
 extern crate failure;
 extern crate image;
+#[macro_use]
 extern crate serde_json;
 extern crate specs;
 extern crate time;
+#[cfg(windows)]
 extern crate winapi;
 extern crate winit;
+#[cfg(windows)]
 extern crate wio;
 
+mod backend;
+mod config;
+mod descriptor;
 mod engine;
+mod frame;
+mod graph;
+mod ibl;
+mod ltc;
+mod memory;
 mod pass;
+mod reference;
+mod resolution;
 mod scene;
+mod state;
 mod swapchain;
+mod texture;
+mod upload;
 
+use backend::{Backend, CommandList, DescriptorHeapType, Device, EngineError};
 use cgmath::*;
+use config::EngineConfig;
 use engine::Engine;
-use failure::Error;
-use pass::lighting;
+use frame::Frames;
+use failure::{err_msg, Error};
+use pass::cluster::{self, ClusterData};
+use pass::geometry::{ViewData, INVALID_ID};
+use pass::lighting::{self, TargetData};
+use pass::pipeline::{Pass, Pipeline, PipelineSettings};
+use pass::postprocess::DisplayMapData;
+use resolution::{ResolutionConfig, ResolutionController};
 use scene::{Scene, SceneLoader};
 use specs::Join;
-use std::{mem, ptr, slice};
-use winapi::shared::dxgiformat::*;
-use winapi::shared::dxgitype::*;
-use winapi::shared::minwindef::FALSE;
-use winapi::um::d3d12::*;
-use winapi::um::d3dcommon::*;
-use winapi::um::synchapi::*;
+use std::path::{Path, PathBuf};
+use std::{env, mem, slice};
+use swapchain::Swapchain;
+use texture::ImportOptions;
 use winit::WindowEvent;
 
-const FRAME_LATENCY: u64 = 2;
-
-#[repr(C)]
-struct ViewData {
-    pub view: [[f32; 4]; 4],
-    pub proj: [[f32; 4]; 4],
-    pub position: [f32; 4],
-    pub _alignment: [f32; 28],
-}
+const WINDOW_WIDTH: u32 = 1440;
+const WINDOW_HEIGHT: u32 = 704;
 
 fn main() -> Result<(), Error> {
+    // Command line options:
+    //  * `--headless`: Run without window on the null backend.
+    //  * `--frames N`: Quit after rendering N frames.
+    //  * `--reference DIR`: Write CPU reference images of the initial view to `DIR`.
+    //  * `--dump-graph PATH`: Write the frame graph as DOT (`.dot`) or JSON.
+    //  * `--stats`: Print memory and descriptor heap usage and frame graph statistics on exit.
+    //  * `--list-adapters`: Print the available adapters and quit.
+    //  * `--simulate-device-removed N`: Simulate a device loss before rendering frame N.
+    //  * `--samples N`: MSAA samples per pixel of the visibility buffer, 1, 2, 4 or 8.
+    //  * `--render-scale SCALE`: Internal render resolution relative to the window, in `(0, 1]`.
+    //  * `--target-frame-time MS`: Scale the render resolution dynamically to reach the
+    //    frame time, starting at `--render-scale`.
+    //  * `--resolution-hysteresis FRACTION`: Tolerated relative frame time deviation.
+    //  * `--no-texture-compression`: Keep imported images uncompressed.
+    //  * `--light-heatmap`: Show the number of lights per cluster instead of the lighting.
+    //  * `--environment PATH`: Light the scene by an equirectangular HDR environment map.
+    //  * `--environment-intensity SCALE`: Scale of the environment map radiance.
+    //  * `--fit-ltc PATH`: Fit the LTC table of the area lights, write it as Rust source
+    //    to `PATH` (`src/ltc/table.rs`) and quit.
+    //
+    // Engine options, applied in order:
+    //  * `--config PATH`: Apply the settings of a JSON config file.
+    //  * `--debug-layer`, `--no-debug-layer`: Toggle the debug layer.
+    //  * `--gpu-validation`: Enable GPU-based validation.
+    //  * `--feature-level LEVEL`: Minimum feature level, e.g. `12_1`.
+    //  * `--adapter ADAPTER`: `first`, `largest-vram`, an index or a name substring.
+    //  * `--frame-latency N`: Number of frames recorded ahead of the GPU.
+    let mut headless = false;
+    let mut list_adapters = false;
+    let mut config = EngineConfig::default();
+    let mut options = Options::default();
+    let mut resolution = ResolutionConfig::default();
+    let mut dynamic_resolution = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--headless" => headless = true,
+            "--list-adapters" => list_adapters = true,
+            "--frames" => {
+                let frames = args
+                    .next()
+                    .ok_or_else(|| err_msg("missing value for `--frames`"))?;
+                options.max_frames = Some(frames.parse::<u64>()?);
+            }
+            "--reference" => {
+                let dir = args
+                    .next()
+                    .ok_or_else(|| err_msg("missing value for `--reference`"))?;
+                options.reference_dir = Some(PathBuf::from(dir));
+            }
+            "--simulate-device-removed" => {
+                let frame = args
+                    .next()
+                    .ok_or_else(|| err_msg("missing value for `--simulate-device-removed`"))?;
+                options.simulate_device_removed = Some(frame.parse::<u64>()?);
+            }
+            "--dump-graph" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| err_msg("missing value for `--dump-graph`"))?;
+                options.graph_path = Some(PathBuf::from(path));
+            }
+            "--samples" => {
+                let samples = args
+                    .next()
+                    .ok_or_else(|| err_msg("missing value for `--samples`"))?
+                    .parse::<u32>()?;
+                if ![1, 2, 4, 8].contains(&samples) {
+                    return Err(err_msg("sample count must be 1, 2, 4 or 8"));
+                }
+                options.samples = samples;
+            }
+            "--render-scale" => {
+                let scale = args
+                    .next()
+                    .ok_or_else(|| err_msg("missing value for `--render-scale`"))?
+                    .parse::<f32>()?;
+                if scale <= 0.0 || scale > 1.0 {
+                    return Err(err_msg("render scale must be in (0, 1]"));
+                }
+                options.render_scale = scale;
+            }
+            "--target-frame-time" => {
+                let time = args
+                    .next()
+                    .ok_or_else(|| err_msg("missing value for `--target-frame-time`"))?;
+                resolution.target_frame_time = time.parse::<f32>()?;
+                dynamic_resolution = true;
+            }
+            "--resolution-hysteresis" => {
+                let hysteresis = args
+                    .next()
+                    .ok_or_else(|| err_msg("missing value for `--resolution-hysteresis`"))?;
+                resolution.hysteresis = hysteresis.parse::<f32>()?;
+            }
+            "--no-texture-compression" => options.texture_options.compress = false,
+            "--light-heatmap" => options.light_heatmap = true,
+            "--stats" => options.stats = true,
+            "--environment" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| err_msg("missing value for `--environment`"))?;
+                options.environment = Some(PathBuf::from(path));
+            }
+            "--environment-intensity" => {
+                let intensity = args
+                    .next()
+                    .ok_or_else(|| err_msg("missing value for `--environment-intensity`"))?;
+                options.environment_intensity = intensity.parse::<f32>()?;
+            }
+            "--fit-ltc" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| err_msg("missing value for `--fit-ltc`"))?;
+                ltc::fit::write_table(Path::new(&path), &ltc::fit::fit_table())?;
+                return Ok(());
+            }
+            _ => {
+                if !config.apply_arg(&arg, &mut args)? {
+                    return Err(err_msg(format!("unknown argument `{}`", arg)));
+                }
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    type DefaultBackend = backend::d3d12::D3D12;
+    #[cfg(not(windows))]
+    type DefaultBackend = backend::null::Null;
+
+    if list_adapters {
+        let adapters = if headless {
+            backend::null::Null::enumerate_adapters()?
+        } else {
+            DefaultBackend::enumerate_adapters()?
+        };
+        for adapter in adapters {
+            println!(
+                "{}: {} ({} MB{})",
+                adapter.index,
+                adapter.name,
+                adapter.dedicated_video_memory / (1024 * 1024),
+                if adapter.software { ", software" } else { "" }
+            );
+        }
+        return Ok(());
+    }
+
+    if dynamic_resolution {
+        if resolution.target_frame_time <= 0.0 {
+            return Err(err_msg("target frame time must be positive"));
+        }
+        options.dynamic_resolution = Some(resolution);
+    }
+
+    if headless {
+        return run::<backend::null::Null>(None, &config, options);
+    }
+
     let mut events_loop = winit::EventsLoop::new();
     let window = winit::WindowBuilder::new()
-        .with_dimensions(1440, 704)
+        .with_dimensions(WINDOW_WIDTH, WINDOW_HEIGHT)
         .with_title("Hati")
         .build(&events_loop)?;
 
-    // Initialization order matters to allow more efficient resource resetting:
-    //  * Engine
-    //  * Pipeline
-    //  * Scene
-    let mut engine = Engine::new(FRAME_LATENCY);
-    let swapchain = engine.create_swapchain(&window);
-
-    let (window_width, window_height) = window.get_inner_size().unwrap();
-    let pipeline_settings = pass::pipeline::PipelineSettings {
-        width: window_width,
-        height: window_height,
-        samples: 1,
-    };
-    let pipeline = pass::pipeline::Pipeline::new(&mut engine, pipeline_settings);
-    let mut scene = Scene::new();
+    run::<DefaultBackend>(Some((&mut events_loop, &window)), &config, options)
+}
 
-    let upload_alloc = engine.create_command_allocator();
+/// Options of the frame loop.
+struct Options {
+    max_frames: Option<u64>,
+    reference_dir: Option<PathBuf>,
+    graph_path: Option<PathBuf>,
+    stats: bool,
+    simulate_device_removed: Option<u64>,
+    samples: u32,
+    render_scale: f32,
+    dynamic_resolution: Option<ResolutionConfig>,
+    texture_options: ImportOptions,
+    light_heatmap: bool,
+    environment: Option<PathBuf>,
+    environment_intensity: f32,
+}
 
-    // Load Scene
-    let upload_list = engine.create_command_list(&upload_alloc);
-    unsafe {
-        upload_list.Reset(upload_alloc.as_raw(), ptr::null_mut());
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            max_frames: None,
+            reference_dir: None,
+            graph_path: None,
+            stats: false,
+            simulate_device_removed: None,
+            samples: 1,
+            render_scale: 1.0,
+            dynamic_resolution: None,
+            texture_options: ImportOptions::default(),
+            light_heatmap: false,
+            environment: None,
+            environment_intensity: 1.0,
+        }
     }
+}
 
-    let upload_resources = {
-        let mut scene_loader = SceneLoader::new(&mut scene, &mut engine);
-        scene_loader.set_upload_list(upload_list.clone());
-        scene_loader.load_hati_scene("scene/Sponza", "sponza.obj")
+fn run<B: Backend>(
+    mut window: Option<(&mut winit::EventsLoop, &winit::Window)>,
+    config: &EngineConfig,
+    options: Options,
+) -> Result<(), Error> {
+    let window_size = |window: &Option<(&mut winit::EventsLoop, &winit::Window)>| match *window {
+        Some((_, ref window)) => window.get_inner_size().unwrap(),
+        None => (WINDOW_WIDTH, WINDOW_HEIGHT),
     };
 
-    unsafe {
-        upload_list.Close();
-        engine
-            .queue
-            .ExecuteCommandLists(1, &(upload_list.as_raw() as *mut _));
+    let (window_width, window_height) = window_size(&window);
+    let mut gpu = Gpu::<B>::new(
+        config,
+        window.as_ref().map(|&(_, ref window)| *window),
+        PipelineSettings {
+            width: window_width,
+            height: window_height,
+            samples: options.samples,
+            render_scale: options.render_scale,
+        },
+    )?;
+    let mut resolution = options
+        .dynamic_resolution
+        .map(|config| ResolutionController::new(config, options.render_scale));
+    // The controller clamps the initial scale to its range.
+    if let Some(ref resolution) = resolution {
+        gpu.pipeline.set_render_scale(resolution.scale());
     }
+    if let Some(path) = options.graph_path {
+        write_graph(&path, gpu.pipeline.graph.compiled())?;
+    }
+    let mut scene = Scene::new();
 
-    // View data (camera)
-    let view_data_size = engine.frame_latency() * mem::size_of::<ViewData>() as u64;
-    let view_data_desc = D3D12_RESOURCE_DESC {
-        Dimension: D3D12_RESOURCE_DIMENSION_BUFFER,
-        Alignment: 0,
-        Width: view_data_size,
-        Height: 1,
-        DepthOrArraySize: 1,
-        Format: DXGI_FORMAT_UNKNOWN,
-        MipLevels: 1,
-        SampleDesc: DXGI_SAMPLE_DESC {
-            Count: 1,
-            Quality: 0,
-        },
-        Layout: D3D12_TEXTURE_LAYOUT_ROW_MAJOR,
-        Flags: D3D12_RESOURCE_FLAG_NONE,
-    };
-    let view_heap = engine.create_descriptor_heap(
-        engine.frame_latency() as _,
-        D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV,
-        D3D12_DESCRIPTOR_HEAP_FLAG_SHADER_VISIBLE,
-    );
-    let view_data = engine.create_committed_resource(
-        D3D12_HEAP_TYPE_UPLOAD,
-        &view_data_desc,
-        D3D12_RESOURCE_STATE_VERTEX_AND_CONSTANT_BUFFER,
-        None,
-    );
-    let view_cbvs = unsafe {
-        let view_buffer_start = view_data.GetGPUVirtualAddress();
-        (0..engine.frame_latency())
-            .map(|i| view_buffer_start + i * mem::size_of::<ViewData>() as u64)
-            .collect::<Vec<_>>()
-    };
+    // Load Scene
+    {
+        let mut scene_loader = SceneLoader::new(&mut scene, &mut gpu.engine);
+        scene_loader.set_texture_options(options.texture_options);
+        scene_loader.load_hati_scene("scene/Sponza", "sponza.obj")?;
+        if let Some(ref path) = options.environment {
+            scene_loader.load_environment(path, options.environment_intensity)?;
+        }
+    }
+    gpu.engine.flush_uploads()?;
 
     let mut camera = scene::Camera {
         position: Point3::new(0.0, 100.0, 0.0),
@@ -131,43 +314,43 @@ fn main() -> Result<(), Error> {
         focal_length: 1.0,
     };
 
-    let present_fence = engine.create_fence(0, D3D12_FENCE_FLAG_NONE);
-
-    let cmd_allocs: [_; FRAME_LATENCY as _] = [
-        engine.create_command_allocator(),
-        engine.create_command_allocator(),
-    ];
-    let cmd_lists: [_; FRAME_LATENCY as _] = [
-        engine.create_command_list(&cmd_allocs[0]),
-        engine.create_command_list(&cmd_allocs[1]),
-    ];
+    if let Some(dir) = options.reference_dir {
+        write_reference(&dir, &scene, &camera, window_width, window_height)?;
+    }
 
-    let mut tick: u64 = 0;
     let time_start = time::PreciseTime::now();
     let mut time_last = time_start;
     let mut quit = false;
+    let mut num_frames = 0;
+    let mut resized = None;
+    // Validation errors of all engines, including the ones of lost devices.
+    let mut validation_errors = Vec::new();
 
     loop {
         // Event handling
-        events_loop.poll_events(|event| match event {
-            winit::Event::WindowEvent {
-                event: WindowEvent::CloseRequested,
-                ..
-            } => quit = true,
-            winit::Event::WindowEvent {
-                event: WindowEvent::KeyboardInput { input, .. },
-                ..
-            } => {
-                camera.on_event(input);
-            }
-            _ => {}
-        });
+        if let Some((ref mut events_loop, _)) = window {
+            events_loop.poll_events(|event| match event {
+                winit::Event::WindowEvent {
+                    event: WindowEvent::CloseRequested,
+                    ..
+                } => quit = true,
+                winit::Event::WindowEvent {
+                    event: WindowEvent::Resized(width, height),
+                    ..
+                } => resized = Some((width, height)),
+                winit::Event::WindowEvent {
+                    event: WindowEvent::KeyboardInput { input, .. },
+                    ..
+                } => {
+                    camera.on_event(input);
+                }
+                _ => {}
+            });
+        }
 
-        // Synchronize GPU -> CPU to handle resource reuse
-        if tick >= FRAME_LATENCY {
-            unsafe {
-                present_fence.SetEventOnCompletion(tick - FRAME_LATENCY, engine.wait_event);
-                WaitForSingleObject(engine.wait_event, 5_0000);
+        if let Some(max_frames) = options.max_frames {
+            if num_frames >= max_frames {
+                quit = true;
             }
         }
 
@@ -181,311 +364,727 @@ fn main() -> Result<(), Error> {
             time_last.to(time_now).num_microseconds().unwrap() as f32 / 1_000_000.0;
         time_last = time_now;
 
-        window.set_title(&format!("Hati - frame: {:.2} ms", time_elapsed_s * 1000.0));
-
-        // ! Frame Begin ----------------------------------------------------------------------------------
-        let frame = swapchain.begin_frame();
-        let (present_target, present_rtv) = swapchain.get_render_target(frame);
-        let (window_width, window_height) = window.get_inner_size().unwrap();
-
-        let cmd_list = &cmd_lists[frame];
-        unsafe {
-            cmd_allocs[frame].Reset();
-            cmd_list.Reset(cmd_allocs[frame].as_raw(), ptr::null_mut());
+        if let Some(ref mut resolution) = resolution {
+            let scale = resolution.update(time_elapsed_s * 1000.0);
+            gpu.pipeline.set_render_scale(scale);
         }
-        engine.bind_descriptor_heaps(&cmd_list);
-
-        // Backbuffer: Present -> RenderTarget
-        let present_rt_transition = [
-            engine::gen_resource_transition(
-                &present_target,
-                D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES,
-                D3D12_RESOURCE_STATE_PRESENT,
-                D3D12_RESOURCE_STATE_RENDER_TARGET,
-                D3D12_RESOURCE_BARRIER_FLAG_NONE,
-            ),
-            engine::gen_resource_transition(
-                &pipeline.geometry_buffer,
-                D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES,
-                D3D12_RESOURCE_STATE_NON_PIXEL_SHADER_RESOURCE,
-                D3D12_RESOURCE_STATE_RENDER_TARGET,
-                D3D12_RESOURCE_BARRIER_FLAG_NONE,
-            ),
-            engine::gen_resource_transition(
-                &pipeline.lighting_buffer,
-                D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES,
-                D3D12_RESOURCE_STATE_PIXEL_SHADER_RESOURCE,
-                D3D12_RESOURCE_STATE_UNORDERED_ACCESS,
-                D3D12_RESOURCE_BARRIER_FLAG_NONE,
-            ),
-        ];
-        unsafe {
-            cmd_list.ResourceBarrier(
-                present_rt_transition.len() as _,
-                present_rt_transition.as_ptr(),
-            );
-            cmd_list.ClearRenderTargetView(
-                pipeline.geometry_rtv_uint,
-                &[0.0, 0.0, 0.0, 0.0],
-                0,
-                ptr::null(),
-            );
-            cmd_list.ClearDepthStencilView(
-                pipeline.dsv,
-                D3D12_CLEAR_FLAG_DEPTH,
-                1.0,
-                0,
-                0,
-                ptr::null(),
-            );
+
+        if let Some((_, ref window)) = window {
+            window.set_title(&format!(
+                "Hati - frame: {:.2} ms, scale: {:.2}",
+                time_elapsed_s * 1000.0,
+                gpu.pipeline.settings().render_scale
+            ));
         }
 
-        // Geometry pass
-        unsafe {
-            cmd_list.SetGraphicsRootSignature(pipeline.geometry.signature.as_raw());
-            cmd_list.SetPipelineState(pipeline.geometry.pipeline.as_raw());
-            cmd_list.IASetPrimitiveTopology(D3D_PRIMITIVE_TOPOLOGY_TRIANGLELIST);
-            cmd_list.RSSetViewports(
-                1,
-                &D3D12_VIEWPORT {
-                    TopLeftX: 0.0,
-                    TopLeftY: 0.0,
-                    Width: window_width as _,
-                    Height: window_height as _,
-                    MinDepth: 0.0,
-                    MaxDepth: 1.0,
-                },
-            );
-            cmd_list.RSSetScissorRects(
-                1,
-                &D3D12_RECT {
-                    left: 0,
-                    top: 0,
-                    right: window_width as _,
-                    bottom: window_height as _,
-                },
-            );
-            cmd_list.OMSetRenderTargets(1, &pipeline.geometry_rtv_uint, FALSE, &pipeline.dsv);
-            cmd_list.SetGraphicsRootConstantBufferView(0, view_cbvs[frame]);
+        if options.simulate_device_removed == Some(num_frames) {
+            gpu.engine.device.simulate_device_removed();
         }
 
-        // Update view data
         camera.update(time_elapsed_s);
+        scene.sync_lights();
+        num_frames += 1;
 
-        let mut view_raw_data = ptr::null_mut();
-        let view_data_cpu = unsafe {
-            view_data.Map(0, ptr::null(), &mut view_raw_data);
-            slice::from_raw_parts_mut::<ViewData>(view_raw_data as _, engine.frame_latency() as _)
+        let frame = match resized.take() {
+            Some((width, height)) => gpu.resize(width, height),
+            None => Ok(()),
         };
-        view_data_cpu[frame].view = camera.view().into();
-        view_data_cpu[frame].proj = {
-            let aspect_ratio = window_width as f32 / window_height as f32;
-            let mut perspective = cgmath::perspective(cgmath::Deg(60.0), aspect_ratio, 1.0, 8192.0);
-            perspective.w.z /= 2.0; // OpenGL NDC -> DX12 NDC
-            perspective.into()
+        let light_heatmap = options.light_heatmap;
+        let frame = frame.and_then(|()| render_frame(&mut gpu, &scene, &camera, light_heatmap));
+        if let Err(err) = frame {
+            let reason = match gpu.engine.device_lost(&err) {
+                Some(reason) => reason,
+                None => return Err(err.into()),
+            };
+            println!("{}, recreating device", reason);
+
+            // Release the swapchain of the lost device before creating a new one
+            // for the same window.
+            let settings = gpu.pipeline.settings();
+            validation_errors.extend(gpu.engine.validation_errors());
+            drop(gpu);
+            let (width, height) = window_size(&window);
+            gpu = Gpu::new(
+                config,
+                window.as_ref().map(|&(_, ref window)| *window),
+                PipelineSettings {
+                    width,
+                    height,
+                    ..settings
+                },
+            )?;
+            SceneLoader::restore(&mut scene, &mut gpu.engine)?;
+            gpu.engine.flush_uploads()?;
+        }
+    }
+
+    gpu.frames.wait_idle(&mut gpu.engine)?;
+
+    if options.stats {
+        print_stats(&gpu.engine, &gpu.pipeline)?;
+    }
+
+    validation_errors.extend(gpu.engine.validation_errors());
+    if !validation_errors.is_empty() {
+        return Err(err_msg(format!(
+            "{} validation errors:\n{}",
+            validation_errors.len(),
+            validation_errors.join("\n")
+        )));
+    }
+
+    Ok(())
+}
+
+/// Print the usage of all memory and descriptor heaps and the frame graph statistics.
+fn print_stats<B: Backend>(engine: &Engine<B>, pipeline: &Pipeline<B>) -> Result<(), Error> {
+    const MB: f64 = 1024.0 * 1024.0;
+
+    println!("memory heaps:");
+    for heap in engine.memory_stats() {
+        let usage = heap.usage;
+        println!(
+            "  {:?} {:?} (alignment {}): {:.1} / {:.1} MB, {} allocations, {} free blocks, largest {:.1} MB",
+            heap.ty,
+            heap.class,
+            heap.alignment,
+            usage.allocated as f64 / MB,
+            usage.capacity as f64 / MB,
+            usage.num_allocations,
+            usage.num_free_blocks,
+            usage.largest_free_block as f64 / MB,
+        );
+    }
+
+    println!("descriptor heaps:");
+    for &ty in &[
+        DescriptorHeapType::CbvSrvUav,
+        DescriptorHeapType::Sampler,
+        DescriptorHeapType::Rtv,
+        DescriptorHeapType::Dsv,
+    ] {
+        let stats = engine.descriptor_stats(ty);
+        println!(
+            "  {:?}: {} / {}, high water mark {}, {} allocations, {} free ranges, fragmentation {:.1}%",
+            ty,
+            stats.allocated,
+            stats.capacity,
+            stats.high_water_mark,
+            stats.num_allocations,
+            stats.num_free_ranges,
+            stats.fragmentation() * 100.0,
+        );
+    }
+
+    let graph = pipeline.graph.compiled();
+    println!("frame graph:");
+    println!("  passes: {:?}", graph.pass_order());
+    println!(
+        "  {} physical transient resources",
+        graph.num_physical_resources()
+    );
+    println!(
+        "  max lights per cluster: {} / {}",
+        pipeline.max_cluster_lights(engine)?,
+        cluster::MAX_CLUSTER_LIGHTS
+    );
+    Ok(())
+}
+
+/// Device dependent state, recreated on device loss.
+struct Gpu<B: Backend> {
+    engine: Engine<B>,
+    swapchain: Swapchain<B>,
+    pipeline: Pipeline<B>,
+    frames: Frames<B>,
+}
+
+impl<B: Backend> Gpu<B> {
+    // Initialization order matters to allow more efficient resource resetting:
+    //  * Engine
+    //  * Pipeline
+    //  * Scene
+    fn new(
+        config: &EngineConfig,
+        window: Option<&winit::Window>,
+        settings: PipelineSettings,
+    ) -> Result<Self, EngineError> {
+        let mut engine = Engine::new(config)?;
+        let swapchain = engine.create_swapchain(window, settings.width, settings.height)?;
+        let pipeline = Pipeline::new(&mut engine, settings)?;
+        let frames = Frames::new(&engine)?;
+
+        Ok(Gpu {
+            engine,
+            swapchain,
+            pipeline,
+            frames,
+        })
+    }
+
+    /// Resize the swapchain and all render targets.
+    ///
+    /// Minimized windows report a size of zero, the targets are kept in this case.
+    fn resize(&mut self, width: u32, height: u32) -> Result<(), EngineError> {
+        let settings = self.pipeline.settings();
+        if width == 0 || height == 0 || (width, height) == (settings.width, settings.height) {
+            return Ok(());
+        }
+
+        self.frames.wait_idle(&mut self.engine)?;
+        self.swapchain.resize(&mut self.engine, width, height)?;
+        self.pipeline.resize(&mut self.engine, width, height)
+    }
+}
+
+/// Record and submit a frame.
+fn render_frame<B: Backend>(
+    gpu: &mut Gpu<B>,
+    scene: &Scene<B>,
+    camera: &scene::Camera,
+    light_heatmap: bool,
+) -> Result<(), EngineError> {
+    let Gpu {
+        ref mut engine,
+        ref swapchain,
+        ref pipeline,
+        ref mut frames,
+    } = *gpu;
+    let settings = pipeline.settings();
+    let (width, height) = (settings.width, settings.height);
+    let (render_width, render_height) = settings.render_size();
+
+    // ! Frame Begin ----------------------------------------------------------------------------------
+    // Synchronize GPU -> CPU to handle resource reuse
+    let frame = frames.begin(&engine.device)?;
+    let backbuffer = swapchain.begin_frame();
+    let (present_target, present_rtv) = swapchain.get_render_target(backbuffer);
+
+    let viewport = |width: u32, height: u32| backend::Viewport {
+        x: 0.0,
+        y: 0.0,
+        width: width as _,
+        height: height as _,
+        min_depth: 0.0,
+        max_depth: 1.0,
+    };
+    let scissor = |width: u32, height: u32| backend::Rect {
+        left: 0,
+        top: 0,
+        right: width as _,
+        bottom: height as _,
+    };
+
+    // Update view data
+    let view_data = ViewData::new(camera, width, height);
+    let view_cbv = frame.push_constants(&view_data);
+
+    // Update light data
+    let light_buffer = scene
+        .world
+        .read_resource::<scene::light::LightDataBuffer<B>>();
+    let lights = scene.light_data(&view_data);
+    let light_srvs = light_buffer.upload(&engine.device, frame, &lights);
+    let light_data_raw: [u32; 4] = unsafe { mem::transmute(lights.light_data()) };
+    let shadow_cbvs = lights
+        .shadow_views
+        .iter()
+        .map(|shadow_view| frame.push_constants(shadow_view))
+        .collect::<Vec<_>>();
+
+    let cmd_list = &frame.cmd_list;
+    engine.bind_descriptor_heaps(&cmd_list);
+
+    // Update instance data
+    let instance_data = scene
+        .world
+        .read_resource::<scene::geometry::InstanceDataBuffer<B>>();
+    {
+        let instance_data_cpu = unsafe {
+            let instance_raw_data = engine.device.map(&instance_data.buffer)?;
+            slice::from_raw_parts_mut::<scene::geometry::InstanceData>(
+                instance_raw_data as _,
+                engine.frame_latency() as usize * instance_data.num_instances,
+            )
         };
-        view_data_cpu[frame].position =
-            [camera.position.x, camera.position.y, camera.position.z, 1.0];
-        unsafe {
-            view_data.Unmap(0, ptr::null());
+        let num_instances = instance_data.num_instances;
+        let frame_instances = frame.index * num_instances..(frame.index + 1) * num_instances;
+        scene.write_instance_data(&mut instance_data_cpu[frame_instances]);
+        engine.device.unmap(&instance_data.buffer);
+    }
+    let instance_srv = instance_data.srvs.gpu(frame.index as _);
+
+    let mesh = scene.assets.read_resource::<scene::Mesh<B>>();
+    let index_buffer_location = engine.device.gpu_virtual_address(&mesh.index_buffer);
+    let vertex_buffer_location = engine.device.gpu_virtual_address(&mesh.vertex_buffer);
+
+    let materials = scene
+        .assets
+        .read_resource::<scene::material::MaterialDataBuffer<B>>();
+    let environment = scene
+        .assets
+        .read_resource::<scene::environment::EnvironmentBuffer<B>>();
+    // Texture table isn't accessed without loaded textures, any valid table works.
+    let texture_srvs = match scene.texture_srvs.srvs {
+        Some(ref srvs) => srvs.gpu(0),
+        None => materials.srvs.gpu(0),
+    };
+
+    let target_data = TargetData {
+        extent: [render_width, render_height],
+    };
+    let target_data_raw: [u32; 2] = unsafe { mem::transmute(target_data) };
+    let cluster_data = ClusterData::new([render_width, render_height], light_heatmap);
+    let cluster_data_raw: [u32; 8] = unsafe { mem::transmute(cluster_data) };
+    let display_map = DisplayMapData::new((render_width, render_height), (width, height));
+    let display_map_raw: [u32; 4] = unsafe { mem::transmute(display_map) };
+
+    // Draw all instances with the geometry or shadow map root signature.
+    let draw_instances = |cmd_list: &B::CommandList| {
+        cmd_list.set_index_buffer(&backend::IndexBufferView {
+            location: index_buffer_location,
+            size: mesh.index_buffer_size,
+            format: mesh.index_format,
+        });
+        cmd_list.set_vertex_buffers(
+            0,
+            &[backend::VertexBufferView {
+                location: vertex_buffer_location,
+                size: mesh.vertex_buffer_size,
+                stride: mesh.vertex_stride,
+            }],
+        );
+        cmd_list.set_graphics_root_descriptor_table(1, mesh.srvs.gpu(0));
+        cmd_list.set_graphics_root_descriptor_table(4, instance_srv);
+        cmd_list.set_graphics_root_descriptor_table(5, materials.srvs.gpu(0));
+        cmd_list.set_graphics_root_descriptor_table(6, texture_srvs);
+
+        let transforms = scene.world.read_storage::<scene::LocalTransform>();
+        let instances = scene.world.read_storage::<scene::Instance>();
+        let geometries = scene.assets.read_storage::<scene::Geometry>();
+        let entities = scene.world.entities();
+
+        for (e, _, instance) in (&*entities, &transforms, &instances).join() {
+            let geometry = geometries.get(instance.geometry).unwrap();
+            let draw_constants = [
+                geometry.base_index as u32,
+                geometry.base_vertex as u32,
+                scene.materials[&geometry.material] as u32,
+            ];
+            cmd_list.set_graphics_root_constants(2, &draw_constants, 0);
+            cmd_list.set_graphics_root_constants(3, &[scene.instances[&e] as _], 0);
+            cmd_list.draw_indexed_instanced(
+                geometry.num_indices as _,
+                1,
+                geometry.base_index as _,
+                geometry.base_vertex as _,
+                0,
+            );
         }
+    };
 
-        let mesh = scene.assets.read_resource::<scene::Mesh>();
-
-        // Draw scene geometry
-        {
-            unsafe {
-                cmd_list.IASetIndexBuffer(&D3D12_INDEX_BUFFER_VIEW {
-                    BufferLocation: mesh.index_buffer.GetGPUVirtualAddress(),
-                    SizeInBytes: mesh.index_buffer_size,
-                    Format: mesh.index_format,
-                });
-                cmd_list.IASetVertexBuffers(
-                    0,
+    pipeline.execute(
+        engine,
+        cmd_list,
+        &present_target,
+        |pass, cmd_list| match pass {
+            Pass::Geometry => {
+                let invalid = INVALID_ID as f32;
+                cmd_list.clear_render_target_view(
+                    pipeline.geometry_rtv_uint,
+                    [invalid, invalid, 0.0, 0.0],
+                );
+                cmd_list.clear_depth_stencil_view(pipeline.dsv, 1.0);
+
+                cmd_list.set_graphics_root_signature(&pipeline.geometry.signature);
+                cmd_list.set_pipeline_state(&pipeline.geometry.pipeline);
+                cmd_list.set_primitive_topology(backend::PrimitiveTopology::TriangleList);
+                cmd_list.set_viewport(&viewport(render_width, render_height));
+                cmd_list.set_scissor(&scissor(render_width, render_height));
+                cmd_list.set_render_targets(&[pipeline.geometry_rtv_uint], Some(pipeline.dsv));
+                cmd_list.set_graphics_root_constant_buffer_view(0, view_cbv);
+
+                // Draw scene geometry
+                draw_instances(cmd_list);
+            }
+            Pass::ShadowMaps => {
+                cmd_list.clear_depth_stencil_view(pipeline.shadow_dsv, 1.0);
+
+                cmd_list.set_graphics_root_signature(&pipeline.shadow_mapping.signature);
+                cmd_list.set_pipeline_state(&pipeline.shadow_mapping.pipeline);
+                cmd_list.set_primitive_topology(backend::PrimitiveTopology::TriangleList);
+                cmd_list.set_render_targets(&[], Some(pipeline.shadow_dsv));
+
+                // Draw scene geometry into the atlas tile of each view
+                for (shadow_view, &shadow_cbv) in lights.shadow_views.iter().zip(&shadow_cbvs) {
+                    let viewport = shadow_view.viewport();
+                    cmd_list.set_viewport(&viewport);
+                    cmd_list.set_scissor(&backend::Rect {
+                        left: viewport.x as _,
+                        top: viewport.y as _,
+                        right: (viewport.x + viewport.width) as _,
+                        bottom: (viewport.y + viewport.height) as _,
+                    });
+                    cmd_list.set_graphics_root_constant_buffer_view(0, shadow_cbv);
+                    draw_instances(cmd_list);
+                }
+            }
+            Pass::LightClustering => {
+                cmd_list.set_compute_root_signature(&pipeline.clustering.signature);
+                cmd_list.set_pipeline_state(&pipeline.clustering.pipeline);
+                cmd_list.set_compute_root_constant_buffer_view(0, view_cbv);
+                cmd_list.set_compute_root_constants(1, &light_data_raw, 0);
+                cmd_list.set_compute_root_descriptor_table(2, light_srvs);
+                cmd_list.set_compute_root_constants(3, &cluster_data_raw, 0);
+                cmd_list.set_compute_root_descriptor_table(4, pipeline.cluster_uavs);
+                cmd_list.dispatch(
+                    (cluster_data.num_clusters() + cluster::CLUSTER_THREADS - 1)
+                        / cluster::CLUSTER_THREADS,
+                    1,
                     1,
-                    &D3D12_VERTEX_BUFFER_VIEW {
-                        BufferLocation: mesh.vertex_buffer.GetGPUVirtualAddress(),
-                        SizeInBytes: mesh.vertex_buffer_size,
-                        StrideInBytes: mesh.vertex_stride,
-                    },
                 );
-                cmd_list.SetGraphicsRootDescriptorTable(
+            }
+            Pass::Lighting => {
+                // Lighting/shading pass
+                cmd_list.set_compute_root_signature(&pipeline.lighting.signature);
+                cmd_list.set_pipeline_state(&pipeline.lighting.pipeline);
+                cmd_list.set_compute_root_descriptor_table(0, pipeline.lighting_uav);
+                cmd_list.set_compute_root_descriptor_table(1, pipeline.geometry_srv_uint);
+                cmd_list.set_compute_root_descriptor_table(2, texture_srvs);
+                cmd_list.set_compute_root_descriptor_table(3, mesh.srvs.gpu(0));
+                cmd_list.set_compute_root_constants(4, &light_data_raw, 0);
+                cmd_list.set_compute_root_descriptor_table(5, light_srvs);
+                cmd_list.set_compute_root_descriptor_table(6, instance_srv);
+                cmd_list.set_compute_root_constants(7, &target_data_raw, 0);
+                cmd_list.set_compute_root_descriptor_table(8, materials.srvs.gpu(0));
+                cmd_list.set_compute_root_constant_buffer_view(9, view_cbv);
+                cmd_list.set_compute_root_constants(10, &cluster_data_raw, 0);
+                cmd_list.set_compute_root_descriptor_table(11, pipeline.cluster_srvs);
+                cmd_list.set_compute_root_descriptor_table(12, pipeline.shadow_srv);
+                cmd_list.set_compute_root_descriptor_table(13, environment.srvs.gpu(0));
+                // Partial tiles at the border are discarded in the shader.
+                cmd_list.dispatch(
+                    (render_width + lighting::TILE_THREADS_X - 1) / lighting::TILE_THREADS_X,
+                    (render_height + lighting::TILE_THREADS_Y - 1) / lighting::TILE_THREADS_Y,
                     1,
-                    D3D12_GPU_DESCRIPTOR_HANDLE {
-                        ptr: engine.cbv_srv_uav_start.1.ptr
-                            + (mesh.start_srvs * engine.cbv_srv_uav_size) as u64,
-                    },
                 );
             }
-
-            let transforms = scene.world.read_storage::<scene::LocalTransform>();
-            let instances = scene.world.read_storage::<scene::Instance>();
-            let geometries = scene.assets.read_storage::<scene::Geometry>();
-
-            for (_, instance) in (&transforms, &instances).join() {
-                let geometry = geometries.get(instance.geometry).unwrap();
-                unsafe {
-                    let draw_constants = [geometry.base_index as u32, geometry.base_vertex as u32];
-                    cmd_list.SetGraphicsRoot32BitConstants(
-                        2,
-                        draw_constants.len() as _,
-                        draw_constants.as_ptr() as _,
-                        0,
-                    );
-                    cmd_list.SetGraphicsRoot32BitConstant(3, geometry.id as _, 0);
-                    cmd_list.DrawIndexedInstanced(
-                        geometry.num_indices as _,
-                        1,
-                        geometry.base_index as _,
-                        geometry.base_vertex as _,
-                        0,
-                    );
-                }
+            Pass::ClusterReadback => {
+                cmd_list.copy_resource(
+                    &pipeline.cluster_readback_buffer,
+                    pipeline.graph.resource(pipeline.cluster_grid),
+                );
             }
-        }
+            Pass::DisplayMap => {
+                // Post Processing
+                cmd_list
+                    .set_graphics_root_signature(&pipeline.post_process.display_map.signature);
+                cmd_list.set_pipeline_state(&pipeline.post_process.display_map.pipeline);
+                cmd_list.set_primitive_topology(backend::PrimitiveTopology::TriangleList);
+                cmd_list.set_viewport(&viewport(width, height));
+                cmd_list.set_scissor(&scissor(width, height));
+                cmd_list.set_render_targets(&[present_rtv], None);
+                cmd_list.set_graphics_root_descriptor_table(0, pipeline.lighting_srv);
+                cmd_list.set_graphics_root_constants(1, &display_map_raw, 0);
+                cmd_list.draw_instanced(3, 1, 0, 0);
+            }
+        },
+    );
 
-        // Geometry/visibility buffer: Render Target -> SRV
-        let geometry_srv_transition = engine::gen_resource_transition(
-            &pipeline.geometry_buffer,
-            D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES,
-            D3D12_RESOURCE_STATE_RENDER_TARGET,
-            D3D12_RESOURCE_STATE_NON_PIXEL_SHADER_RESOURCE,
-            D3D12_RESOURCE_BARRIER_FLAG_NONE,
-        );
-        unsafe {
-            cmd_list.ResourceBarrier(1, &geometry_srv_transition);
+    frames.end(engine);
+    swapchain.end_frame()
+}
+
+/// Render the scene on the CPU and store the visibility and lighting buffer.
+fn write_reference<B: Backend>(
+    dir: &Path,
+    scene: &Scene<B>,
+    camera: &scene::Camera,
+    width: u32,
+    height: u32,
+) -> Result<(), Error> {
+    std::fs::create_dir_all(dir)?;
+
+    let view = ViewData::new(camera, width, height);
+    let (visibility, lighting, light_heatmap) =
+        reference::render_scene(scene, &view, width, height)?;
+    reference::save_visibility_png(dir.join("visibility.png"), &visibility)?;
+    reference::save_lighting_png(dir.join("lighting.png"), &lighting)?;
+    reference::save_lighting_png(dir.join("light_heatmap.png"), &light_heatmap)?;
+
+    Ok(())
+}
+
+/// Store the frame graph structure, DOT for `.dot` files and JSON otherwise.
+fn write_graph<P: Copy>(path: &Path, graph: &graph::CompiledGraph<P>) -> Result<(), Error> {
+    let dump = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("dot") => graph.to_dot(),
+        _ => serde_json::to_string_pretty(&graph.to_json())?,
+    };
+    std::fs::write(path, dump)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::null::{Call, Null};
+    use backend::DescriptorHeapType;
+    use scene::geometry::{DrawData, InstanceData, InstanceDataBuffer, MeshData, VertexPos};
+    use scene::{environment, light, Geometry, Instance, LocalTransform, Material};
+    use specs::prelude::*;
+    use std::ptr;
+
+    const SETTINGS: PipelineSettings = PipelineSettings {
+        width: 64,
+        height: 32,
+        samples: 1,
+        render_scale: 1.0,
+    };
+
+    fn transform(position: Vector3<f32>) -> LocalTransform {
+        LocalTransform::new(
+            position,
+            1.0,
+            Euler::new(Rad(0.0), Rad(0.0), Rad(0.0)),
+            None,
+        )
+    }
+
+    /// Triangle lit by a shadow casting point light, only the CPU data is loaded.
+    fn triangle_scene() -> Scene<Null> {
+        let mut scene = Scene::new();
+
+        let mut mesh_data = MeshData::new(3, 3);
+        mesh_data.positions = vec![
+            VertexPos([0.0, 0.0, 10.0]),
+            VertexPos([1.0, 0.0, 10.0]),
+            VertexPos([0.0, 1.0, 10.0]),
+        ];
+        mesh_data.indices = vec![0, 1, 2];
+        mesh_data.draw_data = vec![DrawData {
+            base_index: 0,
+            base_vertex: 0,
+            material: 0,
+        }];
+        scene.assets.add_resource(mesh_data);
+        scene.assets.add_resource(ibl::Environment::uniform(
+            environment::DEFAULT_RADIANCE,
+            1.0,
+        ));
+
+        let material = scene
+            .assets
+            .create_entity()
+            .with(Material::default())
+            .build();
+        scene.materials.insert(material, 0);
+        let geometry = scene
+            .assets
+            .create_entity()
+            .with(Geometry {
+                id: 0,
+                base_index: 0,
+                num_indices: 3,
+                base_vertex: 0,
+                material,
+            })
+            .build();
+
+        let instance = scene
+            .world
+            .create_entity()
+            .with(Instance { geometry })
+            .with(transform(Vector3::new(0.0, 0.0, 0.0)))
+            .build();
+        scene.instances.insert(instance, 0);
+        scene
+            .world
+            .create_entity()
+            .with(light::PointLight::from_luminous_power([1.0; 3], 1000.0))
+            .with(light::ShadowCaster { resolution: 128 })
+            .with(transform(Vector3::new(0.0, 5.0, 0.0)))
+            .build();
+        scene.sync_lights();
+
+        scene
+    }
+
+    fn camera() -> scene::Camera {
+        scene::Camera {
+            position: Point3::new(0.0, 0.0, 0.0),
+            rotation: [Rad(0.0), Rad(0.0), Rad(0.0)],
+            up: Vector3::new(0.0, 1.0, 0.0),
+            view_move: (false, false),
+            view_rotate: (false, false, false, false),
+            depth_range: 0.0..1.0,
+            focal_length: 1.0,
         }
+    }
 
-        // Lighting/shading pass
-        assert_eq!(pipeline_settings.width % lighting::TILE_THREADS_X, 0);
-        assert_eq!(pipeline_settings.height % lighting::TILE_THREADS_Y, 0);
+    /// Create the device state, restore the scene and render a frame.
+    fn render_scene(config: &EngineConfig, scene: &mut Scene<Null>) -> Gpu<Null> {
+        let mut gpu = Gpu::<Null>::new(config, None, SETTINGS).unwrap();
+        SceneLoader::restore(scene, &mut gpu.engine).unwrap();
+        gpu.engine.flush_uploads().unwrap();
+        render_frame(&mut gpu, scene, &camera(), false).unwrap();
+        gpu.frames.wait_idle(&mut gpu.engine).unwrap();
+        assert_eq!(gpu.engine.validation_errors(), Vec::<String>::new());
+        gpu
+    }
 
-        let light_data = pass::lighting::LightData {
-            num_point_lights: scene.point_lights.len() as _,
-        };
-        let light_data_raw: [u32; 1] = unsafe { mem::transmute(light_data) };
-        let lights = scene.world.read_resource::<scene::light::LightDataBuffer>();
-
-        unsafe {
-            cmd_list.SetComputeRootSignature(pipeline.lighting.signature.as_raw());
-            cmd_list.SetPipelineState(pipeline.lighting.pipeline.as_raw());
-            cmd_list.SetComputeRootDescriptorTable(0, pipeline.lighting_uav);
-            cmd_list.SetComputeRootDescriptorTable(1, pipeline.geometry_srv_uint);
-            cmd_list.SetComputeRootDescriptorTable(
-                2,
-                D3D12_GPU_DESCRIPTOR_HANDLE {
-                    ptr: engine.cbv_srv_uav_start.1.ptr
-                        + engine.cbv_srv_uav_size as u64 * scene.texture_srvs.start_id as u64,
-                },
-            );
-            cmd_list.SetComputeRootDescriptorTable(
-                3,
-                D3D12_GPU_DESCRIPTOR_HANDLE {
-                    ptr: engine.cbv_srv_uav_start.1.ptr
-                        + (mesh.start_srvs * engine.cbv_srv_uav_size) as u64,
-                },
-            );
-            cmd_list.SetComputeRoot32BitConstants(
-                4,
-                light_data_raw.len() as _,
-                light_data_raw.as_ptr() as _,
-                0,
-            );
-            cmd_list.SetComputeRootDescriptorTable(
-                5,
-                D3D12_GPU_DESCRIPTOR_HANDLE {
-                    ptr: engine.cbv_srv_uav_start.1.ptr
-                        + (lights.start_srvs * engine.cbv_srv_uav_size) as u64,
-                },
-            );
-            cmd_list.Dispatch(
-                pipeline_settings.width / lighting::TILE_THREADS_X,
-                pipeline_settings.height / lighting::TILE_THREADS_Y,
-                1,
-            );
+    /// Instance data of the frame `index` in the per-frame instance buffer.
+    fn frame_instances(scene: &Scene<Null>, index: usize) -> Vec<InstanceData> {
+        let instance_data = scene.world.read_resource::<InstanceDataBuffer<Null>>();
+        let data = instance_data.buffer.data();
+        let stride = mem::size_of::<InstanceData>();
+        (0..instance_data.num_instances)
+            .map(|i| {
+                let offset = (index * instance_data.num_instances + i) * stride;
+                unsafe { ptr::read_unaligned(data[offset..].as_ptr() as *const InstanceData) }
+            })
+            .collect()
+    }
 
-            let lighting_uav_barriers = [
-                engine::gen_uav_barrier(
-                    &pipeline.lighting_buffer,
-                    D3D12_RESOURCE_BARRIER_FLAG_NONE,
-                ),
-                engine::gen_resource_transition(
-                    &pipeline.lighting_buffer,
-                    D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES,
-                    D3D12_RESOURCE_STATE_UNORDERED_ACCESS,
-                    D3D12_RESOURCE_STATE_PIXEL_SHADER_RESOURCE,
-                    D3D12_RESOURCE_BARRIER_FLAG_NONE,
+    #[test]
+    fn instance_transforms() {
+        let config = EngineConfig::default();
+        let mut scene = triangle_scene();
+        let (parent, geometry) = {
+            let instances = scene.world.read_storage::<Instance>();
+            let entities = scene.world.entities();
+            (&*entities, &instances)
+                .join()
+                .map(|(e, instance)| (e, instance.geometry))
+                .next()
+                .unwrap()
+        };
+        scene
+            .world
+            .write_storage::<LocalTransform>()
+            .insert(
+                parent,
+                LocalTransform::new(
+                    Vector3::new(1.0, 2.0, 3.0),
+                    2.0,
+                    Euler::new(Rad(0.0), Rad(0.0), Rad(0.0)),
+                    None,
                 ),
-            ];
-            cmd_list.ResourceBarrier(
-                lighting_uav_barriers.len() as _,
-                lighting_uav_barriers.as_ptr(),
-            );
-        }
+            )
+            .unwrap();
+        // Child instance of the same geometry, offset in the space of the parent.
+        let child = scene
+            .world
+            .create_entity()
+            .with(Instance { geometry })
+            .with(LocalTransform::new(
+                Vector3::new(0.0, 1.0, 0.0),
+                1.0,
+                Euler::new(Rad(0.0), Rad(0.0), Rad(0.0)),
+                Some(parent),
+            ))
+            .build();
+        scene.instances.insert(child, 1);
 
-        // Post Processing
-        unsafe {
-            cmd_list.SetGraphicsRootSignature(pipeline.post_process.display_map.signature.as_raw());
-            cmd_list.SetPipelineState(pipeline.post_process.display_map.pipeline.as_raw());
-            cmd_list.IASetPrimitiveTopology(D3D_PRIMITIVE_TOPOLOGY_TRIANGLELIST);
-            cmd_list.RSSetViewports(
-                1,
-                &D3D12_VIEWPORT {
-                    TopLeftX: 0.0,
-                    TopLeftY: 0.0,
-                    Width: window_width as _,
-                    Height: window_height as _,
-                    MinDepth: 0.0,
-                    MaxDepth: 1.0,
-                },
-            );
-            cmd_list.RSSetScissorRects(
-                1,
-                &D3D12_RECT {
-                    left: 0,
-                    top: 0,
-                    right: window_width as _,
-                    bottom: window_height as _,
-                },
-            );
-            cmd_list.OMSetRenderTargets(1, &present_rtv, FALSE, ptr::null());
-            cmd_list.SetGraphicsRootDescriptorTable(0, pipeline.lighting_srv);
-            cmd_list.DrawInstanced(3, 1, 0, 0);
-        }
+        let mut gpu = render_scene(&config, &mut scene);
+        let instances = frame_instances(&scene, 0);
+        let parent_world = Matrix4::from_translation(Vector3::new(1.0, 2.0, 3.0))
+            * Matrix4::from_scale(2.0);
+        let child_world = parent_world * Matrix4::from_translation(Vector3::new(0.0, 1.0, 0.0));
+        assert_eq!(Matrix4::from(instances[0].world), parent_world);
+        assert_eq!(Matrix4::from(instances[1].world), child_world);
+        assert_eq!((instances[0].geometry_id, instances[1].geometry_id), (0, 0));
+
+        // Moving the parent moves the child in the next frame.
+        scene
+            .world
+            .write_storage::<LocalTransform>()
+            .insert(parent, transform(Vector3::new(-1.0, 0.0, 0.0)))
+            .unwrap();
+        render_frame(&mut gpu, &scene, &camera(), false).unwrap();
+        gpu.frames.wait_idle(&mut gpu.engine).unwrap();
+        assert_eq!(gpu.engine.validation_errors(), Vec::<String>::new());
 
-        // Backbuffer: RenderTarget -> Present
-        let rt_present_transition = engine::gen_resource_transition(
-            &present_target,
-            D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES,
-            D3D12_RESOURCE_STATE_RENDER_TARGET,
-            D3D12_RESOURCE_STATE_PRESENT,
-            D3D12_RESOURCE_BARRIER_FLAG_NONE,
+        let instances = frame_instances(&scene, 1);
+        assert_eq!(
+            Matrix4::from(instances[1].world),
+            Matrix4::from_translation(Vector3::new(-1.0, 1.0, 0.0))
         );
-        unsafe {
-            cmd_list.ResourceBarrier(1, &rt_present_transition);
-        }
+        // Data of the previous frame stays untouched while it may be in flight.
+        assert_eq!(
+            Matrix4::from(frame_instances(&scene, 0)[1].world),
+            child_world
+        );
+    }
 
-        unsafe {
-            cmd_list.Close();
-        }
+    #[test]
+    fn resize_targets() {
+        let config = EngineConfig::default();
+        let mut scene = triangle_scene();
+        let mut gpu = render_scene(&config, &mut scene);
 
-        unsafe {
-            engine
-                .queue
-                .ExecuteCommandLists(1, &(cmd_list.as_raw() as *mut _));
-            engine.queue.Signal(present_fence.as_raw(), tick);
-        }
-        swapchain.end_frame();
+        // Extent not divisible by the lighting tile size.
+        gpu.resize(100, 50).unwrap();
+        render_frame(&mut gpu, &scene, &camera(), false).unwrap();
+        gpu.frames.wait_idle(&mut gpu.engine).unwrap();
+        assert_eq!(gpu.engine.validation_errors(), Vec::<String>::new());
 
-        // ! Frame End ----------------------------------------------------------------------------------
+        let settings = gpu.pipeline.settings();
+        assert_eq!((settings.width, settings.height), (100, 50));
+        let extent = |resource: &backend::null::Resource| {
+            let desc = resource.desc();
+            (desc.width, desc.height)
+        };
+        for i in 0..gpu.engine.frame_latency().max(2) as usize {
+            assert_eq!(extent(&gpu.swapchain.get_render_target(i).0), (100, 50));
+        }
+        let pipeline = &gpu.pipeline;
+        for &target in &[
+            pipeline.geometry_buffer,
+            pipeline.lighting_buffer,
+            pipeline.depth_target,
+        ] {
+            assert_eq!(extent(pipeline.graph.resource(target)), (100, 50));
+        }
 
-        tick += 1;
+        // Minimized windows keep the targets.
+        gpu.resize(0, 0).unwrap();
+        let settings = gpu.pipeline.settings();
+        assert_eq!((settings.width, settings.height), (100, 50));
     }
 
-    unsafe {
-        present_fence.SetEventOnCompletion(tick - 1, engine.wait_event);
-        WaitForSingleObject(engine.wait_event, 5_0000);
-    }
+    #[test]
+    fn restore_after_device_removed() {
+        let config = EngineConfig::default();
+        let mut scene = triangle_scene();
 
-    Ok(())
+        let mut gpu = render_scene(&config, &mut scene);
+        let calls = gpu.engine.device.calls();
+        let srv_uavs = gpu.engine.descriptor_stats(DescriptorHeapType::CbvSrvUav);
+        let memory = gpu.engine.memory_stats();
+
+        gpu.engine.device.simulate_device_removed();
+        let err = render_frame(&mut gpu, &scene, &camera(), false)
+            .err()
+            .expect("device removal not reported");
+        assert!(gpu.engine.device_lost(&err).is_some());
+        drop(gpu);
+
+        // New device with all scene resources and descriptors recreated in the same order.
+        let gpu = render_scene(&config, &mut scene);
+        let names = |calls: &[Call]| calls.iter().map(|call| call.name).collect::<Vec<_>>();
+        let restored_calls = gpu.engine.device.calls();
+        assert_eq!(names(&restored_calls), names(&calls));
+        assert!(restored_calls
+            .iter()
+            .any(|call| call.name == "create_placed_resource"));
+        assert_eq!(
+            gpu.engine
+                .descriptor_stats(DescriptorHeapType::CbvSrvUav)
+                .allocated,
+            srv_uavs.allocated
+        );
+        assert_eq!(gpu.engine.memory_stats(), memory);
+    }
 }
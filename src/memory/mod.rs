@@ -0,0 +1,235 @@
+//! GPU memory suballocation.
+//!
+//! Resources are placed in large heaps instead of creating an implicit heap per
+//! committed resource. Heaps are grouped into pools by heap type and resource
+//! category (buffers, textures, render/depth targets), each heap is managed by a
+//! `Tlsf` allocator. Heaps are created on demand and kept alive with the allocator.
+//!
+//! Allocations are returned to their heap on drop, placed resources must not be in
+//! use by the GPU anymore at this point.
+
+pub mod tlsf;
+
+use self::tlsf::{Tlsf, TlsfAllocation, TlsfStats};
+use backend::{
+    Backend, Device, EngineError, Flags, HeapDesc, HeapFlags, HeapType, ResourceDesc,
+    ResourceDimension, ResourceFlags, DEFAULT_MSAA_RESOURCE_PLACEMENT_ALIGNMENT,
+    DEFAULT_RESOURCE_PLACEMENT_ALIGNMENT,
+};
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+/// Default size of a newly created heap, larger resources get a dedicated heap.
+const HEAP_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Resource category of a heap.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ResourceClass {
+    Buffer,
+    Texture,
+    RenderTarget,
+}
+
+impl ResourceClass {
+    pub fn of(desc: &ResourceDesc) -> Self {
+        match desc.dimension {
+            ResourceDimension::Buffer => ResourceClass::Buffer,
+            ResourceDimension::Texture2D
+                if desc.flags.intersects(
+                    ResourceFlags::ALLOW_RENDER_TARGET | ResourceFlags::ALLOW_DEPTH_STENCIL,
+                ) =>
+            {
+                ResourceClass::RenderTarget
+            }
+            ResourceDimension::Texture2D => ResourceClass::Texture,
+        }
+    }
+
+    fn heap_flags(&self) -> HeapFlags {
+        match *self {
+            ResourceClass::Buffer => HeapFlags::ALLOW_ONLY_BUFFERS,
+            ResourceClass::Texture => HeapFlags::ALLOW_ONLY_NON_RT_DS_TEXTURES,
+            ResourceClass::RenderTarget => HeapFlags::ALLOW_ONLY_RT_DS_TEXTURES,
+        }
+    }
+}
+
+/// Usage of a single heap.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HeapStats {
+    pub ty: HeapType,
+    pub class: ResourceClass,
+    pub alignment: u64,
+    pub usage: TlsfStats,
+}
+
+struct MemoryHeap<B: Backend> {
+    heap: Arc<B::Heap>,
+    allocator: Arc<Mutex<Tlsf>>,
+}
+
+struct Pool<B: Backend> {
+    ty: HeapType,
+    class: ResourceClass,
+    alignment: u64,
+    heaps: Vec<MemoryHeap<B>>,
+}
+
+/// Suballocator for placed resources.
+pub struct MemoryAllocator<B: Backend> {
+    pools: Vec<Pool<B>>,
+}
+
+impl<B: Backend> MemoryAllocator<B> {
+    pub fn new() -> Self {
+        MemoryAllocator { pools: Vec::new() }
+    }
+
+    /// Allocate memory for a resource of the given class.
+    ///
+    /// `alignment` selects the pool, multisampled resources are kept apart to
+    /// avoid wasting their large alignment on every heap.
+    pub fn allocate(
+        &mut self,
+        device: &B::Device,
+        ty: HeapType,
+        class: ResourceClass,
+        size: u64,
+        alignment: u64,
+    ) -> Result<Allocation<B>, EngineError> {
+        let heap_alignment = if alignment > DEFAULT_RESOURCE_PLACEMENT_ALIGNMENT {
+            DEFAULT_MSAA_RESOURCE_PLACEMENT_ALIGNMENT
+        } else {
+            DEFAULT_RESOURCE_PLACEMENT_ALIGNMENT
+        };
+
+        let pool_id = match self.pools.iter().position(|pool| {
+            pool.ty == ty && pool.class == class && pool.alignment == heap_alignment
+        }) {
+            Some(id) => id,
+            None => {
+                self.pools.push(Pool {
+                    ty,
+                    class,
+                    alignment: heap_alignment,
+                    heaps: Vec::new(),
+                });
+                self.pools.len() - 1
+            }
+        };
+        let pool = &mut self.pools[pool_id];
+
+        for heap in &pool.heaps {
+            let allocation = heap.allocator.lock().unwrap().allocate(size, alignment);
+            if let Some(allocation) = allocation {
+                return Ok(Allocation {
+                    heap: heap.heap.clone(),
+                    allocator: heap.allocator.clone(),
+                    allocation,
+                });
+            }
+        }
+
+        // No space left in the existing heaps.
+        let heap_size =
+            (size.max(HEAP_SIZE) + heap_alignment - 1) / heap_alignment * heap_alignment;
+        let heap = device.create_heap(&HeapDesc {
+            size: heap_size,
+            ty,
+            alignment: heap_alignment,
+            flags: class.heap_flags(),
+        })?;
+        let mut allocator = Tlsf::new(heap_size);
+        let allocation = allocator
+            .allocate(size, alignment)
+            .expect("allocation doesn't fit into a new heap");
+
+        let heap = MemoryHeap::<B> {
+            heap: Arc::new(heap),
+            allocator: Arc::new(Mutex::new(allocator)),
+        };
+        let allocation = Allocation {
+            heap: heap.heap.clone(),
+            allocator: heap.allocator.clone(),
+            allocation,
+        };
+        pool.heaps.push(heap);
+        Ok(allocation)
+    }
+
+    /// Usage of all heaps.
+    pub fn stats(&self) -> Vec<HeapStats> {
+        self.pools
+            .iter()
+            .flat_map(|pool| {
+                pool.heaps.iter().map(move |heap| HeapStats {
+                    ty: pool.ty,
+                    class: pool.class,
+                    alignment: pool.alignment,
+                    usage: heap.allocator.lock().unwrap().stats(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Memory range in a heap.
+///
+/// Returned to the heap on drop.
+pub struct Allocation<B: Backend> {
+    heap: Arc<B::Heap>,
+    allocator: Arc<Mutex<Tlsf>>,
+    allocation: TlsfAllocation,
+}
+
+impl<B: Backend> Allocation<B> {
+    pub fn heap(&self) -> &B::Heap {
+        &self.heap
+    }
+
+    pub fn offset(&self) -> u64 {
+        self.allocation.offset
+    }
+}
+
+impl<B: Backend> Drop for Allocation<B> {
+    fn drop(&mut self) {
+        self.allocator.lock().unwrap().free(self.allocation);
+    }
+}
+
+impl<B: Backend> fmt::Debug for Allocation<B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Allocation")
+            .field("offset", &self.allocation.offset)
+            .field("size", &self.allocation.size)
+            .finish()
+    }
+}
+
+/// Resource placed in suballocated memory.
+///
+/// Dereferences to the resource, the memory is released after the resource on drop.
+pub struct PlacedResource<B: Backend> {
+    resource: B::Resource,
+    // Keeps the memory of the resource alive.
+    _allocation: Allocation<B>,
+}
+
+impl<B: Backend> PlacedResource<B> {
+    pub fn new(resource: B::Resource, allocation: Allocation<B>) -> Self {
+        PlacedResource {
+            resource,
+            _allocation: allocation,
+        }
+    }
+}
+
+impl<B: Backend> Deref for PlacedResource<B> {
+    type Target = B::Resource;
+
+    fn deref(&self) -> &B::Resource {
+        &self.resource
+    }
+}
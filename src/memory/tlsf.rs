@@ -0,0 +1,513 @@
+//! Two-level segregated fit allocator.
+//!
+//! Manages offsets into a linear range of memory independent of the device.
+//! Free blocks are binned by size into a first level (power of two) and a second
+//! level (linear subdivision), lookup and release are O(1) using the bitmaps of
+//! non-empty bins. Adjacent free blocks are merged on release.
+
+/// Log2 of the number of second level bins per first level.
+const SL_LOG2: u32 = 4;
+const SL_COUNT: usize = 1 << SL_LOG2;
+/// Sizes below are binned linearly in the first level.
+const SMALL_SIZE: u64 = 1 << SL_LOG2;
+const FL_COUNT: usize = 64 - SL_LOG2 as usize + 1;
+
+type BlockId = usize;
+
+#[derive(Copy, Clone, Debug)]
+struct Block {
+    offset: u64,
+    size: u64,
+    free: bool,
+    // Physical neighbours in the memory range.
+    prev: Option<BlockId>,
+    next: Option<BlockId>,
+    // Neighbours in the free list of the bin (free blocks only).
+    prev_free: Option<BlockId>,
+    next_free: Option<BlockId>,
+}
+
+/// Allocated range returned by `Tlsf::allocate`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TlsfAllocation {
+    pub offset: u64,
+    pub size: u64,
+    block: BlockId,
+}
+
+/// Usage statistics of the managed range.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TlsfStats {
+    pub capacity: u64,
+    pub allocated: u64,
+    pub num_allocations: u32,
+    pub num_free_blocks: u32,
+    pub largest_free_block: u64,
+}
+
+#[derive(Debug)]
+pub struct Tlsf {
+    capacity: u64,
+    blocks: Vec<Block>,
+    unused_blocks: Vec<BlockId>,
+    fl_bitmap: u64,
+    sl_bitmaps: [u32; FL_COUNT],
+    bins: [[Option<BlockId>; SL_COUNT]; FL_COUNT],
+    allocated: u64,
+    num_allocations: u32,
+}
+
+fn log2(value: u64) -> u32 {
+    63 - value.leading_zeros()
+}
+
+/// Bin containing blocks of the given size.
+fn mapping(size: u64) -> (usize, usize) {
+    if size < SMALL_SIZE {
+        (0, size as usize)
+    } else {
+        let fl = log2(size);
+        let sl = (size >> (fl - SL_LOG2)) ^ SMALL_SIZE;
+        ((fl - SL_LOG2 + 1) as usize, sl as usize)
+    }
+}
+
+/// First bin only containing blocks of at least the given size.
+fn mapping_search(size: u64) -> (usize, usize) {
+    if size < SMALL_SIZE {
+        mapping(size)
+    } else {
+        let round = (1 << (log2(size) - SL_LOG2)) - 1;
+        mapping(size.saturating_add(round))
+    }
+}
+
+fn align_up(value: u64, alignment: u64) -> u64 {
+    (value + alignment - 1) & !(alignment - 1)
+}
+
+/// Blocks in the free list of a bin.
+struct FreeList<'a> {
+    blocks: &'a [Block],
+    next: Option<BlockId>,
+}
+
+impl<'a> Iterator for FreeList<'a> {
+    type Item = BlockId;
+
+    fn next(&mut self) -> Option<BlockId> {
+        let id = self.next?;
+        self.next = self.blocks[id].next_free;
+        Some(id)
+    }
+}
+
+impl Tlsf {
+    pub fn new(capacity: u64) -> Self {
+        let mut tlsf = Tlsf {
+            capacity,
+            blocks: Vec::new(),
+            unused_blocks: Vec::new(),
+            fl_bitmap: 0,
+            sl_bitmaps: [0; FL_COUNT],
+            bins: [[None; SL_COUNT]; FL_COUNT],
+            allocated: 0,
+            num_allocations: 0,
+        };
+
+        if capacity > 0 {
+            let block = tlsf.new_block(Block {
+                offset: 0,
+                size: capacity,
+                free: true,
+                prev: None,
+                next: None,
+                prev_free: None,
+                next_free: None,
+            });
+            tlsf.insert_free(block);
+        }
+
+        tlsf
+    }
+
+    fn new_block(&mut self, block: Block) -> BlockId {
+        match self.unused_blocks.pop() {
+            Some(id) => {
+                self.blocks[id] = block;
+                id
+            }
+            None => {
+                self.blocks.push(block);
+                self.blocks.len() - 1
+            }
+        }
+    }
+
+    fn insert_free(&mut self, id: BlockId) {
+        let (fl, sl) = mapping(self.blocks[id].size);
+        let head = self.bins[fl][sl];
+        {
+            let block = &mut self.blocks[id];
+            block.free = true;
+            block.prev_free = None;
+            block.next_free = head;
+        }
+        if let Some(head) = head {
+            self.blocks[head].prev_free = Some(id);
+        }
+        self.bins[fl][sl] = Some(id);
+        self.fl_bitmap |= 1 << fl;
+        self.sl_bitmaps[fl] |= 1 << sl;
+    }
+
+    fn remove_free(&mut self, id: BlockId) {
+        let Block {
+            size,
+            prev_free,
+            next_free,
+            ..
+        } = self.blocks[id];
+        let (fl, sl) = mapping(size);
+
+        if let Some(prev) = prev_free {
+            self.blocks[prev].next_free = next_free;
+        } else {
+            self.bins[fl][sl] = next_free;
+            if next_free.is_none() {
+                self.sl_bitmaps[fl] &= !(1 << sl);
+                if self.sl_bitmaps[fl] == 0 {
+                    self.fl_bitmap &= !(1 << fl);
+                }
+            }
+        }
+        if let Some(next) = next_free {
+            self.blocks[next].prev_free = prev_free;
+        }
+        self.blocks[id].free = false;
+    }
+
+    /// Find a free block of at least `size` bytes.
+    fn find_free(&self, size: u64) -> Option<BlockId> {
+        let (fl, sl) = mapping_search(size);
+        if fl >= FL_COUNT {
+            return None;
+        }
+
+        let sl_map = self.sl_bitmaps[fl] & (!0u32 << sl);
+        let (fl, sl) = if sl_map != 0 {
+            (fl, sl_map.trailing_zeros() as usize)
+        } else {
+            let fl_map = self.fl_bitmap & (!0u64).checked_shl(fl as u32 + 1).unwrap_or(0);
+            if fl_map == 0 {
+                return None;
+            }
+            let fl = fl_map.trailing_zeros() as usize;
+            (fl, self.sl_bitmaps[fl].trailing_zeros() as usize)
+        };
+
+        self.bins[fl][sl]
+    }
+
+    /// Any free block of at least `size` bytes after aligning its offset.
+    fn find_aligned_fit(&self, size: u64, alignment: u64) -> Option<BlockId> {
+        let (fl, sl) = mapping(size);
+        (fl..FL_COUNT)
+            .flat_map(|fl| (0..SL_COUNT).map(move |sl| (fl, sl)))
+            .skip_while(|&bin| bin < (fl, sl))
+            .filter(|&(fl, sl)| self.sl_bitmaps[fl] & (1 << sl) != 0)
+            .flat_map(|(fl, sl)| FreeList {
+                blocks: &self.blocks,
+                next: self.bins[fl][sl],
+            })
+            .find(|&id| {
+                let block = &self.blocks[id];
+                let padding = align_up(block.offset, alignment) - block.offset;
+                block.size >= size + padding
+            })
+    }
+
+    /// Split off the memory after `size` bytes as new free block.
+    fn split(&mut self, id: BlockId, size: u64) {
+        let block = self.blocks[id];
+        if block.size == size {
+            return;
+        }
+
+        let remainder = self.new_block(Block {
+            offset: block.offset + size,
+            size: block.size - size,
+            free: true,
+            prev: Some(id),
+            next: block.next,
+            prev_free: None,
+            next_free: None,
+        });
+        if let Some(next) = block.next {
+            self.blocks[next].prev = Some(remainder);
+        }
+        self.blocks[id].size = size;
+        self.blocks[id].next = Some(remainder);
+        self.insert_free(remainder);
+    }
+
+    /// Allocate `size` bytes with the offset aligned to `alignment` (power of two).
+    ///
+    /// Returns `None` if no free block is large enough.
+    pub fn allocate(&mut self, size: u64, alignment: u64) -> Option<TlsfAllocation> {
+        assert!(size > 0, "empty allocation");
+        assert!(
+            alignment.is_power_of_two(),
+            "alignment must be a power of two"
+        );
+
+        // Worst case padding required to align any free block, falls back to checking
+        // the actual padding of each free block if none is large enough.
+        let id = size
+            .checked_add(alignment - 1)
+            .and_then(|padded| self.find_free(padded))
+            .or_else(|| self.find_aligned_fit(size, alignment))?;
+        self.remove_free(id);
+
+        let padding = align_up(self.blocks[id].offset, alignment) - self.blocks[id].offset;
+        let id = if padding > 0 {
+            // Leading padding stays free as own block, the previous physical
+            // block is allocated as free blocks are always merged.
+            self.split(id, padding);
+            let aligned = self.blocks[id].next.unwrap();
+            self.remove_free(aligned);
+            self.insert_free(id);
+            aligned
+        } else {
+            id
+        };
+
+        self.split(id, size);
+
+        self.allocated += size;
+        self.num_allocations += 1;
+        Some(TlsfAllocation {
+            offset: self.blocks[id].offset,
+            size,
+            block: id,
+        })
+    }
+
+    /// Release an allocation, merging with free neighbours.
+    pub fn free(&mut self, allocation: TlsfAllocation) {
+        let mut id = allocation.block;
+        {
+            let block = &self.blocks[id];
+            assert!(
+                !block.free && block.offset == allocation.offset && block.size == allocation.size,
+                "invalid or double free of {:?}",
+                allocation
+            );
+        }
+        self.allocated -= allocation.size;
+        self.num_allocations -= 1;
+
+        if let Some(prev) = self.blocks[id].prev {
+            if self.blocks[prev].free {
+                self.remove_free(prev);
+                self.merge(prev, id);
+                id = prev;
+            }
+        }
+        if let Some(next) = self.blocks[id].next {
+            if self.blocks[next].free {
+                self.remove_free(next);
+                self.merge(id, next);
+            }
+        }
+
+        self.insert_free(id);
+    }
+
+    /// Merge `next` into its physical predecessor `id`.
+    fn merge(&mut self, id: BlockId, next: BlockId) {
+        let Block {
+            size,
+            next: next_next,
+            ..
+        } = self.blocks[next];
+        self.blocks[id].size += size;
+        self.blocks[id].next = next_next;
+        if let Some(next_next) = next_next {
+            self.blocks[next_next].prev = Some(id);
+        }
+        self.unused_blocks.push(next);
+    }
+
+    pub fn stats(&self) -> TlsfStats {
+        // Unused blocks are never marked as free.
+        let (num_free_blocks, largest_free_block) = self
+            .blocks
+            .iter()
+            .filter(|block| block.free)
+            .fold((0, 0), |(num, largest), block| {
+                (num + 1, largest.max(block.size))
+            });
+
+        TlsfStats {
+            capacity: self.capacity,
+            allocated: self.allocated,
+            num_allocations: self.num_allocations,
+            num_free_blocks,
+            largest_free_block,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocate_and_free() {
+        let mut tlsf = Tlsf::new(1024);
+        let a = tlsf.allocate(100, 1).unwrap();
+        let b = tlsf.allocate(100, 1).unwrap();
+        assert_eq!((a.offset, b.offset), (0, 100));
+
+        let stats = tlsf.stats();
+        assert_eq!((stats.allocated, stats.num_allocations), (200, 2));
+        assert_eq!((stats.num_free_blocks, stats.largest_free_block), (1, 824));
+
+        tlsf.free(a);
+        assert_eq!(tlsf.stats().num_free_blocks, 2);
+        tlsf.free(b);
+        assert_eq!(
+            tlsf.stats(),
+            TlsfStats {
+                capacity: 1024,
+                allocated: 0,
+                num_allocations: 0,
+                num_free_blocks: 1,
+                largest_free_block: 1024,
+            }
+        );
+    }
+
+    #[test]
+    fn exhaustion() {
+        let mut tlsf = Tlsf::new(1024);
+        assert!(tlsf.allocate(1025, 1).is_none());
+        let all = tlsf.allocate(1024, 1).unwrap();
+        assert!(tlsf.allocate(1, 1).is_none());
+        tlsf.free(all);
+        assert!(tlsf.allocate(1024, 1).is_some());
+        assert!(Tlsf::new(0).allocate(1, 1).is_none());
+    }
+
+    #[test]
+    fn merge_free_blocks() {
+        let mut tlsf = Tlsf::new(1024);
+        let a = tlsf.allocate(256, 1).unwrap();
+        let b = tlsf.allocate(256, 1).unwrap();
+        let c = tlsf.allocate(256, 1).unwrap();
+
+        // Merge with the next block.
+        tlsf.free(c);
+        assert_eq!(tlsf.stats().num_free_blocks, 1);
+        assert_eq!(tlsf.stats().largest_free_block, 512);
+
+        // Isolated block.
+        tlsf.free(a);
+        assert_eq!(tlsf.stats().num_free_blocks, 2);
+
+        // Merge with the previous and the next block.
+        tlsf.free(b);
+        assert_eq!(tlsf.stats().num_free_blocks, 1);
+        assert_eq!(tlsf.stats().largest_free_block, 1024);
+
+        let a = tlsf.allocate(512, 1).unwrap();
+        let b = tlsf.allocate(512, 1).unwrap();
+        tlsf.free(a);
+        // Merge with the previous block only.
+        tlsf.free(b);
+        assert_eq!(tlsf.stats().num_free_blocks, 1);
+        assert_eq!(tlsf.stats().largest_free_block, 1024);
+    }
+
+    #[test]
+    fn alignment_padding() {
+        let mut tlsf = Tlsf::new(1024);
+        let a = tlsf.allocate(10, 1).unwrap();
+        let b = tlsf.allocate(64, 256).unwrap();
+        assert_eq!(b.offset, 256);
+
+        // Padding in front of the aligned block stays free.
+        let stats = tlsf.stats();
+        assert_eq!((stats.allocated, stats.num_free_blocks), (74, 2));
+        assert_eq!(stats.largest_free_block, 1024 - 256 - 64);
+
+        tlsf.free(b);
+        let stats = tlsf.stats();
+        assert_eq!((stats.num_free_blocks, stats.largest_free_block), (1, 1014));
+        tlsf.free(a);
+        assert_eq!(tlsf.stats().largest_free_block, 1024);
+    }
+
+    #[test]
+    fn exact_aligned_fit() {
+        let mut tlsf = Tlsf::new(512);
+        let _a = tlsf.allocate(256, 1).unwrap();
+        // Only fits without any padding, larger than the worst case search.
+        let b = tlsf.allocate(256, 256).unwrap();
+        assert_eq!(b.offset, 256);
+    }
+
+    #[test]
+    fn random_allocations_never_overlap() {
+        const CAPACITY: u64 = 1 << 20;
+
+        // xorshift64
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut random = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        let mut tlsf = Tlsf::new(CAPACITY);
+        let mut live = Vec::<TlsfAllocation>::new();
+        for _ in 0..5000 {
+            if random() % 3 == 0 && !live.is_empty() {
+                let idx = (random() % live.len() as u64) as usize;
+                tlsf.free(live.swap_remove(idx));
+            } else {
+                let size = 1 + random() % 16384;
+                let alignment = 1 << (random() % 12);
+                if let Some(allocation) = tlsf.allocate(size, alignment) {
+                    assert_eq!(allocation.offset % alignment, 0);
+                    assert!(allocation.offset + allocation.size <= CAPACITY);
+                    live.push(allocation);
+                }
+            }
+
+            let mut ranges = live
+                .iter()
+                .map(|allocation| (allocation.offset, allocation.offset + allocation.size))
+                .collect::<Vec<_>>();
+            ranges.sort();
+            for pair in ranges.windows(2) {
+                assert!(pair[0].1 <= pair[1].0, "overlapping {:?}", pair);
+            }
+
+            let stats = tlsf.stats();
+            assert_eq!(stats.allocated, live.iter().map(|a| a.size).sum::<u64>());
+            assert_eq!(stats.num_allocations as usize, live.len());
+        }
+
+        for allocation in live {
+            tlsf.free(allocation);
+        }
+        let stats = tlsf.stats();
+        assert_eq!(
+            (stats.num_free_blocks, stats.largest_free_block),
+            (1, CAPACITY)
+        );
+    }
+}
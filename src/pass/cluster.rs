@@ -0,0 +1,139 @@
+//! Clustered light assignment pass
+
+use backend::*;
+use engine::Engine;
+use pass::geometry::{FAR_PLANE, NEAR_PLANE};
+use pass::lighting::LightData;
+use std::mem;
+
+// Cluster grid layout.
+//
+// Must match with `cluster.hlsl`.
+pub const CLUSTER_TILE_SIZE: u32 = 64;
+pub const CLUSTER_SLICES: u32 = 16;
+/// Upper bound of point and spot lights per cluster.
+pub const MAX_CLUSTER_LIGHTS: u32 = 128;
+
+// Clusters per compute group.
+//
+// Must match with the number of threads specified in the shader.
+pub const CLUSTER_THREADS: u32 = 64;
+
+/// Froxel grid of the rendered region.
+///
+/// Must match with `ClusterData` in `cluster.hlsl`.
+// #[repr(hlsl)]
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ClusterData {
+    /// Clusters along x, y and depth.
+    pub grid: [u32; 3],
+    /// `CLUSTER_SLICES / log(far / near)`
+    pub depth_scale: f32,
+    /// Rendered region, starting at the origin.
+    pub extent: [u32; 2],
+    /// `-log(near) * depth_scale`
+    pub depth_bias: f32,
+    /// Output the number of lights per cluster instead of the lighting.
+    pub debug_heatmap: u32,
+}
+
+impl ClusterData {
+    /// Clusters covering a rendered region of size `extent`.
+    pub fn new(extent: [u32; 2], debug_heatmap: bool) -> Self {
+        let tiles = |size: u32| (size + CLUSTER_TILE_SIZE - 1) / CLUSTER_TILE_SIZE;
+        let depth_scale = CLUSTER_SLICES as f32 / (FAR_PLANE / NEAR_PLANE).ln();
+        ClusterData {
+            grid: [tiles(extent[0]), tiles(extent[1]), CLUSTER_SLICES],
+            depth_scale,
+            extent,
+            depth_bias: -NEAR_PLANE.ln() * depth_scale,
+            debug_heatmap: debug_heatmap as _,
+        }
+    }
+
+    pub fn num_clusters(&self) -> u32 {
+        self.grid[0] * self.grid[1] * self.grid[2]
+    }
+}
+
+pub struct Clustering<B: Backend> {
+    pub signature: B::RootSignature,
+    pub pipeline: B::PipelineState,
+}
+
+impl<B: Backend> Clustering<B> {
+    pub fn new(engine: &Engine<B>) -> Result<Self, EngineError> {
+        let cs_shader = engine.load_shader(
+            "clustering_cs",
+            "shaders/clustering.hlsl",
+            "cs_cluster_lights\0",
+            "cs_5_1\0",
+            &[],
+        )?;
+
+        // * Point, directional, spot and area lights
+        let table_data_light = [TableRange {
+            ty: RangeType::Srv,
+            count: 4,
+            base_register: 0,
+            space: 4,
+        }];
+
+        // * Cluster grid
+        // * Cluster light indices
+        let table_data_clusters = [TableRange {
+            ty: RangeType::Uav,
+            count: 2,
+            base_register: 0,
+            space: 6,
+        }];
+
+        let parameters = [
+            // View data
+            RootParameter::Cbv {
+                register: 0,
+                space: 0,
+                visibility: ShaderVisibility::All,
+            },
+            // Light data
+            RootParameter::Constants {
+                register: 0,
+                space: 4,
+                num_values: mem::size_of::<LightData>() as u32 / 4,
+                visibility: ShaderVisibility::All,
+            },
+            RootParameter::Table {
+                ranges: &table_data_light,
+                visibility: ShaderVisibility::All,
+            },
+            // Cluster data
+            RootParameter::Constants {
+                register: 0,
+                space: 6,
+                num_values: mem::size_of::<ClusterData>() as u32 / 4,
+                visibility: ShaderVisibility::All,
+            },
+            // Cluster UAVs
+            RootParameter::Table {
+                ranges: &table_data_clusters,
+                visibility: ShaderVisibility::All,
+            },
+        ];
+
+        let signature = engine.device.create_root_signature(&RootSignatureDesc {
+            parameters: &parameters,
+            static_samplers: &[],
+            allow_input_layout: false,
+        })?;
+
+        let pipeline = engine
+            .device
+            .create_compute_pipeline(&signature, &cs_shader)?;
+
+        Ok(Clustering {
+            signature,
+            pipeline,
+        })
+    }
+}
@@ -1,131 +1,199 @@
 //! Geometry buffer creation pass
 
+use backend::*;
+use cgmath;
 use engine::Engine;
 use pass;
+use scene::geometry::{DrawData, NUM_VERTEX_STREAMS};
+use scene::Camera;
+use std::mem;
 
-use std::ptr;
-use winapi::shared::dxgiformat::*;
-use winapi::shared::minwindef::TRUE;
-use winapi::um::d3d12::*;
-use wio::com::ComPtr;
+/// Distance of the near and far plane of the view frustum.
+pub const NEAR_PLANE: f32 = 1.0;
+pub const FAR_PLANE: f32 = 8192.0;
 
-pub struct Geometry {
-    pub signature: ComPtr<ID3D12RootSignature>,
-    pub pipeline: ComPtr<ID3D12PipelineState>,
+/// Primitive and instance id of geometry buffer texels not covered by any triangle.
+///
+/// Must match with `INVALID_ID` in `resources_triangle.hlsl`.
+pub const INVALID_ID: u16 = 0xFFFF;
+
+/// View constant buffer data.
+///
+/// Must match with `ViewData` in `resources.hlsl`.
+// #[repr(hlsl)]
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct ViewData {
+    pub view: [[f32; 4]; 4],
+    pub proj: [[f32; 4]; 4],
+    pub position: [f32; 4],
+    pub _alignment: [f32; 28],
+}
+
+impl ViewData {
+    pub fn new(camera: &Camera, width: u32, height: u32) -> Self {
+        let proj = {
+            let aspect_ratio = width as f32 / height as f32;
+            let mut perspective =
+                cgmath::perspective(cgmath::Deg(60.0), aspect_ratio, NEAR_PLANE, FAR_PLANE);
+            perspective.w.z /= 2.0; // OpenGL NDC -> DX12 NDC
+            perspective.into()
+        };
+
+        ViewData {
+            view: camera.view(),
+            proj,
+            position: [camera.position.x, camera.position.y, camera.position.z, 1.0],
+            _alignment: [0.0; 28],
+        }
+    }
 }
 
-impl Geometry {
-    pub fn new(engine: &Engine) -> Self {
+pub struct Geometry<B: Backend> {
+    pub signature: B::RootSignature,
+    pub pipeline: B::PipelineState,
+}
+
+impl<B: Backend> Geometry<B> {
+    /// Create the pass rendering into targets with `samples` samples per pixel.
+    pub fn new(engine: &Engine<B>, samples: u32) -> Result<Self, EngineError> {
         let vs_shader = engine
             .load_shader(
                 "geometry_vs",
                 "shaders/geometry.hlsl",
                 "vs_main\0",
                 "vs_5_1\0",
-            )
-            .unwrap();
+                &[],
+            )?;
         let ps_shader = engine
             .load_shader(
                 "geometry_ps",
                 "shaders/geometry.hlsl",
                 "ps_main\0",
                 "ps_5_1\0",
-            )
-            .unwrap();
+                &[],
+            )?;
 
-        // Vertex and Index buffer SRVs
+        // Mesh SRVs, see `Mesh::srvs`
         let table_data = [
-            D3D12_DESCRIPTOR_RANGE {
-                RangeType: D3D12_DESCRIPTOR_RANGE_TYPE_SRV,
-                NumDescriptors: 2,
-                BaseShaderRegister: 0,
-                RegisterSpace: 1,
-                OffsetInDescriptorsFromTableStart: D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND,
+            TableRange {
+                ty: RangeType::Srv,
+                count: 3,
+                base_register: 0,
+                space: 1,
+            },
+            TableRange {
+                ty: RangeType::Srv,
+                count: NUM_VERTEX_STREAMS as _,
+                base_register: 4,
+                space: 1,
             },
         ];
 
+        // Instance data SRV
+        let table_instances = [TableRange {
+            ty: RangeType::Srv,
+            count: 1,
+            base_register: 3,
+            space: 1,
+        }];
+
+        let table_materials = [TableRange {
+            ty: RangeType::Srv,
+            count: 1,
+            base_register: 0,
+            space: 5,
+        }];
+
+        let table_textures = [TableRange {
+            ty: RangeType::Srv,
+            count: UNBOUNDED_RANGE,
+            base_register: 0,
+            space: 2,
+        }];
+
         let parameters = [
             // View data
-            pass::gen_root_descriptor_param(
-                D3D12_ROOT_PARAMETER_TYPE_CBV,
-                D3D12_SHADER_VISIBILITY_ALL,
-                D3D12_ROOT_DESCRIPTOR {
-                    ShaderRegister: 0,
-                    RegisterSpace: 0,
-                },
-            ),
-            // Vertex and Index SRVs
-            pass::gen_root_table_param(
-                D3D12_SHADER_VISIBILITY_PIXEL,
-                D3D12_ROOT_DESCRIPTOR_TABLE {
-                    NumDescriptorRanges: table_data.len() as _,
-                    pDescriptorRanges: table_data.as_ptr(),
-                },
-            ),
-            // Base instance root constants
-            pass::gen_root_constants_param(
-                D3D12_SHADER_VISIBILITY_PIXEL,
-                D3D12_ROOT_CONSTANTS {
-                    ShaderRegister: 0,
-                    RegisterSpace: 2,
-                    Num32BitValues: 2,
-                },
-            ),
-            // Draw ID
-            pass::gen_root_constants_param(
-                D3D12_SHADER_VISIBILITY_PIXEL,
-                D3D12_ROOT_CONSTANTS {
-                    ShaderRegister: 1,
-                    RegisterSpace: 2,
-                    Num32BitValues: 1,
-                },
-            ),
+            RootParameter::Cbv {
+                register: 0,
+                space: 0,
+                visibility: ShaderVisibility::All,
+            },
+            // Mesh SRVs
+            RootParameter::Table {
+                ranges: &table_data,
+                visibility: ShaderVisibility::Pixel,
+            },
+            // Draw data root constants
+            RootParameter::Constants {
+                register: 0,
+                space: 2,
+                num_values: mem::size_of::<DrawData>() as u32 / 4,
+                visibility: ShaderVisibility::Pixel,
+            },
+            // Instance ID
+            RootParameter::Constants {
+                register: 1,
+                space: 2,
+                num_values: 1,
+                visibility: ShaderVisibility::All,
+            },
+            // Instance data SRV
+            RootParameter::Table {
+                ranges: &table_instances,
+                visibility: ShaderVisibility::All,
+            },
+            // Material data SRV, alpha testing only
+            RootParameter::Table {
+                ranges: &table_materials,
+                visibility: ShaderVisibility::Pixel,
+            },
+            // Material textures
+            RootParameter::Table {
+                ranges: &table_textures,
+                visibility: ShaderVisibility::Pixel,
+            },
         ];
 
+        let static_samplers = [StaticSampler {
+            filter: Filter::Linear,
+            address: AddressMode::Wrap,
+            register: 0,
+            space: 2,
+            visibility: ShaderVisibility::Pixel,
+        }];
+
         let signature = engine
-            .create_root_signature(&D3D12_ROOT_SIGNATURE_DESC {
-                NumParameters: parameters.len() as _,
-                pParameters: parameters.as_ptr(),
-                NumStaticSamplers: 0,
-                pStaticSamplers: ptr::null(),
-                Flags: D3D12_ROOT_SIGNATURE_FLAG_ALLOW_INPUT_ASSEMBLER_INPUT_LAYOUT,
-            })
-            .unwrap();
-
-        let input_layout = [
-            D3D12_INPUT_ELEMENT_DESC {
-                SemanticName: b"Attr\0" as *const _ as *const _,
-                SemanticIndex: 0,
-                Format: DXGI_FORMAT_R32G32B32_FLOAT,
-                InputSlot: 0,
-                AlignedByteOffset: D3D12_APPEND_ALIGNED_ELEMENT,
-                InputSlotClass: D3D12_INPUT_CLASSIFICATION_PER_VERTEX_DATA,
-                InstanceDataStepRate: 0,
-            },
-        ];
+            .device
+            .create_root_signature(&RootSignatureDesc {
+                parameters: &parameters,
+                static_samplers: &static_samplers,
+                allow_input_layout: true,
+            })?;
 
-        let mut pso_desc = D3D12_GRAPHICS_PIPELINE_STATE_DESC {
-            pRootSignature: signature.as_raw(),
-            VS: pass::unpack_shader_bc(&vs_shader),
-            PS: pass::unpack_shader_bc(&ps_shader),
-            PrimitiveTopologyType: D3D12_PRIMITIVE_TOPOLOGY_TYPE_TRIANGLE,
-            NumRenderTargets: 1,
-            InputLayout: D3D12_INPUT_LAYOUT_DESC {
-                pInputElementDescs: input_layout.as_ptr(),
-                NumElements: input_layout.len() as _,
-            },
-            DSVFormat: pass::DS_FORMAT,
-            ..pass::DEFAULT_PIPELINE_STATE_DESC
-        };
-        pso_desc.RTVFormats[0] = DXGI_FORMAT_R16G16B16A16_UINT;
-        pso_desc.DepthStencilState.DepthEnable = TRUE;
-        pso_desc.DepthStencilState.DepthFunc = D3D12_COMPARISON_FUNC_LESS;
+        let input_layout = [InputElement {
+            semantic: "Attr",
+            index: 0,
+            format: Format::R32G32B32Float,
+            slot: 0,
+        }];
 
-        let pipeline = engine.create_graphics_pipeline(&pso_desc);
+        let pipeline = engine
+            .device
+            .create_graphics_pipeline(&GraphicsPipelineDesc {
+                signature: &signature,
+                vs: &vs_shader,
+                ps: &ps_shader,
+                input_layout: &input_layout,
+                render_targets: &[Format::R16G16B16A16Uint],
+                depth_format: pass::DS_FORMAT,
+                depth_test: Some(CompareFunc::Less),
+                samples,
+            })?;
 
-        Geometry {
+        Ok(Geometry {
             signature,
             pipeline,
-        }
+        })
     }
 }
@@ -1,8 +1,8 @@
+use backend::*;
 use engine::Engine;
-use pass;
+use pass::cluster::ClusterData;
+use scene::geometry::NUM_VERTEX_STREAMS;
 use std::mem;
-use winapi::um::d3d12::*;
-use wio::com::ComPtr;
 
 // Size of a compute tile.
 //
@@ -10,172 +10,314 @@ use wio::com::ComPtr;
 pub const TILE_THREADS_X: u32 = 16;
 pub const TILE_THREADS_Y: u32 = 16;
 
+/// Number of lights of each type.
 // #[repr(hlsl)]
 #[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
 pub struct LightData {
     pub num_point_lights: u32,
+    pub num_directional_lights: u32,
+    pub num_spot_lights: u32,
+    pub num_area_lights: u32,
 }
 
+/// Region of the render targets to shade.
 // #[repr(hlsl)]
 #[repr(C)]
+pub struct TargetData {
+    pub extent: [u32; 2],
+}
+
+// #[repr(hlsl)]
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
 pub struct PointLight {
     pub position: [f32; 3],
+    /// Luminous intensity in candela.
+    pub intensity: f32,
+    /// Linear RGB color.
+    pub color: [f32; 3],
+    /// Distance at which the attenuation window reaches zero.
+    pub range: f32,
+    /// First of the cube face shadow views, `INVALID_SHADOW` without shadows.
+    pub shadow: u32,
+}
+
+// #[repr(hlsl)]
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct DirectionalLight {
+    /// Normalized direction pointing away from the light.
+    pub direction: [f32; 3],
+    /// Illuminance in lux.
+    pub illuminance: f32,
+    pub color: [f32; 3],
+    /// First of the cascade shadow views, `INVALID_SHADOW` without shadows.
+    pub shadow: u32,
+}
+
+// #[repr(hlsl)]
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct SpotLight {
+    pub position: [f32; 3],
+    /// Luminous intensity in candela.
     pub intensity: f32,
+    pub color: [f32; 3],
+    /// `1 / (cos(inner angle) - cos(outer angle))`
+    pub angle_scale: f32,
+    /// Normalized cone axis.
+    pub direction: [f32; 3],
+    /// `-cos(outer angle) * angle_scale`
+    pub angle_offset: f32,
+    /// Distance at which the attenuation window reaches zero.
+    pub range: f32,
+    /// Shadow view, `INVALID_SHADOW` without shadows.
+    pub shadow: u32,
+    pub _alignment: [f32; 2],
 }
 
-pub struct Lighting {
-    pub signature: ComPtr<ID3D12RootSignature>,
-    pub pipeline: ComPtr<ID3D12PipelineState>,
+// #[repr(hlsl)]
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct AreaLight {
+    /// Center of the light.
+    pub position: [f32; 3],
+    /// Luminance in cd/m², emitted from the front face.
+    pub luminance: f32,
+    pub color: [f32; 3],
+    /// `AREA_SHAPE_*`
+    pub shape: u32,
+    /// Half extent (rectangle) or radius (disk) along the local x axis.
+    pub axis_x: [f32; 3],
+    /// Half extent (rectangle) or radius (disk) along the local y axis.
+    pub axis_y: [f32; 3],
 }
 
-impl Lighting {
-    pub fn new(engine: &mut Engine) -> Self {
+pub struct Lighting<B: Backend> {
+    pub signature: B::RootSignature,
+    pub pipeline: B::PipelineState,
+}
+
+impl<B: Backend> Lighting<B> {
+    /// Create the pass reading a geometry buffer with `samples` samples per pixel.
+    pub fn new(engine: &mut Engine<B>, samples: u32) -> Result<Self, EngineError> {
         let cs_shader = engine
             .load_shader(
                 "lighting_cs",
                 "shaders/lighting.hlsl",
                 "cs_lighting\0",
                 "cs_5_1\0",
-            )
-            .unwrap();
+                &[("SAMPLES", &samples.to_string())],
+            )?;
 
         // Lighting buffer UAV
-        let table_data_uav = [
-            D3D12_DESCRIPTOR_RANGE {
-                RangeType: D3D12_DESCRIPTOR_RANGE_TYPE_UAV,
-                NumDescriptors: 1,
-                BaseShaderRegister: 0,
-                RegisterSpace: 3,
-                OffsetInDescriptorsFromTableStart: D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND,
-            },
-        ];
+        let table_data_uav = [TableRange {
+            ty: RangeType::Uav,
+            count: 1,
+            base_register: 0,
+            space: 3,
+        }];
 
-        let table_data_geometry = [
-            D3D12_DESCRIPTOR_RANGE {
-                RangeType: D3D12_DESCRIPTOR_RANGE_TYPE_SRV,
-                NumDescriptors: 1,
-                BaseShaderRegister: 1,
-                RegisterSpace: 3,
-                OffsetInDescriptorsFromTableStart: D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND,
-            },
-        ];
+        let table_data_geometry = [TableRange {
+            ty: RangeType::Srv,
+            count: 1,
+            base_register: 1,
+            space: 3,
+        }];
 
-        let table_data_textures = [
-            D3D12_DESCRIPTOR_RANGE {
-                RangeType: D3D12_DESCRIPTOR_RANGE_TYPE_SRV,
-                NumDescriptors: !0,
-                BaseShaderRegister: 0,
-                RegisterSpace: 2,
-                OffsetInDescriptorsFromTableStart: D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND,
-            },
-        ];
+        let table_data_textures = [TableRange {
+            ty: RangeType::Srv,
+            count: UNBOUNDED_RANGE,
+            base_register: 0,
+            space: 2,
+        }];
 
-        let table_data_light = [
-            D3D12_DESCRIPTOR_RANGE {
-                RangeType: D3D12_DESCRIPTOR_RANGE_TYPE_SRV,
-                NumDescriptors: 1,
-                BaseShaderRegister: 0,
-                RegisterSpace: 4,
-                OffsetInDescriptorsFromTableStart: D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND,
-            },
-        ];
+        // * Point, directional, spot and area lights
+        // * LTC table
+        // * Shadow views
+        let table_data_light = [TableRange {
+            ty: RangeType::Srv,
+            count: 6,
+            base_register: 0,
+            space: 4,
+        }];
 
         // * Index buffer
         // * Vertex position
-        // * Base Index and Vertex
-        let num_draw_buffers = 3;
+        // * Draw data
+        // * Vertex attribute streams (after the instance data register)
         let table_data_draw = [
-            D3D12_DESCRIPTOR_RANGE {
-                RangeType: D3D12_DESCRIPTOR_RANGE_TYPE_SRV,
-                NumDescriptors: num_draw_buffers,
-                BaseShaderRegister: 0,
-                RegisterSpace: 1,
-                OffsetInDescriptorsFromTableStart: D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND,
+            TableRange {
+                ty: RangeType::Srv,
+                count: 3,
+                base_register: 0,
+                space: 1,
+            },
+            TableRange {
+                ty: RangeType::Srv,
+                count: NUM_VERTEX_STREAMS as _,
+                base_register: 4,
+                space: 1,
             },
         ];
 
+        let table_data_instances = [TableRange {
+            ty: RangeType::Srv,
+            count: 1,
+            base_register: 3,
+            space: 1,
+        }];
+
+        let table_data_materials = [TableRange {
+            ty: RangeType::Srv,
+            count: 1,
+            base_register: 0,
+            space: 5,
+        }];
+
+        // * Cluster grid
+        // * Cluster light indices
+        let table_data_clusters = [TableRange {
+            ty: RangeType::Srv,
+            count: 2,
+            base_register: 0,
+            space: 6,
+        }];
+
+        // Shadow atlas
+        let table_data_shadows = [TableRange {
+            ty: RangeType::Srv,
+            count: 1,
+            base_register: 0,
+            space: 7,
+        }];
+
+        // * Specular cube map
+        // * BRDF lookup table
+        // * Environment data
+        let table_data_environment = [TableRange {
+            ty: RangeType::Srv,
+            count: 3,
+            base_register: 0,
+            space: 8,
+        }];
+
         let parameters = [
             // Lighting buffer UAV
-            pass::gen_root_table_param(
-                D3D12_SHADER_VISIBILITY_ALL,
-                D3D12_ROOT_DESCRIPTOR_TABLE {
-                    NumDescriptorRanges: table_data_uav.len() as _,
-                    pDescriptorRanges: table_data_uav.as_ptr(),
-                },
-            ),
-            pass::gen_root_table_param(
-                D3D12_SHADER_VISIBILITY_ALL,
-                D3D12_ROOT_DESCRIPTOR_TABLE {
-                    NumDescriptorRanges: table_data_geometry.len() as _,
-                    pDescriptorRanges: table_data_geometry.as_ptr(),
-                },
-            ),
-            pass::gen_root_table_param(
-                D3D12_SHADER_VISIBILITY_ALL,
-                D3D12_ROOT_DESCRIPTOR_TABLE {
-                    NumDescriptorRanges: table_data_textures.len() as _,
-                    pDescriptorRanges: table_data_textures.as_ptr(),
-                },
-            ),
-            pass::gen_root_table_param(
-                D3D12_SHADER_VISIBILITY_ALL,
-                D3D12_ROOT_DESCRIPTOR_TABLE {
-                    NumDescriptorRanges: table_data_draw.len() as _,
-                    pDescriptorRanges: table_data_draw.as_ptr(),
-                },
-            ),
+            RootParameter::Table {
+                ranges: &table_data_uav,
+                visibility: ShaderVisibility::All,
+            },
+            RootParameter::Table {
+                ranges: &table_data_geometry,
+                visibility: ShaderVisibility::All,
+            },
+            RootParameter::Table {
+                ranges: &table_data_textures,
+                visibility: ShaderVisibility::All,
+            },
+            RootParameter::Table {
+                ranges: &table_data_draw,
+                visibility: ShaderVisibility::All,
+            },
             // Light data
-            pass::gen_root_constants_param(
-                D3D12_SHADER_VISIBILITY_ALL,
-                D3D12_ROOT_CONSTANTS {
-                    ShaderRegister: 0,
-                    RegisterSpace: 4,
-                    Num32BitValues: mem::size_of::<LightData>() as u32 / 4,
-                },
-            ),
-            pass::gen_root_table_param(
-                D3D12_SHADER_VISIBILITY_ALL,
-                D3D12_ROOT_DESCRIPTOR_TABLE {
-                    NumDescriptorRanges: table_data_light.len() as _,
-                    pDescriptorRanges: table_data_light.as_ptr(),
-                },
-            ),
+            RootParameter::Constants {
+                register: 0,
+                space: 4,
+                num_values: mem::size_of::<LightData>() as u32 / 4,
+                visibility: ShaderVisibility::All,
+            },
+            RootParameter::Table {
+                ranges: &table_data_light,
+                visibility: ShaderVisibility::All,
+            },
+            // Instance data
+            RootParameter::Table {
+                ranges: &table_data_instances,
+                visibility: ShaderVisibility::All,
+            },
+            // Target data
+            RootParameter::Constants {
+                register: 0,
+                space: 3,
+                num_values: mem::size_of::<TargetData>() as u32 / 4,
+                visibility: ShaderVisibility::All,
+            },
+            // Material data
+            RootParameter::Table {
+                ranges: &table_data_materials,
+                visibility: ShaderVisibility::All,
+            },
+            // View data, for the texture coordinate gradients and cluster lookup
+            RootParameter::Cbv {
+                register: 0,
+                space: 0,
+                visibility: ShaderVisibility::All,
+            },
+            // Cluster data
+            RootParameter::Constants {
+                register: 0,
+                space: 6,
+                num_values: mem::size_of::<ClusterData>() as u32 / 4,
+                visibility: ShaderVisibility::All,
+            },
+            RootParameter::Table {
+                ranges: &table_data_clusters,
+                visibility: ShaderVisibility::All,
+            },
+            RootParameter::Table {
+                ranges: &table_data_shadows,
+                visibility: ShaderVisibility::All,
+            },
+            RootParameter::Table {
+                ranges: &table_data_environment,
+                visibility: ShaderVisibility::All,
+            },
         ];
 
         let static_samplers = [
-            // Clamp to border sampler for basic material texture sampling (albedo, roughness, ..).
-            D3D12_STATIC_SAMPLER_DESC {
-                Filter: D3D12_FILTER_MIN_MAG_MIP_LINEAR,
-                AddressU: D3D12_TEXTURE_ADDRESS_MODE_CLAMP,
-                AddressV: D3D12_TEXTURE_ADDRESS_MODE_CLAMP,
-                AddressW: D3D12_TEXTURE_ADDRESS_MODE_CLAMP,
-                MipLODBias: 0.0,
-                MaxAnisotropy: 0,
-                ComparisonFunc: D3D12_COMPARISON_FUNC_ALWAYS,
-                BorderColor: D3D12_STATIC_BORDER_COLOR_TRANSPARENT_BLACK,
-                MinLOD: 0.0,
-                MaxLOD: D3D12_FLOAT32_MAX,
-                ShaderRegister: 0,
-                RegisterSpace: 2,
-                ShaderVisibility: D3D12_SHADER_VISIBILITY_ALL,
+            // Repeating sampler for material texture sampling (albedo, roughness, ..).
+            StaticSampler {
+                filter: Filter::Linear,
+                address: AddressMode::Wrap,
+                register: 0,
+                space: 2,
+                visibility: ShaderVisibility::All,
+            },
+            // Depth comparison sampler for the shadow atlas (PCF).
+            StaticSampler {
+                filter: Filter::LinearCompare(CompareFunc::LessEqual),
+                address: AddressMode::Clamp,
+                register: 0,
+                space: 7,
+                visibility: ShaderVisibility::All,
+            },
+            // Trilinear sampler for the environment lighting.
+            StaticSampler {
+                filter: Filter::Linear,
+                address: AddressMode::Clamp,
+                register: 0,
+                space: 8,
+                visibility: ShaderVisibility::All,
             },
         ];
 
         let signature = engine
-            .create_root_signature(&D3D12_ROOT_SIGNATURE_DESC {
-                NumParameters: parameters.len() as _,
-                pParameters: parameters.as_ptr(),
-                NumStaticSamplers: static_samplers.len() as _,
-                pStaticSamplers: static_samplers.as_ptr(),
-                Flags: D3D12_ROOT_SIGNATURE_FLAG_NONE,
-            })
-            .unwrap();
-
-        let pipeline = engine.create_compute_pipeline(&signature, &cs_shader);
-
-        Lighting {
+            .device
+            .create_root_signature(&RootSignatureDesc {
+                parameters: &parameters,
+                static_samplers: &static_samplers,
+                allow_input_layout: false,
+            })?;
+
+        let pipeline = engine
+            .device
+            .create_compute_pipeline(&signature, &cs_shader)?;
+
+        Ok(Lighting {
             signature,
             pipeline,
-        }
+        })
     }
 }
@@ -1,312 +1,568 @@
 //! Rendering pipeline
 
+use backend::*;
+use descriptor::DescriptorRange;
 use engine::Engine;
+use graph::{Access, CompiledGraph, GraphBuilder, RenderGraph, ResourceHandle};
 use pass;
-use pass::geometry::Geometry;
+use pass::cluster::{ClusterData, Clustering, MAX_CLUSTER_LIGHTS};
+use pass::geometry::{Geometry, INVALID_ID};
 use pass::lighting::Lighting;
 use pass::postprocess::PostProcess;
-use std::{mem, ptr};
-use winapi::shared::dxgiformat::*;
-use winapi::shared::dxgitype::DXGI_SAMPLE_DESC;
-use winapi::um::d3d12::*;
-use wio::com::ComPtr;
+use pass::shadow::{ShadowMapping, SHADOW_ATLAS_SIZE};
+use std::{mem, slice};
 
 #[derive(Copy, Clone, Debug)]
 pub struct PipelineSettings {
+    /// Output size.
     pub width: u32,
     pub height: u32,
+    /// Samples per pixel of the geometry buffer and depth target, 1, 2, 4 or 8.
     pub samples: u32,
+    /// Scale of the internal render resolution relative to the output size, in `(0, 1]`.
+    ///
+    /// Targets are allocated for the full output size, lower scales render into the
+    /// top-left region which is upsampled by the display mapping.
+    pub render_scale: f32,
 }
 
-pub struct Pipeline {
-    pub geometry: Geometry,
-    pub lighting: Lighting,
-    pub post_process: PostProcess,
+impl PipelineSettings {
+    /// Size of the region rendered by the geometry and lighting passes.
+    pub fn render_size(&self) -> (u32, u32) {
+        let scale = |size: u32| ((size as f32 * self.render_scale).round() as u32).max(1);
+        (scale(self.width), scale(self.height))
+    }
 
-    ///
-    pub geometry_buffer: ComPtr<ID3D12Resource>,
-    pub geometry_rtv_uint: D3D12_CPU_DESCRIPTOR_HANDLE,
-    pub geometry_srv_uint: D3D12_GPU_DESCRIPTOR_HANDLE,
-    ///
-    pub lighting_buffer: ComPtr<ID3D12Resource>,
-    pub lighting_srv: D3D12_GPU_DESCRIPTOR_HANDLE,
-    pub lighting_uav: D3D12_GPU_DESCRIPTOR_HANDLE,
+    fn validate(&self) -> Result<(), EngineError> {
+        let reason = if ![1, 2, 4, 8].contains(&self.samples) {
+            format!("unsupported sample count {}", self.samples)
+        } else if !(self.render_scale > 0.0 && self.render_scale <= 1.0) {
+            format!("render scale {} outside of (0, 1]", self.render_scale)
+        } else {
+            return Ok(());
+        };
+
+        Err(EngineError::new(
+            "create_pipeline",
+            format!("{:?}", self),
+            ErrorKind::InvalidArgument(reason),
+        ))
+    }
+}
+
+/// Passes of the frame graph.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Pass {
+    ShadowMaps,
+    Geometry,
+    LightClustering,
+    Lighting,
+    ClusterReadback,
+    DisplayMap,
+}
+
+pub struct Pipeline<B: Backend> {
+    pub shadow_mapping: ShadowMapping<B>,
+    pub geometry: Geometry<B>,
+    pub clustering: Clustering<B>,
+    pub lighting: Lighting<B>,
+    pub post_process: PostProcess<B>,
 
-    pub depth_target: ComPtr<ID3D12Resource>,
-    pub dsv: D3D12_CPU_DESCRIPTOR_HANDLE,
+    pub graph: RenderGraph<B, Pass>,
+    pub backbuffer: ResourceHandle,
 
-    depth_heap: ComPtr<ID3D12DescriptorHeap>,
-    rtv_heap: ComPtr<ID3D12DescriptorHeap>,
+    /// Shadow maps of all lights, see `pass::shadow`
+    pub shadow_atlas: ResourceHandle,
+    pub shadow_dsv: CpuDescriptor,
+    pub shadow_srv: GpuDescriptor,
+    /// Visibility buffer of the geometry pass, see `reference::raster`
+    pub geometry_buffer: ResourceHandle,
+    pub geometry_rtv_uint: CpuDescriptor,
+    pub geometry_srv_uint: GpuDescriptor,
+    /// Light counts and light indices of the clusters
+    pub cluster_grid: ResourceHandle,
+    pub cluster_lights: ResourceHandle,
+    pub cluster_uavs: GpuDescriptor,
+    pub cluster_srvs: GpuDescriptor,
+    /// Copy of the cluster grid of the last frame, see `max_cluster_lights`
+    pub cluster_readback: ResourceHandle,
+    pub cluster_readback_buffer: B::Resource,
+    /// Radiance of the lighting pass, upsampled by the display mapping
+    pub lighting_buffer: ResourceHandle,
+    pub lighting_srv: GpuDescriptor,
+    pub lighting_uav: GpuDescriptor,
 
-    srv_uav_start_cpu: D3D12_CPU_DESCRIPTOR_HANDLE,
-    srv_uav_start_gpu: D3D12_GPU_DESCRIPTOR_HANDLE,
-    srv_uav_num: usize,
+    pub depth_target: ResourceHandle,
+    pub dsv: CpuDescriptor,
+
+    rtvs: DescriptorRange,
+    dsvs: DescriptorRange,
+    srv_uavs: DescriptorRange,
+
+    settings: PipelineSettings,
 }
 
-impl Pipeline {
-    pub fn new(engine: &mut Engine, settings: PipelineSettings) -> Self {
-        // TODO: support multisampling
-        assert_eq!(settings.samples, 1);
-
-        // Create depth target
-        let depth_desc = D3D12_RESOURCE_DESC {
-            Dimension: D3D12_RESOURCE_DIMENSION_TEXTURE2D,
-            Alignment: D3D12_DEFAULT_RESOURCE_PLACEMENT_ALIGNMENT as _,
-            Width: settings.width as _,
-            Height: settings.height as _,
-            DepthOrArraySize: 1,
-            Format: pass::DS_FORMAT,
-            MipLevels: 1,
-            SampleDesc: DXGI_SAMPLE_DESC {
-                Count: settings.samples,
-                Quality: 0,
-            },
-            Layout: D3D12_TEXTURE_LAYOUT_UNKNOWN,
-            Flags: D3D12_RESOURCE_FLAG_ALLOW_DEPTH_STENCIL,
-        };
-        let mut depth_clear_value = D3D12_CLEAR_VALUE {
-            Format: pass::DS_FORMAT,
-            ..unsafe { mem::zeroed() }
-        };
-        unsafe {
-            *depth_clear_value.u.DepthStencil_mut() = D3D12_DEPTH_STENCIL_VALUE {
-                Depth: 1.0,
-                Stencil: 0,
-            };
-        }
-        let depth_target = engine.create_committed_resource(
-            D3D12_HEAP_TYPE_DEFAULT,
-            &depth_desc,
-            D3D12_RESOURCE_STATE_DEPTH_WRITE,
-            Some(depth_clear_value),
-        );
+/// Transient resources of the frame graph.
+struct Targets {
+    backbuffer: ResourceHandle,
+    shadow_atlas: ResourceHandle,
+    geometry_buffer: ResourceHandle,
+    cluster_grid: ResourceHandle,
+    cluster_lights: ResourceHandle,
+    cluster_readback: ResourceHandle,
+    lighting_buffer: ResourceHandle,
+    depth_target: ResourceHandle,
+}
 
-        // Geometry buffer, RGBA16F
-        //
-        // Storing only triangle identification data along with barycentric coordinates.
-        //  * R: U16 for draw call number
-        //  * G: U16 for index (relative for the current draw)
-        //  * B: F16 Barycentric U [0,1]
-        //  * A: F16 Barycentric V [0,1]
-        let gbuffer_desc = D3D12_RESOURCE_DESC {
-            Dimension: D3D12_RESOURCE_DIMENSION_TEXTURE2D,
-            Alignment: D3D12_DEFAULT_RESOURCE_PLACEMENT_ALIGNMENT as _,
-            Width: settings.width as _,
-            Height: settings.height as _,
-            DepthOrArraySize: 1,
-            Format: DXGI_FORMAT_R16G16B16A16_TYPELESS,
-            MipLevels: 1,
-            SampleDesc: DXGI_SAMPLE_DESC {
-                Count: settings.samples,
-                Quality: 0,
-            },
-            Layout: D3D12_TEXTURE_LAYOUT_UNKNOWN,
-            Flags: D3D12_RESOURCE_FLAG_ALLOW_RENDER_TARGET,
-        };
-        let mut gbuffer_clear_value = D3D12_CLEAR_VALUE {
-            Format: DXGI_FORMAT_R16G16B16A16_FLOAT,
-            ..unsafe { mem::zeroed() }
-        };
-        unsafe {
-            *gbuffer_clear_value.u.Color_mut() = [0.0, 0.0, 0.0, 0.0];
-        }
-        let geometry_buffer = engine.create_committed_resource(
-            D3D12_HEAP_TYPE_DEFAULT,
-            &gbuffer_desc,
-            D3D12_RESOURCE_STATE_NON_PIXEL_SHADER_RESOURCE,
-            Some(gbuffer_clear_value),
-        );
+impl<B: Backend> Pipeline<B> {
+    pub fn new(engine: &mut Engine<B>, settings: PipelineSettings) -> Result<Self, EngineError> {
+        settings.validate()?;
 
-        // Lighting buffer, RGBA16F
-        let lighting_desc = D3D12_RESOURCE_DESC {
-            Dimension: D3D12_RESOURCE_DIMENSION_TEXTURE2D,
-            Alignment: D3D12_DEFAULT_RESOURCE_PLACEMENT_ALIGNMENT as _,
-            Width: settings.width as _,
-            Height: settings.height as _,
-            DepthOrArraySize: 1,
-            Format: DXGI_FORMAT_R16G16B16A16_TYPELESS,
-            MipLevels: 1,
-            SampleDesc: DXGI_SAMPLE_DESC {
-                Count: settings.samples,
-                Quality: 0,
-            },
-            Layout: D3D12_TEXTURE_LAYOUT_UNKNOWN,
-            Flags: D3D12_RESOURCE_FLAG_ALLOW_UNORDERED_ACCESS,
-        };
-        let mut lighting_clear_value = D3D12_CLEAR_VALUE {
-            Format: DXGI_FORMAT_R16G16B16A16_FLOAT,
-            ..unsafe { mem::zeroed() }
-        };
-        unsafe {
-            *lighting_clear_value.u.Color_mut() = [0.0, 0.0, 0.0, 0.0];
-        }
-        let lighting_buffer = engine.create_committed_resource(
-            D3D12_HEAP_TYPE_DEFAULT,
-            &lighting_desc,
-            D3D12_RESOURCE_STATE_PIXEL_SHADER_RESOURCE,
-            None,
-        );
+        let (graph, targets) = compile_graph(&settings)?;
+        let graph = RenderGraph::new(engine, graph)?;
 
         // Resoure views -------------------------------------
-        //  Allocate heaps
-        let rtv_size = unsafe {
-            engine
-                .device
-                .GetDescriptorHandleIncrementSize(D3D12_DESCRIPTOR_HEAP_TYPE_RTV)
-        };
-        let srv_uav_size = unsafe {
-            engine
-                .device
-                .GetDescriptorHandleIncrementSize(D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV)
+        //  Allocate descriptors
+        let srv_uavs =
+            engine.allocate_descriptors(DescriptorHeapType::CbvSrvUav, 8, "pipeline srv/uav")?;
+        let rtvs = engine.allocate_descriptors(DescriptorHeapType::Rtv, 1, "pipeline rtv")?;
+        let dsvs = engine.allocate_descriptors(DescriptorHeapType::Dsv, 2, "pipeline dsv")?;
+
+        let pipeline = Pipeline {
+            shadow_mapping: ShadowMapping::new(engine)?,
+            shadow_atlas: targets.shadow_atlas,
+            shadow_dsv: dsvs.cpu(1),
+            shadow_srv: srv_uavs.gpu(7),
+            geometry: Geometry::new(engine, settings.samples)?,
+            geometry_buffer: targets.geometry_buffer,
+            geometry_rtv_uint: rtvs.cpu(0),
+            geometry_srv_uint: srv_uavs.gpu(0),
+            clustering: Clustering::new(engine)?,
+            cluster_grid: targets.cluster_grid,
+            cluster_lights: targets.cluster_lights,
+            cluster_uavs: srv_uavs.gpu(3),
+            cluster_srvs: srv_uavs.gpu(5),
+            cluster_readback: targets.cluster_readback,
+            cluster_readback_buffer: create_cluster_readback(engine, &settings)?,
+            lighting: Lighting::new(engine, settings.samples)?,
+            lighting_buffer: targets.lighting_buffer,
+            lighting_srv: srv_uavs.gpu(2),
+            lighting_uav: srv_uavs.gpu(1),
+            post_process: PostProcess::new(engine)?,
+            graph,
+            backbuffer: targets.backbuffer,
+            depth_target: targets.depth_target,
+            dsv: dsvs.cpu(0),
+            rtvs,
+            dsvs,
+            srv_uavs,
+            settings,
         };
+        pipeline.create_views(engine);
 
-        let srv_uav_num = 3;
-        let rtv_num = 1;
-        let dsv_num = 1;
+        Ok(pipeline)
+    }
 
-        let rtv_heap = engine.create_descriptor_heap(
-            rtv_num,
-            D3D12_DESCRIPTOR_HEAP_TYPE_RTV,
-            D3D12_DESCRIPTOR_HEAP_FLAG_NONE,
-        );
-        let depth_heap = engine.create_descriptor_heap(
-            dsv_num,
-            D3D12_DESCRIPTOR_HEAP_TYPE_DSV,
-            D3D12_DESCRIPTOR_HEAP_FLAG_NONE,
-        );
-        let (srv_uav_start_cpu, srv_uav_start_gpu) = {
-            let (start_idx, _) = engine.allocate_descriptors(srv_uav_num, 0);
-            let (srv_uav_begin_cpu, srv_uav_begin_gpu) = engine.cbv_srv_uav_start;
-            let ptr_offset = start_idx as u32 * srv_uav_size;
-            let srv_uav_start_cpu = D3D12_CPU_DESCRIPTOR_HANDLE {
-                ptr: srv_uav_begin_cpu.ptr + ptr_offset as usize,
-            };
-            let srv_uav_start_gpu = D3D12_GPU_DESCRIPTOR_HANDLE {
-                ptr: srv_uav_begin_gpu.ptr + ptr_offset as u64,
-            };
+    pub fn settings(&self) -> PipelineSettings {
+        self.settings
+    }
 
-            (srv_uav_start_cpu, srv_uav_start_gpu)
+    /// Change the internal render resolution, effective for the next recorded frame.
+    pub fn set_render_scale(&mut self, scale: f32) {
+        assert!(scale > 0.0 && scale <= 1.0);
+        self.settings.render_scale = scale;
+    }
+
+    /// Recreate all render targets with the new size.
+    ///
+    /// The GPU must not access the current views anymore, descriptors are kept and
+    /// only the views are rewritten. The old targets and cluster readback buffer are
+    /// released to the engine.
+    pub fn resize(
+        &mut self,
+        engine: &mut Engine<B>,
+        width: u32,
+        height: u32,
+    ) -> Result<(), EngineError> {
+        let settings = PipelineSettings {
+            width,
+            height,
+            ..self.settings
         };
-        let rtv_start = unsafe { rtv_heap.GetCPUDescriptorHandleForHeapStart() };
+        let (graph, targets) = compile_graph(&settings)?;
+
+        self.graph.unregister(&mut engine.states);
+        let graph = RenderGraph::new(engine, graph)?;
+        engine.release(mem::replace(&mut self.graph, graph));
+        let cluster_readback_buffer = create_cluster_readback(engine, &settings)?;
+        engine.release(mem::replace(
+            &mut self.cluster_readback_buffer,
+            cluster_readback_buffer,
+        ));
+        self.backbuffer = targets.backbuffer;
+        self.shadow_atlas = targets.shadow_atlas;
+        self.geometry_buffer = targets.geometry_buffer;
+        self.cluster_grid = targets.cluster_grid;
+        self.cluster_lights = targets.cluster_lights;
+        self.cluster_readback = targets.cluster_readback;
+        self.lighting_buffer = targets.lighting_buffer;
+        self.depth_target = targets.depth_target;
+        self.settings = settings;
+        self.create_views(engine);
+
+        Ok(())
+    }
+
+    fn create_views(&self, engine: &Engine<B>) {
+        let multisampled = self.settings.samples > 1;
 
         //  Geometry buffer
-        let geometry_rtv_uint = rtv_start;
-        let geometry_rtv_uint_desc = D3D12_RENDER_TARGET_VIEW_DESC {
-            Format: DXGI_FORMAT_R16G16B16A16_UINT,
-            ViewDimension: D3D12_RTV_DIMENSION_TEXTURE2D,
-            ..unsafe { mem::zeroed() }
+        let format = Format::R16G16B16A16Uint;
+        let (rtv_desc, srv_desc) = if multisampled {
+            (RtvDesc::Texture2DMs { format }, SrvDesc::Texture2DMs { format })
+        } else {
+            (
+                RtvDesc::Texture2D { format },
+                SrvDesc::Texture2D {
+                    format,
+                    mip_levels: 1,
+                },
+            )
         };
-        unsafe {
-            engine.device.CreateRenderTargetView(
-                geometry_buffer.as_raw(),
-                &geometry_rtv_uint_desc,
-                geometry_rtv_uint,
-            );
-        }
+        engine.device.create_render_target_view(
+            self.graph.resource(self.geometry_buffer),
+            &rtv_desc,
+            self.rtvs.cpu(0),
+        );
+        engine.device.create_shader_resource_view(
+            self.graph.resource(self.geometry_buffer),
+            &srv_desc,
+            self.srv_uavs.cpu(0),
+        );
 
-        let geometry_srv_uint_cpu = srv_uav_start_cpu;
-        let geometry_srv_uint = srv_uav_start_gpu;
-        let mut geometry_srv_uint_desc = D3D12_SHADER_RESOURCE_VIEW_DESC {
-            Format: DXGI_FORMAT_R16G16B16A16_UINT,
-            ViewDimension: D3D12_SRV_DIMENSION_TEXTURE2D,
-            Shader4ComponentMapping: 0x1688, // D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING
-            ..unsafe { mem::zeroed() }
-        };
-        unsafe {
-            *geometry_srv_uint_desc.u.Texture2D_mut() = D3D12_TEX2D_SRV {
-                MostDetailedMip: 0,
-                MipLevels: 1,
-                PlaneSlice: 0,
-                ResourceMinLODClamp: 0.0,
-            };
-            engine.device.CreateShaderResourceView(
-                geometry_buffer.as_raw(),
-                &geometry_srv_uint_desc,
-                geometry_srv_uint_cpu,
+        // Cluster grid and light indices, sized for the full output
+        let num_clusters =
+            ClusterData::new([self.settings.width, self.settings.height], false).num_clusters();
+        let buffers = [
+            (self.cluster_grid, num_clusters, 8),
+            (self.cluster_lights, num_clusters * MAX_CLUSTER_LIGHTS, 4),
+        ];
+        for (i, &(buffer, num_elements, stride)) in buffers.iter().enumerate() {
+            engine.device.create_unordered_access_view(
+                self.graph.resource(buffer),
+                &UavDesc::Buffer {
+                    first_element: 0,
+                    num_elements,
+                    stride,
+                },
+                self.srv_uavs.cpu(3 + i as u32),
+            );
+            engine.device.create_shader_resource_view(
+                self.graph.resource(buffer),
+                &SrvDesc::Buffer {
+                    first_element: 0,
+                    num_elements,
+                    stride,
+                },
+                self.srv_uavs.cpu(5 + i as u32),
             );
         }
 
         // Lighting buffer
-        let lighting_uav_cpu = D3D12_CPU_DESCRIPTOR_HANDLE {
-            ptr: srv_uav_start_cpu.ptr + srv_uav_size as usize,
-        };
-        let lighting_uav_gpu = D3D12_GPU_DESCRIPTOR_HANDLE {
-            ptr: srv_uav_start_gpu.ptr + srv_uav_size as u64,
-        };
-        let mut lighting_uav_desc = D3D12_UNORDERED_ACCESS_VIEW_DESC {
-            Format: DXGI_FORMAT_R16G16B16A16_FLOAT,
-            ViewDimension: D3D12_UAV_DIMENSION_TEXTURE2D,
-            ..unsafe { mem::zeroed() }
-        };
-        unsafe {
-            *lighting_uav_desc.u.Texture2D_mut() = D3D12_TEX2D_UAV {
-                MipSlice: 0,
-                PlaneSlice: 0,
-            };
-            engine.device.CreateUnorderedAccessView(
-                lighting_buffer.as_raw(),
-                ptr::null_mut(),
-                &lighting_uav_desc,
-                lighting_uav_cpu,
-            );
-        }
+        engine.device.create_unordered_access_view(
+            self.graph.resource(self.lighting_buffer),
+            &UavDesc::Texture2D {
+                format: Format::R16G16B16A16Float,
+            },
+            self.srv_uavs.cpu(1),
+        );
 
-        let lighting_srv_cpu = D3D12_CPU_DESCRIPTOR_HANDLE {
-            ptr: srv_uav_start_cpu.ptr + 2 * srv_uav_size as usize,
-        };
-        let lighting_srv_gpu = D3D12_GPU_DESCRIPTOR_HANDLE {
-            ptr: srv_uav_start_gpu.ptr + 2 * srv_uav_size as u64,
-        };
-        let mut lighting_srv_desc = D3D12_SHADER_RESOURCE_VIEW_DESC {
-            Format: DXGI_FORMAT_R16G16B16A16_FLOAT,
-            ViewDimension: D3D12_SRV_DIMENSION_TEXTURE2D,
-            Shader4ComponentMapping: 0x1688, // D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING
-            ..unsafe { mem::zeroed() }
-        };
-        unsafe {
-            *lighting_srv_desc.u.Texture2D_mut() = D3D12_TEX2D_SRV {
-                MostDetailedMip: 0,
-                MipLevels: 1,
-                PlaneSlice: 0,
-                ResourceMinLODClamp: 0.0,
-            };
-            engine.device.CreateShaderResourceView(
-                lighting_buffer.as_raw(),
-                &lighting_srv_desc,
-                lighting_srv_cpu,
-            );
-        }
+        engine.device.create_shader_resource_view(
+            self.graph.resource(self.lighting_buffer),
+            &SrvDesc::Texture2D {
+                format: Format::R16G16B16A16Float,
+                mip_levels: 1,
+            },
+            self.srv_uavs.cpu(2),
+        );
 
         //  Depth target
-        let dsv = unsafe { depth_heap.GetCPUDescriptorHandleForHeapStart() };
-        let mut dsv_desc = D3D12_DEPTH_STENCIL_VIEW_DESC {
-            Format: pass::DS_FORMAT,
-            ViewDimension: D3D12_DSV_DIMENSION_TEXTURE2D,
-            ..unsafe { mem::zeroed() }
+        let format = pass::DS_FORMAT;
+        let dsv_desc = if multisampled {
+            DsvDesc::Texture2DMs { format }
+        } else {
+            DsvDesc::Texture2D { format }
         };
-        unsafe {
-            engine
-                .device
-                .CreateDepthStencilView(depth_target.as_raw(), &dsv_desc, dsv);
-        }
+        engine.device.create_depth_stencil_view(
+            self.graph.resource(self.depth_target),
+            &dsv_desc,
+            self.dsvs.cpu(0),
+        );
+
+        //  Shadow atlas
+        engine.device.create_depth_stencil_view(
+            self.graph.resource(self.shadow_atlas),
+            &DsvDesc::Texture2D {
+                format: pass::DS_FORMAT,
+            },
+            self.dsvs.cpu(1),
+        );
+        engine.device.create_shader_resource_view(
+            self.graph.resource(self.shadow_atlas),
+            &SrvDesc::Texture2D {
+                format: Format::R32Float,
+                mip_levels: 1,
+            },
+            self.srv_uavs.cpu(7),
+        );
+    }
+
+    /// Record the frame graph into `cmd_list`, rendering to `backbuffer`.
+    ///
+    /// `record` is called for each pass in execution order after its barriers.
+    pub fn execute<F>(
+        &self,
+        engine: &mut Engine<B>,
+        cmd_list: &B::CommandList,
+        backbuffer: &B::Resource,
+        record: F,
+    ) where
+        F: FnMut(Pass, &B::CommandList),
+    {
+        self.graph.execute(
+            engine,
+            cmd_list,
+            &[
+                (self.backbuffer, backbuffer),
+                (self.cluster_readback, &self.cluster_readback_buffer),
+            ],
+            record,
+        );
+    }
+
+    /// Highest number of lights assigned to a cluster in the last frame.
+    ///
+    /// Clusters are limited to `MAX_CLUSTER_LIGHTS`, the GPU must be idle. Only the
+    /// clusters of the rendered region are valid.
+    pub fn max_cluster_lights(&self, engine: &Engine<B>) -> Result<u32, EngineError> {
+        let (width, height) = self.settings.render_size();
+        let num_clusters = ClusterData::new([width, height], false).num_clusters();
+        let max_lights = unsafe {
+            let data = engine.device.map(&self.cluster_readback_buffer)?;
+            let grid = slice::from_raw_parts(data as *const [u32; 2], num_clusters as _);
+            let max_lights = grid
+                .iter()
+                .map(|&[num_point_lights, num_spot_lights]| num_point_lights + num_spot_lights)
+                .max()
+                .unwrap_or(0);
+            engine.device.unmap(&self.cluster_readback_buffer);
+            max_lights
+        };
+        Ok(max_lights)
+    }
+}
+
+/// Buffer receiving the cluster grid for the target size of `settings`.
+fn create_cluster_readback<B: Backend>(
+    engine: &mut Engine<B>,
+    settings: &PipelineSettings,
+) -> Result<B::Resource, EngineError> {
+    let num_clusters = ClusterData::new([settings.width, settings.height], false).num_clusters();
+    engine.create_committed_resource(
+        HeapType::Readback,
+        &ResourceDesc::buffer(num_clusters as u64 * 8),
+        ResourceStates::COPY_DEST,
+        None,
+    )
+}
+
+/// Build the frame graph for the target size of `settings`.
+fn compile_graph(
+    settings: &PipelineSettings,
+) -> Result<(CompiledGraph<Pass>, Targets), EngineError> {
+    let mut graph = GraphBuilder::new();
+
+    // Depth target
+    let depth_desc = ResourceDesc {
+        flags: ResourceFlags::ALLOW_DEPTH_STENCIL,
+        ..ResourceDesc::texture_2d(
+            settings.width,
+            settings.height,
+            pass::DS_FORMAT,
+            settings.samples,
+        )
+    };
+    let depth_target = graph.create_texture(
+        "depth target",
+        depth_desc,
+        Some(ClearValue::DepthStencil(pass::DS_FORMAT, 1.0, 0)),
+    );
+
+    // Shadow atlas, R32 typeless
+    //
+    // Depth of the shadow views, read as R32F for the depth comparison in the lighting pass.
+    let shadow_desc = ResourceDesc {
+        flags: ResourceFlags::ALLOW_DEPTH_STENCIL,
+        ..ResourceDesc::texture_2d(SHADOW_ATLAS_SIZE, SHADOW_ATLAS_SIZE, Format::R32Typeless, 1)
+    };
+    let shadow_atlas = graph.create_texture(
+        "shadow atlas",
+        shadow_desc,
+        Some(ClearValue::DepthStencil(pass::DS_FORMAT, 1.0, 0)),
+    );
+
+    // Geometry buffer, RGBA16 typeless, accessed as RGBA16U
+    //
+    // Storing only triangle identification data along with barycentric coordinates.
+    //  * R: U16 primitive id (relative to the base index of the draw)
+    //  * G: U16 instance id
+    //  * B: F16 bits of barycentric U [0,1]
+    //  * A: F16 bits of barycentric V [0,1]
+    //
+    // Pixels not covered by any triangle keep `INVALID_ID` ids.
+    let gbuffer_desc = ResourceDesc {
+        flags: ResourceFlags::ALLOW_RENDER_TARGET,
+        ..ResourceDesc::texture_2d(
+            settings.width,
+            settings.height,
+            Format::R16G16B16A16Typeless,
+            settings.samples,
+        )
+    };
+    let geometry_buffer = graph.create_texture(
+        "geometry buffer",
+        gbuffer_desc,
+        Some(ClearValue::Color(
+            Format::R16G16B16A16Uint,
+            [INVALID_ID as f32, INVALID_ID as f32, 0.0, 0.0],
+        )),
+    );
+
+    // Lighting buffer, RGBA16F
+    //
+    // Single sampled, the lighting pass resolves the samples of the geometry buffer.
+    let lighting_desc = ResourceDesc {
+        flags: ResourceFlags::ALLOW_UNORDERED_ACCESS,
+        ..ResourceDesc::texture_2d(
+            settings.width,
+            settings.height,
+            Format::R16G16B16A16Typeless,
+            1,
+        )
+    };
+    let lighting_buffer = graph.create_texture("lighting buffer", lighting_desc, None);
+
+    // Light clusters
+    //
+    //  * Grid: Number of point and spot lights per cluster (`uint2`).
+    //  * Lights: `MAX_CLUSTER_LIGHTS` light indices per cluster (`uint`).
+    let num_clusters = ClusterData::new([settings.width, settings.height], false).num_clusters();
+    let cluster_buffer_desc = |size: u32| ResourceDesc {
+        flags: ResourceFlags::ALLOW_UNORDERED_ACCESS,
+        ..ResourceDesc::buffer(size as _)
+    };
+    let cluster_grid = graph.create_buffer("cluster grid", cluster_buffer_desc(num_clusters * 8));
+    let cluster_lights = graph.create_buffer(
+        "cluster lights",
+        cluster_buffer_desc(num_clusters * MAX_CLUSTER_LIGHTS * 4),
+    );
+
+    let backbuffer = graph.import("backbuffer", Access::Present);
+    let cluster_readback = graph.import("cluster readback", Access::CopyDest);
+
+    // Passes ---------------------------------------------
+    let shadow_maps = {
+        let mut pass = graph.add_pass("shadow maps", Pass::ShadowMaps);
+        pass.write(shadow_atlas, Access::DepthWrite)
+    };
+
+    let (visibility, _) = {
+        let mut pass = graph.add_pass("geometry", Pass::Geometry);
+        let visibility = pass.write(geometry_buffer, Access::RenderTarget);
+        let depth = pass.write(depth_target, Access::DepthWrite);
+        (visibility, depth)
+    };
 
-        Pipeline {
-            geometry: Geometry::new(engine),
+    let (cluster_grid_lights, cluster_light_indices) = {
+        let mut pass = graph.add_pass("light clustering", Pass::LightClustering);
+        let grid = pass.write(cluster_grid, Access::UnorderedAccess);
+        let lights = pass.write(cluster_lights, Access::UnorderedAccess);
+        (grid, lights)
+    };
+
+    let lighting = {
+        let mut pass = graph.add_pass("lighting", Pass::Lighting);
+        pass.read(visibility, Access::NonPixelShaderResource);
+        pass.read(cluster_grid_lights, Access::NonPixelShaderResource);
+        pass.read(cluster_light_indices, Access::NonPixelShaderResource);
+        pass.read(shadow_maps, Access::NonPixelShaderResource);
+        pass.write(lighting_buffer, Access::UnorderedAccess)
+    };
+
+    {
+        let mut pass = graph.add_pass("cluster readback", Pass::ClusterReadback);
+        pass.read(cluster_grid_lights, Access::CopySource);
+        pass.write(cluster_readback, Access::CopyDest);
+    }
+
+    {
+        let mut pass = graph.add_pass("display map", Pass::DisplayMap);
+        pass.read(lighting, Access::PixelShaderResource);
+        pass.write(backbuffer, Access::RenderTarget);
+    }
+
+    let graph = graph.compile().map_err(|err| {
+        EngineError::new(
+            "compile",
+            "frame graph".into(),
+            ErrorKind::InvalidArgument(err.to_string()),
+        )
+    })?;
+
+    Ok((
+        graph,
+        Targets {
+            backbuffer,
+            shadow_atlas,
             geometry_buffer,
-            geometry_rtv_uint,
-            geometry_srv_uint,
-            lighting: Lighting::new(engine),
+            cluster_grid,
+            cluster_lights,
+            cluster_readback,
             lighting_buffer,
-            lighting_srv: lighting_srv_gpu,
-            lighting_uav: lighting_uav_gpu,
-            post_process: PostProcess::new(engine),
             depth_target,
-            depth_heap,
-            rtv_heap,
-            dsv,
-            srv_uav_start_cpu,
-            srv_uav_start_gpu,
-            srv_uav_num: srv_uav_num as _,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::null::Null;
+    use config::EngineConfig;
+
+    #[test]
+    fn reject_invalid_settings() {
+        let mut engine = Engine::<Null>::new(&EngineConfig::default()).unwrap();
+        let settings = PipelineSettings {
+            width: 64,
+            height: 64,
+            samples: 1,
+            render_scale: 1.0,
+        };
+
+        for &(samples, render_scale) in &[(3, 1.0), (16, 1.0), (1, 0.0), (1, 1.5)] {
+            let settings = PipelineSettings {
+                samples,
+                render_scale,
+                ..settings
+            };
+            let err = Pipeline::new(&mut engine, settings)
+                .err()
+                .expect("invalid settings accepted");
+            match err.kind {
+                ErrorKind::InvalidArgument(_) => {}
+                ref kind => panic!("unexpected error: {}", kind),
+            }
         }
     }
 }
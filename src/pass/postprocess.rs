@@ -1,20 +1,43 @@
+use backend::*;
 use engine::Engine;
-use pass;
-use winapi::shared::dxgiformat::*;
-use winapi::um::d3d12::*;
-use wio::com::ComPtr;
+use std::mem;
 
-pub struct DisplayMap {
-    pub signature: ComPtr<ID3D12RootSignature>,
-    pub pipeline: ComPtr<ID3D12PipelineState>,
+/// Mapping of the output to the rendered region of the input.
+// #[repr(hlsl)]
+#[repr(C)]
+pub struct DisplayMapData {
+    /// Rendered size relative to the input size.
+    pub uv_scale: [f32; 2],
+    /// Texture coordinates of the last texel centers of the rendered region.
+    pub uv_max: [f32; 2],
 }
 
-pub struct PostProcess {
-    pub display_map: DisplayMap,
+impl DisplayMapData {
+    /// Map the output to the region of size `render_size` in an input of size `input_size`.
+    pub fn new(render_size: (u32, u32), input_size: (u32, u32)) -> Self {
+        let (render_width, render_height) = (render_size.0 as f32, render_size.1 as f32);
+        let (input_width, input_height) = (input_size.0 as f32, input_size.1 as f32);
+        DisplayMapData {
+            uv_scale: [render_width / input_width, render_height / input_height],
+            uv_max: [
+                (render_width - 0.5) / input_width,
+                (render_height - 0.5) / input_height,
+            ],
+        }
+    }
+}
+
+pub struct DisplayMap<B: Backend> {
+    pub signature: B::RootSignature,
+    pub pipeline: B::PipelineState,
+}
+
+pub struct PostProcess<B: Backend> {
+    pub display_map: DisplayMap<B>,
 }
 
-impl PostProcess {
-    pub fn new(engine: &Engine) -> Self {
+impl<B: Backend> PostProcess<B> {
+    pub fn new(engine: &Engine<B>) -> Result<Self, EngineError> {
         // Display mapping
         let display_map = {
             let vs_shader = engine
@@ -23,76 +46,68 @@ impl PostProcess {
                     "shaders/displaymap.hlsl",
                     "vs_main\0",
                     "vs_5_1\0",
-                )
-                .unwrap();
+                    &[],
+                )?;
             let ps_shader = engine
                 .load_shader(
                     "display_map_ps",
                     "shaders/displaymap.hlsl",
                     "ps_displaymap\0",
                     "ps_5_1\0",
-                )
-                .unwrap();
+                    &[],
+                )?;
 
-            let table_input = [
-                D3D12_DESCRIPTOR_RANGE {
-                    RangeType: D3D12_DESCRIPTOR_RANGE_TYPE_SRV,
-                    NumDescriptors: 1,
-                    BaseShaderRegister: 0,
-                    RegisterSpace: 0,
-                    OffsetInDescriptorsFromTableStart: D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND,
-                },
-            ];
+            let table_input = [TableRange {
+                ty: RangeType::Srv,
+                count: 1,
+                base_register: 0,
+                space: 0,
+            }];
             let parameters = [
-                pass::gen_root_table_param(
-                    D3D12_SHADER_VISIBILITY_PIXEL,
-                    D3D12_ROOT_DESCRIPTOR_TABLE {
-                        NumDescriptorRanges: table_input.len() as _,
-                        pDescriptorRanges: table_input.as_ptr(),
-                    },
-                ),
+                RootParameter::Table {
+                    ranges: &table_input,
+                    visibility: ShaderVisibility::Pixel,
+                },
+                // Display map data
+                RootParameter::Constants {
+                    register: 0,
+                    space: 0,
+                    num_values: mem::size_of::<DisplayMapData>() as u32 / 4,
+                    visibility: ShaderVisibility::Pixel,
+                },
             ];
 
             let static_samplers = [
-                // Clamp to border sampler for basic material texture sampling (albedo, roughness, ..).
-                D3D12_STATIC_SAMPLER_DESC {
-                    Filter: D3D12_FILTER_MIN_MAG_MIP_POINT,
-                    AddressU: D3D12_TEXTURE_ADDRESS_MODE_CLAMP,
-                    AddressV: D3D12_TEXTURE_ADDRESS_MODE_CLAMP,
-                    AddressW: D3D12_TEXTURE_ADDRESS_MODE_CLAMP,
-                    MipLODBias: 0.0,
-                    MaxAnisotropy: 0,
-                    ComparisonFunc: D3D12_COMPARISON_FUNC_ALWAYS,
-                    BorderColor: D3D12_STATIC_BORDER_COLOR_TRANSPARENT_BLACK,
-                    MinLOD: 0.0,
-                    MaxLOD: D3D12_FLOAT32_MAX,
-                    ShaderRegister: 0,
-                    RegisterSpace: 0,
-                    ShaderVisibility: D3D12_SHADER_VISIBILITY_PIXEL,
+                // Bilinear upsampling of the rendered region.
+                StaticSampler {
+                    filter: Filter::Linear,
+                    address: AddressMode::Clamp,
+                    register: 0,
+                    space: 0,
+                    visibility: ShaderVisibility::Pixel,
                 },
             ];
 
             let signature = engine
-                .create_root_signature(&D3D12_ROOT_SIGNATURE_DESC {
-                    NumParameters: parameters.len() as _,
-                    pParameters: parameters.as_ptr(),
-                    NumStaticSamplers: static_samplers.len() as _,
-                    pStaticSamplers: static_samplers.as_ptr(),
-                    Flags: D3D12_ROOT_SIGNATURE_FLAG_NONE,
-                })
-                .unwrap();
-
-            let mut pso_desc = D3D12_GRAPHICS_PIPELINE_STATE_DESC {
-                pRootSignature: signature.as_raw(),
-                VS: pass::unpack_shader_bc(&vs_shader),
-                PS: pass::unpack_shader_bc(&ps_shader),
-                PrimitiveTopologyType: D3D12_PRIMITIVE_TOPOLOGY_TYPE_TRIANGLE,
-                NumRenderTargets: 1,
-                ..pass::DEFAULT_PIPELINE_STATE_DESC
-            };
-            pso_desc.RTVFormats[0] = DXGI_FORMAT_R8G8B8A8_UNORM_SRGB;
+                .device
+                .create_root_signature(&RootSignatureDesc {
+                    parameters: &parameters,
+                    static_samplers: &static_samplers,
+                    allow_input_layout: false,
+                })?;
 
-            let pipeline = engine.create_graphics_pipeline(&pso_desc);
+            let pipeline = engine
+                .device
+                .create_graphics_pipeline(&GraphicsPipelineDesc {
+                    signature: &signature,
+                    vs: &vs_shader,
+                    ps: &ps_shader,
+                    input_layout: &[],
+                    render_targets: &[Format::R8G8B8A8UnormSrgb],
+                    depth_format: Format::Unknown,
+                    depth_test: None,
+                    samples: 1,
+                })?;
 
             DisplayMap {
                 pipeline,
@@ -100,6 +115,6 @@ impl PostProcess {
             }
         };
 
-        PostProcess { display_map }
+        Ok(PostProcess { display_map })
     }
 }
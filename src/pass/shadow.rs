@@ -0,0 +1,671 @@
+//! Shadow map pass
+//!
+//! The shadow views of all shadow casting lights are rendered into a single depth atlas:
+//!
+//!  * Point lights: cube map, one perspective view per face.
+//!  * Spot lights: perspective view covering the outer cone.
+//!  * Directional lights: cascades along the view frustum.
+//!
+//! Each view gets a square tile with the resolution of its light, packed into the
+//! atlas by `pack_atlas`.
+
+use backend::*;
+use cgmath::{
+    self, EuclideanSpace, InnerSpace, Matrix4, Point3, Rad, SquareMatrix, Vector3, Vector4,
+};
+use engine::Engine;
+use pass;
+use pass::geometry::{ViewData, NEAR_PLANE};
+use scene::geometry::{DrawData, NUM_VERTEX_STREAMS};
+use std::cmp::Reverse;
+use std::{iter, mem};
+
+/// Width and height of the shadow atlas.
+///
+/// Must match with `SHADOW_ATLAS_SIZE` in `shadow.hlsl`.
+pub const SHADOW_ATLAS_SIZE: u32 = 4096;
+/// Smallest tile size, lower resolutions are rounded up.
+pub const MIN_SHADOW_RESOLUTION: u32 = 64;
+
+// Views per light type.
+//
+// Must match with `shadow.hlsl`.
+pub const CUBE_FACES: usize = 6;
+pub const NUM_CASCADES: usize = 4;
+
+/// Shadow view index of lights without shadows.
+///
+/// Must match with `INVALID_SHADOW` in `shadow.hlsl`.
+pub const INVALID_SHADOW: u32 = 0xFFFF_FFFF;
+
+/// View depth covered by the cascades, surfaces beyond are unshadowed.
+pub const CASCADE_DISTANCE: f32 = 4096.0;
+/// Blend between logarithmic (1) and uniform (0) cascade splits.
+pub const CASCADE_SPLIT_LAMBDA: f32 = 0.8;
+/// Extent of the cascades towards the light, shadow casters outside are clipped.
+pub const CASCADE_CASTER_DISTANCE: f32 = 4096.0;
+/// Near plane of the perspective shadow views.
+pub const SHADOW_NEAR_PLANE: f32 = 1.0;
+/// Widest spot light cone covered by a single perspective view.
+const MAX_SPOT_FOV: f32 = 170.0 * ::std::f32::consts::PI / 180.0;
+
+/// Single shadow map in the atlas.
+///
+/// Must match with `ShadowView` in `shadow.hlsl`.
+// #[repr(hlsl)]
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct ShadowView {
+    /// World space to clip space of the shadow map.
+    pub view_proj: [[f32; 4]; 4],
+    /// Texture coordinate offset (xy) and scale (zw) of the tile in the atlas.
+    pub atlas_rect: [f32; 4],
+    /// World space size of a texel, `x + y * distance` at a distance to the light.
+    pub texel_size: [f32; 2],
+    /// Far view depth of a cascade, 0 for other views.
+    pub split_depth: f32,
+    pub _alignment: f32,
+}
+
+impl ShadowView {
+    /// Texel region of the tile in the atlas.
+    pub fn viewport(&self) -> Viewport {
+        let size = SHADOW_ATLAS_SIZE as f32;
+        Viewport {
+            x: self.atlas_rect[0] * size,
+            y: self.atlas_rect[1] * size,
+            width: self.atlas_rect[2] * size,
+            height: self.atlas_rect[3] * size,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        }
+    }
+}
+
+/// Projection of the shadow views of a light.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ShadowProjection {
+    /// Cube map around the light, faces in `+x, -x, +y, -y, +z, -z` order.
+    Cube { position: Vector3<f32>, range: f32 },
+    /// Perspective view along the cone axis, `angle` is the outer half angle.
+    Perspective {
+        position: Vector3<f32>,
+        direction: Vector3<f32>,
+        angle: Rad<f32>,
+        range: f32,
+    },
+    /// Orthographic cascades along the view frustum, `direction` pointing away from the light.
+    Cascades { direction: Vector3<f32> },
+}
+
+impl ShadowProjection {
+    pub fn num_views(&self) -> usize {
+        match *self {
+            ShadowProjection::Cube { .. } => CUBE_FACES,
+            ShadowProjection::Perspective { .. } => 1,
+            ShadowProjection::Cascades { .. } => NUM_CASCADES,
+        }
+    }
+}
+
+/// Shadow views requested by a light.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ShadowRequest {
+    pub projection: ShadowProjection,
+    /// Width and height of each view in texels.
+    pub resolution: u32,
+}
+
+/// Square region of the atlas.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AtlasTile {
+    pub x: u32,
+    pub y: u32,
+    pub size: u32,
+}
+
+impl AtlasTile {
+    fn atlas_rect(&self, atlas_size: u32) -> [f32; 4] {
+        let scale = 1.0 / atlas_size as f32;
+        [
+            self.x as f32 * scale,
+            self.y as f32 * scale,
+            self.size as f32 * scale,
+            self.size as f32 * scale,
+        ]
+    }
+}
+
+/// Pack square tiles with the requested `sizes` into an atlas of `atlas_size`² texels.
+///
+/// Sizes are rounded down to powers of two in `[MIN_SHADOW_RESOLUTION, atlas_size]`.
+/// As long as the tiles exceed the atlas, the largest tiles are halved. Tiles not
+/// fitting at the minimum size are dropped (`None`), starting with the last request.
+///
+/// Tiles are placed along a Z-order curve from the largest to the smallest size,
+/// which aligns each tile to its size and leaves no gaps between them.
+pub fn pack_atlas(atlas_size: u32, sizes: &[u32]) -> Vec<Option<AtlasTile>> {
+    assert!(atlas_size.is_power_of_two() && atlas_size >= MIN_SHADOW_RESOLUTION);
+
+    let mut sizes = sizes
+        .iter()
+        .map(|&size| {
+            let size = size.max(MIN_SHADOW_RESOLUTION).min(atlas_size);
+            1 << (31 - size.leading_zeros())
+        })
+        .collect::<Vec<u32>>();
+
+    let atlas_area = atlas_size as u64 * atlas_size as u64;
+    let area = |sizes: &[u32]| {
+        sizes
+            .iter()
+            .map(|&size| size as u64 * size as u64)
+            .sum::<u64>()
+    };
+    while area(&sizes) > atlas_area {
+        let max_size = sizes.iter().cloned().max().unwrap();
+        if max_size == MIN_SHADOW_RESOLUTION {
+            break;
+        }
+        for size in sizes.iter_mut().filter(|size| **size == max_size) {
+            *size /= 2;
+        }
+    }
+
+    // Stable, tiles of equal size stay in request order.
+    let mut order = (0..sizes.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| Reverse(sizes[i]));
+
+    let mut tiles = vec![None; sizes.len()];
+    let mut offset = 0;
+    for i in order {
+        let size = sizes[i];
+        let tile_area = size as u64 * size as u64;
+        if offset + tile_area > atlas_area {
+            continue;
+        }
+        let (x, y) = morton_decode(offset);
+        tiles[i] = Some(AtlasTile { x, y, size });
+        offset += tile_area;
+    }
+    tiles
+}
+
+/// Texel of the Z-order curve at `index`, x in the even and y in the odd bits.
+fn morton_decode(index: u64) -> (u32, u32) {
+    let (mut x, mut y) = (0, 0);
+    for bit in 0..32 {
+        x |= ((index >> (2 * bit)) & 1) << bit;
+        y |= ((index >> (2 * bit + 1)) & 1) << bit;
+    }
+    (x as u32, y as u32)
+}
+
+/// Far view depth of each cascade covering `near..far`.
+///
+/// Practical split scheme, blending logarithmic and uniform splits by `lambda`.
+pub fn cascade_splits(near: f32, far: f32, num_cascades: usize, lambda: f32) -> Vec<f32> {
+    (1..num_cascades + 1)
+        .map(|i| {
+            let t = i as f32 / num_cascades as f32;
+            let logarithmic = near * (far / near).powf(t);
+            let uniform = near + (far - near) * t;
+            lambda * logarithmic + (1.0 - lambda) * uniform
+        })
+        .collect()
+}
+
+/// Views of all `requests` packed into the shadow atlas, cascades follow `view`.
+///
+/// Returns the views and the index of the first view of each request. All views of
+/// a light are consecutive, lights without space in the atlas get `INVALID_SHADOW`.
+pub fn shadow_views(requests: &[ShadowRequest], view: &ViewData) -> (Vec<ShadowView>, Vec<u32>) {
+    let sizes = requests
+        .iter()
+        .flat_map(|request| iter::repeat(request.resolution).take(request.projection.num_views()))
+        .collect::<Vec<_>>();
+    let mut tiles = pack_atlas(SHADOW_ATLAS_SIZE, &sizes).into_iter();
+
+    let mut views = Vec::new();
+    let mut first_views = Vec::with_capacity(requests.len());
+    for request in requests {
+        let num_views = request.projection.num_views();
+        let light_tiles = tiles.by_ref().take(num_views).collect::<Option<Vec<_>>>();
+        let light_tiles = match light_tiles {
+            Some(light_tiles) => light_tiles,
+            None => {
+                first_views.push(INVALID_SHADOW);
+                continue;
+            }
+        };
+
+        first_views.push(views.len() as u32);
+        match request.projection {
+            ShadowProjection::Cube { position, range } => {
+                let faces = [
+                    (Vector3::unit_x(), Vector3::unit_y()),
+                    (-Vector3::unit_x(), Vector3::unit_y()),
+                    (Vector3::unit_y(), -Vector3::unit_z()),
+                    (-Vector3::unit_y(), Vector3::unit_z()),
+                    (Vector3::unit_z(), Vector3::unit_y()),
+                    (-Vector3::unit_z(), Vector3::unit_y()),
+                ];
+                for (&(direction, up), tile) in faces.iter().zip(light_tiles) {
+                    let light_view =
+                        Matrix4::look_at_dir(Point3::from_vec(position), direction, up);
+                    views.push(perspective_view(
+                        light_view,
+                        Rad(0.5 * ::std::f32::consts::PI),
+                        range,
+                        tile,
+                    ));
+                }
+            }
+            ShadowProjection::Perspective {
+                position,
+                direction,
+                angle,
+                range,
+            } => {
+                let light_view =
+                    Matrix4::look_at_dir(Point3::from_vec(position), direction, view_up(direction));
+                let fov = Rad((2.0 * angle.0).min(MAX_SPOT_FOV));
+                views.push(perspective_view(light_view, fov, range, light_tiles[0]));
+            }
+            ShadowProjection::Cascades { direction } => {
+                let splits = cascade_splits(
+                    NEAR_PLANE,
+                    CASCADE_DISTANCE,
+                    num_views,
+                    CASCADE_SPLIT_LAMBDA,
+                );
+                let mut near = NEAR_PLANE;
+                for (&far, tile) in splits.iter().zip(light_tiles) {
+                    views.push(cascade_view(view, direction, near, far, tile));
+                    near = far;
+                }
+            }
+        }
+    }
+
+    (views, first_views)
+}
+
+/// OpenGL NDC -> DX12 NDC, mapping the depth range `[-1, 1]` to `[0, 1]`.
+fn dx_depth(proj: Matrix4<f32>) -> Matrix4<f32> {
+    Matrix4::from_translation(Vector3::new(0.0, 0.0, 0.5))
+        * Matrix4::from_nonuniform_scale(1.0, 1.0, 0.5)
+        * proj
+}
+
+/// Up vector for views along `direction`, avoiding parallel vectors.
+fn view_up(direction: Vector3<f32>) -> Vector3<f32> {
+    if direction.y.abs() > 0.99 {
+        Vector3::unit_z()
+    } else {
+        Vector3::unit_y()
+    }
+}
+
+fn perspective_view(
+    light_view: Matrix4<f32>,
+    fov: Rad<f32>,
+    range: f32,
+    tile: AtlasTile,
+) -> ShadowView {
+    let far = range.max(2.0 * SHADOW_NEAR_PLANE);
+    let proj = dx_depth(cgmath::perspective(fov, 1.0, SHADOW_NEAR_PLANE, far));
+    ShadowView {
+        view_proj: (proj * light_view).into(),
+        atlas_rect: tile.atlas_rect(SHADOW_ATLAS_SIZE),
+        texel_size: [0.0, 2.0 * (0.5 * fov.0).tan() / tile.size as f32],
+        split_depth: 0.0,
+        _alignment: 0.0,
+    }
+}
+
+/// Orthographic view enclosing the view frustum slice `near..far`.
+///
+/// The bounding sphere of the slice only depends on the frustum shape and the view
+/// is snapped to texels, the shadow map doesn't change under camera rotation and
+/// only moves in whole texels.
+fn cascade_view(
+    view: &ViewData,
+    direction: Vector3<f32>,
+    near: f32,
+    far: f32,
+    tile: AtlasTile,
+) -> ShadowView {
+    // Squared extent of the frustum at unit depth.
+    let unit_sq =
+        1.0 / (view.proj[0][0] * view.proj[0][0]) + 1.0 / (view.proj[1][1] * view.proj[1][1]);
+    let center_depth = 0.5 * (near + far);
+    let radius = ((far - center_depth) * (far - center_depth) + unit_sq * far * far).sqrt();
+    let radius = (radius * 16.0).ceil() / 16.0;
+
+    let inv_view = Matrix4::from(view.view).invert().unwrap();
+    let center = inv_view * Vector4::new(0.0, 0.0, -center_depth, 1.0);
+
+    let direction = direction.normalize();
+    let light_rotation = Matrix4::look_at_dir(Point3::origin(), direction, view_up(direction));
+    let texel = 2.0 * radius / tile.size as f32;
+    let center = (light_rotation * center).truncate();
+    let snapped = Vector3::new(
+        (center.x / texel).floor() * texel,
+        (center.y / texel).floor() * texel,
+        center.z,
+    );
+    let light_view = Matrix4::from_translation(-snapped) * light_rotation;
+    let proj = dx_depth(cgmath::ortho(
+        -radius,
+        radius,
+        -radius,
+        radius,
+        -(radius + CASCADE_CASTER_DISTANCE),
+        radius,
+    ));
+
+    ShadowView {
+        view_proj: (proj * light_view).into(),
+        atlas_rect: tile.atlas_rect(SHADOW_ATLAS_SIZE),
+        texel_size: [texel, 0.0],
+        split_depth: far,
+        _alignment: 0.0,
+    }
+}
+
+pub struct ShadowMapping<B: Backend> {
+    pub signature: B::RootSignature,
+    pub pipeline: B::PipelineState,
+}
+
+impl<B: Backend> ShadowMapping<B> {
+    pub fn new(engine: &Engine<B>) -> Result<Self, EngineError> {
+        let vs_shader = engine.load_shader(
+            "shadow_map_vs",
+            "shaders/shadow_map.hlsl",
+            "vs_shadow\0",
+            "vs_5_1\0",
+            &[],
+        )?;
+        let ps_shader = engine.load_shader(
+            "shadow_map_ps",
+            "shaders/shadow_map.hlsl",
+            "ps_shadow\0",
+            "ps_5_1\0",
+            &[],
+        )?;
+
+        // Mesh SRVs, see `Mesh::srvs`
+        let table_data = [
+            TableRange {
+                ty: RangeType::Srv,
+                count: 3,
+                base_register: 0,
+                space: 1,
+            },
+            TableRange {
+                ty: RangeType::Srv,
+                count: NUM_VERTEX_STREAMS as _,
+                base_register: 4,
+                space: 1,
+            },
+        ];
+
+        // Instance data SRV
+        let table_instances = [TableRange {
+            ty: RangeType::Srv,
+            count: 1,
+            base_register: 3,
+            space: 1,
+        }];
+
+        let table_materials = [TableRange {
+            ty: RangeType::Srv,
+            count: 1,
+            base_register: 0,
+            space: 5,
+        }];
+
+        let table_textures = [TableRange {
+            ty: RangeType::Srv,
+            count: UNBOUNDED_RANGE,
+            base_register: 0,
+            space: 2,
+        }];
+
+        let parameters = [
+            // Shadow view
+            RootParameter::Cbv {
+                register: 0,
+                space: 7,
+                visibility: ShaderVisibility::Vertex,
+            },
+            // Mesh SRVs, texture coordinates for alpha testing
+            RootParameter::Table {
+                ranges: &table_data,
+                visibility: ShaderVisibility::Vertex,
+            },
+            // Draw data root constants
+            RootParameter::Constants {
+                register: 0,
+                space: 2,
+                num_values: mem::size_of::<DrawData>() as u32 / 4,
+                visibility: ShaderVisibility::Pixel,
+            },
+            // Instance ID
+            RootParameter::Constants {
+                register: 1,
+                space: 2,
+                num_values: 1,
+                visibility: ShaderVisibility::Vertex,
+            },
+            // Instance data SRV
+            RootParameter::Table {
+                ranges: &table_instances,
+                visibility: ShaderVisibility::Vertex,
+            },
+            // Material data SRV, alpha testing only
+            RootParameter::Table {
+                ranges: &table_materials,
+                visibility: ShaderVisibility::Pixel,
+            },
+            // Material textures
+            RootParameter::Table {
+                ranges: &table_textures,
+                visibility: ShaderVisibility::Pixel,
+            },
+        ];
+
+        let static_samplers = [StaticSampler {
+            filter: Filter::Linear,
+            address: AddressMode::Wrap,
+            register: 0,
+            space: 2,
+            visibility: ShaderVisibility::Pixel,
+        }];
+
+        let signature = engine.device.create_root_signature(&RootSignatureDesc {
+            parameters: &parameters,
+            static_samplers: &static_samplers,
+            allow_input_layout: true,
+        })?;
+
+        let input_layout = [InputElement {
+            semantic: "Attr",
+            index: 0,
+            format: Format::R32G32B32Float,
+            slot: 0,
+        }];
+
+        let pipeline = engine
+            .device
+            .create_graphics_pipeline(&GraphicsPipelineDesc {
+                signature: &signature,
+                vs: &vs_shader,
+                ps: &ps_shader,
+                input_layout: &input_layout,
+                render_targets: &[],
+                depth_format: pass::DS_FORMAT,
+                depth_test: Some(CompareFunc::Less),
+                samples: 1,
+            })?;
+
+        Ok(ShadowMapping {
+            signature,
+            pipeline,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pass::geometry::FAR_PLANE;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() <= 1e-3 * b.abs(), "{} != {}", a, b);
+    }
+
+    fn overlaps(a: &AtlasTile, b: &AtlasTile) -> bool {
+        a.x < b.x + b.size && b.x < a.x + a.size && a.y < b.y + b.size && b.y < a.y + a.size
+    }
+
+    /// Tiles are power of two sizes aligned to their size, inside the atlas and disjoint.
+    fn assert_disjoint(atlas_size: u32, tiles: &[AtlasTile]) {
+        for (i, a) in tiles.iter().enumerate() {
+            assert!(a.size.is_power_of_two(), "{:?}", a);
+            assert!(a.x % a.size == 0 && a.y % a.size == 0, "{:?}", a);
+            assert!(
+                a.x + a.size <= atlas_size && a.y + a.size <= atlas_size,
+                "{:?}",
+                a
+            );
+            for b in &tiles[i + 1..] {
+                assert!(!overlaps(a, b), "{:?} {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn splits_increase_to_far() {
+        for &lambda in &[0.0, 0.3, 0.8, 1.0] {
+            let splits = cascade_splits(1.0, 4096.0, NUM_CASCADES, lambda);
+            assert_eq!(splits.len(), NUM_CASCADES);
+            assert!(splits[0] > 1.0, "{:?}", splits);
+            assert!(splits.windows(2).all(|w| w[0] < w[1]), "{:?}", splits);
+            assert_close(splits[NUM_CASCADES - 1], 4096.0);
+        }
+    }
+
+    #[test]
+    fn split_blend() {
+        let uniform = cascade_splits(1.0, 4097.0, 4, 0.0);
+        let logarithmic = cascade_splits(1.0, 4096.0, 4, 1.0);
+        let blend = cascade_splits(1.0, 4096.0, 4, 0.5);
+        for (split, &expected) in uniform.iter().zip(&[1025.0, 2049.0, 3073.0, 4097.0]) {
+            assert_close(*split, expected);
+        }
+        for (split, &expected) in logarithmic.iter().zip(&[8.0, 64.0, 512.0, 4096.0]) {
+            assert_close(*split, expected);
+        }
+
+        // Halfway between the logarithmic and the uniform splits of the same range.
+        let uniform = cascade_splits(1.0, 4096.0, 4, 0.0);
+        for i in 0..4 {
+            assert_close(blend[i], 0.5 * (uniform[i] + logarithmic[i]));
+        }
+    }
+
+    #[test]
+    fn pack_disjoint_tiles() {
+        let sizes = [1000, 64, 512, 2048, 300, 100, 1024, 20, 512];
+        let tiles = pack_atlas(4096, &sizes);
+        let tiles = tiles.into_iter().map(Option::unwrap).collect::<Vec<_>>();
+        let sizes = tiles.iter().map(|tile| tile.size).collect::<Vec<_>>();
+        assert_eq!(sizes, [512, 64, 512, 2048, 256, 64, 1024, 64, 512]);
+        assert_disjoint(4096, &tiles);
+    }
+
+    #[test]
+    fn pack_exceeding_atlas() {
+        // The largest tiles are halved until all fit.
+        let tiles = pack_atlas(1024, &[1024, 1024, 512, 256]);
+        let sizes = tiles
+            .iter()
+            .map(|tile| tile.unwrap().size)
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, [512, 512, 512, 256]);
+        assert_disjoint(
+            1024,
+            &tiles.iter().map(|tile| tile.unwrap()).collect::<Vec<_>>(),
+        );
+
+        // Tiles at the minimum size fill the atlas, the last requests are dropped.
+        let tiles = pack_atlas(256, &[64; 20]);
+        assert!(tiles[..16].iter().all(Option::is_some));
+        assert!(tiles[16..].iter().all(Option::is_none));
+        assert_disjoint(
+            256,
+            &tiles[..16]
+                .iter()
+                .map(|tile| tile.unwrap())
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn cascade_views() {
+        let mut proj = cgmath::perspective(cgmath::Deg(60.0), 1.0, NEAR_PLANE, FAR_PLANE);
+        proj.w.z /= 2.0;
+        let view = ViewData {
+            view: Matrix4::identity().into(),
+            proj: proj.into(),
+            position: [0.0, 0.0, 0.0, 1.0],
+            _alignment: [0.0; 28],
+        };
+        let requests = [
+            ShadowRequest {
+                projection: ShadowProjection::Cube {
+                    position: Vector3::new(0.0, 2.0, 0.0),
+                    range: 10.0,
+                },
+                resolution: 512,
+            },
+            ShadowRequest {
+                projection: ShadowProjection::Cascades {
+                    direction: Vector3::new(0.3, -1.0, 0.2),
+                },
+                resolution: 1024,
+            },
+        ];
+        let (views, first_views) = shadow_views(&requests, &view);
+        assert_eq!(first_views, [0, CUBE_FACES as u32]);
+        assert_eq!(views.len(), CUBE_FACES + NUM_CASCADES);
+
+        let splits = cascade_splits(
+            NEAR_PLANE,
+            CASCADE_DISTANCE,
+            NUM_CASCADES,
+            CASCADE_SPLIT_LAMBDA,
+        );
+        for (view, &split) in views[CUBE_FACES..].iter().zip(&splits) {
+            assert_eq!(view.split_depth, split);
+        }
+
+        // Views of all lights share the atlas without overlapping.
+        let tiles = views
+            .iter()
+            .map(|view| {
+                let viewport = view.viewport();
+                assert_eq!(viewport.width, viewport.height);
+                AtlasTile {
+                    x: viewport.x as u32,
+                    y: viewport.y as u32,
+                    size: viewport.width as u32,
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_disjoint(SHADOW_ATLAS_SIZE, &tiles);
+    }
+}
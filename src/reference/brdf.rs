@@ -0,0 +1,201 @@
+//! CPU port of `brdf.hlsl`.
+//!
+//! Cook-Torrance microfacet specular with a Lambertian diffuse lobe, evaluated in
+//! `f32` like the shader. Used by the reference shading and as oracle for the
+//! lighting pass.
+
+use cgmath::{InnerSpace, Vector3};
+use std::f32::consts::PI;
+
+/// Must match with `DIELECTRIC_F0` in `brdf.hlsl`.
+pub const DIELECTRIC_F0: f32 = 0.04;
+
+/// Must match with `MIN_ROUGHNESS` in `brdf.hlsl`.
+pub const MIN_ROUGHNESS: f32 = 0.045;
+
+/// `Surface`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Surface {
+    pub normal: Vector3<f32>,
+    pub albedo: Vector3<f32>,
+    /// Perceptual roughness, `alpha = roughness^2`.
+    pub roughness: f32,
+    pub metallic: f32,
+}
+
+fn saturate(value: f32) -> f32 {
+    value.max(0.0).min(1.0)
+}
+
+impl Surface {
+    /// `specular_f0`
+    pub fn specular_f0(&self) -> Vector3<f32> {
+        let dielectric = Vector3::new(DIELECTRIC_F0, DIELECTRIC_F0, DIELECTRIC_F0);
+        dielectric + (self.albedo - dielectric) * self.metallic
+    }
+
+    /// `diffuse_albedo`
+    pub fn diffuse_albedo(&self) -> Vector3<f32> {
+        self.albedo * (1.0 - self.metallic)
+    }
+}
+
+/// GGX normal distribution.
+pub fn d_ggx(n_dot_h: f32, alpha: f32) -> f32 {
+    let alpha_sq = alpha * alpha;
+    let f = (n_dot_h * alpha_sq - n_dot_h) * n_dot_h + 1.0;
+    alpha_sq / (PI * f * f)
+}
+
+/// Height-correlated Smith visibility, includes the `4 * n_dot_l * n_dot_v` denominator.
+pub fn v_smith_ggx_correlated(n_dot_v: f32, n_dot_l: f32, alpha: f32) -> f32 {
+    let alpha_sq = alpha * alpha;
+    let ggx_l = n_dot_v * (n_dot_l * n_dot_l * (1.0 - alpha_sq) + alpha_sq).sqrt();
+    let ggx_v = n_dot_l * (n_dot_v * n_dot_v * (1.0 - alpha_sq) + alpha_sq).sqrt();
+    0.5 / (ggx_v + ggx_l)
+}
+
+pub fn f_schlick(f0: Vector3<f32>, v_dot_h: f32) -> Vector3<f32> {
+    let f = (1.0 - v_dot_h).powf(5.0);
+    f0 + (Vector3::new(1.0, 1.0, 1.0) - f0) * f
+}
+
+/// `evaluate_brdf`, reflected radiance per unit irradiance multiplied by `n_dot_l`.
+///
+/// `view` and `light` point away from the surface.
+pub fn evaluate(surface: &Surface, view: Vector3<f32>, light: Vector3<f32>) -> Vector3<f32> {
+    let n_dot_l = saturate(surface.normal.dot(light));
+    if n_dot_l <= 0.0 {
+        return Vector3::new(0.0, 0.0, 0.0);
+    }
+    let n_dot_v = surface.normal.dot(view).max(1e-4);
+
+    let half_dir = (view + light).normalize();
+    let n_dot_h = saturate(surface.normal.dot(half_dir));
+    let v_dot_h = saturate(view.dot(half_dir));
+
+    let roughness = surface.roughness.max(MIN_ROUGHNESS);
+    let alpha = roughness * roughness;
+    let fresnel = f_schlick(surface.specular_f0(), v_dot_h);
+    let specular =
+        fresnel * (d_ggx(n_dot_h, alpha) * v_smith_ggx_correlated(n_dot_v, n_dot_l, alpha));
+    let diffuse = surface.diffuse_albedo() / PI;
+
+    (diffuse + specular) * n_dot_l
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NORMAL: Vector3<f32> = Vector3 {
+        x: 0.0,
+        y: 0.0,
+        z: 1.0,
+    };
+
+    fn surface(albedo: f32, roughness: f32, metallic: f32) -> Surface {
+        Surface {
+            normal: NORMAL,
+            albedo: Vector3::new(albedo, albedo, albedo),
+            roughness,
+            metallic,
+        }
+    }
+
+    /// Direction with `cos_theta` to the normal.
+    fn direction(cos_theta: f32, phi: f32) -> Vector3<f32> {
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        Vector3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta)
+    }
+
+    /// Reflected radiance of a uniform white environment, midpoint rule over the
+    /// hemisphere of light directions.
+    fn white_furnace(surface: &Surface, view: Vector3<f32>) -> f32 {
+        const STEPS: u32 = 512;
+        let mut radiance = 0.0;
+        for i in 0..STEPS {
+            let cos_theta = (i as f32 + 0.5) / STEPS as f32;
+            for j in 0..STEPS {
+                let phi = 2.0 * PI * (j as f32 + 0.5) / STEPS as f32;
+                radiance += evaluate(surface, view, direction(cos_theta, phi)).x;
+            }
+        }
+        radiance * 2.0 * PI / (STEPS * STEPS) as f32
+    }
+
+    #[test]
+    fn white_furnace_energy_bound() {
+        // White metal, `f0 = 1` without diffuse reflection.
+        for &roughness in &[0.3, 0.6, 1.0] {
+            let surface = surface(1.0, roughness, 1.0);
+            for &n_dot_v in &[0.2, 0.6, 1.0] {
+                let energy = white_furnace(&surface, direction(n_dot_v, 0.0));
+                assert!(energy <= 1.01, "{} {}: {}", roughness, n_dot_v, energy);
+            }
+        }
+
+        // Smooth surfaces lose little energy to single scattering.
+        let energy = white_furnace(&surface(1.0, 0.3, 1.0), direction(1.0, 0.0));
+        assert!(energy > 0.98, "{}", energy);
+    }
+
+    #[test]
+    fn white_furnace_roughest() {
+        // `D = 1 / PI` and `V = 0.5 / (n_dot_l + n_dot_v)` for `alpha = 1`, the integral
+        // of `n_dot_l / (n_dot_l + n_dot_v)` over `n_dot_l` has a closed form.
+        let surface = surface(1.0, 1.0, 1.0);
+        for &n_dot_v in &[0.2f32, 0.6, 1.0] {
+            let expected = 1.0 - n_dot_v * (1.0 + 1.0 / n_dot_v).ln();
+            let energy = white_furnace(&surface, direction(n_dot_v, 0.0));
+            assert!((energy - expected).abs() < 1e-3, "{}: {}", n_dot_v, energy);
+        }
+    }
+
+    #[test]
+    fn reciprocity() {
+        let surface = surface(0.7, 0.4, 0.3);
+        let directions = [
+            direction(0.9, 0.0),
+            direction(0.5, 1.0),
+            direction(0.2, 2.5),
+            direction(0.7, 4.0),
+        ];
+        for &view in &directions {
+            for &light in &directions {
+                // Without the cosine factor the BRDF is symmetric.
+                let forward = evaluate(&surface, view, light) / NORMAL.dot(light);
+                let backward = evaluate(&surface, light, view) / NORMAL.dot(view);
+                assert!((forward - backward).magnitude() <= 1e-4 * forward.magnitude());
+            }
+        }
+    }
+
+    #[test]
+    fn lambert_diffuse_limit() {
+        let view = direction(0.8, 0.0);
+        for &light in &[
+            direction(1.0, 0.0),
+            direction(0.5, 2.0),
+            direction(0.1, 4.0),
+        ] {
+            // Dielectric specular reflection doesn't depend on the albedo.
+            let specular = evaluate(&surface(0.0, 1.0, 0.0), view, light);
+            let lambert = NORMAL.dot(light) * 0.5 / PI;
+            let diffuse = evaluate(&surface(0.5, 1.0, 0.0), view, light) - specular;
+            assert!((diffuse - Vector3::new(lambert, lambert, lambert)).magnitude() < 1e-6);
+        }
+
+        // Lambertian surfaces reflect their albedo in a white furnace, dielectric
+        // specular reflection adds a few percent.
+        let energy = white_furnace(&surface(0.5, 1.0, 0.0), view);
+        assert!(energy >= 0.5 && energy < 0.55, "{}", energy);
+    }
+
+    #[test]
+    fn below_horizon() {
+        let light = Vector3::new(0.0, 0.6, -0.8);
+        let reflected = evaluate(&surface(1.0, 0.5, 0.0), direction(0.5, 0.0), light);
+        assert_eq!(reflected, Vector3::new(0.0, 0.0, 0.0));
+    }
+}
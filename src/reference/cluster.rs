@@ -0,0 +1,323 @@
+//! Light clustering (`cs_cluster_lights`) and cluster lookup of the lighting pass.
+
+use cgmath::{InnerSpace, Matrix4, Vector3, Vector4};
+use pass::cluster::{ClusterData, CLUSTER_SLICES, CLUSTER_TILE_SIZE, MAX_CLUSTER_LIGHTS};
+use pass::geometry::ViewData;
+use scene::light::Lights;
+
+/// Must match with `HEATMAP_MAX_LIGHTS` in `cluster.hlsl`.
+const HEATMAP_MAX_LIGHTS: u32 = 32;
+
+/// Contents of the cluster buffers.
+#[derive(Clone, Debug)]
+pub struct Clusters {
+    pub data: ClusterData,
+    /// Number of point and spot lights per cluster.
+    pub grid: Vec<[u32; 2]>,
+    /// `MAX_CLUSTER_LIGHTS` light indices per cluster, point lights first.
+    pub light_indices: Vec<u32>,
+}
+
+impl Clusters {
+    /// Point and spot light indices of a cluster.
+    pub fn lights(&self, cluster: u32) -> (&[u32], &[u32]) {
+        let [num_point_lights, num_spot_lights] = self.grid[cluster as usize];
+        let base = (cluster * MAX_CLUSTER_LIGHTS) as usize;
+        let points = base + num_point_lights as usize;
+        let spots = points + num_spot_lights as usize;
+        (
+            &self.light_indices[base..points],
+            &self.light_indices[points..spots],
+        )
+    }
+}
+
+/// `cluster_index`
+pub fn cluster_index(data: &ClusterData, cluster: [u32; 3]) -> u32 {
+    (cluster[2] * data.grid[1] + cluster[1]) * data.grid[0] + cluster[0]
+}
+
+/// `pixel_cluster`, cluster of a pixel with view space `depth`.
+pub fn pixel_cluster(data: &ClusterData, pixel: [u32; 2], depth: f32) -> [u32; 3] {
+    let slice = depth.ln() * data.depth_scale + data.depth_bias;
+    let slice = slice.max(0.0).min((CLUSTER_SLICES - 1) as f32);
+    [
+        pixel[0] / CLUSTER_TILE_SIZE,
+        pixel[1] / CLUSTER_TILE_SIZE,
+        slice as u32,
+    ]
+}
+
+/// `cluster_bounds`, view space bounding box of a cluster.
+pub fn cluster_bounds(
+    data: &ClusterData,
+    view: &ViewData,
+    cluster: [u32; 3],
+) -> (Vector3<f32>, Vector3<f32>) {
+    let extent = [data.extent[0] as f32, data.extent[1] as f32];
+    let pixel_min = [
+        (cluster[0] * CLUSTER_TILE_SIZE) as f32,
+        (cluster[1] * CLUSTER_TILE_SIZE) as f32,
+    ];
+    let pixel_max = [
+        (((cluster[0] + 1) * CLUSTER_TILE_SIZE) as f32).min(extent[0]),
+        (((cluster[1] + 1) * CLUSTER_TILE_SIZE) as f32).min(extent[1]),
+    ];
+
+    // Tile corners at unit depth, y is pointing down in screen space.
+    let unit_min = [
+        (pixel_min[0] / extent[0] * 2.0 - 1.0) / view.proj[0][0],
+        (1.0 - pixel_max[1] / extent[1] * 2.0) / view.proj[1][1],
+    ];
+    let unit_max = [
+        (pixel_max[0] / extent[0] * 2.0 - 1.0) / view.proj[0][0],
+        (1.0 - pixel_min[1] / extent[1] * 2.0) / view.proj[1][1],
+    ];
+
+    let depth_near = ((cluster[2] as f32 - data.depth_bias) / data.depth_scale).exp();
+    let depth_far = ((cluster[2] as f32 + 1.0 - data.depth_bias) / data.depth_scale).exp();
+
+    let aabb_min = Vector3::new(
+        (unit_min[0] * depth_near).min(unit_min[0] * depth_far),
+        (unit_min[1] * depth_near).min(unit_min[1] * depth_far),
+        -depth_far,
+    );
+    let aabb_max = Vector3::new(
+        (unit_max[0] * depth_near).max(unit_max[0] * depth_far),
+        (unit_max[1] * depth_near).max(unit_max[1] * depth_far),
+        -depth_near,
+    );
+    (aabb_min, aabb_max)
+}
+
+/// `sphere_intersects_aabb`
+pub fn sphere_intersects_aabb(
+    center: Vector3<f32>,
+    radius: f32,
+    aabb_min: Vector3<f32>,
+    aabb_max: Vector3<f32>,
+) -> bool {
+    let d = Vector3::new(
+        (aabb_min.x - center.x).max(0.0).max(center.x - aabb_max.x),
+        (aabb_min.y - center.y).max(0.0).max(center.y - aabb_max.y),
+        (aabb_min.z - center.z).max(0.0).max(center.z - aabb_max.z),
+    );
+    d.magnitude2() <= radius * radius
+}
+
+/// `cs_cluster_lights`, assign the point and spot lights to all clusters.
+pub fn build(data: &ClusterData, view: &ViewData, lights: &Lights) -> Clusters {
+    let num_clusters = data.grid[0] * data.grid[1] * data.grid[2];
+    let view_matrix = Matrix4::from(view.view);
+    let view_position = |position: [f32; 3]| {
+        (view_matrix * Vector4::new(position[0], position[1], position[2], 1.0)).truncate()
+    };
+
+    let mut clusters = Clusters {
+        data: *data,
+        grid: vec![[0; 2]; num_clusters as usize],
+        light_indices: vec![0; (num_clusters * MAX_CLUSTER_LIGHTS) as usize],
+    };
+
+    for id in 0..num_clusters {
+        let cluster = [
+            id % data.grid[0],
+            (id / data.grid[0]) % data.grid[1],
+            id / (data.grid[0] * data.grid[1]),
+        ];
+        let (aabb_min, aabb_max) = cluster_bounds(data, view, cluster);
+
+        let base = (id * MAX_CLUSTER_LIGHTS) as usize;
+        let mut num_lights = 0;
+        let points = lights
+            .points
+            .iter()
+            .map(|light| (light.position, light.range));
+        for (i, (position, range)) in points.enumerate() {
+            if num_lights == MAX_CLUSTER_LIGHTS as usize {
+                break;
+            }
+            if sphere_intersects_aabb(view_position(position), range, aabb_min, aabb_max) {
+                clusters.light_indices[base + num_lights] = i as u32;
+                num_lights += 1;
+            }
+        }
+
+        let num_point_lights = num_lights;
+        let spots = lights
+            .spots
+            .iter()
+            .map(|light| (light.position, light.range));
+        for (s, (position, range)) in spots.enumerate() {
+            if num_lights == MAX_CLUSTER_LIGHTS as usize {
+                break;
+            }
+            if sphere_intersects_aabb(view_position(position), range, aabb_min, aabb_max) {
+                clusters.light_indices[base + num_lights] = s as u32;
+                num_lights += 1;
+            }
+        }
+
+        clusters.grid[id as usize] = [
+            num_point_lights as u32,
+            (num_lights - num_point_lights) as u32,
+        ];
+    }
+
+    clusters
+}
+
+/// `light_heatmap`, debug color for the number of lights of a cluster.
+pub fn light_heatmap(num_lights: u32) -> Vector3<f32> {
+    if num_lights == 0 {
+        return Vector3::new(0.0, 0.0, 0.0);
+    }
+    let saturate = |value: f32| value.max(0.0).min(1.0);
+    let t = saturate(num_lights as f32 / HEATMAP_MAX_LIGHTS as f32);
+    Vector3::new(
+        saturate(2.0 * t - 1.0),
+        saturate(1.0 - (2.0 * t - 1.0).abs()),
+        saturate(1.0 - 2.0 * t),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{self, Deg, SquareMatrix};
+    use pass::geometry::{FAR_PLANE, NEAR_PLANE};
+    use pass::lighting::{PointLight, SpotLight};
+    use pass::shadow::INVALID_SHADOW;
+
+    /// 4x2 tiles, the view center is at the corner of the middle tiles.
+    const EXTENT: [u32; 2] = [256, 128];
+
+    /// Camera at the origin looking along -z.
+    fn view() -> ViewData {
+        let mut proj = cgmath::perspective(Deg(60.0), 2.0, NEAR_PLANE, FAR_PLANE);
+        proj.w.z /= 2.0;
+        ViewData {
+            view: Matrix4::identity().into(),
+            proj: proj.into(),
+            position: [0.0, 0.0, 0.0, 1.0],
+            _alignment: [0.0; 28],
+        }
+    }
+
+    fn point_light(position: [f32; 3], range: f32) -> PointLight {
+        PointLight {
+            position,
+            intensity: 1.0,
+            color: [1.0; 3],
+            range,
+            shadow: INVALID_SHADOW,
+        }
+    }
+
+    fn spot_light(position: [f32; 3], range: f32) -> SpotLight {
+        SpotLight {
+            position,
+            intensity: 1.0,
+            color: [1.0; 3],
+            angle_scale: 1.0,
+            direction: [0.0, 0.0, -1.0],
+            angle_offset: 0.0,
+            range,
+            shadow: INVALID_SHADOW,
+            _alignment: [0.0; 2],
+        }
+    }
+
+    /// Clusters with any lights assigned.
+    fn lit_clusters(clusters: &Clusters) -> Vec<[u32; 3]> {
+        let data = &clusters.data;
+        let mut lit = Vec::new();
+        for z in 0..data.grid[2] {
+            for y in 0..data.grid[1] {
+                for x in 0..data.grid[0] {
+                    if clusters.grid[cluster_index(data, [x, y, z]) as usize] != [0, 0] {
+                        lit.push([x, y, z]);
+                    }
+                }
+            }
+        }
+        lit
+    }
+
+    /// Clusters of the middle tiles from `near` to `far` depth.
+    fn middle_clusters(data: &ClusterData, near: f32, far: f32) -> Vec<[u32; 3]> {
+        let slices = pixel_cluster(data, [0, 0], near)[2]..=pixel_cluster(data, [0, 0], far)[2];
+        let mut clusters = Vec::new();
+        for z in slices {
+            for y in 0..2 {
+                for x in 1..3 {
+                    clusters.push([x, y, z]);
+                }
+            }
+        }
+        clusters
+    }
+
+    #[test]
+    fn point_light_froxels() {
+        let data = ClusterData::new(EXTENT, false);
+        let lights = Lights {
+            points: vec![
+                point_light([5.0, 5.0, 10.0], 2.0), // behind the camera
+                point_light([0.0, 0.0, -10.0], 1.0),
+            ],
+            ..Lights::default()
+        };
+        let clusters = build(&data, &view(), &lights);
+
+        let lit = lit_clusters(&clusters);
+        assert_eq!(lit, middle_clusters(&data, 9.0, 11.0));
+        for cluster in lit {
+            let (points, spots) = clusters.lights(cluster_index(&data, cluster));
+            assert_eq!((points, spots), (&[1][..], &[][..]));
+        }
+    }
+
+    #[test]
+    fn spot_light_froxels() {
+        let data = ClusterData::new(EXTENT, false);
+        let lights = Lights {
+            points: vec![point_light([0.0, 0.0, -10.0], 1.0)],
+            spots: vec![
+                spot_light([0.0, 0.0, -50.0], 5.0),
+                spot_light([0.0, 0.0, -10.0], 1.0),
+            ],
+            ..Lights::default()
+        };
+        let clusters = build(&data, &view(), &lights);
+
+        // Spot lights are bounded by the sphere of their range, stored after the point lights.
+        for cluster in middle_clusters(&data, 9.0, 11.0) {
+            let (points, spots) = clusters.lights(cluster_index(&data, cluster));
+            assert_eq!((points, spots), (&[0][..], &[1][..]));
+        }
+        for cluster in middle_clusters(&data, 45.0, 55.0) {
+            let (points, spots) = clusters.lights(cluster_index(&data, cluster));
+            assert_eq!((points, spots), (&[][..], &[0][..]));
+        }
+    }
+
+    #[test]
+    fn truncate_cluster_lights() {
+        let data = ClusterData::new(EXTENT, false);
+        let lights = Lights {
+            points: vec![point_light([0.0, 0.0, -10.0], 1.0); MAX_CLUSTER_LIGHTS as usize + 10],
+            spots: vec![spot_light([0.0, 0.0, -10.0], 1.0)],
+            ..Lights::default()
+        };
+        let clusters = build(&data, &view(), &lights);
+
+        // The first lights fill the cluster, the remaining point and spot lights are dropped.
+        let first = (0..MAX_CLUSTER_LIGHTS).collect::<Vec<_>>();
+        for cluster in middle_clusters(&data, 9.0, 11.0) {
+            let (points, spots) = clusters.lights(cluster_index(&data, cluster));
+            assert_eq!(points, &first[..]);
+            assert!(spots.is_empty());
+        }
+    }
+}
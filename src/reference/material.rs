@@ -0,0 +1,194 @@
+//! Material textures and evaluation (`material.hlsl`).
+//!
+//! Textures are reimported like the GPU textures, including mip generation and block
+//! compression, and sampled like the static sampler of the passes: trilinear
+//! filtering with wrapping addressing. BC7 textures can't be decoded.
+
+use backend::Backend;
+use failure::{err_msg, Error};
+use reference::Image;
+use scene::material::{MaterialData, INVALID_TEXTURE};
+use scene::{Scene, Texture, TextureView};
+use specs::prelude::*;
+use texture::{self, mip};
+
+/// Mip level with linear texels, sRGB textures are decoded on load like `*_SRGB` formats.
+pub type TextureImage = Image<[f32; 4]>;
+
+/// CPU copy of the material data and textures.
+pub struct Materials {
+    pub data: Vec<MaterialData>,
+    /// Mip levels of each texture, indexed by the `TextureView` id.
+    pub textures: Vec<Vec<TextureImage>>,
+}
+
+impl Materials {
+    /// Load the material data and reimport the texture assets.
+    pub fn load<B: Backend>(scene: &Scene<B>) -> Result<Self, Error> {
+        let textures = scene.assets.read_storage::<Texture<B>>();
+        let views = scene.assets.read_storage::<TextureView>();
+
+        let mut images = (&textures, &views)
+            .join()
+            .map(|(texture, view)| Ok((view.id, load_texture(texture)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        images.sort_by_key(|&(id, _)| id);
+
+        Ok(Materials {
+            data: scene.material_data(),
+            textures: images.into_iter().map(|(_, levels)| levels).collect(),
+        })
+    }
+
+    /// `StructuredBuffer` load, out of bounds reads return zero.
+    pub fn load_material(&self, material_id: u32) -> MaterialData {
+        self.data
+            .get(material_id as usize)
+            .cloned()
+            .unwrap_or(MaterialData {
+                albedo: [0.0; 4],
+                emissive: [0.0; 3],
+                roughness: 0.0,
+                metallic: 0.0,
+                alpha_cutoff: 0.0,
+                albedo_texture: 0,
+                opacity_texture: 0,
+                normal_texture: 0,
+                roughness_metal_texture: 0,
+                emissive_texture: 0,
+                alpha_mode: 0,
+            })
+    }
+
+    /// `sample_material_texture`
+    pub fn sample(
+        &self,
+        texture_id: u32,
+        uv: [f32; 2],
+        uv_dx: [f32; 2],
+        uv_dy: [f32; 2],
+        fallback: [f32; 4],
+    ) -> [f32; 4] {
+        if texture_id == INVALID_TEXTURE {
+            return fallback;
+        }
+        self.textures
+            .get(texture_id as usize)
+            .map(|levels| sample_trilinear(levels, uv, uv_dx, uv_dy))
+            .unwrap_or([0.0; 4])
+    }
+
+    /// `material_alpha`
+    pub fn alpha(
+        &self,
+        material: &MaterialData,
+        uv: [f32; 2],
+        uv_dx: [f32; 2],
+        uv_dy: [f32; 2],
+    ) -> f32 {
+        material.albedo[3]
+            * self.sample(material.albedo_texture, uv, uv_dx, uv_dy, [1.0; 4])[3]
+            * self.sample(material.opacity_texture, uv, uv_dx, uv_dy, [1.0; 4])[0]
+    }
+}
+
+/// Import a texture asset and decode all mip levels.
+fn load_texture<B: Backend>(texture: &Texture<B>) -> Result<Vec<TextureImage>, Error> {
+    let data = texture::import(&texture.path, texture.usage, &texture.options)?;
+    let srgb = data.format.is_srgb();
+
+    (0..data.levels.len() as u32)
+        .map(|level| {
+            let texels = data.decode_level(level).ok_or_else(|| {
+                err_msg(format!(
+                    "can't decode {:?} texture `{}`",
+                    data.format,
+                    texture.path.display()
+                ))
+            })?;
+            let (width, height) = data.level_extent(level);
+            let texels = texels
+                .chunks(4)
+                .map(|texel| {
+                    let mut linear = [0.0; 4];
+                    for c in 0..4 {
+                        let value = texel[c] as f32 / 255.0;
+                        // Alpha is always stored linear.
+                        linear[c] = if srgb && c < 3 {
+                            mip::srgb_to_linear(value)
+                        } else {
+                            value
+                        };
+                    }
+                    linear
+                })
+                .collect();
+
+            Ok(Image {
+                width,
+                height,
+                texels,
+            })
+        })
+        .collect()
+}
+
+/// Linear mip filtering between the two levels closest to the level of detail.
+///
+/// The level of detail is derived from the longer gradient in texels of the top level.
+fn sample_trilinear(
+    levels: &[TextureImage],
+    uv: [f32; 2],
+    uv_dx: [f32; 2],
+    uv_dy: [f32; 2],
+) -> [f32; 4] {
+    let (width, height) = (levels[0].width as f32, levels[0].height as f32);
+    let length = |d: [f32; 2]| ((d[0] * width).powi(2) + (d[1] * height).powi(2)).sqrt();
+    let lod = length(uv_dx).max(length(uv_dy)).log2();
+    let lod = lod.max(0.0).min((levels.len() - 1) as f32);
+
+    let level = lod.floor() as usize;
+    let fraction = lod - level as f32;
+    let color0 = sample_bilinear(&levels[level], uv);
+    if fraction == 0.0 {
+        return color0;
+    }
+
+    let color1 = sample_bilinear(&levels[level + 1], uv);
+    let mut color = [0.0; 4];
+    for c in 0..4 {
+        color[c] = color0[c] + (color1[c] - color0[c]) * fraction;
+    }
+    color
+}
+
+/// Bilinear filtering with wrapping addressing.
+fn sample_bilinear(texture: &TextureImage, uv: [f32; 2]) -> [f32; 4] {
+    let wrap = |coord: i64, size: u32| {
+        let size = size as i64;
+        (((coord % size) + size) % size) as u32
+    };
+
+    // Texel centers at half integer coordinates.
+    let x = uv[0] * texture.width as f32 - 0.5;
+    let y = uv[1] * texture.height as f32 - 0.5;
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (x0, y0) = (x0 as i64, y0 as i64);
+
+    let texel = |dx: i64, dy: i64| {
+        texture.get(
+            wrap(x0 + dx, texture.width),
+            wrap(y0 + dy, texture.height),
+        )
+    };
+    let (t00, t10, t01, t11) = (texel(0, 0), texel(1, 0), texel(0, 1), texel(1, 1));
+
+    let mut color = [0.0; 4];
+    for c in 0..4 {
+        let top = t00[c] + (t10[c] - t00[c]) * fx;
+        let bottom = t01[c] + (t11[c] - t01[c]) * fx;
+        color[c] = top + (bottom - top) * fy;
+    }
+    color
+}
@@ -0,0 +1,203 @@
+//! CPU reference renderer.
+//!
+//! Software implementation of the visibility buffer path, following the GPU passes
+//! as close as possible to generate golden images without a GPU:
+//!
+//!  * Raster: Geometry pass (`geometry.hlsl`), outputs the visibility buffer
+//!            bit-compatible to the `R16G16B16A16_UINT` geometry buffer.
+//!  * Shading: Lighting pass (`cs_lighting`) reconstructing the triangles from the
+//!             visibility buffer, outputs the `R16G16B16A16_FLOAT` lighting buffer
+//!             as raw half bits.
+//!  * BRDF: Surface reflectance (`brdf.hlsl`) of the lighting pass.
+//!  * Cluster: Light clustering pass (`cs_cluster_lights`), assigning the point and
+//!             spot lights to the froxels used by the shading.
+//!  * Shadow: Shadow map pass (`shadow_map.hlsl`) rendering the shadow atlas, and the
+//!            PCF lookups of the shading.
+//!  * Environment lighting (`environment.hlsl`) of the shading, from the preprocessed
+//!    environment of the scene (`ibl`).
+//!  * Display map (`ps_displaymap`) for viewable `R8G8B8A8_UNORM_SRGB` images.
+//!
+//! Material textures are reimported from their source files.
+
+use backend::Backend;
+use failure::Error;
+use ibl;
+use image;
+use pass::cluster::ClusterData;
+use pass::geometry::ViewData;
+use scene::{self, Scene};
+use specs::prelude::*;
+use std::path::Path;
+use std::slice;
+
+pub mod brdf;
+pub mod cluster;
+pub mod material;
+pub mod pack;
+pub mod raster;
+pub mod shading;
+pub mod shadow;
+
+/// Simple row-major 2D image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image<T> {
+    pub width: u32,
+    pub height: u32,
+    pub texels: Vec<T>,
+}
+
+impl<T: Copy> Image<T> {
+    pub fn new(width: u32, height: u32, clear: T) -> Self {
+        Image {
+            width,
+            height,
+            texels: vec![clear; (width * height) as usize],
+        }
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> T {
+        self.texels[(y * self.width + x) as usize]
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, value: T) {
+        self.texels[(y * self.width + x) as usize] = value;
+    }
+}
+
+/// Visibility buffer texels: primitive id, instance id and packed barycentrics.
+///
+/// Texels not covered by any triangle store `INVALID_ID` as primitive and instance id.
+pub type VisibilityBuffer = Image<[u16; 4]>;
+
+/// Lighting buffer texels as half float bits.
+pub type LightingBuffer = Image<[u16; 4]>;
+
+/// Render the scene like the first frame of the frame loop.
+///
+/// Returns the visibility buffer, the lighting buffer and the light heatmap debug view.
+pub fn render_scene<B: Backend>(
+    scene: &Scene<B>,
+    view: &ViewData,
+    width: u32,
+    height: u32,
+) -> Result<(VisibilityBuffer, LightingBuffer, LightingBuffer), Error> {
+    let mesh = scene.assets.read_resource::<scene::geometry::MeshData>();
+    let materials = material::Materials::load(scene)?;
+
+    let mut instance_data = vec![
+        scene::geometry::InstanceData {
+            world: [[0.0; 4]; 4],
+            geometry_id: 0,
+            _alignment: [0; 3],
+        };
+        scene.instances.len()
+    ];
+    scene.write_instance_data(&mut instance_data);
+
+    // Draws in submission order of the geometry pass.
+    let draws = {
+        let transforms = scene.world.read_storage::<scene::LocalTransform>();
+        let instances = scene.world.read_storage::<scene::Instance>();
+        let geometries = scene.assets.read_storage::<scene::Geometry>();
+        let entities = scene.world.entities();
+
+        (&*entities, &transforms, &instances)
+            .join()
+            .map(|(e, _, instance)| {
+                let geometry = geometries.get(instance.geometry).unwrap().clone();
+                raster::Draw {
+                    instance_id: scene.instances[&e] as _,
+                    material_id: scene.materials[&geometry.material] as _,
+                    geometry,
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let lights = scene.light_data(view);
+    let environment = scene.assets.read_resource::<ibl::Environment>();
+
+    let visibility = raster::rasterize(
+        &mesh,
+        &instance_data,
+        &materials,
+        &draws,
+        view,
+        width,
+        height,
+    );
+    let shadow_atlas = shadow::render(
+        &mesh,
+        &instance_data,
+        &materials,
+        &draws,
+        &lights.shadow_views,
+    );
+    let clusters = cluster::build(&ClusterData::new([width, height], false), view, &lights);
+    let lighting = shading::shade(
+        slice::from_ref(&visibility),
+        &mesh,
+        &instance_data,
+        &materials,
+        view,
+        &lights,
+        &clusters,
+        &shadow_atlas,
+        &environment,
+    );
+    let heatmap_clusters = cluster::Clusters {
+        data: ClusterData::new([width, height], true),
+        ..clusters
+    };
+    let light_heatmap = shading::shade(
+        slice::from_ref(&visibility),
+        &mesh,
+        &instance_data,
+        &materials,
+        view,
+        &lights,
+        &heatmap_clusters,
+        &shadow_atlas,
+        &environment,
+    );
+
+    Ok((visibility, lighting, light_heatmap))
+}
+
+/// Store the visibility buffer as 16-bit RGBA png.
+pub fn save_visibility_png<P: AsRef<Path>>(
+    path: P,
+    visibility: &VisibilityBuffer,
+) -> Result<(), Error> {
+    let data = visibility
+        .texels
+        .iter()
+        .flat_map(|texel| texel.iter().flat_map(|c| vec![(c >> 8) as u8, *c as u8]))
+        .collect::<Vec<_>>();
+    image::save_buffer(
+        path,
+        &data,
+        visibility.width,
+        visibility.height,
+        image::ColorType::RGBA(16),
+    )?;
+    Ok(())
+}
+
+/// Store the display mapped lighting buffer as 8-bit sRGB png.
+pub fn save_lighting_png<P: AsRef<Path>>(path: P, lighting: &LightingBuffer) -> Result<(), Error> {
+    let display = shading::display_map(lighting);
+    let data = display
+        .texels
+        .iter()
+        .flat_map(|texel| texel.iter().cloned())
+        .collect::<Vec<_>>();
+    image::save_buffer(
+        path,
+        &data,
+        display.width,
+        display.height,
+        image::ColorType::RGBA(8),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,130 @@
+//! CPU port of `pack.hlsl` including the `f32tof16`/`f16tof32` intrinsics.
+
+pub const F16_MAX: f32 = 65504.0;
+
+/// Convert to half precision bits, rounding to nearest even.
+///
+/// Overflowing values are converted to infinity, NaNs stay NaNs.
+pub fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xFF) as i32;
+    let mantissa = bits & 0x7F_FFFF;
+
+    // Inf and NaN
+    if exponent == 0xFF {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7C00 | nan;
+    }
+
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1F {
+        return sign | 0x7C00;
+    }
+
+    if exponent <= 0 {
+        // Denormals or zero
+        if exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - exponent) as u32;
+        let half = 1 << (shift - 1);
+        let rest = mantissa & ((1 << shift) - 1);
+        let mut result = (mantissa >> shift) as u16;
+        if rest > half || (rest == half && result & 1 != 0) {
+            result += 1;
+        }
+        return sign | result;
+    }
+
+    let rest = mantissa & 0x1FFF;
+    let mut result = ((exponent as u32) << 10 | (mantissa >> 13)) as u16;
+    if rest > 0x1000 || (rest == 0x1000 && result & 1 != 0) {
+        // Carry may overflow into the exponent, resulting in infinity as expected.
+        result += 1;
+    }
+    sign | result
+}
+
+pub fn f16_to_f32(value: u16) -> f32 {
+    let sign = ((value & 0x8000) as u32) << 16;
+    let exponent = ((value >> 10) & 0x1F) as u32;
+    let mantissa = (value & 0x3FF) as u32;
+
+    let bits = match exponent {
+        0 if mantissa == 0 => sign,
+        0 => {
+            // Denormal, normalize into f32 range
+            let mut exponent = 127 - 15 + 1;
+            let mut mantissa = mantissa;
+            while mantissa & 0x400 == 0 {
+                mantissa <<= 1;
+                exponent -= 1;
+            }
+            sign | (exponent << 23) | ((mantissa & 0x3FF) << 13)
+        }
+        0x1F => sign | 0x7F80_0000 | (mantissa << 13),
+        _ => sign | ((exponent + 127 - 15) << 23) | (mantissa << 13),
+    };
+
+    f32::from_bits(bits)
+}
+
+pub fn pack_barycentric_f16(uv: [f32; 2]) -> [u16; 2] {
+    [f32_to_f16(uv[0] * F16_MAX), f32_to_f16(uv[1] * F16_MAX)]
+}
+
+pub fn unpack_barycentric_f16(uv: [u16; 2]) -> [f32; 2] {
+    [f16_to_f32(uv[0]) / F16_MAX, f16_to_f32(uv[1]) / F16_MAX]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f32_to_f16_rounding() {
+        assert_eq!(f32_to_f16(0.0), 0x0000);
+        assert_eq!(f32_to_f16(-0.0), 0x8000);
+        assert_eq!(f32_to_f16(1.0), 0x3C00);
+        assert_eq!(f32_to_f16(-2.0), 0xC000);
+        assert_eq!(f32_to_f16(F16_MAX), 0x7BFF);
+        // Halfway between `F16_MAX` and the next step rounds to even, overflowing.
+        assert_eq!(f32_to_f16(65520.0), 0x7C00);
+        assert_eq!(f32_to_f16(f32::INFINITY), 0x7C00);
+        assert_eq!(f32_to_f16(f32::NAN) & 0x7E00, 0x7E00);
+        // Smallest denormal and ties to even below it.
+        assert_eq!(f32_to_f16(2.0f32.powi(-24)), 0x0001);
+        assert_eq!(f32_to_f16(2.0f32.powi(-25)), 0x0000);
+        assert_eq!(f32_to_f16(1.5 * 2.0f32.powi(-24)), 0x0002);
+        // Ties to even in the normal range.
+        assert_eq!(f32_to_f16(1.0 + 2.0f32.powi(-11)), 0x3C00);
+        assert_eq!(f32_to_f16(1.0 + 3.0 * 2.0f32.powi(-11)), 0x3C02);
+    }
+
+    #[test]
+    fn f16_round_trip() {
+        for bits in 0..=0xFFFFu16 {
+            let value = f16_to_f32(bits);
+            if value.is_nan() {
+                assert!(f16_to_f32(f32_to_f16(value)).is_nan());
+            } else {
+                assert_eq!(f32_to_f16(value), bits, "{:04x} -> {}", bits, value);
+            }
+        }
+    }
+
+    #[test]
+    fn barycentric_round_trip() {
+        for i in 0..=64 {
+            for j in 0..=64 - i {
+                let uv = [i as f32 / 64.0, j as f32 / 64.0];
+                let unpacked = unpack_barycentric_f16(pack_barycentric_f16(uv));
+                for c in 0..2 {
+                    assert!((unpacked[c] - uv[c]).abs() <= uv[c] * 2.0f32.powi(-11) + 1e-7);
+                }
+            }
+        }
+    }
+}
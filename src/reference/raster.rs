@@ -0,0 +1,616 @@
+//! Visibility buffer rasterization.
+//!
+//! Follows the D3D12 rasterization rules for the geometry pipeline state:
+//!
+//!  * Clipping against the near and far plane, guard band for x and y.
+//!  * 16.8 fixed point vertex snapping and top-left fill rule at pixel centers.
+//!  * No culling, `LESS` depth test against a depth buffer cleared to 1.0.
+//!  * `SV_PrimitiveID` counting triangles per draw call.
+//!  * First vertex of a triangle as provoking vertex for `nointerpolation`.
+//!  * Alpha tested pixels are discarded before writing depth. The texture coordinate
+//!    gradients of the pixel quads are approximated by the analytic gradients of
+//!    the lighting pass.
+//!  * Multisampling at the standard sample positions. Coverage and depth are tested
+//!    per sample, the pixel shader runs once per pixel with the attributes at the
+//!    pixel center.
+//!
+//! Integer render target outputs are clamped to 16 bit.
+
+use cgmath::{InnerSpace, Matrix4, Vector3, Vector4};
+use pass::geometry::{ViewData, INVALID_ID};
+use reference::material::Materials;
+use reference::shading::uv_gradients;
+use reference::{pack, Image, VisibilityBuffer};
+use scene::geometry::{DrawData, InstanceData, MeshData, VertexAttribute, VERTEX_STREAMS};
+use scene::material::ALPHA_MODE_MASK;
+use scene::Geometry;
+
+/// Single draw call of the geometry pass.
+#[derive(Clone, Debug)]
+pub struct Draw {
+    pub instance_id: u32,
+    pub geometry: Geometry,
+    /// Material id of the draw constants.
+    pub material_id: u32,
+}
+
+/// Subpixel precision of the vertex snapping.
+pub const SUBPIXEL_BITS: u32 = 8;
+
+/// Guard band extent in clip space, relative to the viewport.
+const GUARD_BAND: f32 = 2.0;
+
+/// Standard sample positions in 1/16 pixels relative to the pixel center.
+pub fn sample_positions(samples: u32) -> &'static [[i64; 2]] {
+    match samples {
+        1 => &[[0, 0]],
+        2 => &[[4, 4], [-4, -4]],
+        4 => &[[-2, -6], [6, -2], [-6, 2], [2, 6]],
+        8 => &[
+            [1, -3],
+            [-1, 3],
+            [5, 1],
+            [-3, -5],
+            [-5, 5],
+            [-7, -1],
+            [3, 7],
+            [7, -7],
+        ],
+        _ => panic!("unsupported sample count {}", samples),
+    }
+}
+
+/// Clip space vertex with the interpolated attributes.
+#[derive(Copy, Clone, Debug)]
+pub struct ClipVertex {
+    pub position: Vector4<f32>,
+    /// View direction in the geometry pass.
+    pub attribute: Vector3<f32>,
+}
+
+impl ClipVertex {
+    fn lerp(&self, other: &ClipVertex, t: f32) -> ClipVertex {
+        ClipVertex {
+            position: self.position + (other.position - self.position) * t,
+            attribute: self.attribute + (other.attribute - self.attribute) * t,
+        }
+    }
+}
+
+/// Screen space vertex in fixed point with perspective information.
+#[derive(Copy, Clone, Debug)]
+pub struct ScreenVertex {
+    pub x: i64,
+    pub y: i64,
+    pub z: f32,
+    pub inv_w: f32,
+    pub attribute: Vector3<f32>,
+}
+
+impl ScreenVertex {
+    /// Snap a clip space vertex to the viewport at `offset` with `extent` pixels.
+    pub fn new(v: &ClipVertex, offset: [u32; 2], extent: [u32; 2]) -> Self {
+        let scale = (1 << SUBPIXEL_BITS) as f32;
+        let inv_w = 1.0 / v.position.w;
+        let ndc_x = v.position.x * inv_w;
+        let ndc_y = v.position.y * inv_w;
+        let x = offset[0] as f32 + (ndc_x * 0.5 + 0.5) * extent[0] as f32;
+        let y = offset[1] as f32 + (0.5 - ndc_y * 0.5) * extent[1] as f32;
+
+        ScreenVertex {
+            x: (x * scale).round() as i64,
+            y: (y * scale).round() as i64,
+            z: v.position.z * inv_w,
+            inv_w,
+            attribute: v.attribute,
+        }
+    }
+}
+
+/// Möller–Trumbore intersection, see `resources_triangle.hlsl`.
+pub fn raycast_triangle_barycentric(
+    origin: Vector3<f32>,
+    dir: Vector3<f32>,
+    v0: Vector3<f32>,
+    v1: Vector3<f32>,
+    v2: Vector3<f32>,
+) -> Vector3<f32> {
+    let e0 = v1 - v0;
+    let e1 = v2 - v0;
+    let h = dir.cross(e1);
+    let f = 1.0 / e0.dot(h);
+    let s = origin - v0;
+    let q = s.cross(e0);
+
+    let bary_v = f * s.dot(h);
+    let bary_w = f * dir.dot(q);
+
+    Vector3::new(1.0 - bary_v - bary_w, bary_v, bary_w)
+}
+
+/// `StructuredBuffer` loads, out of bounds reads return zero.
+pub fn load_index(indices: &[u32], index: u32) -> u32 {
+    indices.get(index as usize).cloned().unwrap_or(0)
+}
+
+pub fn load_position(mesh: &MeshData, index: u32) -> Vector3<f32> {
+    mesh.positions
+        .get(index as usize)
+        .map(|v| Vector3::from(v.0))
+        .unwrap_or(Vector3::new(0.0, 0.0, 0.0))
+}
+
+/// Attribute stream load, out of bounds words read as zero.
+pub fn load_attribute(mesh: &MeshData, attribute: VertexAttribute, index: u32) -> Vector4<f32> {
+    let stream = &VERTEX_STREAMS[attribute as usize];
+    let words = &mesh.streams[attribute as usize];
+    let base = index as usize * stream.stride();
+    let src = (base..base + stream.stride())
+        .map(|i| words.get(i).cloned().unwrap_or(0))
+        .collect::<Vec<_>>();
+    Vector4::from(stream.decode(&src))
+}
+
+/// Attribute at `barycentric` of the triangle with the vertex `indices`.
+pub fn interpolate_attribute(
+    mesh: &MeshData,
+    attribute: VertexAttribute,
+    base_vertex: u32,
+    indices: [u32; 3],
+    barycentric: Vector3<f32>,
+) -> Vector4<f32> {
+    load_attribute(mesh, attribute, base_vertex + indices[0]) * barycentric.x
+        + load_attribute(mesh, attribute, base_vertex + indices[1]) * barycentric.y
+        + load_attribute(mesh, attribute, base_vertex + indices[2]) * barycentric.z
+}
+
+pub fn load_instance(instances: &[InstanceData], instance_id: u32) -> InstanceData {
+    instances
+        .get(instance_id as usize)
+        .cloned()
+        .unwrap_or(InstanceData {
+            world: [[0.0; 4]; 4],
+            geometry_id: 0,
+            _alignment: [0; 3],
+        })
+}
+
+pub fn transform_position(world: &Matrix4<f32>, pos: Vector3<f32>) -> Vector3<f32> {
+    (world * pos.extend(1.0)).truncate()
+}
+
+pub fn load_draw_data(mesh: &MeshData, geometry_id: u32) -> DrawData {
+    mesh.draw_data
+        .get(geometry_id as usize)
+        .cloned()
+        .unwrap_or(DrawData {
+            base_index: 0,
+            base_vertex: 0,
+            material: 0,
+        })
+}
+
+/// Clip polygon against the plane `dot(plane, position) >= 0`.
+pub fn clip_polygon(polygon: &[ClipVertex], plane: Vector4<f32>) -> Vec<ClipVertex> {
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for i in 0..polygon.len() {
+        let a = &polygon[i];
+        let b = &polygon[(i + 1) % polygon.len()];
+        let da = plane.dot(a.position);
+        let db = plane.dot(b.position);
+
+        if da >= 0.0 {
+            clipped.push(*a);
+        }
+        if (da >= 0.0) != (db >= 0.0) {
+            clipped.push(a.lerp(b, da / (da - db)));
+        }
+    }
+    clipped
+}
+
+/// Clip a triangle against the near and far plane and the guard band.
+///
+/// Returns the clipped convex polygon, `None` if nothing remains.
+pub fn clip_triangle(triangle: Vec<ClipVertex>) -> Option<Vec<ClipVertex>> {
+    let clip_planes = [
+        Vector4::new(0.0, 0.0, 1.0, 0.0),  // near: z >= 0
+        Vector4::new(0.0, 0.0, -1.0, 1.0), // far: z <= w
+        Vector4::new(1.0, 0.0, 0.0, GUARD_BAND),
+        Vector4::new(-1.0, 0.0, 0.0, GUARD_BAND),
+        Vector4::new(0.0, 1.0, 0.0, GUARD_BAND),
+        Vector4::new(0.0, -1.0, 0.0, GUARD_BAND),
+    ];
+
+    let mut polygon = triangle;
+    for plane in &clip_planes {
+        polygon = clip_polygon(&polygon, *plane);
+        if polygon.len() < 3 {
+            return None;
+        }
+    }
+    if polygon.iter().any(|v| v.position.w <= 0.0) {
+        return None;
+    }
+    Some(polygon)
+}
+
+/// Edge function in fixed point, positive on the inner side.
+pub fn edge(a: &ScreenVertex, b: &ScreenVertex, x: i64, y: i64) -> i64 {
+    (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)
+}
+
+pub fn is_top_left(a: &ScreenVertex, b: &ScreenVertex) -> bool {
+    let dx = b.x - a.x;
+    let dy = b.y - a.y;
+    dy < 0 || (dy == 0 && dx > 0)
+}
+
+/// Geometry pass state shared by all triangles.
+struct Rasterizer<'a> {
+    mesh: &'a MeshData,
+    instances: &'a [InstanceData],
+    materials: &'a Materials,
+    view: &'a ViewData,
+    camera_pos: Vector3<f32>,
+    width: u32,
+    height: u32,
+    samples: &'static [[i64; 2]],
+    // Depth and visibility buffer of each sample.
+    depth: Vec<Image<f32>>,
+    visibility: Vec<VisibilityBuffer>,
+}
+
+impl<'a> Rasterizer<'a> {
+    fn draw_triangle(
+        &mut self,
+        triangle: [ScreenVertex; 3],
+        draw: &Draw,
+        prim_id: u32,
+        vertex0: Vector3<f32>,
+    ) {
+        // No culling, orient the triangle counter clockwise in edge function space.
+        let [v0, mut v1, mut v2] = triangle;
+        let mut area = edge(&v0, &v1, v2.x, v2.y);
+        if area == 0 {
+            return;
+        }
+        if area < 0 {
+            ::std::mem::swap(&mut v1, &mut v2);
+            area = -area;
+        }
+
+        let bias = |a: &ScreenVertex, b: &ScreenVertex| if is_top_left(a, b) { 0 } else { -1 };
+        let bias0 = bias(&v1, &v2);
+        let bias1 = bias(&v2, &v0);
+        let bias2 = bias(&v0, &v1);
+
+        let pixel = 1i64 << SUBPIXEL_BITS;
+        let min_x = v0.x.min(v1.x).min(v2.x).max(0) / pixel;
+        let min_y = v0.y.min(v1.y).min(v2.y).max(0) / pixel;
+        let max_x = (v0.x.max(v1.x).max(v2.x) / pixel).min(self.width as i64 - 1);
+        let max_y = (v0.y.max(v1.y).max(v2.y) / pixel).min(self.height as i64 - 1);
+
+        // Barycentrics of a position, not normalized.
+        let edges = |x: i64, y: i64| {
+            [
+                edge(&v1, &v2, x, y),
+                edge(&v2, &v0, x, y),
+                edge(&v0, &v1, x, y),
+            ]
+        };
+
+        for py in min_y..max_y + 1 {
+            for px in min_x..max_x + 1 {
+                let cx = px * pixel + pixel / 2;
+                let cy = py * pixel + pixel / 2;
+
+                // Coverage and depth test (LESS) of each sample
+                let mut covered = Vec::new();
+                for (sample, offset) in self.samples.iter().enumerate() {
+                    let [w0, w1, w2] =
+                        edges(cx + offset[0] * pixel / 16, cy + offset[1] * pixel / 16);
+                    if w0 + bias0 < 0 || w1 + bias1 < 0 || w2 + bias2 < 0 {
+                        continue;
+                    }
+                    let l0 = w0 as f32 / area as f32;
+                    let l1 = w1 as f32 / area as f32;
+                    let l2 = w2 as f32 / area as f32;
+                    let z = l0 * v0.z + l1 * v1.z + l2 * v2.z;
+                    if z < self.depth[sample].get(px as _, py as _) {
+                        covered.push((sample, z));
+                    }
+                }
+                if covered.is_empty() {
+                    continue;
+                }
+
+                // Perspective correct attribute interpolation at the pixel center
+                let [w0, w1, w2] = edges(cx, cy);
+                let p0 = w0 as f32 / area as f32 * v0.inv_w;
+                let p1 = w1 as f32 / area as f32 * v1.inv_w;
+                let p2 = w2 as f32 / area as f32 * v2.inv_w;
+                let view_dir =
+                    (v0.attribute * p0 + v1.attribute * p1 + v2.attribute * p2) / (p0 + p1 + p2);
+
+                if let Some(texel) = self.shade(view_dir, vertex0, draw, prim_id) {
+                    for (sample, z) in covered {
+                        self.depth[sample].set(px as _, py as _, z);
+                        self.visibility[sample].set(px as _, py as _, texel);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Geometry pixel shader (`ps_main`), `None` if the pixel is discarded.
+    fn shade(
+        &self,
+        view_dir: Vector3<f32>,
+        vertex0: Vector3<f32>,
+        draw: &Draw,
+        prim_id: u32,
+    ) -> Option<[u16; 4]> {
+        let geometry = &draw.geometry;
+        let index0 = 3 * prim_id + geometry.base_index as u32;
+        let e0 = load_index(&self.mesh.indices, index0);
+        let e1 = load_index(&self.mesh.indices, index0 + 1);
+        let e2 = load_index(&self.mesh.indices, index0 + 2);
+
+        let world = Matrix4::from(load_instance(self.instances, draw.instance_id).world);
+        let vertex1 = load_position(self.mesh, geometry.base_vertex as u32 + e1);
+        let vertex1 = transform_position(&world, vertex1);
+        let vertex2 = load_position(self.mesh, geometry.base_vertex as u32 + e2);
+        let vertex2 = transform_position(&world, vertex2);
+
+        let barycentric =
+            raycast_triangle_barycentric(self.camera_pos, view_dir, vertex0, vertex1, vertex2);
+
+        // Alpha test
+        let material = self.materials.load_material(draw.material_id);
+        if material.alpha_mode == ALPHA_MODE_MASK {
+            let base_vertex = geometry.base_vertex as u32;
+            let indices = [e0, e1, e2];
+            let uv = interpolate_attribute(
+                self.mesh,
+                VertexAttribute::Uv0,
+                base_vertex,
+                indices,
+                barycentric,
+            );
+            let uv = [uv.x, uv.y];
+            let world_position =
+                vertex0 * barycentric.x + vertex1 * barycentric.y + vertex2 * barycentric.z;
+            let (uv_dx, uv_dy) = uv_gradients(
+                self.view,
+                [self.width, self.height],
+                world_position,
+                [vertex0, vertex1, vertex2],
+                self.mesh,
+                base_vertex,
+                indices,
+                uv,
+            );
+            if self.materials.alpha(&material, uv, uv_dx, uv_dy) < material.alpha_cutoff {
+                return None;
+            }
+        }
+
+        let [u, v] = pack::pack_barycentric_f16([barycentric.x, barycentric.y]);
+        Some([
+            prim_id.min(INVALID_ID as u32 - 1) as u16,
+            draw.instance_id.min(INVALID_ID as u32 - 1) as u16,
+            u,
+            v,
+        ])
+    }
+}
+
+/// Rasterize the draws in submission order into a visibility buffer.
+pub fn rasterize(
+    mesh: &MeshData,
+    instances: &[InstanceData],
+    materials: &Materials,
+    draws: &[Draw],
+    view: &ViewData,
+    width: u32,
+    height: u32,
+) -> VisibilityBuffer {
+    rasterize_multisampled(mesh, instances, materials, draws, view, width, height, 1).remove(0)
+}
+
+/// Rasterize the draws in submission order into a multisampled visibility buffer.
+///
+/// Returns the visibility buffer of each sample.
+pub fn rasterize_multisampled(
+    mesh: &MeshData,
+    instances: &[InstanceData],
+    materials: &Materials,
+    draws: &[Draw],
+    view: &ViewData,
+    width: u32,
+    height: u32,
+    samples: u32,
+) -> Vec<VisibilityBuffer> {
+    let view_proj = Matrix4::from(view.proj) * Matrix4::from(view.view);
+    let camera_pos = Vector3::new(view.position[0], view.position[1], view.position[2]);
+
+    let mut rasterizer = Rasterizer {
+        mesh,
+        instances,
+        materials,
+        view,
+        camera_pos,
+        width,
+        height,
+        samples: sample_positions(samples),
+        depth: vec![Image::new(width, height, 1.0); samples as usize],
+        visibility: vec![
+            Image::new(width, height, [INVALID_ID, INVALID_ID, 0, 0]);
+            samples as usize
+        ],
+    };
+
+    for draw in draws {
+        let geometry = &draw.geometry;
+        let world = Matrix4::from(load_instance(instances, draw.instance_id).world);
+
+        for prim_id in 0..(geometry.num_indices / 3) as u32 {
+            let index0 = geometry.base_index as u32 + 3 * prim_id;
+            let positions = [0, 1, 2].iter().map(|&i| {
+                let vertex = load_index(&mesh.indices, index0 + i) + geometry.base_vertex as u32;
+                transform_position(&world, load_position(mesh, vertex))
+            });
+
+            // Vertex shader (`vs_main`)
+            let triangle = positions
+                .map(|pos| ClipVertex {
+                    position: view_proj * pos.extend(1.0),
+                    attribute: pos - camera_pos,
+                })
+                .collect::<Vec<_>>();
+            let vertex0 = triangle[0].attribute + camera_pos;
+
+            let polygon = match clip_triangle(triangle) {
+                Some(polygon) => polygon,
+                None => continue,
+            };
+
+            let screen = polygon
+                .iter()
+                .map(|v| ScreenVertex::new(v, [0, 0], [width, height]))
+                .collect::<Vec<_>>();
+            for i in 1..screen.len() - 1 {
+                rasterizer.draw_triangle(
+                    [screen[0], screen[i], screen[i + 1]],
+                    draw,
+                    prim_id,
+                    vertex0,
+                );
+            }
+        }
+    }
+
+    rasterizer.visibility
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reference::pack::unpack_barycentric_f16;
+    use scene::geometry::VertexPos;
+    use specs::prelude::*;
+
+    const SIZE: u32 = 8;
+    const BACKGROUND: [u16; 4] = [INVALID_ID, INVALID_ID, 0, 0];
+
+    /// Mesh of independent triangles, clip space positions with identity transforms.
+    fn triangles(triangles: &[[[f32; 3]; 3]]) -> (MeshData, Vec<Draw>) {
+        let mut mesh = MeshData::new(3 * triangles.len(), 3 * triangles.len());
+        let material = World::new().create_entity().build();
+        let mut draws = Vec::new();
+        for (i, triangle) in triangles.iter().enumerate() {
+            for (j, position) in triangle.iter().enumerate() {
+                mesh.positions[3 * i + j] = VertexPos(*position);
+                mesh.indices[3 * i + j] = j as u32;
+            }
+            draws.push(Draw {
+                instance_id: i as u32 + 1,
+                geometry: Geometry {
+                    id: i,
+                    base_index: 3 * i,
+                    num_indices: 3,
+                    base_vertex: 3 * i,
+                    material,
+                },
+                material_id: 0,
+            });
+        }
+        (mesh, draws)
+    }
+
+    fn render(mesh: &MeshData, draws: &[Draw]) -> VisibilityBuffer {
+        let identity = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        let instances = vec![
+            InstanceData {
+                world: identity,
+                geometry_id: 0,
+                _alignment: [0; 3],
+            };
+            draws.len() + 1
+        ];
+        let materials = Materials {
+            data: Vec::new(),
+            textures: Vec::new(),
+        };
+        let view = ViewData {
+            view: identity,
+            proj: identity,
+            position: [0.0, 0.0, 0.0, 1.0],
+            _alignment: [0.0; 28],
+        };
+        rasterize(mesh, &instances, &materials, draws, &view, SIZE, SIZE)
+    }
+
+    // Quad covering the viewport, split along the diagonal from top-right to bottom-left.
+    const UPPER: [[f32; 3]; 3] = [[-1.0, 1.0, 0.5], [1.0, 1.0, 0.5], [-1.0, -1.0, 0.5]];
+    const LOWER: [[f32; 3]; 3] = [[1.0, 1.0, 0.5], [1.0, -1.0, 0.5], [-1.0, -1.0, 0.5]];
+
+    #[test]
+    fn shared_edge_covered_once() {
+        let (mesh, draws) = triangles(&[UPPER, LOWER]);
+        let visibility = render(&mesh, &draws);
+
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let [prim_id, instance_id, _, _] = visibility.get(x, y);
+                assert_eq!(prim_id, 0);
+                // Pixel centers on the diagonal belong to the triangle with the top-left edge.
+                let expected = if x + y < SIZE - 1 { 1 } else { 2 };
+                assert_eq!(instance_id, expected, "pixel ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn barycentrics_at_pixel_centers() {
+        let (mesh, draws) = triangles(&[UPPER]);
+        let visibility = render(&mesh, &draws);
+
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let texel = visibility.get(x, y);
+                if x + y >= SIZE - 1 {
+                    assert_eq!(texel, BACKGROUND, "pixel ({}, {})", x, y);
+                    continue;
+                }
+                // Vertices at the top-left, top-right and bottom-left corner.
+                let u = (x as f32 + 0.5) / SIZE as f32;
+                let v = (y as f32 + 0.5) / SIZE as f32;
+                let [b0, b1] = unpack_barycentric_f16([texel[2], texel[3]]);
+                assert!((b0 - (1.0 - u - v)).abs() < 1e-3, "pixel ({}, {})", x, y);
+                assert!((b1 - u).abs() < 1e-3, "pixel ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn depth_test_less() {
+        let near = [[-1.0, 1.0, 0.25], [1.0, 1.0, 0.25], [-1.0, -1.0, 0.25]];
+        let (mesh, draws) = triangles(&[near, UPPER, LOWER, UPPER]);
+        let visibility = render(&mesh, &draws);
+
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                // Equal depth of the repeated triangle fails the `LESS` test.
+                let expected = if x + y < SIZE - 1 { 1 } else { 3 };
+                assert_eq!(visibility.get(x, y)[1], expected, "pixel ({}, {})", x, y);
+            }
+        }
+    }
+}
@@ -0,0 +1,705 @@
+//! Lighting pass (`cs_lighting`) and display map (`ps_displaymap`).
+
+use cgmath::{ElementWise, InnerSpace, Matrix, Matrix3, Matrix4, SquareMatrix, Vector3};
+use ibl::{self, Environment, SPECULAR_LEVELS};
+use ltc;
+use pass::geometry::{ViewData, INVALID_ID};
+use pass::lighting::AreaLight;
+use reference::brdf::{self, Surface};
+use reference::cluster::{self, Clusters};
+use reference::material::Materials;
+use reference::raster::{
+    interpolate_attribute, load_draw_data, load_index, load_instance, load_position,
+    raycast_triangle_barycentric, transform_position,
+};
+use reference::shadow::{self, ShadowAtlas};
+use reference::{pack, Image, LightingBuffer, VisibilityBuffer};
+use scene::geometry::{InstanceData, MeshData, VertexAttribute};
+use scene::light::{Lights, AREA_SHAPE_DISK};
+use scene::material::INVALID_TEXTURE;
+use std::f32::consts::PI;
+
+/// Must match with `exposure` in `ps_displaymap`.
+const EXPOSURE: f32 = 8.0;
+
+/// `uv_gradients`, texture coordinate gradients along the pixel axes of a target
+/// with `extent`.
+pub fn uv_gradients(
+    view: &ViewData,
+    extent: [u32; 2],
+    world_position: Vector3<f32>,
+    vertices: [Vector3<f32>; 3],
+    mesh: &MeshData,
+    base_vertex: u32,
+    indices: [u32; 3],
+    uv: [f32; 2],
+) -> ([f32; 2], [f32; 2]) {
+    let view_matrix = Matrix4::from(view.view);
+    let view_rotation = Matrix3::from_cols(
+        view_matrix.x.truncate(),
+        view_matrix.y.truncate(),
+        view_matrix.z.truncate(),
+    );
+    let camera_pos = Vector3::new(view.position[0], view.position[1], view.position[2]);
+
+    // View direction scaled to unit depth in view space.
+    let dir = world_position - camera_pos;
+    let dir = dir / -(view_rotation * dir).z;
+
+    // Offset of the direction to the neighboring pixels.
+    let pixel_dx = view_rotation.transpose()
+        * Vector3::new(2.0 / (extent[0] as f32 * view.proj[0][0]), 0.0, 0.0);
+    let pixel_dy = view_rotation.transpose()
+        * Vector3::new(0.0, -2.0 / (extent[1] as f32 * view.proj[1][1]), 0.0);
+
+    let [vertex0, vertex1, vertex2] = vertices;
+    let gradient = |dir: Vector3<f32>| {
+        let barycentric = raycast_triangle_barycentric(camera_pos, dir, vertex0, vertex1, vertex2);
+        let neighbor = interpolate_attribute(
+            mesh,
+            VertexAttribute::Uv0,
+            base_vertex,
+            indices,
+            barycentric,
+        );
+        [neighbor.x - uv[0], neighbor.y - uv[1]]
+    };
+
+    (gradient(dir + pixel_dx), gradient(dir + pixel_dy))
+}
+
+/// `surface_normal`, shading normal from the vertex normal and the sampled normal map.
+pub fn surface_normal(
+    world: &Matrix4<f32>,
+    vertices: [Vector3<f32>; 3],
+    view_dir: Vector3<f32>,
+    mesh: &MeshData,
+    base_vertex: u32,
+    indices: [u32; 3],
+    barycentric: Vector3<f32>,
+    normal_map: Option<[f32; 4]>,
+) -> Vector3<f32> {
+    let transform_dir = |dir: Vector3<f32>| (world * dir.extend(0.0)).truncate();
+    let attribute =
+        |attribute| interpolate_attribute(mesh, attribute, base_vertex, indices, barycentric);
+
+    let normal = transform_dir(attribute(VertexAttribute::Normal).truncate());
+    if normal.magnitude2() == 0.0 {
+        let [vertex0, vertex1, vertex2] = vertices;
+        let normal = (vertex1 - vertex0).cross(vertex2 - vertex0);
+        let normal = if normal.dot(view_dir) < 0.0 {
+            -normal
+        } else {
+            normal
+        };
+        return normal.normalize();
+    }
+    let normal = normal.normalize();
+
+    let tangent = attribute(VertexAttribute::Tangent);
+    let tangent_dir = transform_dir(tangent.truncate());
+    let normal_map = match normal_map {
+        Some(normal_map) if tangent_dir.magnitude2() != 0.0 => normal_map,
+        _ => return normal,
+    };
+    let tangent_dir = (tangent_dir - normal * normal.dot(tangent_dir)).normalize();
+    let bitangent = normal.cross(tangent_dir) * if tangent.w < 0.0 { -1.0 } else { 1.0 };
+
+    let normal_x = normal_map[0] * 2.0 - 1.0;
+    let normal_y = normal_map[1] * 2.0 - 1.0;
+    let normal_z = (1.0 - normal_x * normal_x - normal_y * normal_y)
+        .max(0.0)
+        .min(1.0)
+        .sqrt();
+    (tangent_dir * normal_x + bitangent * normal_y + normal * normal_z).normalize()
+}
+
+/// `distance_attenuation`, inverse square law windowed to fall off smoothly to zero
+/// at `range`.
+pub fn distance_attenuation(dist_sq: f32, range: f32) -> f32 {
+    let ratio = dist_sq / (range * range);
+    let window = (1.0 - ratio * ratio).max(0.0).min(1.0);
+    window * window / dist_sq
+}
+
+/// Must match with `DISK_VERTICES` in `lighting.hlsl`.
+const DISK_VERTICES: u32 = 8;
+/// Must match with `DISK_RADIUS_SCALE` in `lighting.hlsl`.
+const DISK_RADIUS_SCALE: f32 = 1.0539;
+
+/// `area_light_polygon`, vertices of an area light relative to `origin`.
+pub fn area_light_polygon(light: &AreaLight, origin: Vector3<f32>) -> Vec<Vector3<f32>> {
+    let center = Vector3::from(light.position) - origin;
+    let axis_x = Vector3::from(light.axis_x);
+    let axis_y = Vector3::from(light.axis_y);
+
+    if light.shape == AREA_SHAPE_DISK {
+        return (0..DISK_VERTICES)
+            .map(|v| {
+                let angle = 2.0 * PI * v as f32 / DISK_VERTICES as f32;
+                center + (axis_x * angle.cos() + axis_y * angle.sin()) * DISK_RADIUS_SCALE
+            })
+            .collect();
+    }
+
+    vec![
+        center - axis_x - axis_y,
+        center + axis_x - axis_y,
+        center + axis_x + axis_y,
+        center - axis_x + axis_y,
+    ]
+}
+
+/// Reflected radiance of an area light towards `view`, `ltc` is the table entry of
+/// the surface roughness and view angle.
+pub fn area_lighting(
+    ltc: &ltc::LtcData,
+    surface: &Surface,
+    view: Vector3<f32>,
+    world_position: Vector3<f32>,
+    light: &AreaLight,
+) -> Vector3<f32> {
+    let f0 = surface.specular_f0();
+    let specular_albedo = f0 * ltc.magnitude + (Vector3::new(1.0, 1.0, 1.0) - f0) * ltc.fresnel;
+
+    let points = area_light_polygon(light, world_position);
+    let diffuse = ltc::evaluate(surface.normal, view, Matrix3::identity(), &points);
+    let specular = ltc::evaluate(surface.normal, view, ltc::inv_matrix(ltc), &points);
+    let luminance = Vector3::from(light.color) * light.luminance;
+
+    (surface.diffuse_albedo() * diffuse + specular_albedo * specular).mul_element_wise(luminance)
+}
+
+/// `environment_lighting`, reflected radiance of the environment towards `view`.
+pub fn environment_lighting(
+    environment: &Environment,
+    brdf_lut: &[[f32; 2]],
+    surface: &Surface,
+    view: Vector3<f32>,
+) -> Vector3<f32> {
+    let n_dot_v = surface.normal.dot(view).max(1e-4);
+    let reflected = surface.normal * (2.0 * surface.normal.dot(view)) - view;
+    let lod = surface.roughness.max(0.0).min(1.0) * (SPECULAR_LEVELS - 1) as f32;
+    let radiance = environment.specular.sample(reflected, lod);
+    let [scale, bias] = ibl::brdf_lut::lookup(brdf_lut, n_dot_v, surface.roughness);
+    let specular_albedo = surface.specular_f0() * scale + Vector3::new(bias, bias, bias);
+    let specular = radiance.mul_element_wise(specular_albedo);
+
+    let irradiance = ibl::sh::evaluate(&environment.irradiance, surface.normal);
+    let irradiance = Vector3::new(
+        irradiance.x.max(0.0),
+        irradiance.y.max(0.0),
+        irradiance.z.max(0.0),
+    );
+    let diffuse = surface.diffuse_albedo().mul_element_wise(irradiance) / PI;
+
+    (diffuse + specular) * environment.intensity
+}
+
+/// Lighting pass state shared by all pixels.
+struct Shading<'a> {
+    mesh: &'a MeshData,
+    instances: &'a [InstanceData],
+    materials: &'a Materials,
+    view: &'a ViewData,
+    lights: &'a Lights,
+    clusters: &'a Clusters,
+    shadow_atlas: &'a ShadowAtlas,
+    environment: &'a Environment,
+    ltc_table: Vec<ltc::LtcData>,
+    brdf_lut: Vec<[f32; 2]>,
+    view_matrix: Matrix4<f32>,
+    extent: [u32; 2],
+}
+
+impl<'a> Shading<'a> {
+    /// `shade`, lighting of the surface point stored in a visibility buffer sample
+    /// of `pixel`.
+    fn shade(&self, geometry: [u16; 4], pixel: [u32; 2]) -> Vector3<f32> {
+        let Shading {
+            mesh,
+            instances,
+            materials,
+            view,
+            lights,
+            clusters,
+            shadow_atlas,
+            environment,
+            ref ltc_table,
+            ref brdf_lut,
+            view_matrix,
+            extent,
+        } = *self;
+
+        let prim_id = geometry[0] as u32;
+        let instance_id = geometry[1] as u32;
+        if geometry[1] == INVALID_ID {
+            // Background, stays unlit.
+            return Vector3::new(0.0, 0.0, 0.0);
+        }
+
+        // Reconstruct triangle
+        let instance = load_instance(instances, instance_id);
+        let world = Matrix4::from(instance.world);
+        let draw_data = load_draw_data(mesh, instance.geometry_id);
+
+        let index0 = 3 * prim_id + draw_data.base_index;
+        let e0 = load_index(&mesh.indices, index0);
+        let e1 = load_index(&mesh.indices, index0 + 1);
+        let e2 = load_index(&mesh.indices, index0 + 2);
+
+        let vertex0 = transform_position(&world, load_position(mesh, draw_data.base_vertex + e0));
+        let vertex1 = transform_position(&world, load_position(mesh, draw_data.base_vertex + e1));
+        let vertex2 = transform_position(&world, load_position(mesh, draw_data.base_vertex + e2));
+
+        // Reconstruct barycentrics
+        let [bary_u, bary_v] = pack::unpack_barycentric_f16([geometry[2], geometry[3]]);
+        let bary_w = 1.0 - bary_u - bary_v;
+
+        let world_position = vertex0 * bary_u + vertex1 * bary_v + vertex2 * bary_w;
+
+        // Lights of the cluster
+        let view_depth = -(view_matrix * world_position.extend(1.0)).z;
+        let cluster = cluster::cluster_index(
+            &clusters.data,
+            cluster::pixel_cluster(&clusters.data, pixel, view_depth),
+        );
+        let (cluster_points, cluster_spots) = clusters.lights(cluster);
+        if clusters.data.debug_heatmap != 0 {
+            return cluster::light_heatmap((cluster_points.len() + cluster_spots.len()) as u32);
+        }
+
+        let indices = [e0, e1, e2];
+        let barycentric = Vector3::new(bary_u, bary_v, bary_w);
+        let base_vertex = draw_data.base_vertex;
+        let uv = interpolate_attribute(
+            mesh,
+            VertexAttribute::Uv0,
+            base_vertex,
+            indices,
+            barycentric,
+        );
+        let uv = [uv.x, uv.y];
+        let vertex_color = interpolate_attribute(
+            mesh,
+            VertexAttribute::Color,
+            base_vertex,
+            indices,
+            barycentric,
+        );
+
+        let (uv_dx, uv_dy) = uv_gradients(
+            view,
+            extent,
+            world_position,
+            [vertex0, vertex1, vertex2],
+            mesh,
+            base_vertex,
+            indices,
+            uv,
+        );
+
+        // Material
+        let material = materials.load_material(draw_data.material);
+        let sample = |texture_id| materials.sample(texture_id, uv, uv_dx, uv_dy, [1.0; 4]);
+        let albedo_texture = sample(material.albedo_texture);
+        let emissive_texture = sample(material.emissive_texture);
+        let roughness_metal = sample(material.roughness_metal_texture);
+        let albedo = Vector3::new(
+            material.albedo[0] * vertex_color.x * albedo_texture[0],
+            material.albedo[1] * vertex_color.y * albedo_texture[1],
+            material.albedo[2] * vertex_color.z * albedo_texture[2],
+        );
+        let emissive = Vector3::new(
+            material.emissive[0] * emissive_texture[0],
+            material.emissive[1] * emissive_texture[1],
+            material.emissive[2] * emissive_texture[2],
+        );
+
+        let camera_pos = Vector3::new(view.position[0], view.position[1], view.position[2]);
+        let view_dir = (camera_pos - world_position).normalize();
+
+        let normal_map = if material.normal_texture == INVALID_TEXTURE {
+            None
+        } else {
+            Some(sample(material.normal_texture))
+        };
+        let surface = Surface {
+            normal: surface_normal(
+                &world,
+                [vertex0, vertex1, vertex2],
+                view_dir,
+                mesh,
+                base_vertex,
+                indices,
+                barycentric,
+                normal_map,
+            ),
+            albedo,
+            roughness: material.roughness * roughness_metal[1],
+            metallic: material.metallic * roughness_metal[2],
+        };
+
+        // Accumulate lighting
+        // Point lights, illuminance (lux) from the windowed inverse square law.
+        let mut lighting = Vector3::new(0.0, 0.0, 0.0);
+        for &i in cluster_points {
+            let point_light = &lights.points[i as usize];
+            let v_light = Vector3::from(point_light.position) - world_position;
+            let dist_sq = v_light.magnitude2();
+            let illuminance = Vector3::from(point_light.color)
+                * (point_light.intensity * distance_attenuation(dist_sq, point_light.range))
+                * shadow::point_shadow(
+                    shadow_atlas,
+                    &lights.shadow_views,
+                    point_light.shadow,
+                    Vector3::from(point_light.position),
+                    world_position,
+                    surface.normal,
+                );
+            let brdf = brdf::evaluate(&surface, view_dir, v_light / dist_sq.sqrt());
+            lighting += brdf.mul_element_wise(illuminance);
+        }
+
+        // Directional lights
+        for directional_light in &lights.directionals {
+            let illuminance = Vector3::from(directional_light.color)
+                * directional_light.illuminance
+                * shadow::directional_shadow(
+                    shadow_atlas,
+                    &lights.shadow_views,
+                    directional_light.shadow,
+                    world_position,
+                    surface.normal,
+                    view_depth,
+                );
+            let l = -Vector3::from(directional_light.direction);
+            lighting += brdf::evaluate(&surface, view_dir, l).mul_element_wise(illuminance);
+        }
+
+        // Spot lights
+        for &s in cluster_spots {
+            let spot_light = &lights.spots[s as usize];
+            let v_light = Vector3::from(spot_light.position) - world_position;
+            let dist_sq = v_light.magnitude2();
+            let l = v_light / dist_sq.sqrt();
+            let cone = ((-l).dot(Vector3::from(spot_light.direction)) * spot_light.angle_scale
+                + spot_light.angle_offset)
+                .max(0.0)
+                .min(1.0);
+            let attenuation = distance_attenuation(dist_sq, spot_light.range);
+            let illuminance = Vector3::from(spot_light.color)
+                * (spot_light.intensity * cone * cone * attenuation)
+                * shadow::spot_shadow(
+                    shadow_atlas,
+                    &lights.shadow_views,
+                    spot_light.shadow,
+                    Vector3::from(spot_light.position),
+                    world_position,
+                    surface.normal,
+                );
+            lighting += brdf::evaluate(&surface, view_dir, l).mul_element_wise(illuminance);
+        }
+
+        // Area lights
+        if !lights.areas.is_empty() {
+            let ltc = ltc::lookup(&ltc_table, surface.roughness, surface.normal.dot(view_dir));
+            for area_light in &lights.areas {
+                lighting += area_lighting(&ltc, &surface, view_dir, world_position, area_light);
+            }
+        }
+
+        // Environment
+        lighting += environment_lighting(environment, &brdf_lut, &surface, view_dir);
+
+        lighting + emissive
+    }
+}
+
+/// Reconstruct the triangles from the visibility buffer and accumulate the lighting
+/// of the lights in the cluster of each pixel, shadowed by the shadow views of `lights`,
+/// and of the `environment`.
+///
+/// `visibility` holds the visibility buffer of each sample. Pixels are resolved by
+/// shading each unique triangle once, weighted by the number of samples it covers.
+///
+/// Outputs the light heatmap instead if enabled in the cluster data.
+pub fn shade(
+    visibility: &[VisibilityBuffer],
+    mesh: &MeshData,
+    instances: &[InstanceData],
+    materials: &Materials,
+    view: &ViewData,
+    lights: &Lights,
+    clusters: &Clusters,
+    shadow_atlas: &ShadowAtlas,
+    environment: &Environment,
+) -> LightingBuffer {
+    let (width, height) = (visibility[0].width, visibility[0].height);
+    let shading = Shading {
+        mesh,
+        instances,
+        materials,
+        view,
+        lights,
+        clusters,
+        shadow_atlas,
+        environment,
+        ltc_table: ltc::table(),
+        brdf_lut: ibl::brdf_lut::brdf_lut(),
+        view_matrix: Matrix4::from(view.view),
+        extent: [width, height],
+    };
+
+    let mut lighting_buffer = Image::new(width, height, [0; 4]);
+    for y in 0..height {
+        for x in 0..width {
+            let samples = visibility
+                .iter()
+                .map(|samples| samples.get(x, y))
+                .collect::<Vec<_>>();
+            let same_triangle = |a: &[u16; 4], b: &[u16; 4]| a[..2] == b[..2];
+
+            let mut lighting = Vector3::new(0.0, 0.0, 0.0);
+            for (s, sample) in samples.iter().enumerate() {
+                if samples[..s]
+                    .iter()
+                    .any(|other| same_triangle(other, sample))
+                {
+                    continue;
+                }
+                let coverage = samples[s..]
+                    .iter()
+                    .filter(|other| same_triangle(other, sample))
+                    .count();
+                lighting +=
+                    shading.shade(*sample, [x, y]) * (coverage as f32 / samples.len() as f32);
+            }
+
+            lighting_buffer.set(
+                x,
+                y,
+                [
+                    pack::f32_to_f16(lighting.x),
+                    pack::f32_to_f16(lighting.y),
+                    pack::f32_to_f16(lighting.z),
+                    0,
+                ],
+            );
+        }
+    }
+
+    lighting_buffer
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Tonemap the lighting buffer for `R8G8B8A8_UNORM_SRGB` output.
+pub fn display_map(lighting: &LightingBuffer) -> Image<[u8; 4]> {
+    let mut display = Image::new(lighting.width, lighting.height, [0; 4]);
+
+    for y in 0..lighting.height {
+        for x in 0..lighting.width {
+            let texel = lighting.get(x, y);
+            let mut output = [0xFF; 4];
+            for c in 0..3 {
+                let color = pack::f16_to_f32(texel[c]) * EXPOSURE;
+                let mapped = linear_to_srgb(color / (1.0 + color));
+                output[c] = (mapped.max(0.0).min(1.0) * 255.0).round() as u8;
+            }
+            display.set(x, y, output);
+        }
+    }
+
+    display
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pass::cluster::ClusterData;
+    use reference::raster::{self, Draw};
+    use scene::geometry::{DrawData, VertexPos};
+    use scene::light::AREA_SHAPE_RECT;
+    use scene::material::{MaterialData, ALPHA_MODE_OPAQUE};
+    use scene::Geometry;
+    use specs::prelude::*;
+
+    const IDENTITY: [[f32; 4]; 4] = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ];
+
+    fn emissive(color: [f32; 3]) -> MaterialData {
+        MaterialData {
+            albedo: [0.0; 4],
+            emissive: color,
+            roughness: 1.0,
+            metallic: 0.0,
+            alpha_cutoff: 0.0,
+            albedo_texture: INVALID_TEXTURE,
+            opacity_texture: INVALID_TEXTURE,
+            normal_texture: INVALID_TEXTURE,
+            roughness_metal_texture: INVALID_TEXTURE,
+            emissive_texture: INVALID_TEXTURE,
+            alpha_mode: ALPHA_MODE_OPAQUE,
+        }
+    }
+
+    #[test]
+    fn resolve_weighted_by_coverage() {
+        // Two triangles meeting at x = 0.75 of a single pixel, in clip space. The
+        // left one covers the samples 0, 2 and 3 of the 4x pattern, the right one
+        // sample 1.
+        let triangles = [
+            [[0.5, 3.0, 0.5], [0.5, -3.0, 0.5], [-5.0, 0.0, 0.5]],
+            [[0.5, 3.0, 0.5], [6.0, 0.0, 0.5], [0.5, -3.0, 0.5]],
+        ];
+        let mut mesh = MeshData::new(6, 6);
+        let material = World::new().create_entity().build();
+        let mut instances = Vec::new();
+        let mut draws = Vec::new();
+        for (i, triangle) in triangles.iter().enumerate() {
+            for (j, position) in triangle.iter().enumerate() {
+                mesh.positions[3 * i + j] = VertexPos(*position);
+                mesh.indices[3 * i + j] = j as u32;
+            }
+            mesh.draw_data.push(DrawData {
+                base_index: 3 * i as u32,
+                base_vertex: 3 * i as u32,
+                material: i as u32,
+            });
+            instances.push(InstanceData {
+                world: IDENTITY,
+                geometry_id: i as u32,
+                _alignment: [0; 3],
+            });
+            draws.push(Draw {
+                instance_id: i as u32,
+                geometry: Geometry {
+                    id: i,
+                    base_index: 3 * i,
+                    num_indices: 3,
+                    base_vertex: 3 * i,
+                    material,
+                },
+                material_id: i as u32,
+            });
+        }
+
+        let materials = Materials {
+            data: vec![emissive([1.0, 0.0, 0.0]), emissive([0.0, 1.0, 0.0])],
+            textures: Vec::new(),
+        };
+        let view = ViewData {
+            view: IDENTITY,
+            proj: IDENTITY,
+            position: [0.0, 0.0, 0.0, 1.0],
+            _alignment: [0.0; 28],
+        };
+        let lights = Lights {
+            points: Vec::new(),
+            directionals: Vec::new(),
+            spots: Vec::new(),
+            areas: Vec::new(),
+            shadow_views: Vec::new(),
+        };
+        let clusters = cluster::build(&ClusterData::new([1, 1], false), &view, &lights);
+        let environment = Environment::uniform([0.0; 3], 0.0);
+
+        let visibility =
+            raster::rasterize_multisampled(&mesh, &instances, &materials, &draws, &view, 1, 1, 4);
+        let instance_ids = visibility
+            .iter()
+            .map(|samples| samples.get(0, 0)[1])
+            .collect::<Vec<_>>();
+        assert_eq!(instance_ids, vec![0, 1, 0, 0]);
+
+        let lighting = shade(
+            &visibility,
+            &mesh,
+            &instances,
+            &materials,
+            &view,
+            &lights,
+            &clusters,
+            &Image::new(1, 1, 1.0),
+            &environment,
+        );
+        assert_eq!(
+            lighting.get(0, 0),
+            [pack::f32_to_f16(0.75), pack::f32_to_f16(0.25), 0, 0]
+        );
+    }
+
+    #[test]
+    fn area_light_matches_integrated_brdf() {
+        // 20cm square light 1m above the surface, facing down.
+        let light = AreaLight {
+            position: [0.3, 0.0, 1.0],
+            luminance: 1000.0,
+            color: [1.0, 1.0, 1.0],
+            shape: AREA_SHAPE_RECT,
+            axis_x: [0.1, 0.0, 0.0],
+            axis_y: [0.0, 0.1, 0.0],
+        };
+        let ltc_table = ltc::table();
+        let origin = Vector3::new(0.0, 0.0, 0.0);
+        let normal = Vector3::new(0.0, 0.0, 1.0);
+
+        // The fit is less accurate for grazing views.
+        for &(albedo, roughness, metallic, view, tolerance) in &[
+            (1.0, 0.5, 1.0, Vector3::new(-0.3, 0.0, 1.0), 0.02),
+            (1.0, 0.8, 1.0, Vector3::new(-1.0, 0.5, 0.5), 0.1),
+            (0.5, 0.3, 0.0, Vector3::new(-0.3, 0.0, 1.0), 0.02),
+            (0.5, 1.0, 0.0, Vector3::new(0.5, 0.0, 1.0), 0.02),
+        ] {
+            let surface = Surface {
+                normal,
+                albedo: Vector3::new(albedo, albedo, albedo),
+                roughness,
+                metallic,
+            };
+            let view = view.normalize();
+
+            // Midpoint rule over the area of the light.
+            const STEPS: u32 = 64;
+            let center = Vector3::from(light.position);
+            let (axis_x, axis_y) = (Vector3::from(light.axis_x), Vector3::from(light.axis_y));
+            let light_normal = axis_x.cross(axis_y).normalize();
+            let area = 4.0 * axis_x.magnitude() * axis_y.magnitude();
+            let mut expected = 0.0;
+            for i in 0..STEPS {
+                for j in 0..STEPS {
+                    let u = 2.0 * (i as f32 + 0.5) / STEPS as f32 - 1.0;
+                    let v = 2.0 * (j as f32 + 0.5) / STEPS as f32 - 1.0;
+                    let to_light = center + axis_x * u + axis_y * v - origin;
+                    let dist_sq = to_light.magnitude2();
+                    let l = to_light / dist_sq.sqrt();
+                    let solid_angle = light_normal.dot(l).abs() / dist_sq;
+                    expected += brdf::evaluate(&surface, view, l).x * solid_angle;
+                }
+            }
+            expected *= light.luminance * area / (STEPS * STEPS) as f32;
+
+            let ltc = ltc::lookup(&ltc_table, roughness, normal.dot(view));
+            let radiance = area_lighting(&ltc, &surface, view, origin, &light).x;
+            assert!(
+                (radiance - expected).abs() < tolerance * expected,
+                "roughness {}, metallic {}: {} != {}",
+                roughness,
+                metallic,
+                radiance,
+                expected
+            );
+        }
+    }
+}
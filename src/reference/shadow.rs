@@ -0,0 +1,311 @@
+//! Shadow map pass (`shadow_map.hlsl`) and shadow lookups of the lighting pass (`shadow.hlsl`).
+//!
+//! Shadow views are rasterized into their atlas tile with the rules of the geometry
+//! pass. Texture coordinate gradients of alpha tested pixels are the differences to
+//! the neighboring pixels. The comparison sampler filters the results of the four
+//! nearest texels bilinearly.
+
+use cgmath::{InnerSpace, Matrix4, Vector3, Vector4};
+use pass::shadow::{ShadowView, INVALID_SHADOW, NUM_CASCADES, SHADOW_ATLAS_SIZE};
+use reference::material::Materials;
+use reference::raster::{
+    clip_triangle, edge, is_top_left, load_attribute, load_index, load_instance, load_position,
+    transform_position, ClipVertex, Draw, ScreenVertex, SUBPIXEL_BITS,
+};
+use reference::Image;
+use scene::geometry::{InstanceData, MeshData, VertexAttribute};
+use scene::material::ALPHA_MODE_MASK;
+
+/// Must match with `shadow.hlsl`.
+const SHADOW_NORMAL_OFFSET: f32 = 1.5;
+const SHADOW_PCF_RADIUS: i32 = 1;
+
+/// Depth of all shadow views.
+pub type ShadowAtlas = Image<f32>;
+
+/// Shadow view state shared by all triangles.
+struct ShadowRasterizer<'a> {
+    materials: &'a Materials,
+    /// Tile of the view in the atlas.
+    offset: [u32; 2],
+    size: u32,
+    atlas: &'a mut ShadowAtlas,
+}
+
+impl<'a> ShadowRasterizer<'a> {
+    fn draw_triangle(&mut self, triangle: [ScreenVertex; 3], draw: &Draw) {
+        // No culling, orient the triangle counter clockwise in edge function space.
+        let [v0, mut v1, mut v2] = triangle;
+        let mut area = edge(&v0, &v1, v2.x, v2.y);
+        if area == 0 {
+            return;
+        }
+        if area < 0 {
+            ::std::mem::swap(&mut v1, &mut v2);
+            area = -area;
+        }
+
+        let bias = |a: &ScreenVertex, b: &ScreenVertex| if is_top_left(a, b) { 0 } else { -1 };
+        let bias0 = bias(&v1, &v2);
+        let bias1 = bias(&v2, &v0);
+        let bias2 = bias(&v0, &v1);
+
+        // Scissor to the tile
+        let pixel = 1i64 << SUBPIXEL_BITS;
+        let tile_min = [self.offset[0] as i64, self.offset[1] as i64];
+        let tile_max = [
+            tile_min[0] + self.size as i64 - 1,
+            tile_min[1] + self.size as i64 - 1,
+        ];
+        let min_x = (v0.x.min(v1.x).min(v2.x) / pixel).max(tile_min[0]);
+        let min_y = (v0.y.min(v1.y).min(v2.y) / pixel).max(tile_min[1]);
+        let max_x = (v0.x.max(v1.x).max(v2.x) / pixel).min(tile_max[0]);
+        let max_y = (v0.y.max(v1.y).max(v2.y) / pixel).min(tile_max[1]);
+
+        let material = self.materials.load_material(draw.material_id);
+        let alpha_tested = material.alpha_mode == ALPHA_MODE_MASK;
+
+        // Perspective correct texture coordinates at a sample position.
+        let uv_at = |sx: i64, sy: i64| {
+            let p0 = edge(&v1, &v2, sx, sy) as f32 / area as f32 * v0.inv_w;
+            let p1 = edge(&v2, &v0, sx, sy) as f32 / area as f32 * v1.inv_w;
+            let p2 = edge(&v0, &v1, sx, sy) as f32 / area as f32 * v2.inv_w;
+            let uv = (v0.attribute * p0 + v1.attribute * p1 + v2.attribute * p2) / (p0 + p1 + p2);
+            [uv.x, uv.y]
+        };
+
+        for py in min_y..max_y + 1 {
+            for px in min_x..max_x + 1 {
+                // Sample at pixel center
+                let sx = px * pixel + pixel / 2;
+                let sy = py * pixel + pixel / 2;
+
+                let w0 = edge(&v1, &v2, sx, sy);
+                let w1 = edge(&v2, &v0, sx, sy);
+                let w2 = edge(&v0, &v1, sx, sy);
+                if w0 + bias0 < 0 || w1 + bias1 < 0 || w2 + bias2 < 0 {
+                    continue;
+                }
+
+                // Depth test (LESS)
+                let z = (w0 as f32 * v0.z + w1 as f32 * v1.z + w2 as f32 * v2.z) / area as f32;
+                if !(z < self.atlas.get(px as _, py as _)) {
+                    continue;
+                }
+
+                // Alpha test (`ps_shadow`)
+                if alpha_tested {
+                    let uv = uv_at(sx, sy);
+                    let uv_x = uv_at(sx + pixel, sy);
+                    let uv_y = uv_at(sx, sy + pixel);
+                    let uv_dx = [uv_x[0] - uv[0], uv_x[1] - uv[1]];
+                    let uv_dy = [uv_y[0] - uv[0], uv_y[1] - uv[1]];
+                    if self.materials.alpha(&material, uv, uv_dx, uv_dy) < material.alpha_cutoff {
+                        continue;
+                    }
+                }
+
+                self.atlas.set(px as _, py as _, z);
+            }
+        }
+    }
+}
+
+/// Rasterize the draws into the atlas tiles of all shadow `views`.
+pub fn render(
+    mesh: &MeshData,
+    instances: &[InstanceData],
+    materials: &Materials,
+    draws: &[Draw],
+    views: &[ShadowView],
+) -> ShadowAtlas {
+    let mut atlas = Image::new(SHADOW_ATLAS_SIZE, SHADOW_ATLAS_SIZE, 1.0);
+
+    for view in views {
+        let view_proj = Matrix4::from(view.view_proj);
+        let viewport = view.viewport();
+        let offset = [viewport.x as u32, viewport.y as u32];
+        let size = viewport.width as u32;
+        let mut rasterizer = ShadowRasterizer {
+            materials,
+            offset,
+            size,
+            atlas: &mut atlas,
+        };
+
+        for draw in draws {
+            let geometry = &draw.geometry;
+            let world = Matrix4::from(load_instance(instances, draw.instance_id).world);
+
+            for prim_id in 0..(geometry.num_indices / 3) as u32 {
+                let index0 = geometry.base_index as u32 + 3 * prim_id;
+
+                // Vertex shader (`vs_shadow`)
+                let triangle = (0..3)
+                    .map(|i| {
+                        let vertex =
+                            load_index(&mesh.indices, index0 + i) + geometry.base_vertex as u32;
+                        let position = transform_position(&world, load_position(mesh, vertex));
+                        let uv = load_attribute(mesh, VertexAttribute::Uv0, vertex);
+                        ClipVertex {
+                            position: view_proj * position.extend(1.0),
+                            attribute: Vector3::new(uv.x, uv.y, 0.0),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                let polygon = match clip_triangle(triangle) {
+                    Some(polygon) => polygon,
+                    None => continue,
+                };
+
+                let screen = polygon
+                    .iter()
+                    .map(|v| ScreenVertex::new(v, offset, [size, size]))
+                    .collect::<Vec<_>>();
+                for i in 1..screen.len() - 1 {
+                    rasterizer.draw_triangle([screen[0], screen[i], screen[i + 1]], draw);
+                }
+            }
+        }
+    }
+
+    atlas
+}
+
+/// `SampleCmpLevelZero` with the `LESS_EQUAL` comparison sampler, clamped addressing.
+fn sample_compare(atlas: &ShadowAtlas, uv: [f32; 2], reference: f32) -> f32 {
+    let x = uv[0] * atlas.width as f32 - 0.5;
+    let y = uv[1] * atlas.height as f32 - 0.5;
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+
+    let compare = |x: i64, y: i64| {
+        let x = x.max(0).min(atlas.width as i64 - 1);
+        let y = y.max(0).min(atlas.height as i64 - 1);
+        if reference <= atlas.get(x as _, y as _) {
+            1.0
+        } else {
+            0.0
+        }
+    };
+    let (x0, y0) = (x0 as i64, y0 as i64);
+    let top = compare(x0, y0) * (1.0 - fx) + compare(x0 + 1, y0) * fx;
+    let bottom = compare(x0, y0 + 1) * (1.0 - fx) + compare(x0 + 1, y0 + 1) * fx;
+    top * (1.0 - fy) + bottom * fy
+}
+
+/// `sample_shadow`
+pub fn sample_shadow(
+    atlas: &ShadowAtlas,
+    views: &[ShadowView],
+    view_index: u32,
+    position: Vector3<f32>,
+    normal: Vector3<f32>,
+    distance: f32,
+) -> f32 {
+    let view = &views[view_index as usize];
+    let offset = (view.texel_size[0] + view.texel_size[1] * distance) * SHADOW_NORMAL_OFFSET;
+    let clip = Matrix4::from(view.view_proj) * (position + normal * offset).extend(1.0);
+    let ndc = clip.truncate() / clip.w;
+    if clip.w <= 0.0 || ndc.x.abs() > 1.0 || ndc.y.abs() > 1.0 || ndc.z > 1.0 {
+        return 1.0;
+    }
+
+    let texel = 1.0 / SHADOW_ATLAS_SIZE as f32;
+    let rect = Vector4::from(view.atlas_rect);
+    let tile_min = [rect.x + 0.5 * texel, rect.y + 0.5 * texel];
+    let tile_max = [rect.x + rect.z - 0.5 * texel, rect.y + rect.w - 0.5 * texel];
+    let uv = [
+        rect.x + (ndc.x * 0.5 + 0.5) * rect.z,
+        rect.y + (0.5 - ndc.y * 0.5) * rect.w,
+    ];
+
+    let mut visibility = 0.0;
+    for y in -SHADOW_PCF_RADIUS..SHADOW_PCF_RADIUS + 1 {
+        for x in -SHADOW_PCF_RADIUS..SHADOW_PCF_RADIUS + 1 {
+            let tap = [
+                (uv[0] + x as f32 * texel).max(tile_min[0]).min(tile_max[0]),
+                (uv[1] + y as f32 * texel).max(tile_min[1]).min(tile_max[1]),
+            ];
+            visibility += sample_compare(atlas, tap, ndc.z);
+        }
+    }
+    let taps = (2 * SHADOW_PCF_RADIUS + 1) as f32;
+    visibility / (taps * taps)
+}
+
+/// `cube_face`
+pub fn cube_face(dir: Vector3<f32>) -> u32 {
+    let a = Vector3::new(dir.x.abs(), dir.y.abs(), dir.z.abs());
+    if a.x >= a.y && a.x >= a.z {
+        return if dir.x >= 0.0 { 0 } else { 1 };
+    }
+    if a.y >= a.z {
+        return if dir.y >= 0.0 { 2 } else { 3 };
+    }
+    if dir.z >= 0.0 {
+        4
+    } else {
+        5
+    }
+}
+
+/// `point_shadow`
+pub fn point_shadow(
+    atlas: &ShadowAtlas,
+    views: &[ShadowView],
+    shadow: u32,
+    light_position: Vector3<f32>,
+    position: Vector3<f32>,
+    normal: Vector3<f32>,
+) -> f32 {
+    if shadow == INVALID_SHADOW {
+        return 1.0;
+    }
+    let dir = position - light_position;
+    sample_shadow(
+        atlas,
+        views,
+        shadow + cube_face(dir),
+        position,
+        normal,
+        dir.magnitude(),
+    )
+}
+
+/// `spot_shadow`
+pub fn spot_shadow(
+    atlas: &ShadowAtlas,
+    views: &[ShadowView],
+    shadow: u32,
+    light_position: Vector3<f32>,
+    position: Vector3<f32>,
+    normal: Vector3<f32>,
+) -> f32 {
+    if shadow == INVALID_SHADOW {
+        return 1.0;
+    }
+    let distance = (position - light_position).magnitude();
+    sample_shadow(atlas, views, shadow, position, normal, distance)
+}
+
+/// `directional_shadow`
+pub fn directional_shadow(
+    atlas: &ShadowAtlas,
+    views: &[ShadowView],
+    shadow: u32,
+    position: Vector3<f32>,
+    normal: Vector3<f32>,
+    view_depth: f32,
+) -> f32 {
+    if shadow == INVALID_SHADOW {
+        return 1.0;
+    }
+    for c in 0..NUM_CASCADES as u32 {
+        if view_depth < views[(shadow + c) as usize].split_depth {
+            return sample_shadow(atlas, views, shadow + c, position, normal, 0.0);
+        }
+    }
+    1.0
+}
@@ -0,0 +1,181 @@
+//! Dynamic resolution scaling.
+//!
+//! Adjusts the internal render scale from measured frame times. Frame times are
+//! averaged over a window of frames, the scale only changes if the average leaves
+//! the hysteresis band around the target frame time. The shading cost is assumed to
+//! be proportional to the number of pixels, i.e. quadratic in the scale.
+//!
+//! The controller is independent of the device and only operates on frame times.
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ResolutionConfig {
+    /// Frame time to achieve in milliseconds.
+    pub target_frame_time: f32,
+    /// Relative deviation from the target frame time tolerated without adjusting.
+    pub hysteresis: f32,
+    /// Number of frames averaged before each adjustment.
+    pub window: u32,
+    pub min_scale: f32,
+    pub max_scale: f32,
+    /// Granularity of the render scale.
+    pub step: f32,
+}
+
+impl Default for ResolutionConfig {
+    fn default() -> Self {
+        ResolutionConfig {
+            target_frame_time: 1000.0 / 60.0,
+            hysteresis: 0.1,
+            window: 30,
+            min_scale: 0.5,
+            max_scale: 1.0,
+            step: 0.05,
+        }
+    }
+}
+
+pub struct ResolutionController {
+    config: ResolutionConfig,
+    scale: f32,
+    // Accumulated frame times of the current window.
+    frame_time_sum: f32,
+    num_frames: u32,
+}
+
+impl ResolutionController {
+    pub fn new(config: ResolutionConfig, scale: f32) -> Self {
+        assert!(config.min_scale > 0.0 && config.min_scale <= config.max_scale);
+        assert!(config.window > 0);
+
+        ResolutionController {
+            config,
+            scale: clamp(scale, config.min_scale, config.max_scale),
+            frame_time_sum: 0.0,
+            num_frames: 0,
+        }
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Add the time of the last frame in milliseconds.
+    ///
+    /// Returns the render scale for the next frame.
+    pub fn update(&mut self, frame_time: f32) -> f32 {
+        self.frame_time_sum += frame_time;
+        self.num_frames += 1;
+        if self.num_frames < self.config.window {
+            return self.scale;
+        }
+
+        let average = self.frame_time_sum / self.num_frames as f32;
+        self.frame_time_sum = 0.0;
+        self.num_frames = 0;
+
+        let target = self.config.target_frame_time;
+        let deviation = (average - target).abs() / target;
+        if deviation <= self.config.hysteresis {
+            return self.scale;
+        }
+
+        let scale = self.scale * (target / average).sqrt();
+        let step = self.config.step;
+        // Snap to the step granularity, moving at least one step towards the target.
+        let scale = (scale / step).round() * step;
+        let scale = if average > target {
+            scale.min(self.scale - step)
+        } else {
+            scale.max(self.scale + step)
+        };
+        self.scale = clamp(scale, self.config.min_scale, self.config.max_scale);
+        self.scale
+    }
+}
+
+fn clamp(value: f32, min: f32, max: f32) -> f32 {
+    value.max(min).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: ResolutionConfig = ResolutionConfig {
+        target_frame_time: 10.0,
+        hysteresis: 0.1,
+        window: 4,
+        min_scale: 0.5,
+        max_scale: 1.0,
+        step: 0.05,
+    };
+
+    /// Feed a whole window of frames with the same frame time.
+    fn update_window(controller: &mut ResolutionController, frame_time: f32) -> f32 {
+        for _ in 0..CONFIG.window - 1 {
+            controller.update(frame_time);
+        }
+        controller.update(frame_time)
+    }
+
+    fn assert_scale(scale: f32, expected: f32) {
+        assert!((scale - expected).abs() < 1e-5, "{} != {}", scale, expected);
+    }
+
+    #[test]
+    fn average_over_window() {
+        let mut controller = ResolutionController::new(CONFIG, 1.0);
+        for _ in 0..CONFIG.window - 1 {
+            assert_eq!(controller.update(100.0), 1.0);
+        }
+        assert!(controller.update(100.0) < 1.0);
+
+        // Outliers within the window average out.
+        let mut controller = ResolutionController::new(CONFIG, 0.75);
+        for &frame_time in &[5.0, 15.0, 4.0, 16.0] {
+            assert_eq!(controller.update(frame_time), 0.75);
+        }
+    }
+
+    #[test]
+    fn hysteresis_band() {
+        let mut controller = ResolutionController::new(CONFIG, 0.75);
+        assert_eq!(update_window(&mut controller, 10.9), 0.75);
+        assert_eq!(update_window(&mut controller, 9.1), 0.75);
+        assert!(update_window(&mut controller, 11.5) < 0.75);
+        assert!(update_window(&mut controller, 8.5) > 0.7);
+    }
+
+    #[test]
+    fn snap_to_steps() {
+        // `sqrt(10 / 20) = 0.707` snapped down to the step.
+        let mut controller = ResolutionController::new(CONFIG, 1.0);
+        assert_scale(update_window(&mut controller, 20.0), 0.7);
+
+        // `sqrt(10 / 11.5) = 0.933` rounds to 0.95.
+        let mut controller = ResolutionController::new(CONFIG, 1.0);
+        assert_scale(update_window(&mut controller, 11.5), 0.95);
+
+        // Rounds back to the current scale, but moves by at least one step.
+        let narrow = ResolutionConfig {
+            hysteresis: 0.02,
+            ..CONFIG
+        };
+        let mut controller = ResolutionController::new(narrow, 0.8);
+        assert_scale(update_window(&mut controller, 10.3), 0.75);
+        let mut controller = ResolutionController::new(narrow, 0.8);
+        assert_scale(update_window(&mut controller, 9.7), 0.85);
+    }
+
+    #[test]
+    fn clamp_to_range() {
+        let mut controller = ResolutionController::new(CONFIG, 0.6);
+        assert_eq!(update_window(&mut controller, 1000.0), CONFIG.min_scale);
+        assert_eq!(update_window(&mut controller, 1000.0), CONFIG.min_scale);
+        assert_eq!(update_window(&mut controller, 0.1), CONFIG.max_scale);
+        assert_eq!(update_window(&mut controller, 0.1), CONFIG.max_scale);
+
+        assert_eq!(ResolutionController::new(CONFIG, 2.0).scale(), 1.0);
+        assert_eq!(ResolutionController::new(CONFIG, 0.1).scale(), 0.5);
+    }
+}
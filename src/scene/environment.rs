@@ -0,0 +1,42 @@
+//! Environment lighting of the scene.
+//!
+//! The preprocessed `ibl::Environment` is stored as resource of the scene assets,
+//! its GPU resources are recreated from it, see `SceneLoader::set_environment`.
+
+use backend::Backend;
+use descriptor::DescriptorRange;
+use memory::PlacedResource;
+
+/// Radiance of the default uniform environment, without an environment map.
+pub const DEFAULT_RADIANCE: [f32; 3] = [0.05, 0.05, 0.05];
+
+/// Descriptors of the environment table.
+pub const ENVIRONMENT_TABLE_SIZE: u32 = 3;
+
+/// GPU resources of the environment lighting.
+///
+/// The table has the SRVs of the specular cube map, the BRDF lookup table and the
+/// environment data in this order.
+pub struct EnvironmentBuffer<B: Backend> {
+    // Keep the resources alive, only accessed via `srvs`.
+    _specular: PlacedResource<B>,
+    _brdf_lut: PlacedResource<B>,
+    _data_buffer: B::Resource,
+    pub srvs: DescriptorRange,
+}
+
+impl<B: Backend> EnvironmentBuffer<B> {
+    pub fn new(
+        specular: PlacedResource<B>,
+        brdf_lut: PlacedResource<B>,
+        data_buffer: B::Resource,
+        srvs: DescriptorRange,
+    ) -> Self {
+        EnvironmentBuffer {
+            _specular: specular,
+            _brdf_lut: brdf_lut,
+            _data_buffer: data_buffer,
+            srvs,
+        }
+    }
+}
@@ -6,9 +6,12 @@
 //!          Fully contains all geometry information in the world.
 //!          Vertex information split into multiple deinterleaved attributes:
 //!             - Position: float3
+//!             - Attribute streams described by `VERTEX_STREAMS`:
+//!               normal, tangent, texture coordinates (2 sets) and color
 //!
 //!  * Geometry: Submesh **asset** defining a subslice of the index and vertex data
-//!              from the `Mesh` resource for CPU command submission.
+//!              from the `Mesh` resource for CPU command submission, shaded with
+//!              a single `Material`.
 //!
 //!  * DrawData: GPU representation of `Geometry` data. Unique **resource** allowing
 //!              to rebuild submeshes on the GPU.
@@ -16,61 +19,247 @@
 //!  * Instance: Instantiations of a `Geometry` associated with an entity.
 //!              Instance components are usually coupled with a `LocalTransform` for
 //!              positioning and orientation in the world.
+//!
+//!  * InstanceData: GPU representation of `Instance` data. Per-frame **resource**
+//!                  storing the world transformation of each instance, indexed by
+//!                  the instance id written into the visibility buffer.
 
+use backend::{Backend, Format};
+use descriptor::DescriptorRange;
+use memory::PlacedResource;
 use specs::prelude::*;
-use winapi::shared::dxgiformat::DXGI_FORMAT;
-use winapi::shared::minwindef::UINT;
-use winapi::um::d3d12::*;
-use wio::com::ComPtr;
+use std::mem;
 
 /// Vertex position attribute.
 // #[repr(hlsl)]
 #[repr(C)]
+#[derive(Copy, Clone, Debug)]
 pub struct VertexPos(pub [f32; 3]);
 
+/// Vertex attributes stored in separate streams next to the positions.
+///
+/// Index of the attribute stream, must match with `VERTEX_*` in `resources_triangle.hlsl`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VertexAttribute {
+    Normal = 0,
+    /// Tangent with the sign of the bitangent in `w`.
+    Tangent = 1,
+    Uv0 = 2,
+    Uv1 = 3,
+    Color = 4,
+}
+
+pub const NUM_VERTEX_STREAMS: usize = 5;
+
+/// Encoding of a vertex attribute stream.
+///
+/// Streams are bound as arrays of 32-bit words, missing components of loaded
+/// attributes default to `(0, 0, 0, 1)`.
+#[derive(Copy, Clone, Debug)]
+pub struct VertexStream {
+    pub attribute: VertexAttribute,
+    /// One of `R32G32Float`, `R32G32B32Float`, `R32G32B32A32Float` or `R8G8B8A8Unorm`.
+    pub format: Format,
+    /// Value of vertices without the attribute.
+    pub default: [f32; 4],
+}
+
+/// Layout of the vertex attribute streams, indexed by `VertexAttribute`.
+///
+/// Must match with `vertex_stream_formats` in `resources_triangle.hlsl`.
+pub const VERTEX_STREAMS: [VertexStream; NUM_VERTEX_STREAMS] = [
+    VertexStream {
+        attribute: VertexAttribute::Normal,
+        format: Format::R32G32B32Float,
+        default: [0.0, 0.0, 0.0, 1.0],
+    },
+    VertexStream {
+        attribute: VertexAttribute::Tangent,
+        format: Format::R32G32B32A32Float,
+        default: [0.0, 0.0, 0.0, 1.0],
+    },
+    VertexStream {
+        attribute: VertexAttribute::Uv0,
+        format: Format::R32G32Float,
+        default: [0.0, 0.0, 0.0, 1.0],
+    },
+    VertexStream {
+        attribute: VertexAttribute::Uv1,
+        format: Format::R32G32Float,
+        default: [0.0, 0.0, 0.0, 1.0],
+    },
+    VertexStream {
+        attribute: VertexAttribute::Color,
+        format: Format::R8G8B8A8Unorm,
+        default: [1.0, 1.0, 1.0, 1.0],
+    },
+];
+
+impl VertexStream {
+    /// Size of a single vertex attribute in 32-bit words.
+    pub fn stride(&self) -> usize {
+        self.format.texel_size() as usize / 4
+    }
+
+    /// Encode `value` into the `stride` words of `dst`.
+    pub fn encode(&self, value: [f32; 4], dst: &mut [u32]) {
+        match self.format {
+            Format::R8G8B8A8Unorm => {
+                dst[0] = value.iter().enumerate().fold(0, |packed, (i, c)| {
+                    let c = (c.max(0.0).min(1.0) * 255.0).round() as u32;
+                    packed | c << (8 * i)
+                });
+            }
+            _ => {
+                for (word, c) in dst.iter_mut().zip(value.iter()) {
+                    *word = c.to_bits();
+                }
+            }
+        }
+    }
+
+    /// Decode the attribute from the `stride` words of `src`.
+    pub fn decode(&self, src: &[u32]) -> [f32; 4] {
+        match self.format {
+            Format::R8G8B8A8Unorm => {
+                let mut value = [0.0; 4];
+                for (i, c) in value.iter_mut().enumerate() {
+                    *c = ((src[0] >> (8 * i)) & 0xFF) as f32 / 255.0;
+                }
+                value
+            }
+            _ => {
+                let mut value = [0.0, 0.0, 0.0, 1.0];
+                for (c, word) in value.iter_mut().zip(src.iter()) {
+                    *c = f32::from_bits(*word);
+                }
+                value
+            }
+        }
+    }
+}
+
 /// Mesh resource.
 ///
 /// Defining the whole scene geometry.
-pub struct Mesh {
-    pub vertex_buffer: ComPtr<ID3D12Resource>,
-    pub vertex_buffer_size: UINT,
-    pub vertex_stride: UINT,
-    pub index_buffer: ComPtr<ID3D12Resource>,
-    pub index_format: DXGI_FORMAT,
-    pub index_buffer_size: UINT,
+pub struct Mesh<B: Backend> {
+    pub vertex_buffer: PlacedResource<B>,
+    pub vertex_buffer_size: u32,
+    pub vertex_stride: u32,
+    pub index_buffer: PlacedResource<B>,
+    pub index_format: Format,
+    pub index_buffer_size: u32,
+    // Keeps the attribute streams alive, indexed by `VertexAttribute` and only
+    // accessed via SRVs.
+    _stream_buffers: Vec<PlacedResource<B>>,
     // Index
     // Vertex position
-    pub start_srvs: UINT,
+    // Draw data
+    // Vertex attribute streams
+    pub srvs: DescriptorRange,
+}
+
+impl<B: Backend> Mesh<B> {
+    /// Mesh of `VertexPos` positions and 32-bit indices.
+    pub fn new(
+        vertex_buffer: PlacedResource<B>,
+        vertex_buffer_size: u32,
+        index_buffer: PlacedResource<B>,
+        index_buffer_size: u32,
+        stream_buffers: Vec<PlacedResource<B>>,
+        srvs: DescriptorRange,
+    ) -> Self {
+        Mesh {
+            vertex_buffer,
+            vertex_buffer_size,
+            vertex_stride: mem::size_of::<VertexPos>() as _,
+            index_buffer,
+            index_format: Format::R32Uint,
+            index_buffer_size,
+            _stream_buffers: stream_buffers,
+            srvs,
+        }
+    }
 }
-unsafe impl Send for Mesh {}
-unsafe impl Sync for Mesh {}
 
 /// Submesh geometry asset.
 ///
 /// Geometry of usually one independent object.
+#[derive(Clone, Debug)]
 pub struct Geometry {
     pub id: usize,
     pub base_index: usize,
     pub num_indices: usize,
     pub base_vertex: usize,
+    pub material: Entity,
 }
 impl Component for Geometry {
     type Storage = HashMapStorage<Self>;
 }
 
+/// Per-geometry data.
+///
+/// Must match with `_DrawData` in `resources_triangle.hlsl`.
+// #[repr(hlsl)]
 #[repr(C)]
+#[derive(Copy, Clone, Debug)]
 pub struct DrawData {
     pub base_index: u32,
     pub base_vertex: u32,
+    /// Index into the material data.
+    pub material: u32,
+}
+
+/// CPU copy of the mesh and draw data.
+///
+/// Used by the reference renderer to reconstruct the scene without GPU readback.
+pub struct MeshData {
+    pub positions: Vec<VertexPos>,
+    /// Encoded attribute streams, indexed by `VertexAttribute`.
+    pub streams: Vec<Vec<u32>>,
+    pub indices: Vec<u32>,
+    pub draw_data: Vec<DrawData>,
+}
+
+impl MeshData {
+    /// Mesh data with all vertex attributes set to their stream default.
+    pub fn new(num_vertices: usize, num_indices: usize) -> Self {
+        let streams = VERTEX_STREAMS
+            .iter()
+            .enumerate()
+            .map(|(i, stream)| {
+                assert_eq!(stream.attribute as usize, i, "streams not indexed by attribute");
+                let mut default = vec![0; stream.stride()];
+                stream.encode(stream.default, &mut default);
+                (0..num_vertices)
+                    .flat_map(|_| default.iter().cloned())
+                    .collect()
+            })
+            .collect();
+
+        MeshData {
+            positions: vec![VertexPos([0.0; 3]); num_vertices],
+            streams,
+            indices: vec![0; num_indices],
+            draw_data: Vec::new(),
+        }
+    }
+
+    pub fn set_attribute(&mut self, attribute: VertexAttribute, vertex: usize, value: [f32; 4]) {
+        let stream = &VERTEX_STREAMS[attribute as usize];
+        let stride = stream.stride();
+        stream.encode(
+            value,
+            &mut self.streams[attribute as usize][vertex * stride..(vertex + 1) * stride],
+        );
+    }
 }
 
 /// Draw data resource.
 ///
 /// Connection between the geometry and mesh for the GPU.
 /// GPU representation of the gemeotry assets.
-pub struct DrawDataBuffer(pub ComPtr<ID3D12Resource>);
-unsafe impl Send for DrawDataBuffer {}
-unsafe impl Sync for DrawDataBuffer {}
+pub struct DrawDataBuffer<B: Backend>(pub PlacedResource<B>);
 
 /// Geometry instance.
 ///
@@ -81,3 +270,103 @@ pub struct Instance {
 impl Component for Instance {
     type Storage = VecStorage<Self>;
 }
+
+/// Per-instance data.
+///
+/// Must match with `_InstanceData` in `resources_triangle.hlsl`.
+// #[repr(hlsl)]
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct InstanceData {
+    pub world: [[f32; 4]; 4],
+    pub geometry_id: u32,
+    pub _alignment: [u32; 3],
+}
+
+/// Instance data resource.
+///
+/// Upload buffer with `num_instances` elements for each frame in flight.
+pub struct InstanceDataBuffer<B: Backend> {
+    pub buffer: B::Resource,
+    pub num_instances: usize,
+    pub srvs: DescriptorRange,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deinterleaved_streams() {
+        let mut mesh_data = MeshData::new(4, 6);
+        assert_eq!(mesh_data.positions.len(), 4);
+        assert_eq!(mesh_data.streams.len(), NUM_VERTEX_STREAMS);
+        let strides = VERTEX_STREAMS
+            .iter()
+            .map(|stream| stream.stride())
+            .collect::<Vec<_>>();
+        assert_eq!(strides, vec![3, 4, 2, 2, 1]);
+        for (stream, data) in VERTEX_STREAMS.iter().zip(&mesh_data.streams) {
+            assert_eq!(data.len(), 4 * stream.stride());
+            for vertex in data.chunks(stream.stride()) {
+                assert_eq!(stream.decode(vertex), stream.default);
+            }
+        }
+
+        mesh_data.set_attribute(VertexAttribute::Uv0, 2, [0.25, 0.75, 0.0, 1.0]);
+        mesh_data.set_attribute(VertexAttribute::Color, 1, [1.0, 0.5, 0.0, 1.0]);
+        let attribute = |mesh_data: &MeshData, attribute: VertexAttribute, vertex: usize| {
+            let stream = &VERTEX_STREAMS[attribute as usize];
+            let stride = stream.stride();
+            stream.decode(
+                &mesh_data.streams[attribute as usize][vertex * stride..(vertex + 1) * stride],
+            )
+        };
+        assert_eq!(
+            attribute(&mesh_data, VertexAttribute::Uv0, 2),
+            [0.25, 0.75, 0.0, 1.0]
+        );
+        assert_eq!(
+            attribute(&mesh_data, VertexAttribute::Uv0, 1),
+            [0.0, 0.0, 0.0, 1.0]
+        );
+        assert_eq!(
+            attribute(&mesh_data, VertexAttribute::Color, 1),
+            [1.0, 128.0 / 255.0, 0.0, 1.0]
+        );
+        assert_eq!(attribute(&mesh_data, VertexAttribute::Color, 2), [1.0; 4]);
+        // Other streams are untouched.
+        assert_eq!(
+            attribute(&mesh_data, VertexAttribute::Uv1, 2),
+            [0.0, 0.0, 0.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn shader_stream_formats() {
+        // `vertex_stream_formats` stores the number of words of float streams.
+        let source = include_str!("../../shaders/resources_triangle.hlsl");
+        let table = source
+            .split("vertex_stream_formats[NUM_VERTEX_STREAMS] = {")
+            .nth(1)
+            .and_then(|table| table.split("};").next())
+            .expect("missing stream format table");
+        let formats = table
+            .lines()
+            .filter_map(|line| line.trim().split(',').next())
+            .filter(|format| !format.is_empty())
+            .map(|format| match format {
+                "STREAM_UNORM4" => Format::R8G8B8A8Unorm,
+                "STREAM_FLOAT2" => Format::R32G32Float,
+                "STREAM_FLOAT3" => Format::R32G32B32Float,
+                "STREAM_FLOAT4" => Format::R32G32B32A32Float,
+                _ => panic!("unknown stream format {}", format),
+            })
+            .collect::<Vec<_>>();
+        let expected = VERTEX_STREAMS
+            .iter()
+            .map(|stream| stream.format)
+            .collect::<Vec<_>>();
+        assert_eq!(formats, expected);
+    }
+}
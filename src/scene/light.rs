@@ -1,19 +1,284 @@
+//! Scene lights.
+//!
+//! Lights are components positioned and oriented by the `LocalTransform` of their
+//! entity, pointing along the local -z axis. Each light type is stored in its own
+//! GPU buffer, indexed by the light ids of the scene. The buffers are rebuilt every
+//! frame from the current components, see `Scene::sync_lights`.
+//!
+//! Lights with a `ShadowCaster` component additionally get shadow views in the shadow
+//! atlas (point, spot and directional lights only).
+
+use backend::{Backend, Device, GpuDescriptor};
+use cgmath::{InnerSpace, Matrix4, Rad, Vector3};
+use descriptor::DescriptorRange;
+use frame::FrameContext;
+use pass::lighting::{self, LightData};
+use pass::shadow::{ShadowProjection, ShadowView, INVALID_SHADOW};
 use specs::prelude::*;
-use winapi::shared::minwindef::UINT;
-use winapi::um::d3d12::*;
-use wio::com::ComPtr;
+use std::f32::consts::PI;
+
+/// Must match with `AREA_SHAPE_*` in `light.hlsl`.
+pub const AREA_SHAPE_RECT: u32 = 0;
+pub const AREA_SHAPE_DISK: u32 = 1;
+
+/// Illuminance in lux below which point and spot lights are considered negligible.
+pub const LIGHT_CUTOFF: f32 = 0.01;
+
+/// Distance at which the illuminance of a light with `intensity` (cd) drops to the cutoff.
+pub fn light_range(intensity: f32) -> f32 {
+    (intensity / LIGHT_CUTOFF).sqrt()
+}
+
+/// Descriptors of a light table.
+pub const LIGHT_TABLE_SIZE: u32 = 6;
+
+/// Light tables of the frames in flight and the LTC table of the area lights.
+///
+/// Each frame in flight has a table with the SRVs of the point, directional, spot,
+/// area light buffers, the LTC table and the shadow views in this order.
+pub struct LightDataBuffer<B: Backend> {
+    // Keeps the LTC table alive, only accessed via `srvs`.
+    _ltc_buffer: B::Resource,
+    pub srvs: DescriptorRange,
+}
+
+impl<B: Backend> LightDataBuffer<B> {
+    pub fn new(ltc_buffer: B::Resource, srvs: DescriptorRange) -> Self {
+        LightDataBuffer {
+            _ltc_buffer: ltc_buffer,
+            srvs,
+        }
+    }
+
+    /// Copy the light buffers into the memory of `frame` and update its light table.
+    ///
+    /// Returns the light table of the frame.
+    pub fn upload(
+        &self,
+        device: &B::Device,
+        frame: &mut FrameContext<B>,
+        lights: &Lights,
+    ) -> GpuDescriptor {
+        let base = frame.index as u32 * LIGHT_TABLE_SIZE;
+        let views = [
+            frame.push_structured(&lights.points),
+            frame.push_structured(&lights.directionals),
+            frame.push_structured(&lights.spots),
+            frame.push_structured(&lights.areas),
+        ];
+        let shadow_views = frame.push_structured(&lights.shadow_views);
+        // The table was last used by the previous frame of this context, which the GPU
+        // has finished.
+        for (i, view) in views.iter().enumerate() {
+            device.create_shader_resource_view(
+                frame.memory(),
+                view,
+                self.srvs.cpu(base + i as u32),
+            );
+        }
+        device.create_shader_resource_view(frame.memory(), &shadow_views, self.srvs.cpu(base + 5));
+        self.srvs.gpu(base)
+    }
+}
+
+/// GPU data of all lights, indexed by the light ids of the scene.
+#[derive(Clone, Debug, Default)]
+pub struct Lights {
+    pub points: Vec<lighting::PointLight>,
+    pub directionals: Vec<lighting::DirectionalLight>,
+    pub spots: Vec<lighting::SpotLight>,
+    pub areas: Vec<lighting::AreaLight>,
+    /// Shadow views referenced by the lights.
+    pub shadow_views: Vec<ShadowView>,
+}
 
-pub struct LightDataBuffer {
-    pub point_buffer: ComPtr<ID3D12Resource>,
-    pub start_srvs: UINT,
+impl Lights {
+    pub fn light_data(&self) -> LightData {
+        LightData {
+            num_point_lights: self.points.len() as _,
+            num_directional_lights: self.directionals.len() as _,
+            num_spot_lights: self.spots.len() as _,
+            num_area_lights: self.areas.len() as _,
+        }
+    }
 }
-unsafe impl Send for LightDataBuffer {}
-unsafe impl Sync for LightDataBuffer {}
 
+fn position(transform: &Matrix4<f32>) -> [f32; 3] {
+    transform.w.truncate().into()
+}
+
+/// Normalized local -z axis.
+fn direction(transform: &Matrix4<f32>) -> [f32; 3] {
+    (-transform.z.truncate().normalize()).into()
+}
+
+/// Isotropic point light, positioned by its `LocalTransform`.
 pub struct PointLight {
+    /// Linear RGB color, scales the intensity per channel.
+    pub color: [f32; 3],
+    /// Luminous intensity in candela.
     pub intensity: f32,
+    /// Radius of influence, the attenuation falls off smoothly to zero.
+    pub range: f32,
+}
+
+impl PointLight {
+    /// Point light emitting the luminous power `lumen` uniformly in all directions.
+    ///
+    /// The range is derived from the intensity, see `light_range`.
+    pub fn from_luminous_power(color: [f32; 3], lumen: f32) -> Self {
+        let intensity = lumen / (4.0 * PI);
+        PointLight {
+            color,
+            intensity,
+            range: light_range(intensity),
+        }
+    }
+
+    pub fn data(&self, transform: &Matrix4<f32>) -> lighting::PointLight {
+        lighting::PointLight {
+            position: position(transform),
+            intensity: self.intensity,
+            color: self.color,
+            range: self.range,
+            shadow: INVALID_SHADOW,
+        }
+    }
+
+    pub fn shadow_projection(&self, transform: &Matrix4<f32>) -> ShadowProjection {
+        ShadowProjection::Cube {
+            position: position(transform).into(),
+            range: self.range,
+        }
+    }
 }
 
 impl Component for PointLight {
     type Storage = HashMapStorage<Self>;
 }
+
+/// Light infinitely far away (e.g. sun), only oriented by its `LocalTransform`.
+pub struct DirectionalLight {
+    pub color: [f32; 3],
+    /// Illuminance in lux on surfaces facing the light.
+    pub illuminance: f32,
+}
+
+impl DirectionalLight {
+    pub fn data(&self, transform: &Matrix4<f32>) -> lighting::DirectionalLight {
+        lighting::DirectionalLight {
+            direction: direction(transform),
+            illuminance: self.illuminance,
+            color: self.color,
+            shadow: INVALID_SHADOW,
+        }
+    }
+
+    pub fn shadow_projection(&self, transform: &Matrix4<f32>) -> ShadowProjection {
+        ShadowProjection::Cascades {
+            direction: direction(transform).into(),
+        }
+    }
+}
+
+impl Component for DirectionalLight {
+    type Storage = HashMapStorage<Self>;
+}
+
+/// Point light restricted to a cone.
+///
+/// The intensity falls off smoothly between the inner and outer cone.
+pub struct SpotLight {
+    pub color: [f32; 3],
+    /// Luminous intensity in candela inside the inner cone.
+    pub intensity: f32,
+    /// Half angle of the inner cone.
+    pub inner_angle: Rad<f32>,
+    /// Half angle of the outer cone.
+    pub outer_angle: Rad<f32>,
+    /// Radius of influence, the attenuation falls off smoothly to zero.
+    pub range: f32,
+}
+
+impl SpotLight {
+    pub fn data(&self, transform: &Matrix4<f32>) -> lighting::SpotLight {
+        let cos_outer = self.outer_angle.0.cos();
+        let cos_inner = self.inner_angle.0.cos().max(cos_outer);
+        let angle_scale = 1.0 / (cos_inner - cos_outer).max(1e-4);
+        lighting::SpotLight {
+            position: position(transform),
+            intensity: self.intensity,
+            color: self.color,
+            angle_scale,
+            direction: direction(transform),
+            angle_offset: -cos_outer * angle_scale,
+            range: self.range,
+            shadow: INVALID_SHADOW,
+            _alignment: [0.0; 2],
+        }
+    }
+
+    pub fn shadow_projection(&self, transform: &Matrix4<f32>) -> ShadowProjection {
+        ShadowProjection::Perspective {
+            position: position(transform).into(),
+            direction: direction(transform).into(),
+            angle: self.outer_angle,
+            range: self.range,
+        }
+    }
+}
+
+impl Component for SpotLight {
+    type Storage = HashMapStorage<Self>;
+}
+
+/// Shape of an area light in the local xy-plane, centered at the origin.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AreaShape {
+    Rect { width: f32, height: f32 },
+    Disk { radius: f32 },
+}
+
+/// One-sided emissive surface, evaluated with linearly transformed cosines.
+pub struct AreaLight {
+    pub color: [f32; 3],
+    /// Luminance in cd/m².
+    pub luminance: f32,
+    pub shape: AreaShape,
+}
+
+impl AreaLight {
+    /// The size of the shape is independent of the scaling of the transform.
+    pub fn data(&self, transform: &Matrix4<f32>) -> lighting::AreaLight {
+        let (shape, extent_x, extent_y) = match self.shape {
+            AreaShape::Rect { width, height } => (AREA_SHAPE_RECT, 0.5 * width, 0.5 * height),
+            AreaShape::Disk { radius } => (AREA_SHAPE_DISK, radius, radius),
+        };
+        let axis = |axis: Vector3<f32>, extent: f32| (axis.normalize() * extent).into();
+        lighting::AreaLight {
+            position: position(transform),
+            luminance: self.luminance,
+            color: self.color,
+            shape,
+            axis_x: axis(transform.x.truncate(), extent_x),
+            axis_y: axis(transform.y.truncate(), extent_y),
+        }
+    }
+}
+
+impl Component for AreaLight {
+    type Storage = HashMapStorage<Self>;
+}
+
+/// Renders shadow maps for the point, spot or directional light of its entity.
+#[derive(Copy, Clone, Debug)]
+pub struct ShadowCaster {
+    /// Width and height in texels of each shadow view (cube face, cascade).
+    ///
+    /// Lowered if the views of all lights don't fit into the shadow atlas.
+    pub resolution: u32,
+}
+
+impl Component for ShadowCaster {
+    type Storage = HashMapStorage<Self>;
+}
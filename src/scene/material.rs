@@ -0,0 +1,143 @@
+//! Scene materials.
+//!
+//!  * Material: Surface description **asset** referenced by `Geometry`, imported from
+//!              the model materials (e.g. MTL files). Textures are `Texture` assets.
+//!
+//!  * MaterialData: GPU representation of `Material` data. Unique **resource**
+//!                  indexed by the material id stored in the `DrawData`.
+//!
+//! Textures are accessed bindless via the texture view table of the scene, the
+//! material data stores the index of the `TextureView` of each texture.
+
+use backend::Backend;
+use descriptor::DescriptorRange;
+use memory::PlacedResource;
+use scene::TextureView;
+use specs::prelude::*;
+
+/// Texture index of materials without the respective texture.
+///
+/// Must match with `INVALID_TEXTURE` in `material.hlsl`.
+pub const INVALID_TEXTURE: u32 = !0;
+
+/// Must match with `ALPHA_MODE_*` in `material.hlsl`.
+pub const ALPHA_MODE_OPAQUE: u32 = 0;
+pub const ALPHA_MODE_MASK: u32 = 1;
+pub const ALPHA_MODE_BLEND: u32 = 2;
+
+/// Handling of the material alpha.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AlphaMode {
+    Opaque,
+    /// Surface discarded if the alpha is below `cutoff`.
+    Mask { cutoff: f32 },
+    /// Currently rendered opaque, the visibility buffer only stores a single surface.
+    Blend,
+}
+
+/// Material asset.
+#[derive(Clone, Debug)]
+pub struct Material {
+    /// Linear base color and alpha.
+    pub albedo: [f32; 4],
+    pub albedo_texture: Option<Entity>,
+    /// Alpha in the red channel, combined with the albedo alpha.
+    pub opacity_texture: Option<Entity>,
+    /// Tangent space normal map.
+    pub normal_texture: Option<Entity>,
+    pub roughness: f32,
+    pub metallic: f32,
+    /// Roughness in the green and metallic in the blue channel.
+    pub roughness_metal_texture: Option<Entity>,
+    /// Linear emitted radiance.
+    pub emissive: [f32; 3],
+    pub emissive_texture: Option<Entity>,
+    pub alpha_mode: AlphaMode,
+}
+impl Component for Material {
+    type Storage = HashMapStorage<Self>;
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material {
+            albedo: [1.0; 4],
+            albedo_texture: None,
+            opacity_texture: None,
+            normal_texture: None,
+            roughness: 1.0,
+            metallic: 0.0,
+            roughness_metal_texture: None,
+            emissive: [0.0; 3],
+            emissive_texture: None,
+            alpha_mode: AlphaMode::Opaque,
+        }
+    }
+}
+
+impl Material {
+    /// GPU representation referencing the textures by their view index.
+    pub fn data(&self, views: &ReadStorage<TextureView>) -> MaterialData {
+        let texture = |texture: Option<Entity>| {
+            texture
+                .and_then(|texture| views.get(texture))
+                .map(|view| view.id as u32)
+                .unwrap_or(INVALID_TEXTURE)
+        };
+        let (alpha_mode, alpha_cutoff) = match self.alpha_mode {
+            AlphaMode::Opaque => (ALPHA_MODE_OPAQUE, 0.0),
+            AlphaMode::Mask { cutoff } => (ALPHA_MODE_MASK, cutoff),
+            AlphaMode::Blend => (ALPHA_MODE_BLEND, 0.0),
+        };
+
+        MaterialData {
+            albedo: self.albedo,
+            emissive: self.emissive,
+            roughness: self.roughness,
+            metallic: self.metallic,
+            alpha_cutoff,
+            albedo_texture: texture(self.albedo_texture),
+            opacity_texture: texture(self.opacity_texture),
+            normal_texture: texture(self.normal_texture),
+            roughness_metal_texture: texture(self.roughness_metal_texture),
+            emissive_texture: texture(self.emissive_texture),
+            alpha_mode,
+        }
+    }
+}
+
+/// Per-material data.
+///
+/// Must match with `_MaterialData` in `material.hlsl`.
+// #[repr(hlsl)]
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MaterialData {
+    pub albedo: [f32; 4],
+    pub emissive: [f32; 3],
+    pub roughness: f32,
+    pub metallic: f32,
+    pub alpha_cutoff: f32,
+    pub albedo_texture: u32,
+    pub opacity_texture: u32,
+    pub normal_texture: u32,
+    pub roughness_metal_texture: u32,
+    pub emissive_texture: u32,
+    pub alpha_mode: u32,
+}
+
+/// Material data resource.
+pub struct MaterialDataBuffer<B: Backend> {
+    // Keeps the material data alive, only accessed via `srvs`.
+    _buffer: PlacedResource<B>,
+    pub srvs: DescriptorRange,
+}
+
+impl<B: Backend> MaterialDataBuffer<B> {
+    pub fn new(buffer: PlacedResource<B>, srvs: DescriptorRange) -> Self {
+        MaterialDataBuffer {
+            _buffer: buffer,
+            srvs,
+        }
+    }
+}
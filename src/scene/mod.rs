@@ -1,92 +1,340 @@
 use assimp;
 use assimp::import::Importer;
 use assimp_sys;
+use backend::*;
 use cgmath::*;
-use engine::{self, Engine};
-use image;
-use pass;
+use descriptor::DescriptorRange;
+use engine::Engine;
+use ibl;
+use ltc;
+use memory::PlacedResource;
+use pass::geometry::ViewData;
+use pass::shadow::{self, ShadowProjection, ShadowRequest};
 use specs::prelude::*;
 use std::collections::HashMap;
-use std::path::Path;
-use std::{mem, ptr, slice};
-use winapi::shared::dxgiformat::*;
-use winapi::shared::dxgitype::DXGI_SAMPLE_DESC;
-use winapi::shared::minwindef::UINT;
-use winapi::um::d3d12::*;
-use wio::com::ComPtr;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::{f32, mem, ptr, slice};
+use texture::{self, ImportOptions, TextureData, TextureUsage};
 
 pub mod camera;
+pub mod environment;
 pub mod geometry;
 pub mod light;
+pub mod material;
 pub mod transform;
 
 pub use self::camera::Camera;
 pub use self::geometry::{Geometry, Instance, Mesh};
+pub use self::material::Material;
 pub use self::transform::LocalTransform;
 
-pub struct Scene {
+pub struct Scene<B: Backend> {
     pub world: World,
     pub assets: World,
 
     pub texture_srvs: TextureViewGroup,
+    pub materials: HashMap<Entity, usize>,
     pub point_lights: HashMap<Entity, usize>,
+    pub directional_lights: HashMap<Entity, usize>,
+    pub spot_lights: HashMap<Entity, usize>,
+    pub area_lights: HashMap<Entity, usize>,
+    pub instances: HashMap<Entity, usize>,
+
+    _backend: PhantomData<B>,
 }
 
-impl Scene {
+impl<B: Backend> Scene<B> {
     pub fn new() -> Self {
         let mut world = World::new();
         world.register::<camera::Camera>();
         world.register::<transform::LocalTransform>();
         world.register::<geometry::Instance>();
         world.register::<light::PointLight>();
+        world.register::<light::DirectionalLight>();
+        world.register::<light::SpotLight>();
+        world.register::<light::AreaLight>();
+        world.register::<light::ShadowCaster>();
 
         let mut assets = World::new();
         assets.register::<geometry::Geometry>();
-        assets.register::<Texture>();
+        assets.register::<Texture<B>>();
         assets.register::<TextureView>();
+        assets.register::<material::Material>();
 
         Scene {
             world,
             assets,
-            texture_srvs: TextureViewGroup { start_id: 0 },
+            texture_srvs: TextureViewGroup { srvs: None },
+            materials: HashMap::new(),
             point_lights: HashMap::new(),
+            directional_lights: HashMap::new(),
+            spot_lights: HashMap::new(),
+            area_lights: HashMap::new(),
+            instances: HashMap::new(),
+            _backend: PhantomData,
         }
     }
 
-    pub fn unload(&mut self) {
+    /// Remove all entities and assets.
+    ///
+    /// Textures and the texture table are released to the engine as they may still
+    /// be accessed by the frames in flight.
+    pub fn unload(&mut self, engine: &mut Engine<B>) {
+        if let Some(srvs) = self.texture_srvs.srvs.take() {
+            engine.release(srvs);
+        }
+        {
+            let entities = self.assets.entities();
+            let mut textures = self.assets.write_storage::<Texture<B>>();
+            for e in (&*entities).join() {
+                if let Some(texture) = textures.remove(e) {
+                    engine.release(texture);
+                }
+            }
+        }
+
         self.world.delete_all();
         self.assets.delete_all();
+        self.materials.clear();
+        self.point_lights.clear();
+        self.directional_lights.clear();
+        self.spot_lights.clear();
+        self.area_lights.clear();
+        self.instances.clear();
+
+        // The remaining descriptors are owned by the scene resources (mesh, light and
+        // instance data) and freed once these get replaced by the next load.
+    }
 
-        // TODO: free all descriptors
+    /// Write the current world transforms of all instances, indexed by instance id.
+    pub fn write_instance_data(&self, data: &mut [geometry::InstanceData]) {
+        let transforms = self.world.read_storage::<LocalTransform>();
+        let instances = self.world.read_storage::<Instance>();
+        let geometries = self.assets.read_storage::<Geometry>();
+        let entities = self.world.entities();
+
+        for (e, transform, instance) in (&*entities, &transforms, &instances).join() {
+            let id = self.instances[&e];
+            data[id] = geometry::InstanceData {
+                world: transform.world_transform(&transforms).into(),
+                geometry_id: geometries.get(instance.geometry).unwrap().id as _,
+                _alignment: [0; 3],
+            };
+        }
     }
-}
 
-/// Temporary resources created during resource upload.
-/// Can be destroyed once the upload is complete.
-pub struct UploadResources {
-    resources: Vec<ComPtr<ID3D12Resource>>,
+    /// Update the light ids to the light components currently in the world.
+    ///
+    /// New lights are appended, ids of removed lights are reused by moving the light
+    /// with the highest id. Ids of each light type stay compact in `0..num_lights`.
+    /// Lights require a `LocalTransform` to be positioned.
+    pub fn sync_lights(&mut self) {
+        fn sync_ids<C: Component>(world: &World, ids: &mut HashMap<Entity, usize>) {
+            let transforms = world.read_storage::<LocalTransform>();
+            let lights = world.read_storage::<C>();
+            let entities = world.entities();
+
+            let removed = ids
+                .keys()
+                .cloned()
+                .filter(|&e| {
+                    !entities.is_alive(e) || lights.get(e).is_none() || transforms.get(e).is_none()
+                })
+                .collect::<Vec<_>>();
+            for e in removed {
+                let id = ids.remove(&e).unwrap();
+                let last = ids.len();
+                if id != last {
+                    let moved = ids
+                        .iter()
+                        .find(|&(_, &other)| other == last)
+                        .map(|(&moved, _)| moved)
+                        .unwrap();
+                    ids.insert(moved, id);
+                }
+            }
+
+            for (e, _, _) in (&*entities, &transforms, &lights).join() {
+                if !ids.contains_key(&e) {
+                    let id = ids.len();
+                    ids.insert(e, id);
+                }
+            }
+        }
+
+        sync_ids::<light::PointLight>(&self.world, &mut self.point_lights);
+        sync_ids::<light::DirectionalLight>(&self.world, &mut self.directional_lights);
+        sync_ids::<light::SpotLight>(&self.world, &mut self.spot_lights);
+        sync_ids::<light::AreaLight>(&self.world, &mut self.area_lights);
+    }
+
+    /// GPU data of all lights at their current world transforms, indexed by light id.
+    ///
+    /// Shadow views are allocated for all shadow casters, cascades follow `view`.
+    /// Requires the light ids to be in sync with the world, see `sync_lights`.
+    pub fn light_data(&self, view: &ViewData) -> light::Lights {
+        fn collect<C, T, F>(world: &World, ids: &HashMap<Entity, usize>, light_data: F) -> Vec<T>
+        where
+            C: Component,
+            F: Fn(&C, &Matrix4<f32>) -> T,
+        {
+            let transforms = world.read_storage::<LocalTransform>();
+            let lights = world.read_storage::<C>();
+            let entities = world.entities();
+
+            let mut data = (&*entities, &transforms, &lights)
+                .join()
+                .map(|(e, transform, light)| {
+                    let transform = transform.world_transform(&transforms);
+                    (ids[&e], light_data(light, &transform))
+                })
+                .collect::<Vec<_>>();
+            data.sort_by_key(|&(id, _)| id);
+            data.into_iter().map(|(_, data)| data).collect()
+        }
+
+        // Shadow requests of the casters, ordered by light id.
+        fn collect_shadows<C, F>(
+            world: &World,
+            ids: &HashMap<Entity, usize>,
+            projection: F,
+        ) -> Vec<(usize, ShadowRequest)>
+        where
+            C: Component,
+            F: Fn(&C, &Matrix4<f32>) -> ShadowProjection,
+        {
+            let transforms = world.read_storage::<LocalTransform>();
+            let lights = world.read_storage::<C>();
+            let casters = world.read_storage::<light::ShadowCaster>();
+            let entities = world.entities();
+
+            let mut requests = (&*entities, &transforms, &lights, &casters)
+                .join()
+                .map(|(e, transform, light, caster)| {
+                    let transform = transform.world_transform(&transforms);
+                    let request = ShadowRequest {
+                        projection: projection(light, &transform),
+                        resolution: caster.resolution,
+                    };
+                    (ids[&e], request)
+                })
+                .collect::<Vec<_>>();
+            requests.sort_by_key(|&(id, _)| id);
+            requests
+        }
+
+        let mut lights = light::Lights {
+            points: collect(&self.world, &self.point_lights, light::PointLight::data),
+            directionals: collect(
+                &self.world,
+                &self.directional_lights,
+                light::DirectionalLight::data,
+            ),
+            spots: collect(&self.world, &self.spot_lights, light::SpotLight::data),
+            areas: collect(&self.world, &self.area_lights, light::AreaLight::data),
+            shadow_views: Vec::new(),
+        };
+
+        let directional_shadows = collect_shadows(
+            &self.world,
+            &self.directional_lights,
+            light::DirectionalLight::shadow_projection,
+        );
+        let spot_shadows = collect_shadows(
+            &self.world,
+            &self.spot_lights,
+            light::SpotLight::shadow_projection,
+        );
+        let point_shadows = collect_shadows(
+            &self.world,
+            &self.point_lights,
+            light::PointLight::shadow_projection,
+        );
+
+        // Earlier requests are preferred if the atlas is full.
+        let requests = directional_shadows
+            .iter()
+            .chain(&spot_shadows)
+            .chain(&point_shadows)
+            .map(|&(_, request)| request)
+            .collect::<Vec<_>>();
+        let (shadow_views, first_views) = shadow::shadow_views(&requests, view);
+        let mut first_views = first_views.into_iter();
+        for &(id, _) in &directional_shadows {
+            lights.directionals[id].shadow = first_views.next().unwrap();
+        }
+        for &(id, _) in &spot_shadows {
+            lights.spots[id].shadow = first_views.next().unwrap();
+        }
+        for &(id, _) in &point_shadows {
+            lights.points[id].shadow = first_views.next().unwrap();
+        }
+        lights.shadow_views = shadow_views;
+
+        lights
+    }
+
+    /// Material data of all loaded materials, indexed by material id.
+    pub fn material_data(&self) -> Vec<material::MaterialData> {
+        let materials = self.assets.read_storage::<Material>();
+        let views = self.assets.read_storage::<TextureView>();
+        let entities = self.assets.entities();
+
+        let mut data = (&*entities, &materials)
+            .join()
+            .map(|(e, material)| (self.materials[&e], material.data(&views)))
+            .collect::<Vec<_>>();
+        data.sort_by_key(|&(id, _)| id);
+        data.into_iter().map(|(_, data)| data).collect()
+    }
 }
 
-pub struct SceneLoader<'a> {
-    upload_cmd_list: Option<ComPtr<ID3D12GraphicsCommandList>>,
-    engine: &'a mut Engine,
-    scene: &'a mut Scene,
+/// Loads scenes into the world and the asset storage.
+///
+/// Resource data is staged via the engine uploads, flush the uploads before
+/// rendering the loaded scene.
+pub struct SceneLoader<'a, B: Backend + 'a> {
+    engine: &'a mut Engine<B>,
+    scene: &'a mut Scene<B>,
+    texture_options: ImportOptions,
 }
 
-impl<'a> SceneLoader<'a> {
-    pub fn new(scene: &'a mut Scene, engine: &'a mut Engine) -> Self {
+impl<'a, B: Backend> SceneLoader<'a, B> {
+    pub fn new(scene: &'a mut Scene<B>, engine: &'a mut Engine<B>) -> Self {
         // Currently only support 1 index/vertex buffer.
-        scene.unload();
+        scene.unload(engine);
 
         SceneLoader {
-            upload_cmd_list: None,
             scene,
             engine,
+            texture_options: ImportOptions::default(),
         }
     }
 
-    pub fn set_upload_list(&mut self, list: ComPtr<ID3D12GraphicsCommandList>) {
-        self.upload_cmd_list = Some(list);
+    /// Options for importing the textures of subsequently loaded models.
+    pub fn set_texture_options(&mut self, options: ImportOptions) {
+        self.texture_options = options;
+    }
+
+    /// Recreate the GPU resources of the loaded scene from its CPU data.
+    ///
+    /// Required after recreating the engine, e.g. on device loss.
+    pub fn restore(scene: &'a mut Scene<B>, engine: &'a mut Engine<B>) -> Result<(), EngineError> {
+        scene.texture_srvs.srvs = None;
+
+        let mut loader = SceneLoader {
+            scene,
+            engine,
+            texture_options: ImportOptions::default(),
+        };
+        loader.restore_textures()?;
+        loader.create_texture_views()?;
+        loader.create_material_data()?;
+        loader.create_light_data()?;
+        loader.create_environment_data()?;
+        loader.create_mesh_buffers()?;
+        loader.create_instance_data()
     }
 
     /*
@@ -152,39 +400,35 @@ impl<'a> SceneLoader<'a> {
         &mut self,
         models: &Value,
         scene_dir: P,
-    ) -> Result<UploadResources, Error> {
-        let mut upload_resources = UploadResources {
-            resources: Vec::new(),
-        };
-
+    ) -> Result<(), Error> {
         let models = models.as_array().unwrap();
         for model in models {
             let file_name = model
                 .get("file")
                 .and_then(Value::as_str)
                 .expect("Missing model path");
-            let upload = self.load_assimp(&scene_dir, file_name);
-            upload_resources.resources.extend(upload.resources);
+            self.load_assimp(&scene_dir.as_ref().join(file_name))?;
         }
 
-        Ok(upload_resources)
+        Ok(())
     }
     */
 
-    pub fn load_hati_scene<P0, P1>(&mut self, scene_dir: P0, path: P1) -> UploadResources
+    pub fn load_hati_scene<P0, P1>(&mut self, scene_dir: P0, path: P1) -> Result<(), EngineError>
     where
         P0: AsRef<Path>,
         P1: AsRef<Path>,
     {
         // Generate point lights.
         for i in 0..10 {
-            let e = self
-                .scene
+            self.scene
                 .world
                 .create_entity()
-                .with(light::PointLight {
-                    intensity: 1000.0 * i as f32,
-                })
+                .with(light::PointLight::from_luminous_power(
+                    [1.0, 1.0, 1.0],
+                    12_000.0 * i as f32,
+                ))
+                .with(light::ShadowCaster { resolution: 512 })
                 .with(transform::LocalTransform::new(
                     Vector3::new(-1100.0 + i as f32 * 250.0, 80.0, 0.0),
                     1.0,
@@ -196,201 +440,102 @@ impl<'a> SceneLoader<'a> {
                     None,
                 ))
                 .build();
-            self.scene.point_lights.insert(e, i);
-        }
-
-        let num_point_lights = self.scene.point_lights.len();
-        let point_light_data_size = num_point_lights * mem::size_of::<pass::lighting::PointLight>();
-
-        let light_data_point_desc = D3D12_RESOURCE_DESC {
-            Dimension: D3D12_RESOURCE_DIMENSION_BUFFER,
-            Alignment: 0,
-            Width: point_light_data_size as _,
-            Height: 1,
-            DepthOrArraySize: 1,
-            Format: DXGI_FORMAT_UNKNOWN,
-            MipLevels: 1,
-            SampleDesc: DXGI_SAMPLE_DESC {
-                Count: 1,
-                Quality: 0,
-            },
-            Layout: D3D12_TEXTURE_LAYOUT_ROW_MAJOR,
-            Flags: D3D12_RESOURCE_FLAG_NONE,
-        };
-        let light_data_point = self.engine.create_committed_resource(
-            D3D12_HEAP_TYPE_UPLOAD,
-            &light_data_point_desc,
-            D3D12_RESOURCE_STATE_NON_PIXEL_SHADER_RESOURCE,
-            None,
-        );
-
-        let mut light_data_point_raw = ptr::null_mut();
-        let light_data_point_cpu = unsafe {
-            light_data_point.Map(0, ptr::null(), &mut light_data_point_raw);
-            slice::from_raw_parts_mut::<pass::lighting::PointLight>(
-                light_data_point_raw as _,
-                num_point_lights as _,
-            )
-        };
-
-        {
-            let transforms = self.scene.world.read_storage::<transform::LocalTransform>();
-            let point_lights = self.scene.world.read_storage::<light::PointLight>();
-            let entities = self.scene.world.entities();
-
-            for (e, transform, light) in (&*entities, &transforms, &point_lights).join() {
-                let idx = *self.scene.point_lights.get(&e).unwrap();
-                let transform = transform.world_transform(&transforms);
-                light_data_point_cpu[idx] = pass::lighting::PointLight {
-                    position: [transform.w.x, transform.w.y, transform.w.z],
-                    intensity: light.intensity,
-                };
-            }
         }
+        self.scene.sync_lights();
 
-        unsafe {
-            light_data_point.Unmap(0, ptr::null());
+        // Generate downwards facing area lights.
+        let shapes = [
+            light::AreaShape::Rect {
+                width: 200.0,
+                height: 100.0,
+            },
+            light::AreaShape::Disk { radius: 75.0 },
+        ];
+        for (i, &shape) in shapes.iter().enumerate() {
+            let e = self
+                .scene
+                .world
+                .create_entity()
+                .with(light::AreaLight {
+                    color: [1.0, 1.0, 1.0],
+                    luminance: 10.0,
+                    shape,
+                })
+                .with(transform::LocalTransform::new(
+                    Vector3::new(-600.0 + i as f32 * 1200.0, 400.0, 0.0),
+                    1.0,
+                    Euler {
+                        x: Rad(f32::consts::FRAC_PI_2),
+                        y: Rad(0.0),
+                        z: Rad(0.0),
+                    },
+                    None,
+                ))
+                .build();
+            self.scene.area_lights.insert(e, i);
         }
 
-        let light_srvs = self.engine.allocate_descriptors(1, 0).0;
-        let light_point_srv = D3D12_CPU_DESCRIPTOR_HANDLE {
-            ptr: self.engine.cbv_srv_uav_start.0.ptr
-                + (light_srvs * self.engine.cbv_srv_uav_size) as usize,
-        };
+        self.create_light_data()?;
+        self.set_environment(ibl::Environment::uniform(
+            environment::DEFAULT_RADIANCE,
+            1.0,
+        ))?;
 
-        unsafe {
-            let mut light_point_desc = D3D12_SHADER_RESOURCE_VIEW_DESC {
-                Format: DXGI_FORMAT_UNKNOWN,
-                ViewDimension: D3D12_SRV_DIMENSION_BUFFER,
-                Shader4ComponentMapping: 0x1688, // D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING
-                ..mem::zeroed()
-            };
-            *light_point_desc.u.Buffer_mut() = D3D12_BUFFER_SRV {
-                FirstElement: 0,
-                NumElements: num_point_lights as _,
-                StructureByteStride: mem::size_of::<pass::lighting::PointLight>() as _,
-                Flags: D3D12_BUFFER_SRV_FLAG_NONE,
-            };
-            self.engine.device.CreateShaderResourceView(
-                light_data_point.as_raw(),
-                &light_point_desc,
-                light_point_srv,
-            );
-        }
+        self.load_obj(scene_dir, path)
+    }
 
-        self.scene.world.add_resource(light::LightDataBuffer {
-            point_buffer: light_data_point,
-            start_srvs: light_srvs,
-        });
+    /// Light the scene by an equirectangular HDR environment map, scaling its
+    /// radiance by `intensity`.
+    pub fn load_environment<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        intensity: f32,
+    ) -> Result<(), EngineError> {
+        let map = ibl::EnvironmentMap::import(path.as_ref())?;
+        self.set_environment(ibl::Environment::new(&map, intensity))
+    }
 
-        self.load_obj(scene_dir, path)
+    /// Replace the environment lighting of the scene.
+    pub fn set_environment(&mut self, environment: ibl::Environment) -> Result<(), EngineError> {
+        self.scene.assets.add_resource(environment);
+        self.create_environment_data()
     }
 
-    pub fn load_obj<P0, P1>(&mut self, scene_dir: P0, path: P1) -> UploadResources
+    pub fn load_obj<P0, P1>(&mut self, scene_dir: P0, path: P1) -> Result<(), EngineError>
     where
         P0: AsRef<Path>,
         P1: AsRef<Path>,
     {
-        self.load_assimp(scene_dir, path.as_ref().to_str().unwrap())
+        let path = scene_dir.as_ref().join(path);
+        self.load_assimp(&path)
     }
 
-    fn load_assimp<P: AsRef<Path>>(&mut self, scene_dir: P, scene: &str) -> UploadResources {
+    fn load_assimp(&mut self, path: &Path) -> Result<(), EngineError> {
         let mut importer = Importer::new();
         importer.triangulate(true);
+        // Texture origin at the top left.
+        importer.flip_uvs(true);
+        importer.generate_normals(|normals| {
+            normals.enable = true;
+            normals.smooth = true;
+        });
+        // Requires texture coordinates, otherwise tangents remain at their default.
+        importer.calc_tangent_space(|tangents| tangents.enable = true);
 
+        let asset_error = |reason: String| {
+            EngineError::new("load_assimp", path.display().to_string(), ErrorKind::Asset(reason))
+        };
+        let path_str = path
+            .to_str()
+            .ok_or_else(|| asset_error("path is not valid unicode".into()))?;
         let model_scene = importer
-            .read_file(scene_dir.as_ref().join(Path::new(scene)).to_str().unwrap())
-            .unwrap();
+            .read_file(path_str)
+            .map_err(|err| asset_error(err.into()))?;
 
-        let mut upload_resources = Vec::new();
-
-        // let mut textures = Vec::new();
-        // for material in model_scene.material_iter() {
-        //     enum MaterialKey {
-        //         Name,
-        //         Texture(assimp_sys::AiTextureType),
-        //     }
-        //     fn get_material_string(
-        //         material: &assimp::Material,
-        //         key: MaterialKey,
-        //     ) -> Option<String> {
-        //         let (key, ty) = match key {
-        //             MaterialKey::Name => (b"?mat.name\0", 0),
-        //             MaterialKey::Texture(ty) => (b"$tex.file\0", ty as u32),
-        //         };
-        //         unsafe {
-        //             let mut string_val: assimp_sys::AiString = mem::zeroed();
-        //             let result = assimp_sys::aiGetMaterialString(
-        //                 &**material,
-        //                 key.as_ptr() as *const _,
-        //                 ty,
-        //                 0,
-        //                 &mut string_val,
-        //             ) as usize;
-        //             // FFI result enum values are wrong
-        //             match result {
-        //                 0 => {
-        //                     let string = ::std::ffi::CStr::from_bytes_with_nul_unchecked(
-        //                         &string_val.data[..string_val.length + 1],
-        //                     );
-        //                     Some(string.to_str().unwrap().into())
-        //                 }
-        //                 _ => None,
-        //             }
-        //         }
-        //     }
-        //     println!("{:?}", get_material_string(&material, MaterialKey::Name));
-
-        //     if let Some(albedo_name) = get_material_string(
-        //         &material,
-        //         MaterialKey::Texture(assimp_sys::AiTextureType::Diffuse),
-        //     ) {
-        //         let (albedo_img, albedo_upload) =
-        //             self.load_image_rgba8(scene_dir.as_ref().join(Path::new(&albedo_name)));
-        //         upload_resources.extend(albedo_upload.resources);
-        //         textures.push(
-        //             self.scene
-        //                 .assets
-        //                 .create_entity()
-        //                 .with(Texture {
-        //                     resource: albedo_img,
-        //                 }).build(),
-        //         );
-        //     }
-        // }
-
-        // let (texture_srvs, _) = self.engine.allocate_descriptors(textures.len() as _, 0);
-        // self.scene.texture_srvs.start_id = texture_srvs as _;
-        // {
-        //     let texture_strg = self.scene.assets.read_storage::<Texture>();
-        //     for (i, tid) in textures.iter().enumerate() {
-        //         let texture = texture_strg.get(*tid).unwrap();
-        //         let srv = D3D12_CPU_DESCRIPTOR_HANDLE {
-        //             ptr: self.engine.cbv_srv_uav_start.0.ptr
-        //                 + ((texture_srvs + i as u32) * self.engine.cbv_srv_uav_size) as usize,
-        //         };
-
-        //         unsafe {
-        //             let mut srv_desc = D3D12_SHADER_RESOURCE_VIEW_DESC {
-        //                 Format: DXGI_FORMAT_R8G8B8A8_UNORM, // TODO
-        //                 ViewDimension: D3D12_SRV_DIMENSION_TEXTURE2D,
-        //                 Shader4ComponentMapping: 0x1688, // D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING
-        //                 ..mem::zeroed()
-        //             };
-        //             *srv_desc.u.Texture2D_mut() = D3D12_TEX2D_SRV {
-        //                 MostDetailedMip: 0,
-        //                 MipLevels: 1,
-        //                 PlaneSlice: 0,
-        //                 ResourceMinLODClamp: 0.0,
-        //             };
-        //             self.engine.device.CreateShaderResourceView(
-        //                 texture.resource.as_raw(),
-        //                 &srv_desc,
-        //                 srv,
-        //             );
-        //         }
-        //     }
-        // }
+        let scene_dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let materials = self.load_materials(&model_scene, scene_dir)?;
+        self.create_texture_views()?;
+        self.create_material_data()?;
 
         let mut num_vertices = 0;
         let mut num_indices = 0;
@@ -399,137 +544,7 @@ impl<'a> SceneLoader<'a> {
             num_indices += mesh.num_faces() * 3;
         }
 
-        let default_desc = D3D12_RESOURCE_DESC {
-            Dimension: D3D12_RESOURCE_DIMENSION_BUFFER,
-            Alignment: 0,
-            Width: 0,
-            Height: 1,
-            DepthOrArraySize: 1,
-            Format: DXGI_FORMAT_UNKNOWN,
-            MipLevels: 1,
-            SampleDesc: DXGI_SAMPLE_DESC {
-                Count: 1,
-                Quality: 0,
-            },
-            Layout: D3D12_TEXTURE_LAYOUT_ROW_MAJOR,
-            Flags: D3D12_RESOURCE_FLAG_NONE,
-        };
-
-        let vertex_buffer_size = num_vertices as u64 * mem::size_of::<geometry::VertexPos>() as u64;
-        let vertex_buffer = self.engine.create_committed_resource(
-            D3D12_HEAP_TYPE_DEFAULT,
-            &D3D12_RESOURCE_DESC {
-                Width: vertex_buffer_size,
-                ..default_desc
-            },
-            D3D12_RESOURCE_STATE_COPY_DEST,
-            None,
-        );
-
-        let vertex_buffer_upload = self.engine.create_committed_resource(
-            D3D12_HEAP_TYPE_UPLOAD,
-            &D3D12_RESOURCE_DESC {
-                Width: vertex_buffer_size,
-                ..default_desc
-            },
-            D3D12_RESOURCE_STATE_COPY_SOURCE,
-            None,
-        );
-        let mut vertex_data = ptr::null_mut();
-        let vertices_pos_cpu = unsafe {
-            vertex_buffer_upload.Map(0, ptr::null(), &mut vertex_data);
-            slice::from_raw_parts_mut::<geometry::VertexPos>(vertex_data as _, num_vertices as _)
-        };
-
-        let index_buffer_size = num_indices as u64 * mem::size_of::<u32>() as u64;
-        let index_buffer = self.engine.create_committed_resource(
-            D3D12_HEAP_TYPE_DEFAULT,
-            &D3D12_RESOURCE_DESC {
-                Width: index_buffer_size,
-                ..default_desc
-            },
-            D3D12_RESOURCE_STATE_COPY_DEST,
-            None,
-        );
-
-        let index_buffer_upload = self.engine.create_committed_resource(
-            D3D12_HEAP_TYPE_UPLOAD,
-            &D3D12_RESOURCE_DESC {
-                Width: index_buffer_size,
-                ..default_desc
-            },
-            D3D12_RESOURCE_STATE_COPY_SOURCE,
-            None,
-        );
-        let mut index_data = ptr::null_mut();
-        let indices_cpu = unsafe {
-            index_buffer_upload.Map(0, ptr::null(), &mut index_data);
-            slice::from_raw_parts_mut::<u32>(index_data as _, num_indices as _)
-        };
-
-        // SRVs for index & vertex buffer and draw data.
-        // Required for shading and barycentric coord calculation.
-        let (buffer_srvs, _) = self.engine.allocate_descriptors(3, 0);
-        let index_srv = D3D12_CPU_DESCRIPTOR_HANDLE {
-            ptr: self.engine.cbv_srv_uav_start.0.ptr
-                + (buffer_srvs * self.engine.cbv_srv_uav_size) as usize,
-        };
-        let vertex_srv = D3D12_CPU_DESCRIPTOR_HANDLE {
-            ptr: self.engine.cbv_srv_uav_start.0.ptr
-                + ((buffer_srvs + 1) * self.engine.cbv_srv_uav_size) as usize,
-        };
-        let draw_data_srv = D3D12_CPU_DESCRIPTOR_HANDLE {
-            ptr: self.engine.cbv_srv_uav_start.0.ptr
-                + ((buffer_srvs + 2) * self.engine.cbv_srv_uav_size) as usize,
-        };
-        unsafe {
-            let mut vertex_srv_desc = D3D12_SHADER_RESOURCE_VIEW_DESC {
-                Format: DXGI_FORMAT_UNKNOWN,
-                ViewDimension: D3D12_SRV_DIMENSION_BUFFER,
-                Shader4ComponentMapping: 0x1688, // D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING
-                ..mem::zeroed()
-            };
-            *vertex_srv_desc.u.Buffer_mut() = D3D12_BUFFER_SRV {
-                FirstElement: 0,
-                NumElements: num_vertices,
-                StructureByteStride: mem::size_of::<geometry::VertexPos>() as _,
-                Flags: D3D12_BUFFER_SRV_FLAG_NONE,
-            };
-
-            let mut index_srv_desc = D3D12_SHADER_RESOURCE_VIEW_DESC {
-                Format: DXGI_FORMAT_UNKNOWN,
-                ViewDimension: D3D12_SRV_DIMENSION_BUFFER,
-                Shader4ComponentMapping: 0x1688, // D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING
-                ..mem::zeroed()
-            };
-            *index_srv_desc.u.Buffer_mut() = D3D12_BUFFER_SRV {
-                FirstElement: 0,
-                NumElements: num_indices,
-                StructureByteStride: mem::size_of::<u32>() as _,
-                Flags: D3D12_BUFFER_SRV_FLAG_NONE,
-            };
-
-            self.engine.device.CreateShaderResourceView(
-                vertex_buffer.as_raw(),
-                &vertex_srv_desc,
-                vertex_srv,
-            );
-            self.engine.device.CreateShaderResourceView(
-                index_buffer.as_raw(),
-                &index_srv_desc,
-                index_srv,
-            );
-        }
-
-        self.scene.assets.add_resource(geometry::Mesh {
-            vertex_buffer: vertex_buffer.clone(),
-            vertex_buffer_size: vertex_buffer_size as _,
-            vertex_stride: mem::size_of::<geometry::VertexPos>() as _,
-            index_buffer: index_buffer.clone(),
-            index_buffer_size: index_buffer_size as _,
-            index_format: DXGI_FORMAT_R32_UINT,
-            start_srvs: buffer_srvs,
-        });
+        let mut mesh_data = geometry::MeshData::new(num_vertices as _, num_indices as _);
 
         let mut base_index = 0;
         let mut base_vertex = 0;
@@ -543,15 +558,16 @@ impl<'a> SceneLoader<'a> {
 
                 for (i, vertex) in mesh.vertex_iter().enumerate() {
                     let v = base_vertex + i as usize;
-                    vertices_pos_cpu[v] = geometry::VertexPos([vertex.x, vertex.y, vertex.z]);
+                    mesh_data.positions[v] = geometry::VertexPos([vertex.x, vertex.y, vertex.z]);
                 }
+                load_attributes(&mut mesh_data, &mesh, base_vertex);
 
                 for (i, face) in mesh.face_iter().enumerate() {
                     let e = base_index + 3 * i;
                     let raw_indices = unsafe { slice::from_raw_parts(face.indices, 3) };
-                    indices_cpu[e] = raw_indices[0];
-                    indices_cpu[e + 1] = raw_indices[1];
-                    indices_cpu[e + 2] = raw_indices[2];
+                    mesh_data.indices[e] = raw_indices[0];
+                    mesh_data.indices[e + 1] = raw_indices[1];
+                    mesh_data.indices[e + 2] = raw_indices[2];
                 }
 
                 let geometry = self
@@ -563,6 +579,7 @@ impl<'a> SceneLoader<'a> {
                         base_index,
                         num_indices: num_local_indices,
                         base_vertex,
+                        material: materials[mesh.material_index as usize],
                     })
                     .build();
 
@@ -573,266 +590,584 @@ impl<'a> SceneLoader<'a> {
             })
             .collect::<Vec<_>>();
 
-        unsafe {
-            vertex_buffer_upload.Unmap(0, ptr::null());
-            index_buffer_upload.Unmap(0, ptr::null());
-        }
+        mesh_data.draw_data = {
+            let geometry_data = self.scene.assets.read_storage::<Geometry>();
+            geometries
+                .iter()
+                .map(|geometry| {
+                    let g = geometry_data.get(*geometry).unwrap();
+                    geometry::DrawData {
+                        base_vertex: g.base_vertex as _,
+                        base_index: g.base_index as _,
+                        material: self.scene.materials[&g.material] as _,
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
 
-        let draw_data_buffer_size = geometries.len() * mem::size_of::<geometry::DrawData>();
-        let draw_data = self.engine.create_committed_resource(
-            D3D12_HEAP_TYPE_DEFAULT,
-            &D3D12_RESOURCE_DESC {
-                Width: draw_data_buffer_size as _,
-                ..default_desc
-            },
-            D3D12_RESOURCE_STATE_COPY_DEST,
-            None,
-        );
+        self.scene.assets.add_resource(mesh_data);
+        self.create_mesh_buffers()?;
 
-        let draw_data_upload = self.engine.create_committed_resource(
-            D3D12_HEAP_TYPE_UPLOAD,
-            &D3D12_RESOURCE_DESC {
-                Width: draw_data_buffer_size as _,
-                ..default_desc
-            },
-            D3D12_RESOURCE_STATE_COPY_SOURCE,
-            None,
-        );
+        self.load_node(&geometries, &model_scene.root_node(), None);
+        self.create_instance_data()
+    }
 
-        let mut draw_data_raw = ptr::null_mut();
-        let draw_data_cpu = unsafe {
-            draw_data_upload.Map(0, ptr::null(), &mut draw_data_raw);
-            slice::from_raw_parts_mut::<geometry::DrawData>(draw_data_raw as _, geometries.len())
-        };
+    /// Import the model materials, returns the material entities in model order.
+    ///
+    /// Texture paths are relative to `dir`.
+    fn load_materials(
+        &mut self,
+        model_scene: &assimp::Scene,
+        dir: &Path,
+    ) -> Result<Vec<Entity>, EngineError> {
+        use assimp_sys::AiTextureType;
+
+        // Textures shared between materials are only loaded once.
+        let mut textures = HashMap::new();
+        let mut materials = Vec::new();
+        for material in model_scene.material_iter() {
+            let mut texture = |loader: &mut Self, ty: AiTextureType, usage| {
+                match material_string(&material, b"$tex.file\0", ty as _) {
+                    Some(file) => {
+                        // MTL files exported on Windows use backslash separators.
+                        let path = dir.join(file.replace('\\', "/"));
+                        loader.load_texture(&mut textures, path, usage).map(Some)
+                    }
+                    None => Ok(None),
+                }
+            };
 
-        {
-            let geometry_data = self.scene.assets.read_storage::<Geometry>();
-            for (i, geometry) in geometries.iter().enumerate() {
-                let g = geometry_data.get(*geometry).unwrap();
-                draw_data_cpu[i] = geometry::DrawData {
-                    base_vertex: g.base_vertex as _,
-                    base_index: g.base_index as _,
-                };
-            }
-        }
+            let albedo_texture = texture(self, AiTextureType::Diffuse, TextureUsage::Color)?;
+            let opacity_texture = texture(self, AiTextureType::Opacity, TextureUsage::Data)?;
+            // Height maps (e.g. `map_bump` in MTL files) aren't supported.
+            let normal_texture = texture(self, AiTextureType::Normals, TextureUsage::Normal)?;
+            // Used for the combined roughness/metal textures of PBR materials.
+            let roughness_metal_texture =
+                texture(self, AiTextureType::Unknown, TextureUsage::Data)?;
+            let emissive_texture = texture(self, AiTextureType::Emissive, TextureUsage::Color)?;
+
+            let diffuse = material_color(&material, b"$clr.diffuse\0").unwrap_or([1.0; 4]);
+            let opacity = material_float(&material, b"$mat.opacity\0").unwrap_or(1.0);
+            let emissive = material_color(&material, b"$clr.emissive\0").unwrap_or([0.0; 4]);
+            // Blinn-Phong exponent to GGX roughness, with alpha = roughness^2.
+            let roughness = match material_float(&material, b"$mat.shininess\0") {
+                Some(shininess) => (2.0 / (shininess.max(0.0) + 2.0)).powf(0.25),
+                None => 1.0,
+            };
 
-        unsafe {
-            draw_data_upload.Unmap(0, ptr::null());
+            let alpha_mode = if opacity_texture.is_some() {
+                material::AlphaMode::Mask { cutoff: 0.5 }
+            } else if opacity < 1.0 {
+                material::AlphaMode::Blend
+            } else {
+                material::AlphaMode::Opaque
+            };
+
+            let entity = self
+                .scene
+                .assets
+                .create_entity()
+                .with(Material {
+                    albedo: [diffuse[0], diffuse[1], diffuse[2], opacity],
+                    albedo_texture,
+                    opacity_texture,
+                    normal_texture,
+                    roughness,
+                    metallic: 0.0,
+                    roughness_metal_texture,
+                    emissive: [emissive[0], emissive[1], emissive[2]],
+                    emissive_texture,
+                    alpha_mode,
+                })
+                .build();
+            materials.push(entity);
         }
 
-        unsafe {
-            let mut draw_data_desc = D3D12_SHADER_RESOURCE_VIEW_DESC {
-                Format: DXGI_FORMAT_UNKNOWN,
-                ViewDimension: D3D12_SRV_DIMENSION_BUFFER,
-                Shader4ComponentMapping: 0x1688, // D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING
-                ..mem::zeroed()
-            };
-            *draw_data_desc.u.Buffer_mut() = D3D12_BUFFER_SRV {
-                FirstElement: 0,
-                NumElements: geometries.len() as _,
-                StructureByteStride: mem::size_of::<geometry::DrawData>() as _,
-                Flags: D3D12_BUFFER_SRV_FLAG_NONE,
-            };
-            self.engine.device.CreateShaderResourceView(
-                draw_data.as_raw(),
-                &draw_data_desc,
-                draw_data_srv,
+        if materials.is_empty() {
+            materials.push(
+                self.scene
+                    .assets
+                    .create_entity()
+                    .with(Material::default())
+                    .build(),
             );
         }
+        for (id, material) in materials.iter().enumerate() {
+            self.scene.materials.insert(*material, id);
+        }
 
-        {
-            // Staging vertex & index buffer and draw data
-            let upload_list = self
-                .upload_cmd_list
-                .as_ref()
-                .expect("upload command list not set");
-
-            unsafe {
-                upload_list.CopyResource(vertex_buffer.as_raw(), vertex_buffer_upload.as_raw());
-                upload_list.CopyResource(index_buffer.as_raw(), index_buffer_upload.as_raw());
-                upload_list.CopyResource(draw_data.as_raw(), draw_data_upload.as_raw());
+        Ok(materials)
+    }
+
+    /// Load a texture asset, reusing the texture in `textures` if already loaded.
+    fn load_texture(
+        &mut self,
+        textures: &mut HashMap<(PathBuf, TextureUsage), Entity>,
+        path: PathBuf,
+        usage: TextureUsage,
+    ) -> Result<Entity, EngineError> {
+        if let Some(texture) = textures.get(&(path.clone(), usage)) {
+            return Ok(*texture);
+        }
+
+        let options = self.texture_options;
+        let data = texture::import(&path, usage, &options)?;
+        let resource = self.create_texture(&data)?;
+        let texture = self
+            .scene
+            .assets
+            .create_entity()
+            .with(Texture {
+                resource,
+                path: path.clone(),
+                usage,
+                options,
+                format: data.format,
+                mip_levels: data.levels.len() as _,
+            })
+            .build();
+        textures.insert((path, usage), texture);
+        Ok(texture)
+    }
+
+    /// Reimport the texture resources from their source files.
+    fn restore_textures(&mut self) -> Result<(), EngineError> {
+        let sources = {
+            let entities = self.scene.assets.entities();
+            let textures = self.scene.assets.read_storage::<Texture<B>>();
+            (&*entities, &textures)
+                .join()
+                .map(|(e, texture)| (e, texture.path.clone(), texture.usage, texture.options))
+                .collect::<Vec<_>>()
+        };
+
+        for (e, path, usage, options) in sources {
+            let data = texture::import(&path, usage, &options)?;
+            let resource = self.create_texture(&data)?;
+            let mut textures = self.scene.assets.write_storage::<Texture<B>>();
+            let texture = textures.get_mut(e).unwrap();
+            texture.resource = resource;
+            texture.format = data.format;
+            texture.mip_levels = data.levels.len() as _;
+        }
+
+        Ok(())
+    }
+
+    /// Create the bindless texture table, assigning the `TextureView` ids.
+    fn create_texture_views(&mut self) -> Result<(), EngineError> {
+        let num_textures = self.scene.assets.read_storage::<Texture<B>>().join().count();
+        if num_textures == 0 {
+            if let Some(old) = self.scene.texture_srvs.srvs.take() {
+                self.engine.release(old);
             }
+            return Ok(());
+        }
 
-            // Use resources as index and vertex buffers.
-            // Additionally used as buffer SRVs for barycentric coords calculation
-            // in the geometry pixel shader.
-            let mesh_data_transitions = [
-                engine::gen_resource_transition(
-                    &vertex_buffer,
-                    D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES,
-                    D3D12_RESOURCE_STATE_COPY_DEST,
-                    D3D12_RESOURCE_STATE_VERTEX_AND_CONSTANT_BUFFER
-                        | D3D12_RESOURCE_STATE_PIXEL_SHADER_RESOURCE,
-                    D3D12_RESOURCE_BARRIER_FLAG_NONE,
-                ),
-                engine::gen_resource_transition(
-                    &index_buffer,
-                    D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES,
-                    D3D12_RESOURCE_STATE_COPY_DEST,
-                    D3D12_RESOURCE_STATE_INDEX_BUFFER | D3D12_RESOURCE_STATE_PIXEL_SHADER_RESOURCE,
-                    D3D12_RESOURCE_BARRIER_FLAG_NONE,
-                ),
-                engine::gen_resource_transition(
-                    &index_buffer,
-                    D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES,
-                    D3D12_RESOURCE_STATE_COPY_DEST,
-                    D3D12_RESOURCE_STATE_NON_PIXEL_SHADER_RESOURCE,
-                    D3D12_RESOURCE_BARRIER_FLAG_NONE,
-                ),
-            ];
-            unsafe {
-                upload_list.ResourceBarrier(
-                    mesh_data_transitions.len() as _,
-                    mesh_data_transitions.as_ptr(),
+        let srvs = self.engine.allocate_descriptors(
+            DescriptorHeapType::CbvSrvUav,
+            num_textures as _,
+            "texture srvs",
+        )?;
+        {
+            let entities = self.scene.assets.entities();
+            let textures = self.scene.assets.read_storage::<Texture<B>>();
+            let mut views = self.scene.assets.write_storage::<TextureView>();
+            for (id, (e, texture)) in (&*entities, &textures).join().enumerate() {
+                self.engine.device.create_shader_resource_view(
+                    &texture.resource,
+                    &SrvDesc::Texture2D {
+                        format: texture.format,
+                        mip_levels: texture.mip_levels,
+                    },
+                    srvs.cpu(id as _),
                 );
+                views.insert(e, TextureView { id }).unwrap();
             }
         }
 
+        if let Some(old) = mem::replace(&mut self.scene.texture_srvs.srvs, Some(srvs)) {
+            self.engine.release(old);
+        }
+        Ok(())
+    }
+
+    /// Create and upload the material data buffer from the material assets.
+    ///
+    /// Requires the texture views to be created.
+    fn create_material_data(&mut self) -> Result<(), EngineError> {
+        let material_data = self.scene.material_data();
+        let stride = mem::size_of::<material::MaterialData>();
+
+        let buffer = self.engine.create_placed_resource(
+            HeapType::Default,
+            &ResourceDesc::buffer((material_data.len() * stride) as _),
+            ResourceStates::COPY_DEST,
+            None,
+        )?;
+
+        let srvs =
+            self.engine
+                .allocate_descriptors(DescriptorHeapType::CbvSrvUav, 1, "material data srv")?;
+        self.engine.device.create_shader_resource_view(
+            &buffer,
+            &SrvDesc::Buffer {
+                first_element: 0,
+                num_elements: material_data.len() as _,
+                stride: stride as _,
+            },
+            srvs.cpu(0),
+        );
+
+        self.engine.upload_buffer(&buffer, &material_data)?;
+        // Alpha testing in the geometry pass and shading in the lighting pass.
+        self.engine.states.use_resource(
+            &buffer,
+            ResourceStates::PIXEL_SHADER_RESOURCE | ResourceStates::NON_PIXEL_SHADER_RESOURCE,
+        );
+
         self.scene
             .assets
-            .add_resource(geometry::DrawDataBuffer(draw_data));
+            .add_resource(material::MaterialDataBuffer::<B>::new(buffer, srvs));
+        Ok(())
+    }
 
-        upload_resources.extend(vec![
-            vertex_buffer_upload,
-            index_buffer_upload,
-            draw_data_upload,
-        ]);
+    /// Create the light tables of the frames in flight and the LTC table.
+    ///
+    /// The light buffers are uploaded per frame, see `LightDataBuffer::upload`.
+    fn create_light_data(&mut self) -> Result<(), EngineError> {
+        let frame_latency = self.engine.frame_latency() as u32;
+        let light_srvs = self.engine.allocate_descriptors(
+            DescriptorHeapType::CbvSrvUav,
+            frame_latency * light::LIGHT_TABLE_SIZE,
+            "lights srv",
+        )?;
+
+        // The LTC table is shared by all frames.
+        let ltc_table = ltc::table();
+        let ltc_buffer = self.create_structured_buffer(&ltc_table, light_srvs.cpu(4))?;
+        for frame in 1..frame_latency {
+            self.engine.device.create_shader_resource_view(
+                &ltc_buffer,
+                &SrvDesc::Buffer {
+                    first_element: 0,
+                    num_elements: ltc_table.len() as _,
+                    stride: mem::size_of::<ltc::LtcData>() as _,
+                },
+                light_srvs.cpu(frame * light::LIGHT_TABLE_SIZE + 4),
+            );
+        }
 
-        self.load_node(&geometries, &model_scene.root_node(), None);
+        self.scene
+            .world
+            .add_resource(light::LightDataBuffer::<B>::new(ltc_buffer, light_srvs));
 
-        UploadResources {
-            resources: upload_resources,
-        }
+        Ok(())
     }
 
-    fn load_image_rgba8<P: AsRef<Path>>(
-        &mut self,
-        path: P,
-    ) -> (ComPtr<ID3D12Resource>, UploadResources) {
-        let img = image::open(path).unwrap().to_rgba();
-        let (width, height) = img.dimensions();
-
-        let desc = D3D12_RESOURCE_DESC {
-            Dimension: D3D12_RESOURCE_DIMENSION_TEXTURE2D,
-            Alignment: 0,
-            Width: width as _,
-            Height: height as _,
-            DepthOrArraySize: 1,
-            Format: DXGI_FORMAT_R8G8B8A8_UNORM,
-            MipLevels: 1,
-            SampleDesc: DXGI_SAMPLE_DESC {
-                Count: 1,
-                Quality: 0,
+    /// Create the environment table from the environment of the scene assets.
+    fn create_environment_data(&mut self) -> Result<(), EngineError> {
+        let srvs = self.engine.allocate_descriptors(
+            DescriptorHeapType::CbvSrvUav,
+            environment::ENVIRONMENT_TABLE_SIZE,
+            "environment srvs",
+        )?;
+
+        // Copy, uploading borrows the loader mutably.
+        let environment = ibl::Environment::clone(&self.scene.assets.read_resource());
+        let specular = self.create_texture_resource(
+            &environment.specular.desc(),
+            &environment.specular.subresources(),
+        )?;
+        self.engine.device.create_shader_resource_view(
+            &specular,
+            &SrvDesc::TextureCube {
+                format: ibl::CubeMap::FORMAT,
+                mip_levels: environment.specular.mip_levels,
             },
-            Layout: D3D12_TEXTURE_LAYOUT_UNKNOWN,
-            Flags: D3D12_RESOURCE_FLAG_NONE,
-        };
-        let image = self.engine.create_committed_resource(
-            D3D12_HEAP_TYPE_DEFAULT,
-            &desc,
-            D3D12_RESOURCE_STATE_COPY_DEST,
-            None,
+            srvs.cpu(0),
         );
 
-        let num_texels = width * height;
-        let upload_buffer_desc = D3D12_RESOURCE_DESC {
-            Dimension: D3D12_RESOURCE_DIMENSION_BUFFER,
-            Alignment: 0,
-            Width: 4 * num_texels as u64,
-            Height: 1,
-            DepthOrArraySize: 1,
-            Format: DXGI_FORMAT_UNKNOWN,
-            MipLevels: 1,
-            SampleDesc: DXGI_SAMPLE_DESC {
-                Count: 1,
-                Quality: 0,
+        let brdf_lut = ibl::brdf_lut::texture(&ibl::brdf_lut::brdf_lut());
+        let brdf_lut_texture = self.create_texture(&brdf_lut)?;
+        self.engine.device.create_shader_resource_view(
+            &brdf_lut_texture,
+            &SrvDesc::Texture2D {
+                format: brdf_lut.format,
+                mip_levels: 1,
             },
-            Layout: D3D12_TEXTURE_LAYOUT_ROW_MAJOR,
-            Flags: D3D12_RESOURCE_FLAG_NONE,
-        };
-        let upload_buffer = self.engine.create_committed_resource(
-            D3D12_HEAP_TYPE_UPLOAD,
-            &upload_buffer_desc,
-            D3D12_RESOURCE_STATE_COPY_SOURCE,
-            None,
+            srvs.cpu(1),
         );
 
-        let mut image_data = ptr::null_mut();
-        let image_data_cpu = unsafe {
-            upload_buffer.Map(0, ptr::null(), &mut image_data);
-            slice::from_raw_parts_mut::<u8>(image_data as _, 4 * num_texels as usize)
-        };
+        let data_buffer = self.create_structured_buffer(&[environment.data()], srvs.cpu(2))?;
 
-        let row_pitch = 4 * width as usize; // TODO: alignment
-        for y in 0..height as usize {
-            let row = &(*img)[y * row_pitch..(y + 1) * row_pitch];
-            let dst = y * row_pitch as usize;
-            image_data_cpu[dst..dst + row.len()].copy_from_slice(row);
-        }
+        self.scene
+            .assets
+            .add_resource(environment::EnvironmentBuffer::<B>::new(
+                specular,
+                brdf_lut_texture,
+                data_buffer,
+                srvs,
+            ));
+        Ok(())
+    }
+
+    /// Create a CPU visible structured buffer with an SRV at `srv`.
+    ///
+    /// Empty buffers store a single zeroed element, views can't be empty.
+    fn create_structured_buffer<T: Copy>(
+        &mut self,
+        data: &[T],
+        srv: CpuDescriptor,
+    ) -> Result<B::Resource, EngineError> {
+        let stride = mem::size_of::<T>();
+        let num_elements = data.len().max(1);
+
+        let buffer = self.engine.device.create_committed_resource(
+            HeapType::Upload,
+            &ResourceDesc::buffer((num_elements * stride) as _),
+            ResourceStates::NON_PIXEL_SHADER_RESOURCE,
+            None,
+        )?;
 
         unsafe {
-            upload_buffer.Unmap(0, ptr::null());
+            let buffer_raw = self.engine.device.map(&buffer)?;
+            ptr::write_bytes(buffer_raw, 0, num_elements * stride);
+            ptr::copy_nonoverlapping(data.as_ptr(), buffer_raw as *mut T, data.len());
         }
+        self.engine.device.unmap(&buffer);
+
+        self.engine.device.create_shader_resource_view(
+            &buffer,
+            &SrvDesc::Buffer {
+                first_element: 0,
+                num_elements: num_elements as _,
+                stride: stride as _,
+            },
+            srv,
+        );
+
+        Ok(buffer)
+    }
+
+    /// Create and upload the vertex, index and draw data buffers from the mesh data.
+    fn create_mesh_buffers(&mut self) -> Result<(), EngineError> {
+        let mesh_data = self.scene.assets.read_resource::<geometry::MeshData>();
+        let num_vertices = mesh_data.positions.len() as u32;
+        let num_indices = mesh_data.indices.len() as u32;
+        let num_geometries = mesh_data.draw_data.len();
+
+        let vertex_buffer_size = num_vertices as u64 * mem::size_of::<geometry::VertexPos>() as u64;
+        let vertex_buffer = self.engine.create_placed_resource(
+            HeapType::Default,
+            &ResourceDesc::buffer(vertex_buffer_size),
+            ResourceStates::COPY_DEST,
+            None,
+        )?;
+
+        let engine = &mut self.engine;
+        let stream_buffers = mesh_data
+            .streams
+            .iter()
+            .map(|stream| {
+                engine.create_placed_resource(
+                    HeapType::Default,
+                    &ResourceDesc::buffer((stream.len() * mem::size_of::<u32>()) as _),
+                    ResourceStates::COPY_DEST,
+                    None,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
+        let index_buffer_size = num_indices as u64 * mem::size_of::<u32>() as u64;
+        let index_buffer = self.engine.create_placed_resource(
+            HeapType::Default,
+            &ResourceDesc::buffer(index_buffer_size),
+            ResourceStates::COPY_DEST,
+            None,
+        )?;
+
+        let draw_data_buffer_size = num_geometries * mem::size_of::<geometry::DrawData>();
+        let draw_data = self.engine.create_placed_resource(
+            HeapType::Default,
+            &ResourceDesc::buffer(draw_data_buffer_size as _),
+            ResourceStates::COPY_DEST,
+            None,
+        )?;
+
+        // SRVs for index & vertex buffers, draw data and attribute streams.
+        // Required for shading, attribute interpolation and barycentric coord
+        // calculation.
+        let buffer_srvs = self.engine.allocate_descriptors(
+            DescriptorHeapType::CbvSrvUav,
+            3 + geometry::NUM_VERTEX_STREAMS as u32,
+            "mesh srvs",
+        )?;
+        let index_srv = buffer_srvs.cpu(0);
+        let vertex_srv = buffer_srvs.cpu(1);
+        let draw_data_srv = buffer_srvs.cpu(2);
+
+        self.engine.device.create_shader_resource_view(
+            &vertex_buffer,
+            &SrvDesc::Buffer {
+                first_element: 0,
+                num_elements: num_vertices,
+                stride: mem::size_of::<geometry::VertexPos>() as _,
+            },
+            vertex_srv,
+        );
+        self.engine.device.create_shader_resource_view(
+            &index_buffer,
+            &SrvDesc::Buffer {
+                first_element: 0,
+                num_elements: num_indices,
+                stride: mem::size_of::<u32>() as _,
+            },
+            index_srv,
+        );
+        self.engine.device.create_shader_resource_view(
+            &draw_data,
+            &SrvDesc::Buffer {
+                first_element: 0,
+                num_elements: num_geometries as _,
+                stride: mem::size_of::<geometry::DrawData>() as _,
+            },
+            draw_data_srv,
+        );
+        // Attribute streams are decoded from 32-bit words in the shaders.
+        for (i, (buffer, stream)) in stream_buffers
+            .iter()
+            .zip(mesh_data.streams.iter())
+            .enumerate()
         {
-            // Staging vertex and index buffer data
-            let upload_list = self
-                .upload_cmd_list
-                .as_ref()
-                .expect("upload command list not set");
-
-            // Image mipmap
-            let mut dst_location = D3D12_TEXTURE_COPY_LOCATION {
-                pResource: image.as_raw(),
-                Type: D3D12_TEXTURE_COPY_TYPE_SUBRESOURCE_INDEX,
-                ..unsafe { mem::zeroed() }
-            };
-            unsafe {
-                *dst_location.u.SubresourceIndex_mut() = 0;
-            }
+            self.engine.device.create_shader_resource_view(
+                buffer,
+                &SrvDesc::Buffer {
+                    first_element: 0,
+                    num_elements: stream.len() as _,
+                    stride: mem::size_of::<u32>() as _,
+                },
+                buffer_srvs.cpu(3 + i as u32),
+            );
+        }
 
-            // Upload buffer
-            let mut src_location = D3D12_TEXTURE_COPY_LOCATION {
-                pResource: upload_buffer.as_raw(),
-                Type: D3D12_TEXTURE_COPY_TYPE_PLACED_FOOTPRINT,
-                ..unsafe { mem::zeroed() }
-            };
-            unsafe {
-                *src_location.u.PlacedFootprint_mut() = D3D12_PLACED_SUBRESOURCE_FOOTPRINT {
-                    Offset: 0,
-                    Footprint: D3D12_SUBRESOURCE_FOOTPRINT {
-                        Format: DXGI_FORMAT_R8G8B8A8_UNORM,
-                        Width: width as _,
-                        Height: height as _,
-                        Depth: 1,
-                        RowPitch: 4 * width as UINT,
-                    },
-                };
+        {
+            // Staging vertex & index buffer and draw data
+            self.engine
+                .upload_buffer(&vertex_buffer, &mesh_data.positions)?;
+            self.engine.upload_buffer(&index_buffer, &mesh_data.indices)?;
+            self.engine
+                .upload_buffer(&draw_data, &mesh_data.draw_data)?;
+            for (buffer, stream) in stream_buffers.iter().zip(mesh_data.streams.iter()) {
+                self.engine.upload_buffer(buffer, stream)?;
             }
 
-            unsafe {
-                upload_list.CopyTextureRegion(&dst_location, 0, 0, 0, &src_location, ptr::null());
+            // Use resources as index and vertex buffers.
+            // Additionally used as buffer SRVs for barycentric coords calculation
+            // in the geometry pixel shader and triangle reconstruction in the
+            // lighting compute shader.
+            let shader_resource =
+                ResourceStates::PIXEL_SHADER_RESOURCE | ResourceStates::NON_PIXEL_SHADER_RESOURCE;
+            let states = &mut self.engine.states;
+            states.use_resource(
+                &vertex_buffer,
+                ResourceStates::VERTEX_AND_CONSTANT_BUFFER | shader_resource,
+            );
+            states.use_resource(
+                &index_buffer,
+                ResourceStates::INDEX_BUFFER | shader_resource,
+            );
+            states.use_resource(&draw_data, ResourceStates::NON_PIXEL_SHADER_RESOURCE);
+            for buffer in &stream_buffers {
+                states.use_resource(buffer, shader_resource);
             }
+        }
 
-            // Use image as shader resource view only
-            let transitions = [engine::gen_resource_transition(
-                &image,
-                D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES,
-                D3D12_RESOURCE_STATE_COPY_DEST,
-                D3D12_RESOURCE_STATE_NON_PIXEL_SHADER_RESOURCE
-                    | D3D12_RESOURCE_STATE_PIXEL_SHADER_RESOURCE,
-                D3D12_RESOURCE_BARRIER_FLAG_NONE,
-            )];
-            unsafe {
-                upload_list.ResourceBarrier(transitions.len() as _, transitions.as_ptr());
-            }
+        drop(mesh_data);
+        self.scene.assets.add_resource(geometry::Mesh::<B>::new(
+            vertex_buffer,
+            vertex_buffer_size as _,
+            index_buffer,
+            index_buffer_size as _,
+            stream_buffers,
+            buffer_srvs,
+        ));
+        self.scene
+            .assets
+            .add_resource(geometry::DrawDataBuffer::<B>(draw_data));
+
+        Ok(())
+    }
+
+    /// Allocate the instance data buffer for all loaded instances.
+    ///
+    /// Contents are written each frame via `Scene::write_instance_data`.
+    fn create_instance_data(&mut self) -> Result<(), EngineError> {
+        let frame_latency = self.engine.frame_latency() as usize;
+        let num_instances = self.scene.instances.len();
+        let stride = mem::size_of::<geometry::InstanceData>();
+
+        let buffer = self.engine.device.create_committed_resource(
+            HeapType::Upload,
+            &ResourceDesc::buffer((frame_latency * num_instances * stride) as _),
+            ResourceStates::GENERIC_READ,
+            None,
+        )?;
+
+        // One SRV per frame in flight.
+        let srvs = self.engine.allocate_descriptors(
+            DescriptorHeapType::CbvSrvUav,
+            frame_latency as _,
+            "instance data srvs",
+        )?;
+        for frame in 0..frame_latency {
+            self.engine.device.create_shader_resource_view(
+                &buffer,
+                &SrvDesc::Buffer {
+                    first_element: (frame * num_instances) as _,
+                    num_elements: num_instances as _,
+                    stride: stride as _,
+                },
+                srvs.cpu(frame as _),
+            );
         }
 
-        let upload_resources = UploadResources {
-            resources: vec![upload_buffer],
-        };
+        self.scene
+            .world
+            .add_resource(geometry::InstanceDataBuffer::<B> {
+                buffer,
+                num_instances,
+                srvs,
+            });
+        Ok(())
+    }
+
+    /// Create a texture resource and upload all mip levels of `data`.
+    fn create_texture(&mut self, data: &TextureData) -> Result<PlacedResource<B>, EngineError> {
+        self.create_texture_resource(&data.desc(), &data.subresources())
+    }
+
+    /// Create a texture resource and upload its `subresources`.
+    fn create_texture_resource(
+        &mut self,
+        desc: &ResourceDesc,
+        subresources: &[&[u8]],
+    ) -> Result<PlacedResource<B>, EngineError> {
+        let texture = self.engine.create_placed_resource(
+            HeapType::Default,
+            desc,
+            ResourceStates::COPY_DEST,
+            None,
+        )?;
+        self.engine.upload_texture(&texture, desc, subresources)?;
+
+        // Use texture as shader resource view only
+        self.engine.states.use_resource(
+            &texture,
+            ResourceStates::NON_PIXEL_SHADER_RESOURCE | ResourceStates::PIXEL_SHADER_RESOURCE,
+        );
 
-        (image, upload_resources)
+        Ok(texture)
     }
 
     fn load_node(&mut self, geometries: &[Entity], node: &assimp::Node, parent: Option<Entity>) {
@@ -853,7 +1188,8 @@ impl<'a> SceneLoader<'a> {
             .build();
 
         for mesh in node.meshes() {
-            self.scene
+            let instance = self
+                .scene
                 .world
                 .create_entity()
                 .with(Instance {
@@ -869,6 +1205,8 @@ impl<'a> SceneLoader<'a> {
                     parent: Some(entity),
                 })
                 .build();
+            let id = self.scene.instances.len();
+            self.scene.instances.insert(instance, id);
         }
 
         for child in node.child_iter() {
@@ -877,17 +1215,21 @@ impl<'a> SceneLoader<'a> {
     }
 }
 
-pub struct Texture {
-    pub resource: ComPtr<ID3D12Resource>,
+pub struct Texture<B: Backend> {
+    pub resource: PlacedResource<B>,
+    /// Source file, required for recreating the resource.
+    pub path: PathBuf,
+    pub usage: TextureUsage,
+    pub options: ImportOptions,
+    pub format: Format,
+    pub mip_levels: u32,
 }
-unsafe impl Send for Texture {}
-unsafe impl Sync for Texture {}
-impl Component for Texture {
+impl<B: Backend> Component for Texture<B> {
     type Storage = HashMapStorage<Self>;
 }
 
 pub struct TextureViewGroup {
-    pub start_id: usize,
+    pub srvs: Option<DescriptorRange>,
 }
 
 pub struct TextureView {
@@ -897,3 +1239,332 @@ pub struct TextureView {
 impl Component for TextureView {
     type Storage = HashMapStorage<Self>;
 }
+
+/// Query a string property of an assimp material, e.g. `?mat.name` or `$tex.file`.
+///
+/// `ty` is the texture type for texture properties, otherwise 0.
+fn material_string(material: &assimp::Material, key: &[u8], ty: u32) -> Option<String> {
+    unsafe {
+        let mut value: assimp_sys::AiString = mem::zeroed();
+        let result = assimp_sys::aiGetMaterialString(
+            &**material,
+            key.as_ptr() as *const _,
+            ty,
+            0,
+            &mut value,
+        ) as usize;
+        // FFI result enum values are wrong
+        match result {
+            0 => {
+                let bytes = value.data[..value.length]
+                    .iter()
+                    .map(|&c| c as u8)
+                    .collect::<Vec<_>>();
+                Some(String::from_utf8_lossy(&bytes).into_owned())
+            }
+            _ => None,
+        }
+    }
+}
+
+fn material_color(material: &assimp::Material, key: &[u8]) -> Option<[f32; 4]> {
+    unsafe {
+        let mut value: assimp_sys::AiColor4D = mem::zeroed();
+        let result = assimp_sys::aiGetMaterialColor(
+            &**material,
+            key.as_ptr() as *const _,
+            0,
+            0,
+            &mut value,
+        ) as usize;
+        match result {
+            0 => Some([value.r, value.g, value.b, value.a]),
+            _ => None,
+        }
+    }
+}
+
+fn material_float(material: &assimp::Material, key: &[u8]) -> Option<f32> {
+    unsafe {
+        let mut value = 0.0;
+        let mut count = 1;
+        let result = assimp_sys::aiGetMaterialFloatArray(
+            &**material,
+            key.as_ptr() as *const _,
+            0,
+            0,
+            &mut value,
+            &mut count,
+        ) as usize;
+        match result {
+            0 if count == 1 => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Write the vertex attributes of an assimp mesh into the attribute streams.
+///
+/// Attributes missing in the mesh keep their stream default.
+fn load_attributes(mesh_data: &mut geometry::MeshData, mesh: &assimp::Mesh, base_vertex: usize) {
+    use self::geometry::VertexAttribute;
+
+    if mesh.has_normals() {
+        for (i, n) in mesh.normal_iter().enumerate() {
+            let normal = [n.x, n.y, n.z, 0.0];
+            mesh_data.set_attribute(VertexAttribute::Normal, base_vertex + i, normal);
+        }
+    }
+    if mesh.has_tangents_and_bitangents() {
+        let frames = mesh
+            .normal_iter()
+            .zip(mesh.tangent_iter())
+            .zip(mesh.bitangent_iter());
+        for (i, ((n, t), b)) in frames.enumerate() {
+            let normal = Vector3::new(n.x, n.y, n.z);
+            let tangent = Vector3::new(t.x, t.y, t.z);
+            let bitangent = Vector3::new(b.x, b.y, b.z);
+            // Handedness of the tangent frame, bitangent = sign * cross(normal, tangent).
+            let sign = if normal.cross(tangent).dot(bitangent) < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+            let tangent = [tangent.x, tangent.y, tangent.z, sign];
+            mesh_data.set_attribute(VertexAttribute::Tangent, base_vertex + i, tangent);
+        }
+    }
+    for &(channel, attribute) in &[(0, VertexAttribute::Uv0), (1, VertexAttribute::Uv1)] {
+        if mesh.has_texture_coords(channel) {
+            for (i, uv) in mesh.texture_coords_iter(channel).enumerate() {
+                mesh_data.set_attribute(attribute, base_vertex + i, [uv.x, uv.y, 0.0, 1.0]);
+            }
+        }
+    }
+    if mesh.has_vertex_colors(0) {
+        for (i, color) in mesh.vertex_color_iter(0).enumerate() {
+            let color = [color.r, color.g, color.b, color.a];
+            mesh_data.set_attribute(VertexAttribute::Color, base_vertex + i, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::null::Null;
+    use config::EngineConfig;
+    use image;
+    use reference::material::Materials;
+    use std::{env, fs, process};
+
+    fn add_point_light(scene: &mut Scene<Null>, x: f32, intensity: f32) -> Entity {
+        scene
+            .world
+            .create_entity()
+            .with(LocalTransform::new(
+                Vector3::new(x, 0.0, 0.0),
+                1.0,
+                Euler::new(Rad(0.0), Rad(0.0), Rad(0.0)),
+                None,
+            ))
+            .with(light::PointLight {
+                color: [1.0; 3],
+                intensity,
+                range: 10.0,
+            })
+            .build()
+    }
+
+    fn view_data() -> ViewData {
+        let camera = Camera {
+            position: Point3::new(0.0, 0.0, 0.0),
+            rotation: [Rad(0.0), Rad(0.0), Rad(0.0)],
+            up: Vector3::new(0.0, 1.0, 0.0),
+            view_move: (false, false),
+            view_rotate: (false, false, false, false),
+            depth_range: 0.0..1.0,
+            focal_length: 1.0,
+        };
+        ViewData::new(&camera, 64, 64)
+    }
+
+    /// Check the ids are compact and the data of each light is stored at its id.
+    fn check_point_lights(scene: &Scene<Null>, lights: &[(Entity, f32, f32)]) {
+        let mut ids = scene.point_lights.values().cloned().collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, (0..lights.len()).collect::<Vec<_>>());
+
+        let data = scene.light_data(&view_data());
+        assert_eq!(data.points.len(), lights.len());
+        assert_eq!(data.light_data().num_point_lights, lights.len() as u32);
+        for &(e, x, intensity) in lights {
+            let light = &data.points[scene.point_lights[&e]];
+            assert_eq!(light.position, [x, 0.0, 0.0]);
+            assert_eq!(light.intensity, intensity);
+        }
+    }
+
+    #[test]
+    fn sync_light_ids() {
+        let mut scene = Scene::<Null>::new();
+        let first = add_point_light(&mut scene, 1.0, 10.0);
+        let middle = add_point_light(&mut scene, 2.0, 20.0);
+        let last = add_point_light(&mut scene, 3.0, 30.0);
+        scene.sync_lights();
+        check_point_lights(
+            &scene,
+            &[(first, 1.0, 10.0), (middle, 2.0, 20.0), (last, 3.0, 30.0)],
+        );
+        assert_eq!(scene.point_lights[&middle], 1);
+        assert_eq!(scene.point_lights[&last], 2);
+
+        // The light with the highest id takes over the id of the removed one.
+        scene.world.delete_entity(middle).unwrap();
+        scene.world.maintain();
+        scene.sync_lights();
+        assert!(!scene.point_lights.contains_key(&middle));
+        assert_eq!(scene.point_lights[&last], 1);
+        check_point_lights(&scene, &[(first, 1.0, 10.0), (last, 3.0, 30.0)]);
+
+        let added = add_point_light(&mut scene, 4.0, 40.0);
+        scene.sync_lights();
+        assert_eq!(scene.point_lights[&added], 2);
+        check_point_lights(
+            &scene,
+            &[(first, 1.0, 10.0), (last, 3.0, 30.0), (added, 4.0, 40.0)],
+        );
+
+        let data = scene.light_data(&view_data()).light_data();
+        assert_eq!(
+            (
+                data.num_directional_lights,
+                data.num_spot_lights,
+                data.num_area_lights
+            ),
+            (0, 0, 0)
+        );
+    }
+
+    /// Write a 4x4 image filled with `texel` into `dir`.
+    fn write_image(dir: &Path, name: &str, texel: [u8; 4]) -> PathBuf {
+        let path = dir.join(name);
+        let texels = (0..16).flat_map(|_| texel.to_vec()).collect::<Vec<_>>();
+        image::RgbaImage::from_raw(4, 4, texels)
+            .unwrap()
+            .save(&path)
+            .unwrap();
+        path
+    }
+
+    #[test]
+    fn material_texture_table() {
+        let dir = env::temp_dir().join(format!("hati-materials-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let red = write_image(&dir, "red.png", [255, 0, 0, 255]);
+        let flat = write_image(&dir, "flat.png", [128, 128, 255, 255]);
+
+        let mut engine = Engine::<Null>::new(&EngineConfig::default()).unwrap();
+        let mut scene = Scene::<Null>::new();
+        let (textured, untextured, textures) = {
+            let mut loader = SceneLoader::new(&mut scene, &mut engine);
+            let mut textures = HashMap::new();
+            let albedo = loader
+                .load_texture(&mut textures, red.clone(), TextureUsage::Color)
+                .unwrap();
+            let shared = loader
+                .load_texture(&mut textures, red.clone(), TextureUsage::Color)
+                .unwrap();
+            // Same file with a different usage is imported separately.
+            let opacity = loader
+                .load_texture(&mut textures, red.clone(), TextureUsage::Data)
+                .unwrap();
+            let normal = loader
+                .load_texture(&mut textures, flat.clone(), TextureUsage::Normal)
+                .unwrap();
+            assert_eq!(albedo, shared);
+            assert_ne!(albedo, opacity);
+
+            let textured = loader
+                .scene
+                .assets
+                .create_entity()
+                .with(Material {
+                    albedo_texture: Some(albedo),
+                    opacity_texture: Some(opacity),
+                    normal_texture: Some(normal),
+                    alpha_mode: material::AlphaMode::Mask { cutoff: 0.5 },
+                    ..Material::default()
+                })
+                .build();
+            let untextured = loader
+                .scene
+                .assets
+                .create_entity()
+                .with(Material {
+                    albedo: [0.5, 0.25, 0.125, 1.0],
+                    ..Material::default()
+                })
+                .build();
+            loader.scene.materials.insert(textured, 0);
+            loader.scene.materials.insert(untextured, 1);
+
+            loader.create_texture_views().unwrap();
+            loader.create_material_data().unwrap();
+            (textured, untextured, [albedo, opacity, normal])
+        };
+        engine.flush_uploads().unwrap();
+        assert_eq!(engine.validation_errors(), Vec::<String>::new());
+
+        // Texture views are dense indices into the bindless table.
+        let view_ids = {
+            let views = scene.assets.read_storage::<TextureView>();
+            textures
+                .iter()
+                .map(|&texture| views.get(texture).unwrap().id as u32)
+                .collect::<Vec<_>>()
+        };
+        let mut sorted_ids = view_ids.clone();
+        sorted_ids.sort();
+        assert_eq!(sorted_ids, vec![0, 1, 2]);
+
+        let data = scene.material_data();
+        assert_eq!(data.len(), 2);
+        let textured_data = &data[scene.materials[&textured]];
+        assert_eq!(
+            (
+                textured_data.albedo_texture,
+                textured_data.opacity_texture,
+                textured_data.normal_texture
+            ),
+            (view_ids[0], view_ids[1], view_ids[2])
+        );
+        assert_eq!(textured_data.alpha_mode, material::ALPHA_MODE_MASK);
+        assert_eq!(textured_data.alpha_cutoff, 0.5);
+
+        // Missing textures fall back to the constant material values.
+        let untextured_data = &data[scene.materials[&untextured]];
+        for &texture in &[
+            untextured_data.albedo_texture,
+            untextured_data.opacity_texture,
+            untextured_data.normal_texture,
+            untextured_data.roughness_metal_texture,
+            untextured_data.emissive_texture,
+            textured_data.roughness_metal_texture,
+            textured_data.emissive_texture,
+        ] {
+            assert_eq!(texture, material::INVALID_TEXTURE);
+        }
+
+        let materials = Materials::load(&scene).unwrap();
+        let uv = [0.5, 0.5];
+        let fallback = [0.25; 4];
+        let sample = |texture| materials.sample(texture, uv, [0.0; 2], [0.0; 2], fallback);
+        assert_eq!(sample(material::INVALID_TEXTURE), fallback);
+        let albedo = sample(textured_data.albedo_texture);
+        assert!((albedo[0] - 1.0).abs() < 1e-2 && albedo[1] < 1e-2, "{:?}", albedo);
+        assert_eq!(materials.alpha(untextured_data, uv, [0.0; 2], [0.0; 2]), 1.0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,333 @@
+//! Resource state tracking.
+//!
+//! Records the current state of each resource in submission order and derives the
+//! barriers required to use a resource in a new state:
+//!
+//!  * Transitions between differing states. Read-only states are merged instead of
+//!    transitioning between different read states back and forth.
+//!  * UAV barriers for consecutive unordered access.
+//!
+//! Barriers are batched until `take_barriers` is called. Only whole resources are
+//! tracked, all barriers apply to `ALL_SUBRESOURCES`.
+//!
+//! Independent of the device, resources only need to provide a `ResourceId`.
+
+use backend::{Flags, ResourceId, ResourceStates};
+use std::collections::HashMap;
+
+/// All states which only allow read access.
+const READ_ONLY_STATES: ResourceStates = ResourceStates(
+    ResourceStates::GENERIC_READ.0
+        | ResourceStates::DEPTH_READ.0
+        | ResourceStates::RESOLVE_SOURCE.0,
+);
+
+fn is_read_only(state: ResourceStates) -> bool {
+    state != ResourceStates::COMMON && READ_ONLY_STATES.contains(state)
+}
+
+/// Barrier derived by the tracker.
+#[derive(Clone, Debug)]
+pub enum StateBarrier<R> {
+    Transition {
+        resource: R,
+        before: ResourceStates,
+        after: ResourceStates,
+    },
+    Uav {
+        resource: R,
+    },
+}
+
+pub struct StateTracker<R> {
+    states: HashMap<u64, ResourceStates>,
+    pending: Vec<StateBarrier<R>>,
+    mismatches: Vec<String>,
+}
+
+impl<R: ResourceId + Clone> StateTracker<R> {
+    pub fn new() -> Self {
+        StateTracker {
+            states: HashMap::new(),
+            pending: Vec::new(),
+            mismatches: Vec::new(),
+        }
+    }
+
+    /// Start tracking a resource in its initial state.
+    ///
+    /// Overrides the state of a previously registered resource with the same id.
+    pub fn register(&mut self, resource: &R, state: ResourceStates) {
+        self.states.insert(resource.resource_id(), state);
+    }
+
+    pub fn unregister(&mut self, resource: &R) {
+        self.states.remove(&resource.resource_id());
+    }
+
+    /// Use the resource in the requested state, queuing the required barriers.
+    pub fn use_resource(&mut self, resource: &R, state: ResourceStates) {
+        let id = resource.resource_id();
+        let current = match self.states.get(&id) {
+            Some(&current) => current,
+            None => {
+                self.report(format!(
+                    "resource {:x} used in {:?} without being registered",
+                    id, state
+                ));
+                return;
+            }
+        };
+
+        if current == state {
+            if state == ResourceStates::UNORDERED_ACCESS {
+                self.uav_barrier(resource);
+            }
+            return;
+        }
+
+        let after = if is_read_only(current) && is_read_only(state) {
+            if current.contains(state) {
+                return;
+            }
+            current | state
+        } else {
+            state
+        };
+
+        self.push_transition(resource, current, after);
+    }
+
+    /// Synchronize unordered accesses of consecutive operations.
+    pub fn uav_barrier(&mut self, resource: &R) {
+        self.pending.push(StateBarrier::Uav {
+            resource: resource.clone(),
+        });
+    }
+
+    fn push_transition(&mut self, resource: &R, before: ResourceStates, after: ResourceStates) {
+        let id = resource.resource_id();
+        self.states.insert(id, after);
+
+        // Merge with a pending transition of the same resource, if it's the last
+        // barrier affecting the resource.
+        let last = self.pending.iter().rposition(|barrier| match *barrier {
+            StateBarrier::Transition { ref resource, .. } | StateBarrier::Uav { ref resource } => {
+                resource.resource_id() == id
+            }
+        });
+        let pending = last.and_then(|idx| match self.pending[idx] {
+            StateBarrier::Transition { before, .. } => Some((idx, before)),
+            StateBarrier::Uav { .. } => None,
+        });
+        if let Some((idx, first)) = pending {
+            self.pending.remove(idx);
+            if first == after {
+                return;
+            }
+            self.pending.push(StateBarrier::Transition {
+                resource: resource.clone(),
+                before: first,
+                after,
+            });
+            return;
+        }
+
+        if before != after {
+            self.pending.push(StateBarrier::Transition {
+                resource: resource.clone(),
+                before,
+                after,
+            });
+        }
+    }
+
+    /// Take all batched barriers.
+    pub fn take_barriers(&mut self) -> Vec<StateBarrier<R>> {
+        ::std::mem::replace(&mut self.pending, Vec::new())
+    }
+
+    /// State mismatches reported so far (debug builds only).
+    pub fn mismatches(&self) -> &[String] {
+        &self.mismatches
+    }
+
+    fn report(&mut self, message: String) {
+        if cfg!(debug_assertions) {
+            self.mismatches.push(message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    struct Resource(u64);
+
+    impl ResourceId for Resource {
+        fn resource_id(&self) -> u64 {
+            self.0
+        }
+    }
+
+    /// Barriers as `(resource, before, after)` with `None` for UAV barriers.
+    fn barriers(tracker: &mut StateTracker<Resource>) -> Vec<(u64, Option<(u32, u32)>)> {
+        tracker
+            .take_barriers()
+            .into_iter()
+            .map(|barrier| match barrier {
+                StateBarrier::Transition {
+                    resource,
+                    before,
+                    after,
+                } => (resource.0, Some((before.0, after.0))),
+                StateBarrier::Uav { resource } => (resource.0, None),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn merge_read_states() {
+        let resource = Resource(1);
+        let mut tracker = StateTracker::new();
+        tracker.register(&resource, ResourceStates::PIXEL_SHADER_RESOURCE);
+
+        tracker.use_resource(&resource, ResourceStates::NON_PIXEL_SHADER_RESOURCE);
+        let both =
+            ResourceStates::PIXEL_SHADER_RESOURCE | ResourceStates::NON_PIXEL_SHADER_RESOURCE;
+        assert_eq!(
+            barriers(&mut tracker),
+            vec![(1, Some((ResourceStates::PIXEL_SHADER_RESOURCE.0, both.0)))]
+        );
+
+        // Contained read states don't require a barrier.
+        tracker.use_resource(&resource, ResourceStates::PIXEL_SHADER_RESOURCE);
+        tracker.use_resource(&resource, ResourceStates::NON_PIXEL_SHADER_RESOURCE);
+        assert!(barriers(&mut tracker).is_empty());
+        assert_eq!(tracker.states.get(&1), Some(&both));
+
+        // Writes transition from the merged read state.
+        tracker.use_resource(&resource, ResourceStates::RENDER_TARGET);
+        assert_eq!(
+            barriers(&mut tracker),
+            vec![(1, Some((both.0, ResourceStates::RENDER_TARGET.0)))]
+        );
+    }
+
+    #[test]
+    fn uav_barrier_between_unordered_accesses() {
+        let resource = Resource(1);
+        let mut tracker = StateTracker::new();
+        tracker.register(&resource, ResourceStates::COMMON);
+
+        tracker.use_resource(&resource, ResourceStates::UNORDERED_ACCESS);
+        tracker.use_resource(&resource, ResourceStates::UNORDERED_ACCESS);
+        assert_eq!(
+            barriers(&mut tracker),
+            vec![
+                (
+                    1,
+                    Some((ResourceStates::COMMON.0, ResourceStates::UNORDERED_ACCESS.0))
+                ),
+                (1, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_pending_transitions() {
+        let (a, b) = (Resource(1), Resource(2));
+        let mut tracker = StateTracker::new();
+        tracker.register(&a, ResourceStates::COPY_DEST);
+        tracker.register(&b, ResourceStates::COPY_DEST);
+
+        tracker.use_resource(&a, ResourceStates::RENDER_TARGET);
+        tracker.use_resource(&b, ResourceStates::COPY_SOURCE);
+        tracker.use_resource(&a, ResourceStates::PIXEL_SHADER_RESOURCE);
+        assert_eq!(
+            barriers(&mut tracker),
+            vec![
+                (
+                    2,
+                    Some((ResourceStates::COPY_DEST.0, ResourceStates::COPY_SOURCE.0))
+                ),
+                (
+                    1,
+                    Some((
+                        ResourceStates::COPY_DEST.0,
+                        ResourceStates::PIXEL_SHADER_RESOURCE.0
+                    ))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn cancel_pending_transitions() {
+        let resource = Resource(1);
+        let mut tracker = StateTracker::new();
+        tracker.register(&resource, ResourceStates::RENDER_TARGET);
+
+        tracker.use_resource(&resource, ResourceStates::COPY_SOURCE);
+        tracker.use_resource(&resource, ResourceStates::RENDER_TARGET);
+        assert!(barriers(&mut tracker).is_empty());
+        assert_eq!(tracker.states.get(&1), Some(&ResourceStates::RENDER_TARGET));
+    }
+
+    #[test]
+    fn keep_transitions_across_uav_barriers() {
+        let resource = Resource(1);
+        let mut tracker = StateTracker::new();
+        tracker.register(&resource, ResourceStates::COPY_DEST);
+
+        tracker.use_resource(&resource, ResourceStates::UNORDERED_ACCESS);
+        tracker.uav_barrier(&resource);
+        tracker.use_resource(&resource, ResourceStates::COPY_DEST);
+        assert_eq!(
+            barriers(&mut tracker),
+            vec![
+                (
+                    1,
+                    Some((
+                        ResourceStates::COPY_DEST.0,
+                        ResourceStates::UNORDERED_ACCESS.0
+                    ))
+                ),
+                (1, None),
+                (
+                    1,
+                    Some((
+                        ResourceStates::UNORDERED_ACCESS.0,
+                        ResourceStates::COPY_DEST.0
+                    ))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn report_mismatches() {
+        let (registered, unknown) = (Resource(1), Resource(2));
+        let mut tracker = StateTracker::new();
+        tracker.register(&registered, ResourceStates::COPY_DEST);
+
+        tracker.use_resource(&registered, ResourceStates::COPY_SOURCE);
+        assert!(tracker.mismatches().is_empty());
+
+        // Unregistered resources don't have a state to transition from.
+        tracker.use_resource(&unknown, ResourceStates::COPY_SOURCE);
+        assert_eq!(
+            barriers(&mut tracker),
+            vec![(
+                1,
+                Some((ResourceStates::COPY_DEST.0, ResourceStates::COPY_SOURCE.0))
+            )]
+        );
+        assert_eq!(
+            tracker.mismatches(),
+            &["resource 2 used in ResourceStates(2048) without being registered".to_string()]
+        );
+    }
+}
@@ -1,130 +1,107 @@
-use std::{mem, ptr};
-use winapi::Interface;
-use winapi::shared::dxgi::*;
-use winapi::shared::dxgi1_2::*;
-use winapi::shared::dxgi1_4::*;
-use winapi::shared::dxgiformat::*;
-use winapi::shared::dxgitype::*;
-use winapi::shared::minwindef::*;
-use winapi::um::d3d12::*;
+use backend::{
+    self, Backend, CpuDescriptor, DescriptorHeapType, Device, EngineError, Format, ResourceStates,
+    RtvDesc, SwapchainDesc,
+};
 use winit;
-use winit::os::windows::WindowExt;
-use wio::com::ComPtr;
 
+use descriptor::DescriptorRange;
 use engine::Engine;
 
-pub struct Swapchain {
-    swapchain: ComPtr<IDXGISwapChain3>,
-    render_targets: Vec<ComPtr<ID3D12Resource>>,
-    rtv_pool: ComPtr<ID3D12DescriptorHeap>,
-    rtv_size: usize,
+pub struct Swapchain<B: Backend> {
+    swapchain: B::Swapchain,
+    render_targets: Vec<B::Resource>,
+    rtvs: DescriptorRange,
 }
 
 pub type Frame = usize;
 
-impl Engine {
-    pub fn create_swapchain(&self, window: &winit::Window) -> Swapchain {
-        let buffer_count = self.frame_latency() as UINT;
-        let swapchain = {
-            let mut swapchain: *mut IDXGISwapChain3 = ptr::null_mut();
-            let format = DXGI_FORMAT_R8G8B8A8_UNORM;
-            let (width, height) = window.get_inner_size().unwrap();
-            let desc = DXGI_SWAP_CHAIN_DESC1 {
-                AlphaMode: DXGI_ALPHA_MODE_IGNORE,
-                BufferCount: buffer_count,
-                Width: width,
-                Height: height,
-                Format: format,
-                Flags: 0,
-                BufferUsage: DXGI_USAGE_RENDER_TARGET_OUTPUT,
-                SampleDesc: DXGI_SAMPLE_DESC {
-                    Count: 1,
-                    Quality: 0,
-                },
-                Scaling: DXGI_SCALING_NONE,
-                Stereo: FALSE,
-                SwapEffect: DXGI_SWAP_EFFECT_FLIP_DISCARD,
-            };
-            let _ = unsafe {
-                self.factory.CreateSwapChainForHwnd(
-                    self.queue.as_raw() as *mut _,
-                    window.get_hwnd() as *mut _,
-                    &desc,
-                    ptr::null(),
-                    ptr::null_mut(),
-                    &mut swapchain as *mut *mut _ as *mut *mut _,
-                )
-            };
-            unsafe { ComPtr::from_raw(swapchain) }
-        };
+/// Minimum number of backbuffers supported by flip model swapchains.
+const MIN_BUFFER_COUNT: u32 = 2;
 
-        let rtv_pool = self.create_descriptor_heap(
-            buffer_count as _,
-            D3D12_DESCRIPTOR_HEAP_TYPE_RTV,
-            D3D12_DESCRIPTOR_HEAP_FLAG_NONE,
-        );
-        let rtv_start = unsafe { rtv_pool.GetCPUDescriptorHandleForHeapStart() };
-        let rtv_size = unsafe {
-            self.device
-                .GetDescriptorHandleIncrementSize(D3D12_DESCRIPTOR_HEAP_TYPE_RTV)
-        };
+impl<B: Backend> Engine<B> {
+    /// Create a swapchain with `frame_latency` backbuffers, at least two.
+    ///
+    /// Headless backends don't require a window.
+    pub fn create_swapchain(
+        &mut self,
+        window: Option<&winit::Window>,
+        width: u32,
+        height: u32,
+    ) -> Result<Swapchain<B>, EngineError> {
+        let buffer_count = (self.frame_latency() as u32).max(MIN_BUFFER_COUNT);
+        let swapchain = self.device.create_swapchain(
+            window,
+            &SwapchainDesc {
+                width,
+                height,
+                buffer_count,
+                format: Format::R8G8B8A8Unorm,
+            },
+        )?;
 
-        let render_targets = (0..buffer_count)
-            .map(|i| {
-                let mut resource: *mut ID3D12Resource = ptr::null_mut();
-                unsafe {
-                    swapchain.GetBuffer(
-                        i as _,
-                        &ID3D12Resource::uuidof(),
-                        &mut resource as *mut *mut _ as *mut *mut _,
-                    );
-                }
-
-                let rtv = D3D12_CPU_DESCRIPTOR_HANDLE {
-                    ptr: rtv_start.ptr + (i * rtv_size) as usize,
-                };
-                let rtv_desc = D3D12_RENDER_TARGET_VIEW_DESC {
-                    Format: DXGI_FORMAT_R8G8B8A8_UNORM_SRGB,
-                    ViewDimension: D3D12_RTV_DIMENSION_TEXTURE2D,
-                    ..unsafe { mem::zeroed() }
-                };
-                unsafe {
-                    self.device.CreateRenderTargetView(resource, &rtv_desc, rtv);
-                }
+        let rtvs =
+            self.allocate_descriptors(DescriptorHeapType::Rtv, buffer_count, "swapchain rtv")?;
 
-                unsafe { ComPtr::from_raw(resource) }
-            })
-            .collect::<Vec<_>>();
+        let render_targets = self.create_render_targets(&swapchain, &rtvs);
 
-        Swapchain {
+        Ok(Swapchain {
             swapchain,
             render_targets,
-            rtv_pool,
-            rtv_size: rtv_size as usize,
-        }
+            rtvs,
+        })
     }
-}
 
-impl Swapchain {
-    pub fn get_render_target(
-        &self,
-        idx: usize,
-    ) -> (ComPtr<ID3D12Resource>, D3D12_CPU_DESCRIPTOR_HANDLE) {
-        let rtv_start = unsafe { self.rtv_pool.GetCPUDescriptorHandleForHeapStart() };
-        let rtv = D3D12_CPU_DESCRIPTOR_HANDLE {
-            ptr: rtv_start.ptr + (idx * self.rtv_size) as usize,
-        };
+    fn create_render_targets(
+        &mut self,
+        swapchain: &B::Swapchain,
+        rtvs: &DescriptorRange,
+    ) -> Vec<B::Resource> {
+        (0..backend::Swapchain::num_buffers(swapchain))
+            .map(|i| {
+                let resource = backend::Swapchain::buffer(swapchain, i);
+                self.device.create_render_target_view(
+                    &resource,
+                    &RtvDesc::Texture2D {
+                        format: Format::R8G8B8A8UnormSrgb,
+                    },
+                    rtvs.cpu(i as _),
+                );
+                self.states.register(&resource, ResourceStates::PRESENT);
+
+                resource
+            })
+            .collect()
+    }
+}
 
-        (self.render_targets[idx].clone(), rtv)
+impl<B: Backend> Swapchain<B> {
+    pub fn get_render_target(&self, idx: usize) -> (B::Resource, CpuDescriptor) {
+        (self.render_targets[idx].clone(), self.rtvs.cpu(idx as _))
     }
 
+    /// Index of the backbuffer to render to, independent of the frame context.
     pub fn begin_frame(&self) -> Frame {
-        unsafe { self.swapchain.GetCurrentBackBufferIndex() as _ }
+        backend::Swapchain::current_index(&self.swapchain)
+    }
+
+    pub fn end_frame(&self) -> Result<(), EngineError> {
+        backend::Swapchain::present(&self.swapchain)
     }
 
-    pub fn end_frame(&self) {
-        unsafe {
-            self.swapchain.Present(0, 0);
+    /// Resize the backbuffers, the GPU must be idle.
+    ///
+    /// Render target views are recreated in place.
+    pub fn resize(
+        &mut self,
+        engine: &mut Engine<B>,
+        width: u32,
+        height: u32,
+    ) -> Result<(), EngineError> {
+        for render_target in self.render_targets.drain(..) {
+            engine.states.unregister(&render_target);
         }
+        backend::Swapchain::resize(&mut self.swapchain, &engine.device, width, height)?;
+        self.render_targets = engine.create_render_targets(&self.swapchain, &self.rtvs);
+        Ok(())
     }
 }
@@ -0,0 +1,387 @@
+//! Block compression of RGBA8 texels.
+//!
+//!  * BC1: RGB, endpoints along the principal axis of the block colors. Only the
+//!         opaque four color mode is used for encoding.
+//!  * BC3: BC1 color block and a BC4 block for alpha.
+//!  * BC5: BC4 blocks for red and green, e.g. normal maps.
+//!
+//! Blocks are stored in row-major order, texels of partial blocks at the border are
+//! clamped to the image. BC7 is only loaded from files and can't be decoded.
+
+use backend::Format;
+use texture::{read_le, write_le};
+
+/// Texels of a block, row-major.
+type Block = [[u8; 4]; 16];
+
+/// Compress tightly packed RGBA8 texels into the block compressed `format`.
+pub fn compress(format: Format, width: u32, height: u32, data: &[u8]) -> Vec<u8> {
+    assert_eq!(data.len(), (width * height * 4) as usize);
+
+    let mut blocks = Vec::with_capacity((format.row_size(width) * format.num_rows(height)) as _);
+    for by in 0..format.num_rows(height) {
+        for bx in 0..(width + 3) / 4 {
+            let block = load_block(width, height, data, bx, by);
+            match format {
+                Format::Bc1Unorm | Format::Bc1UnormSrgb => {
+                    blocks.extend_from_slice(&encode_color(&block));
+                }
+                Format::Bc3Unorm | Format::Bc3UnormSrgb => {
+                    blocks.extend_from_slice(&encode_channel(&block, 3));
+                    blocks.extend_from_slice(&encode_color(&block));
+                }
+                Format::Bc5Unorm => {
+                    blocks.extend_from_slice(&encode_channel(&block, 0));
+                    blocks.extend_from_slice(&encode_channel(&block, 1));
+                }
+                _ => panic!("unsupported block compression format {:?}", format),
+            }
+        }
+    }
+    blocks
+}
+
+/// Decompress blocks of `format` into tightly packed RGBA8 texels.
+///
+/// Returns `None` for formats without decoder.
+pub fn decompress(format: Format, width: u32, height: u32, data: &[u8]) -> Option<Vec<u8>> {
+    let block_size = format.block_size() as usize;
+    match format {
+        Format::Bc1Unorm
+        | Format::Bc1UnormSrgb
+        | Format::Bc3Unorm
+        | Format::Bc3UnormSrgb
+        | Format::Bc5Unorm => (),
+        _ => return None,
+    }
+    assert_eq!(
+        data.len(),
+        (format.row_size(width) * format.num_rows(height)) as usize
+    );
+
+    let blocks_x = (width + 3) / 4;
+    let mut texels = vec![0; (width * height * 4) as usize];
+    for (i, block) in data.chunks(block_size).enumerate() {
+        let decoded = match format {
+            Format::Bc1Unorm | Format::Bc1UnormSrgb => decode_color(block, true),
+            Format::Bc3Unorm | Format::Bc3UnormSrgb => {
+                let mut texels = decode_color(&block[8..], false);
+                let alpha = decode_channel(&block[..8]);
+                for (texel, alpha) in texels.iter_mut().zip(alpha.iter()) {
+                    texel[3] = *alpha;
+                }
+                texels
+            }
+            _ => {
+                let (red, green) = (decode_channel(&block[..8]), decode_channel(&block[8..]));
+                let mut texels = [[0, 0, 0, 255]; 16];
+                for t in 0..16 {
+                    texels[t][0] = red[t];
+                    texels[t][1] = green[t];
+                }
+                texels
+            }
+        };
+
+        let (bx, by) = (i as u32 % blocks_x, i as u32 / blocks_x);
+        for t in 0..16 {
+            let (x, y) = (bx * 4 + t % 4, by * 4 + t / 4);
+            if x < width && y < height {
+                let offset = ((y * width + x) * 4) as usize;
+                texels[offset..offset + 4].copy_from_slice(&decoded[t as usize]);
+            }
+        }
+    }
+    Some(texels)
+}
+
+fn load_block(width: u32, height: u32, data: &[u8], bx: u32, by: u32) -> Block {
+    let mut block = [[0; 4]; 16];
+    for t in 0..16 {
+        let x = (bx * 4 + t % 4).min(width - 1);
+        let y = (by * 4 + t / 4).min(height - 1);
+        let offset = ((y * width + x) * 4) as usize;
+        block[t as usize].copy_from_slice(&data[offset..offset + 4]);
+    }
+    block
+}
+
+fn pack_565(color: [f32; 3]) -> u16 {
+    let quantize = |value: f32, max: f32| (value.max(0.0).min(255.0) * max / 255.0).round() as u16;
+    (quantize(color[0], 31.0) << 11) | (quantize(color[1], 63.0) << 5) | quantize(color[2], 31.0)
+}
+
+fn unpack_565(color: u16) -> [u8; 3] {
+    let r = ((color >> 11) & 0x1F) as u8;
+    let g = ((color >> 5) & 0x3F) as u8;
+    let b = (color & 0x1F) as u8;
+    [
+        (r << 3) | (r >> 2),
+        (g << 2) | (g >> 4),
+        (b << 3) | (b >> 2),
+    ]
+}
+
+/// Palette of a color block, `three_color` enables the transparent BC1 mode.
+fn color_palette(color0: u16, color1: u16, three_color: bool) -> [[u8; 4]; 4] {
+    let (c0, c1) = (unpack_565(color0), unpack_565(color1));
+    let mix = |w0: u32, w1: u32| {
+        let mut color = [0, 0, 0, 255];
+        for c in 0..3 {
+            color[c] = ((w0 * c0[c] as u32 + w1 * c1[c] as u32) / (w0 + w1)) as u8;
+        }
+        color
+    };
+
+    let endpoints = [[c0[0], c0[1], c0[2], 255], [c1[0], c1[1], c1[2], 255]];
+    if color0 > color1 || !three_color {
+        [endpoints[0], endpoints[1], mix(2, 1), mix(1, 2)]
+    } else {
+        [endpoints[0], endpoints[1], mix(1, 1), [0; 4]]
+    }
+}
+
+/// Encode the RGB channels of a block in four color mode.
+fn encode_color(block: &Block) -> [u8; 8] {
+    let colors = block
+        .iter()
+        .map(|texel| [texel[0] as f32, texel[1] as f32, texel[2] as f32])
+        .collect::<Vec<_>>();
+
+    let mut mean = [0.0; 3];
+    for color in &colors {
+        for c in 0..3 {
+            mean[c] += color[c] / 16.0;
+        }
+    }
+
+    // Principal axis of the colors via power iteration on the covariance matrix.
+    let mut covariance = [[0.0f32; 3]; 3];
+    for color in &colors {
+        let d = [color[0] - mean[0], color[1] - mean[1], color[2] - mean[2]];
+        for i in 0..3 {
+            for j in 0..3 {
+                covariance[i][j] += d[i] * d[j];
+            }
+        }
+    }
+    let mut axis = [1.0f32, 1.0, 1.0];
+    for _ in 0..8 {
+        let mut next = [0.0; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                next[i] += covariance[i][j] * axis[j];
+            }
+        }
+        let length = (next[0] * next[0] + next[1] * next[1] + next[2] * next[2]).sqrt();
+        if length < 1e-6 {
+            break;
+        }
+        axis = [next[0] / length, next[1] / length, next[2] / length];
+    }
+
+    let project = |color: &[f32; 3]| (0..3).map(|c| (color[c] - mean[c]) * axis[c]).sum::<f32>();
+    let (mut min, mut max) = (0.0f32, 0.0f32);
+    for color in &colors {
+        let t = project(color);
+        min = min.min(t);
+        max = max.max(t);
+    }
+    let endpoint = |t: f32| {
+        [
+            mean[0] + axis[0] * t,
+            mean[1] + axis[1] * t,
+            mean[2] + axis[2] * t,
+        ]
+    };
+
+    let (mut color0, mut color1) = (pack_565(endpoint(max)), pack_565(endpoint(min)));
+    if color0 < color1 {
+        ::std::mem::swap(&mut color0, &mut color1);
+    }
+
+    // Equal endpoints select the first color with all indices 0.
+    let mut indices = 0u32;
+    if color0 != color1 {
+        let palette = color_palette(color0, color1, false);
+        for (t, texel) in block.iter().enumerate() {
+            let index = nearest(&palette, |entry| {
+                (0..3)
+                    .map(|c| {
+                        let d = entry[c] as i32 - texel[c] as i32;
+                        (d * d) as u32
+                    })
+                    .sum()
+            });
+            indices |= (index as u32) << (2 * t);
+        }
+    }
+
+    let mut encoded = [0; 8];
+    write_le(color0 as _, &mut encoded[0..2]);
+    write_le(color1 as _, &mut encoded[2..4]);
+    write_le(indices as _, &mut encoded[4..8]);
+    encoded
+}
+
+fn decode_color(block: &[u8], three_color: bool) -> Block {
+    let color0 = read_le(&block[0..2]) as u16;
+    let color1 = read_le(&block[2..4]) as u16;
+    let indices = read_le(&block[4..8]);
+    let palette = color_palette(color0, color1, three_color);
+
+    let mut texels = [[0; 4]; 16];
+    for t in 0..16 {
+        texels[t] = palette[((indices >> (2 * t)) & 0x3) as usize];
+    }
+    texels
+}
+
+/// Palette of a BC4 block.
+fn channel_palette(value0: u8, value1: u8) -> [u8; 8] {
+    let (v0, v1) = (value0 as u32, value1 as u32);
+    let mut palette = [value0, value1, 0, 0, 0, 0, 0, 255];
+    if value0 > value1 {
+        for i in 2..8 {
+            palette[i] = (((8 - i as u32) * v0 + (i as u32 - 1) * v1) / 7) as u8;
+        }
+    } else {
+        for i in 2..6 {
+            palette[i] = (((6 - i as u32) * v0 + (i as u32 - 1) * v1) / 5) as u8;
+        }
+    }
+    palette
+}
+
+/// Encode a single channel of a block as BC4 block in eight value mode.
+fn encode_channel(block: &Block, channel: usize) -> [u8; 8] {
+    let values = block.iter().map(|texel| texel[channel]);
+    let max = values.clone().max().unwrap();
+    let min = values.min().unwrap();
+
+    // Equal endpoints select the first value with all indices 0.
+    let mut indices = 0u64;
+    if max != min {
+        let palette = channel_palette(max, min);
+        for (t, texel) in block.iter().enumerate() {
+            let index = nearest(&palette, |entry| {
+                (*entry as i32 - texel[channel] as i32).abs() as u32
+            });
+            indices |= (index as u64) << (3 * t);
+        }
+    }
+
+    let mut encoded = [0; 8];
+    encoded[0] = max;
+    encoded[1] = min;
+    write_le(indices, &mut encoded[2..8]);
+    encoded
+}
+
+fn decode_channel(block: &[u8]) -> [u8; 16] {
+    let palette = channel_palette(block[0], block[1]);
+    let indices = read_le(&block[2..8]);
+
+    let mut values = [0; 16];
+    for t in 0..16 {
+        values[t] = palette[((indices >> (3 * t)) & 0x7) as usize];
+    }
+    values
+}
+
+/// Index of the palette entry with the smallest error.
+fn nearest<T, F: Fn(&T) -> u32>(palette: &[T], error: F) -> usize {
+    let mut best = (0, !0);
+    for (i, entry) in palette.iter().enumerate() {
+        let e = error(entry);
+        if e < best.1 {
+            best = (i, e);
+        }
+    }
+    best.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Extent with partial blocks at the right and bottom border.
+    const WIDTH: u32 = 13;
+    const HEIGHT: u32 = 9;
+
+    /// Gradient along the diagonal, the colors of each block lie on a line.
+    ///
+    /// Channels span up to 76 values within a block, the palettes of 4 colors and
+    /// 8 values are at most 13 and 6 values apart.
+    fn gradient() -> Vec<u8> {
+        let mut data = Vec::new();
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let t = (x + y) * 255 / (WIDTH + HEIGHT - 2);
+                data.extend_from_slice(&[
+                    t as u8,
+                    255 - t as u8,
+                    (t / 2) as u8,
+                    (t / 3 + 100) as u8,
+                ]);
+            }
+        }
+        data
+    }
+
+    /// Largest error of the `channels` after compressing and decompressing `data`.
+    fn round_trip_error(format: Format, data: &[u8], channels: &[usize]) -> u8 {
+        let blocks = compress(format, WIDTH, HEIGHT, data);
+        assert_eq!(blocks.len(), 4 * 3 * format.block_size() as usize);
+
+        let decoded = decompress(format, WIDTH, HEIGHT, &blocks).unwrap();
+        let mut error = 0;
+        for (texel, decoded) in data.chunks(4).zip(decoded.chunks(4)) {
+            for &c in channels {
+                error = error.max((texel[c] as i32 - decoded[c] as i32).abs() as u8);
+            }
+        }
+        error
+    }
+
+    #[test]
+    fn bc1_round_trip() {
+        let error = round_trip_error(Format::Bc1Unorm, &gradient(), &[0, 1, 2]);
+        assert!(error <= 16, "error {}", error);
+    }
+
+    #[test]
+    fn bc3_round_trip() {
+        let error = round_trip_error(Format::Bc3UnormSrgb, &gradient(), &[0, 1, 2]);
+        assert!(error <= 16, "error {}", error);
+        let error = round_trip_error(Format::Bc3UnormSrgb, &gradient(), &[3]);
+        assert!(error <= 4, "error {}", error);
+    }
+
+    #[test]
+    fn bc5_round_trip() {
+        let data = gradient();
+        let error = round_trip_error(Format::Bc5Unorm, &data, &[0, 1]);
+        assert!(error <= 6, "error {}", error);
+
+        let blocks = compress(Format::Bc5Unorm, WIDTH, HEIGHT, &data);
+        let decoded = decompress(Format::Bc5Unorm, WIDTH, HEIGHT, &blocks).unwrap();
+        assert!(decoded
+            .chunks(4)
+            .all(|texel| texel[2] == 0 && texel[3] == 255));
+    }
+
+    #[test]
+    fn uniform_block() {
+        // Exactly representable in 5:6:5, all indices select the first endpoint.
+        let data = [255, 0, 255, 255].repeat(16);
+        let blocks = compress(Format::Bc1Unorm, 4, 4, &data);
+        assert_eq!(blocks, [0x1F, 0xF8, 0x1F, 0xF8, 0, 0, 0, 0]);
+        assert_eq!(decompress(Format::Bc1Unorm, 4, 4, &blocks).unwrap(), data);
+    }
+
+    #[test]
+    fn bc7_not_decoded() {
+        assert_eq!(decompress(Format::Bc7Unorm, 4, 4, &[0; 16]), None);
+    }
+}
@@ -0,0 +1,130 @@
+//! DirectDraw Surface files.
+//!
+//! Supports 2D textures with mip levels in BC1, BC3, BC5, BC7 and RGBA8, either
+//! via the legacy pixel format or the DX10 header extension. Cube maps, volumes
+//! and arrays are rejected.
+
+use backend::Format;
+use texture::{mip, read_le, TextureData};
+
+const MAGIC: &[u8] = b"DDS ";
+const HEADER_SIZE: usize = 124;
+const DX10_HEADER_SIZE: usize = 20;
+
+// Header flags
+const DDSD_MIPMAPCOUNT: u64 = 0x2_0000;
+// Pixel format flags
+const DDPF_FOURCC: u64 = 0x4;
+const DDPF_RGB: u64 = 0x40;
+// Caps2 flags
+const DDSCAPS2_CUBEMAP: u64 = 0x200;
+const DDSCAPS2_VOLUME: u64 = 0x20_0000;
+
+const DIMENSION_TEXTURE2D: u64 = 3;
+const MISC_TEXTURECUBE: u64 = 0x4;
+
+pub fn read(bytes: &[u8]) -> Result<TextureData, String> {
+    if bytes.len() < MAGIC.len() + HEADER_SIZE || &bytes[..MAGIC.len()] != MAGIC {
+        return Err("invalid DDS header".into());
+    }
+    let header = &bytes[MAGIC.len()..];
+    let field = |offset: usize| read_le(&header[offset..offset + 4]);
+
+    let flags = field(4);
+    let height = field(8) as u32;
+    let width = field(12) as u32;
+    let mip_levels = if flags & DDSD_MIPMAPCOUNT != 0 {
+        field(24).max(1) as u32
+    } else {
+        1
+    };
+    let (pf_flags, four_cc) = (field(76), &header[80..84]);
+    if field(108) & (DDSCAPS2_CUBEMAP | DDSCAPS2_VOLUME) != 0 {
+        return Err("cube map and volume DDS files aren't supported".into());
+    }
+
+    let mut data_offset = MAGIC.len() + HEADER_SIZE;
+    let mut swizzle_bgra = false;
+    let format = if pf_flags & DDPF_FOURCC != 0 {
+        match four_cc {
+            b"DXT1" => Format::Bc1Unorm,
+            b"DXT5" => Format::Bc3Unorm,
+            b"ATI2" | b"BC5U" => Format::Bc5Unorm,
+            b"DX10" => {
+                if bytes.len() < data_offset + DX10_HEADER_SIZE {
+                    return Err("invalid DX10 header".into());
+                }
+                let dx10 = &bytes[data_offset..];
+                let field = |offset: usize| read_le(&dx10[offset..offset + 4]);
+                if field(4) != DIMENSION_TEXTURE2D
+                    || field(8) & MISC_TEXTURECUBE != 0
+                    || field(12) > 1
+                {
+                    return Err("only single 2D textures are supported".into());
+                }
+                data_offset += DX10_HEADER_SIZE;
+                map_dxgi_format(field(0) as u32)
+                    .ok_or_else(|| format!("unsupported DXGI format {}", field(0)))?
+            }
+            _ => {
+                return Err(format!(
+                    "unsupported pixel format `{}`",
+                    String::from_utf8_lossy(four_cc)
+                ))
+            }
+        }
+    } else if pf_flags & DDPF_RGB != 0 && field(84) == 32 {
+        match (field(88), field(92), field(96)) {
+            (0xFF, 0xFF00, 0xFF_0000) => Format::R8G8B8A8Unorm,
+            (0xFF_0000, 0xFF00, 0xFF) => {
+                swizzle_bgra = true;
+                Format::R8G8B8A8Unorm
+            }
+            _ => return Err("unsupported RGB channel masks".into()),
+        }
+    } else {
+        return Err("unsupported pixel format".into());
+    };
+
+    let mut levels = Vec::new();
+    for level in 0..mip_levels {
+        let (level_width, level_height) = (
+            mip::level_extent(width, level),
+            mip::level_extent(height, level),
+        );
+        let size = (format.row_size(level_width) * format.num_rows(level_height)) as usize;
+        if bytes.len() < data_offset + size {
+            return Err(format!("truncated mip level {}", level));
+        }
+        let mut data = bytes[data_offset..data_offset + size].to_vec();
+        if swizzle_bgra {
+            for texel in data.chunks_mut(4) {
+                texel.swap(0, 2);
+            }
+        }
+        levels.push(data);
+        data_offset += size;
+    }
+
+    Ok(TextureData {
+        format,
+        width,
+        height,
+        levels,
+    })
+}
+
+fn map_dxgi_format(format: u32) -> Option<Format> {
+    match format {
+        28 => Some(Format::R8G8B8A8Unorm),
+        29 => Some(Format::R8G8B8A8UnormSrgb),
+        71 => Some(Format::Bc1Unorm),
+        72 => Some(Format::Bc1UnormSrgb),
+        77 => Some(Format::Bc3Unorm),
+        78 => Some(Format::Bc3UnormSrgb),
+        83 => Some(Format::Bc5Unorm),
+        98 => Some(Format::Bc7Unorm),
+        99 => Some(Format::Bc7UnormSrgb),
+        _ => None,
+    }
+}
@@ -0,0 +1,81 @@
+//! Khronos KTX2 files.
+//!
+//! Supports 2D textures with mip levels in BC1, BC3, BC5, BC7 and RGBA8 without
+//! supercompression. Arrays, cube maps and 3D textures are rejected, the data
+//! format descriptor and key/value data are ignored.
+
+use backend::Format;
+use texture::{mip, read_le, TextureData};
+
+const IDENTIFIER: &[u8] = b"\xABKTX 20\xBB\r\n\x1A\n";
+/// Identifier, header and index.
+const HEADER_SIZE: usize = 80;
+const LEVEL_INDEX_SIZE: usize = 24;
+
+pub fn read(bytes: &[u8]) -> Result<TextureData, String> {
+    if bytes.len() < HEADER_SIZE || &bytes[..IDENTIFIER.len()] != IDENTIFIER {
+        return Err("invalid KTX2 header".into());
+    }
+    let field = |offset: usize| read_le(&bytes[offset..offset + 4]);
+
+    let vk_format = field(12);
+    let width = field(20) as u32;
+    let height = field(24) as u32;
+    let (depth, layers, faces) = (field(28), field(32), field(36));
+    // Zero levels request generating the mip chain, only the top level is stored.
+    let mip_levels = field(40).max(1) as u32;
+    let supercompression = field(44);
+
+    if height == 0 || depth != 0 || layers > 1 || faces != 1 {
+        return Err("only single 2D textures are supported".into());
+    }
+    if supercompression != 0 {
+        return Err(format!(
+            "unsupported supercompression scheme {}",
+            supercompression
+        ));
+    }
+    let format = map_vk_format(vk_format as u32)
+        .ok_or_else(|| format!("unsupported Vulkan format {}", vk_format))?;
+
+    if bytes.len() < HEADER_SIZE + mip_levels as usize * LEVEL_INDEX_SIZE {
+        return Err("truncated level index".into());
+    }
+
+    let mut levels = Vec::new();
+    for level in 0..mip_levels {
+        let index = HEADER_SIZE + level as usize * LEVEL_INDEX_SIZE;
+        let offset = read_le(&bytes[index..index + 8]) as usize;
+        let length = read_le(&bytes[index + 8..index + 16]) as usize;
+
+        let size = format.row_size(mip::level_extent(width, level))
+            * format.num_rows(mip::level_extent(height, level));
+        let end = offset.checked_add(length).unwrap_or(!0);
+        if length != size as usize || end > bytes.len() {
+            return Err(format!("invalid mip level {}", level));
+        }
+        levels.push(bytes[offset..end].to_vec());
+    }
+
+    Ok(TextureData {
+        format,
+        width,
+        height,
+        levels,
+    })
+}
+
+fn map_vk_format(format: u32) -> Option<Format> {
+    match format {
+        37 => Some(Format::R8G8B8A8Unorm),
+        43 => Some(Format::R8G8B8A8UnormSrgb),
+        131 | 133 => Some(Format::Bc1Unorm),
+        132 | 134 => Some(Format::Bc1UnormSrgb),
+        137 => Some(Format::Bc3Unorm),
+        138 => Some(Format::Bc3UnormSrgb),
+        141 => Some(Format::Bc5Unorm),
+        145 => Some(Format::Bc7Unorm),
+        146 => Some(Format::Bc7UnormSrgb),
+        _ => None,
+    }
+}
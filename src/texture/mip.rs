@@ -0,0 +1,205 @@
+//! Mip chain generation of RGBA8 images.
+//!
+//! Each level is a box filtered reduction of the previous level to half its extent,
+//! rounded down. Destination texels average the source area they cover, including
+//! fractional texels for odd extents. Color channels of sRGB images are filtered in
+//! linear space, alpha is always linear.
+
+/// Number of levels of a full mip chain down to 1x1.
+pub fn num_levels(width: u32, height: u32) -> u32 {
+    32 - width.max(height).max(1).leading_zeros()
+}
+
+/// Extent of a mip level, at least 1.
+pub fn level_extent(size: u32, level: u32) -> u32 {
+    (size >> level).max(1)
+}
+
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Generate the full mip chain of tightly packed RGBA8 texels.
+///
+/// Returns all levels including a copy of the top level.
+pub fn generate_mips(width: u32, height: u32, data: &[u8], srgb: bool) -> Vec<Vec<u8>> {
+    assert_eq!(data.len(), (width * height * 4) as usize);
+
+    // Decode table of the color channels.
+    let decode = (0..256)
+        .map(|value| {
+            let value = value as f32 / 255.0;
+            if srgb {
+                srgb_to_linear(value)
+            } else {
+                value
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut levels = vec![data.to_vec()];
+    let mut src = data
+        .chunks(4)
+        .map(|texel| {
+            [
+                decode[texel[0] as usize],
+                decode[texel[1] as usize],
+                decode[texel[2] as usize],
+                texel[3] as f32 / 255.0,
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    for level in 1..num_levels(width, height) {
+        let (src_width, src_height) = (
+            level_extent(width, level - 1),
+            level_extent(height, level - 1),
+        );
+        let (dst_width, dst_height) = (level_extent(width, level), level_extent(height, level));
+        let dst = downsample(&src, src_width, src_height, dst_width, dst_height);
+
+        let mut texels = Vec::with_capacity(dst.len() * 4);
+        for texel in &dst {
+            for c in 0..4 {
+                let value = if srgb && c < 3 {
+                    linear_to_srgb(texel[c])
+                } else {
+                    texel[c]
+                };
+                texels.push((value.max(0.0).min(1.0) * 255.0).round() as u8);
+            }
+        }
+        levels.push(texels);
+        src = dst;
+    }
+
+    levels
+}
+
+/// Source texels and weights covered by each destination texel along one axis.
+fn filter_weights(src_size: u32, dst_size: u32) -> Vec<Vec<(u32, f32)>> {
+    let scale = src_size as f32 / dst_size as f32;
+    (0..dst_size)
+        .map(|x| {
+            let start = x as f32 * scale;
+            let end = start + scale;
+            let mut weights = Vec::new();
+            let mut texel = start.floor() as u32;
+            while (texel as f32) < end && texel < src_size {
+                let coverage = end.min(texel as f32 + 1.0) - start.max(texel as f32);
+                if coverage > 0.0 {
+                    weights.push((texel, coverage / scale));
+                }
+                texel += 1;
+            }
+            weights
+        })
+        .collect()
+}
+
+/// Box filter of linear texels.
+fn downsample(
+    src: &[[f32; 4]],
+    src_width: u32,
+    src_height: u32,
+    dst_width: u32,
+    dst_height: u32,
+) -> Vec<[f32; 4]> {
+    let weights_x = filter_weights(src_width, dst_width);
+    let weights_y = filter_weights(src_height, dst_height);
+
+    let mut dst = Vec::with_capacity((dst_width * dst_height) as usize);
+    for wy in &weights_y {
+        for wx in &weights_x {
+            let mut texel = [0.0; 4];
+            for &(y, weight_y) in wy {
+                for &(x, weight_x) in wx {
+                    let src_texel = src[(y * src_width + x) as usize];
+                    for c in 0..4 {
+                        texel[c] += src_texel[c] * weight_x * weight_y;
+                    }
+                }
+            }
+            dst.push(texel);
+        }
+    }
+    dst
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Single channel value replicated to the color channels with opaque alpha.
+    fn gray(values: &[u8]) -> Vec<u8> {
+        values.iter().flat_map(|&v| vec![v, v, v, 255]).collect()
+    }
+
+    #[test]
+    fn level_extents() {
+        assert_eq!(num_levels(1, 1), 1);
+        assert_eq!(num_levels(256, 256), 9);
+        assert_eq!(num_levels(300, 5), 9);
+
+        let widths = (0..num_levels(300, 5))
+            .map(|level| level_extent(300, level))
+            .collect::<Vec<_>>();
+        assert_eq!(widths, [300, 150, 75, 37, 18, 9, 4, 2, 1]);
+        assert_eq!(level_extent(5, 8), 1);
+    }
+
+    #[test]
+    fn level_sizes() {
+        let levels = generate_mips(300, 5, &vec![0; 300 * 5 * 4], false);
+        assert_eq!(levels.len(), 9);
+        for (level, texels) in levels.iter().enumerate() {
+            let level = level as u32;
+            let size = level_extent(300, level) * level_extent(5, level) * 4;
+            assert_eq!(texels.len(), size as usize, "level {}", level);
+        }
+    }
+
+    #[test]
+    fn odd_extent_covers_fractional_texels() {
+        // Each of the three source texels covers a third of the destination texel.
+        let levels = generate_mips(3, 1, &gray(&[0, 255, 0]), false);
+        assert_eq!(levels[1], [85, 85, 85, 255]);
+
+        // 5 -> 2: the middle texel is split between both destination texels.
+        let levels = generate_mips(5, 1, &gray(&[0, 0, 255, 0, 0]), false);
+        assert_eq!(levels[1], [51, 51, 51, 255, 51, 51, 51, 255]);
+    }
+
+    #[test]
+    fn srgb_filtered_in_linear_space() {
+        let data = [0, 0, 0, 0, 255, 255, 255, 255];
+
+        let linear = generate_mips(2, 1, &data, false);
+        assert_eq!(linear[1], [128, 128, 128, 128]);
+
+        // Linear 0.5 encoded to sRGB, alpha is always linear.
+        let srgb = generate_mips(2, 1, &data, true);
+        assert_eq!(srgb[1], [188, 188, 188, 128]);
+    }
+
+    #[test]
+    fn srgb_round_trip() {
+        for value in 0..256 {
+            let value = value as f32 / 255.0;
+            let round_trip = linear_to_srgb(srgb_to_linear(value));
+            assert!((round_trip - value).abs() < 1e-5, "{}", value);
+        }
+    }
+}
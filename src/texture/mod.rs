@@ -0,0 +1,220 @@
+//! Texture import.
+//!
+//! Textures are imported on the CPU into `TextureData`, a mip chain in a single
+//! format ready for uploading:
+//!
+//!  * Images (PNG, JPEG, TGA, ..): Decoded to RGBA8 with a generated mip chain,
+//!    block compressed depending on the usage of the texture.
+//!  * DDS and KTX2: Loaded as stored. Uncompressed files without mip levels are
+//!    processed like images.
+//!
+//! The color encoding (sRGB or linear) is defined by the usage, overriding the
+//! format stored in DDS and KTX2 files.
+
+pub mod bc;
+pub mod dds;
+pub mod ktx2;
+pub mod mip;
+
+use backend::{EngineError, ErrorKind, Format, ResourceDesc};
+use image;
+use std::fs;
+use std::path::Path;
+
+/// Linear and sRGB variants of the texture formats.
+const SRGB_FORMATS: [(Format, Format); 4] = [
+    (Format::R8G8B8A8Unorm, Format::R8G8B8A8UnormSrgb),
+    (Format::Bc1Unorm, Format::Bc1UnormSrgb),
+    (Format::Bc3Unorm, Format::Bc3UnormSrgb),
+    (Format::Bc7Unorm, Format::Bc7UnormSrgb),
+];
+
+/// Content of a texture, selects the color encoding and compression of images.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TextureUsage {
+    /// sRGB encoded color, e.g. albedo. Compressed to BC1, or BC3 if it has alpha.
+    Color,
+    /// Linear data, e.g. opacity or roughness. Compressed to BC1, or BC3 if it has alpha.
+    Data,
+    /// Tangent space normal map. Compressed to BC5 storing only `x` and `y`, `z`
+    /// needs to be reconstructed when sampling.
+    Normal,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ImportOptions {
+    /// Block compress images, extents not a multiple of the block size stay uncompressed.
+    pub compress: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        ImportOptions { compress: true }
+    }
+}
+
+/// CPU copy of a 2D texture.
+#[derive(Clone, Debug)]
+pub struct TextureData {
+    pub format: Format,
+    pub width: u32,
+    pub height: u32,
+    /// Tightly packed rows of each mip level, starting with the top level.
+    pub levels: Vec<Vec<u8>>,
+}
+
+impl TextureData {
+    pub fn desc(&self) -> ResourceDesc {
+        ResourceDesc {
+            mip_levels: self.levels.len() as _,
+            ..ResourceDesc::texture_2d(self.width, self.height, self.format, 1)
+        }
+    }
+
+    pub fn level_extent(&self, level: u32) -> (u32, u32) {
+        (
+            mip::level_extent(self.width, level),
+            mip::level_extent(self.height, level),
+        )
+    }
+
+    /// Upload data of all mip levels.
+    pub fn subresources(&self) -> Vec<&[u8]> {
+        self.levels.iter().map(|data| &data[..]).collect()
+    }
+
+    /// Decode a mip level into RGBA8 texels, `None` for formats without decoder.
+    pub fn decode_level(&self, level: u32) -> Option<Vec<u8>> {
+        let data = &self.levels[level as usize];
+        match self.format {
+            Format::R8G8B8A8Unorm | Format::R8G8B8A8UnormSrgb => Some(data.clone()),
+            format => {
+                let (width, height) = self.level_extent(level);
+                bc::decompress(format, width, height, data)
+            }
+        }
+    }
+}
+
+/// Import a texture from an image, DDS or KTX2 file.
+pub fn import(
+    path: &Path,
+    usage: TextureUsage,
+    options: &ImportOptions,
+) -> Result<TextureData, EngineError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_lowercase());
+    let texture = match extension.as_ref().map(String::as_str) {
+        Some("dds") => fs::read(path)
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| dds::read(&bytes)),
+        Some("ktx2") => fs::read(path)
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| ktx2::read(&bytes)),
+        _ => image::open(path).map_err(|err| err.to_string()).map(|img| {
+            let img = img.to_rgba();
+            let (width, height) = img.dimensions();
+            TextureData {
+                format: Format::R8G8B8A8Unorm,
+                width,
+                height,
+                levels: vec![img.into_raw()],
+            }
+        }),
+    }
+    .and_then(|texture| {
+        let extent = texture.format.block_extent();
+        if texture.width % extent != 0 || texture.height % extent != 0 {
+            Err("extent of block compressed texture isn't a multiple of the block size".into())
+        } else {
+            Ok(texture)
+        }
+    })
+    .map_err(|err| {
+        EngineError::new(
+            "import_texture",
+            path.display().to_string(),
+            ErrorKind::Asset(err),
+        )
+    })?;
+
+    let mut texture = if !texture.format.is_compressed() && texture.levels.len() == 1 {
+        process_image(texture, usage, options)
+    } else {
+        texture
+    };
+    texture.format = color_encoding(texture.format, usage == TextureUsage::Color);
+    Ok(texture)
+}
+
+/// Generate the mip chain of an RGBA8 texture and compress it.
+fn process_image(
+    texture: TextureData,
+    usage: TextureUsage,
+    options: &ImportOptions,
+) -> TextureData {
+    let (width, height) = (texture.width, texture.height);
+    let data = &texture.levels[0];
+    let levels = mip::generate_mips(width, height, data, usage == TextureUsage::Color);
+
+    let format = if !options.compress || width % 4 != 0 || height % 4 != 0 {
+        Format::R8G8B8A8Unorm
+    } else if usage == TextureUsage::Normal {
+        Format::Bc5Unorm
+    } else if data.chunks(4).all(|texel| texel[3] == 255) {
+        Format::Bc1Unorm
+    } else {
+        Format::Bc3Unorm
+    };
+
+    let levels = if format.is_compressed() {
+        levels
+            .iter()
+            .enumerate()
+            .map(|(level, data)| {
+                let level = level as u32;
+                bc::compress(
+                    format,
+                    mip::level_extent(width, level),
+                    mip::level_extent(height, level),
+                    data,
+                )
+            })
+            .collect()
+    } else {
+        levels
+    };
+
+    TextureData {
+        format,
+        width,
+        height,
+        levels,
+    }
+}
+
+/// sRGB or linear variant of `format`, formats without sRGB variant are returned as is.
+fn color_encoding(format: Format, srgb: bool) -> Format {
+    SRGB_FORMATS
+        .iter()
+        .find(|&&(linear, srgb_format)| format == linear || format == srgb_format)
+        .map(|&(linear, srgb_format)| if srgb { srgb_format } else { linear })
+        .unwrap_or(format)
+}
+
+/// Read a little endian integer of up to 8 bytes.
+fn read_le(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .rev()
+        .fold(0, |value, &byte| (value << 8) | byte as u64)
+}
+
+/// Write the lower bytes of `value` little endian, filling `bytes`.
+fn write_le(value: u64, bytes: &mut [u8]) {
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = (value >> (8 * i)) as u8;
+    }
+}
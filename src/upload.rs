@@ -0,0 +1,683 @@
+//! Resource uploads through a persistent staging ring.
+//!
+//! Data is written into a persistently mapped buffer in the upload heap and copied
+//! into the destination resources on the direct queue. Regions of the ring are
+//! reclaimed once the fence value signaled after the submission using them has been
+//! reached. Uploads larger than the ring are split into chunks, submitting pending
+//! copies and waiting for the GPU whenever the ring is exhausted.
+//!
+//! Copies are recorded into an internal command list and submitted on `flush`.
+//! Resource states are tracked in submission order, pending uploads need to be
+//! flushed before executing command lists recorded afterwards.
+
+use backend::{
+    Backend, CommandList, Device, EngineError, ErrorKind, Format, HeapType, PlacedFootprint,
+    ResourceDesc, ResourceStates,
+};
+use engine;
+use frame::FENCE_TIMEOUT_MS;
+use state::StateTracker;
+use std::collections::VecDeque;
+use std::{mem, ptr, slice};
+
+/// Alignment of buffer regions in the ring.
+const BUFFER_ALIGNMENT: u64 = 16;
+/// Required alignment of texture footprints in a buffer.
+const TEXTURE_PLACEMENT_ALIGNMENT: u64 = 512;
+/// Required alignment of texture footprint rows.
+const TEXTURE_PITCH_ALIGNMENT: u32 = 256;
+
+fn align_up(value: u64, alignment: u64) -> u64 {
+    (value + alignment - 1) & !(alignment - 1)
+}
+
+/// Layout of a subresource in a buffer, as returned by `GetCopyableFootprints`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SubresourceFootprint {
+    /// Extent is aligned to the compression blocks of the format.
+    pub footprint: PlacedFootprint,
+    /// Rows of texels or compression blocks.
+    pub num_rows: u32,
+    /// Size of a row without the pitch padding.
+    pub row_size: u32,
+}
+
+impl SubresourceFootprint {
+    /// Footprint of a `width` x `height` subresource placed at or after `offset`.
+    pub fn place(format: Format, width: u32, height: u32, offset: u64) -> Self {
+        let extent = format.block_extent();
+        let row_size = format.row_size(width);
+        SubresourceFootprint {
+            footprint: PlacedFootprint {
+                offset: align_up(offset, TEXTURE_PLACEMENT_ALIGNMENT),
+                format,
+                width: (width + extent - 1) / extent * extent,
+                height: (height + extent - 1) / extent * extent,
+                depth: 1,
+                row_pitch: align_up(row_size as _, TEXTURE_PITCH_ALIGNMENT as _) as u32,
+            },
+            num_rows: format.num_rows(height),
+            row_size,
+        }
+    }
+
+    /// End of the subresource in the buffer, excluding the padding of the last row.
+    pub fn end(&self) -> u64 {
+        self.footprint.offset
+            + self.footprint.row_pitch as u64 * (self.num_rows as u64 - 1)
+            + self.row_size as u64
+    }
+}
+
+/// Layout of all subresources of the texture `desc` in a buffer starting at `base_offset`.
+///
+/// Returns the footprints in subresource order and the total size in bytes.
+pub fn copyable_footprints(
+    desc: &ResourceDesc,
+    base_offset: u64,
+) -> (Vec<SubresourceFootprint>, u64) {
+    let mut footprints = Vec::new();
+    let mut offset = base_offset;
+    for _ in 0..desc.depth_or_array_size {
+        for level in 0..desc.mip_levels as u32 {
+            let footprint = SubresourceFootprint::place(
+                desc.format,
+                (desc.width as u32 >> level).max(1),
+                (desc.height >> level).max(1),
+                offset,
+            );
+            offset = footprint.end();
+            footprints.push(footprint);
+        }
+    }
+    (footprints, offset - base_offset)
+}
+
+/// Ring buffer allocation of the staging memory, independent of the device.
+///
+/// Allocations are released in batches, each batch is closed by `submit` with the
+/// fence value marking the end of its GPU usage.
+#[derive(Debug)]
+pub struct RingAllocator {
+    capacity: u64,
+    head: u64,
+    tail: u64,
+    used: u64,
+    // Bytes allocated since the last submit.
+    pending: u64,
+    // Fence value and size of submitted batches, oldest first.
+    submissions: VecDeque<(u64, u64)>,
+}
+
+impl RingAllocator {
+    pub fn new(capacity: u64) -> Self {
+        RingAllocator {
+            capacity,
+            head: 0,
+            tail: 0,
+            used: 0,
+            pending: 0,
+            submissions: VecDeque::new(),
+        }
+    }
+
+    /// Allocate `size` bytes aligned to `alignment` (power of two).
+    ///
+    /// Returns `None` if the ring has no contiguous range of this size left.
+    pub fn allocate(&mut self, size: u64, alignment: u64) -> Option<u64> {
+        assert!(
+            alignment.is_power_of_two(),
+            "alignment must be a power of two"
+        );
+        if self.used == 0 {
+            self.head = 0;
+            self.tail = 0;
+        }
+
+        let aligned = align_up(self.head, alignment);
+        let (offset, consumed) = if self.used == 0 || self.head > self.tail {
+            // Free space at the end of the ring and in front of the tail.
+            if aligned + size <= self.capacity {
+                (aligned, aligned - self.head + size)
+            } else if size <= self.tail {
+                // Wrap around, the remaining end of the ring is wasted.
+                (0, self.capacity - self.head + size)
+            } else {
+                return None;
+            }
+        } else if self.head < self.tail && aligned + size <= self.tail {
+            (aligned, aligned - self.head + size)
+        } else {
+            return None;
+        };
+
+        self.head = (offset + size) % self.capacity;
+        self.used += consumed;
+        self.pending += consumed;
+        Some(offset)
+    }
+
+    /// Close the current batch, it will be released once `fence_value` is reached.
+    pub fn submit(&mut self, fence_value: u64) {
+        if self.pending > 0 {
+            self.submissions.push_back((fence_value, self.pending));
+            self.pending = 0;
+        }
+    }
+
+    /// Release all batches up to the `completed` fence value.
+    pub fn retire(&mut self, completed: u64) {
+        while let Some(&(fence_value, size)) = self.submissions.front() {
+            if fence_value > completed {
+                break;
+            }
+            self.submissions.pop_front();
+            self.tail = (self.tail + size) % self.capacity;
+            self.used -= size;
+        }
+    }
+
+    /// Fence value of the oldest batch still in use.
+    pub fn oldest_submission(&self) -> Option<u64> {
+        self.submissions
+            .front()
+            .map(|&(fence_value, _)| fence_value)
+    }
+
+    pub fn has_pending(&self) -> bool {
+        self.pending > 0
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+}
+
+/// Staging ring for uploads to default heap resources.
+pub struct UploadRing<B: Backend> {
+    buffer: B::Resource,
+    mapped: *mut u8,
+    ring: RingAllocator,
+
+    fence: B::Fence,
+    fence_value: u64,
+
+    cmd_list: B::CommandList,
+    cmd_allocator: B::CommandAllocator,
+    // Command allocators of previous submissions, reusable after their fence value.
+    retired_allocators: VecDeque<(B::CommandAllocator, u64)>,
+    recording: bool,
+}
+
+impl<B: Backend> UploadRing<B> {
+    pub fn new(device: &B::Device, capacity: u64) -> Result<Self, EngineError> {
+        let buffer = device.create_committed_resource(
+            HeapType::Upload,
+            &ResourceDesc::buffer(capacity),
+            ResourceStates::GENERIC_READ,
+            None,
+        )?;
+        let mapped = unsafe { device.map(&buffer)? };
+        let cmd_allocator = device.create_command_allocator()?;
+        let cmd_list = device.create_command_list(&cmd_allocator)?;
+
+        Ok(UploadRing {
+            buffer,
+            mapped,
+            ring: RingAllocator::new(capacity),
+            fence: device.create_fence(0)?,
+            fence_value: 0,
+            cmd_list,
+            cmd_allocator,
+            retired_allocators: VecDeque::new(),
+            recording: false,
+        })
+    }
+
+    /// Copy `data` to the start of the buffer `dst`.
+    pub fn upload_buffer<T: Copy>(
+        &mut self,
+        device: &B::Device,
+        states: &mut StateTracker<B::Resource>,
+        dst: &B::Resource,
+        data: &[T],
+    ) -> Result<(), EngineError> {
+        let data = unsafe {
+            slice::from_raw_parts(data.as_ptr() as *const u8, data.len() * mem::size_of::<T>())
+        };
+
+        states.use_resource(dst, ResourceStates::COPY_DEST);
+
+        let mut offset = 0;
+        while offset < data.len() {
+            let size = (data.len() - offset).min(self.ring.capacity() as usize);
+            let src_offset = self.allocate(device, states, size as _, BUFFER_ALIGNMENT)?;
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    data[offset..].as_ptr(),
+                    self.mapped.offset(src_offset as _),
+                    size,
+                );
+            }
+
+            engine::record_barriers::<B>(states, &self.cmd_list);
+            self.cmd_list
+                .copy_buffer_region(dst, offset as _, &self.buffer, src_offset, size as _);
+            offset += size;
+        }
+        Ok(())
+    }
+
+    /// Copy `subresources` into the texture `dst` described by `desc`, starting at
+    /// subresource 0.
+    ///
+    /// Each subresource consists of tightly packed rows of texels, rows of compressed
+    /// formats are rows of blocks, see `Format::row_size`. Subresources fitting into
+    /// the ring are staged with a single allocation, larger textures are uploaded per
+    /// subresource in chunks of rows.
+    pub fn upload_texture(
+        &mut self,
+        device: &B::Device,
+        states: &mut StateTracker<B::Resource>,
+        dst: &B::Resource,
+        desc: &ResourceDesc,
+        subresources: &[&[u8]],
+    ) -> Result<(), EngineError> {
+        states.use_resource(dst, ResourceStates::COPY_DEST);
+
+        let (footprints, size) = copyable_footprints(desc, 0);
+        assert_eq!(
+            subresources.len(),
+            footprints.len(),
+            "subresources don't match the texture"
+        );
+        for (data, footprint) in subresources.iter().zip(&footprints) {
+            assert_eq!(
+                data.len(),
+                (footprint.row_size * footprint.num_rows) as usize,
+                "subresource data doesn't match its extent"
+            );
+        }
+
+        if size <= self.ring.capacity() {
+            let base = self.allocate(device, states, size, TEXTURE_PLACEMENT_ALIGNMENT)?;
+            engine::record_barriers::<B>(states, &self.cmd_list);
+            for (index, (subresource, footprint)) in
+                subresources.iter().zip(&footprints).enumerate()
+            {
+                let offset = base + footprint.footprint.offset;
+                self.write_rows(subresource, footprint, 0, footprint.num_rows, offset);
+                self.cmd_list.copy_texture_region(
+                    dst,
+                    index as _,
+                    [0, 0, 0],
+                    &self.buffer,
+                    &PlacedFootprint {
+                        offset,
+                        ..footprint.footprint
+                    },
+                );
+            }
+            return Ok(());
+        }
+
+        for (index, footprint) in footprints.iter().enumerate() {
+            let extent = footprint.footprint.format.block_extent();
+            let row_pitch = footprint.footprint.row_pitch;
+            let max_rows = (self.ring.capacity() / row_pitch as u64) as u32;
+            if max_rows == 0 {
+                return Err(self.exceeds_ring(row_pitch as u64));
+            }
+
+            let mut row = 0;
+            while row < footprint.num_rows {
+                let rows = (footprint.num_rows - row).min(max_rows);
+                let offset = self.allocate(
+                    device,
+                    states,
+                    row_pitch as u64 * rows as u64,
+                    TEXTURE_PLACEMENT_ALIGNMENT,
+                )?;
+                self.write_rows(subresources[index], footprint, row, rows, offset);
+
+                engine::record_barriers::<B>(states, &self.cmd_list);
+                self.cmd_list.copy_texture_region(
+                    dst,
+                    index as _,
+                    [0, row * extent, 0],
+                    &self.buffer,
+                    &PlacedFootprint {
+                        offset,
+                        height: rows * extent,
+                        ..footprint.footprint
+                    },
+                );
+                row += rows;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write `rows` tightly packed rows of `data` starting at `first_row` with the
+    /// row pitch of `footprint` to the ring `offset`.
+    fn write_rows(
+        &mut self,
+        data: &[u8],
+        footprint: &SubresourceFootprint,
+        first_row: u32,
+        rows: u32,
+        offset: u64,
+    ) {
+        let row_size = footprint.row_size;
+        for row in 0..rows {
+            let src = ((first_row + row) * row_size) as usize;
+            let dst_offset = offset + (row * footprint.footprint.row_pitch) as u64;
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    data[src..].as_ptr(),
+                    self.mapped.offset(dst_offset as _),
+                    row_size as _,
+                );
+            }
+        }
+    }
+
+    /// Submit all recorded copies and pending barriers.
+    pub fn flush(
+        &mut self,
+        device: &B::Device,
+        states: &mut StateTracker<B::Resource>,
+    ) -> Result<(), EngineError> {
+        if !self.recording {
+            return Ok(());
+        }
+
+        engine::record_barriers::<B>(states, &self.cmd_list);
+        self.cmd_list.close();
+        device.execute_command_lists(&[&self.cmd_list]);
+        self.fence_value += 1;
+        device.signal(&self.fence, self.fence_value);
+        self.ring.submit(self.fence_value);
+        self.recording = false;
+
+        // Continue with a command allocator the GPU is done with.
+        let completed = device.fence_value(&self.fence);
+        let next_allocator = match self.retired_allocators.front() {
+            Some(&(_, fence_value)) if fence_value <= completed => {
+                self.retired_allocators.pop_front().unwrap().0
+            }
+            _ => device.create_command_allocator()?,
+        };
+        let allocator = mem::replace(&mut self.cmd_allocator, next_allocator);
+        self.retired_allocators
+            .push_back((allocator, self.fence_value));
+        Ok(())
+    }
+
+    /// Allocate staging memory, reclaiming memory of completed submissions.
+    fn allocate(
+        &mut self,
+        device: &B::Device,
+        states: &mut StateTracker<B::Resource>,
+        size: u64,
+        alignment: u64,
+    ) -> Result<u64, EngineError> {
+        self.ring.retire(device.fence_value(&self.fence));
+        let offset = loop {
+            if let Some(offset) = self.ring.allocate(size, alignment) {
+                break offset;
+            }
+
+            // Ring exhausted, wait for the oldest submission.
+            if self.ring.has_pending() {
+                self.flush(device, states)?;
+            }
+            let fence_value = match self.ring.oldest_submission() {
+                Some(fence_value) => fence_value,
+                None => return Err(self.exceeds_ring(size)),
+            };
+            self.wait(device, fence_value)?;
+        };
+
+        if !self.recording {
+            self.cmd_list.reset(&self.cmd_allocator);
+            self.recording = true;
+        }
+        Ok(offset)
+    }
+
+    /// Error for staging `size` bytes at once, which can never fit into the ring.
+    fn exceeds_ring(&self, size: u64) -> EngineError {
+        EngineError::new(
+            "upload",
+            format!("{} bytes", size),
+            ErrorKind::InvalidArgument(format!(
+                "exceeds the staging ring of {} bytes",
+                self.ring.capacity()
+            )),
+        )
+    }
+
+    /// Wait for `fence_value` and reclaim the memory of completed submissions.
+    fn wait(&mut self, device: &B::Device, fence_value: u64) -> Result<(), EngineError> {
+        device.wait_for_fence(&self.fence, fence_value, FENCE_TIMEOUT_MS)?;
+        self.ring.retire(device.fence_value(&self.fence));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::null::{self, Null};
+
+    fn upload_ring(device: &null::Device, capacity: u64) -> UploadRing<Null> {
+        UploadRing::new(device, capacity).unwrap()
+    }
+
+    fn copies(device: &null::Device, name: &str) -> Vec<String> {
+        device
+            .calls()
+            .into_iter()
+            .filter(|call| call.name == name)
+            .map(|call| call.args)
+            .collect()
+    }
+
+    #[test]
+    fn ring_wrap_around() {
+        let mut ring = RingAllocator::new(1024);
+        assert_eq!(ring.allocate(400, 16), Some(0));
+        ring.submit(1);
+        assert_eq!(ring.allocate(400, 16), Some(400));
+        ring.submit(2);
+
+        // The end of the ring is too small, continue in front of the retired tail.
+        ring.retire(1);
+        assert_eq!(ring.allocate(400, 16), Some(0));
+        ring.submit(3);
+        // Head caught up with the tail, the ring is full.
+        assert_eq!(ring.allocate(1, 1), None);
+
+        // The wasted end of the ring is released along with the wrapped allocation.
+        ring.retire(2);
+        assert_eq!(ring.allocate(400, 16), Some(400));
+        ring.submit(4);
+        ring.retire(4);
+        assert_eq!(ring.oldest_submission(), None);
+        assert_eq!(ring.allocate(1024, 512), Some(0));
+    }
+
+    #[test]
+    fn ring_retire_after_fence() {
+        let mut ring = RingAllocator::new(1024);
+        assert_eq!(ring.allocate(1024, 16), Some(0));
+        ring.submit(5);
+        assert_eq!(ring.allocate(16, 16), None);
+
+        // Memory is only released once the fence value of its batch is reached.
+        ring.retire(4);
+        assert_eq!(ring.oldest_submission(), Some(5));
+        assert_eq!(ring.allocate(16, 16), None);
+
+        ring.retire(5);
+        assert_eq!(ring.oldest_submission(), None);
+        assert_eq!(ring.allocate(16, 16), Some(0));
+    }
+
+    #[test]
+    fn ring_refuse_pending_tail() {
+        let mut ring = RingAllocator::new(1024);
+        assert_eq!(ring.allocate(600, 16), Some(0));
+        assert!(ring.has_pending());
+
+        // Allocations not submitted yet aren't released by any fence value.
+        ring.retire(u64::max_value());
+        assert_eq!(ring.allocate(600, 16), None);
+        assert_eq!(ring.oldest_submission(), None);
+
+        ring.submit(1);
+        assert!(!ring.has_pending());
+        assert_eq!(ring.allocate(600, 16), None);
+        ring.retire(1);
+        assert_eq!(ring.allocate(600, 16), Some(0));
+    }
+
+    #[test]
+    fn chunk_buffer_upload() {
+        let device = null::Device::new();
+        let mut states = StateTracker::new();
+        let mut upload = upload_ring(&device, 4096);
+        let dst = device
+            .create_committed_resource(
+                HeapType::Default,
+                &ResourceDesc::buffer(10_000),
+                ResourceStates::COMMON,
+                None,
+            )
+            .unwrap();
+        states.register(&dst, ResourceStates::COMMON);
+
+        let data = (0..10_000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        upload
+            .upload_buffer(&device, &mut states, &dst, &data)
+            .unwrap();
+        upload.flush(&device, &mut states).unwrap();
+
+        assert_eq!(copies(&device, "copy_buffer_region").len(), 3);
+        assert_eq!(copies(&device, "signal").len(), 3);
+        assert_eq!(dst.data(), data);
+        assert!(device.validation_errors().is_empty());
+    }
+
+    #[test]
+    fn chunk_texture_upload() {
+        let device = null::Device::new();
+        let mut states = StateTracker::new();
+        let mut upload = upload_ring(&device, 4096);
+        // 256 byte rows, 16 rows fit into the ring.
+        let desc = ResourceDesc::texture_2d(64, 40, Format::R8G8B8A8Unorm, 1);
+        let dst = device
+            .create_committed_resource(HeapType::Default, &desc, ResourceStates::COMMON, None)
+            .unwrap();
+        states.register(&dst, ResourceStates::COMMON);
+
+        let data = vec![0x80; 64 * 40 * 4];
+        upload
+            .upload_texture(&device, &mut states, &dst, &desc, &[&data])
+            .unwrap();
+        upload.flush(&device, &mut states).unwrap();
+
+        let copies = copies(&device, "copy_texture_region");
+        assert_eq!(copies.len(), 3);
+        for (copy, (row, rows)) in copies.iter().zip(&[(0, 16), (16, 16), (32, 8)]) {
+            assert!(copy.contains(&format!("at [0, {}, 0]", row)), "{}", copy);
+            assert!(copy.contains(&format!("height: {},", rows)), "{}", copy);
+        }
+        assert!(device.validation_errors().is_empty());
+
+        // Rows larger than the whole ring can't be staged.
+        let desc = ResourceDesc::texture_2d(2048, 1, Format::R8G8B8A8Unorm, 1);
+        let dst = device
+            .create_committed_resource(HeapType::Default, &desc, ResourceStates::COMMON, None)
+            .unwrap();
+        states.register(&dst, ResourceStates::COMMON);
+        let data = vec![0; 2048 * 4];
+        let err = match upload.upload_texture(&device, &mut states, &dst, &desc, &[&data]) {
+            Err(err) => err,
+            Ok(_) => panic!("uploaded a row larger than the ring"),
+        };
+        assert_eq!(err.operation, "upload");
+        match err.kind {
+            ErrorKind::InvalidArgument(_) => {}
+            ref kind => panic!("unexpected error {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn place_unaligned_rows() {
+        let footprint = SubresourceFootprint::place(Format::R8G8B8A8Unorm, 100, 3, 1);
+        assert_eq!(footprint.footprint.offset, 512);
+        assert_eq!(
+            (footprint.footprint.width, footprint.footprint.height),
+            (100, 3)
+        );
+        assert_eq!(footprint.row_size, 400);
+        assert_eq!(footprint.footprint.row_pitch, 512);
+        assert_eq!(footprint.num_rows, 3);
+        assert_eq!(footprint.end(), 512 + 2 * 512 + 400);
+
+        // Rows already aligned aren't padded.
+        let footprint = SubresourceFootprint::place(Format::R32Uint, 64, 1, 512);
+        assert_eq!(footprint.footprint.offset, 512);
+        assert_eq!(footprint.footprint.row_pitch, 256);
+        assert_eq!(footprint.end(), 512 + 256);
+    }
+
+    #[test]
+    fn place_block_rows() {
+        // Partial blocks at the border are padded to full blocks.
+        let footprint = SubresourceFootprint::place(Format::Bc1Unorm, 10, 6, 0);
+        assert_eq!(
+            (footprint.footprint.width, footprint.footprint.height),
+            (12, 8)
+        );
+        assert_eq!(footprint.row_size, 3 * 8);
+        assert_eq!(footprint.footprint.row_pitch, 256);
+        assert_eq!(footprint.num_rows, 2);
+
+        let footprint = SubresourceFootprint::place(Format::Bc3Unorm, 1000, 1, 0);
+        assert_eq!(
+            (footprint.footprint.width, footprint.footprint.height),
+            (1000, 4)
+        );
+        assert_eq!(footprint.row_size, 250 * 16);
+        assert_eq!(footprint.footprint.row_pitch, 4096);
+        assert_eq!(footprint.num_rows, 1);
+    }
+
+    #[test]
+    fn mip_chain_footprints() {
+        let mut desc = ResourceDesc::texture_2d(100, 60, Format::Bc5Unorm, 1);
+        desc.mip_levels = 7;
+        desc.depth_or_array_size = 2;
+
+        let (footprints, size) = copyable_footprints(&desc, 16);
+        assert_eq!(footprints.len(), 14);
+        assert_eq!(size, footprints.last().unwrap().end() - 16);
+
+        let mut end = 16;
+        for (i, footprint) in footprints.iter().enumerate() {
+            let level = i as u32 % 7;
+            let width = (100 >> level).max(1);
+            let height = (60 >> level).max(1);
+            assert_eq!(
+                *footprint,
+                SubresourceFootprint::place(Format::Bc5Unorm, width, height, end)
+            );
+            assert_eq!(footprint.footprint.offset % TEXTURE_PLACEMENT_ALIGNMENT, 0);
+            assert!(footprint.footprint.offset >= end);
+            end = footprint.end();
+        }
+    }
+}